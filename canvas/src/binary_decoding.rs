@@ -0,0 +1,1258 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::{
+    color::*, decoding::*, draw::*, font::*, font_face::*, gradient::*, namespace::*, path::*,
+    sprite::*, texture::*, transform2d::*,
+};
+
+use futures::task::Poll;
+use futures::*;
+
+use uuid::*;
+
+use std::result::Result;
+use std::sync::*;
+
+/// The maximum number of bytes used by a compact u64
+const MAX_COMPACT_U64_LEN: usize = 10;
+
+/// Once this many bytes have been decoded, they're removed from the start of the decoder's buffer
+const COMPACT_BUFFER_THRESHOLD: usize = 65536;
+
+///
+/// Reads the values from the bytes that make up a single binary instruction
+///
+struct BinaryReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+///
+/// Reasons a binary instruction might not be read
+///
+enum ReadError {
+    /// The instruction has an opcode that this decoder doesn't know about (usually because it was written by a newer version
+    /// of this library). As every instruction has a length, these can be skipped.
+    UnknownOpcode,
+
+    /// The instruction could not be decoded
+    Decoder(DecoderError),
+}
+
+impl From<DecoderError> for ReadError {
+    fn from(err: DecoderError) -> ReadError {
+        ReadError::Decoder(err)
+    }
+}
+
+///
+/// Represents a (stateful) decoder for the binary canvas encoding
+///
+/// Bytes can be added to the decoder in chunks of any size: instructions are returned once all of their bytes have been
+/// received.
+///
+pub struct CanvasBinaryDecoder {
+    /// The bytes that have been received but not decoded yet
+    buffer: Vec<u8>,
+
+    /// The position in the buffer of the next instruction
+    pos: usize,
+
+    /// Set to true once the decoder has returned an error
+    in_error: bool,
+}
+
+///
+/// Reads a compact u64 from the start of a slice, returning the value and the number of bytes it uses, or None if
+/// the slice doesn't contain the whole value
+///
+fn read_compact_u64(bytes: &[u8]) -> Result<Option<(u64, usize)>, DecoderError> {
+    let mut val = 0u64;
+
+    for (idx, byte) in bytes.iter().take(MAX_COMPACT_U64_LEN).enumerate() {
+        val |= ((byte & 0x7f) as u64) << (idx * 7);
+
+        if byte & 0x80 == 0 {
+            return Ok(Some((val, idx + 1)));
+        }
+    }
+
+    if bytes.len() >= MAX_COMPACT_U64_LEN {
+        Err(DecoderError::BadNumber)
+    } else {
+        Ok(None)
+    }
+}
+
+impl<'a> BinaryReader<'a> {
+    fn new(bytes: &'a [u8]) -> BinaryReader<'a> {
+        BinaryReader { bytes, pos: 0 }
+    }
+
+    fn u8(&mut self) -> Result<u8, DecoderError> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or(DecoderError::BadInstructionLength)?;
+        self.pos += 1;
+
+        Ok(byte)
+    }
+
+    fn slice(&mut self, len: usize) -> Result<&'a [u8], DecoderError> {
+        if self.bytes.len() - self.pos < len {
+            return Err(DecoderError::BadInstructionLength);
+        }
+
+        let slice = &self.bytes[self.pos..(self.pos + len)];
+        self.pos += len;
+
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32, DecoderError> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.slice(4)?);
+
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn u64(&mut self) -> Result<u64, DecoderError> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.slice(8)?);
+
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn f32(&mut self) -> Result<f32, DecoderError> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    fn point(&mut self) -> Result<(f32, f32), DecoderError> {
+        Ok((self.f32()?, self.f32()?))
+    }
+
+    fn compact_u64(&mut self) -> Result<u64, DecoderError> {
+        let (val, len) =
+            read_compact_u64(&self.bytes[self.pos..])?.ok_or(DecoderError::BadInstructionLength)?;
+        self.pos += len;
+
+        Ok(val)
+    }
+
+    fn len(&mut self) -> Result<usize, DecoderError> {
+        let len = self.compact_u64()?;
+
+        // Lengths can't be longer than the rest of the instruction
+        if len > (self.bytes.len() - self.pos) as u64 {
+            Err(DecoderError::BadInstructionLength)
+        } else {
+            Ok(len as usize)
+        }
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], DecoderError> {
+        let len = self.len()?;
+        self.slice(len)
+    }
+
+    fn string(&mut self) -> Result<String, DecoderError> {
+        let bytes = self.bytes()?;
+
+        String::from_utf8(bytes.to_vec()).map_err(|_| DecoderError::BadString)
+    }
+
+    fn color(&mut self) -> Result<Color, DecoderError> {
+        match self.u8()? {
+            b'R' => Ok(Color::Rgba(
+                self.f32()?,
+                self.f32()?,
+                self.f32()?,
+                self.f32()?,
+            )),
+            _ => Err(DecoderError::UnknownColorType),
+        }
+    }
+
+    fn transform(&mut self) -> Result<Transform2D, DecoderError> {
+        let mut transform = [[0.0; 3]; 3];
+
+        for row in transform.iter_mut() {
+            for val in row.iter_mut() {
+                *val = self.f32()?;
+            }
+        }
+
+        Ok(Transform2D(transform))
+    }
+
+    fn layer_id(&mut self) -> Result<LayerId, DecoderError> {
+        Ok(LayerId(self.compact_u64()?))
+    }
+
+    fn sprite_id(&mut self) -> Result<SpriteId, DecoderError> {
+        Ok(SpriteId(self.compact_u64()?))
+    }
+
+    fn texture_id(&mut self) -> Result<TextureId, DecoderError> {
+        Ok(TextureId(self.compact_u64()?))
+    }
+
+    fn font_id(&mut self) -> Result<FontId, DecoderError> {
+        Ok(FontId(self.compact_u64()?))
+    }
+
+    fn gradient_id(&mut self) -> Result<GradientId, DecoderError> {
+        Ok(GradientId(self.compact_u64()?))
+    }
+
+    fn line_join(&mut self) -> Result<LineJoin, DecoderError> {
+        match self.u8()? {
+            b'M' => Ok(LineJoin::Miter),
+            b'R' => Ok(LineJoin::Round),
+            b'B' => Ok(LineJoin::Bevel),
+            other => Err(DecoderError::InvalidByte(other)),
+        }
+    }
+
+    fn line_cap(&mut self) -> Result<LineCap, DecoderError> {
+        match self.u8()? {
+            b'B' => Ok(LineCap::Butt),
+            b'R' => Ok(LineCap::Round),
+            b'S' => Ok(LineCap::Square),
+            other => Err(DecoderError::InvalidByte(other)),
+        }
+    }
+
+    fn winding_rule(&mut self) -> Result<WindingRule, DecoderError> {
+        match self.u8()? {
+            b'n' => Ok(WindingRule::NonZero),
+            b'e' => Ok(WindingRule::EvenOdd),
+            other => Err(DecoderError::InvalidByte(other)),
+        }
+    }
+
+    fn blend_mode(&mut self) -> Result<BlendMode, DecoderError> {
+        use self::BlendMode::*;
+
+        match (self.u8()?, self.u8()?) {
+            (b'S', b'V') => Ok(SourceOver),
+            (b'S', b'I') => Ok(SourceIn),
+            (b'S', b'O') => Ok(SourceOut),
+            (b'D', b'V') => Ok(DestinationOver),
+            (b'D', b'I') => Ok(DestinationIn),
+            (b'D', b'O') => Ok(DestinationOut),
+            (b'S', b'A') => Ok(SourceAtop),
+            (b'D', b'A') => Ok(DestinationAtop),
+            (b'X', b'O') => Ok(Xor),
+            (b'C', b'L') => Ok(Clear),
+            (b'S', b'S') => Ok(Copy),
+            (b'D', b'D') => Ok(Destination),
+
+            (b'E', b'M') => Ok(Multiply),
+            (b'E', b'S') => Ok(Screen),
+            (b'E', b'D') => Ok(Darken),
+            (b'E', b'L') => Ok(Lighten),
+            (b'E', b'O') => Ok(Overlay),
+            (b'E', b'C') => Ok(ColorDodge),
+            (b'E', b'B') => Ok(ColorBurn),
+            (b'E', b'H') => Ok(HardLight),
+            (b'E', b'T') => Ok(SoftLight),
+            (b'E', b'I') => Ok(Difference),
+            (b'E', b'X') => Ok(Exclusion),
+
+            (b'N', b'H') => Ok(Hue),
+            (b'N', b'S') => Ok(Saturation),
+            (b'N', b'C') => Ok(Color),
+            (b'N', b'L') => Ok(Luminosity),
+
+            (b'P', b'L') => Ok(Plus),
+
+            (_, other) => Err(DecoderError::InvalidByte(other)),
+        }
+    }
+
+    fn text_alignment(&mut self) -> Result<TextAlignment, DecoderError> {
+        match self.u8()? {
+            b'l' => Ok(TextAlignment::Left),
+            b'r' => Ok(TextAlignment::Right),
+            b'c' => Ok(TextAlignment::Center),
+            other => Err(DecoderError::InvalidByte(other)),
+        }
+    }
+
+    fn sprite_transform(&mut self) -> Result<SpriteTransform, DecoderError> {
+        match self.u8()? {
+            b'i' => Ok(SpriteTransform::Identity),
+            b't' => Ok(SpriteTransform::Translate(self.f32()?, self.f32()?)),
+            b's' => Ok(SpriteTransform::Scale(self.f32()?, self.f32()?)),
+            b'r' => Ok(SpriteTransform::Rotate(self.f32()?)),
+            b'T' => Ok(SpriteTransform::Transform2D(self.transform()?)),
+            other => Err(DecoderError::InvalidByte(other)),
+        }
+    }
+
+    fn texture_filter(&mut self) -> Result<TextureFilter, DecoderError> {
+        match self.u8()? {
+            b'B' => Ok(TextureFilter::GaussianBlur(self.f32()?)),
+            b'A' => Ok(TextureFilter::AlphaBlend(self.f32()?)),
+            b'M' => Ok(TextureFilter::Mask(self.texture_id()?)),
+            b'D' => Ok(TextureFilter::DisplacementMap(
+                self.texture_id()?,
+                self.f32()?,
+                self.f32()?,
+            )),
+            other => Err(DecoderError::InvalidByte(other)),
+        }
+    }
+
+    fn texture_filters(&mut self) -> Result<Vec<TextureFilter>, DecoderError> {
+        // Each filter is at least 2 bytes long
+        let len = self.len()?;
+
+        (0..len).map(|_| self.texture_filter()).collect()
+    }
+
+    fn texture_op(&mut self) -> Result<TextureOp, ReadError> {
+        match self.u8()? {
+            b'N' => {
+                let size = TextureSize(self.u32()?, self.u32()?);
+
+                match self.u8()? {
+                    b'r' => Ok(TextureOp::Create(size, TextureFormat::Rgba)),
+                    other => Err(DecoderError::InvalidByte(other).into()),
+                }
+            }
+            b'X' => Ok(TextureOp::Free),
+            b'D' => {
+                let position = TexturePosition(self.u32()?, self.u32()?);
+                let size = TextureSize(self.u32()?, self.u32()?);
+                let bytes = self.bytes()?.to_vec();
+
+                Ok(TextureOp::SetBytes(position, size, Arc::new(bytes)))
+            }
+            b'S' => {
+                let sprite_id = self.sprite_id()?;
+                let (x, y) = self.point()?;
+                let (w, h) = self.point()?;
+
+                Ok(TextureOp::SetFromSprite(
+                    sprite_id,
+                    SpriteBounds(SpritePosition(x, y), SpriteSize(w, h)),
+                ))
+            }
+            b's' => {
+                let sprite_id = self.sprite_id()?;
+                let (x, y) = self.point()?;
+                let (sprite_w, sprite_h) = self.point()?;
+                let (canvas_w, canvas_h) = self.point()?;
+
+                Ok(TextureOp::CreateDynamicSprite(
+                    sprite_id,
+                    SpriteBounds(SpritePosition(x, y), SpriteSize(sprite_w, sprite_h)),
+                    CanvasSize(canvas_w, canvas_h),
+                ))
+            }
+            b't' => Ok(TextureOp::FillTransparency(self.f32()?)),
+            b'C' => Ok(TextureOp::Copy(self.texture_id()?)),
+            b'F' => Ok(TextureOp::Filter(self.texture_filter()?)),
+            _ => Err(ReadError::UnknownOpcode),
+        }
+    }
+
+    fn gradient_op(&mut self) -> Result<GradientOp, ReadError> {
+        match self.u8()? {
+            b'N' => Ok(GradientOp::Create(self.color()?)),
+            b'S' => Ok(GradientOp::AddStop(self.f32()?, self.color()?)),
+            b'M' => match self.u8()? {
+                b'P' => Ok(GradientOp::SpreadMode(GradientSpread::Pad)),
+                b'R' => Ok(GradientOp::SpreadMode(GradientSpread::Repeat)),
+                b'F' => Ok(GradientOp::SpreadMode(GradientSpread::Reflect)),
+                other => Err(DecoderError::InvalidByte(other).into()),
+            },
+            _ => Err(ReadError::UnknownOpcode),
+        }
+    }
+
+    fn font_op(&mut self) -> Result<FontOp, ReadError> {
+        match self.u8()? {
+            b'S' => Ok(FontOp::FontSize(self.f32()?)),
+            b'd' => match self.u8()? {
+                b'T' => Ok(FontOp::UseFontDefinition(CanvasFontFace::from_slice(
+                    self.bytes()?,
+                ))),
+                other => Err(DecoderError::InvalidByte(other).into()),
+            },
+            b'G' => {
+                let len = self.len()?;
+                let glyphs = (0..len)
+                    .map(|_| {
+                        Ok(GlyphPosition {
+                            id: GlyphId(self.u32()?),
+                            location: self.point()?,
+                            em_size: self.f32()?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, DecoderError>>()?;
+
+                Ok(FontOp::DrawGlyphs(glyphs))
+            }
+            b'L' => Ok(FontOp::LayoutText(self.string()?)),
+            _ => Err(ReadError::UnknownOpcode),
+        }
+    }
+
+    ///
+    /// Reads a drawing instruction (the whole of the data passed to this reader should be used by the instruction)
+    ///
+    fn draw(&mut self) -> Result<Draw, ReadError> {
+        use self::Draw::*;
+        use self::PathOp::*;
+
+        let draw = match self.u8()? {
+            b'N' => match self.u8()? {
+                b'F' => StartFrame,
+                b'f' => ShowFrame,
+                b'G' => ResetFrame,
+                b'p' => Path(NewPath),
+                b'A' => ClearCanvas(self.color()?),
+                b'L' => Layer(self.layer_id()?),
+                b'B' => LayerBlend(self.layer_id()?, self.blend_mode()?),
+                b't' => LayerAlpha(self.layer_id()?, self.f32()?),
+                b'C' => ClearLayer,
+                b'a' => ClearAllLayers,
+                b'X' => SwapLayers(self.layer_id()?, self.layer_id()?),
+                b's' => Sprite(self.sprite_id()?),
+                b'N' => {
+                    let (global_a, global_b) = (self.u64()?, self.u64()?);
+                    Namespace(NamespaceId::with_id(Uuid::from_u64_pair(
+                        global_a, global_b,
+                    )))
+                }
+                _ => Err(ReadError::UnknownOpcode)?,
+            },
+
+            b'm' => Path(Move(self.f32()?, self.f32()?)),
+            b'l' => Path(Line(self.f32()?, self.f32()?)),
+            b'c' => {
+                let (p, cp1, cp2) = (self.point()?, self.point()?, self.point()?);
+                Path(BezierCurve((cp1, cp2), p))
+            }
+            b'.' => Path(ClosePath),
+            b'F' => Fill,
+            b'S' => Stroke,
+
+            b'L' => match self.u8()? {
+                b'w' => LineWidth(self.f32()?),
+                b'p' => LineWidthPixels(self.f32()?),
+                b'j' => LineJoin(self.line_join()?),
+                b'c' => LineCap(self.line_cap()?),
+                _ => Err(ReadError::UnknownOpcode)?,
+            },
+
+            b'W' => WindingRule(self.winding_rule()?),
+
+            b'D' => match self.u8()? {
+                b'n' => NewDashPattern,
+                b'l' => DashLength(self.f32()?),
+                b'o' => DashOffset(self.f32()?),
+                _ => Err(ReadError::UnknownOpcode)?,
+            },
+
+            b'C' => match self.u8()? {
+                b's' => StrokeColor(self.color()?),
+                b'f' => FillColor(self.color()?),
+                b't' => FillTexture(self.texture_id()?, self.point()?, self.point()?),
+                b'g' => FillGradient(self.gradient_id()?, self.point()?, self.point()?),
+                b'r' => FillRadialGradient(
+                    self.gradient_id()?,
+                    (self.f32()?, self.f32()?, self.f32()?),
+                    (self.f32()?, self.f32()?, self.f32()?),
+                ),
+                b'c' => FillConicGradient(self.gradient_id()?, self.point()?, self.f32()?),
+                b'T' => FillTransform(self.transform()?),
+                _ => Err(ReadError::UnknownOpcode)?,
+            },
+
+            b'M' => BlendMode(self.blend_mode()?),
+
+            b'T' => match self.u8()? {
+                b'i' => IdentityTransform,
+                b'h' => CanvasHeight(self.f32()?),
+                b'c' => CenterRegion(self.point()?, self.point()?),
+                b'm' => MultiplyTransform(self.transform()?),
+                _ => Err(ReadError::UnknownOpcode)?,
+            },
+
+            b'Z' => match self.u8()? {
+                b'n' => Unclip,
+                b'c' => Clip,
+                b's' => Store,
+                b'r' => Restore,
+                b'f' => FreeStoredBuffer,
+                _ => Err(ReadError::UnknownOpcode)?,
+            },
+
+            b'P' => PushState,
+            b'p' => PopState,
+
+            b's' => match self.u8()? {
+                b'C' => ClearSprite,
+                b'T' => SpriteTransform(self.sprite_transform()?),
+                b'm' => MoveSpriteFrom(self.sprite_id()?),
+                b'D' => DrawSprite(self.sprite_id()?),
+                b'F' => DrawSpriteWithFilters(self.sprite_id()?, self.texture_filters()?),
+                _ => Err(ReadError::UnknownOpcode)?,
+            },
+
+            b'B' => Texture(self.texture_id()?, self.texture_op()?),
+            b'f' => Font(self.font_id()?, self.font_op()?),
+
+            b't' => match self.u8()? {
+                b'T' => DrawText(self.font_id()?, self.string()?, self.f32()?, self.f32()?),
+                b'l' => BeginLineLayout(self.f32()?, self.f32()?, self.text_alignment()?),
+                b'R' => DrawLaidOutText,
+                _ => Err(ReadError::UnknownOpcode)?,
+            },
+
+            b'G' => Gradient(self.gradient_id()?, self.gradient_op()?),
+
+            _ => Err(ReadError::UnknownOpcode)?,
+        };
+
+        if self.pos != self.bytes.len() {
+            Err(DecoderError::BadInstructionLength.into())
+        } else {
+            Ok(draw)
+        }
+    }
+}
+
+impl Default for CanvasBinaryDecoder {
+    fn default() -> Self {
+        CanvasBinaryDecoder::new()
+    }
+}
+
+impl CanvasBinaryDecoder {
+    ///
+    /// Creates a new binary canvas decoder
+    ///
+    pub fn new() -> CanvasBinaryDecoder {
+        CanvasBinaryDecoder {
+            buffer: vec![],
+            pos: 0,
+            in_error: false,
+        }
+    }
+
+    ///
+    /// Adds some bytes to the data waiting to be decoded
+    ///
+    pub fn append_bytes(&mut self, bytes: &[u8]) {
+        // Discard the data that has already been decoded once there's enough of it
+        if self.pos == self.buffer.len() {
+            self.buffer.clear();
+            self.pos = 0;
+        } else if self.pos >= COMPACT_BUFFER_THRESHOLD {
+            self.buffer.drain(0..self.pos);
+            self.pos = 0;
+        }
+
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    ///
+    /// True if there are bytes waiting that are not part of a complete instruction
+    ///
+    pub fn has_partial_instruction(&self) -> bool {
+        self.pos < self.buffer.len()
+    }
+
+    ///
+    /// Decodes the next instruction from the bytes that have been added to this decoder, or returns None if more bytes
+    /// are needed
+    ///
+    pub fn next_draw(&mut self) -> Result<Option<Draw>, DecoderError> {
+        if self.in_error {
+            return Err(DecoderError::IsInErrorState);
+        }
+
+        let result = self.decode_next();
+        if result.is_err() {
+            self.in_error = true;
+        }
+
+        result
+    }
+
+    ///
+    /// Indicates that no more bytes will be added to this decoder, returning an error if the data ends part way through an instruction
+    ///
+    pub fn finish(&mut self) -> Result<(), DecoderError> {
+        if self.in_error {
+            Err(DecoderError::IsInErrorState)
+        } else if self.has_partial_instruction() {
+            self.in_error = true;
+            Err(DecoderError::TruncatedInstruction)
+        } else {
+            Ok(())
+        }
+    }
+
+    ///
+    /// Decodes the next instruction, if it's available
+    ///
+    /// Instructions with opcodes that this decoder doesn't recognise are skipped.
+    ///
+    fn decode_next(&mut self) -> Result<Option<Draw>, DecoderError> {
+        loop {
+            let remaining = &self.buffer[self.pos..];
+
+            // Read the length of the next instruction
+            let (len, len_bytes) = match read_compact_u64(remaining)? {
+                Some(len) => len,
+                None => {
+                    return Ok(None);
+                }
+            };
+
+            // Wait until the whole instruction is available
+            if len > (remaining.len() - len_bytes) as u64 {
+                return Ok(None);
+            }
+
+            let instruction_end = len_bytes + (len as usize);
+            let draw = BinaryReader::new(&remaining[len_bytes..instruction_end]).draw();
+
+            match draw {
+                Ok(draw) => {
+                    self.pos += instruction_end;
+                    return Ok(Some(draw));
+                }
+                Err(ReadError::UnknownOpcode) => {
+                    self.pos += instruction_end;
+                }
+                Err(ReadError::Decoder(err)) => {
+                    return Err(err);
+                }
+            }
+        }
+    }
+}
+
+///
+/// Decodes a canvas drawing represented in the binary encoding. If there's an error in the data, it will be the last item decoded.
+///
+/// Instructions that this decoder doesn't recognise are skipped. It's an error if the data ends part way through an instruction.
+///
+pub fn decode_binary_drawing(
+    source: impl AsRef<[u8]>,
+) -> impl Iterator<Item = Result<Draw, DecoderError>> {
+    let mut decoder = CanvasBinaryDecoder::new();
+    let mut seen_error = false;
+
+    decoder.append_bytes(source.as_ref());
+
+    std::iter::from_fn(move || {
+        if seen_error {
+            return None;
+        }
+
+        match decoder.next_draw() {
+            Ok(Some(draw)) => Some(Ok(draw)),
+            Ok(None) => match decoder.finish() {
+                Ok(()) => None,
+                Err(err) => {
+                    seen_error = true;
+                    Some(Err(err))
+                }
+            },
+            Err(err) => {
+                seen_error = true;
+                Some(Err(err))
+            }
+        }
+    })
+}
+
+///
+/// Decodes a canvas drawing represented in the binary encoding as a stream of chunks of bytes (for example, `Vec<u8>` or
+/// `bytes::Bytes`). The chunks do not need to contain whole instructions, but it's an error if the stream ends part way through
+/// an instruction. Instructions that this decoder doesn't recognise are skipped.
+///
+pub fn decode_binary_drawing_stream<In, Chunk, E>(
+    source: In,
+) -> impl Unpin + Stream<Item = Result<Draw, StreamDecoderError<E>>>
+where
+    In: Unpin + Stream<Item = Result<Chunk, E>>,
+    Chunk: AsRef<[u8]>,
+{
+    let mut source = source;
+    let mut decoder = CanvasBinaryDecoder::new();
+    let mut seen_error = false;
+
+    stream::poll_fn(move |context| {
+        if seen_error {
+            // Only allow one error from the decoder (it remains in an error state after this)
+            Poll::Ready(None)
+        } else {
+            loop {
+                // Return any instructions that have already been received before reading more data
+                match decoder.next_draw() {
+                    Ok(Some(draw)) => {
+                        return Poll::Ready(Some(Ok(draw)));
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        seen_error = true;
+                        return Poll::Ready(Some(Err(StreamDecoderError::Decoder(err))));
+                    }
+                }
+
+                match source.poll_next_unpin(context) {
+                    Poll::Ready(None) => {
+                        // The stream should not finish part way through an instruction
+                        return match decoder.finish() {
+                            Ok(()) => Poll::Ready(None),
+                            Err(err) => {
+                                seen_error = true;
+                                Poll::Ready(Some(Err(StreamDecoderError::Decoder(err))))
+                            }
+                        };
+                    }
+                    Poll::Pending => {
+                        return Poll::Pending;
+                    }
+                    Poll::Ready(Some(Ok(bytes))) => {
+                        decoder.append_bytes(bytes.as_ref());
+                    }
+                    Poll::Ready(Some(Err(err))) => {
+                        return Poll::Ready(Some(Err(StreamDecoderError::Stream(err))));
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::encoding::*;
+
+    use futures::executor;
+
+    ///
+    /// Simple xorshift random number generator (so the tests are repeatable)
+    ///
+    struct TestRng(u64);
+
+    impl TestRng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, max: u64) -> u64 {
+            self.next() % max
+        }
+
+        fn f32(&mut self) -> f32 {
+            // Any bit pattern is allowed, including NaNs and infinities, as the encoding should be bit-exact
+            f32::from_bits(self.next() as u32)
+        }
+
+        fn point(&mut self) -> (f32, f32) {
+            (self.f32(), self.f32())
+        }
+
+        fn id(&mut self) -> u64 {
+            // Mix of small and large IDs to check the compact encoding
+            match self.below(3) {
+                0 => self.below(128),
+                1 => self.below(65536),
+                _ => self.next(),
+            }
+        }
+
+        fn color(&mut self) -> Color {
+            Color::Rgba(self.f32(), self.f32(), self.f32(), self.f32())
+        }
+
+        fn transform(&mut self) -> Transform2D {
+            Transform2D([
+                [self.f32(), self.f32(), self.f32()],
+                [self.f32(), self.f32(), self.f32()],
+                [self.f32(), self.f32(), self.f32()],
+            ])
+        }
+
+        fn bytes(&mut self) -> Vec<u8> {
+            let len = self.below(300);
+            (0..len).map(|_| self.next() as u8).collect()
+        }
+
+        fn string(&mut self) -> String {
+            let len = self.below(20);
+            (0..len)
+                .map(|_| match self.below(3) {
+                    0 => 'a',
+                    1 => 'é',
+                    _ => '😀',
+                })
+                .collect()
+        }
+
+        fn blend_mode(&mut self) -> BlendMode {
+            use self::BlendMode::*;
+
+            let modes = [
+                SourceOver,
+                SourceIn,
+                SourceOut,
+                DestinationOver,
+                DestinationIn,
+                DestinationOut,
+                SourceAtop,
+                DestinationAtop,
+                Xor,
+                Clear,
+                Copy,
+                Destination,
+                Multiply,
+                Screen,
+                Darken,
+                Lighten,
+                Overlay,
+                ColorDodge,
+                ColorBurn,
+                HardLight,
+                SoftLight,
+                Difference,
+                Exclusion,
+                Hue,
+                Saturation,
+                Color,
+                Luminosity,
+                Plus,
+            ];
+
+            modes[self.below(modes.len() as u64) as usize]
+        }
+
+        fn texture_filter(&mut self) -> TextureFilter {
+            match self.below(4) {
+                0 => TextureFilter::GaussianBlur(self.f32()),
+                1 => TextureFilter::AlphaBlend(self.f32()),
+                2 => TextureFilter::Mask(TextureId(self.id())),
+                _ => TextureFilter::DisplacementMap(TextureId(self.id()), self.f32(), self.f32()),
+            }
+        }
+
+        fn draw(&mut self) -> Draw {
+            use self::Draw::*;
+
+            match self.below(60) {
+                0 => StartFrame,
+                1 => ShowFrame,
+                2 => ResetFrame,
+                3 => Path(PathOp::NewPath),
+                4 => Path(PathOp::Move(self.f32(), self.f32())),
+                5 => Path(PathOp::Line(self.f32(), self.f32())),
+                6 => Path(PathOp::BezierCurve(
+                    (self.point(), self.point()),
+                    self.point(),
+                )),
+                7 => Path(PathOp::ClosePath),
+                8 => Fill,
+                9 => Stroke,
+                10 => LineWidth(self.f32()),
+                11 => LineWidthPixels(self.f32()),
+                12 => LineJoin(
+                    [
+                        self::LineJoin::Miter,
+                        self::LineJoin::Round,
+                        self::LineJoin::Bevel,
+                    ][self.below(3) as usize],
+                ),
+                13 => LineCap(
+                    [
+                        self::LineCap::Butt,
+                        self::LineCap::Round,
+                        self::LineCap::Square,
+                    ][self.below(3) as usize],
+                ),
+                14 => NewDashPattern,
+                15 => DashLength(self.f32()),
+                16 => DashOffset(self.f32()),
+                17 => FillColor(self.color()),
+                18 => FillTexture(TextureId(self.id()), self.point(), self.point()),
+                19 => FillGradient(GradientId(self.id()), self.point(), self.point()),
+                20 => FillRadialGradient(
+                    GradientId(self.id()),
+                    (self.f32(), self.f32(), self.f32()),
+                    (self.f32(), self.f32(), self.f32()),
+                ),
+                21 => FillConicGradient(GradientId(self.id()), self.point(), self.f32()),
+                22 => FillTransform(self.transform()),
+                23 => StrokeColor(self.color()),
+                24 => WindingRule(if self.below(2) == 0 {
+                    self::WindingRule::NonZero
+                } else {
+                    self::WindingRule::EvenOdd
+                }),
+                25 => BlendMode(self.blend_mode()),
+                26 => IdentityTransform,
+                27 => CanvasHeight(self.f32()),
+                28 => CenterRegion(self.point(), self.point()),
+                29 => MultiplyTransform(self.transform()),
+                30 => Unclip,
+                31 => Clip,
+                32 => Store,
+                33 => Restore,
+                34 => FreeStoredBuffer,
+                35 => PushState,
+                36 => PopState,
+                37 => ClearCanvas(self.color()),
+                38 => Layer(LayerId(self.id())),
+                39 => LayerBlend(LayerId(self.id()), self.blend_mode()),
+                40 => LayerAlpha(LayerId(self.id()), self.f32()),
+                41 => ClearLayer,
+                42 => ClearAllLayers,
+                43 => SwapLayers(LayerId(self.id()), LayerId(self.id())),
+                44 => Sprite(SpriteId(self.id())),
+                45 => MoveSpriteFrom(SpriteId(self.id())),
+                46 => ClearSprite,
+                47 => SpriteTransform(match self.below(5) {
+                    0 => self::SpriteTransform::Identity,
+                    1 => self::SpriteTransform::Translate(self.f32(), self.f32()),
+                    2 => self::SpriteTransform::Scale(self.f32(), self.f32()),
+                    3 => self::SpriteTransform::Rotate(self.f32()),
+                    _ => self::SpriteTransform::Transform2D(self.transform()),
+                }),
+                48 => DrawSprite(SpriteId(self.id())),
+                49 => {
+                    let num_filters = self.below(4);
+                    DrawSpriteWithFilters(
+                        SpriteId(self.id()),
+                        (0..num_filters).map(|_| self.texture_filter()).collect(),
+                    )
+                }
+                50 => Texture(
+                    TextureId(self.id()),
+                    match self.below(9) {
+                        0 => TextureOp::Create(
+                            TextureSize(self.next() as u32, self.next() as u32),
+                            TextureFormat::Rgba,
+                        ),
+                        1 => TextureOp::Free,
+                        2 => TextureOp::SetFromSprite(
+                            SpriteId(self.id()),
+                            SpriteBounds(
+                                SpritePosition(self.f32(), self.f32()),
+                                SpriteSize(self.f32(), self.f32()),
+                            ),
+                        ),
+                        3 => TextureOp::CreateDynamicSprite(
+                            SpriteId(self.id()),
+                            SpriteBounds(
+                                SpritePosition(self.f32(), self.f32()),
+                                SpriteSize(self.f32(), self.f32()),
+                            ),
+                            CanvasSize(self.f32(), self.f32()),
+                        ),
+                        4 => TextureOp::FillTransparency(self.f32()),
+                        5 => TextureOp::Copy(TextureId(self.id())),
+                        6 => TextureOp::Filter(self.texture_filter()),
+                        _ => TextureOp::SetBytes(
+                            TexturePosition(self.next() as u32, self.next() as u32),
+                            TextureSize(self.next() as u32, self.next() as u32),
+                            Arc::new(self.bytes()),
+                        ),
+                    },
+                ),
+                51 => Font(FontId(self.id()), FontOp::FontSize(self.f32())),
+                52 => Font(FontId(self.id()), FontOp::LayoutText(self.string())),
+                53 => {
+                    let num_glyphs = self.below(10);
+                    Font(
+                        FontId(self.id()),
+                        FontOp::DrawGlyphs(
+                            (0..num_glyphs)
+                                .map(|_| GlyphPosition {
+                                    id: GlyphId(self.next() as u32),
+                                    location: self.point(),
+                                    em_size: self.f32(),
+                                })
+                                .collect(),
+                        ),
+                    )
+                }
+                54 => BeginLineLayout(
+                    self.f32(),
+                    self.f32(),
+                    [
+                        TextAlignment::Left,
+                        TextAlignment::Right,
+                        TextAlignment::Center,
+                    ][self.below(3) as usize],
+                ),
+                55 => DrawLaidOutText,
+                56 => DrawText(FontId(self.id()), self.string(), self.f32(), self.f32()),
+                57 => Gradient(
+                    GradientId(self.id()),
+                    match self.below(3) {
+                        0 => GradientOp::Create(self.color()),
+                        1 => GradientOp::AddStop(self.f32(), self.color()),
+                        _ => GradientOp::SpreadMode(
+                            [
+                                GradientSpread::Pad,
+                                GradientSpread::Repeat,
+                                GradientSpread::Reflect,
+                            ][self.below(3) as usize],
+                        ),
+                    },
+                ),
+                58 => Namespace(NamespaceId::new()),
+                _ => Namespace(NamespaceId::default()),
+            }
+        }
+    }
+
+    fn encode(instructions: &[Draw]) -> Vec<u8> {
+        let mut encoded = vec![];
+        instructions
+            .iter()
+            .for_each(|draw| draw.encode_canvas(&mut encoded));
+        encoded
+    }
+
+    ///
+    /// Checks that a set of instructions decode to instructions that encode to exactly the same bytes (NaNs mean that
+    /// the instructions can't always be compared directly)
+    ///
+    fn check_round_trip(instructions: Vec<Draw>) {
+        let encoded = encode(&instructions);
+        let decoded = decode_binary_drawing(&encoded)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert!(decoded.len() == instructions.len());
+        assert!(encode(&decoded) == encoded);
+    }
+
+    #[test]
+    fn decode_simple_drawing() {
+        let instructions = vec![
+            Draw::ClearCanvas(Color::Rgba(1.0, 1.0, 1.0, 1.0)),
+            Draw::Path(PathOp::NewPath),
+            Draw::Path(PathOp::Move(10.0, 20.0)),
+            Draw::Path(PathOp::BezierCurve(((1.0, 2.0), (3.0, 4.0)), (5.0, 6.0))),
+            Draw::Path(PathOp::ClosePath),
+            Draw::FillColor(Color::Rgba(0.5, 0.25, 0.125, 1.0)),
+            Draw::Fill,
+        ];
+
+        let decoded = decode_binary_drawing(encode(&instructions))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert!(decoded == instructions);
+    }
+
+    #[test]
+    fn decode_font_data() {
+        let font = CanvasFontFace::from_slice(include_bytes!("../test_data/Lato-Regular.ttf"));
+        let instructions = vec![Draw::Font(
+            FontId(42),
+            FontOp::UseFontDefinition(font.clone()),
+        )];
+
+        let decoded = decode_binary_drawing(encode(&instructions))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        match &decoded[0] {
+            Draw::Font(FontId(42), FontOp::UseFontDefinition(decoded_font)) => {
+                assert!(decoded_font.font_data() == font.font_data())
+            }
+            other => panic!("Unexpected instruction {:?}", other),
+        }
+    }
+
+    #[test]
+    fn binary_is_smaller_than_text_for_texture_data() {
+        let bytes = Arc::new((0..4096).map(|idx| idx as u8).collect::<Vec<u8>>());
+        let draw = Draw::Texture(
+            TextureId(0),
+            TextureOp::SetBytes(TexturePosition(0, 0), TextureSize(32, 32), bytes),
+        );
+
+        let mut text = String::new();
+        draw.encode_canvas(&mut text);
+        let binary = encode(&[draw]);
+
+        assert!(binary.len() < 4200);
+        assert!(binary.len() * 4 < text.len() * 3);
+    }
+
+    #[test]
+    fn fuzz_round_trip_every_instruction() {
+        let mut rng = TestRng(0x1234_5678_9abc_def0);
+
+        for _ in 0..200 {
+            let num_instructions = rng.below(50) + 1;
+            let instructions = (0..num_instructions).map(|_| rng.draw()).collect();
+
+            check_round_trip(instructions);
+        }
+    }
+
+    #[test]
+    fn fuzz_round_trip_in_random_chunks() {
+        let mut rng = TestRng(0x0fed_cba9_8765_4321);
+
+        for _ in 0..50 {
+            let instructions = (0..100).map(|_| rng.draw()).collect::<Vec<_>>();
+            let encoded = encode(&instructions);
+
+            // Split the bytes into chunks of random sizes (including empty chunks)
+            let mut chunks = vec![];
+            let mut pos = 0;
+            while pos < encoded.len() {
+                let len = (rng.below(64) as usize).min(encoded.len() - pos);
+                chunks.push(Ok::<_, ()>(encoded[pos..(pos + len)].to_vec()));
+                pos += len;
+            }
+
+            let decoded = executor::block_on(async {
+                decode_binary_drawing_stream(stream::iter(chunks))
+                    .collect::<Vec<_>>()
+                    .await
+            });
+            let decoded = decoded.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+
+            assert!(decoded.len() == instructions.len());
+            assert!(encode(&decoded) == encoded);
+        }
+    }
+
+    #[test]
+    fn fuzz_corrupted_data_does_not_panic() {
+        let mut rng = TestRng(0x5555_aaaa_5555_aaaa);
+
+        for _ in 0..500 {
+            let instructions = (0..10).map(|_| rng.draw()).collect::<Vec<_>>();
+            let mut encoded = encode(&instructions);
+
+            // Change some bytes or truncate the data
+            for _ in 0..rng.below(4) {
+                let pos = rng.below(encoded.len() as u64) as usize;
+                encoded[pos] = rng.next() as u8;
+            }
+            if rng.below(2) == 0 {
+                let len = rng.below(encoded.len() as u64) as usize;
+                encoded.truncate(len);
+            }
+
+            // Decoding can fail, but should not panic or return more than one error
+            let decoded = decode_binary_drawing(&encoded).collect::<Vec<_>>();
+            assert!(decoded.iter().filter(|draw| draw.is_err()).count() <= 1);
+            assert!(decoded.len() <= instructions.len());
+        }
+    }
+
+    #[test]
+    fn wrong_length_is_an_error() {
+        // 'Fill' with an extra byte
+        let decoded = decode_binary_drawing(vec![2, b'F', 0]).collect::<Vec<_>>();
+        assert!(decoded == vec![Err(DecoderError::BadInstructionLength)]);
+
+        // Layer with a missing ID
+        let decoded = decode_binary_drawing(vec![2, b'N', b'L']).collect::<Vec<_>>();
+        assert!(decoded == vec![Err(DecoderError::BadInstructionLength)]);
+    }
+
+    #[test]
+    fn unknown_instructions_are_skipped() {
+        // Unknown instruction, unknown instruction in a known group, unknown texture operation
+        let decoded = decode_binary_drawing(vec![
+            1, b'F', 3, b'?', 1, 2, 1, b'S', 2, b'N', b'?', 1, b'S', 3, b'B', 1, b'?', 1, b'F',
+        ])
+        .collect::<Vec<_>>();
+
+        assert!(
+            decoded
+                == vec![
+                    Ok(Draw::Fill),
+                    Ok(Draw::Stroke),
+                    Ok(Draw::Stroke),
+                    Ok(Draw::Fill)
+                ],
+            "{:?}",
+            decoded
+        );
+    }
+
+    #[test]
+    fn bad_value_in_known_instruction_is_an_error() {
+        let decoded = decode_binary_drawing(vec![1, b'F', 2, b'W', b'?']).collect::<Vec<_>>();
+
+        assert!(decoded == vec![Ok(Draw::Fill), Err(DecoderError::InvalidByte(b'?'))]);
+    }
+
+    #[test]
+    fn truncated_instruction_is_an_error() {
+        let encoded = encode(&[Draw::Fill, Draw::LineWidth(2.0)]);
+        let decoded = decode_binary_drawing(&encoded[0..4]).collect::<Vec<_>>();
+
+        assert!(decoded == vec![Ok(Draw::Fill), Err(DecoderError::TruncatedInstruction)]);
+    }
+
+    #[test]
+    fn truncated_stream_is_an_error() {
+        let encoded = encode(&[Draw::Fill, Draw::LineWidth(2.0)]);
+        let chunks = vec![
+            Ok::<_, ()>(encoded[0..3].to_vec()),
+            Ok(encoded[3..5].to_vec()),
+        ];
+
+        let decoded = executor::block_on(async {
+            decode_binary_drawing_stream(stream::iter(chunks))
+                .collect::<Vec<_>>()
+                .await
+        });
+
+        assert!(
+            decoded
+                == vec![
+                    Ok(Draw::Fill),
+                    Err(StreamDecoderError::Decoder(
+                        DecoderError::TruncatedInstruction
+                    ))
+                ]
+        );
+    }
+
+    #[test]
+    fn partial_instruction_waits_for_more_data() {
+        let encoded = encode(&[Draw::LineWidth(2.0)]);
+        let mut decoder = CanvasBinaryDecoder::new();
+
+        decoder.append_bytes(&encoded[0..3]);
+        assert!(decoder.next_draw() == Ok(None));
+        assert!(decoder.has_partial_instruction());
+
+        decoder.append_bytes(&encoded[3..]);
+        assert!(decoder.next_draw() == Ok(Some(Draw::LineWidth(2.0))));
+        assert!(decoder.next_draw() == Ok(None));
+        assert!(!decoder.has_partial_instruction());
+    }
+}
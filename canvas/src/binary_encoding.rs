@@ -0,0 +1,625 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//!
+//! The binary encoding uses the same tags as the text encoding, but stores them as bytes, alongside little-endian numbers
+//! and raw byte data. Each instruction is preceded by its length in bytes, so a decoder can always find where the next
+//! instruction starts.
+//!
+
+use crate::color::*;
+use crate::draw::*;
+use crate::encoding::*;
+use crate::font::*;
+use crate::gradient::*;
+use crate::namespace::*;
+use crate::path::*;
+use crate::sprite::*;
+use crate::texture::*;
+use crate::transform2d::*;
+
+///
+/// Encodes a u64 using a compact representation that is smaller for smaller values (7 bits per byte, with the top bit set
+/// on all but the last byte)
+///
+#[inline]
+fn encode_binary_compact_u64(val: u64, append_to: &mut Vec<u8>) {
+    let mut val = val;
+
+    loop {
+        let seven_bits = (val & 0x7f) as u8;
+        let remaining = val >> 7;
+
+        if remaining != 0 {
+            append_to.push(seven_bits | 0x80);
+        } else {
+            append_to.push(seven_bits);
+            break;
+        }
+
+        val = remaining;
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for u8 {
+    #[inline]
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        append_to.push(*self)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for u32 {
+    #[inline]
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        append_to.extend_from_slice(&self.to_le_bytes())
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for u64 {
+    #[inline]
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        append_to.extend_from_slice(&self.to_le_bytes())
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for f32 {
+    #[inline]
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        // Storing the bits means that the value is reproduced exactly (including NaNs)
+        f32::to_bits(*self).encode_canvas(append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &f32 {
+    #[inline]
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        f32::to_bits(**self).encode_canvas(append_to)
+    }
+}
+
+//
+// Some convenience encodings for implementing the main canvas encoding
+//
+
+impl<A: CanvasEncoding<Vec<u8>>, B: CanvasEncoding<Vec<u8>>> CanvasEncoding<Vec<u8>> for (A, B) {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        self.0.encode_canvas(append_to);
+        self.1.encode_canvas(append_to);
+    }
+}
+
+impl<A: CanvasEncoding<Vec<u8>>, B: CanvasEncoding<Vec<u8>>, C: CanvasEncoding<Vec<u8>>>
+    CanvasEncoding<Vec<u8>> for (A, B, C)
+{
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        self.0.encode_canvas(append_to);
+        self.1.encode_canvas(append_to);
+        self.2.encode_canvas(append_to);
+    }
+}
+
+impl<
+        A: CanvasEncoding<Vec<u8>>,
+        B: CanvasEncoding<Vec<u8>>,
+        C: CanvasEncoding<Vec<u8>>,
+        D: CanvasEncoding<Vec<u8>>,
+    > CanvasEncoding<Vec<u8>> for (A, B, C, D)
+{
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        self.0.encode_canvas(append_to);
+        self.1.encode_canvas(append_to);
+        self.2.encode_canvas(append_to);
+        self.3.encode_canvas(append_to);
+    }
+}
+
+impl<
+        A: CanvasEncoding<Vec<u8>>,
+        B: CanvasEncoding<Vec<u8>>,
+        C: CanvasEncoding<Vec<u8>>,
+        D: CanvasEncoding<Vec<u8>>,
+        E: CanvasEncoding<Vec<u8>>,
+    > CanvasEncoding<Vec<u8>> for (A, B, C, D, E)
+{
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        self.0.encode_canvas(append_to);
+        self.1.encode_canvas(append_to);
+        self.2.encode_canvas(append_to);
+        self.3.encode_canvas(append_to);
+        self.4.encode_canvas(append_to);
+    }
+}
+
+impl<
+        A: CanvasEncoding<Vec<u8>>,
+        B: CanvasEncoding<Vec<u8>>,
+        C: CanvasEncoding<Vec<u8>>,
+        D: CanvasEncoding<Vec<u8>>,
+        E: CanvasEncoding<Vec<u8>>,
+        F: CanvasEncoding<Vec<u8>>,
+    > CanvasEncoding<Vec<u8>> for (A, B, C, D, E, F)
+{
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        self.0.encode_canvas(append_to);
+        self.1.encode_canvas(append_to);
+        self.2.encode_canvas(append_to);
+        self.3.encode_canvas(append_to);
+        self.4.encode_canvas(append_to);
+        self.5.encode_canvas(append_to);
+    }
+}
+
+//
+// Main canvas encoding
+//
+
+impl CanvasEncoding<Vec<u8>> for &Color {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        match self {
+            Color::Rgba(r, g, b, a) => (b'R', *r, *g, *b, *a),
+
+            other => {
+                let (r, g, b, a) = other.to_rgba_components();
+                (b'R', r, g, b, a)
+            }
+        }
+        .encode_canvas(append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &LineJoin {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        use self::LineJoin::*;
+
+        match *self {
+            Miter => b'M',
+            Round => b'R',
+            Bevel => b'B',
+        }
+        .encode_canvas(append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &LineCap {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        use self::LineCap::*;
+
+        match *self {
+            Butt => b'B',
+            Round => b'R',
+            Square => b'S',
+        }
+        .encode_canvas(append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &WindingRule {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        use self::WindingRule::*;
+
+        match *self {
+            NonZero => b'n',
+            EvenOdd => b'e',
+        }
+        .encode_canvas(append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &BlendMode {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        use self::BlendMode::*;
+
+        match *self {
+            SourceOver => (b'S', b'V'),
+            SourceIn => (b'S', b'I'),
+            SourceOut => (b'S', b'O'),
+            DestinationOver => (b'D', b'V'),
+            DestinationIn => (b'D', b'I'),
+            DestinationOut => (b'D', b'O'),
+            SourceAtop => (b'S', b'A'),
+            DestinationAtop => (b'D', b'A'),
+            Xor => (b'X', b'O'),
+            Clear => (b'C', b'L'),
+            Copy => (b'S', b'S'),
+            Destination => (b'D', b'D'),
+
+            Multiply => (b'E', b'M'),
+            Screen => (b'E', b'S'),
+            Darken => (b'E', b'D'),
+            Lighten => (b'E', b'L'),
+            Overlay => (b'E', b'O'),
+            ColorDodge => (b'E', b'C'),
+            ColorBurn => (b'E', b'B'),
+            HardLight => (b'E', b'H'),
+            SoftLight => (b'E', b'T'),
+            Difference => (b'E', b'I'),
+            Exclusion => (b'E', b'X'),
+
+            Hue => (b'N', b'H'),
+            Saturation => (b'N', b'S'),
+            Color => (b'N', b'C'),
+            Luminosity => (b'N', b'L'),
+
+            Plus => (b'P', b'L'),
+        }
+        .encode_canvas(append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &Transform2D {
+    #[inline]
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        let Transform2D(rows) = *self;
+
+        for row in rows.iter() {
+            for val in row.iter() {
+                val.encode_canvas(append_to);
+            }
+        }
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &LayerId {
+    #[inline]
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        let LayerId(layer_id) = self;
+        encode_binary_compact_u64(*layer_id, append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &SpriteId {
+    #[inline]
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        let SpriteId(sprite_id) = self;
+        encode_binary_compact_u64(*sprite_id, append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &TextureId {
+    #[inline]
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        let TextureId(texture_id) = self;
+        encode_binary_compact_u64(*texture_id, append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &FontId {
+    #[inline]
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        let FontId(font_id) = self;
+        encode_binary_compact_u64(*font_id, append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &GradientId {
+    #[inline]
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        let GradientId(gradient_id) = self;
+        encode_binary_compact_u64(*gradient_id, append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &SpriteTransform {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        use self::SpriteTransform::*;
+
+        match self {
+            Identity => b'i'.encode_canvas(append_to),
+            Translate(x, y) => (b't', *x, *y).encode_canvas(append_to),
+            Scale(x, y) => (b's', *x, *y).encode_canvas(append_to),
+            Rotate(degrees) => (b'r', *degrees).encode_canvas(append_to),
+            Transform2D(transform) => (b'T', transform).encode_canvas(append_to),
+        }
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &TextureFormat {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        use self::TextureFormat::*;
+
+        match self {
+            Rgba => b'r'.encode_canvas(append_to),
+        }
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &TextureOp {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        use self::TextureOp::*;
+
+        match self {
+            Create(TextureSize(width, height), format) => {
+                (b'N', *width, *height, format).encode_canvas(append_to)
+            }
+            Free => b'X'.encode_canvas(append_to),
+            SetBytes(TexturePosition(x, y), TextureSize(width, height), bytes) => {
+                (b'D', *x, *y, *width, *height, bytes.as_slice()).encode_canvas(append_to)
+            }
+            SetFromSprite(sprite_id, SpriteBounds(SpritePosition(x, y), SpriteSize(w, h))) => {
+                (b'S', sprite_id, *x, *y, *w, *h).encode_canvas(append_to)
+            }
+            CreateDynamicSprite(
+                sprite_id,
+                SpriteBounds(SpritePosition(x, y), SpriteSize(sprite_w, sprite_h)),
+                CanvasSize(canvas_w, canvas_h),
+            ) => (
+                b's',
+                sprite_id,
+                (*x, *y, *sprite_w, *sprite_h),
+                (*canvas_w, *canvas_h),
+            )
+                .encode_canvas(append_to),
+            FillTransparency(alpha) => (b't', *alpha).encode_canvas(append_to),
+            Copy(target_texture) => (b'C', target_texture).encode_canvas(append_to),
+            Filter(filter) => (b'F', filter).encode_canvas(append_to),
+        }
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &TextureFilter {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        use self::TextureFilter::*;
+
+        match self {
+            GaussianBlur(radius) => (b'B', *radius).encode_canvas(append_to),
+            AlphaBlend(alpha) => (b'A', *alpha).encode_canvas(append_to),
+            Mask(texture) => (b'M', texture).encode_canvas(append_to),
+            DisplacementMap(texture, xr, yr) => (b'D', texture, *xr, *yr).encode_canvas(append_to),
+        }
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &Vec<TextureFilter> {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        encode_binary_compact_u64(self.len() as u64, append_to);
+        self.iter()
+            .for_each(|filter| filter.encode_canvas(append_to));
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &GradientSpread {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        use self::GradientSpread::*;
+
+        match *self {
+            Pad => b'P',
+            Repeat => b'R',
+            Reflect => b'F',
+        }
+        .encode_canvas(append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &GradientOp {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        use self::GradientOp::*;
+
+        match self {
+            Create(color) => (b'N', color).encode_canvas(append_to),
+            AddStop(pos, color) => (b'S', pos, color).encode_canvas(append_to),
+            SpreadMode(spread) => (b'M', spread).encode_canvas(append_to),
+        }
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &FontOp {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        use self::FontOp::*;
+
+        match self {
+            FontSize(font_size) => (b'S', *font_size).encode_canvas(append_to),
+
+            UseFontDefinition(data) => (b'd', b'T', data.font_data()).encode_canvas(append_to),
+            DrawGlyphs(glyphs) => (b'G', glyphs).encode_canvas(append_to),
+            LayoutText(text) => (b'L', text.as_str()).encode_canvas(append_to),
+        }
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &TextAlignment {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        use TextAlignment::*;
+
+        match self {
+            Left => b'l',
+            Right => b'r',
+            Center => b'c',
+        }
+        .encode_canvas(append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &[u8] {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        encode_binary_compact_u64(self.len() as u64, append_to);
+        append_to.extend_from_slice(self);
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &str {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        self.as_bytes().encode_canvas(append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for GlyphPosition {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        self.id.0.encode_canvas(append_to);
+        self.location.encode_canvas(append_to);
+        self.em_size.encode_canvas(append_to);
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &Vec<GlyphPosition> {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        encode_binary_compact_u64(self.len() as u64, append_to);
+        self.iter().for_each(|pos| pos.encode_canvas(append_to));
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for &NamespaceId {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        let global_id = self.global_id();
+        let (global_a, global_b) = global_id.as_u64_pair();
+
+        (global_a, global_b).encode_canvas(append_to)
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for Draw {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        use self::Draw::*;
+        use self::PathOp::*;
+
+        // The instruction is written after the length, which is filled in once the instruction has been encoded
+        let start = append_to.len();
+
+        match self {
+            StartFrame => (b'N', b'F').encode_canvas(append_to),
+            ShowFrame => (b'N', b'f').encode_canvas(append_to),
+            ResetFrame => (b'N', b'G').encode_canvas(append_to),
+            Path(NewPath) => (b'N', b'p').encode_canvas(append_to),
+            Path(Move(x, y)) => (b'm', x, y).encode_canvas(append_to),
+            Path(Line(x, y)) => (b'l', x, y).encode_canvas(append_to),
+            Path(BezierCurve((cp1, cp2), p)) => (b'c', *p, *cp1, *cp2).encode_canvas(append_to),
+            Path(ClosePath) => b'.'.encode_canvas(append_to),
+            Fill => b'F'.encode_canvas(append_to),
+            Stroke => b'S'.encode_canvas(append_to),
+            LineWidth(width) => (b'L', b'w', width).encode_canvas(append_to),
+            LineWidthPixels(width) => (b'L', b'p', width).encode_canvas(append_to),
+            LineJoin(join) => (b'L', b'j', join).encode_canvas(append_to),
+            LineCap(cap) => (b'L', b'c', cap).encode_canvas(append_to),
+            WindingRule(rule) => (b'W', rule).encode_canvas(append_to),
+            NewDashPattern => (b'D', b'n').encode_canvas(append_to),
+            DashLength(length) => (b'D', b'l', length).encode_canvas(append_to),
+            DashOffset(offset) => (b'D', b'o', offset).encode_canvas(append_to),
+            StrokeColor(col) => (b'C', b's', col).encode_canvas(append_to),
+            FillColor(col) => (b'C', b'f', col).encode_canvas(append_to),
+            FillTexture(texture, (x1, y1), (x2, y2)) => {
+                (b'C', b't', texture, (x1, y1), (x2, y2)).encode_canvas(append_to)
+            }
+            FillGradient(gradient, (x1, y1), (x2, y2)) => {
+                (b'C', b'g', gradient, (x1, y1), (x2, y2)).encode_canvas(append_to)
+            }
+            FillRadialGradient(gradient, (x1, y1, r1), (x2, y2, r2)) => {
+                (b'C', b'r', gradient, (x1, y1, r1), (x2, y2, r2)).encode_canvas(append_to)
+            }
+            FillConicGradient(gradient, (x, y), start_angle) => {
+                (b'C', b'c', gradient, (x, y), start_angle).encode_canvas(append_to)
+            }
+            FillTransform(transform) => (b'C', b'T', transform).encode_canvas(append_to),
+            BlendMode(mode) => (b'M', mode).encode_canvas(append_to),
+            IdentityTransform => (b'T', b'i').encode_canvas(append_to),
+            CanvasHeight(height) => (b'T', b'h', height).encode_canvas(append_to),
+            CenterRegion(min, max) => (b'T', b'c', *min, *max).encode_canvas(append_to),
+            MultiplyTransform(transform) => (b'T', b'm', transform).encode_canvas(append_to),
+            Unclip => (b'Z', b'n').encode_canvas(append_to),
+            Clip => (b'Z', b'c').encode_canvas(append_to),
+            Store => (b'Z', b's').encode_canvas(append_to),
+            Restore => (b'Z', b'r').encode_canvas(append_to),
+            FreeStoredBuffer => (b'Z', b'f').encode_canvas(append_to),
+            PushState => b'P'.encode_canvas(append_to),
+            PopState => b'p'.encode_canvas(append_to),
+            ClearCanvas(color) => (b'N', b'A', color).encode_canvas(append_to),
+            Layer(layer_id) => (b'N', b'L', layer_id).encode_canvas(append_to),
+            LayerBlend(layer_id, blend_mode) => {
+                (b'N', b'B', layer_id, blend_mode).encode_canvas(append_to)
+            }
+            LayerAlpha(layer_id, alpha) => (b'N', b't', layer_id, alpha).encode_canvas(append_to),
+            ClearLayer => (b'N', b'C').encode_canvas(append_to),
+            ClearAllLayers => (b'N', b'a').encode_canvas(append_to),
+            SwapLayers(layer1, layer2) => (b'N', b'X', layer1, layer2).encode_canvas(append_to),
+            Sprite(sprite_id) => (b'N', b's', sprite_id).encode_canvas(append_to),
+            ClearSprite => (b's', b'C').encode_canvas(append_to),
+            SpriteTransform(sprite_transform) => {
+                (b's', b'T', sprite_transform).encode_canvas(append_to)
+            }
+            MoveSpriteFrom(sprite_id) => (b's', b'm', sprite_id).encode_canvas(append_to),
+            DrawSprite(sprite_id) => (b's', b'D', sprite_id).encode_canvas(append_to),
+            DrawSpriteWithFilters(sprite_id, filters) => {
+                (b's', b'F', sprite_id, filters).encode_canvas(append_to)
+            }
+            Texture(texture_id, ref op) => (b'B', texture_id, op).encode_canvas(append_to),
+            Font(font_id, ref op) => (b'f', font_id, op).encode_canvas(append_to),
+            DrawText(font_id, ref string, x, y) => {
+                (b't', b'T', font_id, string.as_str(), x, y).encode_canvas(append_to)
+            }
+            BeginLineLayout(x, y, align) => (b't', b'l', x, y, align).encode_canvas(append_to),
+            DrawLaidOutText => (b't', b'R').encode_canvas(append_to),
+            Gradient(gradient_id, ref gradient_op) => {
+                (b'G', gradient_id, gradient_op).encode_canvas(append_to)
+            }
+            Namespace(namespace_id) => (b'N', b'N', namespace_id).encode_canvas(append_to),
+        }
+
+        // Insert the length before the instruction
+        let mut length = vec![];
+        encode_binary_compact_u64((append_to.len() - start) as u64, &mut length);
+        append_to.splice(start..start, length);
+    }
+}
+
+impl CanvasEncoding<Vec<u8>> for Vec<Draw> {
+    fn encode_canvas(&self, append_to: &mut Vec<u8>) {
+        self.iter().for_each(|item| item.encode_canvas(append_to));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn encode_draw(item: Draw) -> Vec<u8> {
+        let mut result = vec![];
+        item.encode_canvas(&mut result);
+        result
+    }
+
+    #[test]
+    fn encode_compact_numbers() {
+        let mut encoded = vec![];
+        encode_binary_compact_u64(1, &mut encoded);
+        encode_binary_compact_u64(300, &mut encoded);
+
+        assert!(encoded == vec![0x01, 0xac, 0x02]);
+    }
+
+    #[test]
+    fn encode_fill() {
+        assert!(encode_draw(Draw::Fill) == vec![1, b'F']);
+    }
+
+    #[test]
+    fn encode_move() {
+        assert!(
+            encode_draw(Draw::Path(PathOp::Move(20.0, 1.0)))
+                == vec![9, b'm', 0x00, 0x00, 0xa0, 0x41, 0x00, 0x00, 0x80, 0x3f]
+        );
+    }
+
+    #[test]
+    fn encode_layer() {
+        assert!(encode_draw(Draw::Layer(LayerId(2))) == vec![3, b'N', b'L', 2]);
+    }
+
+    #[test]
+    fn encode_texture_bytes_without_expanding_them() {
+        let bytes = (0..=255).collect::<Vec<u8>>();
+        let encoded = encode_draw(Draw::Texture(
+            TextureId(1),
+            TextureOp::SetBytes(
+                TexturePosition(0, 0),
+                TextureSize(8, 8),
+                std::sync::Arc::new(bytes.clone()),
+            ),
+        ));
+
+        // Length (2 bytes), tags and ID (3 bytes), position and size (16 bytes), byte length (2 bytes) and the data
+        assert!(encoded.len() == 2 + 3 + 16 + 2 + 256);
+        assert!(encoded[0..2] == [0x95, 0x02]);
+        assert!(encoded[(encoded.len() - 256)..] == bytes[..]);
+    }
+}
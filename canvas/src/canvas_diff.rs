@@ -0,0 +1,712 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::draw::*;
+use crate::draw_resource::*;
+use crate::font::*;
+use crate::gradient::*;
+use crate::namespace::*;
+use crate::sprite::*;
+use crate::texture::*;
+
+use std::collections::HashMap;
+use std::mem;
+
+///
+/// Identifies a resource on a canvas that can be compared between two drawings
+///
+/// Sprites, textures and gradients belong to the namespace that was active when they were declared. Layers and fonts
+/// are shared between all namespaces.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CanvasResource {
+    /// The canvas itself (the background colour set by `ClearCanvas`)
+    Canvas,
+
+    /// The instructions drawn on a layer
+    Layer(LayerId),
+
+    /// The instructions drawn on a sprite
+    Sprite(NamespaceId, SpriteId),
+
+    /// The instructions that define a texture
+    Texture(NamespaceId, TextureId),
+
+    /// The instructions that define a gradient
+    Gradient(NamespaceId, GradientId),
+
+    /// The instructions that define a font
+    Font(FontId),
+}
+
+///
+/// A change to a resource between two drawings
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum CanvasResourceChange {
+    /// A resource that is only in the second drawing, along with the instructions that define it
+    Added {
+        resource: CanvasResource,
+        drawing: Vec<Draw>,
+    },
+
+    /// A resource that is only in the first drawing, along with the instructions that defined it
+    Removed {
+        resource: CanvasResource,
+        drawing: Vec<Draw>,
+    },
+
+    /// A resource that is in both drawings but is defined differently
+    Changed {
+        resource: CanvasResource,
+        before: Vec<Draw>,
+        after: Vec<Draw>,
+    },
+}
+
+///
+/// The instructions in a drawing, divided up according to the resource that they define
+///
+/// Instructions that change the drawing state (such as `FillColor` or `MultiplyTransform`) are not stored directly. Instead,
+/// every time something is drawn on a layer or a sprite after the state has changed, the instructions needed to recreate the
+/// whole state are added first. This means that the instructions stored for a layer or sprite only change if what is drawn
+/// on it changes, and that changing the state before switching to a different layer is attributed to that layer.
+///
+/// Resources with no instructions (for instance, a layer that was selected but never drawn on) are left out.
+///
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct CanvasResources {
+    /// The resources in the order they were first defined
+    order: Vec<CanvasResource>,
+
+    /// The instructions that define each resource
+    resources: HashMap<CanvasResource, Vec<Draw>>,
+}
+
+///
+/// The differences between two drawings, as a list of resources that were added, removed or changed
+///
+/// This is usually used to compare two results from `Canvas::get_drawing()`. The `Added` and `Changed` entries contain the
+/// instructions that define each resource in the second drawing, so something that is already displaying the first drawing
+/// only needs to redraw those resources.
+///
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct CanvasDiff {
+    changes: Vec<CanvasResourceChange>,
+}
+
+///
+/// Tracks the state while dividing a drawing into resources
+///
+struct ResourceState {
+    /// The resources found so far
+    resources: CanvasResources,
+
+    /// The layer or sprite that is currently being drawn on
+    active: CanvasResource,
+
+    /// The namespace that sprites, textures and gradients are declared in
+    namespace: NamespaceId,
+
+    /// The instructions that set up the current drawing state, along with the drawing resource that they change
+    state: Vec<(DrawResource, Draw)>,
+
+    /// The states saved by `PushState`
+    state_stack: Vec<Vec<(DrawResource, Draw)>>,
+
+    /// True if the state needs to be written out before anything else is drawn on the active resource
+    state_changed: bool,
+}
+
+impl CanvasResourceChange {
+    ///
+    /// The resource that was changed
+    ///
+    pub fn resource(&self) -> CanvasResource {
+        match self {
+            CanvasResourceChange::Added { resource, .. }
+            | CanvasResourceChange::Removed { resource, .. }
+            | CanvasResourceChange::Changed { resource, .. } => *resource,
+        }
+    }
+}
+
+impl CanvasResources {
+    ///
+    /// Divides up the instructions in a drawing according to the resources they define
+    ///
+    pub fn from_drawing<'a, DrawIter: IntoIterator<Item = &'a Draw>>(
+        drawing: DrawIter,
+    ) -> CanvasResources {
+        let mut state = ResourceState::new();
+
+        for draw in drawing {
+            state.draw(draw);
+        }
+
+        state.finish()
+    }
+
+    ///
+    /// Returns the instructions that define a resource, or None if the resource is not part of the drawing
+    ///
+    pub fn get(&self, resource: &CanvasResource) -> Option<&[Draw]> {
+        self.resources
+            .get(resource)
+            .map(|drawing| drawing.as_slice())
+    }
+
+    ///
+    /// Iterates over the resources in this drawing, in the order they were first defined
+    ///
+    pub fn iter(&self) -> impl '_ + Iterator<Item = (CanvasResource, &[Draw])> {
+        self.order
+            .iter()
+            .map(move |resource| (*resource, self.resources[resource].as_slice()))
+    }
+
+    ///
+    /// Returns the number of resources in this drawing
+    ///
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    ///
+    /// Returns true if the drawing does not define any resources
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    ///
+    /// Finds the differences between this drawing and a later one
+    ///
+    /// Added and changed resources are listed in the order they are defined in `after`, followed by the removed resources
+    /// in the order they were defined in this drawing.
+    ///
+    pub fn diff(&self, after: &CanvasResources) -> CanvasDiff {
+        let mut changes = vec![];
+
+        for (resource, after_drawing) in after.iter() {
+            match self.get(&resource) {
+                None => changes.push(CanvasResourceChange::Added {
+                    resource,
+                    drawing: after_drawing.to_vec(),
+                }),
+                Some(before_drawing) if before_drawing != after_drawing => {
+                    changes.push(CanvasResourceChange::Changed {
+                        resource,
+                        before: before_drawing.to_vec(),
+                        after: after_drawing.to_vec(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        for (resource, before_drawing) in self.iter() {
+            if after.get(&resource).is_none() {
+                changes.push(CanvasResourceChange::Removed {
+                    resource,
+                    drawing: before_drawing.to_vec(),
+                });
+            }
+        }
+
+        CanvasDiff { changes }
+    }
+
+    ///
+    /// Returns the instructions for a resource, creating it if it doesn't already exist
+    ///
+    fn resource_mut(&mut self, resource: CanvasResource) -> &mut Vec<Draw> {
+        let order = &mut self.order;
+
+        self.resources.entry(resource).or_insert_with(|| {
+            order.push(resource);
+            vec![]
+        })
+    }
+
+    ///
+    /// Removes a resource from this drawing
+    ///
+    fn remove(&mut self, resource: &CanvasResource) -> Vec<Draw> {
+        if let Some(drawing) = self.resources.remove(resource) {
+            self.order.retain(|existing| existing != resource);
+            drawing
+        } else {
+            vec![]
+        }
+    }
+}
+
+impl CanvasDiff {
+    ///
+    /// Finds the differences between two drawings (usually two results from `Canvas::get_drawing()`)
+    ///
+    pub fn between(before: &[Draw], after: &[Draw]) -> CanvasDiff {
+        CanvasResources::from_drawing(before).diff(&CanvasResources::from_drawing(after))
+    }
+
+    ///
+    /// True if the two drawings define the same resources
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    ///
+    /// The changes between the two drawings
+    ///
+    pub fn changes(&self) -> &[CanvasResourceChange] {
+        &self.changes
+    }
+
+    ///
+    /// Returns the change for a particular resource, or None if it's the same in both drawings
+    ///
+    pub fn change(&self, resource: &CanvasResource) -> Option<&CanvasResourceChange> {
+        self.changes
+            .iter()
+            .find(|change| &change.resource() == resource)
+    }
+
+    ///
+    /// The resources that are only in the second drawing
+    ///
+    pub fn added(&self) -> impl '_ + Iterator<Item = CanvasResource> {
+        self.changes.iter().filter_map(|change| match change {
+            CanvasResourceChange::Added { resource, .. } => Some(*resource),
+            _ => None,
+        })
+    }
+
+    ///
+    /// The resources that are only in the first drawing
+    ///
+    pub fn removed(&self) -> impl '_ + Iterator<Item = CanvasResource> {
+        self.changes.iter().filter_map(|change| match change {
+            CanvasResourceChange::Removed { resource, .. } => Some(*resource),
+            _ => None,
+        })
+    }
+
+    ///
+    /// The resources that are in both drawings but are defined differently
+    ///
+    pub fn changed(&self) -> impl '_ + Iterator<Item = CanvasResource> {
+        self.changes.iter().filter_map(|change| match change {
+            CanvasResourceChange::Changed { resource, .. } => Some(*resource),
+            _ => None,
+        })
+    }
+}
+
+impl IntoIterator for CanvasDiff {
+    type Item = CanvasResourceChange;
+    type IntoIter = std::vec::IntoIter<CanvasResourceChange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
+}
+
+impl ResourceState {
+    ///
+    /// Creates the state for the start of a drawing
+    ///
+    fn new() -> ResourceState {
+        ResourceState {
+            resources: CanvasResources::default(),
+            active: CanvasResource::Layer(LayerId(0)),
+            namespace: NamespaceId::default(),
+            state: vec![],
+            state_stack: vec![],
+            state_changed: false,
+        }
+    }
+
+    ///
+    /// The drawing resource corresponding to the active layer or sprite
+    ///
+    fn active_draw_resource(&self) -> DrawResource {
+        match self.active {
+            CanvasResource::Sprite(_, sprite_id) => DrawResource::Sprite(sprite_id),
+            CanvasResource::Layer(layer_id) => DrawResource::Layer(layer_id),
+            _ => DrawResource::Canvas,
+        }
+    }
+
+    ///
+    /// Changes the layer or sprite that is being drawn on
+    ///
+    fn select(&mut self, resource: CanvasResource) {
+        if self.active != resource {
+            self.active = resource;
+            self.state_changed = true;
+        }
+    }
+
+    ///
+    /// Adds an instruction that draws on the active layer or sprite
+    ///
+    fn draw_on_active(&mut self, draw: &Draw) {
+        let active = self.resources.resource_mut(self.active);
+
+        if self.state_changed {
+            active.extend(self.state.iter().map(|(_, state)| state.clone()));
+            self.state_changed = false;
+        }
+
+        active.push(draw.clone());
+    }
+
+    ///
+    /// Updates the drawing state
+    ///
+    fn change_state(&mut self, draw: &Draw) {
+        let active = self.active_draw_resource();
+        let target = draw.target_resource(&active);
+
+        // Instructions that don't depend on the previous value of their resource replace it
+        if !draw.source_resource(&active).contains(&target) {
+            self.state.retain(|(resource, _)| resource != &target);
+        }
+
+        self.state.push((target, draw.clone()));
+        self.state_changed = true;
+    }
+
+    ///
+    /// Processes the next instruction in the drawing
+    ///
+    fn draw(&mut self, draw: &Draw) {
+        use self::Draw::*;
+
+        let namespace = self.namespace;
+
+        match draw {
+            // Frames don't change what's drawn
+            StartFrame | ShowFrame | ResetFrame => {}
+
+            ClearCanvas(_) => {
+                *self = ResourceState::new();
+                self.resources
+                    .resource_mut(CanvasResource::Canvas)
+                    .push(draw.clone());
+            }
+
+            Namespace(namespace_id) => {
+                self.namespace = *namespace_id;
+            }
+
+            Layer(layer_id) => self.select(CanvasResource::Layer(*layer_id)),
+            Sprite(sprite_id) => self.select(CanvasResource::Sprite(namespace, *sprite_id)),
+
+            ClearLayer | ClearSprite => {
+                self.resources.resource_mut(self.active).clear();
+                self.state_changed = true;
+            }
+
+            ClearAllLayers => {
+                for (resource, drawing) in self.resources.resources.iter_mut() {
+                    if let CanvasResource::Layer(_) = resource {
+                        drawing.clear();
+                    }
+                }
+                self.state_changed = true;
+            }
+
+            SwapLayers(layer1, layer2) => {
+                let drawing1 =
+                    mem::take(self.resources.resource_mut(CanvasResource::Layer(*layer1)));
+                let drawing2 = mem::replace(
+                    self.resources.resource_mut(CanvasResource::Layer(*layer2)),
+                    drawing1,
+                );
+                *self.resources.resource_mut(CanvasResource::Layer(*layer1)) = drawing2;
+            }
+
+            LayerBlend(layer_id, _) | LayerAlpha(layer_id, _) => {
+                self.resources
+                    .resource_mut(CanvasResource::Layer(*layer_id))
+                    .push(draw.clone());
+            }
+
+            Texture(texture_id, op) => {
+                let resource = CanvasResource::Texture(namespace, *texture_id);
+
+                match op {
+                    TextureOp::Create(_, _) | TextureOp::CreateDynamicSprite(_, _, _) => {
+                        *self.resources.resource_mut(resource) = vec![draw.clone()];
+                    }
+
+                    TextureOp::Free => {
+                        self.resources.remove(&resource);
+                    }
+
+                    TextureOp::Copy(target_id) => {
+                        // The target texture becomes a copy of this one
+                        let mut copy = self.resources.resource_mut(resource).clone();
+                        copy.push(draw.clone());
+
+                        self.resources
+                            .remove(&CanvasResource::Texture(namespace, *target_id));
+                        *self
+                            .resources
+                            .resource_mut(CanvasResource::Texture(namespace, *target_id)) = copy;
+                    }
+
+                    _ => self.resources.resource_mut(resource).push(draw.clone()),
+                }
+            }
+
+            Gradient(gradient_id, op) => {
+                let resource = CanvasResource::Gradient(namespace, *gradient_id);
+
+                match op {
+                    GradientOp::Create(_) => {
+                        *self.resources.resource_mut(resource) = vec![draw.clone()]
+                    }
+                    _ => self.resources.resource_mut(resource).push(draw.clone()),
+                }
+            }
+
+            Font(font_id, FontOp::UseFontDefinition(_)) => {
+                *self.resources.resource_mut(CanvasResource::Font(*font_id)) = vec![draw.clone()];
+            }
+
+            Font(font_id, FontOp::FontSize(_)) => {
+                self.resources
+                    .resource_mut(CanvasResource::Font(*font_id))
+                    .push(draw.clone());
+            }
+
+            PushState => {
+                self.state_stack.push(self.state.clone());
+            }
+
+            PopState => {
+                if let Some(state) = self.state_stack.pop() {
+                    if state != self.state {
+                        self.state = state;
+                        self.state_changed = true;
+                    }
+                }
+            }
+
+            _ => {
+                if draw.is_state_stack_resource() {
+                    self.change_state(draw);
+                } else {
+                    self.draw_on_active(draw);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Returns the resources defined by the drawing, leaving out any that are empty
+    ///
+    fn finish(self) -> CanvasResources {
+        let mut resources = self.resources;
+
+        let empty = resources
+            .iter()
+            .filter(|(_, drawing)| drawing.is_empty())
+            .map(|(resource, _)| resource)
+            .collect::<Vec<_>>();
+
+        for resource in empty {
+            resources.remove(&resource);
+        }
+
+        resources
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::color::*;
+    use crate::context::*;
+    use crate::primitives::*;
+
+    fn draw_circle(gc: &mut Vec<Draw>, layer: u64, radius: f32) {
+        gc.layer(LayerId(layer));
+        gc.new_path();
+        gc.circle(100.0, 100.0, radius);
+        gc.fill();
+    }
+
+    #[test]
+    fn same_drawing_has_no_changes() {
+        let mut drawing = vec![];
+        drawing.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+        drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        draw_circle(&mut drawing, 0, 50.0);
+        draw_circle(&mut drawing, 1, 20.0);
+
+        let diff = CanvasDiff::between(&drawing, &drawing.clone());
+
+        assert!(diff.is_empty(), "{:?}", diff);
+    }
+
+    #[test]
+    fn changed_layer() {
+        let mut before = vec![];
+        draw_circle(&mut before, 0, 50.0);
+        draw_circle(&mut before, 1, 20.0);
+
+        let mut after = vec![];
+        draw_circle(&mut after, 0, 50.0);
+        draw_circle(&mut after, 1, 30.0);
+
+        let diff = CanvasDiff::between(&before, &after);
+
+        assert!(diff.changed().collect::<Vec<_>>() == vec![CanvasResource::Layer(LayerId(1))]);
+        assert!(diff.added().count() == 0);
+        assert!(diff.removed().count() == 0);
+    }
+
+    #[test]
+    fn added_and_removed_layers() {
+        let mut before = vec![];
+        draw_circle(&mut before, 0, 50.0);
+        draw_circle(&mut before, 1, 20.0);
+
+        let mut after = vec![];
+        draw_circle(&mut after, 0, 50.0);
+        draw_circle(&mut after, 2, 20.0);
+
+        let diff = CanvasDiff::between(&before, &after);
+
+        assert!(diff.added().collect::<Vec<_>>() == vec![CanvasResource::Layer(LayerId(2))]);
+        assert!(diff.removed().collect::<Vec<_>>() == vec![CanvasResource::Layer(LayerId(1))]);
+        assert!(diff.changed().count() == 0);
+
+        match diff.change(&CanvasResource::Layer(LayerId(2))) {
+            Some(CanvasResourceChange::Added { drawing, .. }) => {
+                assert!(drawing.contains(&Draw::Fill));
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn empty_layer_is_not_a_resource() {
+        let mut before = vec![];
+        draw_circle(&mut before, 0, 50.0);
+
+        let mut after = before.clone();
+        after.layer(LayerId(3));
+
+        assert!(CanvasDiff::between(&before, &after).is_empty());
+    }
+
+    #[test]
+    fn state_change_is_attributed_to_the_layer_drawn_on() {
+        let mut before = vec![];
+        draw_circle(&mut before, 0, 50.0);
+        before.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        draw_circle(&mut before, 1, 20.0);
+
+        let mut after = vec![];
+        draw_circle(&mut after, 0, 50.0);
+        after.fill_color(Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        draw_circle(&mut after, 1, 20.0);
+
+        let diff = CanvasDiff::between(&before, &after);
+
+        assert!(diff.changed().collect::<Vec<_>>() == vec![CanvasResource::Layer(LayerId(1))]);
+    }
+
+    #[test]
+    fn unused_state_change_is_ignored() {
+        let mut before = vec![];
+        draw_circle(&mut before, 0, 50.0);
+
+        let mut after = vec![];
+        draw_circle(&mut after, 0, 50.0);
+        after.push_state();
+        after.fill_color(Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        after.pop_state();
+        after.line_width(4.0);
+
+        assert!(CanvasDiff::between(&before, &after).is_empty());
+    }
+
+    #[test]
+    fn swapped_layers() {
+        let mut before = vec![];
+        draw_circle(&mut before, 0, 50.0);
+        draw_circle(&mut before, 1, 20.0);
+
+        let mut after = before.clone();
+        after.swap_layers(LayerId(0), LayerId(1));
+
+        let diff = CanvasDiff::between(&before, &after);
+        let after_resources = CanvasResources::from_drawing(&after);
+        let before_resources = CanvasResources::from_drawing(&before);
+
+        assert!(diff.changed().count() == 2);
+        assert!(
+            after_resources.get(&CanvasResource::Layer(LayerId(0)))
+                == before_resources.get(&CanvasResource::Layer(LayerId(1)))
+        );
+    }
+
+    #[test]
+    fn clear_canvas_removes_everything() {
+        let mut before = vec![];
+        before.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+        draw_circle(&mut before, 0, 50.0);
+        before.create_gradient(GradientId(1), Color::Rgba(0.0, 0.0, 0.0, 1.0));
+
+        let mut after = before.clone();
+        after.clear_canvas(Color::Rgba(0.0, 0.0, 0.0, 1.0));
+
+        let diff = CanvasDiff::between(&before, &after);
+
+        assert!(diff.changed().collect::<Vec<_>>() == vec![CanvasResource::Canvas]);
+        assert!(diff.removed().count() == 2);
+    }
+
+    #[test]
+    fn sprites_textures_and_gradients() {
+        let namespace = NamespaceId::default();
+
+        let mut before = vec![];
+        before.sprite(SpriteId(0));
+        before.new_path();
+        before.rect(0.0, 0.0, 10.0, 10.0);
+        before.fill();
+        before.layer(LayerId(0));
+        before.draw_sprite(SpriteId(0));
+        before.create_gradient(GradientId(1), Color::Rgba(0.0, 0.0, 0.0, 1.0));
+        before.create_texture(TextureId(2), 4, 4, TextureFormat::Rgba);
+
+        let mut after = before.clone();
+        after.gradient_stop(GradientId(1), 1.0, Color::Rgba(1.0, 1.0, 1.0, 1.0));
+        after.free_texture(TextureId(2));
+
+        let diff = CanvasDiff::between(&before, &after);
+
+        assert!(
+            diff.changed().collect::<Vec<_>>()
+                == vec![CanvasResource::Gradient(namespace, GradientId(1))]
+        );
+        assert!(
+            diff.removed().collect::<Vec<_>>()
+                == vec![CanvasResource::Texture(namespace, TextureId(2))]
+        );
+        assert!(CanvasResources::from_drawing(&after)
+            .get(&CanvasResource::Sprite(namespace, SpriteId(0)))
+            .is_some());
+    }
+}
@@ -137,6 +137,31 @@ pub trait GraphicsContext {
         self.draw(Draw::FillGradient(gradient_id, (x1, y1), (x2, y2)));
     }
 
+    /// Sets a radial gradient to use for the next fill() operation
+    ///
+    /// The gradient starts at the circle `start_circle` and ends at the circle `end_circle`, each given as `(x, y, radius)`.
+    /// Use the same center point for both circles for a simple radial gradient, or move the start circle to create a focal
+    /// point.
+    fn fill_radial_gradient(
+        &mut self,
+        gradient_id: GradientId,
+        start_circle: (f32, f32, f32),
+        end_circle: (f32, f32, f32),
+    ) {
+        self.draw(Draw::FillRadialGradient(
+            gradient_id,
+            start_circle,
+            end_circle,
+        ));
+    }
+
+    /// Sets a conic gradient to use for the next fill() operation
+    ///
+    /// The gradient sweeps anticlockwise around the point `x, y`, starting at `start_angle` (in radians, measured from the x axis)
+    fn fill_conic_gradient(&mut self, gradient_id: GradientId, x: f32, y: f32, start_angle: f32) {
+        self.draw(Draw::FillConicGradient(gradient_id, (x, y), start_angle));
+    }
+
     /// Applies a transformation to the fill texture or gradient
     fn fill_transform(&mut self, transform: Transform2D) {
         self.draw(Draw::FillTransform(transform));
@@ -462,6 +487,11 @@ pub trait GraphicsContext {
         self.draw(Draw::Gradient(gradient_id, GradientOp::AddStop(pos, color)));
     }
 
+    /// Sets how a gradient is extended beyond its first and last stops (gradients are padded by default)
+    fn gradient_spread(&mut self, gradient_id: GradientId, spread: GradientSpread) {
+        self.draw(Draw::Gradient(gradient_id, GradientOp::SpreadMode(spread)));
+    }
+
     /// Sends a single drawing instruction to this graphics context
     fn draw(&mut self, d: Draw);
 }
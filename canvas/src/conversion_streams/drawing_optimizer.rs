@@ -0,0 +1,903 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::draw::*;
+use crate::draw_resource::*;
+use crate::namespace::*;
+use crate::path::*;
+use crate::sprite::*;
+use crate::transform2d::*;
+
+use flo_stream::*;
+use futures::prelude::*;
+
+use std::collections::HashMap;
+use std::sync::*;
+
+///
+/// Counts of the instructions that were removed or merged by a `DrawingOptimizer`
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OptimizationStats {
+    /// The number of instructions passed to the optimizer
+    pub instructions_read: usize,
+
+    /// The number of instructions generated by the optimizer
+    pub instructions_written: usize,
+
+    /// State changes (such as `FillColor` or `LineWidth`) that were removed because they set a value that was already set
+    pub redundant_state_changes: usize,
+
+    /// Transform instructions that were merged into a neighbouring transform, or removed because they had no effect
+    pub folded_transforms: usize,
+
+    /// `Fill` instructions that were removed because the path was entirely outside of the viewport
+    pub culled_fills: usize,
+
+    /// Layer selections that were removed because nothing was drawn on the layer before another was selected
+    pub empty_layers: usize,
+
+    /// Sprite selections that were removed because nothing was drawn on the sprite before another was selected
+    pub empty_sprites: usize,
+}
+
+impl OptimizationStats {
+    ///
+    /// The total number of instructions removed by the optimizer
+    ///
+    pub fn instructions_removed(&self) -> usize {
+        self.instructions_read
+            .saturating_sub(self.instructions_written)
+    }
+}
+
+///
+/// The layer or sprite that drawing instructions are being sent to
+///
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum DrawingTarget {
+    Layer(LayerId),
+    Sprite(NamespaceId, SpriteId),
+}
+
+///
+/// State saved by a `PushState` instruction
+///
+#[derive(Clone)]
+struct OptimizerStackEntry {
+    target: DrawingTarget,
+    known_state: HashMap<DrawResource, Draw>,
+    transform: Option<Transform2D>,
+    namespace: NamespaceId,
+}
+
+///
+/// Removes instructions from a drawing that will not change how it is rendered
+///
+/// The optimizer makes these changes:
+///
+/// * State changes such as `FillColor` or `LineWidth` that set the value that's already in effect are removed
+/// * Consecutive `MultiplyTransform` instructions are combined into one, and transforms that are immediately replaced
+///   by `IdentityTransform` or `CanvasHeight` are removed
+/// * `Fill` instructions are removed if the path is entirely outside of the viewport (only when a viewport size has been
+///   set with `with_viewport()`)
+/// * Selecting a layer or a sprite is removed if another is selected before anything is drawn on it
+///
+/// The optimizer keeps track of the drawing state between calls to `optimize()`, so a drawing can be optimized in
+/// sections as it's generated, as `optimized_drawing_stream()` does. It makes conservative assumptions about the state
+/// of the renderer: for example, state changes are assumed to be unknown after a different layer is selected, and
+/// fills are only culled on layers (sprites can be drawn anywhere).
+///
+pub struct DrawingOptimizer {
+    /// The size of the viewport in pixels, if known
+    viewport: Option<(f32, f32)>,
+
+    /// The layer or sprite that's currently selected
+    target: DrawingTarget,
+
+    /// The target that was selected before the most recent `Layer` or `Sprite` instruction, if that is the last instruction in the output
+    previous_target: Option<DrawingTarget>,
+
+    /// The number of layers that are known to exist
+    layer_count: u64,
+
+    /// The current namespace, which is used to identify sprites
+    namespace: NamespaceId,
+
+    /// The last instruction that set each state resource for the current target
+    known_state: HashMap<DrawResource, Draw>,
+
+    /// The current canvas transform, if known
+    transform: Option<Transform2D>,
+
+    /// The bounds of the current path (in the coordinates it was specified in)
+    path_bounds: Option<((f32, f32), (f32, f32))>,
+
+    /// True if the path bounds can be used to cull fills (the transform has not changed since the path was started)
+    path_cullable: bool,
+
+    /// The states saved by `PushState`
+    stack: Vec<OptimizerStackEntry>,
+
+    /// The statistics for this optimizer
+    stats: Arc<Mutex<OptimizationStats>>,
+}
+
+impl Default for DrawingOptimizer {
+    fn default() -> Self {
+        DrawingOptimizer::new()
+    }
+}
+
+impl DrawingOptimizer {
+    ///
+    /// Creates a new drawing optimizer, which will not cull any fills
+    ///
+    pub fn new() -> DrawingOptimizer {
+        DrawingOptimizer {
+            viewport: None,
+            target: DrawingTarget::Layer(LayerId(0)),
+            previous_target: None,
+            layer_count: 1,
+            namespace: NamespaceId::default(),
+            known_state: HashMap::new(),
+            transform: None,
+            path_bounds: None,
+            path_cullable: false,
+            stack: vec![],
+            stats: Arc::new(Mutex::new(OptimizationStats::default())),
+        }
+    }
+
+    ///
+    /// Sets the size in pixels of the window the drawing will be rendered to, which allows fills outside of the window to be removed
+    ///
+    /// Only the aspect ratio of the window affects which fills are removed, but the size is used to leave a one pixel margin
+    /// for antialiasing.
+    ///
+    pub fn with_viewport(mut self, width: f32, height: f32) -> DrawingOptimizer {
+        if width > 0.0 && height > 0.0 {
+            self.viewport = Some((width, height));
+        } else {
+            self.viewport = None;
+        }
+
+        self
+    }
+
+    ///
+    /// Returns the statistics for all of the instructions optimized so far
+    ///
+    pub fn stats(&self) -> OptimizationStats {
+        *self.stats.lock().unwrap()
+    }
+
+    ///
+    /// Returns a reference to the statistics for this optimizer, which will be updated as more instructions are optimized
+    ///
+    /// This can be used to read the statistics after the optimizer has been passed to `optimized_drawing_stream()`.
+    ///
+    pub fn shared_stats(&self) -> Arc<Mutex<OptimizationStats>> {
+        Arc::clone(&self.stats)
+    }
+
+    ///
+    /// Optimizes a section of a drawing
+    ///
+    /// The state at the end of this section is used when optimizing the next one.
+    ///
+    pub fn optimize(&mut self, drawing: impl IntoIterator<Item = Draw>) -> Vec<Draw> {
+        let mut stats = OptimizationStats::default();
+        let mut output = vec![];
+
+        for draw in drawing {
+            stats.instructions_read += 1;
+            self.optimize_instruction(draw, &mut output, &mut stats);
+        }
+
+        // Instructions in the output can't be changed once they've been returned
+        self.previous_target = None;
+
+        stats.instructions_written = output.len();
+        self.add_stats(&stats);
+
+        output
+    }
+
+    ///
+    /// Adds the statistics from a section of the drawing to the totals for this optimizer
+    ///
+    fn add_stats(&self, new_stats: &OptimizationStats) {
+        let mut stats = self.stats.lock().unwrap();
+
+        stats.instructions_read += new_stats.instructions_read;
+        stats.instructions_written += new_stats.instructions_written;
+        stats.redundant_state_changes += new_stats.redundant_state_changes;
+        stats.folded_transforms += new_stats.folded_transforms;
+        stats.culled_fills += new_stats.culled_fills;
+        stats.empty_layers += new_stats.empty_layers;
+        stats.empty_sprites += new_stats.empty_sprites;
+    }
+
+    ///
+    /// Adds an instruction to the output
+    ///
+    #[inline]
+    fn write(&mut self, draw: Draw, output: &mut Vec<Draw>) {
+        self.previous_target = None;
+        output.push(draw);
+    }
+
+    ///
+    /// Processes a single instruction, adding it to the output if it's needed
+    ///
+    fn optimize_instruction(
+        &mut self,
+        draw: Draw,
+        output: &mut Vec<Draw>,
+        stats: &mut OptimizationStats,
+    ) {
+        use self::Draw::*;
+
+        match draw {
+            Layer(layer_id) => {
+                self.select_target(DrawingTarget::Layer(layer_id), draw, output, stats)
+            }
+            Sprite(sprite_id) => self.select_target(
+                DrawingTarget::Sprite(self.namespace, sprite_id),
+                draw,
+                output,
+                stats,
+            ),
+
+            Namespace(namespace) => {
+                self.namespace = namespace;
+                self.write(draw, output);
+            }
+
+            ClearCanvas(_) => {
+                self.target = DrawingTarget::Layer(LayerId(0));
+                self.layer_count = 1;
+                self.namespace = NamespaceId::default();
+                self.known_state.clear();
+                self.transform = Some(Transform2D::identity());
+                self.path_bounds = None;
+                self.path_cullable = false;
+                self.stack.clear();
+
+                self.write(draw, output);
+            }
+
+            PushState => {
+                self.stack.push(OptimizerStackEntry {
+                    target: self.target,
+                    known_state: self.known_state.clone(),
+                    transform: self.transform,
+                    namespace: self.namespace,
+                });
+
+                self.write(draw, output);
+            }
+
+            PopState => {
+                if let Some(entry) = self.stack.pop() {
+                    // Some renderers store the state for each layer, so the state is only known if the same target is selected
+                    self.known_state = if entry.target == self.target {
+                        entry.known_state
+                    } else {
+                        HashMap::new()
+                    };
+                    self.transform = entry.transform;
+                    self.namespace = entry.namespace;
+                } else {
+                    self.known_state.clear();
+                    self.transform = None;
+                }
+
+                self.path_cullable = false;
+                self.write(draw, output);
+            }
+
+            IdentityTransform | CanvasHeight(_) => {
+                // These replace the transform, so any transform immediately before them has no effect (sprites apply transforms as they're set, so this only applies to layers)
+                if let DrawingTarget::Layer(_) = self.target {
+                    while let Some(
+                        IdentityTransform
+                        | CanvasHeight(_)
+                        | CenterRegion(_, _)
+                        | MultiplyTransform(_),
+                    ) = output.last()
+                    {
+                        output.pop();
+                        stats.folded_transforms += 1;
+                    }
+                }
+
+                self.transform = match draw {
+                    CanvasHeight(height) if height >= 1.0 => {
+                        Some(Transform2D::scale(2.0 / height, 2.0 / height))
+                    }
+                    CanvasHeight(_) => None,
+                    _ => Some(Transform2D::identity()),
+                };
+                self.path_cullable = false;
+                self.write(draw, output);
+            }
+
+            CenterRegion((x1, y1), (x2, y2)) => {
+                self.transform = self.transform.and_then(|transform| {
+                    let (center_x, center_y) = transform.invert()?.transform_point(0.0, 0.0);
+                    let (new_x, new_y) = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+
+                    Some(
+                        transform
+                            * Transform2D::translate(-(new_x - center_x), -(new_y - center_y)),
+                    )
+                });
+                self.path_cullable = false;
+                self.write(draw, output);
+            }
+
+            MultiplyTransform(transform) => {
+                self.transform = self.transform.map(|current| current * transform);
+                self.path_cullable = false;
+
+                if transform == Transform2D::identity() {
+                    stats.folded_transforms += 1;
+                } else if let Some(MultiplyTransform(last_transform)) = output.last_mut() {
+                    *last_transform = *last_transform * transform;
+                    stats.folded_transforms += 1;
+                } else {
+                    self.write(draw, output);
+                }
+            }
+
+            Path(path_op) => {
+                self.path_op(&path_op);
+                self.write(draw, output);
+            }
+
+            Fill => {
+                if self.fill_is_outside_viewport() {
+                    stats.culled_fills += 1;
+                } else {
+                    self.write(draw, output);
+                }
+            }
+
+            ClearLayer | ClearSprite => {
+                // Clearing a layer resets its state and the path
+                self.known_state.clear();
+                self.path_bounds = None;
+                self.path_cullable = false;
+                self.write(draw, output);
+            }
+
+            ClearAllLayers | SwapLayers(_, _) | MoveSpriteFrom(_) => {
+                self.known_state.clear();
+                self.write(draw, output);
+            }
+
+            draw => {
+                if draw.is_state_stack_resource() {
+                    let resource = draw.target_resource(&DrawResource::Canvas);
+
+                    if Self::is_replaceable_state(&draw) {
+                        if self.known_state.get(&resource) == Some(&draw) {
+                            stats.redundant_state_changes += 1;
+                        } else {
+                            self.known_state.insert(resource, draw.clone());
+                            self.write(draw, output);
+                        }
+                    } else {
+                        // Instructions that modify the existing state (or depend on other resources) leave the state unknown
+                        self.known_state.remove(&resource);
+                        self.write(draw, output);
+                    }
+                } else {
+                    self.write(draw, output);
+                }
+            }
+        }
+    }
+
+    ///
+    /// True if a state instruction replaces the state entirely (so a second identical instruction has no effect)
+    ///
+    fn is_replaceable_state(draw: &Draw) -> bool {
+        use self::Draw::*;
+
+        matches!(
+            draw,
+            FillColor(_)
+                | StrokeColor(_)
+                | LineWidth(_)
+                | LineJoin(_)
+                | LineCap(_)
+                | WindingRule(_)
+                | BlendMode(_)
+        )
+    }
+
+    ///
+    /// Selects a new layer or sprite for drawing
+    ///
+    fn select_target(
+        &mut self,
+        target: DrawingTarget,
+        draw: Draw,
+        output: &mut Vec<Draw>,
+        stats: &mut OptimizationStats,
+    ) {
+        // If the previous instruction selected a layer or sprite, nothing was drawn there and it can be removed
+        if let Some(previous_target) = self.previous_target {
+            let empty_target = self.target;
+
+            // Selecting a layer also creates all of the layers below it, so a layer selection is only removed if the layer already exists
+            let can_remove = match (empty_target, target) {
+                (
+                    DrawingTarget::Layer(LayerId(empty_id)),
+                    DrawingTarget::Layer(LayerId(new_id)),
+                ) => empty_id < self.layer_count || empty_id <= new_id,
+                (DrawingTarget::Layer(LayerId(empty_id)), _) => empty_id < self.layer_count,
+                (DrawingTarget::Sprite(_, _), _) => true,
+            };
+
+            if can_remove {
+                output.pop();
+                self.target = previous_target;
+                self.previous_target = None;
+
+                match empty_target {
+                    DrawingTarget::Layer(_) => stats.empty_layers += 1,
+                    DrawingTarget::Sprite(_, _) => stats.empty_sprites += 1,
+                }
+            }
+        }
+
+        if let DrawingTarget::Layer(LayerId(layer_id)) = self.target {
+            self.layer_count = self.layer_count.max(layer_id + 1);
+        }
+
+        if target == self.target {
+            // Selecting the target that's already selected has no effect
+            stats.redundant_state_changes += 1;
+        } else {
+            let previous_target = self.target;
+
+            self.known_state.clear();
+            self.target = target;
+            self.write(draw, output);
+            self.previous_target = Some(previous_target);
+        }
+    }
+
+    ///
+    /// Updates the bounds of the current path
+    ///
+    fn path_op(&mut self, path_op: &PathOp) {
+        use self::PathOp::*;
+
+        let mut add_point = |(x, y): (f32, f32)| {
+            self.path_bounds = match self.path_bounds {
+                None => Some(((x, y), (x, y))),
+                Some(((min_x, min_y), (max_x, max_y))) => {
+                    Some(((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y))))
+                }
+            };
+        };
+
+        match path_op {
+            NewPath => {
+                self.path_bounds = None;
+                self.path_cullable = true;
+            }
+
+            Move(x, y) | Line(x, y) => add_point((*x, *y)),
+
+            // Bezier curves are always inside the bounds of their control points
+            BezierCurve((cp1, cp2), end_point) => {
+                add_point(*cp1);
+                add_point(*cp2);
+                add_point(*end_point);
+            }
+
+            ClosePath => {}
+        }
+    }
+
+    ///
+    /// True if the current path would be filled entirely outside of the viewport
+    ///
+    fn fill_is_outside_viewport(&self) -> bool {
+        let ((width, height), transform, ((min_x, min_y), (max_x, max_y))) =
+            match (self.viewport, self.transform, self.path_bounds) {
+                (Some(viewport), Some(transform), Some(bounds)) => (viewport, transform, bounds),
+                _ => return false,
+            };
+
+        if !self.path_cullable {
+            return false;
+        }
+        if let DrawingTarget::Sprite(_, _) = self.target {
+            return false;
+        }
+
+        // The renderers map the height of the viewport to the range -1 to 1, and preserve the aspect ratio
+        let margin = 2.0 / height;
+        let view_x = width / height + margin;
+        let view_y = 1.0 + margin;
+
+        let corners = [
+            (min_x, min_y),
+            (min_x, max_y),
+            (max_x, min_y),
+            (max_x, max_y),
+        ]
+        .map(|(x, y)| transform.transform_point(x, y));
+
+        corners.iter().all(|(x, _)| *x < -view_x)
+            || corners.iter().all(|(x, _)| *x > view_x)
+            || corners.iter().all(|(_, y)| *y < -view_y)
+            || corners.iter().all(|(_, y)| *y > view_y)
+    }
+}
+
+///
+/// Optimizes a drawing, returning the optimized instructions and the statistics for what was removed
+///
+/// See `DrawingOptimizer` for the optimizations that are performed. No fills are culled, as the viewport is unknown: use
+/// `DrawingOptimizer::with_viewport()` to remove fills outside of the window.
+///
+pub fn optimize_drawing(drawing: impl IntoIterator<Item = Draw>) -> (Vec<Draw>, OptimizationStats) {
+    let mut optimizer = DrawingOptimizer::new();
+    let drawing = optimizer.optimize(drawing);
+
+    (drawing, optimizer.stats())
+}
+
+///
+/// Optimizes a stream of drawing instructions
+///
+/// Instructions are optimized in batches of those that are ready to be read from the input stream, so there's no delay
+/// in the output while the optimizer waits for further instructions. Use `DrawingOptimizer::shared_stats()` to read the
+/// statistics while the stream is running.
+///
+pub fn optimized_drawing_stream<InStream: 'static + Send + Unpin + Stream<Item = Draw>>(
+    draw_stream: InStream,
+    optimizer: DrawingOptimizer,
+) -> impl Send + Unpin + Stream<Item = Draw> {
+    generator_stream(move |yield_value| async move {
+        let mut draw_stream = draw_stream.ready_chunks(1000);
+        let mut optimizer = optimizer;
+
+        while let Some(drawing) = draw_stream.next().await {
+            for draw in optimizer.optimize(drawing) {
+                yield_value(draw).await;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::color::*;
+    use crate::context::*;
+    use crate::primitives::*;
+
+    use futures::executor;
+    use futures::stream;
+
+    #[test]
+    fn remove_repeated_fill_color() {
+        let mut drawing = vec![];
+        drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.new_path();
+        drawing.rect(0.0, 0.0, 10.0, 10.0);
+        drawing.fill();
+        drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.new_path();
+        drawing.rect(20.0, 0.0, 30.0, 10.0);
+        drawing.fill();
+
+        let (optimized, stats) = optimize_drawing(drawing.clone());
+
+        assert!(stats.redundant_state_changes == 1, "{:?}", stats);
+        assert!(
+            optimized
+                .iter()
+                .filter(|draw| matches!(draw, Draw::FillColor(_)))
+                .count()
+                == 1
+        );
+        assert!(optimized.len() == drawing.len() - 1);
+        assert!(stats.instructions_removed() == 1);
+    }
+
+    #[test]
+    fn keep_fill_color_after_different_fill() {
+        let drawing = vec![
+            Draw::FillColor(Color::Rgba(1.0, 0.0, 0.0, 1.0)),
+            Draw::LineWidth(2.0),
+            Draw::FillColor(Color::Rgba(0.0, 1.0, 0.0, 1.0)),
+            Draw::LineWidthPixels(2.0),
+            Draw::LineWidth(2.0),
+            Draw::FillColor(Color::Rgba(1.0, 0.0, 0.0, 1.0)),
+        ];
+
+        let (optimized, stats) = optimize_drawing(drawing.clone());
+
+        assert!(optimized == drawing, "{:?}", optimized);
+        assert!(stats.instructions_removed() == 0);
+    }
+
+    #[test]
+    fn state_is_unknown_after_selecting_layer() {
+        let drawing = vec![
+            Draw::FillColor(Color::Rgba(1.0, 0.0, 0.0, 1.0)),
+            Draw::Layer(LayerId(1)),
+            Draw::FillColor(Color::Rgba(1.0, 0.0, 0.0, 1.0)),
+        ];
+
+        let (optimized, _stats) = optimize_drawing(drawing.clone());
+
+        assert!(optimized == drawing, "{:?}", optimized);
+    }
+
+    #[test]
+    fn state_restored_by_pop_state() {
+        let drawing = vec![
+            Draw::LineWidth(4.0),
+            Draw::PushState,
+            Draw::LineWidth(8.0),
+            Draw::PopState,
+            Draw::LineWidth(4.0),
+            Draw::LineWidth(8.0),
+        ];
+
+        let (optimized, stats) = optimize_drawing(drawing);
+
+        assert!(
+            optimized
+                == vec![
+                    Draw::LineWidth(4.0),
+                    Draw::PushState,
+                    Draw::LineWidth(8.0),
+                    Draw::PopState,
+                    Draw::LineWidth(8.0),
+                ],
+            "{:?}",
+            optimized
+        );
+        assert!(stats.redundant_state_changes == 1);
+    }
+
+    #[test]
+    fn fold_multiply_transforms() {
+        let drawing = vec![
+            Draw::MultiplyTransform(Transform2D::translate(10.0, 20.0)),
+            Draw::MultiplyTransform(Transform2D::scale(2.0, 2.0)),
+            Draw::MultiplyTransform(Transform2D::identity()),
+            Draw::MultiplyTransform(Transform2D::rotate_degrees(30.0)),
+        ];
+
+        let (optimized, stats) = optimize_drawing(drawing);
+
+        assert!(optimized.len() == 1, "{:?}", optimized);
+        assert!(stats.folded_transforms == 3);
+
+        let expected = Transform2D::translate(10.0, 20.0)
+            * Transform2D::scale(2.0, 2.0)
+            * Transform2D::rotate_degrees(30.0);
+        let Draw::MultiplyTransform(folded) = optimized[0] else {
+            panic!("{:?}", optimized)
+        };
+        let (x, y) = folded.transform_point(3.0, 4.0);
+        let (expected_x, expected_y) = expected.transform_point(3.0, 4.0);
+
+        assert!((x - expected_x).abs() < 0.001 && (y - expected_y).abs() < 0.001);
+    }
+
+    #[test]
+    fn remove_transform_replaced_by_canvas_height() {
+        let drawing = vec![
+            Draw::MultiplyTransform(Transform2D::scale(2.0, 2.0)),
+            Draw::CenterRegion((0.0, 0.0), (100.0, 100.0)),
+            Draw::CanvasHeight(1000.0),
+        ];
+
+        let (optimized, stats) = optimize_drawing(drawing);
+
+        assert!(
+            optimized == vec![Draw::CanvasHeight(1000.0)],
+            "{:?}",
+            optimized
+        );
+        assert!(stats.folded_transforms == 2);
+    }
+
+    #[test]
+    fn cull_fill_outside_viewport() {
+        let mut drawing = vec![];
+        drawing.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+        drawing.canvas_height(1000.0);
+        drawing.center_region(0.0, 0.0, 1000.0, 1000.0);
+
+        // Visible
+        drawing.new_path();
+        drawing.circle(500.0, 500.0, 100.0);
+        drawing.fill();
+
+        // Off the right-hand side of the window
+        drawing.new_path();
+        drawing.circle(2500.0, 500.0, 100.0);
+        drawing.fill();
+
+        // Below the window
+        drawing.new_path();
+        drawing.rect(0.0, -300.0, 100.0, -200.0);
+        drawing.fill();
+
+        let mut optimizer = DrawingOptimizer::new().with_viewport(1920.0, 1080.0);
+        let optimized = optimizer.optimize(drawing.clone());
+        let stats = optimizer.stats();
+
+        assert!(stats.culled_fills == 2, "{:?}", stats);
+        assert!(optimized.iter().filter(|draw| draw == &&Draw::Fill).count() == 1);
+
+        // Fills are not culled without a viewport
+        let (not_culled, stats) = optimize_drawing(drawing.clone());
+        assert!(not_culled == drawing);
+        assert!(stats.culled_fills == 0);
+    }
+
+    #[test]
+    fn keep_fill_near_edge_of_wide_viewport() {
+        let mut drawing = vec![];
+        drawing.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+        drawing.canvas_height(1000.0);
+        drawing.center_region(0.0, 0.0, 1000.0, 1000.0);
+
+        // Outside of a square window, but inside a 16:9 window
+        drawing.new_path();
+        drawing.rect(1100.0, 400.0, 1200.0, 500.0);
+        drawing.fill();
+
+        let mut optimizer = DrawingOptimizer::new().with_viewport(1920.0, 1080.0);
+        optimizer.optimize(drawing.clone());
+        assert!(optimizer.stats().culled_fills == 0);
+
+        let mut optimizer = DrawingOptimizer::new().with_viewport(1000.0, 1000.0);
+        optimizer.optimize(drawing);
+        assert!(optimizer.stats().culled_fills == 1);
+    }
+
+    #[test]
+    fn do_not_cull_when_transform_changes_during_path() {
+        let mut drawing = vec![];
+        drawing.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+        drawing.canvas_height(1000.0);
+
+        drawing.new_path();
+        drawing.rect(2000.0, 0.0, 2100.0, 100.0);
+        drawing.transform(Transform2D::translate(-2000.0, 0.0));
+        drawing.fill();
+
+        let mut optimizer = DrawingOptimizer::new().with_viewport(1000.0, 1000.0);
+        let optimized = optimizer.optimize(drawing);
+
+        assert!(optimized.contains(&Draw::Fill));
+        assert!(optimizer.stats().culled_fills == 0);
+    }
+
+    #[test]
+    fn remove_empty_layers() {
+        let drawing = vec![
+            Draw::ClearCanvas(Color::Rgba(1.0, 1.0, 1.0, 1.0)),
+            Draw::Layer(LayerId(1)),
+            Draw::Layer(LayerId(2)),
+            Draw::Layer(LayerId(3)),
+            Draw::Fill,
+            Draw::Layer(LayerId(3)),
+            Draw::Fill,
+        ];
+
+        let (optimized, stats) = optimize_drawing(drawing);
+
+        assert!(
+            optimized
+                == vec![
+                    Draw::ClearCanvas(Color::Rgba(1.0, 1.0, 1.0, 1.0)),
+                    Draw::Layer(LayerId(3)),
+                    Draw::Fill,
+                    Draw::Fill,
+                ],
+            "{:?}",
+            optimized
+        );
+        assert!(stats.empty_layers == 2);
+        assert!(stats.redundant_state_changes == 1);
+    }
+
+    #[test]
+    fn keep_layer_that_would_not_be_created() {
+        // Selecting layer 3 creates layers 0-3, so the empty selection is needed for the LayerBlend to apply
+        let drawing = vec![
+            Draw::ClearCanvas(Color::Rgba(1.0, 1.0, 1.0, 1.0)),
+            Draw::Layer(LayerId(3)),
+            Draw::Layer(LayerId(0)),
+            Draw::LayerBlend(LayerId(3), BlendMode::Multiply),
+        ];
+
+        let (optimized, stats) = optimize_drawing(drawing.clone());
+
+        assert!(optimized == drawing, "{:?}", optimized);
+        assert!(stats.empty_layers == 0);
+    }
+
+    #[test]
+    fn remove_empty_sprites() {
+        let drawing = vec![
+            Draw::Sprite(SpriteId(1)),
+            Draw::Sprite(SpriteId(2)),
+            Draw::ClearSprite,
+            Draw::Layer(LayerId(0)),
+        ];
+
+        let (optimized, stats) = optimize_drawing(drawing);
+
+        assert!(
+            optimized
+                == vec![
+                    Draw::Sprite(SpriteId(2)),
+                    Draw::ClearSprite,
+                    Draw::Layer(LayerId(0)),
+                ],
+            "{:?}",
+            optimized
+        );
+        assert!(stats.empty_sprites == 1);
+    }
+
+    #[test]
+    fn optimize_stream() {
+        let mut drawing = vec![];
+        drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.transform(Transform2D::translate(1.0, 2.0));
+        drawing.transform(Transform2D::translate(3.0, 4.0));
+        drawing.new_path();
+        drawing.circle(0.0, 0.0, 10.0);
+        drawing.fill();
+
+        let optimizer = DrawingOptimizer::new();
+        let stats = optimizer.shared_stats();
+        let optimized = executor::block_on(
+            optimized_drawing_stream(stream::iter(drawing.clone()), optimizer).collect::<Vec<_>>(),
+        );
+
+        assert!(optimized == optimize_drawing(drawing.clone()).0);
+
+        let stats = *stats.lock().unwrap();
+        assert!(stats.redundant_state_changes == 1, "{:?}", stats);
+        assert!(stats.folded_transforms == 1, "{:?}", stats);
+        assert!(stats.instructions_read == drawing.len());
+        assert!(stats.instructions_written == optimized.len());
+    }
+
+    #[test]
+    fn state_carries_between_sections() {
+        let mut optimizer = DrawingOptimizer::new();
+
+        let first = optimizer.optimize(vec![Draw::LineWidth(3.0), Draw::Stroke]);
+        let second = optimizer.optimize(vec![Draw::LineWidth(3.0), Draw::Stroke]);
+
+        assert!(first == vec![Draw::LineWidth(3.0), Draw::Stroke]);
+        assert!(second == vec![Draw::Stroke]);
+        assert!(optimizer.stats().redundant_state_changes == 1);
+    }
+}
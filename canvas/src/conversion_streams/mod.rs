@@ -21,3 +21,7 @@ pub use self::outline_fonts::*;
 mod dashed_lines;
 
 pub use self::dashed_lines::*;
+
+mod drawing_optimizer;
+
+pub use self::drawing_optimizer::*;
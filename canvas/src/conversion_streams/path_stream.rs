@@ -38,6 +38,17 @@ pub enum PathAttribute {
 
     /// Path is filled with the specified gradient
     FillGradient(GradientId, (f32, f32), (f32, f32), Option<Transform2D>),
+
+    /// Path is filled with the specified radial gradient
+    FillRadialGradient(
+        GradientId,
+        (f32, f32, f32),
+        (f32, f32, f32),
+        Option<Transform2D>,
+    ),
+
+    /// Path is filled with the specified conic gradient
+    FillConicGradient(GradientId, (f32, f32), f32, Option<Transform2D>),
 }
 
 ///
@@ -311,6 +322,16 @@ where
                     fill_color = PathAttribute::FillGradient(gradient, (x1, y1), (x2, y2), None);
                 }
 
+                Draw::FillRadialGradient(gradient, circle1, circle2) => {
+                    fill_color =
+                        PathAttribute::FillRadialGradient(gradient, circle1, circle2, None);
+                }
+
+                Draw::FillConicGradient(gradient, center, start_angle) => {
+                    fill_color =
+                        PathAttribute::FillConicGradient(gradient, center, start_angle, None);
+                }
+
                 Draw::FillTexture(texture, (x1, y1), (x2, y2)) => {
                     fill_color = PathAttribute::FillTexture(texture, (x1, y1), (x2, y2), None);
                 }
@@ -345,6 +366,36 @@ where
                             coord2,
                             Some(existing_transform * transform),
                         ),
+                        PathAttribute::FillRadialGradient(
+                            gradient,
+                            circle1,
+                            circle2,
+                            existing_transform,
+                        ) => PathAttribute::FillRadialGradient(
+                            gradient,
+                            circle1,
+                            circle2,
+                            Some(
+                                existing_transform
+                                    .map(|t| t * transform)
+                                    .unwrap_or(transform),
+                            ),
+                        ),
+                        PathAttribute::FillConicGradient(
+                            gradient,
+                            center,
+                            start_angle,
+                            existing_transform,
+                        ) => PathAttribute::FillConicGradient(
+                            gradient,
+                            center,
+                            start_angle,
+                            Some(
+                                existing_transform
+                                    .map(|t| t * transform)
+                                    .unwrap_or(transform),
+                            ),
+                        ),
 
                         other_fill_color => other_fill_color,
                     };
@@ -15,6 +15,7 @@ use futures::*;
 use itertools::*;
 use uuid::*;
 
+use std::convert::TryFrom;
 use std::mem;
 use std::result::Result;
 use std::str::*;
@@ -407,6 +408,10 @@ enum DecoderState {
     // 'Ct' (texture_id, x1, y1, x2, y2)
     ColorGradient(DecodeGradientId, String),
     // 'Cg' (gradient_id, x1, y1, x2, y2)
+    ColorRadialGradient(DecodeGradientId, String),
+    // 'Cr' (gradient_id, x1, y1, r1, x2, y2, r2)
+    ColorConicGradient(DecodeGradientId, String),
+    // 'Cc' (gradient_id, x, y, start_angle)
     ColorTransform(String), // 'CT' (transform)
 
     BlendMode(String), // 'M' (mode)
@@ -490,8 +495,18 @@ enum DecoderState {
     GradientOpNew(GradientId, String),
     // 'G<id>N' (r, g, b, a)
     GradientOpAddStop(GradientId, String), // 'G<id>S' (pos, r, g, b, a)
+    GradientOpSpreadMode(GradientId),      // 'G<id>M' (spread)
+
+    Extended(String),
+    // '#' (length)
+    ExtendedPayload(u64, String), // '#<len>' (payload)
 }
 
+///
+/// The maximum number of bytes of an instruction that are kept for `CanvasDecoder::partial_instruction()`
+///
+pub const MAX_PARTIAL_INSTRUCTION_LEN: usize = 32;
+
 ///
 /// Possible error from the decoder
 ///
@@ -517,13 +532,38 @@ pub enum DecoderError {
 
     /// The decoder was expecting a state as a partial match but it was completed
     UnexpectedlyComplete,
+
+    /// The byte was not valid for the current state of the binary decoder
+    InvalidByte(u8),
+
+    /// A binary instruction did not have the length that was specified before it
+    BadInstructionLength,
+
+    /// A string was not valid UTF-8
+    BadString,
+
+    /// The data ended part way through an instruction
+    TruncatedInstruction,
 }
 
 ///
 /// Represents a (stateful) canvas decoder
 ///
 pub struct CanvasDecoder {
+    /// The state of the instruction that's being decoded
     state: DecoderState,
+
+    /// The version from the header of the stream, if one has been read
+    version: Option<u32>,
+
+    /// The number of bytes that have been passed to the decoder so far
+    offset: usize,
+
+    /// The byte offset of the start of the current instruction
+    instruction_offset: usize,
+
+    /// The first characters that have been read for the current instruction (up to `MAX_PARTIAL_INSTRUCTION_LEN` bytes)
+    partial_instruction: String,
 }
 
 impl CanvasDecoder {
@@ -533,13 +573,66 @@ impl CanvasDecoder {
     pub fn new() -> CanvasDecoder {
         CanvasDecoder {
             state: DecoderState::None,
+            version: None,
+            offset: 0,
+            instruction_offset: 0,
+            partial_instruction: String::new(),
         }
     }
 
+    ///
+    /// The format version from the header of the stream being decoded, or None if no header has been read
+    ///
+    pub fn version(&self) -> Option<u32> {
+        self.version
+    }
+
+    ///
+    /// The byte offset in the source of the start of the instruction that is being decoded
+    ///
+    /// After an error, this is the offset of the instruction that could not be decoded
+    ///
+    pub fn instruction_offset(&self) -> usize {
+        self.instruction_offset
+    }
+
+    ///
+    /// The characters that have been read so far for the instruction that is being decoded
+    ///
+    /// After an error, these are the characters of the instruction that could not be decoded, up to and including the
+    /// one that caused the error. Only the first `MAX_PARTIAL_INSTRUCTION_LEN` bytes are kept, as instructions such as
+    /// font data can be very long.
+    ///
+    pub fn partial_instruction(&self) -> &str {
+        &self.partial_instruction
+    }
+
     ///
     /// Decodes a character, returning the next Draw operation if there is one
     ///
     pub fn decode(&mut self, next_chr: char) -> Result<Option<Draw>, DecoderError> {
+        // Track where the current instruction started, for error reporting (leaving the instruction that caused an error alone)
+        match self.state {
+            DecoderState::Error => return Err(DecoderError::IsInErrorState),
+            DecoderState::None => {
+                self.instruction_offset = self.offset;
+                self.partial_instruction.clear();
+            }
+            _ => {}
+        }
+
+        self.offset += next_chr.len_utf8();
+        if self.partial_instruction.len() + next_chr.len_utf8() <= MAX_PARTIAL_INSTRUCTION_LEN {
+            self.partial_instruction.push(next_chr);
+        }
+
+        self.decode_char(next_chr)
+    }
+
+    ///
+    /// Decodes a character, updating the state of the decoder
+    ///
+    fn decode_char(&mut self, next_chr: char) -> Result<Option<Draw>, DecoderError> {
         use self::DecoderState::*;
 
         // Next state depends on the character and the current state
@@ -577,6 +670,12 @@ impl CanvasDecoder {
             ColorFill(param) => Self::decode_color_fill(next_chr, param)?,
             ColorTexture(id, param) => Self::decode_color_texture(next_chr, id, param)?,
             ColorGradient(id, param) => Self::decode_color_gradient(next_chr, id, param)?,
+            ColorRadialGradient(id, param) => {
+                Self::decode_color_radial_gradient(next_chr, id, param)?
+            }
+            ColorConicGradient(id, param) => {
+                Self::decode_color_conic_gradient(next_chr, id, param)?
+            }
             ColorTransform(param) => Self::decode_color_transform(next_chr, param)?,
 
             BlendMode(param) => Self::decode_blend_mode(next_chr, param)?,
@@ -658,6 +757,14 @@ impl CanvasDecoder {
             GradientOpAddStop(gradient_id, param) => {
                 Self::decode_gradient_add_stop(next_chr, gradient_id, param)?
             }
+            GradientOpSpreadMode(gradient_id) => {
+                Self::decode_gradient_spread_mode(next_chr, gradient_id)?
+            }
+
+            Extended(param) => Self::decode_extended(next_chr, param)?,
+            ExtendedPayload(length, payload) => {
+                self.decode_extended_payload(next_chr, length, payload)?
+            }
         };
 
         self.state = next_state;
@@ -706,6 +813,9 @@ impl CanvasDecoder {
 
             'G' => Ok((DecoderState::GradientOp(PartialResult::new()), None)),
 
+            // Length-delimited instructions
+            '#' => Ok((DecoderState::Extended(String::new()), None)),
+
             // Other characters are not accepted
             _ => Err(DecoderError::InvalidCharacter(next_chr)),
         }
@@ -783,6 +893,14 @@ impl CanvasDecoder {
                 DecoderState::ColorGradient(DecodeGradientId::new(), String::new()),
                 None,
             )),
+            'r' => Ok((
+                DecoderState::ColorRadialGradient(DecodeGradientId::new(), String::new()),
+                None,
+            )),
+            'c' => Ok((
+                DecoderState::ColorConicGradient(DecodeGradientId::new(), String::new()),
+                None,
+            )),
             'T' => Ok((DecoderState::ColorTransform(String::new()), None)),
 
             _ => Err(DecoderError::InvalidCharacter(next_chr)),
@@ -1168,6 +1286,95 @@ impl CanvasDecoder {
         }
     }
 
+    #[inline]
+    fn decode_color_radial_gradient(
+        next_chr: char,
+        gradient_id: DecodeGradientId,
+        mut param: String,
+    ) -> Result<(DecoderState, Option<Draw>), DecoderError> {
+        use self::PartialResult::*;
+
+        // Decode the gradient ID first
+        let gradient_id = match gradient_id {
+            MatchMore(gradient_id) => {
+                let gradient_id = Self::decode_gradient_id(next_chr, gradient_id)?;
+                return Ok((DecoderState::ColorRadialGradient(gradient_id, param), None));
+            }
+
+            FullMatch(gradient_id) => gradient_id,
+        };
+
+        // There are 2 circles following the gradient ID (at 3 coordinates of 6 bytes each)
+        param.push(next_chr);
+
+        if param.len() < 36 {
+            // More characters required
+            Ok((
+                DecoderState::ColorRadialGradient(FullMatch(gradient_id), param),
+                None,
+            ))
+        } else {
+            // Decode the circles
+            let mut param = param.chars();
+            let x1 = Self::decode_f32(&mut param)?;
+            let y1 = Self::decode_f32(&mut param)?;
+            let r1 = Self::decode_f32(&mut param)?;
+            let x2 = Self::decode_f32(&mut param)?;
+            let y2 = Self::decode_f32(&mut param)?;
+            let r2 = Self::decode_f32(&mut param)?;
+
+            Ok((
+                DecoderState::None,
+                Some(Draw::FillRadialGradient(
+                    gradient_id,
+                    (x1, y1, r1),
+                    (x2, y2, r2),
+                )),
+            ))
+        }
+    }
+
+    #[inline]
+    fn decode_color_conic_gradient(
+        next_chr: char,
+        gradient_id: DecodeGradientId,
+        mut param: String,
+    ) -> Result<(DecoderState, Option<Draw>), DecoderError> {
+        use self::PartialResult::*;
+
+        // Decode the gradient ID first
+        let gradient_id = match gradient_id {
+            MatchMore(gradient_id) => {
+                let gradient_id = Self::decode_gradient_id(next_chr, gradient_id)?;
+                return Ok((DecoderState::ColorConicGradient(gradient_id, param), None));
+            }
+
+            FullMatch(gradient_id) => gradient_id,
+        };
+
+        // The center point and start angle follow the gradient ID (at 6 bytes each)
+        param.push(next_chr);
+
+        if param.len() < 18 {
+            // More characters required
+            Ok((
+                DecoderState::ColorConicGradient(FullMatch(gradient_id), param),
+                None,
+            ))
+        } else {
+            // Decode the center and angle
+            let mut param = param.chars();
+            let x = Self::decode_f32(&mut param)?;
+            let y = Self::decode_f32(&mut param)?;
+            let start_angle = Self::decode_f32(&mut param)?;
+
+            Ok((
+                DecoderState::None,
+                Some(Draw::FillConicGradient(gradient_id, (x, y), start_angle)),
+            ))
+        }
+    }
+
     #[inline]
     fn decode_color_transform(
         next_chr: char,
@@ -1671,10 +1878,29 @@ impl CanvasDecoder {
             ('D', 'O') => Ok(BlendMode::DestinationOut),
             ('D', 'A') => Ok(BlendMode::DestinationAtop),
 
+            ('X', 'O') => Ok(BlendMode::Xor),
+            ('C', 'L') => Ok(BlendMode::Clear),
+            ('S', 'S') => Ok(BlendMode::Copy),
+            ('D', 'D') => Ok(BlendMode::Destination),
+
             ('E', 'M') => Ok(BlendMode::Multiply),
             ('E', 'S') => Ok(BlendMode::Screen),
             ('E', 'D') => Ok(BlendMode::Darken),
             ('E', 'L') => Ok(BlendMode::Lighten),
+            ('E', 'O') => Ok(BlendMode::Overlay),
+            ('E', 'C') => Ok(BlendMode::ColorDodge),
+            ('E', 'B') => Ok(BlendMode::ColorBurn),
+            ('E', 'H') => Ok(BlendMode::HardLight),
+            ('E', 'T') => Ok(BlendMode::SoftLight),
+            ('E', 'I') => Ok(BlendMode::Difference),
+            ('E', 'X') => Ok(BlendMode::Exclusion),
+
+            ('N', 'H') => Ok(BlendMode::Hue),
+            ('N', 'S') => Ok(BlendMode::Saturation),
+            ('N', 'C') => Ok(BlendMode::Color),
+            ('N', 'L') => Ok(BlendMode::Luminosity),
+
+            ('P', 'L') => Ok(BlendMode::Plus),
 
             _ => Err(DecoderError::InvalidCharacter(a)),
         }
@@ -2524,6 +2750,7 @@ impl CanvasDecoder {
                 DecoderState::GradientOpAddStop(gradient_id, String::new()),
                 None,
             )),
+            'M' => Ok((DecoderState::GradientOpSpreadMode(gradient_id), None)),
 
             _ => Err(DecoderError::InvalidCharacter(chr)),
         }
@@ -2567,6 +2794,100 @@ impl CanvasDecoder {
         }
     }
 
+    ///
+    /// Decodes the length of a length-delimited instruction
+    ///
+    fn decode_extended(
+        next_chr: char,
+        param: String,
+    ) -> Result<(DecoderState, Option<Draw>), DecoderError> {
+        match Self::decode_compact_id(next_chr, param)? {
+            PartialResult::MatchMore(param) => Ok((DecoderState::Extended(param), None)),
+            PartialResult::FullMatch(0) => Ok((DecoderState::None, None)),
+            PartialResult::FullMatch(length) => {
+                Ok((DecoderState::ExtendedPayload(length, String::new()), None))
+            }
+        }
+    }
+
+    ///
+    /// Decodes the payload of a length-delimited instruction
+    ///
+    /// The payload is either the version header ('V' followed by the version number) or a single instruction. Any
+    /// instruction this decoder doesn't understand (usually because it was written by a newer version of this library)
+    /// is skipped.
+    ///
+    fn decode_extended_payload(
+        &mut self,
+        next_chr: char,
+        length: u64,
+        mut payload: String,
+    ) -> Result<(DecoderState, Option<Draw>), DecoderError> {
+        payload.push(next_chr);
+
+        if (payload.len() as u64) < length {
+            return Ok((DecoderState::ExtendedPayload(length, payload), None));
+        } else if (payload.len() as u64) > length {
+            return Err(DecoderError::BadInstructionLength);
+        }
+
+        let mut chars = payload.chars();
+
+        if chars.next() == Some('V') {
+            // Version header (later versions may add more data after the version number)
+            let version =
+                Self::try_decode_compact_u64(&mut chars)?.ok_or(DecoderError::BadNumber)?;
+            let version = u32::try_from(version).map_err(|_| DecoderError::BadNumber)?;
+
+            self.version = Some(version);
+            Ok((DecoderState::None, None))
+        } else {
+            Ok((DecoderState::None, Self::decode_length_delimited(&payload)))
+        }
+    }
+
+    ///
+    /// Decodes a single instruction from the payload of a length-delimited instruction, returning None if it can't be decoded
+    ///
+    fn decode_length_delimited(payload: &str) -> Option<Draw> {
+        let mut decoder = CanvasDecoder::new();
+        let mut result = None;
+
+        for chr in payload.chars() {
+            if result.is_some() {
+                // Payloads should contain only a single instruction
+                return None;
+            }
+
+            match decoder.decode_char(chr) {
+                Ok(draw) => result = draw,
+                Err(_) => return None,
+            }
+        }
+
+        result
+    }
+
+    ///
+    /// Decodes the GradientOp::SpreadMode instruction
+    ///
+    fn decode_gradient_spread_mode(
+        next_chr: char,
+        gradient_id: GradientId,
+    ) -> Result<(DecoderState, Option<Draw>), DecoderError> {
+        let spread = match next_chr {
+            'P' => GradientSpread::Pad,
+            'R' => GradientSpread::Repeat,
+            'F' => GradientSpread::Reflect,
+            _ => return Err(DecoderError::InvalidCharacter(next_chr)),
+        };
+
+        Ok((
+            DecoderState::None,
+            Some(Draw::Gradient(gradient_id, GradientOp::SpreadMode(spread))),
+        ))
+    }
+
     ///
     /// Decodes the GradientOp::AddStop instruction
     ///
@@ -2970,6 +3291,45 @@ mod test {
         check_round_trip_single(Draw::BlendMode(BlendMode::Lighten));
     }
 
+    #[test]
+    fn decode_all_blend_modes() {
+        use self::BlendMode::*;
+
+        for blend_mode in [
+            SourceOver,
+            SourceIn,
+            SourceOut,
+            DestinationOver,
+            DestinationIn,
+            DestinationOut,
+            SourceAtop,
+            DestinationAtop,
+            Xor,
+            Clear,
+            Copy,
+            Destination,
+            Multiply,
+            Screen,
+            Darken,
+            Lighten,
+            Overlay,
+            ColorDodge,
+            ColorBurn,
+            HardLight,
+            SoftLight,
+            Difference,
+            Exclusion,
+            Hue,
+            Saturation,
+            Color,
+            Luminosity,
+            Plus,
+        ] {
+            check_round_trip_single(Draw::BlendMode(blend_mode));
+            check_round_trip_single(Draw::LayerBlend(LayerId(3), blend_mode));
+        }
+    }
+
     #[test]
     fn decode_identity_transform() {
         check_round_trip_single(Draw::IdentityTransform);
@@ -3146,6 +3506,207 @@ mod test {
         assert!(decoder.decode('x') == Err(DecoderError::InvalidCharacter('x')));
     }
 
+    ///
+    /// Decodes a string until the decoder returns an error, returning the decoder, the instructions decoded before the error and the error
+    ///
+    fn decode_until_error(encoded: &str) -> (CanvasDecoder, Vec<Draw>, DecoderError) {
+        let mut decoder = CanvasDecoder::new();
+        let mut decoded = vec![];
+
+        for chr in encoded.chars() {
+            match decoder.decode(chr) {
+                Ok(Some(draw)) => decoded.push(draw),
+                Ok(None) => {}
+                Err(err) => return (decoder, decoded, err),
+            }
+        }
+
+        panic!("Decoded without an error: {:?}", decoded);
+    }
+
+    #[test]
+    fn error_has_offset_and_partial_instruction() {
+        let (mut decoder, decoded, err) = decode_until_error("F\nNp\nLw******");
+
+        assert!(decoded == vec![Draw::Fill, Draw::Path(PathOp::NewPath)]);
+        assert!(err == DecoderError::BadNumber);
+        assert!(decoder.instruction_offset() == 5);
+        assert!(decoder.partial_instruction() == "Lw******");
+
+        // The location of the error is kept after further characters are sent to the decoder
+        assert!(decoder.decode('F') == Err(DecoderError::IsInErrorState));
+        assert!(decoder.instruction_offset() == 5);
+        assert!(decoder.partial_instruction() == "Lw******");
+    }
+
+    #[test]
+    fn error_offset_counts_bytes() {
+        let mut encoded = String::new();
+        Draw::DrawText(FontId(1), "\u{1f600}".to_string(), 0.0, 0.0).encode_canvas(&mut encoded);
+        encoded.push('?');
+
+        let (decoder, _, _) = decode_until_error(&encoded);
+
+        assert!(decoder.instruction_offset() == encoded.len() - 1);
+        assert!(decoder.partial_instruction() == "?");
+    }
+
+    #[test]
+    fn partial_instruction_is_limited_to_prefix() {
+        let mut encoded = String::new();
+        Draw::DrawText(FontId(1), "a".repeat(100), 0.0, 0.0).encode_canvas(&mut encoded);
+
+        // Corrupt the coordinates at the end of the instruction
+        encoded.truncate(encoded.len() - 12);
+        encoded.push_str("************");
+
+        let (decoder, decoded, err) = decode_until_error(&encoded);
+
+        assert!(decoded.is_empty());
+        assert!(err == DecoderError::BadNumber);
+        assert!(decoder.instruction_offset() == 0);
+        assert!(decoder.partial_instruction() == &encoded[0..MAX_PARTIAL_INSTRUCTION_LEN]);
+    }
+
+    #[test]
+    fn decoder_stays_in_error_state() {
+        let mut decoder = CanvasDecoder::new();
+        assert!(decoder.decode('?').is_err());
+        assert!(decoder.decode('F') == Err(DecoderError::IsInErrorState));
+    }
+
+    #[test]
+    fn decode_version_header() {
+        let mut encoded = String::new();
+        encode_canvas_header(&mut encoded);
+        Draw::Fill.encode_canvas(&mut encoded);
+
+        let mut decoder = CanvasDecoder::new();
+        let decoded = encoded
+            .chars()
+            .map(|chr| decoder.decode(chr))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert!(decoded.into_iter().flatten().collect::<Vec<_>>() == vec![Draw::Fill]);
+        assert!(decoder.version() == Some(CANVAS_ENCODING_VERSION));
+    }
+
+    #[test]
+    fn no_version_without_header() {
+        let mut decoder = CanvasDecoder::new();
+        assert!(decoder.decode('F') == Ok(Some(Draw::Fill)));
+        assert!(decoder.version().is_none());
+    }
+
+    #[test]
+    fn newer_version_header_with_extra_data() {
+        // A later version might add more information to the header: this should be ignored
+        let decoded = decode_drawing("#FVCxyzF".chars()).collect::<Vec<_>>();
+        assert!(decoded == vec![Ok(Draw::Fill)]);
+
+        let mut decoder = CanvasDecoder::new();
+        "#FVCxyz".chars().for_each(|chr| {
+            decoder.decode(chr).unwrap();
+        });
+        assert!(decoder.version() == Some(2));
+    }
+
+    #[test]
+    fn decode_length_delimited_instruction() {
+        let mut encoded = String::new();
+        encode_length_delimited(Draw::LineWidth(2.0), &mut encoded);
+        encode_length_delimited(Draw::Stroke, &mut encoded);
+
+        let decoded = decode_drawing(encoded.chars()).collect::<Vec<_>>();
+        assert!(decoded == vec![Ok(Draw::LineWidth(2.0)), Ok(Draw::Stroke)]);
+    }
+
+    #[test]
+    fn skip_unknown_length_delimited_instruction() {
+        // '?' is not a known instruction, 'Lq' is not a known line style, and 'FF' is more than one instruction
+        let decoded = decode_drawing("#F?abcdNp\n#DLqAS#CFFF".chars()).collect::<Vec<_>>();
+
+        assert!(
+            decoded
+                == vec![
+                    Ok(Draw::Path(PathOp::NewPath)),
+                    Ok(Draw::Stroke),
+                    Ok(Draw::Fill)
+                ]
+        );
+    }
+
+    #[test]
+    fn skip_unknown_length_delimited_instruction_in_stream() {
+        let all_stream = stream::iter("#F?abcdNp".chars().map(|c| -> Result<_, ()> { Ok(c) }));
+
+        executor::block_on(async {
+            let decoded = decode_drawing_stream(all_stream).collect::<Vec<_>>().await;
+            assert!(decoded == vec![Ok(Draw::Path(PathOp::NewPath))]);
+        });
+    }
+
+    #[test]
+    fn skip_unknown_length_delimited_instruction_in_drawing() {
+        // An instruction from a later version of the encoding that this decoder doesn't know about
+        struct FutureInstruction;
+
+        impl CanvasEncoding<String> for FutureInstruction {
+            fn encode_canvas(&self, append_to: &mut String) {
+                ('N', '~', 42.0f32, 'Q').encode_canvas(append_to)
+            }
+        }
+
+        let drawing = vec![
+            Draw::ClearCanvas(Color::Rgba(1.0, 1.0, 1.0, 1.0)),
+            Draw::Path(PathOp::NewPath),
+            Draw::Path(PathOp::Move(10.0, 10.0)),
+            Draw::Path(PathOp::Line(100.0, 10.0)),
+            Draw::Path(PathOp::Line(100.0, 100.0)),
+            Draw::Path(PathOp::ClosePath),
+            Draw::BlendMode(BlendMode::Overlay),
+            Draw::Gradient(
+                GradientId(1),
+                GradientOp::Create(Color::Rgba(1.0, 0.0, 0.0, 1.0)),
+            ),
+            Draw::Gradient(
+                GradientId(1),
+                GradientOp::SpreadMode(GradientSpread::Reflect),
+            ),
+            Draw::FillRadialGradient(GradientId(1), (50.0, 50.0, 0.0), (50.0, 50.0, 40.0)),
+            Draw::Fill,
+        ];
+
+        let mut encoded = String::new();
+        encode_canvas_header(&mut encoded);
+        drawing[0..6].to_vec().encode_canvas(&mut encoded);
+        encode_length_delimited(FutureInstruction, &mut encoded);
+        encoded.push('\n');
+        drawing[6..].to_vec().encode_canvas(&mut encoded);
+
+        let decoded = decode_drawing(encoded.chars())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert!(decoded == drawing);
+    }
+
+    #[test]
+    fn empty_length_delimited_instruction() {
+        let decoded = decode_drawing("#AF".chars()).collect::<Vec<_>>();
+        assert!(decoded == vec![Ok(Draw::Fill)]);
+    }
+
+    #[test]
+    fn length_delimited_instruction_with_bad_length() {
+        // Length is 1 byte, but the payload is a 2-byte character
+        let decoded = decode_drawing("#B\u{e9}F".chars()).collect::<Vec<_>>();
+
+        assert!(decoded.len() == 1);
+        assert!(decoded[0] == Err(DecoderError::BadInstructionLength));
+    }
+
     #[test]
     fn decode_font_data() {
         let font = CanvasFontFace::from_slice(include_bytes!("../test_data/Lato-Regular.ttf"));
@@ -3282,6 +3843,22 @@ mod test {
         ));
     }
 
+    #[test]
+    fn decode_gradient_spread_mode() {
+        check_round_trip_single(Draw::Gradient(
+            GradientId(46),
+            GradientOp::SpreadMode(GradientSpread::Pad),
+        ));
+        check_round_trip_single(Draw::Gradient(
+            GradientId(46),
+            GradientOp::SpreadMode(GradientSpread::Repeat),
+        ));
+        check_round_trip_single(Draw::Gradient(
+            GradientId(46),
+            GradientOp::SpreadMode(GradientSpread::Reflect),
+        ));
+    }
+
     #[test]
     fn decode_gradient_fill() {
         check_round_trip_single(Draw::FillGradient(
@@ -3291,6 +3868,20 @@ mod test {
         ));
     }
 
+    #[test]
+    fn decode_radial_gradient_fill() {
+        check_round_trip_single(Draw::FillRadialGradient(
+            GradientId(24),
+            (42.0, 43.0, 5.0),
+            (44.0, 45.0, 50.0),
+        ));
+    }
+
+    #[test]
+    fn decode_conic_gradient_fill() {
+        check_round_trip_single(Draw::FillConicGradient(GradientId(24), (42.0, 43.0), 1.5));
+    }
+
     #[test]
     fn decode_fill_transform() {
         check_round_trip_single(Draw::FillTransform(Transform2D::identity()));
@@ -3365,6 +3956,8 @@ mod test {
             Draw::FillColor(Color::Rgba(0.2, 0.3, 0.4, 0.5)),
             Draw::FillTexture(TextureId(23), (42.0, 43.0), (44.0, 45.0)),
             Draw::FillGradient(GradientId(24), (42.0, 43.0), (44.0, 45.0)),
+            Draw::FillRadialGradient(GradientId(25), (42.0, 43.0, 1.0), (44.0, 45.0, 10.0)),
+            Draw::FillConicGradient(GradientId(26), (42.0, 43.0), 0.5),
             Draw::FillTransform(Transform2D::identity()),
             Draw::BlendMode(BlendMode::Lighten),
             Draw::IdentityTransform,
@@ -3457,6 +4050,10 @@ mod test {
                 GradientId(44),
                 GradientOp::AddStop(0.5, Color::Rgba(0.1, 0.2, 0.3, 0.4)),
             ),
+            Draw::Gradient(
+                GradientId(44),
+                GradientOp::SpreadMode(GradientSpread::Reflect),
+            ),
         ]);
     }
 
@@ -3483,6 +4080,8 @@ mod test {
             Draw::FillColor(Color::Rgba(0.2, 0.3, 0.4, 0.5)),
             Draw::FillTexture(TextureId(23), (42.0, 43.0), (44.0, 45.0)),
             Draw::FillGradient(GradientId(24), (42.0, 43.0), (44.0, 45.0)),
+            Draw::FillRadialGradient(GradientId(25), (42.0, 43.0, 1.0), (44.0, 45.0, 10.0)),
+            Draw::FillConicGradient(GradientId(26), (42.0, 43.0), 0.5),
             Draw::FillTransform(Transform2D::identity()),
             Draw::BlendMode(BlendMode::Lighten),
             Draw::IdentityTransform,
@@ -3577,6 +4176,10 @@ mod test {
                 GradientId(44),
                 GradientOp::AddStop(0.5, Color::Rgba(0.1, 0.2, 0.3, 0.4)),
             ),
+            Draw::Gradient(
+                GradientId(44),
+                GradientOp::SpreadMode(GradientSpread::Reflect),
+            ),
         ];
         let mut encoded = String::new();
         all.encode_canvas(&mut encoded);
@@ -51,10 +51,37 @@ pub enum BlendMode {
     SourceAtop,
     DestinationAtop,
 
+    /// Keeps the parts of the source and the destination that do not overlap
+    Xor,
+
+    /// Clears the destination wherever the source is drawn
+    Clear,
+
+    /// Replaces the destination with the source
+    Copy,
+
+    /// Leaves the destination unchanged
+    Destination,
+
     Multiply,
     Screen,
     Darken,
     Lighten,
+    Overlay,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+
+    /// Adds the source and destination colours together (sometimes called 'add' or 'lighter')
+    Plus,
 }
 
 ///
@@ -174,6 +201,12 @@ pub enum Draw {
     /// Sets the fill to be a gradient (coordinates are the start and end of the gradient)
     FillGradient(GradientId, (f32, f32), (f32, f32)),
 
+    /// Sets the fill to be a radial gradient, which runs from the first circle to the second (each circle is specified as `(x, y, radius)`)
+    FillRadialGradient(GradientId, (f32, f32, f32), (f32, f32, f32)),
+
+    /// Sets the fill to be a conic gradient, which sweeps around the center point starting at the specified angle (in radians)
+    FillConicGradient(GradientId, (f32, f32), f32),
+
     /// For a gradient or texture fill, apply a transformation matrix
     FillTransform(Transform2D),
 
@@ -113,7 +113,11 @@ impl Draw {
                 _ => false,
             },
             FillTexture(texture_id, _, _) => resource == &DrawResource::Texture(*texture_id),
-            FillGradient(gradient_id, _, _) => resource == &DrawResource::Gradient(*gradient_id),
+            FillGradient(gradient_id, _, _)
+            | FillRadialGradient(gradient_id, _, _)
+            | FillConicGradient(gradient_id, _, _) => {
+                resource == &DrawResource::Gradient(*gradient_id)
+            }
 
             // Transforms use the 'canvas' resource (setting the height or the identity transform resets any previous transform)
             CenterRegion(_, _) | MultiplyTransform(_) => resource == &DrawResource::CanvasTransform,
@@ -216,7 +220,11 @@ impl Draw {
                 DrawResource::FontSize(*font_id)
             ],
             FillTexture(texture_id, _, _) => smallvec![DrawResource::Texture(*texture_id)],
-            FillGradient(gradient_id, _, _) => smallvec![DrawResource::Gradient(*gradient_id)],
+            FillGradient(gradient_id, _, _)
+            | FillRadialGradient(gradient_id, _, _)
+            | FillConicGradient(gradient_id, _, _) => {
+                smallvec![DrawResource::Gradient(*gradient_id)]
+            }
             FillTransform(_) => smallvec![DrawResource::FillColor],
 
             // Transforms use the 'canvas' resource (setting the height or the identity transform resets any previous transform)
@@ -262,9 +270,12 @@ impl Draw {
 
             WindingRule(_) => DrawResource::FillWindingRule,
             BlendMode(_) => DrawResource::FillBlend,
-            FillColor(_) | FillGradient(_, _, _) | FillTexture(_, _, _) | FillTransform(_) => {
-                DrawResource::FillColor
-            }
+            FillColor(_)
+            | FillGradient(_, _, _)
+            | FillRadialGradient(_, _, _)
+            | FillConicGradient(_, _, _)
+            | FillTexture(_, _, _)
+            | FillTransform(_) => DrawResource::FillColor,
 
             SwapLayers(layer1, _layer2) => DrawResource::Layer(*layer1),
             LayerBlend(layer_id, _) => DrawResource::Layer(*layer_id),
@@ -307,6 +318,8 @@ impl Draw {
             | BlendMode(_)
             | FillColor(_)
             | FillGradient(_, _, _)
+            | FillRadialGradient(_, _, _)
+            | FillConicGradient(_, _, _)
             | FillTexture(_, _, _)
             | FillTransform(_) => true,
 
@@ -55,6 +55,45 @@ fn encode_compact_u64(val: &u64, append_to: &mut String) {
     }
 }
 
+///
+/// The version of the canvas encoding written by `encode_canvas_header()`
+///
+pub const CANVAS_ENCODING_VERSION: u32 = 1;
+
+///
+/// Encodes an item as a length-delimited instruction
+///
+/// Decoders that can't decode the item will skip over it, so instructions added to `Draw` after version 1 of the
+/// encoding should be encoded this way to make it possible for older versions of the decoder to read newer streams.
+///
+pub(crate) fn encode_length_delimited(item: impl CanvasEncoding<String>, append_to: &mut String) {
+    let mut payload = String::new();
+    item.encode_canvas(&mut payload);
+
+    append_to.push('#');
+    encode_compact_u64(&(payload.len() as u64), append_to);
+    append_to.push_str(&payload);
+}
+
+///
+/// Writes a header indicating the version of the encoding used for the instructions that follow it
+///
+/// The header is optional, but including it makes it possible for a decoder to tell which version of the encoding
+/// was used to write a stream (see `CanvasDecoder::version()`).
+///
+pub fn encode_canvas_header(append_to: &mut String) {
+    struct VersionHeader;
+
+    impl CanvasEncoding<String> for VersionHeader {
+        fn encode_canvas(&self, append_to: &mut String) {
+            append_to.push('V');
+            encode_compact_u64(&(CANVAS_ENCODING_VERSION as u64), append_to);
+        }
+    }
+
+    encode_length_delimited(VersionHeader, append_to);
+}
+
 impl CanvasEncoding<String> for char {
     #[inline]
     fn encode_canvas(&self, append_to: &mut String) {
@@ -259,16 +298,57 @@ impl CanvasEncoding<String> for &BlendMode {
             &DestinationOut => ('D', 'O'),
             &SourceAtop => ('S', 'A'),
             &DestinationAtop => ('D', 'A'),
+            &Xor => ('X', 'O'),
+            &Clear => ('C', 'L'),
+            &Copy => ('S', 'S'),
+            &Destination => ('D', 'D'),
 
             &Multiply => ('E', 'M'),
             &Screen => ('E', 'S'),
             &Darken => ('E', 'D'),
             &Lighten => ('E', 'L'),
+            &Overlay => ('E', 'O'),
+            &ColorDodge => ('E', 'C'),
+            &ColorBurn => ('E', 'B'),
+            &HardLight => ('E', 'H'),
+            &SoftLight => ('E', 'T'),
+            &Difference => ('E', 'I'),
+            &Exclusion => ('E', 'X'),
+
+            &Hue => ('N', 'H'),
+            &Saturation => ('N', 'S'),
+            &Color => ('N', 'C'),
+            &Luminosity => ('N', 'L'),
+
+            &Plus => ('P', 'L'),
         }
         .encode_canvas(append_to)
     }
 }
 
+///
+/// True if a blend mode was in version 1 of the encoding (blend modes added later are written as length-delimited instructions)
+///
+fn is_version_1_blend_mode(mode: &BlendMode) -> bool {
+    use self::BlendMode::*;
+
+    matches!(
+        mode,
+        SourceOver
+            | SourceIn
+            | SourceOut
+            | DestinationOver
+            | DestinationIn
+            | DestinationOut
+            | SourceAtop
+            | DestinationAtop
+            | Multiply
+            | Screen
+            | Darken
+            | Lighten
+    )
+}
+
 impl CanvasEncoding<String> for &Transform2D {
     #[inline]
     fn encode_canvas(&self, append_to: &mut String) {
@@ -396,6 +476,19 @@ impl<'a> CanvasEncoding<String> for &'a Vec<TextureFilter> {
     }
 }
 
+impl CanvasEncoding<String> for &GradientSpread {
+    fn encode_canvas(&self, append_to: &mut String) {
+        use self::GradientSpread::*;
+
+        match self {
+            &Pad => 'P',
+            &Repeat => 'R',
+            &Reflect => 'F',
+        }
+        .encode_canvas(append_to)
+    }
+}
+
 impl<'a> CanvasEncoding<String> for &'a GradientOp {
     fn encode_canvas(&self, append_to: &mut String) {
         use self::GradientOp::*;
@@ -403,6 +496,7 @@ impl<'a> CanvasEncoding<String> for &'a GradientOp {
         match self {
             Create(color) => ('N', color).encode_canvas(append_to),
             AddStop(pos, color) => ('S', pos, color).encode_canvas(append_to),
+            SpreadMode(spread) => ('M', spread).encode_canvas(append_to),
         }
     }
 }
@@ -603,8 +697,17 @@ impl CanvasEncoding<String> for Draw {
             FillGradient(gradient, (x1, y1), (x2, y2)) => {
                 ('C', 'g', gradient, (x1, y1), (x2, y2)).encode_canvas(append_to)
             }
+            FillRadialGradient(gradient, (x1, y1, r1), (x2, y2, r2)) => {
+                encode_length_delimited(('C', 'r', gradient, (x1, y1, r1), (x2, y2, r2)), append_to)
+            }
+            FillConicGradient(gradient, (x, y), start_angle) => {
+                encode_length_delimited(('C', 'c', gradient, (x, y), start_angle), append_to)
+            }
             FillTransform(transform) => ('C', 'T', transform).encode_canvas(append_to),
-            BlendMode(mode) => ('M', mode).encode_canvas(append_to),
+            BlendMode(mode) if is_version_1_blend_mode(mode) => {
+                ('M', mode).encode_canvas(append_to)
+            }
+            BlendMode(mode) => encode_length_delimited(('M', mode), append_to),
             IdentityTransform => ('T', 'i').encode_canvas(append_to),
             CanvasHeight(height) => ('T', 'h', height).encode_canvas(append_to),
             CenterRegion(min, max) => ('T', 'c', *min, *max).encode_canvas(append_to),
@@ -618,9 +721,12 @@ impl CanvasEncoding<String> for Draw {
             PopState => 'p'.encode_canvas(append_to),
             ClearCanvas(color) => ('N', 'A', color).encode_canvas(append_to),
             Layer(layer_id) => ('N', 'L', layer_id).encode_canvas(append_to),
-            LayerBlend(layer_id, blend_mode) => {
+            LayerBlend(layer_id, blend_mode) if is_version_1_blend_mode(blend_mode) => {
                 ('N', 'B', layer_id, blend_mode).encode_canvas(append_to)
             }
+            LayerBlend(layer_id, blend_mode) => {
+                encode_length_delimited(('N', 'B', layer_id, blend_mode), append_to)
+            }
             LayerAlpha(layer_id, alpha) => ('N', 't', layer_id, alpha).encode_canvas(append_to),
             ClearLayer => ('N', 'C').encode_canvas(append_to),
             ClearAllLayers => ('N', 'a').encode_canvas(append_to),
@@ -642,6 +748,9 @@ impl CanvasEncoding<String> for Draw {
             }
             BeginLineLayout(x, y, align) => ('t', 'l', x, y, align).encode_canvas(append_to),
             DrawLaidOutText => ('t', 'R').encode_canvas(append_to),
+            Gradient(gradient_id, gradient_op @ GradientOp::SpreadMode(_)) => {
+                encode_length_delimited(('G', gradient_id, gradient_op), append_to)
+            }
             Gradient(gradient_id, ref gradient_op) => {
                 ('G', gradient_id, gradient_op).encode_canvas(append_to)
             }
@@ -785,6 +894,36 @@ mod test {
         assert!(&encode_draw(Draw::BlendMode(BlendMode::SourceOver)) == "MSV")
     }
 
+    #[test]
+    fn encode_non_separable_blendmode() {
+        // Blend modes added after version 1 of the encoding are length-delimited
+        assert!(&encode_draw(Draw::BlendMode(BlendMode::Luminosity)) == "#DMNL")
+    }
+
+    #[test]
+    fn encode_layer_blend_after_version_1() {
+        assert!(&encode_draw(Draw::LayerBlend(LayerId(2), BlendMode::Overlay)).starts_with('#'));
+        assert!(!&encode_draw(Draw::LayerBlend(LayerId(2), BlendMode::Screen)).starts_with('#'));
+    }
+
+    #[test]
+    fn encode_gradients_after_version_1() {
+        assert!(encode_draw(Draw::FillRadialGradient(
+            GradientId(1),
+            (0.0, 0.0, 0.0),
+            (1.0, 1.0, 1.0)
+        ))
+        .starts_with('#'));
+        assert!(
+            encode_draw(Draw::FillConicGradient(GradientId(1), (0.0, 0.0), 0.0)).starts_with('#')
+        );
+        assert!(encode_draw(Draw::Gradient(
+            GradientId(1),
+            GradientOp::SpreadMode(GradientSpread::Repeat)
+        ))
+        .starts_with('#'));
+    }
+
     #[test]
     fn encode_identity_transform() {
         assert!(&encode_draw(Draw::IdentityTransform) == "Ti")
@@ -15,12 +15,14 @@ use serde::de;
 use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
 use serde::ser::{Serialize, SerializeStruct, Serializer};
 
+use crate::serialized_data::*;
+
 use std::fmt;
 use std::sync::*;
 
 /// allsorts table provider implementation based on a unsafe (based on lifetime) pointer to a TTF parser face
 #[cfg(feature = "outline-fonts")]
-pub struct CanvasTableProvider<'a>(&'a ttf_parser::Face<'a>);
+pub struct CanvasTableProvider<'a>(pub(crate) &'a ttf_parser::Face<'a>);
 
 #[cfg(feature = "outline-fonts")]
 impl<'b> FontTableProvider for CanvasTableProvider<'b> {
@@ -264,7 +266,7 @@ impl Serialize for CanvasFontFace {
         S: Serializer,
     {
         let mut s = serializer.serialize_struct("CanvasFontFace", 1)?;
-        s.serialize_field("data", self.font_data())?;
+        s.serialize_field("data", &SerializedData(self.font_data()))?;
         s.end()
     }
 }
@@ -321,7 +323,7 @@ impl<'de> Deserialize<'de> for CanvasFontFace {
             where
                 V: SeqAccess<'de>,
             {
-                let bytes: Vec<u8> = seq
+                let DeserializedData(bytes) = seq
                     .next_element()?
                     .ok_or_else(|| de::Error::invalid_length(0, &self))?;
                 let data = bytes.into_boxed_slice();
@@ -340,7 +342,7 @@ impl<'de> Deserialize<'de> for CanvasFontFace {
                             if data.is_some() {
                                 return Err(de::Error::duplicate_field("data"));
                             }
-                            data = Some(map.next_value()?);
+                            data = Some(map.next_value::<DeserializedData>()?.0);
                         }
                     }
                 }
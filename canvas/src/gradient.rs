@@ -16,6 +16,27 @@ use std::cmp::Ordering;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct GradientId(pub u64);
 
+///
+/// How a gradient is extended beyond the range of its colour stops
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GradientSpread {
+    /// The colours at the first and last stops are used for the areas outside of the gradient (the default)
+    Pad,
+
+    /// The gradient is repeated, so the colour after the last stop is the colour of the first stop
+    Repeat,
+
+    /// The gradient is mirrored each time it repeats
+    Reflect,
+}
+
+impl Default for GradientSpread {
+    fn default() -> Self {
+        GradientSpread::Pad
+    }
+}
+
 ///
 /// Operations that can be applied to a gradient
 ///
@@ -26,6 +47,25 @@ pub enum GradientOp {
 
     /// Adds a new gradient stop of the specified colour
     AddStop(f32, Color),
+
+    /// Sets how the gradient is extended beyond its first and last stops
+    SpreadMode(GradientSpread),
+}
+
+///
+/// Returns the spread mode set by a list of gradient operations
+///
+pub fn gradient_spread<'a, GradientIter: IntoIterator<Item = &'a GradientOp>>(
+    description: GradientIter,
+) -> GradientSpread {
+    description
+        .into_iter()
+        .filter_map(|op| match op {
+            GradientOp::SpreadMode(spread) => Some(*spread),
+            _ => None,
+        })
+        .last()
+        .unwrap_or_default()
 }
 
 ///
@@ -67,9 +107,10 @@ pub fn gradient_scale<GradientIter: IntoIterator<Item = GradientOp>, const N: us
     // Create a list of colour stops by position
     let mut stops = description
         .into_iter()
-        .map(|op| match op {
-            GradientOp::Create(col) => (0.0, col.to_rgba_components()),
-            GradientOp::AddStop(pos, col) => (pos, col.to_rgba_components()),
+        .filter_map(|op| match op {
+            GradientOp::Create(col) => Some((0.0, col.to_rgba_components())),
+            GradientOp::AddStop(pos, col) => Some((pos, col.to_rgba_components())),
+            GradientOp::SpreadMode(_) => None,
         })
         .collect::<Vec<_>>();
 
@@ -278,6 +319,33 @@ mod test {
         assert!(scale[14] == [0, 0, 0, 238]);
     }
 
+    #[test]
+    fn spread_mode_does_not_affect_scale() {
+        let padded = gradient_scale::<_, 16>(vec![
+            GradientOp::Create(Color::Rgba(0.0, 0.0, 0.0, 0.0)),
+            GradientOp::AddStop(1.0, Color::Rgba(1.0, 1.0, 1.0, 1.0)),
+        ]);
+        let reflected = gradient_scale::<_, 16>(vec![
+            GradientOp::Create(Color::Rgba(0.0, 0.0, 0.0, 0.0)),
+            GradientOp::SpreadMode(GradientSpread::Reflect),
+            GradientOp::AddStop(1.0, Color::Rgba(1.0, 1.0, 1.0, 1.0)),
+        ]);
+
+        assert!(padded == reflected);
+    }
+
+    #[test]
+    fn last_spread_mode_is_used() {
+        let ops = vec![
+            GradientOp::Create(Color::Rgba(0.0, 0.0, 0.0, 0.0)),
+            GradientOp::SpreadMode(GradientSpread::Reflect),
+            GradientOp::SpreadMode(GradientSpread::Repeat),
+        ];
+
+        assert!(gradient_spread(&ops) == GradientSpread::Repeat);
+        assert!(gradient_spread(&ops[0..1]) == GradientSpread::Pad);
+    }
+
     #[test]
     fn generate_two_stop_scale() {
         let scale = gradient_scale::<_, 17>(vec![
@@ -0,0 +1,569 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//!
+//! JSON and MessagePack documents containing canvas drawings
+//!
+//! These formats are intended for tools that generate or inspect drawings without using this crate. A document is
+//! a map with two fields: `version`, which is `DRAWING_INTERCHANGE_VERSION` for documents written by this version of
+//! the crate, and `drawing`, which is a list of `Draw` instructions:
+//!
+//! ```json
+//! { "version": 1, "drawing": [ { "ClearCanvas": { "Rgba": [1.0, 1.0, 1.0, 1.0] } }, ... ] }
+//! ```
+//!
+//! The instructions use serde's default representation of the `Draw` enum and the types it contains:
+//!
+//! * Variants without any data are strings: `"Fill"`, `{ "Path": "NewPath" }`
+//! * Variants with one value are a map from the variant name to the value: `{ "LineWidth": 2.0 }`
+//! * Variants with more than one value are a map from the variant name to a list: `{ "Path": { "Move": [10.0, 20.0] } }`
+//! * IDs such as `LayerId` or `TextureId` are numbers, and structs with unnamed fields (such as `TextureSize`) are lists.
+//!   `Transform2D` is a list of three rows of three numbers
+//! * Structs with named fields (`FontProperties`, `GlyphPosition`) are maps
+//! * Namespace IDs are UUIDs: a string in JSON and 16 bytes in MessagePack
+//! * Font files (`FontOp::UseFontDefinition`) are a map with a `data` field, and bitmaps (`TextureOp::SetBytes`) are
+//!   the third value of the variant. In JSON this data is a base64 string, and in MessagePack it's a byte array
+//!
+//! For example, this creates a 1x1 red texture:
+//!
+//! ```json
+//! { "Texture": [1, { "Create": [[1, 1], "Rgba"] }] }
+//! { "Texture": [1, { "SetBytes": [[0, 0], [1, 1], "/wAA/w=="] }] }
+//! ```
+//!
+//! MessagePack documents have the same structure as JSON documents, with maps for the structs.
+//!
+
+use crate::draw::*;
+
+use std::fmt;
+
+/// The version of the interchange format written by this crate
+pub const DRAWING_INTERCHANGE_VERSION: u32 = 1;
+
+///
+/// Errors that can occur while reading or writing an interchange document
+///
+#[derive(Debug)]
+pub enum InterchangeError {
+    /// The document was written using a newer version of the format
+    UnsupportedVersion(u32),
+
+    /// The JSON document could not be read or written
+    #[cfg(feature = "json")]
+    Json(serde_json::Error),
+
+    /// The MessagePack document could not be written
+    #[cfg(feature = "msgpack")]
+    EncodeMessagePack(rmp_serde::encode::Error),
+
+    /// The MessagePack document could not be read
+    #[cfg(feature = "msgpack")]
+    DecodeMessagePack(rmp_serde::decode::Error),
+}
+
+///
+/// A drawing with the version of the format it was written with
+///
+#[derive(Serialize, Deserialize)]
+struct InterchangeDocument<TDrawing> {
+    version: u32,
+    drawing: TDrawing,
+}
+
+///
+/// The version of a document (read before the drawing so that documents from newer versions can be rejected)
+///
+#[derive(Deserialize)]
+struct InterchangeVersion {
+    version: u32,
+}
+
+impl fmt::Display for InterchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterchangeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported interchange format version {}", version)
+            }
+            #[cfg(feature = "json")]
+            InterchangeError::Json(err) => write!(f, "{}", err),
+            #[cfg(feature = "msgpack")]
+            InterchangeError::EncodeMessagePack(err) => write!(f, "{}", err),
+            #[cfg(feature = "msgpack")]
+            InterchangeError::DecodeMessagePack(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for InterchangeError {}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for InterchangeError {
+    fn from(err: serde_json::Error) -> InterchangeError {
+        InterchangeError::Json(err)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl From<rmp_serde::encode::Error> for InterchangeError {
+    fn from(err: rmp_serde::encode::Error) -> InterchangeError {
+        InterchangeError::EncodeMessagePack(err)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl From<rmp_serde::decode::Error> for InterchangeError {
+    fn from(err: rmp_serde::decode::Error) -> InterchangeError {
+        InterchangeError::DecodeMessagePack(err)
+    }
+}
+
+///
+/// Returns an error if a document version can't be read by this crate
+///
+fn check_version(version: u32) -> Result<(), InterchangeError> {
+    if version > DRAWING_INTERCHANGE_VERSION {
+        Err(InterchangeError::UnsupportedVersion(version))
+    } else {
+        Ok(())
+    }
+}
+
+///
+/// Writes a drawing as a JSON interchange document
+///
+#[cfg(feature = "json")]
+pub fn drawing_to_json(drawing: &[Draw]) -> Result<String, InterchangeError> {
+    Ok(serde_json::to_string(&InterchangeDocument {
+        version: DRAWING_INTERCHANGE_VERSION,
+        drawing,
+    })?)
+}
+
+///
+/// Reads a drawing from a JSON interchange document
+///
+#[cfg(feature = "json")]
+pub fn drawing_from_json(json: &str) -> Result<Vec<Draw>, InterchangeError> {
+    check_version(serde_json::from_str::<InterchangeVersion>(json)?.version)?;
+
+    Ok(serde_json::from_str::<InterchangeDocument<Vec<Draw>>>(json)?.drawing)
+}
+
+///
+/// Writes a drawing as a MessagePack interchange document
+///
+#[cfg(feature = "msgpack")]
+pub fn drawing_to_msgpack(drawing: &[Draw]) -> Result<Vec<u8>, InterchangeError> {
+    Ok(rmp_serde::to_vec_named(&InterchangeDocument {
+        version: DRAWING_INTERCHANGE_VERSION,
+        drawing,
+    })?)
+}
+
+///
+/// Reads a drawing from a MessagePack interchange document
+///
+#[cfg(feature = "msgpack")]
+pub fn drawing_from_msgpack(msgpack: &[u8]) -> Result<Vec<Draw>, InterchangeError> {
+    check_version(rmp_serde::from_slice::<InterchangeVersion>(msgpack)?.version)?;
+
+    Ok(rmp_serde::from_slice::<InterchangeDocument<Vec<Draw>>>(msgpack)?.drawing)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::color::*;
+    use crate::font::*;
+    use crate::font_face::*;
+    use crate::gradient::*;
+    use crate::namespace::*;
+    use crate::path::*;
+    use crate::sprite::*;
+    use crate::texture::*;
+    use crate::transform2d::*;
+
+    use uuid::*;
+
+    use std::sync::*;
+
+    ///
+    /// Identifies which variant of `Draw` an instruction is
+    ///
+    /// There's no wildcard here, so this will stop compiling if a new instruction is added, as a reminder to add it
+    /// to `every_instruction()`
+    ///
+    fn variant_index(draw: &Draw) -> usize {
+        use self::Draw::*;
+
+        match draw {
+            StartFrame => 0,
+            ShowFrame => 1,
+            ResetFrame => 2,
+            Path(_) => 3,
+            Fill => 4,
+            Stroke => 5,
+            LineWidth(_) => 6,
+            LineWidthPixels(_) => 7,
+            LineJoin(_) => 8,
+            LineCap(_) => 9,
+            NewDashPattern => 10,
+            DashLength(_) => 11,
+            DashOffset(_) => 12,
+            FillColor(_) => 13,
+            FillTexture(_, _, _) => 14,
+            FillGradient(_, _, _) => 15,
+            FillRadialGradient(_, _, _) => 16,
+            FillConicGradient(_, _, _) => 17,
+            FillTransform(_) => 18,
+            StrokeColor(_) => 19,
+            WindingRule(_) => 20,
+            BlendMode(_) => 21,
+            IdentityTransform => 22,
+            CanvasHeight(_) => 23,
+            CenterRegion(_, _) => 24,
+            MultiplyTransform(_) => 25,
+            Unclip => 26,
+            Clip => 27,
+            Store => 28,
+            Restore => 29,
+            FreeStoredBuffer => 30,
+            PushState => 31,
+            PopState => 32,
+            ClearCanvas(_) => 33,
+            Layer(_) => 34,
+            LayerBlend(_, _) => 35,
+            LayerAlpha(_, _) => 36,
+            ClearLayer => 37,
+            ClearAllLayers => 38,
+            SwapLayers(_, _) => 39,
+            Sprite(_) => 40,
+            MoveSpriteFrom(_) => 41,
+            ClearSprite => 42,
+            SpriteTransform(_) => 43,
+            DrawSprite(_) => 44,
+            DrawSpriteWithFilters(_, _) => 45,
+            Texture(_, _) => 46,
+            Font(_, _) => 47,
+            BeginLineLayout(_, _, _) => 48,
+            DrawLaidOutText => 49,
+            DrawText(_, _, _, _) => 50,
+            Gradient(_, _) => 51,
+            Namespace(_) => 52,
+        }
+    }
+
+    /// The number of variants identified by `variant_index()`
+    const NUM_VARIANTS: usize = 53;
+
+    ///
+    /// A drawing that contains every instruction (and every variant of the types used by the instructions)
+    ///
+    fn every_instruction() -> Vec<Draw> {
+        let font = CanvasFontFace::from_slice(include_bytes!("../test_data/Lato-Regular.ttf"));
+
+        vec![
+            Draw::StartFrame,
+            Draw::ShowFrame,
+            Draw::ResetFrame,
+            Draw::Path(PathOp::NewPath),
+            Draw::Path(PathOp::Move(10.0, 15.0)),
+            Draw::Path(PathOp::Line(20.0, 42.0)),
+            Draw::Path(PathOp::BezierCurve(((1.0, 2.0), (3.0, 4.0)), (5.0, 6.0))),
+            Draw::Path(PathOp::ClosePath),
+            Draw::Fill,
+            Draw::Stroke,
+            Draw::LineWidth(23.0),
+            Draw::LineWidthPixels(43.0),
+            Draw::LineJoin(LineJoin::Miter),
+            Draw::LineJoin(LineJoin::Round),
+            Draw::LineJoin(LineJoin::Bevel),
+            Draw::LineCap(LineCap::Butt),
+            Draw::LineCap(LineCap::Round),
+            Draw::LineCap(LineCap::Square),
+            Draw::NewDashPattern,
+            Draw::DashLength(56.0),
+            Draw::DashOffset(13.0),
+            Draw::FillColor(Color::Rgba(0.2, 0.3, 0.4, 0.5)),
+            Draw::FillColor(Color::Hsluv(120.0, 50.0, 60.0, 0.5)),
+            Draw::FillTexture(TextureId(23), (42.0, 43.0), (44.0, 45.0)),
+            Draw::FillGradient(GradientId(24), (42.0, 43.0), (44.0, 45.0)),
+            Draw::FillRadialGradient(GradientId(25), (42.0, 43.0, 1.0), (44.0, 45.0, 10.0)),
+            Draw::FillConicGradient(GradientId(26), (42.0, 43.0), 0.5),
+            Draw::FillTransform(Transform2D::identity()),
+            Draw::StrokeColor(Color::Rgba(0.1, 0.2, 0.3, 0.4)),
+            Draw::WindingRule(WindingRule::NonZero),
+            Draw::WindingRule(WindingRule::EvenOdd),
+            Draw::BlendMode(BlendMode::SourceOver),
+            Draw::BlendMode(BlendMode::Luminosity),
+            Draw::IdentityTransform,
+            Draw::CanvasHeight(81.0),
+            Draw::CenterRegion((6.0, 7.0), (8.0, 9.0)),
+            Draw::MultiplyTransform(Transform2D([
+                [1.0, 2.0, 3.0],
+                [4.0, 5.0, 6.0],
+                [7.0, 8.0, 9.0],
+            ])),
+            Draw::Unclip,
+            Draw::Clip,
+            Draw::Store,
+            Draw::Restore,
+            Draw::FreeStoredBuffer,
+            Draw::PushState,
+            Draw::PopState,
+            Draw::ClearCanvas(Color::Rgba(0.1, 0.2, 0.3, 0.4)),
+            Draw::Layer(LayerId(21)),
+            Draw::LayerBlend(LayerId(21), BlendMode::Multiply),
+            Draw::LayerAlpha(LayerId(21), 0.5),
+            Draw::ClearLayer,
+            Draw::ClearAllLayers,
+            Draw::SwapLayers(LayerId(1), LayerId(2)),
+            Draw::Sprite(SpriteId(1000)),
+            Draw::MoveSpriteFrom(SpriteId(48)),
+            Draw::ClearSprite,
+            Draw::SpriteTransform(SpriteTransform::Identity),
+            Draw::SpriteTransform(SpriteTransform::Translate(4.0, 5.0)),
+            Draw::SpriteTransform(SpriteTransform::Scale(2.0, 3.0)),
+            Draw::SpriteTransform(SpriteTransform::Rotate(45.0)),
+            Draw::SpriteTransform(SpriteTransform::Transform2D(Transform2D::scale(3.0, 4.0))),
+            Draw::DrawSprite(SpriteId(1300)),
+            Draw::DrawSpriteWithFilters(
+                SpriteId(10),
+                vec![
+                    TextureFilter::GaussianBlur(4.0),
+                    TextureFilter::AlphaBlend(0.5),
+                    TextureFilter::Mask(TextureId(3)),
+                    TextureFilter::DisplacementMap(TextureId(4), 1.0, 2.0),
+                ],
+            ),
+            Draw::Texture(
+                TextureId(42),
+                TextureOp::Create(TextureSize(1024, 768), TextureFormat::Rgba),
+            ),
+            Draw::Texture(TextureId(43), TextureOp::Free),
+            Draw::Texture(
+                TextureId(44),
+                TextureOp::SetBytes(
+                    TexturePosition(2, 3),
+                    TextureSize(1, 2),
+                    Arc::new(vec![1, 2, 3, 4, 5, 6, 7, 8]),
+                ),
+            ),
+            Draw::Texture(
+                TextureId(44),
+                TextureOp::SetFromSprite(
+                    SpriteId(42),
+                    SpriteBounds(SpritePosition(20.0, 30.0), SpriteSize(40.0, 50.0)),
+                ),
+            ),
+            Draw::Texture(
+                TextureId(44),
+                TextureOp::CreateDynamicSprite(
+                    SpriteId(42),
+                    SpriteBounds(SpritePosition(20.0, 30.0), SpriteSize(40.0, 50.0)),
+                    CanvasSize(60.0, 70.0),
+                ),
+            ),
+            Draw::Texture(TextureId(45), TextureOp::FillTransparency(0.5)),
+            Draw::Texture(TextureId(46), TextureOp::Copy(TextureId(47))),
+            Draw::Texture(
+                TextureId(47),
+                TextureOp::Filter(TextureFilter::GaussianBlur(23.0)),
+            ),
+            Draw::Font(FontId(1), FontOp::UseFontDefinition(font)),
+            Draw::Font(FontId(1), FontOp::FontSize(12.0)),
+            Draw::Font(FontId(1), FontOp::LayoutText("Hello \u{2603}".to_string())),
+            Draw::Font(
+                FontId(1),
+                FontOp::DrawGlyphs(vec![GlyphPosition {
+                    id: GlyphId(20),
+                    location: (1.0, 2.0),
+                    em_size: 12.0,
+                }]),
+            ),
+            Draw::BeginLineLayout(1.0, 2.0, TextAlignment::Left),
+            Draw::BeginLineLayout(1.0, 2.0, TextAlignment::Right),
+            Draw::BeginLineLayout(1.0, 2.0, TextAlignment::Center),
+            Draw::DrawLaidOutText,
+            Draw::DrawText(FontId(1), "Text".to_string(), 100.0, 200.0),
+            Draw::Gradient(
+                GradientId(42),
+                GradientOp::Create(Color::Rgba(0.1, 0.2, 0.3, 0.4)),
+            ),
+            Draw::Gradient(
+                GradientId(42),
+                GradientOp::AddStop(0.5, Color::Rgba(0.1, 0.2, 0.3, 0.4)),
+            ),
+            Draw::Gradient(GradientId(42), GradientOp::SpreadMode(GradientSpread::Pad)),
+            Draw::Gradient(
+                GradientId(42),
+                GradientOp::SpreadMode(GradientSpread::Reflect),
+            ),
+            Draw::Gradient(
+                GradientId(42),
+                GradientOp::SpreadMode(GradientSpread::Repeat),
+            ),
+            Draw::Namespace(NamespaceId::default()),
+            Draw::Namespace(NamespaceId::with_id(uuid![
+                "498D1CE4-D05B-43D0-BBDD-DDBE6F3AF6E7"
+            ])),
+        ]
+    }
+
+    #[test]
+    fn every_instruction_covers_every_variant() {
+        let mut seen = [false; NUM_VARIANTS];
+
+        for draw in every_instruction() {
+            seen[variant_index(&draw)] = true;
+        }
+
+        assert!(
+            seen.iter().all(|seen| *seen),
+            "Missing variants: {:?}",
+            seen.iter()
+                .enumerate()
+                .filter(|(_, seen)| !**seen)
+                .map(|(idx, _)| idx)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_round_trip() {
+        let drawing = every_instruction();
+        let json = drawing_to_json(&drawing).unwrap();
+        let decoded = drawing_from_json(&json).unwrap();
+
+        assert!(decoded.len() == drawing.len());
+        for (original, decoded) in drawing.iter().zip(decoded.iter()) {
+            assert!(original == decoded, "{:?} != {:?}", original, decoded);
+        }
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_format() {
+        let drawing = vec![
+            Draw::ClearCanvas(Color::Rgba(1.0, 1.0, 1.0, 1.0)),
+            Draw::Path(PathOp::NewPath),
+            Draw::Path(PathOp::Move(10.0, 20.0)),
+            Draw::LineWidth(2.0),
+            Draw::Fill,
+            Draw::Layer(LayerId(1)),
+            Draw::Texture(
+                TextureId(1),
+                TextureOp::Create(TextureSize(1, 1), TextureFormat::Rgba),
+            ),
+            Draw::Texture(
+                TextureId(1),
+                TextureOp::SetBytes(
+                    TexturePosition(0, 0),
+                    TextureSize(1, 1),
+                    Arc::new(vec![255, 0, 0, 255]),
+                ),
+            ),
+            Draw::Namespace(NamespaceId::default()),
+        ];
+
+        let json = drawing_to_json(&drawing).unwrap();
+
+        assert!(
+            json == concat!(
+                "{\"version\":1,\"drawing\":[",
+                "{\"ClearCanvas\":{\"Rgba\":[1.0,1.0,1.0,1.0]}},",
+                "{\"Path\":\"NewPath\"},",
+                "{\"Path\":{\"Move\":[10.0,20.0]}},",
+                "{\"LineWidth\":2.0},",
+                "\"Fill\",",
+                "{\"Layer\":1},",
+                "{\"Texture\":[1,{\"Create\":[[1,1],\"Rgba\"]}]},",
+                "{\"Texture\":[1,{\"SetBytes\":[[0,0],[1,1],\"/wAA/w==\"]}]},",
+                "{\"Namespace\":\"00000000-0000-0000-0000-000000000000\"}",
+                "]}"
+            ),
+            "{}",
+            json
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_font_data_is_base64() {
+        let font = CanvasFontFace::from_slice(include_bytes!("../test_data/Lato-Regular.ttf"));
+        let json =
+            drawing_to_json(&[Draw::Font(FontId(0), FontOp::UseFontDefinition(font))]).unwrap();
+
+        // Lato-Regular.ttf starts with the bytes 00 01 00 00
+        assert!(
+            json.starts_with(
+                "{\"version\":1,\"drawing\":[{\"Font\":[0,{\"UseFontDefinition\":{\"data\":\"AAEAAA"
+            ),
+            "{}",
+            &json[0..100]
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_newer_version() {
+        let result = drawing_from_json("{\"version\":2,\"drawing\":[\"NotAnInstruction\"]}");
+
+        assert!(matches!(
+            result,
+            Err(InterchangeError::UnsupportedVersion(2))
+        ));
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn msgpack_round_trip() {
+        let drawing = every_instruction();
+        let msgpack = drawing_to_msgpack(&drawing).unwrap();
+        let decoded = drawing_from_msgpack(&msgpack).unwrap();
+
+        assert!(decoded.len() == drawing.len());
+        for (original, decoded) in drawing.iter().zip(decoded.iter()) {
+            assert!(original == decoded, "{:?} != {:?}", original, decoded);
+        }
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn msgpack_texture_data_is_binary() {
+        let drawing = vec![Draw::Texture(
+            TextureId(1),
+            TextureOp::SetBytes(
+                TexturePosition(0, 0),
+                TextureSize(1, 1),
+                Arc::new(vec![255, 0, 0, 255]),
+            ),
+        )];
+        let msgpack = drawing_to_msgpack(&drawing).unwrap();
+
+        // 0xc4 is the MessagePack marker for a byte array with an 8-bit length
+        assert!(msgpack
+            .windows(6)
+            .any(|bytes| bytes == [0xc4, 4, 255, 0, 0, 255]));
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn msgpack_newer_version() {
+        let msgpack = rmp_serde::to_vec_named(&InterchangeDocument {
+            version: 2,
+            drawing: Vec::<Draw>::new(),
+        })
+        .unwrap();
+
+        assert!(matches!(
+            drawing_from_msgpack(&msgpack),
+            Err(InterchangeError::UnsupportedVersion(2))
+        ));
+    }
+}
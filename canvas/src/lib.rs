@@ -13,7 +13,8 @@
 //! The main features that this library supports are the set of primitives in the `Draw` enum, the
 //! `Canvas` type for streaming drawing instructions elsewhere, and the encoding and decoding
 //! functions that can be used to send canvas instructions over a byte stream. Encoding uses MIME64
-//! characters, so it's easy to embed encoded canvases in other protocols.
+//! characters, so it's easy to embed encoded canvases in other protocols. A more compact binary encoding
+//! is also available via `decode_binary_drawing()` and `decode_binary_drawing_stream()`.
 //!
 //! By itself, `flo_canvas` is an excellent way to describe how a 2D scene should be rendered without
 //! needing to depend on a system-specific library.
@@ -37,6 +38,18 @@
 //!   another stream of Draw instructions, except all the font commands will be removed and replaced
 //!   with an outline rendering of the font (useful for rendering back-ends that don't have native
 //!   font support or for generating vector files that don't require particular fonts to be installed)
+//! * `svg` - provides `SvgWriter` and `drawing_to_svg()`, which convert a stream of Draw instructions into
+//!   an SVG document (textures are embedded as PNG images), and `SvgReader` and `svg_to_drawing()`, which
+//!   convert an SVG document into Draw instructions
+//! * `pdf` - provides `PdfWriter` and `drawing_to_pdf()`, which convert a stream of Draw instructions into a
+//!   PDF document (fonts are embedded as subsets, and each frame becomes a separate page). This also turns on
+//!   the `outline-fonts` feature, which is used to lay out text
+//! * `remote` - provides `CanvasServer`, which sends a drawing to clients over TCP or unix domain sockets, and
+//!   `remote_drawing_stream()` and `remote_canvas()`, which receive a drawing from a server
+//! * `json` - provides `drawing_to_json()` and `drawing_from_json()`, which store drawings in a documented JSON format
+//!   that can be generated by tools that aren't written in Rust (font and texture data is stored as base64)
+//! * `msgpack` - provides `drawing_to_msgpack()` and `drawing_from_msgpack()`, which use the same format as the JSON
+//!   functions but in MessagePack form (font and texture data is stored as byte arrays)
 //!
 #![warn(bare_trait_objects)]
 
@@ -47,7 +60,10 @@ extern crate desync;
 extern crate futures;
 extern crate hsluv;
 
+mod binary_decoding;
+mod binary_encoding;
 mod canvas;
+mod canvas_diff;
 mod color;
 mod context;
 mod conversion_streams;
@@ -63,16 +79,28 @@ mod gradient;
 mod namespace;
 mod path;
 mod primitives;
+mod recording;
+mod serialized_data;
 mod sprite;
 mod texture;
 mod transform2d;
 
 #[cfg(feature = "outline-fonts")]
 mod font_line_layout;
+#[cfg(any(feature = "json", feature = "msgpack"))]
+mod interchange;
+#[cfg(feature = "pdf")]
+mod pdf;
+#[cfg(feature = "remote")]
+mod remote;
 #[cfg(feature = "scenery")]
 pub mod scenery;
+#[cfg(feature = "svg")]
+mod svg;
 
+pub use self::binary_decoding::*;
 pub use self::canvas::*;
+pub use self::canvas_diff::*;
 pub use self::color::*;
 pub use self::context::*;
 pub use self::conversion_streams::*;
@@ -87,12 +115,21 @@ pub use self::gradient::*;
 pub use self::namespace::*;
 pub use self::path::*;
 pub use self::primitives::*;
+pub use self::recording::*;
 pub use self::sprite::*;
 pub use self::texture::*;
 pub use self::transform2d::*;
 
 #[cfg(feature = "outline-fonts")]
 pub use self::font_line_layout::*;
+#[cfg(any(feature = "json", feature = "msgpack"))]
+pub use self::interchange::*;
+#[cfg(feature = "pdf")]
+pub use self::pdf::*;
+#[cfg(feature = "remote")]
+pub use self::remote::*;
+#[cfg(feature = "svg")]
+pub use self::svg::*;
 
 pub use flo_curves as curves;
 pub use flo_curves::geo::{Coord2, Coordinate2D};
@@ -29,7 +29,11 @@ static KNOWN_IDS: Lazy<Mutex<HashMap<Uuid, usize>>> = Lazy::new(|| Mutex::new(Ha
 /// The main use case for namespaces is for when a rendering target has many clients: a client can use its own namespace
 /// to avoid needing to coordinate with other clients over which resources it can use.
 ///
+/// Namespaces are serialized using their global ID, so a namespace that is sent to another process will refer to the
+/// same namespace when it's deserialized there.
+///
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(into = "SerializedNamespaceId", from = "SerializedNamespaceId")]
 pub struct NamespaceId {
     /// The local ID of this namespace, which is used to compare the namespace inside the process
     local_id: usize,
@@ -38,6 +42,35 @@ pub struct NamespaceId {
     global_id: Uuid,
 }
 
+///
+/// How a namespace ID is serialized
+///
+/// Namespace IDs used to be serialized with their local ID as well as their global ID, so the older form can also be read.
+///
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SerializedNamespaceId {
+    GlobalId(Uuid),
+    LocalAndGlobalId { global_id: Uuid },
+}
+
+impl From<NamespaceId> for SerializedNamespaceId {
+    fn from(namespace: NamespaceId) -> SerializedNamespaceId {
+        SerializedNamespaceId::GlobalId(namespace.global_id)
+    }
+}
+
+impl From<SerializedNamespaceId> for NamespaceId {
+    fn from(namespace: SerializedNamespaceId) -> NamespaceId {
+        match namespace {
+            SerializedNamespaceId::GlobalId(global_id)
+            | SerializedNamespaceId::LocalAndGlobalId { global_id } => {
+                NamespaceId::with_id(global_id)
+            }
+        }
+    }
+}
+
 impl NamespaceId {
     ///
     /// Creates a new unique namespace ID
@@ -140,4 +173,27 @@ mod test {
                 == NamespaceId::with_id(uuid!["498D1CE4-D05B-43D0-BBDD-DDBE6F3AF6E7"])
         );
     }
+
+    #[test]
+    fn serialize_as_global_id() {
+        let namespace = NamespaceId::with_id(uuid!["498D1CE4-D05B-43D0-BBDD-DDBE6F3AF6E7"]);
+        let json = serde_json::to_string(&namespace).unwrap();
+
+        assert!(
+            json == "\"498d1ce4-d05b-43d0-bbdd-ddbe6f3af6e7\"",
+            "{}",
+            json
+        );
+        assert!(serde_json::from_str::<NamespaceId>(&json).unwrap() == namespace);
+    }
+
+    #[test]
+    fn deserialize_local_and_global_id() {
+        let namespace = serde_json::from_str::<NamespaceId>(
+            "{\"local_id\":12345,\"global_id\":\"498d1ce4-d05b-43d0-bbdd-ddbe6f3af6e7\"}",
+        )
+        .unwrap();
+
+        assert!(namespace == NamespaceId::with_id(uuid!["498D1CE4-D05B-43D0-BBDD-DDBE6F3AF6E7"]));
+    }
 }
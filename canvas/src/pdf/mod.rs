@@ -0,0 +1,17 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//!
+//! Conversion of canvas drawings to the PDF format
+//!
+
+mod pdf_document;
+mod pdf_font;
+mod pdf_format;
+mod pdf_resources;
+mod pdf_writer;
+
+pub use self::pdf_writer::*;
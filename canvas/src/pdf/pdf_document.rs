@@ -0,0 +1,288 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::pdf_format::*;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as FmtWrite;
+use std::io::Write;
+use std::sync::*;
+
+/// The object containing the resource dictionary shared by every page and form in the document
+pub const RESOURCES_OBJECT: usize = 1;
+
+/// The object containing the page tree
+const PAGES_OBJECT: usize = 2;
+
+/// The document catalog
+const CATALOG_OBJECT: usize = 3;
+
+///
+/// The types of resource that can be referenced by name from a content stream
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PdfResourceType {
+    ExtGState,
+    Pattern,
+    XObject,
+    Font,
+}
+
+///
+/// The objects that make up a PDF document
+///
+/// Every page and form in the document uses the same resource dictionary, so resources can be referenced by name from
+/// any content stream once they've been added to the document.
+///
+#[derive(Clone)]
+pub struct PdfDocument {
+    /// True if streams should be compressed
+    compress: bool,
+
+    /// The body of each object in the document (the object ID is the index + 1). Reserved objects are None until they are set.
+    objects: Vec<Option<Arc<Vec<u8>>>>,
+
+    /// The named resources, by type
+    resources: BTreeMap<PdfResourceType, BTreeMap<String, usize>>,
+
+    /// The graphics states that have been written, indexed by fill alpha, stroke alpha and blend mode
+    ext_g_states: HashMap<(u32, u32, &'static str), String>,
+
+    /// The IDs of the content stream objects for each page
+    pages: Vec<usize>,
+
+    /// Used to generate unique resource names
+    next_name: usize,
+}
+
+impl PdfDocument {
+    ///
+    /// Creates a new empty document
+    ///
+    pub fn new(compress: bool) -> PdfDocument {
+        PdfDocument {
+            compress,
+            objects: vec![None, None, None],
+            resources: BTreeMap::new(),
+            ext_g_states: HashMap::new(),
+            pages: vec![],
+            next_name: 0,
+        }
+    }
+
+    ///
+    /// Adds an object to the document, returning its ID
+    ///
+    pub fn add_object(&mut self, body: impl Into<Vec<u8>>) -> usize {
+        self.objects.push(Some(Arc::new(body.into())));
+        self.objects.len()
+    }
+
+    ///
+    /// Generates the body of a stream object with the specified dictionary entries (the length and the filter are added
+    /// to the dictionary)
+    ///
+    pub fn stream_object(&self, dictionary: &str, data: &[u8]) -> Vec<u8> {
+        let compressed;
+        let (data, filter) = if self.compress {
+            let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+            encoder.write_all(data).ok();
+            compressed = encoder.finish().unwrap_or_default();
+
+            (&compressed[..], " /Filter /FlateDecode")
+        } else {
+            (data, "")
+        };
+
+        let dictionary = if dictionary.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", dictionary)
+        };
+
+        let mut object = format!(
+            "<<{}{} /Length {} >>\nstream\n",
+            dictionary,
+            filter,
+            data.len()
+        )
+        .into_bytes();
+        object.extend_from_slice(data);
+        object.extend_from_slice(b"\nendstream");
+
+        object
+    }
+
+    ///
+    /// Adds a stream object to the document, returning its ID
+    ///
+    pub fn add_stream(&mut self, dictionary: &str, data: &[u8]) -> usize {
+        let object = self.stream_object(dictionary, data);
+        self.add_object(object)
+    }
+
+    ///
+    /// Adds an object to the resource dictionary, returning the name it can be referenced by
+    ///
+    pub fn add_resource(
+        &mut self,
+        resource_type: PdfResourceType,
+        prefix: &str,
+        object_id: usize,
+    ) -> String {
+        let name = format!("{}{}", prefix, self.next_name);
+        self.next_name += 1;
+
+        self.insert_resource(resource_type, name.clone(), object_id);
+        name
+    }
+
+    ///
+    /// Adds an object to the resource dictionary with a specific name
+    ///
+    pub fn insert_resource(
+        &mut self,
+        resource_type: PdfResourceType,
+        name: String,
+        object_id: usize,
+    ) {
+        self.resources
+            .entry(resource_type)
+            .or_default()
+            .insert(name, object_id);
+    }
+
+    ///
+    /// Returns the name of a graphics state that sets the transparency and blend mode, or None if the defaults can be used
+    ///
+    pub fn ext_g_state(
+        &mut self,
+        fill_alpha: f32,
+        stroke_alpha: f32,
+        blend_mode: &'static str,
+    ) -> Option<String> {
+        let fill_alpha = fill_alpha.clamp(0.0, 1.0);
+        let stroke_alpha = stroke_alpha.clamp(0.0, 1.0);
+
+        if fill_alpha >= 1.0 && stroke_alpha >= 1.0 && blend_mode == "Normal" {
+            return None;
+        }
+
+        let key = (fill_alpha.to_bits(), stroke_alpha.to_bits(), blend_mode);
+        if let Some(name) = self.ext_g_states.get(&key) {
+            return Some(name.clone());
+        }
+
+        let object_id = self.add_object(format!(
+            "<< /Type /ExtGState /ca {} /CA {} /BM /{} >>",
+            pdf_number(fill_alpha),
+            pdf_number(stroke_alpha),
+            blend_mode
+        ));
+        let name = self.add_resource(PdfResourceType::ExtGState, "GS", object_id);
+        self.ext_g_states.insert(key, name.clone());
+
+        Some(name)
+    }
+
+    ///
+    /// Adds a page with the specified content to the end of the document
+    ///
+    pub fn add_page(&mut self, content: &str) {
+        let content_id = self.add_stream("", content.as_bytes());
+        self.pages.push(content_id);
+    }
+
+    ///
+    /// The number of pages that have been added to this document
+    ///
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    ///
+    /// Generates the PDF file for this document, using the specified page size (in points)
+    ///
+    pub fn to_bytes(&self, (width, height): (f32, f32)) -> Vec<u8> {
+        let mut objects = self.objects.clone();
+
+        // Resource dictionary
+        let mut resources = String::from("<<");
+        for (resource_type, names) in self.resources.iter() {
+            write!(resources, " /{:?} <<", resource_type).ok();
+            for (name, object_id) in names.iter() {
+                write!(resources, " /{} {} 0 R", name, object_id).ok();
+            }
+            resources.push_str(" >>");
+        }
+        resources.push_str(" >>");
+        objects[RESOURCES_OBJECT - 1] = Some(Arc::new(resources.into_bytes()));
+
+        // Page objects
+        let mut kids = vec![];
+        for content_id in self.pages.iter() {
+            objects.push(Some(Arc::new(
+                format!(
+                    "<< /Type /Page /Parent {} 0 R /Resources {} 0 R /Contents {} 0 R >>",
+                    PAGES_OBJECT, RESOURCES_OBJECT, content_id
+                )
+                .into_bytes(),
+            )));
+            kids.push(format!("{} 0 R", objects.len()));
+        }
+
+        objects[PAGES_OBJECT - 1] = Some(Arc::new(
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} /MediaBox [0 0 {} {}] >>",
+                kids.join(" "),
+                kids.len(),
+                pdf_number(width),
+                pdf_number(height)
+            )
+            .into_bytes(),
+        ));
+        objects[CATALOG_OBJECT - 1] = Some(Arc::new(
+            format!("<< /Type /Catalog /Pages {} 0 R >>", PAGES_OBJECT).into_bytes(),
+        ));
+
+        // Write the file (the comment after the header marks this as a binary file)
+        let mut pdf = b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = vec![];
+
+        for (idx, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+
+            writeln!(pdf, "{} 0 obj", idx + 1).ok();
+            if let Some(object) = object {
+                pdf.extend_from_slice(object);
+            } else {
+                // Reserved objects that were never set are written as null
+                pdf.extend_from_slice(b"null");
+            }
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = pdf.len();
+        write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).ok();
+        for offset in offsets {
+            writeln!(pdf, "{:010} 00000 n ", offset).ok();
+        }
+
+        write!(
+            pdf,
+            "trailer\n<< /Size {} /Root {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            CATALOG_OBJECT,
+            xref_offset
+        )
+        .ok();
+
+        pdf
+    }
+}
@@ -0,0 +1,428 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::pdf_document::*;
+use super::pdf_format::*;
+
+use crate::font_face::*;
+
+use allsorts::subset::subset;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::iter;
+use std::sync::*;
+
+///
+/// A font that's embedded in a PDF document
+///
+/// Glyphs are drawn using their glyph ID in the original font as the character code, so the content streams can be
+/// written before the subset of the font is generated. The font is embedded as a subset, along with a map from these
+/// character codes to the glyphs in the subset.
+///
+#[derive(Clone)]
+pub struct PdfFont {
+    /// The font face that this font is generated from
+    pub face: Arc<CanvasFontFace>,
+
+    /// The name used for this font in the resource dictionary
+    pub name: String,
+
+    /// The glyphs that have been drawn using this font
+    pub glyphs: BTreeSet<u16>,
+}
+
+///
+/// Generates the 6 letter tag that's added to the name of a subset font
+///
+fn subset_tag(index: usize) -> String {
+    let mut index = index;
+
+    (0..6)
+        .map(|_| {
+            let letter = (b'A' + (index % 26) as u8) as char;
+            index /= 26;
+            letter
+        })
+        .collect()
+}
+
+///
+/// Generates a CMap that maps character codes to CIDs
+///
+fn cid_cmap(name: &str, cids: &BTreeMap<u16, u16>) -> String {
+    let mut cmap = String::new();
+
+    cmap.push_str("/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n");
+    cmap.push_str(
+        "/CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> def\n",
+    );
+    writeln!(cmap, "/CMapName /{} def\n/CMapType 1 def", name).ok();
+    cmap.push_str("1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n");
+
+    // A cidchar section can contain at most 100 mappings
+    let cids = cids.iter().collect::<Vec<_>>();
+    for section in cids.chunks(100) {
+        writeln!(cmap, "{} begincidchar", section.len()).ok();
+
+        for (code, cid) in section.iter() {
+            writeln!(cmap, "<{:04X}> {}", code, cid).ok();
+        }
+
+        cmap.push_str("endcidchar\n");
+    }
+
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+
+    cmap
+}
+
+///
+/// Generates a ToUnicode CMap that maps character IDs to the Unicode characters they represent
+///
+fn to_unicode_cmap(characters: &BTreeMap<u16, char>) -> String {
+    let mut cmap = String::new();
+
+    cmap.push_str("/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n");
+    cmap.push_str("/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n");
+    cmap.push_str("/CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n");
+    cmap.push_str("1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n");
+
+    // A bfchar section can contain at most 100 mappings
+    let characters = characters.iter().collect::<Vec<_>>();
+    for section in characters.chunks(100) {
+        writeln!(cmap, "{} beginbfchar", section.len()).ok();
+
+        for (glyph, chr) in section.iter() {
+            let mut utf16 = [0u16; 2];
+            let utf16 = chr
+                .encode_utf16(&mut utf16)
+                .iter()
+                .map(|unit| format!("{:04X}", unit))
+                .collect::<String>();
+
+            writeln!(cmap, "<{:04X}> <{}>", glyph, utf16).ok();
+        }
+
+        cmap.push_str("endbfchar\n");
+    }
+
+    cmap.push_str("endcmap\nCMapName currentdict /CMapResource defineresource pop\nend\nend\n");
+
+    cmap
+}
+
+impl PdfFont {
+    ///
+    /// Creates a new font that hasn't been used to draw any glyphs yet
+    ///
+    pub fn new(face: Arc<CanvasFontFace>, name: String) -> PdfFont {
+        PdfFont {
+            face,
+            name,
+            glyphs: BTreeSet::new(),
+        }
+    }
+
+    ///
+    /// Returns the PostScript name of this font, with any characters that can't be used in a PDF name removed
+    ///
+    fn postscript_name(&self) -> String {
+        let name = self
+            .face
+            .ttf_font()
+            .names()
+            .into_iter()
+            .filter(|name| {
+                name.name_id == ttf_parser::name_id::POST_SCRIPT_NAME && name.is_unicode()
+            })
+            .find_map(|name| name.to_string())
+            .unwrap_or_default();
+        let name = name
+            .chars()
+            .filter(|chr| chr.is_ascii_alphanumeric() || *chr == '-' || *chr == '_')
+            .collect::<String>();
+
+        if name.is_empty() {
+            "FloFont".to_string()
+        } else {
+            name
+        }
+    }
+
+    ///
+    /// Finds the Unicode characters for the glyphs that have been used from the character map of the font
+    ///
+    /// Glyphs that aren't in the character map (such as ligatures) have no character. If several characters map to the
+    /// same glyph, the one with the lowest code point is used.
+    ///
+    fn glyph_characters(&self) -> BTreeMap<u16, char> {
+        let mut characters = BTreeMap::new();
+        let cmap = if let Some(cmap) = self.face.ttf_font().tables().cmap {
+            cmap
+        } else {
+            return characters;
+        };
+
+        for subtable in cmap
+            .subtables
+            .into_iter()
+            .filter(|subtable| subtable.is_unicode())
+        {
+            subtable.codepoints(|codepoint| {
+                let glyph = subtable.glyph_index(codepoint).map(|glyph| glyph.0);
+
+                if let (Some(glyph), Some(chr)) = (glyph, char::from_u32(codepoint)) {
+                    if self.glyphs.contains(&glyph) {
+                        let existing = characters.entry(glyph).or_insert(chr);
+                        *existing = (*existing).min(chr);
+                    }
+                }
+            });
+        }
+
+        characters
+    }
+
+    ///
+    /// Adds the objects for this font to a document, returning the ID of the font dictionary
+    ///
+    /// The font is embedded as a subset containing only the glyphs that have been used, or in its entirety if it can't be
+    /// subset. Every font has a ToUnicode CMap so that the text in the document can be searched and copied.
+    ///
+    pub fn embed(&self, document: &mut PdfDocument, index: usize) -> usize {
+        let ttf = self.face.ttf_font();
+        let scale = 1000.0 / (ttf.units_per_em().max(1) as f32);
+        let cff = ttf.tables().cff;
+        let is_cff = cff.is_some();
+
+        // Glyph 0 (the .notdef glyph) must be in every subset
+        let num_glyphs = ttf.number_of_glyphs();
+        let glyph_ids = iter::once(0)
+            .chain(
+                self.glyphs
+                    .iter()
+                    .copied()
+                    .filter(|glyph| *glyph != 0 && *glyph < num_glyphs),
+            )
+            .collect::<Vec<_>>();
+
+        let subset_data = subset(&CanvasTableProvider(ttf), &glyph_ids).ok();
+
+        // The character codes are the glyph IDs in the original font, so a subset needs a map to its new glyphs. TrueType
+        // glyphs are found using a CIDToGIDMap, but CFF glyphs are found by CID, so the character codes are mapped to new
+        // CIDs instead
+        let mut cids = glyph_ids.clone();
+        let mut encoding = "/Identity-H".to_string();
+        let mut cid_to_gid_map = "/Identity".to_string();
+
+        let (font_data, base_font) = if let Some(subset_data) = subset_data {
+            let base_font = format!("{}+{}", subset_tag(index), self.postscript_name());
+
+            if let Some(cff) = cff {
+                // The subset keeps the CIDs of a CID-keyed font, otherwise the CID is the glyph ID in the subset
+                cids = glyph_ids
+                    .iter()
+                    .enumerate()
+                    .map(|(new_glyph, old_glyph)| {
+                        cff.glyph_cid(ttf_parser::GlyphId(*old_glyph))
+                            .unwrap_or(new_glyph as u16)
+                    })
+                    .collect();
+
+                let cmap_name = format!("{}-H", base_font);
+                let code_cids = glyph_ids
+                    .iter()
+                    .copied()
+                    .zip(cids.iter().copied())
+                    .collect::<BTreeMap<_, _>>();
+                let cmap_id = document.add_stream(
+                    &format!("/Type /CMap /CMapName /{} /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >>", cmap_name),
+                    cid_cmap(&cmap_name, &code_cids).as_bytes(),
+                );
+
+                encoding = format!("{} 0 R", cmap_id);
+            } else {
+                let max_glyph = glyph_ids.iter().copied().max().unwrap_or(0) as usize;
+                let mut cid_to_gid = vec![0u8; (max_glyph + 1) * 2];
+
+                for (new_glyph, old_glyph) in glyph_ids.iter().enumerate() {
+                    let old_glyph = *old_glyph as usize;
+                    cid_to_gid[old_glyph * 2..old_glyph * 2 + 2]
+                        .copy_from_slice(&(new_glyph as u16).to_be_bytes());
+                }
+
+                let map_id = document.add_stream("", &cid_to_gid);
+                cid_to_gid_map = format!("{} 0 R", map_id);
+            }
+
+            (subset_data, base_font)
+        } else {
+            (self.face.font_data().to_vec(), self.postscript_name())
+        };
+
+        // The font program
+        let font_file = if is_cff {
+            let file_id = document.add_stream("/Subtype /OpenType", &font_data);
+            format!("/FontFile3 {} 0 R", file_id)
+        } else {
+            let file_id = document.add_stream(&format!("/Length1 {}", font_data.len()), &font_data);
+            format!("/FontFile2 {} 0 R", file_id)
+        };
+
+        // The font descriptor
+        let bounds = ttf.global_bounding_box();
+        let flags =
+            4 + if ttf.is_monospaced() { 1 } else { 0 } + if ttf.is_italic() { 64 } else { 0 };
+        let descriptor_id = document.add_object(format!(
+            "<< /Type /FontDescriptor /FontName /{} /Flags {} /FontBBox [{} {} {} {}] /ItalicAngle {} /Ascent {} /Descent {} /CapHeight {} /StemV 80 {} >>",
+            base_font,
+            flags,
+            pdf_number(bounds.x_min as f32 * scale),
+            pdf_number(bounds.y_min as f32 * scale),
+            pdf_number(bounds.x_max as f32 * scale),
+            pdf_number(bounds.y_max as f32 * scale),
+            pdf_number(ttf.italic_angle().unwrap_or(0.0)),
+            pdf_number(ttf.ascender() as f32 * scale),
+            pdf_number(ttf.descender() as f32 * scale),
+            pdf_number(ttf.capital_height().unwrap_or_else(|| ttf.ascender()) as f32 * scale),
+            font_file
+        ));
+
+        // The widths of the glyphs that were used
+        let widths = glyph_ids
+            .iter()
+            .zip(cids.iter())
+            .map(|(glyph, cid)| {
+                let advance = ttf
+                    .glyph_hor_advance(ttf_parser::GlyphId(*glyph))
+                    .unwrap_or(0);
+                format!("{} [{}]", cid, pdf_number(advance as f32 * scale))
+            })
+            .collect::<Vec<_>>();
+
+        // The descendant font contains the glyphs, and the Type0 font maps 2-byte character codes to them
+        let cid_font_id = if is_cff {
+            document.add_object(format!(
+                "<< /Type /Font /Subtype /CIDFontType0 /BaseFont /{} /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> /FontDescriptor {} 0 R /W [{}] >>",
+                base_font, descriptor_id, widths.join(" ")
+            ))
+        } else {
+            document.add_object(format!(
+                "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /{} /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> /FontDescriptor {} 0 R /W [{}] /CIDToGIDMap {} >>",
+                base_font, descriptor_id, widths.join(" "), cid_to_gid_map
+            ))
+        };
+
+        // Map the character IDs back to the text they were generated from
+        let to_unicode_id =
+            document.add_stream("", to_unicode_cmap(&self.glyph_characters()).as_bytes());
+
+        document.add_object(format!(
+            "<< /Type /Font /Subtype /Type0 /BaseFont /{} /Encoding {} /DescendantFonts [{} 0 R] /ToUnicode {} 0 R >>",
+            base_font, encoding, cid_font_id, to_unicode_id
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn subset_tags_are_unique() {
+        assert!(subset_tag(0) == "AAAAAA");
+        assert!(subset_tag(1) == "BAAAAA");
+        assert!(subset_tag(27) == "BBAAAA");
+    }
+
+    #[test]
+    fn to_unicode_maps_glyphs_to_characters() {
+        let characters = vec![(3, 'A'), (36, '\u{e9}'), (512, '\u{1f600}')]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        let cmap = to_unicode_cmap(&characters);
+
+        assert!(cmap
+            .contains("3 beginbfchar\n<0003> <0041>\n<0024> <00E9>\n<0200> <D83DDE00>\nendbfchar"));
+    }
+
+    #[test]
+    fn to_unicode_splits_long_maps() {
+        let characters = (0..150u16)
+            .map(|glyph| (glyph, 'a'))
+            .collect::<BTreeMap<_, _>>();
+        let cmap = to_unicode_cmap(&characters);
+
+        assert!(cmap.contains("100 beginbfchar"));
+        assert!(cmap.contains("50 beginbfchar"));
+    }
+
+    #[test]
+    fn glyph_characters_from_font() {
+        let face = CanvasFontFace::from_slice(include_bytes!("../../test_data/Lato-Regular.ttf"));
+        let glyph_a = face.ttf_font().glyph_index('A').unwrap().0;
+
+        let mut font = PdfFont::new(face, "F0".to_string());
+        font.glyphs.insert(glyph_a);
+
+        assert!(font.glyph_characters().get(&glyph_a) == Some(&'A'));
+    }
+
+    #[test]
+    fn cff_font_is_subset() {
+        let source = include_bytes!("../../test_data/FloTestCff.otf");
+        let face = CanvasFontFace::from_slice(source);
+        let glyph_a = face.ttf_font().glyph_index('A').unwrap().0;
+        let glyph_z = face.ttf_font().glyph_index('z').unwrap().0;
+
+        let mut font = PdfFont::new(face, "F0".to_string());
+        font.glyphs.insert(glyph_a);
+        font.glyphs.insert(glyph_z);
+
+        let mut document = PdfDocument::new(false);
+        font.embed(&mut document, 0);
+        let pdf_bytes = document.to_bytes((100.0, 100.0));
+        let pdf = String::from_utf8_lossy(&pdf_bytes);
+
+        // Find the embedded font program
+        let marker = b"/Subtype /OpenType /Length ";
+        let start = pdf_bytes
+            .windows(marker.len())
+            .position(|window| window == marker)
+            .unwrap()
+            + marker.len();
+        let length_end = start + pdf_bytes[start..].iter().position(|c| *c == b' ').unwrap();
+        let length = str::from_utf8(&pdf_bytes[start..length_end])
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+        let data_start = length_end + b" >>\nstream\n".len();
+        let font_data = &pdf_bytes[data_start..data_start + length];
+
+        // The subset should contain just the .notdef glyph and the two glyphs that were used
+        assert!(length < source.len() / 2, "{} >= {}", length, source.len());
+        assert!(
+            ttf_parser::Face::parse(font_data, 0)
+                .unwrap()
+                .number_of_glyphs()
+                == 3
+        );
+
+        // The character codes are mapped to the CIDs of the glyphs in the subset
+        assert!(pdf.contains("/BaseFont /AAAAAA+FloTestCff"), "{}", pdf);
+        assert!(!pdf.contains("/Identity-H"), "{}", pdf);
+        assert!(pdf.contains("/W [0 [600] 1 [600] 2 [600]]"), "{}", pdf);
+        assert!(
+            pdf.contains(&format!(
+                "3 begincidchar\n<0000> 0\n<{:04X}> 1\n<{:04X}> 2\n",
+                glyph_a, glyph_z
+            )),
+            "{}",
+            pdf
+        );
+    }
+}
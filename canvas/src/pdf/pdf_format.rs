@@ -0,0 +1,224 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::draw::*;
+use crate::path::*;
+use crate::transform2d::*;
+
+use std::fmt::Write;
+
+///
+/// Formats a number for use in a PDF file
+///
+/// PDF files can't use exponents, so numbers are written with a fixed number of decimal places (with any trailing zeros
+/// removed)
+///
+pub fn pdf_number(value: f32) -> String {
+    if !value.is_finite() {
+        return "0".to_string();
+    }
+
+    let mut number = format!("{:.4}", value);
+
+    if number.contains('.') {
+        let trimmed_len = number.trim_end_matches('0').trim_end_matches('.').len();
+        number.truncate(trimmed_len);
+    }
+
+    if number == "-0" {
+        "0".to_string()
+    } else {
+        number
+    }
+}
+
+///
+/// Formats a transformation as the 6 numbers used by the `cm` operator and the `/Matrix` entry of patterns and forms
+///
+pub fn pdf_matrix(transform: &Transform2D) -> String {
+    let Transform2D(m) = transform;
+
+    format!(
+        "{} {} {} {} {} {}",
+        pdf_number(m[0][0]),
+        pdf_number(m[1][0]),
+        pdf_number(m[0][1]),
+        pdf_number(m[1][1]),
+        pdf_number(m[0][2]),
+        pdf_number(m[1][2])
+    )
+}
+
+///
+/// Formats the RGB components of a colour (clamped to the range 0-1)
+///
+pub fn pdf_rgb((r, g, b): (f32, f32, f32)) -> String {
+    format!(
+        "{} {} {}",
+        pdf_number(r.clamp(0.0, 1.0)),
+        pdf_number(g.clamp(0.0, 1.0)),
+        pdf_number(b.clamp(0.0, 1.0))
+    )
+}
+
+///
+/// Converts a list of path operations to the path construction operators of a content stream, transforming each point
+///
+pub fn pdf_path(path: &[PathOp], transform: &Transform2D) -> String {
+    let mut ops = String::new();
+    let point = |x: f32, y: f32| {
+        let (x, y) = transform.transform_point(x, y);
+        format!("{} {}", pdf_number(x), pdf_number(y))
+    };
+
+    for path_op in path.iter() {
+        match path_op {
+            PathOp::NewPath => {}
+            PathOp::Move(x, y) => {
+                writeln!(ops, "{} m", point(*x, *y)).ok();
+            }
+            PathOp::Line(x, y) => {
+                writeln!(ops, "{} l", point(*x, *y)).ok();
+            }
+            PathOp::BezierCurve(((cp1x, cp1y), (cp2x, cp2y)), (x, y)) => {
+                writeln!(
+                    ops,
+                    "{} {} {} c",
+                    point(*cp1x, *cp1y),
+                    point(*cp2x, *cp2y),
+                    point(*x, *y)
+                )
+                .ok();
+            }
+            PathOp::ClosePath => {
+                ops.push_str("h\n");
+            }
+        }
+    }
+
+    ops
+}
+
+///
+/// Returns the operator used to fill a path with a particular winding rule
+///
+pub fn pdf_fill_operator(winding_rule: WindingRule) -> &'static str {
+    match winding_rule {
+        WindingRule::NonZero => "f",
+        WindingRule::EvenOdd => "f*",
+    }
+}
+
+///
+/// Returns the operator used to clip to a path with a particular winding rule
+///
+pub fn pdf_clip_operator(winding_rule: WindingRule) -> &'static str {
+    match winding_rule {
+        WindingRule::NonZero => "W",
+        WindingRule::EvenOdd => "W*",
+    }
+}
+
+///
+/// Returns the value of the `j` operator for a line join
+///
+pub fn pdf_line_join(join: LineJoin) -> u8 {
+    match join {
+        LineJoin::Miter => 0,
+        LineJoin::Round => 1,
+        LineJoin::Bevel => 2,
+    }
+}
+
+///
+/// Returns the value of the `J` operator for a line cap
+///
+pub fn pdf_line_cap(cap: LineCap) -> u8 {
+    match cap {
+        LineCap::Butt => 0,
+        LineCap::Round => 1,
+        LineCap::Square => 2,
+    }
+}
+
+///
+/// Returns the PDF name of a blend mode
+///
+/// PDF has no equivalent of the Porter-Duff modes other than 'source over', or of the 'plus' mode: these use the normal
+/// blend mode.
+///
+pub fn pdf_blend_mode(blend_mode: BlendMode) -> &'static str {
+    use self::BlendMode::*;
+
+    match blend_mode {
+        Multiply => "Multiply",
+        Screen => "Screen",
+        Darken => "Darken",
+        Lighten => "Lighten",
+        Overlay => "Overlay",
+        ColorDodge => "ColorDodge",
+        ColorBurn => "ColorBurn",
+        HardLight => "HardLight",
+        SoftLight => "SoftLight",
+        Difference => "Difference",
+        Exclusion => "Exclusion",
+        Hue => "Hue",
+        Saturation => "Saturation",
+        Color => "Color",
+        Luminosity => "Luminosity",
+
+        SourceOver | SourceIn | SourceOut | DestinationOver | DestinationIn | DestinationOut
+        | SourceAtop | DestinationAtop | Xor | Clear | Copy | Destination | Plus => "Normal",
+    }
+}
+
+///
+/// Returns the scale factor of a transform (how much it changes the size of a unit length)
+///
+pub fn pdf_transform_scale(transform: &Transform2D) -> f32 {
+    let Transform2D(m) = transform;
+
+    (m[0][0] * m[1][1] - m[0][1] * m[1][0]).abs().sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn format_numbers() {
+        assert!(pdf_number(1.0) == "1");
+        assert!(pdf_number(-2.5) == "-2.5");
+        assert!(pdf_number(0.00001) == "0");
+        assert!(pdf_number(-0.00001) == "0");
+        assert!(pdf_number(1.0e-7) == "0");
+        assert!(pdf_number(123456.0) == "123456");
+        assert!(pdf_number(1.23456) == "1.2346");
+        assert!(pdf_number(f32::NAN) == "0");
+    }
+
+    #[test]
+    fn format_matrix() {
+        let transform = Transform2D::translate(10.0, 20.0) * Transform2D::scale(2.0, 3.0);
+
+        assert!(pdf_matrix(&transform) == "2 0 0 3 10 20");
+    }
+
+    #[test]
+    fn format_path() {
+        let path = vec![
+            PathOp::NewPath,
+            PathOp::Move(0.0, 0.0),
+            PathOp::Line(10.0, 0.0),
+            PathOp::BezierCurve(((1.0, 2.0), (3.0, 4.0)), (5.0, 6.0)),
+            PathOp::ClosePath,
+        ];
+
+        assert!(
+            pdf_path(&path, &Transform2D::scale(2.0, 2.0)) == "0 0 m\n20 0 l\n2 4 6 8 10 12 c\nh\n"
+        );
+    }
+}
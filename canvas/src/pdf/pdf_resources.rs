@@ -0,0 +1,432 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::pdf_document::*;
+use super::pdf_format::*;
+
+use crate::gradient::*;
+use crate::sprite::*;
+use crate::texture::*;
+
+use std::cmp::Ordering;
+use std::sync::*;
+
+/// The largest number of times a repeating or reflected gradient will be repeated
+const MAX_GRADIENT_REPEATS: f32 = 256.0;
+
+/// A stop in a gradient: its position and its colour as RGBA components
+pub type PdfGradientStop = (f32, (f32, f32, f32, f32));
+
+///
+/// The content of a texture that's being written to a PDF document
+///
+#[derive(Clone, Debug)]
+pub enum PdfTextureContent {
+    /// A bitmap, as RGBA bytes
+    Bitmap(Arc<Vec<u8>>),
+
+    /// A sprite, rendered so that its bounds cover the texture (dynamic sprites are updated whenever the sprite changes)
+    Sprite {
+        sprite_id: SpriteId,
+        bounds: SpriteBounds,
+        dynamic: bool,
+        sprite_form: Option<String>,
+    },
+}
+
+///
+/// A texture that's being written to a PDF document
+///
+#[derive(Clone, Debug)]
+pub struct PdfTexture {
+    /// The width of the texture in pixels
+    pub width: u32,
+
+    /// The height of the texture in pixels
+    pub height: u32,
+
+    /// The content of this texture
+    pub content: PdfTextureContent,
+
+    /// The transparency to use when filling with this texture
+    pub alpha: f32,
+
+    /// The name of the XObject for the current content of this texture, if it has been written
+    pub x_object: Option<String>,
+}
+
+impl PdfTexture {
+    ///
+    /// Creates a new transparent bitmap texture
+    ///
+    pub fn new(TextureSize(width, height): TextureSize) -> PdfTexture {
+        PdfTexture {
+            width,
+            height,
+            content: PdfTextureContent::Bitmap(Arc::new(vec![
+                0;
+                (width as usize)
+                    * (height as usize)
+                    * 4
+            ])),
+            alpha: 1.0,
+            x_object: None,
+        }
+    }
+
+    ///
+    /// Writes bytes to a region of this texture
+    ///
+    pub fn set_bytes(
+        &mut self,
+        TexturePosition(x, y): TexturePosition,
+        TextureSize(width, height): TextureSize,
+        bytes: &[u8],
+    ) {
+        // Writing bytes to a sprite texture replaces it with a bitmap
+        if let PdfTextureContent::Sprite { .. } = &self.content {
+            self.content = PdfTexture::new(TextureSize(self.width, self.height)).content;
+        }
+
+        if let PdfTextureContent::Bitmap(pixels) = &mut self.content {
+            let pixels = Arc::make_mut(pixels);
+            let texture_width = self.width as usize;
+
+            for row in 0..(height as usize) {
+                let target_y = (y as usize) + row;
+                if target_y >= self.height as usize {
+                    break;
+                }
+
+                // Clip the row to the width of the texture
+                let row_width = (width as usize).min(texture_width.saturating_sub(x as usize));
+                let source_start = row * (width as usize) * 4;
+                let target_start = (target_y * texture_width + (x as usize)) * 4;
+
+                if source_start + row_width * 4 > bytes.len() {
+                    break;
+                }
+
+                pixels[target_start..(target_start + row_width * 4)]
+                    .copy_from_slice(&bytes[source_start..(source_start + row_width * 4)]);
+            }
+        }
+
+        self.x_object = None;
+    }
+}
+
+///
+/// Adds an image XObject for a RGBA bitmap to a document, returning its object ID
+///
+/// The alpha channel is written as a soft mask, which is left out if every pixel is opaque.
+///
+pub fn pdf_add_image(document: &mut PdfDocument, width: u32, height: u32, pixels: &[u8]) -> usize {
+    let rgb = pixels
+        .chunks_exact(4)
+        .flat_map(|pixel| pixel[0..3].iter().copied())
+        .collect::<Vec<_>>();
+    let alpha = pixels
+        .chunks_exact(4)
+        .map(|pixel| pixel[3])
+        .collect::<Vec<_>>();
+
+    let image_dictionary = format!(
+        "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8",
+        width, height
+    );
+
+    if alpha.iter().all(|alpha| *alpha == 255) {
+        document.add_stream(&image_dictionary, &rgb)
+    } else {
+        let mask_id = document.add_stream(
+            &format!(
+                "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceGray /BitsPerComponent 8",
+                width, height
+            ),
+            &alpha,
+        );
+
+        document.add_stream(
+            &format!("{} /SMask {} 0 R", image_dictionary, mask_id),
+            &rgb,
+        )
+    }
+}
+
+///
+/// Returns the stops in a gradient, ordered by position, as RGBA components
+///
+pub fn pdf_gradient_stops(gradient: &[GradientOp]) -> Vec<PdfGradientStop> {
+    let mut stops = vec![];
+
+    for op in gradient.iter() {
+        match op {
+            GradientOp::Create(color) => {
+                stops = vec![(0.0, color.to_rgba_components())];
+            }
+            GradientOp::AddStop(pos, color) => {
+                stops.push((*pos, color.to_rgba_components()));
+            }
+            GradientOp::SpreadMode(_) => {}
+        }
+    }
+
+    stops.sort_by(|(pos_a, _), (pos_b, _)| pos_a.partial_cmp(pos_b).unwrap_or(Ordering::Equal));
+    stops
+}
+
+///
+/// Returns the alpha value to use for a gradient
+///
+/// PDF shadings have no transparency, so the gradient uses the transparency of its first stop
+///
+pub fn pdf_gradient_alpha(gradient: &[GradientOp]) -> f32 {
+    pdf_gradient_stops(gradient)
+        .first()
+        .map(|(_, (_, _, _, a))| *a)
+        .unwrap_or(1.0)
+}
+
+///
+/// Generates a function that interpolates between the colours of the stops of a gradient over the domain 0-1
+///
+fn pdf_stop_function(stops: &[PdfGradientStop]) -> String {
+    let rgb = |(r, g, b, _): (f32, f32, f32, f32)| pdf_rgb((r, g, b));
+
+    // Stops are clamped to the domain of the function, which needs to be fully covered
+    let mut stops = stops
+        .iter()
+        .map(|(pos, color)| (pos.clamp(0.0, 1.0), *color))
+        .collect::<Vec<_>>();
+
+    if stops.is_empty() {
+        stops.push((0.0, (0.0, 0.0, 0.0, 0.0)));
+    }
+    if stops[0].0 > 0.0 {
+        stops.insert(0, (0.0, stops[0].1));
+    }
+    if stops[stops.len() - 1].0 < 1.0 {
+        stops.push((1.0, stops[stops.len() - 1].1));
+    }
+    if stops.len() == 1 {
+        stops.push((1.0, stops[0].1));
+    }
+
+    let segments = stops
+        .windows(2)
+        .map(|stop| {
+            format!(
+                "<< /FunctionType 2 /Domain [0 1] /C0 [{}] /C1 [{}] /N 1 >>",
+                rgb(stop[0].1),
+                rgb(stop[1].1)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if segments.len() == 1 {
+        segments[0].clone()
+    } else {
+        let bounds = stops[1..(stops.len() - 1)]
+            .iter()
+            .map(|(pos, _)| pdf_number(*pos))
+            .collect::<Vec<_>>();
+        let encode = segments.iter().map(|_| "0 1").collect::<Vec<_>>();
+
+        format!(
+            "<< /FunctionType 3 /Domain [0 1] /Functions [{}] /Bounds [{}] /Encode [{}] >>",
+            segments.join(" "),
+            bounds.join(" "),
+            encode.join(" ")
+        )
+    }
+}
+
+///
+/// Generates the function for a gradient over the domain `first_repeat` to `last_repeat`
+///
+/// Each whole number in the domain starts a new repeat of the gradient, which is reversed for odd repeats if the gradient
+/// is reflected. A gradient with the 'pad' spread mode always uses the domain 0-1, and relies on the shading to extend it.
+///
+pub fn pdf_gradient_function(
+    gradient: &[GradientOp],
+    first_repeat: i32,
+    last_repeat: i32,
+) -> String {
+    let function = pdf_stop_function(&pdf_gradient_stops(gradient));
+    let spread = gradient_spread(gradient);
+
+    if spread == GradientSpread::Pad || (first_repeat, last_repeat) == (0, 1) {
+        return function;
+    }
+
+    let repeats = first_repeat..last_repeat;
+    let num_repeats = (last_repeat - first_repeat).max(1) as usize;
+    let bounds = ((first_repeat + 1)..last_repeat)
+        .map(|repeat| repeat.to_string())
+        .collect::<Vec<_>>();
+    let encode = repeats
+        .map(|repeat| {
+            if spread == GradientSpread::Reflect && repeat.rem_euclid(2) == 1 {
+                "1 0"
+            } else {
+                "0 1"
+            }
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        "<< /FunctionType 3 /Domain [{} {}] /Functions [{}] /Bounds [{}] /Encode [{}] >>",
+        first_repeat,
+        last_repeat,
+        vec![function; num_repeats].join(" "),
+        bounds.join(" "),
+        encode.join(" ")
+    )
+}
+
+///
+/// Returns the range of repeats needed for a linear gradient to cover a set of points, given in gradient coordinates
+///
+pub fn pdf_gradient_repeats(
+    gradient: &[GradientOp],
+    (x1, y1): (f32, f32),
+    (x2, y2): (f32, f32),
+    points: impl IntoIterator<Item = (f32, f32)>,
+) -> (i32, i32) {
+    if gradient_spread(gradient) == GradientSpread::Pad {
+        return (0, 1);
+    }
+
+    let (dx, dy) = (x2 - x1, y2 - y1);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared <= 0.0 {
+        return (0, 1);
+    }
+
+    // Project each point onto the line of the gradient
+    let (min_pos, max_pos) = points
+        .into_iter()
+        .map(|(x, y)| ((x - x1) * dx + (y - y1) * dy) / length_squared)
+        .filter(|pos| pos.is_finite())
+        .fold((0.0f32, 1.0f32), |(min, max), pos| {
+            (min.min(pos), max.max(pos))
+        });
+
+    let first = min_pos.floor().max(-MAX_GRADIENT_REPEATS) as i32;
+    let last = max_pos.ceil().min(MAX_GRADIENT_REPEATS) as i32;
+
+    (first, last.max(first + 1))
+}
+
+///
+/// Returns the colour at a particular position in a gradient (positions outside of the stops use the colour of the nearest stop)
+///
+pub fn pdf_gradient_color(gradient: &[GradientOp], pos: f32) -> (f32, f32, f32, f32) {
+    let stops = pdf_gradient_stops(gradient);
+
+    if stops.is_empty() {
+        return (0.0, 0.0, 0.0, 0.0);
+    }
+
+    if pos <= stops[0].0 {
+        return stops[0].1;
+    }
+
+    for idx in 1..stops.len() {
+        let (start_pos, start_color) = stops[idx - 1];
+        let (end_pos, end_color) = stops[idx];
+
+        if pos <= end_pos {
+            let ratio = if end_pos > start_pos {
+                (pos - start_pos) / (end_pos - start_pos)
+            } else {
+                1.0
+            };
+            let mix = |a: f32, b: f32| a + (b - a) * ratio;
+
+            return (
+                mix(start_color.0, end_color.0),
+                mix(start_color.1, end_color.1),
+                mix(start_color.2, end_color.2),
+                mix(start_color.3, end_color.3),
+            );
+        }
+    }
+
+    stops[stops.len() - 1].1
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::color::*;
+
+    fn red_to_blue(spread: GradientSpread) -> Vec<GradientOp> {
+        vec![
+            GradientOp::Create(Color::Rgba(1.0, 0.0, 0.0, 1.0)),
+            GradientOp::AddStop(1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0)),
+            GradientOp::SpreadMode(spread),
+        ]
+    }
+
+    #[test]
+    fn two_stop_function() {
+        let function = pdf_gradient_function(&red_to_blue(GradientSpread::Pad), 0, 1);
+
+        assert!(
+            function == "<< /FunctionType 2 /Domain [0 1] /C0 [1 0 0] /C1 [0 0 1] /N 1 >>",
+            "{}",
+            function
+        );
+    }
+
+    #[test]
+    fn three_stop_function() {
+        let mut gradient = red_to_blue(GradientSpread::Pad);
+        gradient.push(GradientOp::AddStop(0.25, Color::Rgba(0.0, 1.0, 0.0, 1.0)));
+
+        let function = pdf_gradient_function(&gradient, 0, 1);
+
+        assert!(function.starts_with("<< /FunctionType 3 /Domain [0 1] /Functions [<< /FunctionType 2 /Domain [0 1] /C0 [1 0 0] /C1 [0 1 0] /N 1 >> << /FunctionType 2 /Domain [0 1] /C0 [0 1 0] /C1 [0 0 1] /N 1 >>] /Bounds [0.25] /Encode [0 1 0 1] >>"), "{}", function);
+    }
+
+    #[test]
+    fn reflected_function() {
+        let function = pdf_gradient_function(&red_to_blue(GradientSpread::Reflect), -1, 2);
+
+        assert!(
+            function.starts_with("<< /FunctionType 3 /Domain [-1 2] /Functions ["),
+            "{}",
+            function
+        );
+        assert!(
+            function.ends_with("/Bounds [0 1] /Encode [1 0 0 1 1 0] >>"),
+            "{}",
+            function
+        );
+    }
+
+    #[test]
+    fn repeats_cover_points() {
+        let repeats = pdf_gradient_repeats(
+            &red_to_blue(GradientSpread::Repeat),
+            (0.0, 0.0),
+            (10.0, 0.0),
+            vec![(-15.0, 3.0), (25.0, -2.0)],
+        );
+
+        assert!(repeats == (-2, 3), "{:?}", repeats);
+
+        let padded = pdf_gradient_repeats(
+            &red_to_blue(GradientSpread::Pad),
+            (0.0, 0.0),
+            (10.0, 0.0),
+            vec![(-15.0, 3.0), (25.0, -2.0)],
+        );
+        assert!(padded == (0, 1));
+    }
+}
@@ -0,0 +1,1957 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::pdf_document::*;
+use super::pdf_font::*;
+use super::pdf_format::*;
+use super::pdf_resources::*;
+
+use crate::color::*;
+use crate::draw::*;
+use crate::font::*;
+use crate::font_face::*;
+use crate::font_line_layout::*;
+use crate::gradient::*;
+use crate::namespace::*;
+use crate::path::*;
+use crate::sprite::*;
+use crate::texture::*;
+use crate::transform2d::*;
+
+use futures::prelude::*;
+
+use std::collections::{BTreeMap, HashMap};
+use std::f32;
+use std::fmt::Write;
+use std::iter;
+use std::sync::*;
+
+/// The number of wedges used to approximate a conic gradient (PDF has no native conic gradient)
+const CONIC_GRADIENT_WEDGES: usize = 180;
+
+/// The bounding box used for sprite forms, which can contain drawing anywhere on the page
+const SPRITE_FORM_BOUNDS: &str = "[-100000 -100000 100000 100000]";
+
+///
+/// How the current path will be filled
+///
+#[derive(Clone, Debug)]
+enum PdfFill {
+    /// Fill with a solid colour
+    Color(Color),
+
+    /// Fill with a texture (XObject name, width, height, alpha, lower-left and upper-right coordinates)
+    Texture(String, u32, u32, f32, (f32, f32), (f32, f32)),
+
+    /// Fill with a linear gradient (the gradient operations are copied from when the fill was set)
+    LinearGradient(Vec<GradientOp>, (f32, f32), (f32, f32)),
+
+    /// Fill with a radial gradient
+    RadialGradient(Vec<GradientOp>, (f32, f32, f32), (f32, f32, f32)),
+
+    /// Fill with a conic gradient
+    ConicGradient(Vec<GradientOp>, (f32, f32), f32),
+}
+
+///
+/// The part of the state of the writer that is saved by `PushState`
+///
+#[derive(Clone, Debug)]
+struct PdfState {
+    /// The current path
+    path: Vec<PathOp>,
+
+    /// Transform from canvas coordinates to viewport coordinates
+    transform: Transform2D,
+
+    /// The current fill
+    fill: PdfFill,
+
+    /// Transform applied to gradient and texture fills
+    fill_transform: Transform2D,
+
+    /// Colour for strokes
+    stroke_color: Color,
+
+    /// Width of the line in canvas units
+    line_width: f32,
+
+    /// Width of the line in points, if it was set using `LineWidthPixels`
+    line_width_pixels: Option<f32>,
+
+    line_join: LineJoin,
+    line_cap: LineCap,
+    dash_pattern: Vec<f32>,
+    dash_offset: f32,
+    winding_rule: WindingRule,
+    blend_mode: BlendMode,
+
+    /// The clip paths that are intersected to make the clip region, in page coordinates and followed by their clipping operator
+    clip: Vec<String>,
+
+    /// The transform applied by `DrawSprite`
+    sprite_transform: Transform2D,
+}
+
+///
+/// Where the drawing instructions are being sent
+///
+#[derive(Clone, Copy, Debug)]
+enum PdfTarget {
+    Layer(LayerId),
+    Sprite(NamespaceId, SpriteId),
+}
+
+///
+/// A layer in the PDF output
+///
+#[derive(Clone, Debug)]
+struct PdfLayer {
+    /// The content stream operations for each element in this layer
+    elements: Vec<String>,
+    blend_mode: BlendMode,
+    alpha: f32,
+
+    /// The number of elements that were in the layer when `Store` was called
+    stored: Option<usize>,
+}
+
+///
+/// A sprite in the PDF output
+///
+#[derive(Clone, Debug)]
+struct PdfSprite {
+    elements: Vec<String>,
+
+    /// Transform from page coordinates back to the canvas coordinates used when the sprite was selected
+    inverse_transform: Transform2D,
+
+    /// The name of the form XObject for the current version of this sprite, if it has been written
+    form: Option<String>,
+}
+
+///
+/// Text that's being laid out by `BeginLineLayout` and `LayoutText`
+///
+struct PdfTextLayout {
+    /// The layout so far
+    layout: CanvasFontLineLayout,
+
+    /// The font that is being used for the text that's currently being laid out
+    font_id: FontId,
+}
+
+///
+/// Converts canvas drawing instructions into a PDF document
+///
+/// The canvas coordinates are mapped to each page in the same way as they are mapped to a window: the viewport coordinates
+/// run from -1 to 1 vertically, with the x axis scaled so units are square. The page size is measured in points.
+///
+/// Each frame (a `ShowFrame` instruction that ends the outermost `StartFrame`) generates a new page containing the
+/// content of the canvas at that point, and the final page shows whatever has been drawn since the last frame.
+///
+/// Paths, fills, strokes, clipping, layers, transforms, sprites, textures and text are all supported. Fonts are
+/// embedded as subsets containing only the glyphs that were drawn, and every font has a map back to Unicode so the text
+/// can be searched. Gradients are written as shading patterns: PDF shadings are opaque, so a gradient uses the
+/// transparency of its first stop, and radial gradients are always padded. Conic gradients are approximated by a set of
+/// wedges. Blend modes are mapped to the PDF blend modes, which have no equivalent for the Porter-Duff modes other than
+/// 'source over': these are drawn using the normal blend mode. Texture filters are ignored, except for the alpha blend
+/// filter when drawing a sprite.
+///
+pub struct PdfWriter {
+    /// The size of each page in points
+    size: (f32, f32),
+
+    /// The colour set by the last `ClearCanvas` instruction
+    background: Color,
+
+    /// The namespace for resource IDs
+    namespace: NamespaceId,
+
+    state: PdfState,
+    state_stack: Vec<PdfState>,
+    target: PdfTarget,
+
+    layers: BTreeMap<u64, PdfLayer>,
+    sprites: HashMap<(NamespaceId, SpriteId), PdfSprite>,
+    gradients: HashMap<(NamespaceId, GradientId), Vec<GradientOp>>,
+    textures: HashMap<(NamespaceId, TextureId), PdfTexture>,
+
+    font_faces: HashMap<(NamespaceId, FontId), Arc<CanvasFontFace>>,
+    font_sizes: HashMap<(NamespaceId, FontId), f32>,
+
+    /// The text layout started by `BeginLineLayout`, along with its position and alignment
+    text_layout: Option<PdfTextLayout>,
+    text_position: (f32, f32, TextAlignment),
+
+    /// The fonts that have been used to draw glyphs (these are embedded when the document is generated)
+    fonts: Vec<PdfFont>,
+
+    /// The objects written so far, and the pages that have been completed
+    document: PdfDocument,
+
+    /// The number of `StartFrame` instructions that haven't been matched by a `ShowFrame` instruction
+    frame_depth: usize,
+
+    /// True if the canvas has changed since the last page was added to the document
+    changed_since_page: bool,
+}
+
+impl Default for PdfState {
+    fn default() -> PdfState {
+        PdfState {
+            path: vec![],
+            transform: Transform2D::identity(),
+            fill: PdfFill::Color(Color::Rgba(0.0, 0.0, 0.0, 1.0)),
+            fill_transform: Transform2D::identity(),
+            stroke_color: Color::Rgba(0.0, 0.0, 0.0, 1.0),
+            line_width: 1.0,
+            line_width_pixels: None,
+            line_join: LineJoin::Round,
+            line_cap: LineCap::Butt,
+            dash_pattern: vec![],
+            dash_offset: 0.0,
+            winding_rule: WindingRule::NonZero,
+            blend_mode: BlendMode::SourceOver,
+            clip: vec![],
+            sprite_transform: Transform2D::identity(),
+        }
+    }
+}
+
+impl PdfLayer {
+    fn new() -> PdfLayer {
+        PdfLayer {
+            elements: vec![],
+            blend_mode: BlendMode::SourceOver,
+            alpha: 1.0,
+            stored: None,
+        }
+    }
+}
+
+impl PdfWriter {
+    ///
+    /// Creates a new PDF writer that will generate a document with pages of the specified size in points
+    ///
+    pub fn new(width: f32, height: f32) -> PdfWriter {
+        PdfWriter {
+            size: (width, height),
+            background: Color::Rgba(0.0, 0.0, 0.0, 0.0),
+            namespace: NamespaceId::default(),
+            state: PdfState::default(),
+            state_stack: vec![],
+            target: PdfTarget::Layer(LayerId(0)),
+            layers: BTreeMap::new(),
+            sprites: HashMap::new(),
+            gradients: HashMap::new(),
+            textures: HashMap::new(),
+            font_faces: HashMap::new(),
+            font_sizes: HashMap::new(),
+            text_layout: None,
+            text_position: (0.0, 0.0, TextAlignment::Left),
+            fonts: vec![],
+            document: PdfDocument::new(true),
+            frame_depth: 0,
+            changed_since_page: false,
+        }
+    }
+
+    ///
+    /// Sets whether or not the streams in the document are compressed (they are compressed by default)
+    ///
+    /// This should be set before any drawing instructions are written.
+    ///
+    pub fn with_compression(mut self, compress: bool) -> PdfWriter {
+        self.document = PdfDocument::new(compress);
+        self
+    }
+
+    ///
+    /// Adds some drawing instructions to the document
+    ///
+    pub fn draw(&mut self, drawing: impl IntoIterator<Item = Draw>) {
+        for draw in drawing {
+            self.draw_one(draw);
+        }
+    }
+
+    ///
+    /// Generates the PDF document for the drawing instructions that have been written so far
+    ///
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut document = self.document.clone();
+
+        // The last page shows anything drawn since the last frame (a document always has at least one page)
+        if document.page_count() == 0 || self.changed_since_page {
+            let content = page_content(&self.layers, &self.background, self.size, &mut document);
+            document.add_page(&content);
+        }
+
+        // The fonts are embedded once all of the glyphs they need are known
+        for (index, font) in self.fonts.iter().enumerate() {
+            let font_id = font.embed(&mut document, index);
+            document.insert_resource(PdfResourceType::Font, font.name.clone(), font_id);
+        }
+
+        document.to_bytes(self.size)
+    }
+
+    ///
+    /// Adds a page showing the current state of the canvas to the document
+    ///
+    fn add_page(&mut self) {
+        let content = page_content(
+            &self.layers,
+            &self.background,
+            self.size,
+            &mut self.document,
+        );
+
+        self.document.add_page(&content);
+        self.changed_since_page = false;
+    }
+
+    ///
+    /// Processes a single drawing instruction
+    ///
+    fn draw_one(&mut self, draw: Draw) {
+        use self::Draw::*;
+
+        match draw {
+            StartFrame => self.frame_depth += 1,
+            ShowFrame => {
+                if self.frame_depth > 0 {
+                    self.frame_depth -= 1;
+
+                    if self.frame_depth == 0 {
+                        self.add_page();
+                    }
+                }
+            }
+            ResetFrame => self.frame_depth = 0,
+
+            Path(path_op) => {
+                if let PathOp::NewPath = path_op {
+                    self.state.path.clear();
+                } else {
+                    self.state.path.push(path_op);
+                }
+            }
+
+            Fill => self.fill(),
+            Stroke => self.stroke(),
+
+            LineWidth(width) => {
+                self.state.line_width = width;
+                self.state.line_width_pixels = None;
+            }
+            LineWidthPixels(width) => self.state.line_width_pixels = Some(width),
+            LineJoin(join) => self.state.line_join = join,
+            LineCap(cap) => self.state.line_cap = cap,
+            NewDashPattern => self.state.dash_pattern = vec![],
+            DashLength(length) => self.state.dash_pattern.push(length),
+            DashOffset(offset) => self.state.dash_offset = offset,
+            StrokeColor(color) => self.state.stroke_color = color,
+            WindingRule(winding_rule) => self.state.winding_rule = winding_rule,
+            BlendMode(blend_mode) => self.state.blend_mode = blend_mode,
+
+            FillColor(color) => {
+                // Colour changes are part of the text that's being laid out
+                if let Some(text_layout) = &mut self.text_layout {
+                    text_layout.layout.draw(iter::once(FillColor(color)));
+                }
+
+                self.set_fill(PdfFill::Color(color));
+            }
+            FillTexture(texture_id, lower_left, upper_right) => {
+                if let Some((x_object, width, height, alpha)) = self.texture_x_object(texture_id) {
+                    self.set_fill(PdfFill::Texture(
+                        x_object,
+                        width,
+                        height,
+                        alpha,
+                        lower_left,
+                        upper_right,
+                    ));
+                }
+            }
+            FillGradient(gradient_id, start, end) => {
+                if let Some(gradient) = self.gradients.get(&(self.namespace, gradient_id)) {
+                    self.set_fill(PdfFill::LinearGradient(gradient.clone(), start, end));
+                }
+            }
+            FillRadialGradient(gradient_id, start, end) => {
+                if let Some(gradient) = self.gradients.get(&(self.namespace, gradient_id)) {
+                    self.set_fill(PdfFill::RadialGradient(gradient.clone(), start, end));
+                }
+            }
+            FillConicGradient(gradient_id, center, start_angle) => {
+                if let Some(gradient) = self.gradients.get(&(self.namespace, gradient_id)) {
+                    self.set_fill(PdfFill::ConicGradient(
+                        gradient.clone(),
+                        center,
+                        start_angle,
+                    ));
+                }
+            }
+            FillTransform(transform) => {
+                self.state.fill_transform = transform * self.state.fill_transform
+            }
+
+            IdentityTransform => self.state.transform = Transform2D::identity(),
+            CanvasHeight(height) => {
+                // The viewport is 2 units high
+                let scale = 2.0 / height.max(0.0000001);
+                self.state.transform = Transform2D::scale(scale, scale);
+            }
+            CenterRegion((x1, y1), (x2, y2)) => {
+                // Find the point that's currently at the center of the viewport and move the center of the region there
+                let inverse = self
+                    .state
+                    .transform
+                    .invert()
+                    .unwrap_or_else(Transform2D::identity);
+                let (center_x, center_y) = inverse.transform_point(0.0, 0.0);
+                let (new_x, new_y) = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+
+                self.state.transform = self.state.transform
+                    * Transform2D::translate(-(new_x - center_x), -(new_y - center_y));
+            }
+            MultiplyTransform(transform) => self.state.transform = self.state.transform * transform,
+
+            Unclip => self.state.clip = vec![],
+            Clip => self.clip(),
+
+            Store => {
+                if let Some(layer) = self.current_layer() {
+                    layer.stored = Some(layer.elements.len());
+                }
+            }
+            Restore => {
+                if let Some(layer) = self.current_layer() {
+                    if let Some(stored) = layer.stored {
+                        layer.elements.truncate(stored);
+                    }
+                }
+                self.changed_since_page = true;
+            }
+            FreeStoredBuffer => {
+                if let Some(layer) = self.current_layer() {
+                    layer.stored = None;
+                }
+            }
+
+            PushState => self.state_stack.push(self.state.clone()),
+            PopState => {
+                if let Some(state) = self.state_stack.pop() {
+                    self.state = state;
+                }
+            }
+
+            ClearCanvas(color) => self.clear_canvas(color),
+
+            Layer(layer_id) => {
+                self.text_layout = None;
+                self.layers.entry(layer_id.0).or_insert_with(PdfLayer::new);
+                self.target = PdfTarget::Layer(layer_id);
+            }
+            LayerBlend(layer_id, blend_mode) => {
+                self.layers
+                    .entry(layer_id.0)
+                    .or_insert_with(PdfLayer::new)
+                    .blend_mode = blend_mode;
+                self.changed_since_page = true;
+            }
+            LayerAlpha(layer_id, alpha) => {
+                self.layers
+                    .entry(layer_id.0)
+                    .or_insert_with(PdfLayer::new)
+                    .alpha = alpha;
+                self.changed_since_page = true;
+            }
+            ClearLayer => {
+                self.text_layout = None;
+
+                match self.target {
+                    PdfTarget::Layer(_) => {
+                        if let Some(layer) = self.current_layer() {
+                            layer.elements.clear();
+                        }
+                        self.changed_since_page = true;
+                    }
+                    PdfTarget::Sprite(..) => self.clear_sprite(),
+                }
+            }
+            ClearAllLayers => {
+                self.layers
+                    .values_mut()
+                    .for_each(|layer| layer.elements.clear());
+                self.changed_since_page = true;
+            }
+            SwapLayers(layer1, layer2) => {
+                let first = self.layers.remove(&layer1.0).unwrap_or_else(PdfLayer::new);
+                let second = self.layers.remove(&layer2.0).unwrap_or_else(PdfLayer::new);
+
+                self.layers.insert(layer1.0, second);
+                self.layers.insert(layer2.0, first);
+                self.changed_since_page = true;
+            }
+
+            Sprite(sprite_id) => {
+                self.text_layout = None;
+
+                let inverse_transform = self
+                    .canvas_transform()
+                    .invert()
+                    .unwrap_or_else(Transform2D::identity);
+                let sprite = self
+                    .sprites
+                    .entry((self.namespace, sprite_id))
+                    .or_insert_with(|| PdfSprite {
+                        elements: vec![],
+                        inverse_transform,
+                        form: None,
+                    });
+
+                sprite.inverse_transform = inverse_transform;
+                self.target = PdfTarget::Sprite(self.namespace, sprite_id);
+            }
+            MoveSpriteFrom(sprite_id) => {
+                if let PdfTarget::Sprite(namespace, target_sprite_id) = self.target {
+                    let elements = self
+                        .sprites
+                        .get(&(self.namespace, sprite_id))
+                        .map(|sprite| sprite.elements.clone())
+                        .unwrap_or_default();
+
+                    if let Some(sprite) = self.sprites.get_mut(&(namespace, target_sprite_id)) {
+                        sprite.elements = elements;
+                        sprite.form = None;
+                    }
+                }
+            }
+            ClearSprite => self.clear_sprite(),
+            SpriteTransform(transform) => {
+                self.state.sprite_transform = match transform {
+                    crate::draw::SpriteTransform::Identity => Transform2D::identity(),
+                    other => self.state.sprite_transform * Transform2D::from(other),
+                };
+            }
+            DrawSprite(sprite_id) => self.draw_sprite(sprite_id, &[]),
+            DrawSpriteWithFilters(sprite_id, filters) => self.draw_sprite(sprite_id, &filters),
+
+            Texture(texture_id, texture_op) => self.texture(texture_id, texture_op),
+            Gradient(gradient_id, gradient_op) => {
+                let gradient = self
+                    .gradients
+                    .entry((self.namespace, gradient_id))
+                    .or_default();
+
+                if let GradientOp::Create(_) = &gradient_op {
+                    gradient.clear();
+                }
+                gradient.push(gradient_op);
+            }
+
+            Font(font_id, font_op) => self.font(font_id, font_op),
+            BeginLineLayout(x, y, alignment) => {
+                // Any existing layout is discarded
+                self.text_layout = None;
+                self.text_position = (x, y, alignment);
+            }
+            DrawLaidOutText => {
+                if let Some(text_layout) = self.text_layout.take() {
+                    let (x, y, alignment) = self.text_position;
+                    let mut layout = text_layout.layout;
+
+                    layout.align(x, y, alignment);
+                    self.draw(layout.to_drawing(text_layout.font_id));
+                }
+            }
+            DrawText(font_id, text, x, y) => {
+                let key = (self.namespace, font_id);
+
+                if let (Some(face), Some(size)) =
+                    (self.font_faces.get(&key), self.font_sizes.get(&key))
+                {
+                    let mut layout = CanvasFontLineLayout::new(face, *size);
+
+                    layout.add_text(&text);
+                    layout.align(x, y, TextAlignment::Left);
+                    self.draw(layout.to_drawing(font_id));
+                }
+            }
+
+            Namespace(namespace) => self.namespace = namespace,
+        }
+    }
+
+    ///
+    /// The transform from canvas coordinates to page coordinates
+    ///
+    fn canvas_transform(&self) -> Transform2D {
+        // The viewport runs from -1 to 1 vertically, and the PDF y axis already points upwards
+        let (width, height) = self.size;
+        let page_transform = Transform2D::translate(width / 2.0, height / 2.0)
+            * Transform2D::scale(height / 2.0, height / 2.0);
+
+        page_transform * self.state.transform
+    }
+
+    ///
+    /// Resets the canvas to a single colour
+    ///
+    /// The objects that have already been written to the document are kept, as these may be used by earlier pages.
+    ///
+    fn clear_canvas(&mut self, color: Color) {
+        self.background = color;
+        self.namespace = NamespaceId::default();
+        self.state = PdfState::default();
+        self.state_stack = vec![];
+        self.target = PdfTarget::Layer(LayerId(0));
+        self.layers = BTreeMap::new();
+        self.sprites = HashMap::new();
+        self.gradients = HashMap::new();
+        self.textures = HashMap::new();
+        self.font_faces = HashMap::new();
+        self.font_sizes = HashMap::new();
+        self.text_layout = None;
+        self.changed_since_page = true;
+    }
+
+    ///
+    /// Retrieves the layer that is being drawn on (None if a sprite is selected)
+    ///
+    fn current_layer(&mut self) -> Option<&mut PdfLayer> {
+        match self.target {
+            PdfTarget::Layer(layer_id) => {
+                Some(self.layers.entry(layer_id.0).or_insert_with(PdfLayer::new))
+            }
+            PdfTarget::Sprite(..) => None,
+        }
+    }
+
+    ///
+    /// Removes the content of the current sprite
+    ///
+    fn clear_sprite(&mut self) {
+        if let PdfTarget::Sprite(namespace, sprite_id) = self.target {
+            if let Some(sprite) = self.sprites.get_mut(&(namespace, sprite_id)) {
+                sprite.elements.clear();
+                sprite.form = None;
+            }
+        }
+    }
+
+    ///
+    /// Sets the fill for future shapes
+    ///
+    fn set_fill(&mut self, fill: PdfFill) {
+        self.state.fill = fill;
+        self.state.fill_transform = Transform2D::identity();
+    }
+
+    ///
+    /// Adds an element to the current layer or sprite
+    ///
+    fn add_element(&mut self, element: String) {
+        match self.target {
+            PdfTarget::Layer(layer_id) => {
+                self.layers
+                    .entry(layer_id.0)
+                    .or_insert_with(PdfLayer::new)
+                    .elements
+                    .push(element);
+                self.changed_since_page = true;
+            }
+
+            PdfTarget::Sprite(namespace, sprite_id) => {
+                if let Some(sprite) = self.sprites.get_mut(&(namespace, sprite_id)) {
+                    sprite.elements.push(element);
+                    sprite.form = None;
+                }
+            }
+        }
+    }
+
+    ///
+    /// Starts a new element: saves the graphics state, then sets up the clip region, transparency and blend mode
+    ///
+    /// Elements are self-contained, so the element should finish by restoring the graphics state using `Q`.
+    ///
+    fn start_element(&mut self, fill_alpha: f32, stroke_alpha: f32) -> String {
+        let mut element = String::from("q\n");
+
+        self.state
+            .clip
+            .iter()
+            .for_each(|clip| element.push_str(clip));
+
+        let blend_mode = pdf_blend_mode(self.state.blend_mode);
+        if let Some(ext_g_state) = self
+            .document
+            .ext_g_state(fill_alpha, stroke_alpha, blend_mode)
+        {
+            writeln!(element, "/{} gs", ext_g_state).ok();
+        }
+
+        element
+    }
+
+    ///
+    /// The points that define the current path, in canvas coordinates
+    ///
+    fn path_points(&self) -> Vec<(f32, f32)> {
+        self.state
+            .path
+            .iter()
+            .flat_map(|path_op| match path_op {
+                PathOp::Move(x, y) | PathOp::Line(x, y) => vec![(*x, *y)],
+                PathOp::BezierCurve((cp1, cp2), end) => vec![*cp1, *cp2, *end],
+                PathOp::NewPath | PathOp::ClosePath => vec![],
+            })
+            .collect()
+    }
+
+    ///
+    /// Writes a pattern to the document that fills the area covered by `points` (in canvas coordinates) with the current
+    /// fill, returning the operators that select the pattern and its transparency
+    ///
+    /// Solid colours and conic gradients have no pattern, so these return None.
+    ///
+    fn fill_pattern(&mut self, points: &[(f32, f32)]) -> Option<(String, f32)> {
+        // Patterns are positioned relative to the page (or form) rather than the current transformation matrix
+        let pattern_transform = self.canvas_transform() * self.state.fill_transform;
+        let to_gradient = self
+            .state
+            .fill_transform
+            .invert()
+            .unwrap_or_else(Transform2D::identity);
+
+        let (pattern_id, alpha) = match self.state.fill.clone() {
+            PdfFill::Color(_) | PdfFill::ConicGradient(..) => {
+                return None;
+            }
+
+            PdfFill::Texture(x_object, width, height, alpha, (x1, y1), (x2, y2)) => {
+                // Map the texture pixels onto the rectangle specified by the fill
+                let pattern_transform = pattern_transform
+                    * Transform2D::translate(x1, y1)
+                    * Transform2D::scale((x2 - x1) / (width as f32), (y2 - y1) / (height as f32));
+                let pattern_id = self.document.add_stream(
+                    &format!(
+                        "/Type /Pattern /PatternType 1 /PaintType 1 /TilingType 1 /BBox [0 0 {} {}] /XStep {} /YStep {} /Resources {} 0 R /Matrix [{}]",
+                        width, height, width, height, RESOURCES_OBJECT, pdf_matrix(&pattern_transform)
+                    ),
+                    format!("/{} Do", x_object).as_bytes(),
+                );
+
+                (pattern_id, alpha)
+            }
+
+            PdfFill::LinearGradient(gradient, start, end) => {
+                // Repeating gradients are extended far enough to cover the path
+                let points = points
+                    .iter()
+                    .map(|(x, y)| to_gradient.transform_point(*x, *y));
+                let (first_repeat, last_repeat) =
+                    pdf_gradient_repeats(&gradient, start, end, points);
+
+                let (x1, y1) = start;
+                let (dx, dy) = (end.0 - x1, end.1 - y1);
+                let point_at = |pos: i32| {
+                    let pos = pos as f32;
+                    format!(
+                        "{} {}",
+                        pdf_number(x1 + dx * pos),
+                        pdf_number(y1 + dy * pos)
+                    )
+                };
+
+                let pattern_id = self.document.add_object(format!(
+                    "<< /Type /Pattern /PatternType 2 /Shading << /ShadingType 2 /ColorSpace /DeviceRGB /Coords [{} {}] /Domain [{} {}] /Function {} /Extend [true true] >> /Matrix [{}] >>",
+                    point_at(first_repeat), point_at(last_repeat),
+                    first_repeat, last_repeat,
+                    pdf_gradient_function(&gradient, first_repeat, last_repeat),
+                    pdf_matrix(&pattern_transform)
+                ));
+
+                (pattern_id, pdf_gradient_alpha(&gradient))
+            }
+
+            PdfFill::RadialGradient(gradient, (x1, y1, r1), (x2, y2, r2)) => {
+                let pattern_id = self.document.add_object(format!(
+                    "<< /Type /Pattern /PatternType 2 /Shading << /ShadingType 3 /ColorSpace /DeviceRGB /Coords [{} {} {} {} {} {}] /Function {} /Extend [true true] >> /Matrix [{}] >>",
+                    pdf_number(x1), pdf_number(y1), pdf_number(r1.abs()),
+                    pdf_number(x2), pdf_number(y2), pdf_number(r2.abs()),
+                    pdf_gradient_function(&gradient, 0, 1),
+                    pdf_matrix(&pattern_transform)
+                ));
+
+                (pattern_id, pdf_gradient_alpha(&gradient))
+            }
+        };
+
+        let pattern_name = self
+            .document
+            .add_resource(PdfResourceType::Pattern, "P", pattern_id);
+
+        Some((format!("/Pattern cs /{} scn\n", pattern_name), alpha))
+    }
+
+    ///
+    /// Returns the operators that set the fill colour for an area covering `points`, along with its transparency
+    ///
+    fn fill_paint(&mut self, points: &[(f32, f32)]) -> (String, f32) {
+        if let Some(pattern) = self.fill_pattern(points) {
+            return pattern;
+        }
+
+        let (r, g, b, a) = match &self.state.fill {
+            PdfFill::Color(color) => color.to_rgba_components(),
+            PdfFill::ConicGradient(gradient, _, _) => pdf_gradient_color(gradient, 0.5),
+            _ => (0.0, 0.0, 0.0, 1.0),
+        };
+
+        (format!("{} rg\n", pdf_rgb((r, g, b))), a)
+    }
+
+    ///
+    /// Fills the current path
+    ///
+    fn fill(&mut self) {
+        if self.state.path.is_empty() {
+            return;
+        }
+
+        if let PdfFill::ConicGradient(gradient, center, start_angle) = self.state.fill.clone() {
+            self.fill_conic_gradient(&gradient, center, start_angle);
+            return;
+        }
+
+        let (paint, alpha) = self.fill_paint(&self.path_points());
+        let mut element = self.start_element(alpha, 1.0);
+
+        writeln!(element, "{} cm", pdf_matrix(&self.canvas_transform())).ok();
+        element.push_str(&paint);
+        element.push_str(&pdf_path(&self.state.path, &Transform2D::identity()));
+        writeln!(element, "{}", pdf_fill_operator(self.state.winding_rule)).ok();
+        element.push_str("Q\n");
+
+        self.add_element(element);
+    }
+
+    ///
+    /// Fills the current path with a conic gradient, which is approximated by clipping a set of wedges against the path
+    ///
+    fn fill_conic_gradient(
+        &mut self,
+        gradient: &[GradientOp],
+        (x, y): (f32, f32),
+        start_angle: f32,
+    ) {
+        // The wedges are drawn around the origin in 'gradient' coordinates
+        let gradient_transform = self.state.fill_transform
+            * Transform2D::translate(x, y)
+            * Transform2D::rotate(start_angle);
+        let to_gradient = gradient_transform
+            .invert()
+            .unwrap_or_else(Transform2D::identity);
+
+        // The wedges need to be large enough to cover every point in the path
+        let radius = self
+            .path_points()
+            .into_iter()
+            .map(|(x, y)| {
+                let (x, y) = to_gradient.transform_point(x, y);
+                (x * x + y * y).sqrt()
+            })
+            .fold(0.0, f32::max)
+            * 1.1;
+
+        // Clip the wedges against the path
+        let mut element = self.start_element(pdf_gradient_alpha(gradient), 1.0);
+
+        writeln!(element, "{} cm", pdf_matrix(&self.canvas_transform())).ok();
+        element.push_str(&pdf_path(&self.state.path, &Transform2D::identity()));
+        writeln!(element, "{} n", pdf_clip_operator(self.state.winding_rule)).ok();
+        writeln!(element, "{} cm", pdf_matrix(&gradient_transform)).ok();
+
+        for wedge in 0..CONIC_GRADIENT_WEDGES {
+            // Wedges overlap slightly to avoid gaps between them
+            let wedge_size = 1.0 / (CONIC_GRADIENT_WEDGES as f32);
+            let start = (wedge as f32) * wedge_size;
+            let end = start + wedge_size * 1.5;
+            let (r, g, b, _) = pdf_gradient_color(gradient, start + wedge_size * 0.5);
+
+            let angle1 = start * 2.0 * f32::consts::PI;
+            let angle2 = end.min(1.0) * 2.0 * f32::consts::PI;
+
+            writeln!(
+                element,
+                "{} rg 0 0 m {} {} l {} {} l h f",
+                pdf_rgb((r, g, b)),
+                pdf_number(angle1.cos() * radius),
+                pdf_number(angle1.sin() * radius),
+                pdf_number(angle2.cos() * radius),
+                pdf_number(angle2.sin() * radius)
+            )
+            .ok();
+        }
+
+        element.push_str("Q\n");
+        self.add_element(element);
+    }
+
+    ///
+    /// Strokes the current path
+    ///
+    fn stroke(&mut self) {
+        if self.state.path.is_empty() {
+            return;
+        }
+
+        let canvas_transform = self.canvas_transform();
+        let (r, g, b, a) = self.state.stroke_color.to_rgba_components();
+        let mut element = self.start_element(1.0, a);
+
+        writeln!(element, "{} cm", pdf_matrix(&canvas_transform)).ok();
+        writeln!(element, "{} RG", pdf_rgb((r, g, b))).ok();
+
+        let line_width = if let Some(width_pixels) = self.state.line_width_pixels {
+            // Pixel widths are measured in points, so they need to be converted back to canvas units
+            let scale = pdf_transform_scale(&canvas_transform);
+            if scale > 0.0 {
+                width_pixels / scale
+            } else {
+                width_pixels
+            }
+        } else {
+            self.state.line_width
+        };
+
+        writeln!(element, "{} w", pdf_number(line_width)).ok();
+        writeln!(element, "{} j", pdf_line_join(self.state.line_join)).ok();
+        writeln!(element, "{} J", pdf_line_cap(self.state.line_cap)).ok();
+
+        if !self.state.dash_pattern.is_empty() {
+            let dash_array = self
+                .state
+                .dash_pattern
+                .iter()
+                .map(|length| pdf_number(*length))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            writeln!(
+                element,
+                "[{}] {} d",
+                dash_array,
+                pdf_number(self.state.dash_offset)
+            )
+            .ok();
+        }
+
+        element.push_str(&pdf_path(&self.state.path, &Transform2D::identity()));
+        element.push_str("S\nQ\n");
+
+        self.add_element(element);
+    }
+
+    ///
+    /// Intersects the clip region with the current path
+    ///
+    fn clip(&mut self) {
+        // Clip paths are converted to page coordinates, as they're applied before the transform for each element
+        let mut clip = pdf_path(&self.state.path, &self.canvas_transform());
+        writeln!(clip, "{} n", pdf_clip_operator(self.state.winding_rule)).ok();
+
+        self.state.clip.push(clip);
+    }
+
+    ///
+    /// Returns the name of the form XObject for the current version of a sprite, writing it if needed
+    ///
+    fn sprite_form(&mut self, namespace: NamespaceId, sprite_id: SpriteId) -> Option<String> {
+        let sprite = self.sprites.get_mut(&(namespace, sprite_id))?;
+        if let Some(form) = &sprite.form {
+            return Some(form.clone());
+        }
+
+        let form_id = self.document.add_stream(
+            &format!(
+                "/Type /XObject /Subtype /Form /BBox {} /Resources {} 0 R",
+                SPRITE_FORM_BOUNDS, RESOURCES_OBJECT
+            ),
+            sprite.elements.concat().as_bytes(),
+        );
+        let form = self
+            .document
+            .add_resource(PdfResourceType::XObject, "Fm", form_id);
+        sprite.form = Some(form.clone());
+
+        Some(form)
+    }
+
+    ///
+    /// Draws a sprite using the current sprite transform
+    ///
+    /// Only the alpha blend filter is supported: other filters are ignored.
+    ///
+    fn draw_sprite(&mut self, sprite_id: SpriteId, filters: &[TextureFilter]) {
+        let form = if let Some(form) = self.sprite_form(self.namespace, sprite_id) {
+            form
+        } else {
+            return;
+        };
+        let inverse_transform = self.sprites[&(self.namespace, sprite_id)].inverse_transform;
+
+        let alpha = filters
+            .iter()
+            .map(|filter| match filter {
+                TextureFilter::AlphaBlend(alpha) => *alpha,
+                _ => 1.0,
+            })
+            .product();
+
+        // The sprite content is in page coordinates, so map it back to canvas coordinates before applying the sprite transform
+        let transform = self.canvas_transform() * self.state.sprite_transform * inverse_transform;
+        let mut element = self.start_element(alpha, alpha);
+
+        writeln!(element, "{} cm", pdf_matrix(&transform)).ok();
+        writeln!(element, "/{} Do", form).ok();
+        element.push_str("Q\n");
+
+        self.add_element(element);
+    }
+
+    ///
+    /// Performs an operation on a texture
+    ///
+    fn texture(&mut self, texture_id: TextureId, texture_op: TextureOp) {
+        use self::TextureOp::*;
+
+        let key = (self.namespace, texture_id);
+
+        match texture_op {
+            Create(size, TextureFormat::Rgba) => {
+                self.textures.insert(key, PdfTexture::new(size));
+            }
+
+            Free => {
+                self.textures.remove(&key);
+            }
+
+            SetBytes(position, size, bytes) => {
+                if let Some(texture) = self.textures.get_mut(&key) {
+                    texture.set_bytes(position, size, &bytes);
+                }
+            }
+
+            SetFromSprite(sprite_id, bounds) => {
+                let sprite_form = self.sprite_form(self.namespace, sprite_id);
+
+                if let Some(texture) = self.textures.get_mut(&key) {
+                    texture.content = PdfTextureContent::Sprite {
+                        sprite_id,
+                        bounds,
+                        dynamic: false,
+                        sprite_form,
+                    };
+                    texture.x_object = None;
+                }
+            }
+
+            CreateDynamicSprite(sprite_id, bounds, CanvasSize(width, height)) => {
+                let size = TextureSize(width.ceil().max(1.0) as u32, height.ceil().max(1.0) as u32);
+                let mut texture = PdfTexture::new(size);
+
+                texture.content = PdfTextureContent::Sprite {
+                    sprite_id,
+                    bounds,
+                    dynamic: true,
+                    sprite_form: None,
+                };
+                self.textures.insert(key, texture);
+            }
+
+            FillTransparency(alpha) => {
+                if let Some(texture) = self.textures.get_mut(&key) {
+                    texture.alpha = alpha;
+                }
+            }
+
+            Copy(target_texture_id) => {
+                if let Some(texture) = self.textures.get(&key).cloned() {
+                    self.textures
+                        .insert((self.namespace, target_texture_id), texture);
+                }
+            }
+
+            Filter(_) => {}
+        }
+    }
+
+    ///
+    /// Returns the name of a form XObject that draws the current content of a texture over the area 0,0 to width,height,
+    /// along with its size and alpha value
+    ///
+    fn texture_x_object(&mut self, texture_id: TextureId) -> Option<(String, u32, u32, f32)> {
+        let key = (self.namespace, texture_id);
+
+        // Dynamic textures follow the current version of their sprite
+        if let PdfTextureContent::Sprite {
+            sprite_id,
+            dynamic: true,
+            ..
+        } = &self.textures.get(&key)?.content
+        {
+            let sprite_id = *sprite_id;
+            let latest_form = self.sprite_form(self.namespace, sprite_id);
+
+            if let Some(PdfTexture {
+                content: PdfTextureContent::Sprite { sprite_form, .. },
+                x_object,
+                ..
+            }) = self.textures.get_mut(&key)
+            {
+                if *sprite_form != latest_form {
+                    *sprite_form = latest_form;
+                    *x_object = None;
+                }
+            }
+        }
+
+        let texture = self.textures.get(&key)?.clone();
+        if texture.width == 0 || texture.height == 0 {
+            return None;
+        }
+
+        if let Some(x_object) = &texture.x_object {
+            return Some((
+                x_object.clone(),
+                texture.width,
+                texture.height,
+                texture.alpha,
+            ));
+        }
+
+        // Write a new form for this texture
+        let (width, height) = (texture.width as f32, texture.height as f32);
+        let content = match &texture.content {
+            PdfTextureContent::Bitmap(pixels) => {
+                // Images cover the unit square, with the first row at the top
+                let image_id =
+                    pdf_add_image(&mut self.document, texture.width, texture.height, pixels);
+                let image = self
+                    .document
+                    .add_resource(PdfResourceType::XObject, "Im", image_id);
+
+                format!(
+                    "q {} 0 0 {} 0 {} cm /{} Do Q",
+                    pdf_number(width),
+                    pdf_number(-height),
+                    pdf_number(height),
+                    image
+                )
+            }
+
+            PdfTextureContent::Sprite {
+                sprite_id,
+                bounds: SpriteBounds(SpritePosition(x, y), SpriteSize(sprite_width, sprite_height)),
+                sprite_form: Some(sprite_form),
+                ..
+            } => {
+                // Map the sprite bounds onto the texture pixels
+                let inverse_transform = self
+                    .sprites
+                    .get(&(self.namespace, *sprite_id))
+                    .map(|sprite| sprite.inverse_transform)
+                    .unwrap_or_else(Transform2D::identity);
+                let transform = Transform2D::scale(width / sprite_width, height / sprite_height)
+                    * Transform2D::translate(-x, -y)
+                    * inverse_transform;
+
+                format!("q {} cm /{} Do Q", pdf_matrix(&transform), sprite_form)
+            }
+
+            PdfTextureContent::Sprite {
+                sprite_form: None, ..
+            } => String::new(),
+        };
+
+        let form_id = self.document.add_stream(
+            &format!(
+                "/Type /XObject /Subtype /Form /BBox [0 0 {} {}] /Resources {} 0 R",
+                texture.width, texture.height, RESOURCES_OBJECT
+            ),
+            content.as_bytes(),
+        );
+        let x_object = self
+            .document
+            .add_resource(PdfResourceType::XObject, "Tex", form_id);
+
+        if let Some(texture) = self.textures.get_mut(&key) {
+            texture.x_object = Some(x_object.clone());
+        }
+
+        Some((x_object, texture.width, texture.height, texture.alpha))
+    }
+
+    ///
+    /// Performs an operation on a font
+    ///
+    fn font(&mut self, font_id: FontId, font_op: FontOp) {
+        let key = (self.namespace, font_id);
+
+        match font_op {
+            FontOp::UseFontDefinition(face) => {
+                // Defining a font interrupts any existing text layout
+                self.text_layout = None;
+                self.font_faces.insert(key, face);
+                self.font_sizes.insert(key, 12.0);
+            }
+
+            FontOp::FontSize(size) => {
+                // Changing the size of the font that's being laid out continues the layout with the new size
+                if let Some(text_layout) = self.text_layout.take() {
+                    let layout = if text_layout.font_id == font_id {
+                        let face = text_layout.layout.font();
+                        text_layout
+                            .layout
+                            .continue_with_new_font(font_id, &face, size)
+                    } else {
+                        text_layout.layout
+                    };
+
+                    self.text_layout = Some(PdfTextLayout {
+                        layout,
+                        font_id: text_layout.font_id,
+                    });
+                }
+
+                self.font_sizes.insert(key, size);
+            }
+
+            FontOp::LayoutText(text) => {
+                let current_font = self.text_layout.as_ref().map(|layout| layout.font_id);
+
+                if current_font != Some(font_id) {
+                    if let (Some(face), Some(size)) =
+                        (self.font_faces.get(&key), self.font_sizes.get(&key))
+                    {
+                        let layout = match self.text_layout.take() {
+                            Some(text_layout) => text_layout.layout.continue_with_new_font(
+                                text_layout.font_id,
+                                face,
+                                *size,
+                            ),
+                            None => CanvasFontLineLayout::new(face, *size),
+                        };
+
+                        self.text_layout = Some(PdfTextLayout { layout, font_id });
+                    }
+                }
+
+                if let Some(text_layout) = &mut self.text_layout {
+                    text_layout.layout.add_text(&text);
+                }
+            }
+
+            FontOp::DrawGlyphs(glyphs) => self.draw_glyphs(font_id, glyphs),
+        }
+    }
+
+    ///
+    /// Draws some glyphs using the current fill
+    ///
+    fn draw_glyphs(&mut self, font_id: FontId, glyphs: Vec<GlyphPosition>) {
+        let face = if let Some(face) = self.font_faces.get(&(self.namespace, font_id)) {
+            Arc::clone(face)
+        } else {
+            return;
+        };
+
+        // PDF fonts are written using 2-byte character codes, so glyphs outside of that range can't be drawn
+        let glyphs = glyphs
+            .into_iter()
+            .filter(|glyph| glyph.id.0 <= u16::MAX as u32)
+            .collect::<Vec<_>>();
+        if glyphs.is_empty() {
+            return;
+        }
+
+        // Each font face is written to the document once
+        let font_index = if let Some(index) = self
+            .fonts
+            .iter()
+            .position(|font| Arc::ptr_eq(&font.face, &face))
+        {
+            index
+        } else {
+            let name = format!("F{}", self.fonts.len());
+            self.fonts.push(PdfFont::new(face, name));
+            self.fonts.len() - 1
+        };
+
+        let font = &mut self.fonts[font_index];
+        glyphs.iter().for_each(|glyph| {
+            font.glyphs.insert(glyph.id.0 as u16);
+        });
+        let font_name = font.name.clone();
+
+        // Text is drawn using a font size of 1, so the text matrix sets the size of each glyph
+        let points = glyphs
+            .iter()
+            .map(|glyph| glyph.location)
+            .collect::<Vec<_>>();
+        let (paint, alpha) = self.fill_paint(&points);
+        let mut element = self.start_element(alpha, 1.0);
+
+        writeln!(element, "{} cm", pdf_matrix(&self.canvas_transform())).ok();
+        element.push_str(&paint);
+        writeln!(element, "BT\n/{} 1 Tf", font_name).ok();
+
+        for glyph in glyphs.iter() {
+            let (x, y) = glyph.location;
+            let em_size = pdf_number(glyph.em_size);
+
+            writeln!(
+                element,
+                "{} 0 0 {} {} {} Tm <{:04X}> Tj",
+                em_size,
+                em_size,
+                pdf_number(x),
+                pdf_number(y),
+                glyph.id.0
+            )
+            .ok();
+        }
+
+        element.push_str("ET\nQ\n");
+        self.add_element(element);
+    }
+}
+
+///
+/// Generates the content stream for a page showing a set of layers
+///
+fn page_content(
+    layers: &BTreeMap<u64, PdfLayer>,
+    background: &Color,
+    (width, height): (f32, f32),
+    document: &mut PdfDocument,
+) -> String {
+    let mut content = String::new();
+
+    // Background
+    let (r, g, b, a) = background.to_rgba_components();
+    if a > 0.0 {
+        content.push_str("q\n");
+        if let Some(ext_g_state) = document.ext_g_state(a, 1.0, "Normal") {
+            writeln!(content, "/{} gs", ext_g_state).ok();
+        }
+        writeln!(
+            content,
+            "{} rg\n0 0 {} {} re\nf\nQ",
+            pdf_rgb((r, g, b)),
+            pdf_number(width),
+            pdf_number(height)
+        )
+        .ok();
+    }
+
+    for layer in layers.values() {
+        if layer.elements.is_empty() {
+            continue;
+        }
+
+        let layer_content = layer.elements.concat();
+        let blend_mode = pdf_blend_mode(layer.blend_mode);
+
+        if let Some(ext_g_state) = document.ext_g_state(layer.alpha, layer.alpha, blend_mode) {
+            // Layers with transparency or a blend mode are drawn as a transparency group, so they are composited as a whole
+            let form_id = document.add_stream(
+                &format!(
+                    "/Type /XObject /Subtype /Form /BBox [0 0 {} {}] /Group << /S /Transparency >> /Resources {} 0 R",
+                    pdf_number(width),
+                    pdf_number(height),
+                    RESOURCES_OBJECT
+                ),
+                layer_content.as_bytes(),
+            );
+            let form = document.add_resource(PdfResourceType::XObject, "Fm", form_id);
+
+            writeln!(content, "q\n/{} gs\n/{} Do\nQ", ext_g_state, form).ok();
+        } else {
+            content.push_str(&layer_content);
+        }
+    }
+
+    content
+}
+
+///
+/// Converts a set of drawing instructions into a PDF document with pages of the specified size (in points)
+///
+pub fn drawing_to_pdf(
+    drawing: impl IntoIterator<Item = Draw>,
+    (width, height): (f32, f32),
+) -> Vec<u8> {
+    let mut writer = PdfWriter::new(width, height);
+    writer.draw(drawing);
+
+    writer.to_pdf()
+}
+
+///
+/// Reads a stream of drawing instructions and converts them to a PDF document with pages of the specified size (in points)
+///
+pub async fn drawing_stream_to_pdf(
+    drawing: impl Unpin + Stream<Item = Draw>,
+    (width, height): (f32, f32),
+) -> Vec<u8> {
+    let mut writer = PdfWriter::new(width, height);
+    let mut drawing = drawing;
+
+    while let Some(draw) = drawing.next().await {
+        writer.draw_one(draw);
+    }
+
+    writer.to_pdf()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::context::*;
+    use crate::primitives::*;
+
+    use futures::executor;
+    use futures::stream;
+
+    fn square() -> Vec<Draw> {
+        let mut drawing = vec![];
+
+        drawing.new_path();
+        drawing.move_to(0.0, 0.0);
+        drawing.line_to(10.0, 0.0);
+        drawing.line_to(10.0, 10.0);
+        drawing.line_to(0.0, 10.0);
+        drawing.close_path();
+
+        drawing
+    }
+
+    fn uncompressed_pdf(drawing: Vec<Draw>) -> String {
+        let mut writer = PdfWriter::new(100.0, 100.0).with_compression(false);
+        writer.draw(drawing);
+
+        String::from_utf8_lossy(&writer.to_pdf()).to_string()
+    }
+
+    #[test]
+    fn empty_document() {
+        let pdf = drawing_to_pdf(vec![], (640.0, 480.0));
+        let pdf = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.starts_with("%PDF-1.7"));
+        assert!(
+            pdf.contains("/Type /Pages /Kids [5 0 R] /Count 1 /MediaBox [0 0 640 480]"),
+            "{}",
+            pdf
+        );
+        assert!(pdf.trim_end().ends_with("%%EOF"), "{}", pdf);
+    }
+
+    #[test]
+    fn xref_offsets_point_at_objects() {
+        let mut drawing = square();
+        drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 0.5));
+        drawing.fill();
+
+        let pdf = drawing_to_pdf(drawing, (100.0, 100.0));
+
+        // Read the location of the cross-reference table from the trailer
+        let startxref = pdf
+            .windows(9)
+            .rposition(|bytes| bytes == b"startxref")
+            .unwrap();
+        let xref_offset = String::from_utf8_lossy(&pdf[(startxref + 9)..])
+            .split_whitespace()
+            .next()
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+        assert!(pdf[xref_offset..].starts_with(b"xref\n"));
+
+        let xref = String::from_utf8_lossy(&pdf[xref_offset..]).to_string();
+        let mut lines = xref.lines().skip(1);
+        let num_objects = lines
+            .next()
+            .unwrap()
+            .split_whitespace()
+            .nth(1)
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+
+        // Every object (other than the free entry) should be at the offset in the table
+        for (object_id, line) in lines.take(num_objects).enumerate().skip(1) {
+            let offset = line[0..10].parse::<usize>().unwrap();
+            let header = format!("{} 0 obj", object_id);
+
+            assert!(
+                pdf[offset..].starts_with(header.as_bytes()),
+                "Object {} is not at {}",
+                object_id,
+                offset
+            );
+        }
+    }
+
+    #[test]
+    fn compressed_by_default() {
+        let mut drawing = square();
+        drawing.fill();
+
+        let pdf = drawing_to_pdf(drawing, (100.0, 100.0));
+        let pdf = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.contains("/Filter /FlateDecode"), "{}", pdf);
+        assert!(!pdf.contains("0 0 m\n"), "{}", pdf);
+    }
+
+    #[test]
+    fn fill_square() {
+        let mut drawing = vec![];
+        drawing.canvas_height(100.0);
+        drawing.extend(square());
+        drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.fill();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(
+            pdf.contains(
+                "q\n1 0 0 1 50 50 cm\n1 0 0 rg\n0 0 m\n10 0 l\n10 10 l\n0 10 l\nh\nf\nQ\n"
+            ),
+            "{}",
+            pdf
+        );
+    }
+
+    #[test]
+    fn even_odd_fill() {
+        let mut drawing = square();
+        drawing.winding_rule(WindingRule::EvenOdd);
+        drawing.fill();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(pdf.contains("h\nf*\nQ\n"), "{}", pdf);
+    }
+
+    #[test]
+    fn transparent_fill_uses_graphics_state() {
+        let mut drawing = square();
+        drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 0.5));
+        drawing.fill();
+        drawing.fill();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(
+            pdf.contains("<< /Type /ExtGState /ca 0.5 /CA 1 /BM /Normal >>"),
+            "{}",
+            pdf
+        );
+        assert!(pdf.matches("/Type /ExtGState").count() == 1, "{}", pdf);
+        assert!(pdf.matches("q\n/GS0 gs\n").count() == 2, "{}", pdf);
+    }
+
+    #[test]
+    fn stroke_square() {
+        let mut drawing = square();
+        drawing.line_width(2.0);
+        drawing.line_join(LineJoin::Bevel);
+        drawing.line_cap(LineCap::Square);
+        drawing.stroke_color(Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        drawing.new_dash_pattern();
+        drawing.dash_length(3.0);
+        drawing.dash_length(1.0);
+        drawing.dash_offset(0.5);
+        drawing.stroke();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(
+            pdf.contains("0 0 1 RG\n2 w\n2 j\n2 J\n[3 1] 0.5 d\n0 0 m\n"),
+            "{}",
+            pdf
+        );
+        assert!(pdf.contains("h\nS\nQ\n"), "{}", pdf);
+    }
+
+    #[test]
+    fn stroke_pixel_width_does_not_scale() {
+        let mut drawing = vec![];
+        drawing.canvas_height(100.0);
+        drawing.extend(square());
+        drawing.line_width_pixels(3.0);
+        drawing.stroke();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(pdf.contains("\n3 w\n"), "{}", pdf);
+    }
+
+    #[test]
+    fn clip_and_unclip() {
+        let mut drawing = vec![];
+        drawing.canvas_height(100.0);
+        drawing.rect(-10.0, -10.0, 10.0, 10.0);
+        drawing.clip();
+        drawing.extend(square());
+        drawing.fill();
+        drawing.unclip();
+        drawing.fill();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        // Clip paths are written in page coordinates
+        assert!(pdf.contains("q\n40 40 m\n"), "{}", pdf);
+        assert!(pdf.matches("W n\n").count() == 1, "{}", pdf);
+        assert!(pdf.matches("0 0 m\n10 0 l").count() == 2, "{}", pdf);
+    }
+
+    #[test]
+    fn push_and_pop_state() {
+        let mut drawing = square();
+        drawing.push_state();
+        drawing.fill_color(Color::Rgba(0.0, 1.0, 0.0, 1.0));
+        drawing.pop_state();
+        drawing.fill();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(pdf.contains("0 0 0 rg"), "{}", pdf);
+        assert!(!pdf.contains("0 1 0 rg"), "{}", pdf);
+    }
+
+    #[test]
+    fn layers_are_ordered_by_id() {
+        let mut drawing = vec![];
+        drawing.layer(LayerId(2));
+        drawing.extend(square());
+        drawing.fill_color(Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        drawing.fill();
+        drawing.layer(LayerId(1));
+        drawing.extend(square());
+        drawing.fill_color(Color::Rgba(0.0, 1.0, 0.0, 1.0));
+        drawing.fill();
+        drawing.layer_blend(LayerId(2), BlendMode::Multiply);
+        drawing.layer_alpha(LayerId(2), 0.25);
+
+        let pdf = uncompressed_pdf(drawing);
+
+        // Layer 2 is drawn as a transparency group after layer 1
+        let green = pdf.find("0 1 0 rg").unwrap();
+        let group = pdf.find("q\n/GS0 gs\n/Fm1 Do\nQ").unwrap();
+        assert!(green < group, "{}", pdf);
+        assert!(pdf.contains("0 0 1 rg"), "{}", pdf);
+        assert!(pdf.contains("/Group << /S /Transparency >>"), "{}", pdf);
+        assert!(pdf.contains("/ca 0.25 /CA 0.25 /BM /Multiply"), "{}", pdf);
+    }
+
+    #[test]
+    fn draw_sprite_uses_form() {
+        let mut drawing = vec![];
+        drawing.sprite(SpriteId(1));
+        drawing.extend(square());
+        drawing.fill();
+        drawing.layer(LayerId(0));
+        drawing.sprite_transform(SpriteTransform::Translate(20.0, 0.0));
+        drawing.draw_sprite(SpriteId(1));
+        drawing.draw_sprite(SpriteId(1));
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(
+            pdf.matches("/Subtype /Form /BBox [-100000").count() == 1,
+            "{}",
+            pdf
+        );
+        assert!(
+            pdf.matches("1 0 0 1 1000 0 cm\n/Fm0 Do\n").count() == 2,
+            "{}",
+            pdf
+        );
+    }
+
+    #[test]
+    fn linear_gradient_fill() {
+        let mut drawing = vec![];
+        drawing.create_gradient(GradientId(1), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.gradient_stop(GradientId(1), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        drawing.extend(square());
+        drawing.fill_gradient(GradientId(1), 0.0, 0.0, 10.0, 0.0);
+        drawing.fill();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(pdf.contains("/PatternType 2 /Shading << /ShadingType 2 /ColorSpace /DeviceRGB /Coords [0 0 10 0] /Domain [0 1] /Function << /FunctionType 2 /Domain [0 1] /C0 [1 0 0] /C1 [0 0 1] /N 1 >> /Extend [true true] >>"), "{}", pdf);
+        assert!(pdf.contains("/Pattern cs /P0 scn\n"), "{}", pdf);
+    }
+
+    #[test]
+    fn repeating_gradient_covers_path() {
+        let mut drawing = vec![];
+        drawing.create_gradient(GradientId(1), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.gradient_stop(GradientId(1), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        drawing.draw(Draw::Gradient(
+            GradientId(1),
+            GradientOp::SpreadMode(GradientSpread::Reflect),
+        ));
+        drawing.extend(square());
+        drawing.fill_gradient(GradientId(1), 0.0, 0.0, 5.0, 0.0);
+        drawing.fill();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(pdf.contains("/Coords [0 0 10 0] /Domain [0 2]"), "{}", pdf);
+        assert!(pdf.contains("/Encode [0 1 1 0]"), "{}", pdf);
+    }
+
+    #[test]
+    fn radial_gradient_fill() {
+        let mut drawing = vec![];
+        drawing.create_gradient(GradientId(1), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.gradient_stop(GradientId(1), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        drawing.extend(square());
+        drawing.draw(Draw::FillRadialGradient(
+            GradientId(1),
+            (1.0, 2.0, 0.5),
+            (5.0, 5.0, 4.0),
+        ));
+        drawing.fill();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(
+            pdf.contains("/ShadingType 3 /ColorSpace /DeviceRGB /Coords [1 2 0.5 5 5 4]"),
+            "{}",
+            pdf
+        );
+    }
+
+    #[test]
+    fn conic_gradient_fill() {
+        let mut drawing = vec![];
+        drawing.create_gradient(GradientId(1), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.gradient_stop(GradientId(1), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        drawing.extend(square());
+        drawing.draw(Draw::FillConicGradient(GradientId(1), (5.0, 5.0), 0.0));
+        drawing.fill();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(pdf.contains("h\nW n\n1 0 0 1 5 5 cm\n"), "{}", pdf);
+        assert!(
+            pdf.matches(" l h f\n").count() == CONIC_GRADIENT_WEDGES,
+            "{}",
+            pdf
+        );
+    }
+
+    #[test]
+    fn texture_is_embedded_as_image() {
+        let mut pixels = vec![255; 16];
+        pixels[3] = 128;
+
+        let mut drawing = vec![];
+        drawing.create_texture(TextureId(1), 2, 2, TextureFormat::Rgba);
+        drawing.set_texture_bytes(TextureId(1), 0, 0, 2, 2, Arc::new(pixels));
+        drawing.extend(square());
+        drawing.fill_texture(TextureId(1), 0.0, 0.0, 10.0, 10.0);
+        drawing.fill();
+        drawing.fill();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(
+            pdf.matches("/Subtype /Image /Width 2 /Height 2 /ColorSpace /DeviceRGB")
+                .count()
+                == 1,
+            "{}",
+            pdf
+        );
+        assert!(pdf.contains("/SMask"), "{}", pdf);
+        assert!(pdf.contains("q 2 0 0 -2 0 2 cm /Im"), "{}", pdf);
+        assert!(
+            pdf.contains(
+                "/PatternType 1 /PaintType 1 /TilingType 1 /BBox [0 0 2 2] /XStep 2 /YStep 2"
+            ),
+            "{}",
+            pdf
+        );
+    }
+
+    #[test]
+    fn opaque_texture_has_no_mask() {
+        let mut drawing = vec![];
+        drawing.create_texture(TextureId(1), 2, 2, TextureFormat::Rgba);
+        drawing.set_texture_bytes(TextureId(1), 0, 0, 2, 2, Arc::new(vec![255; 16]));
+        drawing.extend(square());
+        drawing.fill_texture(TextureId(1), 0.0, 0.0, 10.0, 10.0);
+        drawing.fill();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(pdf.contains("/Subtype /Image"), "{}", pdf);
+        assert!(!pdf.contains("/SMask"), "{}", pdf);
+    }
+
+    #[test]
+    fn show_frame_adds_pages() {
+        let mut drawing = vec![];
+        drawing.start_frame();
+        drawing.extend(square());
+        drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.fill();
+        drawing.show_frame();
+        drawing.start_frame();
+        drawing.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+        drawing.extend(square());
+        drawing.fill_color(Color::Rgba(0.0, 1.0, 0.0, 1.0));
+        drawing.fill();
+        drawing.show_frame();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(pdf.contains("/Count 2"), "{}", pdf);
+        assert!(pdf.contains("1 1 1 rg\n0 0 100 100 re\nf\n"), "{}", pdf);
+    }
+
+    #[test]
+    fn nested_frames_add_one_page() {
+        let mut drawing = vec![];
+        drawing.start_frame();
+        drawing.start_frame();
+        drawing.extend(square());
+        drawing.fill();
+        drawing.show_frame();
+        drawing.show_frame();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(pdf.contains("/Count 1"), "{}", pdf);
+    }
+
+    #[test]
+    fn drawing_after_last_frame_adds_page() {
+        let mut drawing = vec![];
+        drawing.start_frame();
+        drawing.extend(square());
+        drawing.fill();
+        drawing.show_frame();
+        drawing.fill();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(pdf.contains("/Count 2"), "{}", pdf);
+    }
+
+    #[test]
+    fn text_embeds_subset_font() {
+        let lato = CanvasFontFace::from_slice(include_bytes!("../../test_data/Lato-Regular.ttf"));
+
+        let mut drawing = vec![];
+        drawing.canvas_height(100.0);
+        drawing.define_font_data(FontId(1), Arc::clone(&lato));
+        drawing.set_font_size(FontId(1), 12.0);
+        drawing.draw_text(FontId(1), "Hello".to_string(), 0.0, 0.0);
+
+        let mut writer = PdfWriter::new(100.0, 100.0).with_compression(false);
+        writer.draw(drawing);
+        let pdf_bytes = writer.to_pdf();
+        let pdf = String::from_utf8_lossy(&pdf_bytes);
+
+        assert!(pdf.contains("BT\n/F0 1 Tf\n12 0 0 12 0 0 Tm <"), "{}", pdf);
+        assert!(pdf.matches(" Tj\n").count() == 5, "{}", pdf);
+        assert!(
+            pdf.contains("/Subtype /Type0 /BaseFont /AAAAAA+Lato-Regular"),
+            "{}",
+            pdf
+        );
+        assert!(pdf.contains("/FontFile2"), "{}", pdf);
+        assert!(pdf.contains("/Font << /F0 "), "{}", pdf);
+
+        // The subset font should be much smaller than the original font
+        let lato_size = include_bytes!("../../test_data/Lato-Regular.ttf").len();
+        assert!(
+            pdf_bytes.len() < lato_size / 2,
+            "{} >= {}",
+            pdf_bytes.len(),
+            lato_size
+        );
+    }
+
+    #[test]
+    fn text_maps_glyphs_to_unicode() {
+        let lato = CanvasFontFace::from_slice(include_bytes!("../../test_data/Lato-Regular.ttf"));
+        let glyph_h = lato.ttf_font().glyph_index('H').unwrap().0;
+
+        let mut drawing = vec![];
+        drawing.canvas_height(100.0);
+        drawing.define_font_data(FontId(1), Arc::clone(&lato));
+        drawing.set_font_size(FontId(1), 12.0);
+        drawing.draw_text(FontId(1), "Hello".to_string(), 0.0, 0.0);
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(pdf.contains("/ToUnicode "), "{}", pdf);
+        assert!(pdf.contains("4 beginbfchar\n"), "{}", pdf);
+        assert!(
+            pdf.contains(&format!("<{:04X}> <0048>\n", glyph_h)),
+            "{}",
+            pdf
+        );
+    }
+
+    #[test]
+    fn laid_out_text_changes_color() {
+        let lato = CanvasFontFace::from_slice(include_bytes!("../../test_data/Lato-Regular.ttf"));
+
+        let mut drawing = vec![];
+        drawing.define_font_data(FontId(1), Arc::clone(&lato));
+        drawing.begin_line_layout(0.0, 0.0, TextAlignment::Left);
+        drawing.layout_text(FontId(1), "Red".to_string());
+        drawing.fill_color(Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        drawing.layout_text(FontId(1), "Blue".to_string());
+        drawing.draw_text_layout();
+
+        let pdf = uncompressed_pdf(drawing);
+
+        assert!(pdf.matches("BT\n/F0 1 Tf\n").count() == 2, "{}", pdf);
+        assert!(pdf.contains("0 0 1 rg\nBT\n"), "{}", pdf);
+    }
+
+    #[test]
+    fn stream_to_pdf() {
+        let mut drawing = square();
+        drawing.fill();
+
+        let from_stream = executor::block_on(drawing_stream_to_pdf(
+            stream::iter(drawing.clone()),
+            (100.0, 100.0),
+        ));
+        let from_iter = drawing_to_pdf(drawing, (100.0, 100.0));
+
+        assert!(from_stream == from_iter);
+    }
+}
@@ -121,6 +121,32 @@ pub trait GraphicsPrimitives: GraphicsContext {
                             ]
                         }
 
+                        PathAttribute::FillRadialGradient(
+                            gradient,
+                            circle1,
+                            circle2,
+                            transform,
+                        ) => {
+                            smallvec![
+                                Draw::FillRadialGradient(*gradient, *circle1, *circle2),
+                                Draw::FillTransform(transform.unwrap_or(Transform2D::identity())),
+                                Draw::Fill,
+                            ]
+                        }
+
+                        PathAttribute::FillConicGradient(
+                            gradient,
+                            center,
+                            start_angle,
+                            transform,
+                        ) => {
+                            smallvec![
+                                Draw::FillConicGradient(*gradient, *center, *start_angle),
+                                Draw::FillTransform(transform.unwrap_or(Transform2D::identity())),
+                                Draw::Fill,
+                            ]
+                        }
+
                         PathAttribute::FillTexture(
                             texture,
                             (x1, y1),
@@ -0,0 +1,706 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//!
+//! A recording is a header followed by a list of entries. Each entry is the time the instruction was recorded in
+//! microseconds (a little-endian u64), the length of the instruction (a little-endian u32) and then the instruction
+//! itself in the binary canvas encoding.
+//!
+
+use crate::binary_decoding::*;
+use crate::decoding::*;
+use crate::draw::*;
+use crate::encoding::*;
+
+use futures::channel::{mpsc, oneshot};
+use futures::executor;
+use futures::prelude::*;
+use futures::stream;
+use futures::task::Poll;
+
+use std::convert::TryFrom;
+use std::io;
+use std::io::{BufWriter, Read, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The bytes that start every recording
+const RECORDING_MAGIC: &[u8] = b"FLOREC";
+
+/// The version of the recording format written by `CanvasRecorder`
+pub const CANVAS_RECORDING_VERSION: u8 = 1;
+
+/// The number of instructions that `record_drawing_stream()` can have waiting to be written before the stream waits
+/// for the recording to catch up
+const MAX_PENDING_ENTRIES: usize = 1000;
+
+///
+/// A drawing instruction in a recording, along with the time since the start of the recording that it was sent
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct RecordedDraw {
+    pub time: Duration,
+    pub draw: Draw,
+}
+
+///
+/// Errors that can occur while reading a recording
+///
+#[derive(Debug)]
+pub enum RecordingError {
+    /// The data does not start with the recording header
+    NotARecording,
+
+    /// The recording was written using a newer version of the format
+    UnsupportedVersion(u8),
+
+    /// The instruction in the entry starting at the specified byte offset could not be decoded
+    BadInstruction { offset: usize, error: DecoderError },
+
+    /// The recording could not be read
+    Io(io::Error),
+}
+
+impl From<io::Error> for RecordingError {
+    fn from(error: io::Error) -> RecordingError {
+        RecordingError::Io(error)
+    }
+}
+
+///
+/// Writes drawing instructions to a recording, along with the time they were written
+///
+/// Recordings can be read back using `CanvasRecording`. `record_drawing_stream()` can be used to record a stream of
+/// drawing instructions as they are read.
+///
+pub struct CanvasRecorder<Target: Write> {
+    /// Where the recording is written
+    target: Target,
+
+    /// The time that the recording started
+    start_time: Instant,
+}
+
+impl<Target: Write> CanvasRecorder<Target> {
+    ///
+    /// Starts a new recording, writing the header to the target
+    ///
+    pub fn new(target: Target) -> io::Result<CanvasRecorder<Target>> {
+        let mut target = target;
+
+        target.write_all(RECORDING_MAGIC)?;
+        target.write_all(&[CANVAS_RECORDING_VERSION])?;
+
+        Ok(CanvasRecorder {
+            target,
+            start_time: Instant::now(),
+        })
+    }
+
+    ///
+    /// Records a drawing instruction, timestamped with the time since the recording started
+    ///
+    pub fn record(&mut self, draw: &Draw) -> io::Result<()> {
+        let time = self.start_time.elapsed();
+        self.record_at(time, draw)
+    }
+
+    ///
+    /// Records a drawing instruction with a specific time since the start of the recording
+    ///
+    pub fn record_at(&mut self, time: Duration, draw: &Draw) -> io::Result<()> {
+        let mut encoded = vec![];
+        draw.encode_canvas(&mut encoded);
+
+        let time = u64::try_from(time.as_micros()).unwrap_or(u64::MAX);
+        let length = u32::try_from(encoded.len()).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "instruction is too large to record",
+            )
+        })?;
+
+        // Write the entry as a single buffer, so a failed write will be less likely to leave a partial entry
+        let mut entry = Vec::with_capacity(encoded.len() + 12);
+        entry.extend_from_slice(&time.to_le_bytes());
+        entry.extend_from_slice(&length.to_le_bytes());
+        entry.extend(encoded);
+
+        self.target.write_all(&entry)
+    }
+
+    ///
+    /// Flushes the recording to its target
+    ///
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.target.flush()
+    }
+
+    ///
+    /// Finishes the recording, returning the target it was written to
+    ///
+    pub fn into_inner(self) -> Target {
+        self.target
+    }
+}
+
+///
+/// Records the instructions from a drawing stream to a target as they are read, returning a stream that passes the
+/// instructions on unchanged, and a future that returns the result of writing the recording
+///
+/// This can be used as a 'flight recorder' for a drawing window: wrapping the stream for a `DrawingTarget` makes it
+/// possible to replay exactly what was sent to the window later on. The recording is written from a background thread,
+/// so reading the stream never blocks on the target. Writes to the target are buffered, and the recording is flushed
+/// whenever there are no more instructions waiting to be written. If the target falls too far behind, the stream waits
+/// for it to catch up.
+///
+/// If the recording can't be written, the recording is stopped but the instructions continue to be passed on. The
+/// future returns the error as soon as this happens, or `Ok(())` once the stream has finished (or has been dropped) and
+/// the recording has been flushed.
+///
+pub fn record_drawing_stream<InStream, Target>(
+    stream: InStream,
+    target: Target,
+) -> (
+    impl Unpin + Stream<Item = Draw>,
+    impl Send + Unpin + Future<Output = io::Result<()>>,
+)
+where
+    InStream: Unpin + Stream<Item = Draw>,
+    Target: 'static + Send + Write,
+{
+    let mut stream = stream;
+    let mut target = Some(target);
+    let mut recorder: Option<(Instant, mpsc::Sender<RecordedDraw>)> = None;
+    let (send_result, recv_result) = oneshot::channel();
+    let mut send_result = Some(send_result);
+
+    let recorded_stream = stream::poll_fn(move |context| {
+        // The recording starts when the stream is first read
+        if let (Some(target), Some(send_result)) = (target.take(), send_result.take()) {
+            let (send_entries, recv_entries) = mpsc::channel(MAX_PENDING_ENTRIES);
+
+            thread::spawn(move || {
+                send_result.send(write_recording(target, recv_entries)).ok();
+            });
+
+            recorder = Some((Instant::now(), send_entries));
+        }
+
+        // Wait for the recording thread if it has too many instructions waiting, or stop recording if it has stopped
+        let recording_stopped = match &mut recorder {
+            Some((_, send_entries)) => match send_entries.poll_ready(context) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(ready) => ready.is_err(),
+            },
+            None => false,
+        };
+
+        if recording_stopped {
+            recorder = None;
+        }
+
+        let next = stream.poll_next_unpin(context);
+
+        match (&next, &mut recorder) {
+            (Poll::Ready(Some(draw)), Some((start_time, send_entries))) => {
+                send_entries
+                    .start_send(RecordedDraw {
+                        time: start_time.elapsed(),
+                        draw: draw.clone(),
+                    })
+                    .ok();
+            }
+
+            // Dropping the sender finishes the recording
+            (Poll::Ready(None), _) => recorder = None,
+            _ => {}
+        }
+
+        next
+    });
+
+    let result = recv_result.map(|result| result.unwrap_or(Ok(())));
+
+    (recorded_stream, result)
+}
+
+///
+/// Writes the entries for `record_drawing_stream()` to a target until there are no more entries or an error occurs
+///
+fn write_recording(target: impl Write, entries: mpsc::Receiver<RecordedDraw>) -> io::Result<()> {
+    let mut entries = entries;
+    let mut recorder = CanvasRecorder::new(BufWriter::new(target))?;
+
+    loop {
+        let entry = match entries.try_recv() {
+            Ok(entry) => entry,
+            Err(mpsc::TryRecvError::Closed) => break,
+
+            Err(mpsc::TryRecvError::Empty) => {
+                // Flush the recording while waiting for more instructions
+                recorder.flush()?;
+
+                match executor::block_on(entries.next()) {
+                    Some(entry) => entry,
+                    None => break,
+                }
+            }
+        };
+
+        recorder.record_at(entry.time, &entry.draw)?;
+    }
+
+    recorder.flush()
+}
+
+///
+/// A recording of a set of drawing instructions, read from the data written by a `CanvasRecorder`
+///
+/// The recording is divided into frames: a frame ends when a `ShowFrame` instruction ends the outermost `StartFrame`
+/// instruction, which is the point where a drawing window would display the frame. Frame 0 starts at the beginning of
+/// the recording, and frame `n` starts after the `n`th frame has been shown.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct CanvasRecording {
+    /// The instructions in this recording
+    entries: Vec<RecordedDraw>,
+
+    /// The index of the first entry in each frame
+    frame_starts: Vec<usize>,
+
+    /// The indexes of the `ClearCanvas` entries, which are the places where a replay can start (the canvas is regenerated
+    /// from scratch after them)
+    seek_points: Vec<usize>,
+}
+
+impl CanvasRecording {
+    ///
+    /// Creates a recording from a list of recorded instructions
+    ///
+    pub fn from_entries(entries: Vec<RecordedDraw>) -> CanvasRecording {
+        let mut frame_starts = vec![0];
+        let mut seek_points = vec![];
+        let mut frame_depth = 0usize;
+
+        // Find the frame boundaries in the same way as a drawing window does
+        for (idx, entry) in entries.iter().enumerate() {
+            match &entry.draw {
+                Draw::StartFrame => frame_depth += 1,
+                Draw::ShowFrame => {
+                    frame_depth = frame_depth.saturating_sub(1);
+
+                    if frame_depth == 0 {
+                        frame_starts.push(idx + 1);
+                    }
+                }
+                Draw::ResetFrame => frame_depth = 0,
+                Draw::ClearCanvas(_) => {
+                    frame_depth = 0;
+                    seek_points.push(idx);
+                }
+                _ => {}
+            }
+        }
+
+        CanvasRecording {
+            entries,
+            frame_starts,
+            seek_points,
+        }
+    }
+
+    ///
+    /// Reads a recording from the bytes written by a `CanvasRecorder`
+    ///
+    /// A recording that ends part way through an entry (for instance, because the application that was writing it
+    /// stopped unexpectedly) is read up to the last complete entry.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<CanvasRecording, RecordingError> {
+        // Check the header
+        if bytes.len() < RECORDING_MAGIC.len() + 1 || !bytes.starts_with(RECORDING_MAGIC) {
+            return Err(RecordingError::NotARecording);
+        }
+
+        let version = bytes[RECORDING_MAGIC.len()];
+        if version > CANVAS_RECORDING_VERSION {
+            return Err(RecordingError::UnsupportedVersion(version));
+        }
+
+        // Read the entries
+        let mut entries = vec![];
+        let mut pos = RECORDING_MAGIC.len() + 1;
+
+        while bytes.len() - pos >= 12 {
+            let mut time = [0u8; 8];
+            let mut length = [0u8; 4];
+            time.copy_from_slice(&bytes[pos..(pos + 8)]);
+            length.copy_from_slice(&bytes[(pos + 8)..(pos + 12)]);
+
+            let time = Duration::from_micros(u64::from_le_bytes(time));
+            let length = u32::from_le_bytes(length) as usize;
+
+            let instruction_start = pos + 12;
+            if bytes.len() - instruction_start < length {
+                break;
+            }
+
+            let instruction = &bytes[instruction_start..(instruction_start + length)];
+            let bad_instruction = |error| RecordingError::BadInstruction { offset: pos, error };
+            // Instructions that the decoder doesn't recognise are skipped
+            match decode_binary_drawing(instruction).next() {
+                Some(Ok(draw)) => entries.push(RecordedDraw { time, draw }),
+                Some(Err(error)) => return Err(bad_instruction(error)),
+                None => {}
+            }
+            pos = instruction_start + length;
+        }
+
+        Ok(CanvasRecording::from_entries(entries))
+    }
+
+    ///
+    /// Reads a recording from a source (such as a file)
+    ///
+    pub fn read(source: impl Read) -> Result<CanvasRecording, RecordingError> {
+        let mut source = source;
+        let mut bytes = vec![];
+        source.read_to_end(&mut bytes)?;
+
+        CanvasRecording::from_bytes(&bytes)
+    }
+
+    ///
+    /// The instructions in this recording
+    ///
+    pub fn entries(&self) -> &[RecordedDraw] {
+        &self.entries
+    }
+
+    ///
+    /// The number of frames in this recording (this includes the instructions after the last frame, if there are any)
+    ///
+    pub fn frame_count(&self) -> usize {
+        if self.frame_starts.len() > 1
+            && self.frame_starts[self.frame_starts.len() - 1] >= self.entries.len()
+        {
+            self.frame_starts.len() - 1
+        } else {
+            self.frame_starts.len()
+        }
+    }
+
+    ///
+    /// The index into `entries()` of the first instruction in a frame
+    ///
+    pub fn frame_start(&self, frame: usize) -> Option<usize> {
+        if frame < self.frame_count() {
+            Some(self.frame_starts[frame])
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// The time that a frame starts, which is the time the previous frame was shown
+    ///
+    pub fn frame_time(&self, frame: usize) -> Option<Duration> {
+        let start = self.frame_start(frame)?;
+
+        if start == 0 {
+            Some(Duration::default())
+        } else {
+            Some(self.entries[start - 1].time)
+        }
+    }
+
+    ///
+    /// The index into `entries()` to start replaying from in order to display a frame
+    ///
+    /// This is the last `ClearCanvas` instruction at or before the start of the frame, which is where the canvas starts
+    /// being regenerated: the instructions before it don't need to be replayed. `ResetFrame` only resets the frame
+    /// nesting, so it's not used as a place to start. If there are no `ClearCanvas` instructions before the frame, this
+    /// is the start of the recording.
+    ///
+    pub fn seek_start(&self, frame: usize) -> Option<usize> {
+        let start = self.frame_start(frame)?;
+        let num_before = self.seek_points.partition_point(|idx| *idx <= start);
+
+        if num_before == 0 {
+            Some(0)
+        } else {
+            Some(self.seek_points[num_before - 1])
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::color::*;
+    use crate::context::*;
+    use crate::primitives::*;
+
+    use std::sync::{Arc, Mutex};
+
+    fn recording_bytes(entries: &[RecordedDraw]) -> Vec<u8> {
+        let mut recorder = CanvasRecorder::new(vec![]).unwrap();
+
+        for entry in entries.iter() {
+            recorder.record_at(entry.time, &entry.draw).unwrap();
+        }
+
+        recorder.into_inner()
+    }
+
+    fn frames(num_frames: u64) -> Vec<RecordedDraw> {
+        let mut entries = vec![];
+
+        for frame in 0..num_frames {
+            let mut drawing = vec![];
+            drawing.start_frame();
+            drawing.clear_layer();
+            drawing.rect(0.0, 0.0, frame as f32, frame as f32);
+            drawing.fill();
+            drawing.show_frame();
+
+            entries.extend(drawing.into_iter().map(|draw| RecordedDraw {
+                time: Duration::from_millis(frame * 16),
+                draw,
+            }));
+        }
+
+        entries
+    }
+
+    #[test]
+    fn round_trip_recording() {
+        let entries = frames(3);
+        let recording = CanvasRecording::from_bytes(&recording_bytes(&entries)).unwrap();
+
+        assert!(recording.entries() == &entries[..]);
+    }
+
+    #[test]
+    fn recording_starts_with_header() {
+        let bytes = recording_bytes(&frames(1));
+
+        assert!(bytes.starts_with(b"FLOREC\x01"));
+    }
+
+    #[test]
+    fn not_a_recording() {
+        let result = CanvasRecording::from_bytes(b"Not a recording");
+
+        assert!(matches!(result, Err(RecordingError::NotARecording)));
+    }
+
+    #[test]
+    fn newer_version_is_unsupported() {
+        let result = CanvasRecording::from_bytes(b"FLOREC\x02");
+
+        assert!(matches!(result, Err(RecordingError::UnsupportedVersion(2))));
+    }
+
+    #[test]
+    fn truncated_recording_keeps_complete_entries() {
+        let entries = frames(2);
+        let bytes = recording_bytes(&entries);
+
+        let recording = CanvasRecording::from_bytes(&bytes[0..(bytes.len() - 3)]).unwrap();
+
+        assert!(recording.entries() == &entries[0..(entries.len() - 1)]);
+    }
+
+    #[test]
+    fn bad_instruction_reports_offset() {
+        let mut bytes = RECORDING_MAGIC.to_vec();
+        bytes.push(CANVAS_RECORDING_VERSION);
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        bytes.extend_from_slice(&[2, b'W', 0xff]);
+
+        let result = CanvasRecording::from_bytes(&bytes);
+
+        assert!(
+            matches!(
+                result,
+                Err(RecordingError::BadInstruction { offset: 7, .. })
+            ),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn find_frames() {
+        let recording = CanvasRecording::from_entries(frames(3));
+        let frame_length = recording.entries().len() / 3;
+
+        assert!(recording.frame_count() == 3);
+        assert!(recording.frame_start(0) == Some(0));
+        assert!(recording.frame_start(1) == Some(frame_length));
+        assert!(recording.frame_start(2) == Some(frame_length * 2));
+        assert!(recording.frame_start(3).is_none());
+        assert!(recording.frame_time(2) == Some(Duration::from_millis(16)));
+    }
+
+    #[test]
+    fn nested_frames_are_one_frame() {
+        let mut drawing = vec![];
+        drawing.start_frame();
+        drawing.start_frame();
+        drawing.show_frame();
+        drawing.show_frame();
+        drawing.clear_layer();
+
+        let recording = CanvasRecording::from_entries(
+            drawing
+                .into_iter()
+                .map(|draw| RecordedDraw {
+                    time: Duration::default(),
+                    draw,
+                })
+                .collect(),
+        );
+
+        assert!(recording.frame_count() == 2);
+        assert!(recording.frame_start(1) == Some(4));
+    }
+
+    #[test]
+    fn record_stream() {
+        let mut drawing = vec![];
+        drawing.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+        drawing.circle(0.0, 0.0, 10.0);
+        drawing.fill();
+
+        let recording = SharedWriter::default();
+        let (recorded, result) =
+            record_drawing_stream(stream::iter(drawing.clone()), recording.clone());
+        let passed_on = executor::block_on(recorded.collect::<Vec<_>>());
+
+        assert!(executor::block_on(result).is_ok());
+
+        let recording = CanvasRecording::from_bytes(&recording.0.lock().unwrap()).unwrap();
+
+        assert!(passed_on == drawing);
+        assert!(
+            recording
+                .entries()
+                .iter()
+                .map(|entry| entry.draw.clone())
+                .collect::<Vec<_>>()
+                == drawing
+        );
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("write failed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Err(io::Error::other("flush failed"))
+        }
+    }
+
+    #[test]
+    fn record_stream_reports_errors() {
+        let drawing = frames(2)
+            .into_iter()
+            .map(|entry| entry.draw)
+            .collect::<Vec<_>>();
+
+        let (recorded, result) =
+            record_drawing_stream(stream::iter(drawing.clone()), FailingWriter);
+        let passed_on = executor::block_on(recorded.collect::<Vec<_>>());
+
+        // The instructions are still passed on when the recording fails
+        assert!(passed_on == drawing);
+        assert!(executor::block_on(result).is_err());
+    }
+
+    #[test]
+    fn seek_to_last_clear() {
+        let mut drawing = vec![];
+        drawing.start_frame();
+        drawing.circle(0.0, 0.0, 10.0);
+        drawing.show_frame();
+        drawing.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+        drawing.start_frame();
+        drawing.fill();
+        drawing.show_frame();
+        drawing.start_frame();
+        drawing.fill();
+        drawing.show_frame();
+
+        let recording = CanvasRecording::from_entries(
+            drawing
+                .into_iter()
+                .map(|draw| RecordedDraw {
+                    time: Duration::default(),
+                    draw,
+                })
+                .collect(),
+        );
+        let clear_idx = recording
+            .entries()
+            .iter()
+            .position(|entry| matches!(entry.draw, Draw::ClearCanvas(_)))
+            .unwrap();
+
+        assert!(recording.frame_count() == 3);
+        assert!(recording.seek_start(0) == Some(0));
+        assert!(recording.seek_start(1) == Some(clear_idx));
+        assert!(recording.seek_start(2) == Some(clear_idx));
+        assert!(recording.seek_start(3).is_none());
+    }
+
+    #[test]
+    fn reset_frame_is_not_a_seek_point() {
+        let mut drawing = vec![];
+        drawing.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+        drawing.start_frame();
+        drawing.circle(0.0, 0.0, 10.0);
+        drawing.fill();
+        drawing.show_frame();
+        drawing.push(Draw::ResetFrame);
+        drawing.start_frame();
+        drawing.fill();
+        drawing.show_frame();
+
+        let recording = CanvasRecording::from_entries(
+            drawing
+                .into_iter()
+                .map(|draw| RecordedDraw {
+                    time: Duration::default(),
+                    draw,
+                })
+                .collect(),
+        );
+
+        // The second frame still needs the circle drawn before the ResetFrame
+        assert!(recording.frame_count() == 2);
+        assert!(recording.seek_start(1) == Some(0));
+    }
+}
@@ -0,0 +1,19 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//!
+//! Sending canvas drawings to other processes using the text encoding
+//!
+//! A connection is a version header (see `encode_canvas_header()`) followed by the encoded drawing instructions. The
+//! server starts every connection with the instructions needed to recreate the current drawing, so clients can join
+//! at any time.
+//!
+
+mod remote_client;
+mod remote_server;
+
+pub use self::remote_client::*;
+pub use self::remote_server::*;
@@ -0,0 +1,363 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::canvas::*;
+use crate::decoding::*;
+use crate::draw::*;
+
+use futures::channel::mpsc;
+use futures::executor;
+use futures::prelude::*;
+use futures::stream;
+
+use std::io;
+use std::io::Read;
+use std::str;
+use std::thread;
+
+///
+/// Reads the characters sent by a remote canvas server
+///
+/// The connection is read from a background thread and sent to the stream in chunks
+///
+fn remote_characters(
+    connection: impl 'static + Send + Read,
+) -> impl Send + Unpin + Stream<Item = Result<char, io::Error>> {
+    let (mut sender, receiver) = mpsc::channel::<Result<String, io::Error>>(16);
+
+    thread::spawn(move || {
+        let mut connection = connection;
+        let mut buffer = vec![0u8; 65536];
+        let mut partial = vec![];
+
+        loop {
+            let chunk = match connection.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(len) => {
+                    partial.extend_from_slice(&buffer[0..len]);
+                    take_utf8(&mut partial)
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => Err(err),
+            };
+
+            let is_error = chunk.is_err();
+            if executor::block_on(sender.send(chunk)).is_err() || is_error {
+                break;
+            }
+        }
+    });
+
+    receiver.flat_map(|chunk| match chunk {
+        Ok(chunk) => stream::iter(chunk.chars().map(Ok).collect::<Vec<_>>()),
+        Err(err) => stream::iter(vec![Err(err)]),
+    })
+}
+
+///
+/// Removes the complete UTF-8 characters from the start of a buffer, leaving behind any partial character at the end
+///
+fn take_utf8(buffer: &mut Vec<u8>) -> Result<String, io::Error> {
+    let valid_len = match str::from_utf8(buffer) {
+        Ok(_) => buffer.len(),
+        Err(err) if err.error_len().is_none() => err.valid_up_to(),
+        Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+    };
+
+    let remaining = buffer.split_off(valid_len);
+    let chunk = String::from_utf8(std::mem::replace(buffer, remaining))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    Ok(chunk)
+}
+
+///
+/// Decodes the drawing instructions sent by a `CanvasServer`
+///
+/// This is typically used with a `TcpStream` or a `UnixStream` connected to the server. The connection is read from a
+/// background thread. The stream ends when the connection is closed, or after the first error.
+///
+pub fn remote_drawing_stream(
+    connection: impl 'static + Send + Read,
+) -> impl Send + Unpin + Stream<Item = Result<Draw, StreamDecoderError<io::Error>>> {
+    let mut seen_error = false;
+
+    decode_drawing_stream(remote_characters(connection)).take_while(move |draw| {
+        let continue_stream = !seen_error;
+        seen_error = seen_error || draw.is_err();
+
+        future::ready(continue_stream)
+    })
+}
+
+///
+/// Creates a canvas that mirrors the drawing sent by a `CanvasServer`
+///
+/// The canvas is updated from a background thread until the connection is closed or an error is received. It can be
+/// displayed or rendered like any other canvas (for example, by passing the result of `Canvas::stream()` to a window).
+///
+pub fn remote_canvas(connection: impl 'static + Send + Read) -> Canvas {
+    let canvas = Canvas::new();
+    let remote = canvas.clone();
+
+    thread::spawn(move || {
+        executor::block_on(async move {
+            let mut instructions = remote_drawing_stream(connection).ready_chunks(1000);
+
+            while let Some(instructions) = instructions.next().await {
+                let (instructions, errors): (Vec<_>, Vec<_>) =
+                    instructions.into_iter().partition(|draw| draw.is_ok());
+
+                remote.write(instructions.into_iter().flatten().collect());
+
+                if !errors.is_empty() {
+                    break;
+                }
+            }
+        })
+    });
+
+    canvas
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::color::*;
+    use crate::context::*;
+    use crate::primitives::*;
+    use crate::remote::*;
+
+    use std::io::Write;
+    use std::net::{TcpListener, TcpStream};
+    use std::time::{Duration, Instant};
+
+    fn circle(radius: f32) -> Vec<Draw> {
+        let mut drawing = vec![];
+        drawing.new_path();
+        drawing.circle(100.0, 100.0, radius);
+        drawing.fill_color(Color::Rgba(0.2, 0.4, 0.6, 1.0));
+        drawing.fill();
+        drawing
+    }
+
+    fn tcp_server(canvas: &Canvas) -> (CanvasServer, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = CanvasServer::for_canvas(canvas);
+        server.listen_tcp(listener);
+
+        (server, TcpStream::connect(address).unwrap())
+    }
+
+    ///
+    /// Reads from a stream of remote instructions until the specified instruction is read
+    ///
+    fn read_until(
+        stream: &mut (impl Unpin + Stream<Item = Result<Draw, StreamDecoderError<io::Error>>>),
+        until: &Draw,
+    ) -> Vec<Draw> {
+        executor::block_on(async {
+            let mut result = vec![];
+
+            while let Some(draw) = stream.next().await {
+                let draw = draw.unwrap();
+                let finished = &draw == until;
+
+                result.push(draw);
+                if finished {
+                    break;
+                }
+            }
+
+            result
+        })
+    }
+
+    ///
+    /// Waits for a condition to become true, for up to 10 seconds
+    ///
+    fn wait_for(condition: impl Fn() -> bool) -> bool {
+        let start = Instant::now();
+
+        while start.elapsed() < Duration::from_secs(10) {
+            if condition() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        false
+    }
+
+    #[test]
+    fn utf8_split_across_reads() {
+        let mut buffer = "ab\u{2603}".as_bytes().to_vec();
+        buffer.pop();
+
+        assert!(take_utf8(&mut buffer).unwrap() == "ab");
+        assert!(buffer.len() == 2);
+
+        buffer.push(0x83);
+        assert!(take_utf8(&mut buffer).unwrap() == "\u{2603}");
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn client_receives_drawing() {
+        let canvas = Canvas::new();
+        let (_server, connection) = tcp_server(&canvas);
+        let mut client = remote_drawing_stream(connection);
+
+        canvas.write(circle(50.0));
+
+        let received = read_until(&mut client, &Draw::Fill);
+        assert!(received.ends_with(&circle(50.0)), "{:?}", received);
+    }
+
+    #[test]
+    fn late_client_receives_snapshot() {
+        let canvas = Canvas::new();
+        canvas.write(vec![Draw::ClearCanvas(Color::Rgba(1.0, 1.0, 1.0, 1.0))]);
+        canvas.write(circle(50.0));
+
+        let (server, connection) = tcp_server(&canvas);
+        let mut first_client = remote_drawing_stream(connection);
+        read_until(&mut first_client, &Draw::Fill);
+
+        // Connect a second client once the first has seen the drawing
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        server.listen_tcp(listener);
+
+        let mut late_client = remote_drawing_stream(TcpStream::connect(address).unwrap());
+        let snapshot = read_until(&mut late_client, &Draw::Fill);
+
+        assert!(snapshot[0] == Draw::ResetFrame);
+        assert!(snapshot.contains(&Draw::ClearCanvas(Color::Rgba(1.0, 1.0, 1.0, 1.0))));
+        assert!(snapshot.ends_with(&circle(50.0)), "{:?}", snapshot);
+
+        // Both clients receive further updates
+        canvas.write(vec![Draw::ClearLayer]);
+        canvas.write(circle(20.0));
+
+        assert!(read_until(&mut first_client, &Draw::Fill).ends_with(&circle(20.0)));
+        assert!(read_until(&mut late_client, &Draw::Fill).ends_with(&circle(20.0)));
+    }
+
+    #[test]
+    fn remote_canvas_mirrors_drawing() {
+        let canvas = Canvas::new();
+        let (server, connection) = tcp_server(&canvas);
+        let remote = remote_canvas(connection);
+
+        canvas.write(vec![Draw::ClearCanvas(Color::Rgba(0.0, 0.0, 0.0, 1.0))]);
+        canvas.write(circle(30.0));
+
+        // The server and the client also receive the frame instructions that the canvas generates when it's written to
+        let without_frames = |drawing: Vec<Draw>| {
+            drawing
+                .into_iter()
+                .filter(|draw| {
+                    !matches!(draw, Draw::StartFrame | Draw::ShowFrame | Draw::ResetFrame)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert!(wait_for(
+            || without_frames(server.get_drawing()) == canvas.get_drawing()
+        ));
+        assert!(wait_for(
+            || without_frames(remote.get_drawing()) == canvas.get_drawing()
+        ));
+    }
+
+    #[test]
+    fn client_disconnected_when_drawing_finishes() {
+        let (mut sender, receiver) = mpsc::channel(10);
+        let server = CanvasServer::new(receiver);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        server.listen_tcp(listener);
+
+        let mut client = remote_drawing_stream(TcpStream::connect(address).unwrap());
+        assert!(wait_for(|| server.client_count() == 1));
+
+        executor::block_on(async {
+            for draw in circle(40.0) {
+                sender.send(draw).await.unwrap();
+            }
+        });
+        drop(sender);
+
+        let received = executor::block_on(client.by_ref().collect::<Vec<_>>());
+        let received = received
+            .into_iter()
+            .map(|draw| draw.unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(received.ends_with(&circle(40.0)), "{:?}", received);
+        assert!(server.client_count() == 0);
+    }
+
+    ///
+    /// A connection that never finishes writing anything until it's dropped
+    ///
+    struct StalledConnection(std::sync::mpsc::Receiver<()>);
+
+    impl Write for StalledConnection {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            self.0.recv().ok();
+            Err(io::Error::from(io::ErrorKind::BrokenPipe))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn client_that_stops_reading_is_disconnected() {
+        let canvas = Canvas::new();
+        let server = CanvasServer::for_canvas(&canvas);
+
+        let (_stall, stalled) = std::sync::mpsc::channel();
+        server.add_client(StalledConnection(stalled));
+        assert!(server.client_count() == 1);
+
+        // Keep drawing until the server gives up on the client
+        assert!(wait_for(|| {
+            canvas.write(circle(10.0));
+            server.client_count() == 0
+        }));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_socket_client() {
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        let path = std::env::temp_dir().join(format!(
+            "flo_canvas_remote_test_{}.sock",
+            std::process::id()
+        ));
+        std::fs::remove_file(&path).ok();
+
+        let canvas = Canvas::new();
+        canvas.write(circle(10.0));
+
+        let server = CanvasServer::for_canvas(&canvas);
+        server.listen_unix(UnixListener::bind(&path).unwrap());
+
+        let mut client = remote_drawing_stream(UnixStream::connect(&path).unwrap());
+        let received = read_until(&mut client, &Draw::Fill);
+
+        std::fs::remove_file(&path).ok();
+        assert!(received.ends_with(&circle(10.0)), "{:?}", received);
+    }
+}
@@ -0,0 +1,205 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::canvas::*;
+use crate::draw::*;
+use crate::encoding::*;
+
+use futures::executor;
+use futures::prelude::*;
+
+use std::io::Write;
+use std::iter;
+use std::net::TcpListener;
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+use std::sync::mpsc;
+use std::sync::*;
+use std::thread;
+
+/// The number of updates that can be waiting to be written to a client before it's disconnected
+const MAX_PENDING_UPDATES: usize = 64;
+
+///
+/// The shared state of a canvas server
+///
+struct CanvasServerCore {
+    /// A copy of the drawing sent to the server, used to create the snapshot sent to new clients
+    canvas: Canvas,
+
+    /// The channels used to send encoded instructions to each client
+    clients: Vec<mpsc::SyncSender<Arc<String>>>,
+
+    /// Set to true once the drawing stream has finished
+    finished: bool,
+}
+
+///
+/// Serves a stream of drawing instructions to remote clients
+///
+/// Every client receives the text encoding of the drawing (see `remote_drawing_stream()` or `remote_canvas()` for a way
+/// to read it). Clients that connect after the drawing has started are sent a snapshot of the drawing so far before
+/// any new instructions, so they see the same picture as a client that has been connected since the start.
+///
+/// Each client is served from its own thread, so a slow client will not hold up the others. A client is disconnected
+/// if it can't be written to, if it falls too far behind the drawing (as the updates waiting to be sent to it would
+/// otherwise grow without limit), or when the drawing stream ends.
+///
+#[derive(Clone)]
+pub struct CanvasServer {
+    core: Arc<Mutex<CanvasServerCore>>,
+}
+
+impl CanvasServer {
+    ///
+    /// Creates a server that will send the instructions from a drawing stream to its clients
+    ///
+    /// This is usually used with the stream returned by `Canvas::stream()`: see `CanvasServer::for_canvas()`.
+    ///
+    pub fn new(drawing: impl 'static + Send + Stream<Item = Draw>) -> CanvasServer {
+        let core = CanvasServerCore {
+            canvas: Canvas::new(),
+            clients: vec![],
+            finished: false,
+        };
+        let core = Arc::new(Mutex::new(core));
+
+        // Read the drawing from a background thread
+        let pump_core = Arc::clone(&core);
+        thread::spawn(move || {
+            executor::block_on(async move {
+                let mut drawing = drawing.ready_chunks(1000).boxed();
+
+                while let Some(instructions) = drawing.next().await {
+                    Self::send_to_clients(&pump_core, instructions);
+                }
+
+                // Disconnect all of the clients once the drawing is finished
+                let mut core = pump_core.lock().unwrap();
+                core.finished = true;
+                core.clients.clear();
+            })
+        });
+
+        CanvasServer { core }
+    }
+
+    ///
+    /// Creates a server that sends what's drawn on a canvas to its clients
+    ///
+    pub fn for_canvas(canvas: &Canvas) -> CanvasServer {
+        Self::new(canvas.stream())
+    }
+
+    ///
+    /// Sends some new instructions to all of the clients
+    ///
+    fn send_to_clients(core: &Mutex<CanvasServerCore>, instructions: Vec<Draw>) {
+        let mut encoded = String::new();
+        instructions.encode_canvas(&mut encoded);
+        let encoded = Arc::new(encoded);
+
+        let mut core = core.lock().unwrap();
+
+        // The snapshot for new clients is updated at the same time as the instructions are sent so no client misses any instructions
+        core.canvas.write(instructions);
+
+        // Clients that have disconnected or that have too many updates waiting are removed
+        core.clients
+            .retain(|client| client.try_send(Arc::clone(&encoded)).is_ok());
+    }
+
+    ///
+    /// Starts sending the drawing to a new client
+    ///
+    /// The client is sent a snapshot of the current drawing followed by any further instructions. This can be used to
+    /// send the drawing over connections other than the ones supported by `listen_tcp()` or `listen_unix()`.
+    ///
+    pub fn add_client(&self, connection: impl 'static + Send + Write) {
+        let (sender, receiver) = mpsc::sync_channel::<Arc<String>>(MAX_PENDING_UPDATES);
+
+        {
+            let mut core = self.core.lock().unwrap();
+
+            // The snapshot is sent in the same way as a new canvas stream would receive it
+            let mut snapshot = String::new();
+            encode_canvas_header(&mut snapshot);
+            iter::once(Draw::ResetFrame)
+                .chain(core.canvas.get_drawing())
+                .collect::<Vec<_>>()
+                .encode_canvas(&mut snapshot);
+
+            sender.try_send(Arc::new(snapshot)).ok();
+
+            // The connection is closed after the snapshot if the drawing has already finished
+            if !core.finished {
+                core.clients.push(sender);
+            }
+        }
+
+        // Write to the client from its own thread
+        thread::spawn(move || {
+            let mut connection = connection;
+
+            while let Ok(encoded) = receiver.recv() {
+                if connection.write_all(encoded.as_bytes()).is_err() {
+                    break;
+                }
+                if connection.flush().is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    ///
+    /// Accepts clients from a TCP listener
+    ///
+    /// The listener is run on a background thread, which continues to accept connections for as long as the listener
+    /// is open.
+    ///
+    pub fn listen_tcp(&self, listener: TcpListener) {
+        let server = self.clone();
+
+        thread::spawn(move || {
+            for connection in listener.incoming().flatten() {
+                connection.set_nodelay(true).ok();
+                server.add_client(connection);
+            }
+        });
+    }
+
+    ///
+    /// Accepts clients from a unix domain socket listener
+    ///
+    /// The listener is run on a background thread, which continues to accept connections for as long as the listener
+    /// is open.
+    ///
+    #[cfg(unix)]
+    pub fn listen_unix(&self, listener: UnixListener) {
+        let server = self.clone();
+
+        thread::spawn(move || {
+            for connection in listener.incoming().flatten() {
+                server.add_client(connection);
+            }
+        });
+    }
+
+    ///
+    /// The number of clients that are currently receiving the drawing
+    ///
+    pub fn client_count(&self) -> usize {
+        self.core.lock().unwrap().clients.len()
+    }
+
+    ///
+    /// The drawing that will be sent to any new clients
+    ///
+    pub fn get_drawing(&self) -> Vec<Draw> {
+        self.core.lock().unwrap().canvas.get_drawing()
+    }
+}
@@ -0,0 +1,158 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//!
+//! Serialization for blocks of binary data (font files and texture bitmaps)
+//!
+//! Human-readable formats such as JSON store the data as a base64 string, and binary formats such as MessagePack store
+//! it as a byte array. Data written as a list of numbers (by earlier versions of this crate) can still be read.
+//!
+
+use base64::engine::general_purpose;
+use base64::Engine;
+
+use serde::de;
+use serde::de::{Deserializer, SeqAccess, Visitor};
+use serde::ser::Serializer;
+
+use std::fmt;
+use std::sync::*;
+
+///
+/// Wrapper that serializes a byte slice as a block of data
+///
+pub(crate) struct SerializedData<'a>(pub &'a [u8]);
+
+///
+/// Wrapper that deserializes a block of data as a byte vec
+///
+pub(crate) struct DeserializedData(pub Vec<u8>);
+
+impl<'a> serde::Serialize for SerializedData<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&general_purpose::STANDARD.encode(self.0))
+        } else {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for DeserializedData {
+    fn deserialize<D>(deserializer: D) -> Result<DeserializedData, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct DataVisitor;
+
+        impl<'de> Visitor<'de> for DataVisitor {
+            type Value = DeserializedData;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a base64 string or a byte array")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<DeserializedData, E>
+            where
+                E: de::Error,
+            {
+                general_purpose::STANDARD
+                    .decode(value)
+                    .map(DeserializedData)
+                    .map_err(|_| de::Error::invalid_value(de::Unexpected::Str(value), &self))
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<DeserializedData, E>
+            where
+                E: de::Error,
+            {
+                Ok(DeserializedData(value.to_vec()))
+            }
+
+            fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<DeserializedData, E>
+            where
+                E: de::Error,
+            {
+                Ok(DeserializedData(value))
+            }
+
+            fn visit_seq<V>(self, mut seq: V) -> Result<DeserializedData, V::Error>
+            where
+                V: SeqAccess<'de>,
+            {
+                let mut data = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    data.push(byte);
+                }
+
+                Ok(DeserializedData(data))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(DataVisitor)
+        } else {
+            deserializer.deserialize_byte_buf(DataVisitor)
+        }
+    }
+}
+
+///
+/// `#[serde(with = ...)]` implementation for `Arc<Vec<u8>>` fields
+///
+pub(crate) mod arc_data {
+    use super::*;
+
+    use serde::{Deserialize, Serialize};
+
+    pub fn serialize<S>(data: &Arc<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        SerializedData(data).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Arc<Vec<u8>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let DeserializedData(data) = DeserializedData::deserialize(deserializer)?;
+        Ok(Arc::new(data))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Bitmap {
+        #[serde(with = "arc_data")]
+        data: Arc<Vec<u8>>,
+    }
+
+    #[test]
+    fn data_is_base64_in_json() {
+        let bitmap = Bitmap {
+            data: Arc::new(vec![1, 2, 3, 4, 5]),
+        };
+        let json = serde_json::to_string(&bitmap).unwrap();
+
+        assert!(json == "{\"data\":\"AQIDBAU=\"}", "{}", json);
+        assert!(serde_json::from_str::<Bitmap>(&json).unwrap() == bitmap);
+    }
+
+    #[test]
+    fn read_data_as_list_of_numbers() {
+        let bitmap = serde_json::from_str::<Bitmap>("{\"data\":[1,2,3,4,5]}").unwrap();
+
+        assert!(*bitmap.data == vec![1, 2, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,19 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//!
+//! Conversion of canvas drawings to and from the SVG format
+//!
+
+mod svg_format;
+mod svg_parse;
+mod svg_path_data;
+mod svg_reader;
+mod svg_resources;
+mod svg_writer;
+
+pub use self::svg_reader::*;
+pub use self::svg_writer::*;
@@ -0,0 +1,160 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::color::*;
+use crate::draw::*;
+use crate::path::*;
+use crate::transform2d::*;
+
+use std::fmt::Write;
+
+///
+/// Formats a number for use in an SVG attribute
+///
+#[inline]
+pub fn svg_number(value: f32) -> String {
+    if value.is_finite() {
+        // Rust generates the shortest representation that will read back as the same value
+        format!("{}", value)
+    } else {
+        "0".to_string()
+    }
+}
+
+///
+/// Returns the RGB part of a colour as a hex string and its alpha component
+///
+pub fn svg_color(color: &Color) -> (String, f32) {
+    let (r, g, b, a) = color.to_rgba_components();
+    let to_byte = |component: f32| (component.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    (
+        format!("#{:02x}{:02x}{:02x}", to_byte(r), to_byte(g), to_byte(b)),
+        a.clamp(0.0, 1.0),
+    )
+}
+
+///
+/// Formats a transformation as the parameters of a SVG 'matrix()' transform
+///
+pub fn svg_matrix(transform: &Transform2D) -> String {
+    let Transform2D(m) = transform;
+
+    format!(
+        "matrix({} {} {} {} {} {})",
+        svg_number(m[0][0]),
+        svg_number(m[1][0]),
+        svg_number(m[0][1]),
+        svg_number(m[1][1]),
+        svg_number(m[0][2]),
+        svg_number(m[1][2])
+    )
+}
+
+///
+/// Converts a list of path operations to the contents of a SVG path data attribute
+///
+pub fn svg_path_data(path: &[PathOp]) -> String {
+    use self::PathOp::*;
+
+    let mut path_data = String::new();
+
+    for path_op in path.iter() {
+        if !path_data.is_empty() {
+            path_data.push(' ');
+        }
+
+        match path_op {
+            NewPath => path_data.clear(),
+            Move(x, y) => {
+                write!(path_data, "M{} {}", svg_number(*x), svg_number(*y)).ok();
+            }
+            Line(x, y) => {
+                write!(path_data, "L{} {}", svg_number(*x), svg_number(*y)).ok();
+            }
+            BezierCurve(((cp1x, cp1y), (cp2x, cp2y)), (x, y)) => {
+                write!(
+                    path_data,
+                    "C{} {} {} {} {} {}",
+                    svg_number(*cp1x),
+                    svg_number(*cp1y),
+                    svg_number(*cp2x),
+                    svg_number(*cp2y),
+                    svg_number(*x),
+                    svg_number(*y)
+                )
+                .ok();
+            }
+            ClosePath => path_data.push('Z'),
+        }
+    }
+
+    path_data
+}
+
+///
+/// Returns the CSS 'mix-blend-mode' that corresponds to a blend mode
+///
+/// The Porter-Duff modes other than 'source over' have no equivalent in SVG, so these return None and are drawn using the
+/// normal blend mode.
+///
+pub fn svg_blend_mode(blend_mode: BlendMode) -> Option<&'static str> {
+    use self::BlendMode::*;
+
+    match blend_mode {
+        Multiply => Some("multiply"),
+        Screen => Some("screen"),
+        Darken => Some("darken"),
+        Lighten => Some("lighten"),
+        Overlay => Some("overlay"),
+        ColorDodge => Some("color-dodge"),
+        ColorBurn => Some("color-burn"),
+        HardLight => Some("hard-light"),
+        SoftLight => Some("soft-light"),
+        Difference => Some("difference"),
+        Exclusion => Some("exclusion"),
+        Hue => Some("hue"),
+        Saturation => Some("saturation"),
+        Color => Some("color"),
+        Luminosity => Some("luminosity"),
+        Plus => Some("plus-lighter"),
+
+        SourceOver | SourceIn | SourceOut | DestinationOver | DestinationIn | DestinationOut
+        | SourceAtop | DestinationAtop | Xor | Clear | Copy | Destination => None,
+    }
+}
+
+///
+/// Returns the value of the 'stroke-linejoin' attribute for a line join
+///
+pub fn svg_line_join(join: LineJoin) -> &'static str {
+    match join {
+        LineJoin::Miter => "miter",
+        LineJoin::Round => "round",
+        LineJoin::Bevel => "bevel",
+    }
+}
+
+///
+/// Returns the value of the 'stroke-linecap' attribute for a line cap
+///
+pub fn svg_line_cap(cap: LineCap) -> &'static str {
+    match cap {
+        LineCap::Butt => "butt",
+        LineCap::Round => "round",
+        LineCap::Square => "square",
+    }
+}
+
+///
+/// Returns the value of the 'fill-rule' attribute for a winding rule
+///
+pub fn svg_fill_rule(winding_rule: WindingRule) -> &'static str {
+    match winding_rule {
+        WindingRule::NonZero => "nonzero",
+        WindingRule::EvenOdd => "evenodd",
+    }
+}
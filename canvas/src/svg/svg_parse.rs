@@ -0,0 +1,758 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::color::*;
+use crate::transform2d::*;
+
+use std::f32;
+
+///
+/// The colours that can be referred to by name in a SVG document
+///
+const SVG_NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+///
+/// The value of a 'fill' or 'stroke' property
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum SvgPaint {
+    /// Nothing is painted
+    None,
+
+    /// Paint using a solid colour
+    Color(Color),
+
+    /// Paint using the colour from the 'color' property
+    CurrentColor,
+
+    /// Paint using the element with the specified ID (a gradient), or the fallback if the element can't be used
+    Reference(String, Option<Color>),
+}
+
+///
+/// Reads the numbers and flags that make up the values of SVG attributes
+///
+pub struct SvgTokens<'a> {
+    /// The characters being read
+    chars: &'a [u8],
+
+    /// The position of the next character to read
+    pos: usize,
+}
+
+impl<'a> SvgTokens<'a> {
+    ///
+    /// Creates a reader for the numbers in a string
+    ///
+    pub fn new(value: &'a str) -> SvgTokens<'a> {
+        SvgTokens {
+            chars: value.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    ///
+    /// Skips whitespace
+    ///
+    pub fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    ///
+    /// Skips whitespace and at most one comma
+    ///
+    pub fn skip_separator(&mut self) {
+        self.skip_whitespace();
+
+        if self.pos < self.chars.len() && self.chars[self.pos] == b',' {
+            self.pos += 1;
+            self.skip_whitespace();
+        }
+    }
+
+    ///
+    /// True if there are no more characters to read
+    ///
+    pub fn is_at_end(&self) -> bool {
+        self.pos >= self.chars.len()
+    }
+
+    ///
+    /// Returns the next character without reading it
+    ///
+    pub fn peek(&self) -> Option<u8> {
+        self.chars.get(self.pos).copied()
+    }
+
+    ///
+    /// Reads the next character
+    ///
+    pub fn next_char(&mut self) -> Option<u8> {
+        let next = self.peek();
+        if next.is_some() {
+            self.pos += 1;
+        }
+        next
+    }
+
+    ///
+    /// Reads the next number, followed by any separator
+    ///
+    pub fn next_number(&mut self) -> Option<f32> {
+        self.skip_whitespace();
+
+        let start = self.pos;
+        let chars = self.chars;
+        let digits = |pos: &mut usize| {
+            let start = *pos;
+            while *pos < chars.len() && chars[*pos].is_ascii_digit() {
+                *pos += 1;
+            }
+            *pos > start
+        };
+
+        let mut pos = self.pos;
+        if pos < chars.len() && (chars[pos] == b'+' || chars[pos] == b'-') {
+            pos += 1;
+        }
+
+        let mut has_digits = digits(&mut pos);
+        if pos < chars.len() && chars[pos] == b'.' {
+            pos += 1;
+            has_digits = digits(&mut pos) || has_digits;
+        }
+
+        if !has_digits {
+            return None;
+        }
+
+        // The exponent is only part of the number if it's followed by some digits (so units like 'em' and 'ex' are left alone)
+        if pos < chars.len() && (chars[pos] == b'e' || chars[pos] == b'E') {
+            let mut exponent_pos = pos + 1;
+            if exponent_pos < chars.len()
+                && (chars[exponent_pos] == b'+' || chars[exponent_pos] == b'-')
+            {
+                exponent_pos += 1;
+            }
+
+            if digits(&mut exponent_pos) {
+                pos = exponent_pos;
+            }
+        }
+
+        let number = std::str::from_utf8(&chars[start..pos]).ok()?.parse().ok()?;
+        self.pos = pos;
+        self.skip_separator();
+
+        Some(number)
+    }
+
+    ///
+    /// Reads a flag (a single '0' or '1', as used for the arc command in path data), followed by any separator
+    ///
+    pub fn next_flag(&mut self) -> Option<bool> {
+        self.skip_whitespace();
+
+        let flag = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => {
+                return None;
+            }
+        };
+
+        self.pos += 1;
+        self.skip_separator();
+
+        Some(flag)
+    }
+
+    ///
+    /// Reads an identifier (a run of letters and '-' characters)
+    ///
+    pub fn next_identifier(&mut self) -> &'a str {
+        self.skip_whitespace();
+
+        let start = self.pos;
+        while self.pos < self.chars.len()
+            && (self.chars[self.pos].is_ascii_alphabetic() || self.chars[self.pos] == b'-')
+        {
+            self.pos += 1;
+        }
+
+        std::str::from_utf8(&self.chars[start..self.pos]).unwrap_or("")
+    }
+
+    ///
+    /// Returns the remaining characters
+    ///
+    pub fn remaining(&self) -> &'a str {
+        std::str::from_utf8(&self.chars[self.pos..]).unwrap_or("")
+    }
+}
+
+///
+/// Reads a list of numbers, stopping at the first value that is not a number
+///
+pub fn svg_parse_number_list(value: &str) -> Vec<f32> {
+    let mut tokens = SvgTokens::new(value);
+    let mut numbers = vec![];
+
+    while let Some(number) = tokens.next_number() {
+        numbers.push(number);
+    }
+
+    numbers
+}
+
+///
+/// Reads a number, which can be followed by a '%' to indicate a fraction (so '50%' and '0.5' both read as 0.5)
+///
+pub fn svg_parse_fraction(value: &str) -> Option<f32> {
+    let mut tokens = SvgTokens::new(value);
+    let number = tokens.next_number()?;
+
+    if tokens.remaining().trim() == "%" {
+        Some(number / 100.0)
+    } else if tokens.is_at_end() {
+        Some(number)
+    } else {
+        None
+    }
+}
+
+///
+/// Reads a length, converting it to user units
+///
+/// Percentages are relative to `percent_of`, and 'em' and 'ex' units are relative to `font_size`.
+///
+pub fn svg_parse_length(value: &str, percent_of: f32, font_size: f32) -> Option<f32> {
+    let mut tokens = SvgTokens::new(value);
+    let number = tokens.next_number()?;
+
+    let scale = match tokens.remaining().trim() {
+        "" | "px" => 1.0,
+        "%" => percent_of / 100.0,
+        "em" => font_size,
+        "ex" => font_size * 0.5,
+        "pt" => 96.0 / 72.0,
+        "pc" => 16.0,
+        "in" => 96.0,
+        "cm" => 96.0 / 2.54,
+        "mm" => 96.0 / 25.4,
+        _ => {
+            return None;
+        }
+    };
+
+    Some(number * scale)
+}
+
+///
+/// Reads a list of lengths (as used for 'stroke-dasharray')
+///
+pub fn svg_parse_length_list(value: &str, percent_of: f32, font_size: f32) -> Option<Vec<f32>> {
+    value
+        .split(|c: char| c == ',' || c.is_ascii_whitespace())
+        .filter(|length| !length.is_empty())
+        .map(|length| svg_parse_length(length, percent_of, font_size))
+        .collect()
+}
+
+///
+/// Converts a colour stored as a 24-bit RGB value into a Color
+///
+fn rgb_color(rgb: u32, alpha: f32) -> Color {
+    let r = ((rgb >> 16) & 0xff) as f32 / 255.0;
+    let g = ((rgb >> 8) & 0xff) as f32 / 255.0;
+    let b = (rgb & 0xff) as f32 / 255.0;
+
+    Color::Rgba(r, g, b, alpha)
+}
+
+///
+/// Reads a colour in hex notation (the '#' has already been removed)
+///
+fn parse_hex_color(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    let nibble = |shift: u32| ((value >> shift) & 0xf) as f32 / 15.0;
+    let byte = |shift: u32| ((value >> shift) & 0xff) as f32 / 255.0;
+
+    match hex.len() {
+        3 => Some(Color::Rgba(nibble(8), nibble(4), nibble(0), 1.0)),
+        4 => Some(Color::Rgba(nibble(12), nibble(8), nibble(4), nibble(0))),
+        6 => Some(Color::Rgba(byte(16), byte(8), byte(0), 1.0)),
+        8 => Some(Color::Rgba(byte(24), byte(16), byte(8), byte(0))),
+        _ => None,
+    }
+}
+
+///
+/// Reads a colour in the 'rgb()' or 'rgba()' functional notation (the parameters are passed in)
+///
+fn parse_rgb_function(parameters: &str) -> Option<Color> {
+    let components = parameters
+        .split(|c: char| c == ',' || c == '/' || c.is_ascii_whitespace())
+        .filter(|component| !component.is_empty())
+        .collect::<Vec<_>>();
+
+    if components.len() != 3 && components.len() != 4 {
+        return None;
+    }
+
+    let color_component = |component: &str| {
+        if let Some(percent) = component.strip_suffix('%') {
+            percent.trim().parse::<f32>().ok().map(|val| val / 100.0)
+        } else {
+            component.parse::<f32>().ok().map(|val| val / 255.0)
+        }
+    };
+
+    let r = color_component(components[0])?;
+    let g = color_component(components[1])?;
+    let b = color_component(components[2])?;
+    let a = if components.len() == 4 {
+        svg_parse_fraction(components[3])?
+    } else {
+        1.0
+    };
+
+    Some(Color::Rgba(
+        r.clamp(0.0, 1.0),
+        g.clamp(0.0, 1.0),
+        b.clamp(0.0, 1.0),
+        a.clamp(0.0, 1.0),
+    ))
+}
+
+///
+/// Reads a colour value
+///
+pub fn svg_parse_color(value: &str) -> Option<Color> {
+    let value = value.trim();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        parse_hex_color(hex)
+    } else if let Some(parameters) = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))
+    {
+        parse_rgb_function(parameters.strip_suffix(')')?)
+    } else if value.eq_ignore_ascii_case("transparent") {
+        Some(Color::Rgba(0.0, 0.0, 0.0, 0.0))
+    } else {
+        let name = value.to_ascii_lowercase();
+
+        SVG_NAMED_COLORS
+            .binary_search_by(|(color_name, _)| (*color_name).cmp(name.as_str()))
+            .ok()
+            .map(|idx| rgb_color(SVG_NAMED_COLORS[idx].1, 1.0))
+    }
+}
+
+///
+/// Reads the value of a 'fill' or 'stroke' property
+///
+pub fn svg_parse_paint(value: &str) -> Option<SvgPaint> {
+    let value = value.trim();
+
+    if value == "none" {
+        Some(SvgPaint::None)
+    } else if value == "currentColor" {
+        Some(SvgPaint::CurrentColor)
+    } else if let Some(reference) = value.strip_prefix("url(") {
+        let end = reference.find(')')?;
+        let id = svg_url_id(&value[..(end + 5)])?;
+        let fallback = svg_parse_color(&reference[(end + 1)..]);
+
+        Some(SvgPaint::Reference(id.to_string(), fallback))
+    } else {
+        svg_parse_color(value).map(SvgPaint::Color)
+    }
+}
+
+///
+/// Returns the ID referred to by a 'url(#id)' value
+///
+pub fn svg_url_id(value: &str) -> Option<&str> {
+    let reference = value.trim().strip_prefix("url(")?.strip_suffix(')')?.trim();
+    let reference = reference.trim_matches(|c| c == '\'' || c == '"');
+
+    reference.strip_prefix('#')
+}
+
+///
+/// Reads the value of a 'transform' attribute
+///
+/// Returns None if the transform is not valid
+///
+pub fn svg_parse_transform(value: &str) -> Option<Transform2D> {
+    let mut tokens = SvgTokens::new(value);
+    let mut transform = Transform2D::identity();
+
+    loop {
+        tokens.skip_separator();
+        if tokens.is_at_end() {
+            break;
+        }
+
+        // Read the name of the transformation and its parameters
+        let name = tokens.next_identifier();
+        tokens.skip_whitespace();
+        if tokens.next_char() != Some(b'(') {
+            return None;
+        }
+
+        let mut parameters = vec![];
+        while let Some(parameter) = tokens.next_number() {
+            parameters.push(parameter);
+        }
+
+        tokens.skip_whitespace();
+        if tokens.next_char() != Some(b')') {
+            return None;
+        }
+
+        // Each transformation in the list is applied after the previous one
+        let next_transform = match (name, parameters.len()) {
+            ("matrix", 6) => {
+                let p = &parameters;
+                Transform2D([[p[0], p[2], p[4]], [p[1], p[3], p[5]], [0.0, 0.0, 1.0]])
+            }
+            ("translate", 1) => Transform2D::translate(parameters[0], 0.0),
+            ("translate", 2) => Transform2D::translate(parameters[0], parameters[1]),
+            ("scale", 1) => Transform2D::scale(parameters[0], parameters[0]),
+            ("scale", 2) => Transform2D::scale(parameters[0], parameters[1]),
+            ("rotate", 1) => Transform2D::rotate_degrees(parameters[0]),
+            ("rotate", 3) => {
+                let (x, y) = (parameters[1], parameters[2]);
+                Transform2D::translate(x, y)
+                    * Transform2D::rotate_degrees(parameters[0])
+                    * Transform2D::translate(-x, -y)
+            }
+            ("skewX", 1) => {
+                let tan = parameters[0].to_radians().tan();
+                Transform2D([[1.0, tan, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+            }
+            ("skewY", 1) => {
+                let tan = parameters[0].to_radians().tan();
+                Transform2D([[1.0, 0.0, 0.0], [tan, 1.0, 0.0], [0.0, 0.0, 1.0]])
+            }
+            _ => {
+                return None;
+            }
+        };
+
+        transform = transform * next_transform;
+    }
+
+    Some(transform)
+}
+
+///
+/// Splits the value of a 'style' attribute into its properties
+///
+pub fn svg_parse_style(value: &str) -> impl Iterator<Item = (&str, &str)> {
+    value.split(';').filter_map(|declaration| {
+        let (name, value) = declaration.split_once(':')?;
+        let value = value.trim();
+        let value = value
+            .strip_suffix("!important")
+            .map(|value| value.trim())
+            .unwrap_or(value);
+
+        Some((name.trim(), value))
+    })
+}
+
+///
+/// Reads the value of a 'viewBox' attribute, as `(x, y, width, height)`
+///
+pub fn svg_parse_view_box(value: &str) -> Option<(f32, f32, f32, f32)> {
+    let numbers = svg_parse_number_list(value);
+
+    if numbers.len() == 4 && numbers[2] > 0.0 && numbers[3] > 0.0 {
+        Some((numbers[0], numbers[1], numbers[2], numbers[3]))
+    } else {
+        None
+    }
+}
+
+///
+/// Returns the transform that maps a view box onto a viewport of the specified size
+///
+pub fn svg_view_box_transform(
+    (x, y, width, height): (f32, f32, f32, f32),
+    (viewport_width, viewport_height): (f32, f32),
+    preserve_aspect_ratio: Option<&str>,
+) -> Transform2D {
+    let scale_x = viewport_width / width;
+    let scale_y = viewport_height / height;
+
+    let mut parts = preserve_aspect_ratio.unwrap_or("").split_whitespace();
+    let align = parts.next().unwrap_or("xMidYMid");
+    let slice = parts.next() == Some("slice");
+
+    if align == "none" {
+        return Transform2D::scale(scale_x, scale_y) * Transform2D::translate(-x, -y);
+    }
+
+    let scale = if slice {
+        scale_x.max(scale_y)
+    } else {
+        scale_x.min(scale_y)
+    };
+
+    let extra_width = viewport_width - width * scale;
+    let extra_height = viewport_height - height * scale;
+
+    let offset_x = if align.starts_with("xMin") {
+        0.0
+    } else if align.starts_with("xMax") {
+        extra_width
+    } else {
+        extra_width / 2.0
+    };
+    let offset_y = if align.ends_with("YMin") {
+        0.0
+    } else if align.ends_with("YMax") {
+        extra_height
+    } else {
+        extra_height / 2.0
+    };
+
+    Transform2D::translate(offset_x, offset_y)
+        * Transform2D::scale(scale, scale)
+        * Transform2D::translate(-x, -y)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn named_colors_are_sorted() {
+        assert!(SVG_NAMED_COLORS
+            .windows(2)
+            .all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn parse_colors() {
+        assert!(svg_parse_color("#f00") == Some(Color::Rgba(1.0, 0.0, 0.0, 1.0)));
+        assert!(svg_parse_color("#00ff0080") == Some(Color::Rgba(0.0, 1.0, 0.0, 128.0 / 255.0)));
+        assert!(svg_parse_color("rgb(0, 0, 255)") == Some(Color::Rgba(0.0, 0.0, 1.0, 1.0)));
+        assert!(
+            svg_parse_color("rgba(100%, 0%, 0%, 0.5)") == Some(Color::Rgba(1.0, 0.0, 0.0, 0.5))
+        );
+        assert!(svg_parse_color("White") == Some(Color::Rgba(1.0, 1.0, 1.0, 1.0)));
+        assert!(svg_parse_color("notacolor").is_none());
+    }
+
+    #[test]
+    fn parse_paint() {
+        assert!(svg_parse_paint("none") == Some(SvgPaint::None));
+        assert!(
+            svg_parse_paint("url(#gradient) red")
+                == Some(SvgPaint::Reference(
+                    "gradient".to_string(),
+                    Some(Color::Rgba(1.0, 0.0, 0.0, 1.0))
+                ))
+        );
+    }
+
+    #[test]
+    fn parse_numbers_without_separators() {
+        assert!(svg_parse_number_list("1.5.5-2e1,3") == vec![1.5, 0.5, -20.0, 3.0]);
+    }
+
+    #[test]
+    fn parse_lengths() {
+        assert!(svg_parse_length("10", 100.0, 16.0) == Some(10.0));
+        assert!(svg_parse_length("50%", 200.0, 16.0) == Some(100.0));
+        assert!(svg_parse_length("2em", 200.0, 16.0) == Some(32.0));
+        assert!(svg_parse_length("1in", 200.0, 16.0) == Some(96.0));
+        assert!(svg_parse_length("1furlong", 200.0, 16.0).is_none());
+    }
+
+    #[test]
+    fn parse_transform_list() {
+        let transform = svg_parse_transform("translate(10, 20) scale(2)").unwrap();
+        assert!(transform.transform_point(1.0, 1.0) == (12.0, 22.0));
+
+        let transform = svg_parse_transform("matrix(1 0 0 1 5 6)").unwrap();
+        assert!(transform.transform_point(0.0, 0.0) == (5.0, 6.0));
+
+        let transform = svg_parse_transform("rotate(90 10 10)").unwrap();
+        let (x, y) = transform.transform_point(20.0, 10.0);
+        assert!((x - 10.0).abs() < 0.001 && (y - 20.0).abs() < 0.001);
+
+        assert!(svg_parse_transform("spin(10)").is_none());
+    }
+
+    #[test]
+    fn view_box_is_centered() {
+        let transform = svg_view_box_transform((0.0, 0.0, 10.0, 10.0), (40.0, 20.0), None);
+
+        assert!(transform.transform_point(0.0, 0.0) == (10.0, 0.0));
+        assert!(transform.transform_point(10.0, 10.0) == (30.0, 20.0));
+    }
+}
@@ -0,0 +1,634 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::svg_parse::*;
+
+use crate::path::*;
+
+use flo_curves::arc;
+use flo_curves::bezier;
+use flo_curves::bezier::BezierCurve;
+use flo_curves::*;
+
+use std::f64;
+
+///
+/// Generates the curves for an arc of an ellipse
+///
+/// The ellipse has the specified center and radii, and is rotated by `rotation` radians. The arc starts at `start_angle`
+/// and sweeps `sweep_angle` radians (positive values go from the x axis towards the y axis). The path should already be
+/// at the start point of the arc.
+///
+pub fn svg_ellipse_arc(
+    center: (f64, f64),
+    (radius_x, radius_y): (f64, f64),
+    rotation: f64,
+    start_angle: f64,
+    sweep_angle: f64,
+) -> Vec<PathOp> {
+    // flo_curves can approximate arcs of up to 90 degrees accurately, so larger arcs are divided up
+    let num_segments = (sweep_angle.abs() / (f64::consts::PI / 2.0) - 1e-6)
+        .ceil()
+        .max(1.0) as usize;
+    let segment_angle = sweep_angle / (num_segments as f64);
+
+    // Curves are generated for a unit circle, then moved onto the ellipse
+    let unit_circle = arc::Circle::new(Coord2(0.0, 0.0), 1.0);
+    let (cos, sin) = (rotation.cos(), rotation.sin());
+    let to_ellipse = |point: Coord2| {
+        let (x, y) = (point.x() * radius_x, point.y() * radius_y);
+
+        (
+            (x * cos - y * sin + center.0) as f32,
+            (x * sin + y * cos + center.1) as f32,
+        )
+    };
+
+    (0..num_segments)
+        .map(|segment| {
+            // flo_curves measures angles clockwise from the y axis rather than from the x axis
+            let angle = f64::consts::PI / 2.0 - (start_angle + segment_angle * (segment as f64));
+            let curve = unit_circle
+                .arc(angle, angle - segment_angle)
+                .to_bezier_curve::<bezier::Curve<Coord2>>();
+            let (cp1, cp2) = curve.control_points();
+
+            PathOp::BezierCurve(
+                (to_ellipse(cp1), to_ellipse(cp2)),
+                to_ellipse(curve.end_point()),
+            )
+        })
+        .collect()
+}
+
+///
+/// Generates the curves for an arc in the form used by the SVG 'A' path command
+///
+/// See the 'elliptical arc implementation notes' section in the SVG specification for details on how the parameters are
+/// converted to an arc of an ellipse.
+///
+pub fn svg_endpoint_arc(
+    start: (f32, f32),
+    (radius_x, radius_y): (f32, f32),
+    x_axis_rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    end: (f32, f32),
+) -> Vec<PathOp> {
+    let (x1, y1) = (start.0 as f64, start.1 as f64);
+    let (x2, y2) = (end.0 as f64, end.1 as f64);
+    let mut radius_x = (radius_x as f64).abs();
+    let mut radius_y = (radius_y as f64).abs();
+
+    if x1 == x2 && y1 == y2 {
+        // Arcs that end where they start are omitted
+        return vec![];
+    }
+
+    if radius_x == 0.0 || radius_y == 0.0 {
+        // Arcs with no radius are treated as lines
+        return vec![PathOp::Line(end.0, end.1)];
+    }
+
+    // Move the start point to the coordinate space of the ellipse
+    let rotation = (x_axis_rotation as f64).to_radians();
+    let (cos, sin) = (rotation.cos(), rotation.sin());
+    let (dx, dy) = ((x1 - x2) / 2.0, (y1 - y2) / 2.0);
+    let x1p = cos * dx + sin * dy;
+    let y1p = -sin * dx + cos * dy;
+
+    // Scale up the radii if they are too small to reach the end point
+    let lambda = (x1p * x1p) / (radius_x * radius_x) + (y1p * y1p) / (radius_y * radius_y);
+    if lambda > 1.0 {
+        radius_x *= lambda.sqrt();
+        radius_y *= lambda.sqrt();
+    }
+
+    // Find the center of the ellipse
+    let rx2 = radius_x * radius_x;
+    let ry2 = radius_y * radius_y;
+    let numerator = rx2 * ry2 - rx2 * y1p * y1p - ry2 * x1p * x1p;
+    let denominator = rx2 * y1p * y1p + ry2 * x1p * x1p;
+    let coefficient = (numerator / denominator).max(0.0).sqrt();
+    let coefficient = if large_arc == sweep {
+        -coefficient
+    } else {
+        coefficient
+    };
+
+    let cxp = coefficient * radius_x * y1p / radius_y;
+    let cyp = -coefficient * radius_y * x1p / radius_x;
+    let cx = cos * cxp - sin * cyp + (x1 + x2) / 2.0;
+    let cy = sin * cxp + cos * cyp + (y1 + y2) / 2.0;
+
+    // Work out the angles that the arc covers
+    let angle =
+        |(ux, uy): (f64, f64), (vx, vy): (f64, f64)| (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+    let start_vector = ((x1p - cxp) / radius_x, (y1p - cyp) / radius_y);
+    let end_vector = ((-x1p - cxp) / radius_x, (-y1p - cyp) / radius_y);
+
+    let start_angle = angle((1.0, 0.0), start_vector);
+    let mut sweep_angle = angle(start_vector, end_vector);
+
+    if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= f64::consts::PI * 2.0;
+    } else if sweep && sweep_angle < 0.0 {
+        sweep_angle += f64::consts::PI * 2.0;
+    }
+
+    let mut curves = svg_ellipse_arc(
+        (cx, cy),
+        (radius_x, radius_y),
+        rotation,
+        start_angle,
+        sweep_angle,
+    );
+
+    // Make sure that the arc finishes exactly at the end point
+    if let Some(PathOp::BezierCurve(_, last_point)) = curves.last_mut() {
+        *last_point = end;
+    }
+
+    curves
+}
+
+///
+/// Converts the value of a 'd' attribute into a list of path operations
+///
+/// Quadratic curves are converted to cubic curves and arcs are approximated by cubic curves. If the path data contains
+/// an error, the path up to the error is returned (which is how the SVG specification says errors should be handled)
+///
+pub fn svg_parse_path_data(data: &str) -> Vec<PathOp> {
+    let mut tokens = SvgTokens::new(data);
+    let mut path = vec![];
+
+    // The current point, the start of the current subpath and the control point for the 'smooth' curve commands
+    let mut current = (0.0, 0.0);
+    let mut subpath_start = (0.0, 0.0);
+    let mut last_cubic_control: Option<(f32, f32)> = None;
+    let mut last_quadratic_control: Option<(f32, f32)> = None;
+
+    let mut command = None;
+
+    loop {
+        tokens.skip_whitespace();
+        if tokens.is_at_end() {
+            break;
+        }
+
+        // Read the next command (or repeat the last one if there are more parameters)
+        match tokens.peek() {
+            Some(c) if c.is_ascii_alphabetic() => {
+                tokens.next_char();
+                command = Some(c);
+            }
+
+            _ => {
+                command = match command {
+                    // Coordinates after a move are treated as lines
+                    Some(b'M') => Some(b'L'),
+                    Some(b'm') => Some(b'l'),
+
+                    // Closing the path can't be repeated
+                    Some(b'Z') | Some(b'z') | None => {
+                        break;
+                    }
+
+                    other => other,
+                };
+            }
+        }
+
+        let command = if let Some(command) = command {
+            command
+        } else {
+            break;
+        };
+        let relative = command.is_ascii_lowercase();
+        let offset = |(x, y): (f32, f32)| {
+            if relative {
+                (x + current.0, y + current.1)
+            } else {
+                (x, y)
+            }
+        };
+
+        // Reads a coordinate from the tokens
+        macro_rules! point {
+            () => {
+                match (tokens.next_number(), tokens.next_number()) {
+                    (Some(x), Some(y)) => offset((x, y)),
+                    _ => {
+                        break;
+                    }
+                }
+            };
+        }
+
+        macro_rules! number {
+            () => {
+                match tokens.next_number() {
+                    Some(number) => number,
+                    None => {
+                        break;
+                    }
+                }
+            };
+        }
+
+        let mut cubic_control = None;
+        let mut quadratic_control = None;
+
+        match command.to_ascii_uppercase() {
+            b'M' => {
+                let point = point!();
+                path.push(PathOp::Move(point.0, point.1));
+                current = point;
+                subpath_start = point;
+            }
+
+            b'L' => {
+                let point = point!();
+                path.push(PathOp::Line(point.0, point.1));
+                current = point;
+            }
+
+            b'H' => {
+                let x = number!();
+                let x = if relative { x + current.0 } else { x };
+                path.push(PathOp::Line(x, current.1));
+                current = (x, current.1);
+            }
+
+            b'V' => {
+                let y = number!();
+                let y = if relative { y + current.1 } else { y };
+                path.push(PathOp::Line(current.0, y));
+                current = (current.0, y);
+            }
+
+            b'C' | b'S' => {
+                let cp1 = if command == b'C' || command == b'c' {
+                    point!()
+                } else {
+                    // The first control point is the reflection of the last one
+                    last_cubic_control
+                        .map(|(x, y)| (current.0 * 2.0 - x, current.1 * 2.0 - y))
+                        .unwrap_or(current)
+                };
+                let cp2 = point!();
+                let end = point!();
+
+                path.push(PathOp::BezierCurve((cp1, cp2), end));
+                current = end;
+                cubic_control = Some(cp2);
+            }
+
+            b'Q' | b'T' => {
+                let control = if command == b'Q' || command == b'q' {
+                    point!()
+                } else {
+                    last_quadratic_control
+                        .map(|(x, y)| (current.0 * 2.0 - x, current.1 * 2.0 - y))
+                        .unwrap_or(current)
+                };
+                let end = point!();
+
+                // Quadratic curves can be represented exactly as cubic curves
+                let cp1 = (
+                    current.0 + (control.0 - current.0) * 2.0 / 3.0,
+                    current.1 + (control.1 - current.1) * 2.0 / 3.0,
+                );
+                let cp2 = (
+                    end.0 + (control.0 - end.0) * 2.0 / 3.0,
+                    end.1 + (control.1 - end.1) * 2.0 / 3.0,
+                );
+
+                path.push(PathOp::BezierCurve((cp1, cp2), end));
+                current = end;
+                quadratic_control = Some(control);
+            }
+
+            b'A' => {
+                let radius_x = number!();
+                let radius_y = number!();
+                let rotation = number!();
+                let (large_arc, sweep) = match (tokens.next_flag(), tokens.next_flag()) {
+                    (Some(large_arc), Some(sweep)) => (large_arc, sweep),
+                    _ => {
+                        break;
+                    }
+                };
+                let end = point!();
+
+                path.extend(svg_endpoint_arc(
+                    current,
+                    (radius_x, radius_y),
+                    rotation,
+                    large_arc,
+                    sweep,
+                    end,
+                ));
+                current = end;
+            }
+
+            b'Z' => {
+                path.push(PathOp::ClosePath);
+                current = subpath_start;
+            }
+
+            _ => {
+                // Unknown command
+                break;
+            }
+        }
+
+        last_cubic_control = cubic_control;
+        last_quadratic_control = quadratic_control;
+    }
+
+    path
+}
+
+///
+/// Generates the path for a rectangle with optionally rounded corners
+///
+pub fn svg_rect_path(
+    (x, y): (f32, f32),
+    (width, height): (f32, f32),
+    (radius_x, radius_y): (f32, f32),
+) -> Vec<PathOp> {
+    use self::PathOp::*;
+
+    let radius_x = radius_x.clamp(0.0, width / 2.0);
+    let radius_y = radius_y.clamp(0.0, height / 2.0);
+
+    if radius_x <= 0.0 || radius_y <= 0.0 {
+        return vec![
+            Move(x, y),
+            Line(x + width, y),
+            Line(x + width, y + height),
+            Line(x, y + height),
+            ClosePath,
+        ];
+    }
+
+    let (rx, ry) = (radius_x as f64, radius_y as f64);
+    let (x1, y1) = (x as f64, y as f64);
+    let (x2, y2) = ((x + width) as f64, (y + height) as f64);
+    let quarter = f64::consts::PI / 2.0;
+
+    let mut path = vec![Move(x + radius_x, y), Line(x + width - radius_x, y)];
+    path.extend(svg_ellipse_arc(
+        (x2 - rx, y1 + ry),
+        (rx, ry),
+        0.0,
+        -quarter,
+        quarter,
+    ));
+    path.push(Line(x + width, y + height - radius_y));
+    path.extend(svg_ellipse_arc(
+        (x2 - rx, y2 - ry),
+        (rx, ry),
+        0.0,
+        0.0,
+        quarter,
+    ));
+    path.push(Line(x + radius_x, y + height));
+    path.extend(svg_ellipse_arc(
+        (x1 + rx, y2 - ry),
+        (rx, ry),
+        0.0,
+        quarter,
+        quarter,
+    ));
+    path.push(Line(x, y + radius_y));
+    path.extend(svg_ellipse_arc(
+        (x1 + rx, y1 + ry),
+        (rx, ry),
+        0.0,
+        quarter * 2.0,
+        quarter,
+    ));
+    path.push(ClosePath);
+
+    path
+}
+
+///
+/// Generates the path for an ellipse
+///
+pub fn svg_ellipse_path(
+    (center_x, center_y): (f32, f32),
+    (radius_x, radius_y): (f32, f32),
+) -> Vec<PathOp> {
+    let mut path = vec![PathOp::Move(center_x + radius_x, center_y)];
+
+    path.extend(svg_ellipse_arc(
+        (center_x as f64, center_y as f64),
+        (radius_x as f64, radius_y as f64),
+        0.0,
+        0.0,
+        f64::consts::PI * 2.0,
+    ));
+    path.push(PathOp::ClosePath);
+
+    path
+}
+
+///
+/// Generates the path for a 'polyline' or 'polygon' element
+///
+pub fn svg_points_path(points: &str, closed: bool) -> Vec<PathOp> {
+    let numbers = svg_parse_number_list(points);
+    let mut path = numbers
+        .chunks_exact(2)
+        .enumerate()
+        .map(|(idx, point)| {
+            if idx == 0 {
+                PathOp::Move(point[0], point[1])
+            } else {
+                PathOp::Line(point[0], point[1])
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if closed && !path.is_empty() {
+        path.push(PathOp::ClosePath);
+    }
+
+    path
+}
+
+///
+/// Returns the bounding box of a path, as `((min_x, min_y), (max_x, max_y))`
+///
+pub fn svg_path_bounds(path: &[PathOp]) -> Option<((f32, f32), (f32, f32))> {
+    let mut bounds: Option<(Coord2, Coord2)> = None;
+    let mut current = Coord2(0.0, 0.0);
+    let add_point = |bounds: &mut Option<(Coord2, Coord2)>, point: Coord2| {
+        *bounds = Some(match bounds {
+            Some((min, max)) => (
+                Coord2::from_smallest_components(*min, point),
+                Coord2::from_biggest_components(*max, point),
+            ),
+            None => (point, point),
+        });
+    };
+
+    for op in path.iter() {
+        match op {
+            PathOp::NewPath | PathOp::ClosePath => {}
+
+            PathOp::Move(x, y) | PathOp::Line(x, y) => {
+                current = Coord2(*x as f64, *y as f64);
+                add_point(&mut bounds, current);
+            }
+
+            PathOp::BezierCurve(((cp1x, cp1y), (cp2x, cp2y)), (x, y)) => {
+                let end = Coord2(*x as f64, *y as f64);
+                let (min, max): (Coord2, Coord2) = bezier::bounding_box4(
+                    current,
+                    Coord2(*cp1x as f64, *cp1y as f64),
+                    Coord2(*cp2x as f64, *cp2y as f64),
+                    end,
+                );
+
+                add_point(&mut bounds, min);
+                add_point(&mut bounds, max);
+                current = end;
+            }
+        }
+    }
+
+    bounds.map(|(min, max)| {
+        (
+            (min.x() as f32, min.y() as f32),
+            (max.x() as f32, max.y() as f32),
+        )
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn end_point(op: &PathOp) -> (f32, f32) {
+        match op {
+            PathOp::Move(x, y) | PathOp::Line(x, y) => (*x, *y),
+            PathOp::BezierCurve(_, end) => *end,
+            _ => panic!("No end point"),
+        }
+    }
+
+    fn is_close((x1, y1): (f32, f32), (x2, y2): (f32, f32)) -> bool {
+        (x1 - x2).abs() < 0.01 && (y1 - y2).abs() < 0.01
+    }
+
+    #[test]
+    fn absolute_and_relative_lines() {
+        let path = svg_parse_path_data("M10 10 L20 10 l0 10 h-10 V10 z");
+
+        assert!(
+            path == vec![
+                PathOp::Move(10.0, 10.0),
+                PathOp::Line(20.0, 10.0),
+                PathOp::Line(20.0, 20.0),
+                PathOp::Line(10.0, 20.0),
+                PathOp::Line(10.0, 10.0),
+                PathOp::ClosePath
+            ]
+        );
+    }
+
+    #[test]
+    fn implicit_line_after_move() {
+        let path = svg_parse_path_data("m10,10 5,5 5,0");
+
+        assert!(
+            path == vec![
+                PathOp::Move(10.0, 10.0),
+                PathOp::Line(15.0, 15.0),
+                PathOp::Line(20.0, 15.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn smooth_cubic_reflects_control_point() {
+        let path = svg_parse_path_data("M0 0 C0 10 10 10 10 0 S20 -10 20 0");
+
+        assert!(path[2] == PathOp::BezierCurve(((10.0, -10.0), (20.0, -10.0)), (20.0, 0.0)));
+    }
+
+    #[test]
+    fn quadratic_becomes_cubic() {
+        let path = svg_parse_path_data("M0 0 Q15 30 30 0");
+
+        assert!(path[1] == PathOp::BezierCurve(((10.0, 20.0), (20.0, 20.0)), (30.0, 0.0)));
+    }
+
+    #[test]
+    fn path_with_error_stops_at_error() {
+        let path = svg_parse_path_data("M0 0 L10 10 L20 #");
+
+        assert!(path == vec![PathOp::Move(0.0, 0.0), PathOp::Line(10.0, 10.0)]);
+    }
+
+    #[test]
+    fn half_circle_arc() {
+        let path = svg_parse_path_data("M0 0 A10 10 0 0 1 20 0");
+
+        // Semicircle should be two curves, passing through the point 10 units from the center
+        assert!(path.len() == 3);
+        assert!(is_close(end_point(&path[1]), (10.0, -10.0)));
+        assert!(is_close(end_point(&path[2]), (20.0, 0.0)));
+    }
+
+    #[test]
+    fn arc_sweep_flag_changes_direction() {
+        let path = svg_parse_path_data("M0 0 A10 10 0 0 0 20 0");
+
+        assert!(is_close(end_point(&path[1]), (10.0, 10.0)));
+    }
+
+    #[test]
+    fn large_arc_goes_the_long_way() {
+        let path = svg_parse_path_data("M10 0 A10 10 0 1 0 0 10");
+
+        // 270 degree arc around the center at 0,0 (going anticlockwise as the sweep flag is not set)
+        assert!(path.len() == 4);
+        assert!(is_close(end_point(&path[1]), (0.0, -10.0)));
+        assert!(is_close(end_point(&path[2]), (-10.0, 0.0)));
+        assert!(is_close(end_point(&path[3]), (0.0, 10.0)));
+    }
+
+    #[test]
+    fn small_arc_radius_is_scaled_up() {
+        let path = svg_parse_path_data("M0 0 A1 1 0 0 1 20 0");
+
+        assert!(is_close(end_point(&path[1]), (10.0, -10.0)));
+    }
+
+    #[test]
+    fn ellipse_bounds() {
+        let ((min_x, min_y), (max_x, max_y)) =
+            svg_path_bounds(&svg_ellipse_path((10.0, 20.0), (5.0, 2.0))).unwrap();
+
+        assert!(is_close((min_x, min_y), (5.0, 18.0)));
+        assert!(is_close((max_x, max_y), (15.0, 22.0)));
+    }
+
+    #[test]
+    fn rounded_rect_bounds() {
+        let path = svg_rect_path((0.0, 0.0), (10.0, 20.0), (2.0, 2.0));
+        let (min, max) = svg_path_bounds(&path).unwrap();
+
+        assert!(is_close(min, (0.0, 0.0)));
+        assert!(is_close(max, (10.0, 20.0)));
+        assert!(is_close(end_point(&path[path.len() - 2]), (2.0, 0.0)));
+    }
+}
@@ -0,0 +1,1546 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::svg_parse::*;
+use super::svg_path_data::*;
+use super::svg_resources::*;
+
+use crate::color::*;
+use crate::draw::*;
+use crate::font::*;
+use crate::gradient::*;
+use crate::path::*;
+use crate::sprite::*;
+use crate::texture::*;
+use crate::transform2d::*;
+
+use roxmltree::{Document, Node};
+
+use std::collections::HashMap;
+use std::f32;
+
+/// The namespace used for the 'xlink:href' attribute in older SVG documents
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+/// How deeply `<use>` elements and gradient references can be nested before they are ignored (stops reference loops)
+const MAX_REFERENCE_DEPTH: usize = 16;
+
+///
+/// Errors that can occur while reading a SVG document
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum SvgReadError {
+    /// The document is not valid XML (the string describes the problem)
+    InvalidXml(String),
+
+    /// The root element of the document is not a `<svg>` element
+    NotSvg,
+}
+
+///
+/// The style properties that are inherited by child elements
+///
+#[derive(Clone, Debug)]
+struct SvgStyle {
+    fill: SvgPaint,
+    fill_opacity: f32,
+    fill_rule: WindingRule,
+
+    stroke: SvgPaint,
+    stroke_opacity: f32,
+    stroke_width: f32,
+    line_join: LineJoin,
+    line_cap: LineCap,
+    dash_array: Vec<f32>,
+    dash_offset: f32,
+
+    /// The value of the 'color' property, used by 'currentColor'
+    color: Color,
+
+    font_size: f32,
+    font_family: String,
+    text_anchor: TextAlignment,
+
+    /// False if the 'visibility' property is set to hide the element
+    visible: bool,
+}
+
+///
+/// A paint value after any references to gradients have been resolved
+///
+#[derive(Clone, Debug)]
+enum SvgFillStyle {
+    None,
+    Color(Color),
+    Gradient(GradientId, SvgGradientShape, Transform2D, Color),
+}
+
+///
+/// The shape of a gradient, in the gradient's coordinate system
+///
+#[derive(Clone, Copy, Debug)]
+enum SvgGradientShape {
+    /// Start and end points
+    Linear((f32, f32), (f32, f32)),
+
+    /// Focal circle and end circle
+    Radial((f32, f32, f32), (f32, f32, f32)),
+}
+
+///
+/// Where drawing instructions are currently being sent
+///
+#[derive(Clone, Copy, Debug)]
+enum SvgTarget {
+    Layer(LayerId),
+    Sprite(SpriteId),
+}
+
+///
+/// Converts SVG documents into canvas drawing instructions
+///
+/// The drawing uses the coordinates of the SVG document in pixels, except that the y axis is flipped to match the canvas:
+/// (0, 0) is the lower-left corner of the document and (width, height) the upper-right corner (`svg_document_size()`
+/// returns the size of a document). A transform such as `Draw::CenterRegion` can be used to fit the drawing to a canvas.
+///
+/// Paths, basic shapes, fills, strokes, transforms, linear and radial gradients and clip paths are supported. Groups with
+/// an opacity are drawn to a sprite, which is then drawn with an alpha blend filter. Text is drawn using `Draw::DrawText`
+/// with the font registered for its font family (fonts must be loaded separately), and is positioned using the `x` and `y`
+/// attributes of the `<text>` element. CSS stylesheets, images, markers, patterns and filters are not supported, gradients
+/// can only be used to fill shapes, and where clip paths are nested only the innermost clip path is applied.
+///
+#[derive(Clone, Debug)]
+pub struct SvgReader {
+    /// The font used for text with no font family or a font family that isn't in the `fonts` list
+    default_font: FontId,
+
+    /// The font IDs for each font family
+    fonts: HashMap<String, FontId>,
+
+    /// The layer that the drawing is rendered to (reselected after drawing to a sprite)
+    layer: LayerId,
+
+    /// The first ID to use for the gradients in the document
+    first_gradient: GradientId,
+
+    /// The first ID to use for the sprites used to draw groups with an opacity
+    first_sprite: SpriteId,
+}
+
+///
+/// The state while converting a single document
+///
+struct SvgDocumentReader<'a, 'input> {
+    /// The settings for the conversion
+    settings: &'a SvgReader,
+
+    /// The elements in the document with an ID
+    elements: HashMap<&'a str, Node<'a, 'input>>,
+
+    /// The drawing instructions generated so far
+    drawing: Vec<Draw>,
+
+    /// The gradients that have been defined, indexed by element ID and opacity
+    gradients: HashMap<(&'a str, u32), GradientId>,
+
+    /// The targets that have been selected (the last one is the current target)
+    targets: Vec<SvgTarget>,
+
+    /// The size of the current viewport (used for lengths specified as percentages)
+    viewport: (f32, f32),
+
+    next_gradient: u64,
+    next_sprite: u64,
+
+    /// The number of `<use>` elements that are being drawn
+    use_depth: usize,
+}
+
+impl Default for SvgStyle {
+    fn default() -> SvgStyle {
+        SvgStyle {
+            fill: SvgPaint::Color(Color::Rgba(0.0, 0.0, 0.0, 1.0)),
+            fill_opacity: 1.0,
+            fill_rule: WindingRule::NonZero,
+
+            stroke: SvgPaint::None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            line_join: LineJoin::Miter,
+            line_cap: LineCap::Butt,
+            dash_array: vec![],
+            dash_offset: 0.0,
+
+            color: Color::Rgba(0.0, 0.0, 0.0, 1.0),
+
+            font_size: 16.0,
+            font_family: String::new(),
+            text_anchor: TextAlignment::Left,
+
+            visible: true,
+        }
+    }
+}
+
+impl Default for SvgReader {
+    fn default() -> SvgReader {
+        SvgReader::new()
+    }
+}
+
+///
+/// Returns the value of a property set on an element, either in its 'style' attribute or as a presentation attribute
+///
+fn svg_property<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    let from_style = node
+        .attribute("style")
+        .and_then(|style| {
+            svg_parse_style(style)
+                .filter(|(property, _)| *property == name)
+                .last()
+        })
+        .map(|(_, value)| value);
+
+    from_style
+        .or_else(|| node.attribute(name))
+        .map(|value| value.trim())
+        .filter(|value| *value != "inherit")
+}
+
+///
+/// Returns the 'href' attribute of an element (which can be in the xlink namespace for older documents)
+///
+fn svg_href<'a>(node: Node<'a, '_>) -> Option<&'a str> {
+    node.attribute("href")
+        .or_else(|| node.attribute((XLINK_NAMESPACE, "href")))
+        .and_then(|href| href.trim().strip_prefix('#'))
+}
+
+///
+/// Multiplies the alpha value of a colour
+///
+fn svg_color_with_opacity(color: Color, opacity: f32) -> Color {
+    let (r, g, b, a) = color.to_rgba_components();
+
+    Color::Rgba(r, g, b, a * opacity)
+}
+
+///
+/// Returns the transform from the SVG coordinates to canvas coordinates for a document with the specified height
+///
+fn svg_flip_transform(height: f32) -> Transform2D {
+    Transform2D([[1.0, 0.0, 0.0], [0.0, -1.0, height], [0.0, 0.0, 1.0]])
+}
+
+///
+/// Reads the size of the viewport set by a `<svg>` element, and the transform from its view box
+///
+fn svg_viewport(
+    node: Node,
+    (parent_width, parent_height): (f32, f32),
+) -> ((f32, f32), Transform2D) {
+    let view_box = node.attribute("viewBox").and_then(svg_parse_view_box);
+    let length = |name, percent_of, default| {
+        node.attribute(name)
+            .and_then(|value| svg_parse_length(value, percent_of, 16.0))
+            .unwrap_or(default)
+    };
+
+    let (default_width, default_height) = view_box
+        .map(|(_, _, width, height)| (width, height))
+        .unwrap_or((parent_width, parent_height));
+    let width = length("width", parent_width, default_width);
+    let height = length("height", parent_height, default_height);
+
+    let transform = view_box
+        .map(|view_box| {
+            svg_view_box_transform(
+                view_box,
+                (width, height),
+                node.attribute("preserveAspectRatio"),
+            )
+        })
+        .unwrap_or_else(Transform2D::identity);
+
+    ((width, height), transform)
+}
+
+///
+/// Collects the text content of an element and its children, with whitespace collapsed as for `xml:space="default"`
+///
+fn svg_text_content(node: Node) -> String {
+    let text = node
+        .descendants()
+        .filter(|node| node.is_text())
+        .filter_map(|node| node.text())
+        .collect::<String>();
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+///
+/// Returns the size of a SVG document in pixels
+///
+pub fn svg_document_size(svg: &str) -> Result<(f32, f32), SvgReadError> {
+    let document = Document::parse(svg).map_err(|err| SvgReadError::InvalidXml(err.to_string()))?;
+    let root = document.root_element();
+
+    if root.tag_name().name() != "svg" {
+        return Err(SvgReadError::NotSvg);
+    }
+
+    Ok(svg_viewport(root, (100.0, 100.0)).0)
+}
+
+///
+/// Converts a SVG document into a set of drawing instructions, using the default settings for `SvgReader`
+///
+pub fn svg_to_drawing(svg: &str) -> Result<Vec<Draw>, SvgReadError> {
+    SvgReader::new().read(svg)
+}
+
+impl SvgReader {
+    ///
+    /// Creates a reader that draws to layer 0 and uses font 0 for any text
+    ///
+    pub fn new() -> SvgReader {
+        SvgReader {
+            default_font: FontId(0),
+            fonts: HashMap::new(),
+            layer: LayerId(0),
+            first_gradient: GradientId(0),
+            first_sprite: SpriteId(0),
+        }
+    }
+
+    ///
+    /// Sets the font used for text that doesn't use one of the font families set by `with_font()`
+    ///
+    pub fn with_default_font(mut self, font_id: FontId) -> SvgReader {
+        self.default_font = font_id;
+        self
+    }
+
+    ///
+    /// Sets the font to use for a font family
+    ///
+    pub fn with_font(mut self, font_family: &str, font_id: FontId) -> SvgReader {
+        self.fonts.insert(font_family.to_string(), font_id);
+        self
+    }
+
+    ///
+    /// Sets the layer that the drawing will be rendered on
+    ///
+    pub fn with_layer(mut self, layer_id: LayerId) -> SvgReader {
+        self.layer = layer_id;
+        self
+    }
+
+    ///
+    /// Sets the first ID to use for gradients (subsequent gradients will use the following IDs)
+    ///
+    pub fn with_first_gradient(mut self, gradient_id: GradientId) -> SvgReader {
+        self.first_gradient = gradient_id;
+        self
+    }
+
+    ///
+    /// Sets the first ID to use for sprites (subsequent sprites will use the following IDs)
+    ///
+    pub fn with_first_sprite(mut self, sprite_id: SpriteId) -> SvgReader {
+        self.first_sprite = sprite_id;
+        self
+    }
+
+    ///
+    /// Converts a SVG document into a set of drawing instructions
+    ///
+    pub fn read(&self, svg: &str) -> Result<Vec<Draw>, SvgReadError> {
+        let document =
+            Document::parse(svg).map_err(|err| SvgReadError::InvalidXml(err.to_string()))?;
+        let root = document.root_element();
+
+        if root.tag_name().name() != "svg" {
+            return Err(SvgReadError::NotSvg);
+        }
+
+        let elements = document
+            .descendants()
+            .filter_map(|node| node.attribute("id").map(|id| (id, node)))
+            .collect();
+
+        let mut reader = SvgDocumentReader {
+            settings: self,
+            elements,
+            drawing: vec![],
+            gradients: HashMap::new(),
+            targets: vec![SvgTarget::Layer(self.layer)],
+            viewport: (100.0, 100.0),
+            next_gradient: self.first_gradient.0,
+            next_sprite: self.first_sprite.0,
+            use_depth: 0,
+        };
+
+        // The state is saved so the settings used by the document don't affect any later drawing
+        let ((_, height), _) = svg_viewport(root, reader.viewport);
+        reader.drawing.push(Draw::PushState);
+        reader.read_element(root, &SvgStyle::default(), svg_flip_transform(height));
+        reader.drawing.push(Draw::PopState);
+
+        Ok(reader.drawing)
+    }
+}
+
+impl SvgStyle {
+    ///
+    /// Returns the style for an element, given the style of its parent
+    ///
+    fn for_element(&self, node: Node, viewport: (f32, f32)) -> SvgStyle {
+        let mut style = self.clone();
+        let diagonal = ((viewport.0 * viewport.0 + viewport.1 * viewport.1) / 2.0).sqrt();
+
+        // Font size is read first as it's used for the 'em' units in other lengths
+        if let Some(font_size) = svg_property(node, "font-size")
+            .and_then(|value| svg_parse_length(value, self.font_size, self.font_size))
+        {
+            style.font_size = font_size;
+        }
+        let font_size = style.font_size;
+
+        if let Some(color) = svg_property(node, "color").and_then(svg_parse_color) {
+            style.color = color;
+        }
+
+        if let Some(fill) = svg_property(node, "fill").and_then(svg_parse_paint) {
+            style.fill = fill;
+        }
+        if let Some(opacity) = svg_property(node, "fill-opacity").and_then(svg_parse_fraction) {
+            style.fill_opacity = opacity.clamp(0.0, 1.0);
+        }
+        match svg_property(node, "fill-rule") {
+            Some("nonzero") => style.fill_rule = WindingRule::NonZero,
+            Some("evenodd") => style.fill_rule = WindingRule::EvenOdd,
+            _ => {}
+        }
+
+        if let Some(stroke) = svg_property(node, "stroke").and_then(svg_parse_paint) {
+            style.stroke = stroke;
+        }
+        if let Some(opacity) = svg_property(node, "stroke-opacity").and_then(svg_parse_fraction) {
+            style.stroke_opacity = opacity.clamp(0.0, 1.0);
+        }
+        if let Some(width) = svg_property(node, "stroke-width")
+            .and_then(|value| svg_parse_length(value, diagonal, font_size))
+        {
+            style.stroke_width = width.max(0.0);
+        }
+        match svg_property(node, "stroke-linejoin") {
+            Some("miter") | Some("miter-clip") | Some("arcs") => style.line_join = LineJoin::Miter,
+            Some("round") => style.line_join = LineJoin::Round,
+            Some("bevel") => style.line_join = LineJoin::Bevel,
+            _ => {}
+        }
+        match svg_property(node, "stroke-linecap") {
+            Some("butt") => style.line_cap = LineCap::Butt,
+            Some("round") => style.line_cap = LineCap::Round,
+            Some("square") => style.line_cap = LineCap::Square,
+            _ => {}
+        }
+        match svg_property(node, "stroke-dasharray") {
+            Some("none") => style.dash_array = vec![],
+            Some(value) => {
+                if let Some(dashes) = svg_parse_length_list(value, diagonal, font_size) {
+                    if dashes.iter().all(|dash| *dash >= 0.0)
+                        && dashes.iter().any(|dash| *dash > 0.0)
+                    {
+                        // An odd number of dashes is repeated to make an even number
+                        style.dash_array = if dashes.len() % 2 == 1 {
+                            dashes.repeat(2)
+                        } else {
+                            dashes
+                        };
+                    } else {
+                        style.dash_array = vec![];
+                    }
+                }
+            }
+            None => {}
+        }
+        if let Some(offset) = svg_property(node, "stroke-dashoffset")
+            .and_then(|value| svg_parse_length(value, diagonal, font_size))
+        {
+            style.dash_offset = offset;
+        }
+
+        if let Some(family) = svg_property(node, "font-family") {
+            style.font_family = family.to_string();
+        }
+        match svg_property(node, "text-anchor") {
+            Some("start") => style.text_anchor = TextAlignment::Left,
+            Some("middle") => style.text_anchor = TextAlignment::Center,
+            Some("end") => style.text_anchor = TextAlignment::Right,
+            _ => {}
+        }
+
+        match svg_property(node, "visibility") {
+            Some("visible") => style.visible = true,
+            Some("hidden") | Some("collapse") => style.visible = false,
+            _ => {}
+        }
+
+        style
+    }
+}
+
+impl<'a, 'input> SvgDocumentReader<'a, 'input> {
+    ///
+    /// Reads a length attribute from an element
+    ///
+    /// `percent_of` is 0 for the width of the viewport, 1 for the height and 2 for the diagonal
+    ///
+    fn length(&self, node: Node, name: &str, percent_of: usize, style: &SvgStyle) -> Option<f32> {
+        let (width, height) = self.viewport;
+        let percent_of = match percent_of {
+            0 => width,
+            1 => height,
+            _ => ((width * width + height * height) / 2.0).sqrt(),
+        };
+
+        // Lists of lengths (as used by the `<text>` element) are truncated to their first value
+        let value = node.attribute(name)?;
+        let value = value
+            .split(|c: char| c == ',' || c.is_ascii_whitespace())
+            .find(|value| !value.is_empty())?;
+
+        svg_parse_length(value, percent_of, style.font_size)
+    }
+
+    ///
+    /// Draws an element and its children
+    ///
+    fn read_element(
+        &mut self,
+        node: Node<'a, 'input>,
+        parent_style: &SvgStyle,
+        parent_transform: Transform2D,
+    ) {
+        if !node.is_element() || svg_property(node, "display") == Some("none") {
+            return;
+        }
+
+        let name = node.tag_name().name();
+        let style = parent_style.for_element(node, self.viewport);
+
+        // The transform attribute is applied before any of the element's other attributes
+        let mut transform = parent_transform;
+        if let Some(element_transform) = node.attribute("transform").and_then(svg_parse_transform) {
+            transform = transform * element_transform;
+        }
+
+        let opacity = svg_property(node, "opacity")
+            .and_then(svg_parse_fraction)
+            .unwrap_or(1.0)
+            .clamp(0.0, 1.0);
+
+        match name {
+            "svg" | "g" | "a" | "switch" | "use" => {
+                let clip = self.clip_path(node, transform, None);
+
+                if opacity < 1.0 {
+                    // The group is drawn to a sprite so that the opacity can be applied to all of its elements at once
+                    let sprite_id = SpriteId(self.next_sprite);
+                    self.next_sprite += 1;
+
+                    self.targets.push(SvgTarget::Sprite(sprite_id));
+                    self.drawing.push(Draw::Sprite(sprite_id));
+                    self.drawing.push(Draw::ClearSprite);
+                    self.read_children(node, &style, transform);
+                    self.targets.pop();
+                    self.select_target();
+
+                    self.drawing.push(Draw::PushState);
+                    self.apply_clip(clip);
+                    self.drawing
+                        .push(Draw::SpriteTransform(SpriteTransform::Identity));
+                    self.drawing.push(Draw::DrawSpriteWithFilters(
+                        sprite_id,
+                        vec![TextureFilter::AlphaBlend(opacity)],
+                    ));
+                    self.drawing.push(Draw::PopState);
+                } else if let Some(clip) = clip {
+                    self.drawing.push(Draw::PushState);
+                    self.apply_clip(Some(clip));
+                    self.read_children(node, &style, transform);
+                    self.drawing.push(Draw::PopState);
+                } else {
+                    self.read_children(node, &style, transform);
+                }
+            }
+
+            "path" | "rect" | "circle" | "ellipse" | "line" | "polyline" | "polygon" => {
+                let path = self.shape_path(node, &style);
+                let clip = self.clip_path(node, transform, svg_path_bounds(&path));
+
+                let has_clip = clip.is_some();
+                if has_clip {
+                    self.drawing.push(Draw::PushState);
+                    self.apply_clip(clip);
+                }
+
+                self.draw_shape(node, path, &style, transform, opacity);
+
+                if has_clip {
+                    self.drawing.push(Draw::PopState);
+                }
+            }
+
+            "text" => {
+                let clip = self.clip_path(node, transform, None);
+
+                let has_clip = clip.is_some();
+                if has_clip {
+                    self.drawing.push(Draw::PushState);
+                    self.apply_clip(clip);
+                }
+
+                self.draw_text(node, &style, transform, opacity);
+
+                if has_clip {
+                    self.drawing.push(Draw::PopState);
+                }
+            }
+
+            // Other elements (such as `<defs>`, gradients and clip paths) are not drawn directly
+            _ => {}
+        }
+    }
+
+    ///
+    /// Draws the children of a container element
+    ///
+    fn read_children(&mut self, node: Node<'a, 'input>, style: &SvgStyle, transform: Transform2D) {
+        match node.tag_name().name() {
+            "svg" => {
+                // Nested `<svg>` elements establish a new viewport
+                let x = self.length(node, "x", 0, style).unwrap_or(0.0);
+                let y = self.length(node, "y", 1, style).unwrap_or(0.0);
+                let (viewport, view_box_transform) = svg_viewport(node, self.viewport);
+                let is_root = node.parent_element().is_none();
+
+                let old_viewport = self.viewport;
+                self.viewport = viewport;
+
+                let transform = if is_root {
+                    transform * view_box_transform
+                } else {
+                    transform * Transform2D::translate(x, y) * view_box_transform
+                };
+                for child in node.children() {
+                    self.read_element(child, style, transform);
+                }
+
+                self.viewport = old_viewport;
+            }
+
+            "use" => {
+                if self.use_depth >= MAX_REFERENCE_DEPTH {
+                    return;
+                }
+
+                let x = self.length(node, "x", 0, style).unwrap_or(0.0);
+                let y = self.length(node, "y", 1, style).unwrap_or(0.0);
+                let transform = transform * Transform2D::translate(x, y);
+
+                if let Some(referenced) =
+                    svg_href(node).and_then(|id| self.elements.get(id)).copied()
+                {
+                    self.use_depth += 1;
+
+                    if referenced.tag_name().name() == "symbol" {
+                        // Symbols are only drawn when they're used, so their children are drawn instead of the symbol itself
+                        let style = style.for_element(referenced, self.viewport);
+                        for child in referenced.children() {
+                            self.read_element(child, &style, transform);
+                        }
+                    } else {
+                        self.read_element(referenced, style, transform);
+                    }
+
+                    self.use_depth -= 1;
+                }
+            }
+
+            "switch" => {
+                // Conditional processing attributes are not supported, so the first child element is always drawn
+                if let Some(child) = node.children().find(|child| child.is_element()) {
+                    self.read_element(child, style, transform);
+                }
+            }
+
+            _ => {
+                for child in node.children() {
+                    self.read_element(child, style, transform);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Sends the drawing instructions to the current target again (after drawing a sprite)
+    ///
+    fn select_target(&mut self) {
+        match self.targets.last() {
+            Some(SvgTarget::Layer(layer_id)) => self.drawing.push(Draw::Layer(*layer_id)),
+            Some(SvgTarget::Sprite(sprite_id)) => self.drawing.push(Draw::Sprite(*sprite_id)),
+            None => {}
+        }
+    }
+
+    ///
+    /// Generates the path for a shape element (in the element's coordinates)
+    ///
+    fn shape_path(&self, node: Node, style: &SvgStyle) -> Vec<PathOp> {
+        let length = |name, percent_of| self.length(node, name, percent_of, style);
+
+        match node.tag_name().name() {
+            "path" => node
+                .attribute("d")
+                .map(svg_parse_path_data)
+                .unwrap_or_default(),
+
+            "rect" => {
+                let position = (length("x", 0).unwrap_or(0.0), length("y", 1).unwrap_or(0.0));
+                let size = (
+                    length("width", 0).unwrap_or(0.0),
+                    length("height", 1).unwrap_or(0.0),
+                );
+
+                // If only one of the radii is specified, it's used for both
+                let radius_x = length("rx", 0);
+                let radius_y = length("ry", 1);
+                let radius = match (radius_x, radius_y) {
+                    (Some(rx), Some(ry)) => (rx, ry),
+                    (Some(rx), None) => (rx, rx),
+                    (None, Some(ry)) => (ry, ry),
+                    (None, None) => (0.0, 0.0),
+                };
+
+                if size.0 > 0.0 && size.1 > 0.0 {
+                    svg_rect_path(position, size, radius)
+                } else {
+                    vec![]
+                }
+            }
+
+            "circle" => {
+                let center = (
+                    length("cx", 0).unwrap_or(0.0),
+                    length("cy", 1).unwrap_or(0.0),
+                );
+                let radius = length("r", 2).unwrap_or(0.0);
+
+                if radius > 0.0 {
+                    svg_ellipse_path(center, (radius, radius))
+                } else {
+                    vec![]
+                }
+            }
+
+            "ellipse" => {
+                let center = (
+                    length("cx", 0).unwrap_or(0.0),
+                    length("cy", 1).unwrap_or(0.0),
+                );
+                let radius = (
+                    length("rx", 0).unwrap_or(0.0),
+                    length("ry", 1).unwrap_or(0.0),
+                );
+
+                if radius.0 > 0.0 && radius.1 > 0.0 {
+                    svg_ellipse_path(center, radius)
+                } else {
+                    vec![]
+                }
+            }
+
+            "line" => vec![
+                PathOp::Move(
+                    length("x1", 0).unwrap_or(0.0),
+                    length("y1", 1).unwrap_or(0.0),
+                ),
+                PathOp::Line(
+                    length("x2", 0).unwrap_or(0.0),
+                    length("y2", 1).unwrap_or(0.0),
+                ),
+            ],
+
+            "polyline" => svg_points_path(node.attribute("points").unwrap_or(""), false),
+            "polygon" => svg_points_path(node.attribute("points").unwrap_or(""), true),
+
+            _ => vec![],
+        }
+    }
+
+    ///
+    /// Generates the path for the 'clip-path' property of an element, in canvas coordinates
+    ///
+    /// The bounds are the bounding box of the element, which is used when the clip path uses `objectBoundingBox` units
+    /// (elements that have no bounds can only use clip paths in `userSpaceOnUse` units)
+    ///
+    fn clip_path(
+        &self,
+        node: Node,
+        transform: Transform2D,
+        bounds: Option<((f32, f32), (f32, f32))>,
+    ) -> Option<Vec<PathOp>> {
+        let clip_node = svg_property(node, "clip-path")
+            .and_then(svg_url_id)
+            .and_then(|id| self.elements.get(id))
+            .copied()
+            .filter(|clip_node| clip_node.tag_name().name() == "clipPath")?;
+
+        let mut transform = transform;
+        if let Some(clip_transform) = clip_node
+            .attribute("transform")
+            .and_then(svg_parse_transform)
+        {
+            transform = transform * clip_transform;
+        }
+        if clip_node.attribute("clipPathUnits") == Some("objectBoundingBox") {
+            let ((min_x, min_y), (max_x, max_y)) = bounds?;
+            transform = transform
+                * Transform2D::translate(min_x, min_y)
+                * Transform2D::scale(max_x - min_x, max_y - min_y);
+        }
+
+        // The clip path is made up from all of the shapes in the clip path element
+        let style = SvgStyle::default().for_element(clip_node, self.viewport);
+        let mut path = vec![];
+
+        for child in clip_node.children().filter(|child| child.is_element()) {
+            if svg_property(child, "display") == Some("none") {
+                continue;
+            }
+
+            let child_style = style.for_element(child, self.viewport);
+            let mut child_transform = transform;
+            if let Some(element_transform) =
+                child.attribute("transform").and_then(svg_parse_transform)
+            {
+                child_transform = child_transform * element_transform;
+            }
+
+            path.extend(
+                self.shape_path(child, &child_style)
+                    .into_iter()
+                    .map(|op| transform_path_op(op, &child_transform)),
+            );
+        }
+
+        Some(path)
+    }
+
+    ///
+    /// Sets a clip path generated by `clip_path()`
+    ///
+    fn apply_clip(&mut self, clip: Option<Vec<PathOp>>) {
+        if let Some(clip) = clip {
+            self.drawing.push(Draw::Path(PathOp::NewPath));
+            self.drawing.extend(clip.into_iter().map(Draw::Path));
+            self.drawing.push(Draw::Clip);
+            self.drawing.push(Draw::Path(PathOp::NewPath));
+        }
+    }
+
+    ///
+    /// Resolves a paint property to a fill style
+    ///
+    fn fill_style(
+        &mut self,
+        paint: &SvgPaint,
+        style: &SvgStyle,
+        opacity: f32,
+        bounds: Option<((f32, f32), (f32, f32))>,
+        transform: Transform2D,
+    ) -> SvgFillStyle {
+        match paint {
+            SvgPaint::None => SvgFillStyle::None,
+            SvgPaint::Color(color) => SvgFillStyle::Color(svg_color_with_opacity(*color, opacity)),
+            SvgPaint::CurrentColor => {
+                SvgFillStyle::Color(svg_color_with_opacity(style.color, opacity))
+            }
+
+            SvgPaint::Reference(id, fallback) => {
+                let gradient = self
+                    .elements
+                    .get(id.as_str())
+                    .copied()
+                    .and_then(|node| self.gradient(node, opacity, bounds, transform));
+
+                match (gradient, fallback) {
+                    (Some(gradient), _) => gradient,
+                    (None, Some(fallback)) => {
+                        SvgFillStyle::Color(svg_color_with_opacity(*fallback, opacity))
+                    }
+                    (None, None) => SvgFillStyle::None,
+                }
+            }
+        }
+    }
+
+    ///
+    /// Returns the value of an attribute of a gradient, following the 'href' attribute to find inherited values
+    ///
+    fn gradient_attribute(&self, node: Node<'a, 'input>, name: &str) -> Option<&'a str> {
+        let mut node = node;
+
+        for _ in 0..MAX_REFERENCE_DEPTH {
+            if let Some(value) = node.attribute(name) {
+                return Some(value);
+            }
+
+            node = svg_href(node)
+                .and_then(|id| self.elements.get(id))
+                .copied()?;
+        }
+
+        None
+    }
+
+    ///
+    /// Finds the stops for a gradient element, as a list of offsets and colours
+    ///
+    fn gradient_stops(&self, node: Node<'a, 'input>, opacity: f32) -> Vec<(f32, Color)> {
+        let mut node = node;
+
+        for _ in 0..MAX_REFERENCE_DEPTH {
+            let stop_nodes = node
+                .children()
+                .filter(|child| child.tag_name().name() == "stop")
+                .collect::<Vec<_>>();
+
+            if !stop_nodes.is_empty() {
+                // Offsets must be in order, so an offset lower than the previous one is moved up to match it
+                let mut last_offset = 0.0f32;
+
+                return stop_nodes
+                    .into_iter()
+                    .map(|stop| {
+                        let offset = stop
+                            .attribute("offset")
+                            .and_then(svg_parse_fraction)
+                            .unwrap_or(0.0)
+                            .clamp(0.0, 1.0)
+                            .max(last_offset);
+                        let color = svg_property(stop, "stop-color")
+                            .and_then(svg_parse_color)
+                            .unwrap_or(Color::Rgba(0.0, 0.0, 0.0, 1.0));
+                        let stop_opacity = svg_property(stop, "stop-opacity")
+                            .and_then(svg_parse_fraction)
+                            .unwrap_or(1.0)
+                            .clamp(0.0, 1.0);
+                        last_offset = offset;
+
+                        (
+                            offset,
+                            svg_color_with_opacity(color, stop_opacity * opacity),
+                        )
+                    })
+                    .collect();
+            }
+
+            node = match svg_href(node).and_then(|id| self.elements.get(id)).copied() {
+                Some(node) => node,
+                None => break,
+            };
+        }
+
+        vec![]
+    }
+
+    ///
+    /// Defines the gradient for a gradient element if it hasn't been defined already, returning the fill style that uses it
+    ///
+    fn gradient(
+        &mut self,
+        node: Node<'a, 'input>,
+        opacity: f32,
+        bounds: Option<((f32, f32), (f32, f32))>,
+        transform: Transform2D,
+    ) -> Option<SvgFillStyle> {
+        let name = node.tag_name().name();
+        if name != "linearGradient" && name != "radialGradient" {
+            return None;
+        }
+
+        let stops = self.gradient_stops(node, opacity);
+        match stops.len() {
+            0 => return Some(SvgFillStyle::None),
+            1 => return Some(SvgFillStyle::Color(stops[0].1)),
+            _ => {}
+        }
+
+        // Work out the coordinate system for the gradient
+        let bounding_box_units =
+            self.gradient_attribute(node, "gradientUnits") != Some("userSpaceOnUse");
+        let mut gradient_transform = transform;
+
+        if bounding_box_units {
+            let ((min_x, min_y), (max_x, max_y)) = bounds?;
+            if max_x <= min_x || max_y <= min_y {
+                // Bounding box units can't be used for elements with no width or height
+                return None;
+            }
+
+            gradient_transform = gradient_transform
+                * Transform2D::translate(min_x, min_y)
+                * Transform2D::scale(max_x - min_x, max_y - min_y);
+        }
+        if let Some(extra_transform) = self
+            .gradient_attribute(node, "gradientTransform")
+            .and_then(svg_parse_transform)
+        {
+            gradient_transform = gradient_transform * extra_transform;
+        }
+
+        // Coordinates in bounding box units are fractions, or lengths in user space units
+        let (width, height) = self.viewport;
+        let diagonal = ((width * width + height * height) / 2.0).sqrt();
+        let coordinate = |name: &str, percent_of: f32, default: &str| {
+            let value = self.gradient_attribute(node, name).unwrap_or(default);
+
+            if bounding_box_units {
+                svg_parse_fraction(value)
+            } else {
+                svg_parse_length(value, percent_of, 16.0)
+            }
+        };
+
+        let shape = if name == "linearGradient" {
+            SvgGradientShape::Linear(
+                (
+                    coordinate("x1", width, "0%")?,
+                    coordinate("y1", height, "0%")?,
+                ),
+                (
+                    coordinate("x2", width, "100%")?,
+                    coordinate("y2", height, "0%")?,
+                ),
+            )
+        } else {
+            let (cx, cy, r) = (
+                coordinate("cx", width, "50%")?,
+                coordinate("cy", height, "50%")?,
+                coordinate("r", diagonal, "50%")?,
+            );
+            let fx = self
+                .gradient_attribute(node, "fx")
+                .and_then(|_| coordinate("fx", width, "0"))
+                .unwrap_or(cx);
+            let fy = self
+                .gradient_attribute(node, "fy")
+                .and_then(|_| coordinate("fy", height, "0"))
+                .unwrap_or(cy);
+            let fr = coordinate("fr", diagonal, "0%")?;
+
+            SvgGradientShape::Radial((fx, fy, fr), (cx, cy, r))
+        };
+
+        // Gradients are shared between all the elements that use them with the same opacity
+        let id = node.attribute("id").unwrap_or("");
+        let key = (id, opacity.to_bits());
+        let gradient_id = if let Some(gradient_id) = self.gradients.get(&key) {
+            *gradient_id
+        } else {
+            let gradient_id = GradientId(self.next_gradient);
+            self.next_gradient += 1;
+
+            let spread = match self.gradient_attribute(node, "spreadMethod") {
+                Some("repeat") => GradientSpread::Repeat,
+                Some("reflect") => GradientSpread::Reflect,
+                _ => GradientSpread::Pad,
+            };
+
+            // The start and end of the gradient use the colours of the first and last stops
+            let (last_offset, last_color) = stops[stops.len() - 1];
+            self.drawing
+                .push(Draw::Gradient(gradient_id, GradientOp::Create(stops[0].1)));
+            self.drawing.extend(stops.iter().map(|(offset, color)| {
+                Draw::Gradient(gradient_id, GradientOp::AddStop(*offset, *color))
+            }));
+            if last_offset < 1.0 {
+                self.drawing.push(Draw::Gradient(
+                    gradient_id,
+                    GradientOp::AddStop(1.0, last_color),
+                ));
+            }
+            self.drawing
+                .push(Draw::Gradient(gradient_id, GradientOp::SpreadMode(spread)));
+
+            self.gradients.insert(key, gradient_id);
+            gradient_id
+        };
+
+        // Strokes and text use a single colour taken from the middle of the gradient
+        let gradient_ops = stops
+            .iter()
+            .map(|(offset, color)| GradientOp::AddStop(*offset, *color))
+            .collect::<Vec<_>>();
+        let (r, g, b, a) = svg_gradient_color(&gradient_ops, 0.5);
+
+        Some(SvgFillStyle::Gradient(
+            gradient_id,
+            shape,
+            gradient_transform,
+            Color::Rgba(r, g, b, a),
+        ))
+    }
+
+    ///
+    /// Sets the fill for a fill style
+    ///
+    fn set_fill(&mut self, fill: &SvgFillStyle) {
+        match fill {
+            SvgFillStyle::None => {}
+            SvgFillStyle::Color(color) => self.drawing.push(Draw::FillColor(*color)),
+
+            SvgFillStyle::Gradient(gradient_id, shape, transform, _) => {
+                match shape {
+                    SvgGradientShape::Linear(start, end) => {
+                        self.drawing
+                            .push(Draw::FillGradient(*gradient_id, *start, *end))
+                    }
+                    SvgGradientShape::Radial(focus, end) => self
+                        .drawing
+                        .push(Draw::FillRadialGradient(*gradient_id, *focus, *end)),
+                }
+
+                self.drawing.push(Draw::FillTransform(*transform));
+            }
+        }
+    }
+
+    ///
+    /// Draws a shape with the fill and stroke from its style
+    ///
+    fn draw_shape(
+        &mut self,
+        node: Node,
+        path: Vec<PathOp>,
+        style: &SvgStyle,
+        transform: Transform2D,
+        opacity: f32,
+    ) {
+        if path.is_empty() || !style.visible {
+            return;
+        }
+
+        // Lines have no area so they are never filled
+        let bounds = svg_path_bounds(&path);
+        let fill = if node.tag_name().name() == "line" {
+            SvgFillStyle::None
+        } else {
+            self.fill_style(
+                &style.fill,
+                style,
+                style.fill_opacity * opacity,
+                bounds,
+                transform,
+            )
+        };
+        let stroke = if style.stroke_width > 0.0 {
+            self.fill_style(
+                &style.stroke,
+                style,
+                style.stroke_opacity * opacity,
+                bounds,
+                transform,
+            )
+        } else {
+            SvgFillStyle::None
+        };
+
+        if matches!((&fill, &stroke), (SvgFillStyle::None, SvgFillStyle::None)) {
+            return;
+        }
+
+        self.drawing.push(Draw::Path(PathOp::NewPath));
+        self.drawing.extend(
+            path.into_iter()
+                .map(|op| Draw::Path(transform_path_op(op, &transform))),
+        );
+
+        if !matches!(fill, SvgFillStyle::None) {
+            self.set_fill(&fill);
+            self.drawing.push(Draw::WindingRule(style.fill_rule));
+            self.drawing.push(Draw::Fill);
+        }
+
+        let stroke_color = match stroke {
+            SvgFillStyle::None => None,
+            SvgFillStyle::Color(color) => Some(color),
+            SvgFillStyle::Gradient(_, _, _, color) => Some(color),
+        };
+
+        if let Some(stroke_color) = stroke_color {
+            // The transform has already been applied to the path, so the stroke measurements need to be scaled to match
+            let scale = svg_transform_scale(&transform);
+
+            self.drawing.push(Draw::StrokeColor(stroke_color));
+            self.drawing
+                .push(Draw::LineWidth(style.stroke_width * scale));
+            self.drawing.push(Draw::LineJoin(style.line_join));
+            self.drawing.push(Draw::LineCap(style.line_cap));
+            self.drawing.push(Draw::NewDashPattern);
+            if !style.dash_array.is_empty() {
+                self.drawing.extend(
+                    style
+                        .dash_array
+                        .iter()
+                        .map(|dash| Draw::DashLength(dash * scale)),
+                );
+                self.drawing
+                    .push(Draw::DashOffset(style.dash_offset * scale));
+            }
+            self.drawing.push(Draw::Stroke);
+        }
+    }
+
+    ///
+    /// Draws a `<text>` element
+    ///
+    fn draw_text(&mut self, node: Node, style: &SvgStyle, transform: Transform2D, opacity: f32) {
+        let text = svg_text_content(node);
+        if text.is_empty() || !style.visible {
+            return;
+        }
+
+        // Text is drawn using the fill colour (gradients are not supported for text, so the middle colour is used instead)
+        let color = match self.fill_style(
+            &style.fill,
+            style,
+            style.fill_opacity * opacity,
+            None,
+            transform,
+        ) {
+            SvgFillStyle::None => {
+                return;
+            }
+            SvgFillStyle::Color(color) => color,
+            SvgFillStyle::Gradient(_, _, _, color) => color,
+        };
+
+        // The first font family that has a font ID is used
+        let font_id = style
+            .font_family
+            .split(',')
+            .map(|family| family.trim().trim_matches(|c| c == '\'' || c == '"'))
+            .find_map(|family| self.settings.fonts.get(family).copied())
+            .unwrap_or(self.settings.default_font);
+
+        let x = self.length(node, "x", 0, style).unwrap_or(0.0);
+        let y = self.length(node, "y", 1, style).unwrap_or(0.0);
+        let (x, y) = transform.transform_point(x, y);
+        let font_size = style.font_size * svg_transform_scale(&transform);
+
+        self.drawing.push(Draw::FillColor(color));
+        self.drawing
+            .push(Draw::Font(font_id, FontOp::FontSize(font_size)));
+
+        match style.text_anchor {
+            TextAlignment::Left => {
+                self.drawing.push(Draw::DrawText(font_id, text, x, y));
+            }
+
+            alignment => {
+                self.drawing.push(Draw::BeginLineLayout(x, y, alignment));
+                self.drawing
+                    .push(Draw::Font(font_id, FontOp::LayoutText(text)));
+                self.drawing.push(Draw::DrawLaidOutText);
+            }
+        }
+    }
+}
+
+///
+/// Applies a transform to the coordinates in a path operation
+///
+fn transform_path_op(op: PathOp, transform: &Transform2D) -> PathOp {
+    match op {
+        PathOp::NewPath => PathOp::NewPath,
+        PathOp::ClosePath => PathOp::ClosePath,
+        PathOp::Move(x, y) => {
+            let (x, y) = transform.transform_point(x, y);
+            PathOp::Move(x, y)
+        }
+        PathOp::Line(x, y) => {
+            let (x, y) = transform.transform_point(x, y);
+            PathOp::Line(x, y)
+        }
+        PathOp::BezierCurve(((cp1x, cp1y), (cp2x, cp2y)), (x, y)) => PathOp::BezierCurve(
+            (
+                transform.transform_point(cp1x, cp1y),
+                transform.transform_point(cp2x, cp2y),
+            ),
+            transform.transform_point(x, y),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn paths(drawing: &[Draw]) -> Vec<Vec<PathOp>> {
+        let mut paths = vec![];
+
+        for draw in drawing.iter() {
+            match draw {
+                Draw::Path(PathOp::NewPath) => paths.push(vec![]),
+                Draw::Path(op) => paths.last_mut().unwrap().push(*op),
+                _ => {}
+            }
+        }
+
+        paths.into_iter().filter(|path| !path.is_empty()).collect()
+    }
+
+    #[test]
+    fn not_svg() {
+        assert!(svg_to_drawing("<html></html>") == Err(SvgReadError::NotSvg));
+        assert!(matches!(
+            svg_to_drawing("<svg"),
+            Err(SvgReadError::InvalidXml(_))
+        ));
+    }
+
+    #[test]
+    fn document_size_from_view_box() {
+        assert!(
+            svg_document_size("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 64 32\"/>")
+                == Ok((64.0, 32.0))
+        );
+        assert!(svg_document_size("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"1in\" height=\"20\" viewBox=\"0 0 64 32\"/>") == Ok((96.0, 20.0)));
+    }
+
+    #[test]
+    fn filled_rect_is_flipped() {
+        let drawing = svg_to_drawing("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"100\"><rect x=\"10\" y=\"20\" width=\"30\" height=\"40\" fill=\"red\"/></svg>").unwrap();
+
+        assert!(
+            paths(&drawing)
+                == vec![vec![
+                    PathOp::Move(10.0, 80.0),
+                    PathOp::Line(40.0, 80.0),
+                    PathOp::Line(40.0, 40.0),
+                    PathOp::Line(10.0, 40.0),
+                    PathOp::ClosePath
+                ]]
+        );
+        assert!(drawing.contains(&Draw::FillColor(Color::Rgba(1.0, 0.0, 0.0, 1.0))));
+        assert!(drawing.contains(&Draw::Fill));
+        assert!(!drawing.contains(&Draw::Stroke));
+        assert!(
+            drawing.first() == Some(&Draw::PushState) && drawing.last() == Some(&Draw::PopState)
+        );
+    }
+
+    #[test]
+    fn stroke_from_style_and_transform() {
+        let drawing = svg_to_drawing(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"100\">
+                <g style=\"stroke: #0000ff; stroke-width: 2; stroke-dasharray: 1 2 3\" transform=\"scale(2)\">
+                    <line x1=\"0\" y1=\"0\" x2=\"10\" y2=\"0\" stroke-linecap=\"round\"/>
+                </g>
+            </svg>",
+        )
+        .unwrap();
+
+        assert!(paths(&drawing) == vec![vec![PathOp::Move(0.0, 100.0), PathOp::Line(20.0, 100.0)]]);
+        assert!(drawing.contains(&Draw::StrokeColor(Color::Rgba(0.0, 0.0, 1.0, 1.0))));
+        assert!(drawing.contains(&Draw::LineWidth(4.0)));
+        assert!(drawing.contains(&Draw::LineCap(LineCap::Round)));
+        assert!(!drawing.contains(&Draw::Fill));
+
+        let dashes = drawing
+            .iter()
+            .filter_map(|draw| match draw {
+                Draw::DashLength(length) => Some(*length),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(dashes == vec![2.0, 4.0, 6.0, 2.0, 4.0, 6.0]);
+    }
+
+    #[test]
+    fn linear_gradient_in_bounding_box_units() {
+        let drawing = svg_to_drawing(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"100\">
+                <defs>
+                    <linearGradient id=\"stops\" spreadMethod=\"reflect\">
+                        <stop offset=\"0\" stop-color=\"red\"/>
+                        <stop offset=\"50%\" style=\"stop-color: blue; stop-opacity: 0.5\"/>
+                    </linearGradient>
+                    <linearGradient id=\"gradient\" href=\"#stops\" x2=\"0\" y2=\"1\"/>
+                </defs>
+                <rect x=\"10\" y=\"10\" width=\"20\" height=\"40\" fill=\"url(#gradient)\"/>
+            </svg>",
+        )
+        .unwrap();
+
+        assert!(drawing.contains(&Draw::Gradient(
+            GradientId(0),
+            GradientOp::Create(Color::Rgba(1.0, 0.0, 0.0, 1.0))
+        )));
+        assert!(drawing.contains(&Draw::Gradient(
+            GradientId(0),
+            GradientOp::AddStop(0.5, Color::Rgba(0.0, 0.0, 1.0, 0.5))
+        )));
+        assert!(drawing.contains(&Draw::Gradient(
+            GradientId(0),
+            GradientOp::AddStop(1.0, Color::Rgba(0.0, 0.0, 1.0, 0.5))
+        )));
+        assert!(drawing.contains(&Draw::Gradient(
+            GradientId(0),
+            GradientOp::SpreadMode(GradientSpread::Reflect)
+        )));
+        assert!(drawing.contains(&Draw::FillGradient(GradientId(0), (0.0, 0.0), (0.0, 1.0))));
+
+        // The fill transform maps the unit square onto the bounds of the rectangle
+        let transform = drawing
+            .iter()
+            .filter_map(|draw| match draw {
+                Draw::FillTransform(transform) => Some(*transform),
+                _ => None,
+            })
+            .next()
+            .unwrap();
+        assert!(transform.transform_point(0.0, 0.0) == (10.0, 90.0));
+        assert!(transform.transform_point(1.0, 1.0) == (30.0, 50.0));
+    }
+
+    #[test]
+    fn radial_gradient_focus_defaults_to_center() {
+        let drawing = svg_to_drawing(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"100\">
+                <radialGradient id=\"gradient\" gradientUnits=\"userSpaceOnUse\" cx=\"50\" cy=\"40\" r=\"30\">
+                    <stop offset=\"0\" stop-color=\"white\"/>
+                    <stop offset=\"1\" stop-color=\"black\"/>
+                </radialGradient>
+                <circle cx=\"50\" cy=\"50\" r=\"40\" fill=\"url(#gradient)\"/>
+            </svg>",
+        )
+        .unwrap();
+
+        assert!(drawing.contains(&Draw::FillRadialGradient(
+            GradientId(0),
+            (50.0, 40.0, 0.0),
+            (50.0, 40.0, 30.0)
+        )));
+    }
+
+    #[test]
+    fn missing_gradient_uses_fallback() {
+        let drawing = svg_to_drawing("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"10\" height=\"10\"><path d=\"M0 0 L10 10 L0 10 Z\" fill=\"url(#missing) lime\"/></svg>").unwrap();
+
+        assert!(drawing.contains(&Draw::FillColor(Color::Rgba(0.0, 1.0, 0.0, 1.0))));
+    }
+
+    #[test]
+    fn clip_path_is_applied() {
+        let drawing = svg_to_drawing(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"100\">
+                <clipPath id=\"clip\"><rect width=\"50\" height=\"50\"/></clipPath>
+                <g clip-path=\"url(#clip)\"><circle cx=\"50\" cy=\"50\" r=\"50\"/></g>
+            </svg>",
+        )
+        .unwrap();
+
+        let clip_pos = drawing.iter().position(|draw| draw == &Draw::Clip).unwrap();
+        let fill_pos = drawing.iter().position(|draw| draw == &Draw::Fill).unwrap();
+        assert!(clip_pos < fill_pos);
+        assert!(drawing[clip_pos - 1] == Draw::Path(PathOp::ClosePath));
+        assert!(drawing.contains(&Draw::Path(PathOp::Line(50.0, 100.0))));
+        assert!(drawing[fill_pos + 1] == Draw::PopState);
+    }
+
+    #[test]
+    fn group_opacity_uses_sprite() {
+        let drawing = SvgReader::new()
+            .with_layer(LayerId(3))
+            .with_first_sprite(SpriteId(7))
+            .read("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"100\"><g opacity=\"0.5\"><rect width=\"10\" height=\"10\"/></g></svg>")
+            .unwrap();
+
+        let sprite_pos = drawing
+            .iter()
+            .position(|draw| draw == &Draw::Sprite(SpriteId(7)))
+            .unwrap();
+        let layer_pos = drawing
+            .iter()
+            .position(|draw| draw == &Draw::Layer(LayerId(3)))
+            .unwrap();
+        let fill_pos = drawing.iter().position(|draw| draw == &Draw::Fill).unwrap();
+
+        assert!(sprite_pos < fill_pos && fill_pos < layer_pos);
+        assert!(drawing.contains(&Draw::DrawSpriteWithFilters(
+            SpriteId(7),
+            vec![TextureFilter::AlphaBlend(0.5)]
+        )));
+    }
+
+    #[test]
+    fn text_uses_font_for_family() {
+        let drawing = SvgReader::new()
+            .with_font("Sans", FontId(2))
+            .read(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"100\">
+                    <text x=\"10\" y=\"20\" font-family=\"'Missing', Sans\" font-size=\"12\" fill=\"blue\">Hello <tspan>world</tspan></text>
+                    <text x=\"50\" y=\"50\" text-anchor=\"middle\">Centered</text>
+                </svg>",
+            )
+            .unwrap();
+
+        assert!(drawing.contains(&Draw::Font(FontId(2), FontOp::FontSize(12.0))));
+        assert!(drawing.contains(&Draw::DrawText(
+            FontId(2),
+            "Hello world".to_string(),
+            10.0,
+            80.0
+        )));
+        assert!(drawing.contains(&Draw::BeginLineLayout(50.0, 50.0, TextAlignment::Center)));
+        assert!(drawing.contains(&Draw::Font(
+            FontId(0),
+            FontOp::LayoutText("Centered".to_string())
+        )));
+    }
+
+    #[test]
+    fn use_element_draws_symbol() {
+        let drawing = svg_to_drawing(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" width=\"100\" height=\"100\">
+                <symbol id=\"dot\"><rect width=\"1\" height=\"1\"/></symbol>
+                <use xlink:href=\"#dot\" x=\"5\" y=\"5\"/>
+                <use href=\"#dot\" x=\"10\" y=\"10\"/>
+            </svg>",
+        )
+        .unwrap();
+
+        let paths = paths(&drawing);
+        assert!(paths.len() == 2);
+        assert!(paths[0][0] == PathOp::Move(5.0, 95.0));
+        assert!(paths[1][0] == PathOp::Move(10.0, 90.0));
+    }
+
+    #[test]
+    fn view_box_scales_drawing() {
+        let drawing = svg_to_drawing("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"100\" viewBox=\"0 0 10 10\"><path d=\"M1 1 H 2\" stroke=\"black\" fill=\"none\"/></svg>").unwrap();
+
+        assert!(paths(&drawing) == vec![vec![PathOp::Move(10.0, 90.0), PathOp::Line(20.0, 90.0)]]);
+        assert!(drawing.contains(&Draw::LineWidth(10.0)));
+    }
+}
@@ -0,0 +1,346 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::svg_format::*;
+
+use crate::gradient::*;
+use crate::sprite::*;
+use crate::texture::*;
+use crate::transform2d::*;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+
+use std::cmp::Ordering;
+use std::fmt::Write;
+use std::sync::*;
+
+///
+/// The content of a texture that's being written to a SVG document
+///
+#[derive(Clone, Debug)]
+pub enum SvgTextureContent {
+    /// A bitmap, as RGBA bytes
+    Bitmap(Arc<Vec<u8>>),
+
+    /// A sprite, rendered so that its bounds cover the texture (dynamic sprites are updated whenever the sprite changes)
+    Sprite {
+        sprite_id: SpriteId,
+        bounds: SpriteBounds,
+        dynamic: bool,
+        sprite_definition: Option<String>,
+    },
+}
+
+///
+/// A texture that's being written to a SVG document
+///
+#[derive(Clone, Debug)]
+pub struct SvgTexture {
+    /// The width of the texture in pixels
+    pub width: u32,
+
+    /// The height of the texture in pixels
+    pub height: u32,
+
+    /// The content of this texture
+    pub content: SvgTextureContent,
+
+    /// The transparency to use when filling with this texture
+    pub alpha: f32,
+
+    /// The filters that have been applied to this texture
+    pub filters: Vec<TextureFilter>,
+
+    /// The ID of the definition for the current content of this texture, if it has been written
+    pub definition: Option<String>,
+}
+
+impl SvgTexture {
+    ///
+    /// Creates a new transparent bitmap texture
+    ///
+    pub fn new(TextureSize(width, height): TextureSize) -> SvgTexture {
+        SvgTexture {
+            width,
+            height,
+            content: SvgTextureContent::Bitmap(Arc::new(vec![
+                0;
+                (width as usize)
+                    * (height as usize)
+                    * 4
+            ])),
+            alpha: 1.0,
+            filters: vec![],
+            definition: None,
+        }
+    }
+
+    ///
+    /// Writes bytes to a region of this texture
+    ///
+    pub fn set_bytes(
+        &mut self,
+        TexturePosition(x, y): TexturePosition,
+        TextureSize(width, height): TextureSize,
+        bytes: &[u8],
+    ) {
+        // Writing bytes to a sprite texture replaces it with a bitmap
+        if let SvgTextureContent::Sprite { .. } = &self.content {
+            self.content = SvgTexture::new(TextureSize(self.width, self.height)).content;
+        }
+
+        if let SvgTextureContent::Bitmap(pixels) = &mut self.content {
+            let pixels = Arc::make_mut(pixels);
+            let texture_width = self.width as usize;
+
+            for row in 0..(height as usize) {
+                let target_y = (y as usize) + row;
+                if target_y >= self.height as usize {
+                    break;
+                }
+
+                // Clip the row to the width of the texture
+                let row_width = (width as usize).min(texture_width.saturating_sub(x as usize));
+                let source_start = row * (width as usize) * 4;
+                let target_start = (target_y * texture_width + (x as usize)) * 4;
+
+                if source_start + row_width * 4 > bytes.len() {
+                    break;
+                }
+
+                pixels[target_start..(target_start + row_width * 4)]
+                    .copy_from_slice(&bytes[source_start..(source_start + row_width * 4)]);
+            }
+        }
+
+        self.definition = None;
+    }
+}
+
+///
+/// Encodes a RGBA bitmap as a PNG data URI
+///
+pub fn svg_png_data_uri(width: u32, height: u32, pixels: &[u8]) -> String {
+    let mut png_data = vec![];
+
+    {
+        let mut encoder = png::Encoder::new(&mut png_data, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let written = encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(pixels));
+
+        if written.is_err() {
+            // Only happens if the size of the image does not match the number of pixels, or for textures with no pixels
+            png_data.clear();
+        }
+    }
+
+    format!(
+        "data:image/png;base64,{}",
+        general_purpose::STANDARD.encode(&png_data)
+    )
+}
+
+///
+/// Returns the value of the 'spreadMethod' attribute for a gradient
+///
+pub fn svg_spread_method(spread: GradientSpread) -> &'static str {
+    match spread {
+        GradientSpread::Pad => "pad",
+        GradientSpread::Repeat => "repeat",
+        GradientSpread::Reflect => "reflect",
+    }
+}
+
+///
+/// Returns the stops in a gradient, ordered by position, as RGBA components
+///
+fn gradient_stops(gradient: &[GradientOp]) -> Vec<(f32, (f32, f32, f32, f32))> {
+    let mut stops = vec![];
+
+    for op in gradient.iter() {
+        match op {
+            GradientOp::Create(color) => {
+                stops = vec![(0.0, color.to_rgba_components())];
+            }
+            GradientOp::AddStop(pos, color) => {
+                stops.push((*pos, color.to_rgba_components()));
+            }
+            GradientOp::SpreadMode(_) => {}
+        }
+    }
+
+    stops.sort_by(|(pos_a, _), (pos_b, _)| pos_a.partial_cmp(pos_b).unwrap_or(Ordering::Equal));
+    stops
+}
+
+///
+/// Generates the `<stop>` elements for a gradient
+///
+pub fn svg_gradient_stops(gradient: &[GradientOp]) -> String {
+    let mut stops = String::new();
+
+    for (pos, (r, g, b, a)) in gradient_stops(gradient) {
+        let (color, alpha) = svg_color(&crate::color::Color::Rgba(r, g, b, a));
+
+        write!(
+            stops,
+            "<stop offset=\"{}\" stop-color=\"{}\" stop-opacity=\"{}\"/>",
+            svg_number(pos.clamp(0.0, 1.0)),
+            color,
+            svg_number(alpha)
+        )
+        .ok();
+    }
+
+    stops
+}
+
+///
+/// Returns the colour at a particular position in a gradient (positions outside of the stops use the colour of the nearest stop)
+///
+pub fn svg_gradient_color(gradient: &[GradientOp], pos: f32) -> (f32, f32, f32, f32) {
+    let stops = gradient_stops(gradient);
+
+    if stops.is_empty() {
+        return (0.0, 0.0, 0.0, 0.0);
+    }
+
+    if pos <= stops[0].0 {
+        return stops[0].1;
+    }
+
+    for idx in 1..stops.len() {
+        let (start_pos, start_color) = stops[idx - 1];
+        let (end_pos, end_color) = stops[idx];
+
+        if pos <= end_pos {
+            let ratio = if end_pos > start_pos {
+                (pos - start_pos) / (end_pos - start_pos)
+            } else {
+                1.0
+            };
+            let mix = |a: f32, b: f32| a + (b - a) * ratio;
+
+            return (
+                mix(start_color.0, end_color.0),
+                mix(start_color.1, end_color.1),
+                mix(start_color.2, end_color.2),
+                mix(start_color.3, end_color.3),
+            );
+        }
+    }
+
+    stops[stops.len() - 1].1
+}
+
+///
+/// Returns the scale factor of a transform (how much it changes the size of a unit length)
+///
+pub fn svg_transform_scale(transform: &Transform2D) -> f32 {
+    let Transform2D(m) = transform;
+
+    (m[0][0] * m[1][1] - m[0][1] * m[1][0]).abs().sqrt()
+}
+
+///
+/// Generates the content of a `<filter>` element that applies a list of texture filters in order
+///
+/// Measurements in the filters are multiplied by `unit_scale` to convert them to the coordinates used by the filtered
+/// element. Mask and displacement map textures are read using the `texture_image` function, which should return a data
+/// URI for the texture: filters that use a texture that can't be found are skipped.
+///
+pub fn svg_filter_primitives(
+    filters: &[TextureFilter],
+    unit_scale: f32,
+    texture_image: impl Fn(TextureId) -> Option<String>,
+) -> String {
+    use self::TextureFilter::*;
+
+    let mut primitives = String::new();
+    let mut input = "SourceGraphic".to_string();
+
+    for (idx, filter) in filters.iter().enumerate() {
+        let result = format!("filter{}", idx);
+
+        match filter {
+            GaussianBlur(radius) => {
+                // The sigma for a blur is a quarter of its radius
+                write!(
+                    primitives,
+                    "<feGaussianBlur in=\"{}\" stdDeviation=\"{}\" result=\"{}\"/>",
+                    input,
+                    svg_number(radius.abs() * 0.25 * unit_scale),
+                    result
+                )
+                .ok();
+            }
+
+            AlphaBlend(alpha) => {
+                write!(primitives, "<feComponentTransfer in=\"{}\" result=\"{}\"><feFuncA type=\"linear\" slope=\"{}\"/></feComponentTransfer>", input, result, svg_number(*alpha)).ok();
+            }
+
+            Mask(mask_texture) => {
+                if let Some(mask_image) = texture_image(*mask_texture) {
+                    write!(primitives, "<feImage preserveAspectRatio=\"none\" xlink:href=\"{}\" result=\"{}_mask\"/>", mask_image, result).ok();
+                    write!(
+                        primitives,
+                        "<feComposite in=\"{}\" in2=\"{}_mask\" operator=\"in\" result=\"{}\"/>",
+                        input, result, result
+                    )
+                    .ok();
+                } else {
+                    continue;
+                }
+            }
+
+            DisplacementMap(displacement_texture, x_radius, y_radius) => {
+                if let Some(displacement_image) = texture_image(*displacement_texture) {
+                    // SVG uses the same scale factor for both directions, so the channel for the smaller radius is scaled down
+                    let x_radius = x_radius * unit_scale;
+                    let y_radius = y_radius * unit_scale;
+                    let max_radius = x_radius.abs().max(y_radius.abs());
+                    let (x_slope, y_slope) = if max_radius > 0.0 {
+                        (x_radius / max_radius, y_radius / max_radius)
+                    } else {
+                        (0.0, 0.0)
+                    };
+
+                    write!(primitives, "<feImage preserveAspectRatio=\"none\" xlink:href=\"{}\" result=\"{}_map\"/>", displacement_image, result).ok();
+                    write!(primitives, "<feComponentTransfer in=\"{}_map\" result=\"{}_scaled\"><feFuncR type=\"linear\" slope=\"{}\" intercept=\"{}\"/><feFuncG type=\"linear\" slope=\"{}\" intercept=\"{}\"/></feComponentTransfer>",
+                        result, result,
+                        svg_number(x_slope), svg_number(0.5 - 0.5 * x_slope),
+                        svg_number(y_slope), svg_number(0.5 - 0.5 * y_slope)).ok();
+                    write!(primitives, "<feDisplacementMap in=\"{}\" in2=\"{}_scaled\" scale=\"{}\" xChannelSelector=\"R\" yChannelSelector=\"G\" result=\"{}\"/>",
+                        input, result, svg_number(max_radius * 2.0), result).ok();
+                } else {
+                    continue;
+                }
+            }
+        }
+
+        input = result;
+    }
+
+    primitives
+}
+
+///
+/// True if a list of filters needs the filter region to match the bounds of the element being filtered
+///
+/// Masks and displacement maps are stretched over the whole filter region, so they need to match the element being filtered,
+/// but other filters can spread beyond the edges of the element.
+///
+pub fn svg_filter_uses_bounds(filters: &[TextureFilter]) -> bool {
+    filters.iter().any(|filter| match filter {
+        TextureFilter::Mask(_) | TextureFilter::DisplacementMap(..) => true,
+        TextureFilter::GaussianBlur(_) | TextureFilter::AlphaBlend(_) => false,
+    })
+}
@@ -0,0 +1,1548 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::svg_format::*;
+use super::svg_resources::*;
+
+use crate::color::*;
+use crate::draw::*;
+use crate::gradient::*;
+use crate::namespace::*;
+use crate::path::*;
+use crate::sprite::*;
+use crate::texture::*;
+use crate::transform2d::*;
+
+use futures::prelude::*;
+
+use std::collections::{BTreeMap, HashMap};
+use std::f32;
+use std::fmt::Write;
+
+/// The number of wedges used to approximate a conic gradient (SVG has no native conic gradient)
+const CONIC_GRADIENT_WEDGES: usize = 180;
+
+///
+/// How the current path will be filled
+///
+#[derive(Clone, Debug)]
+enum SvgFill {
+    /// Fill with a solid colour
+    Color(Color),
+
+    /// Fill with a texture (definition, width, height, alpha, lower-left and upper-right coordinates)
+    Texture(String, u32, u32, f32, (f32, f32), (f32, f32)),
+
+    /// Fill with a linear gradient (the gradient operations are copied from when the fill was set)
+    LinearGradient(Vec<GradientOp>, (f32, f32), (f32, f32)),
+
+    /// Fill with a radial gradient
+    RadialGradient(Vec<GradientOp>, (f32, f32, f32), (f32, f32, f32)),
+
+    /// Fill with a conic gradient
+    ConicGradient(Vec<GradientOp>, (f32, f32), f32),
+}
+
+///
+/// The part of the state of the writer that is saved by `PushState`
+///
+#[derive(Clone, Debug)]
+struct SvgState {
+    /// The current path
+    path: Vec<PathOp>,
+
+    /// Transform from canvas coordinates to viewport coordinates
+    transform: Transform2D,
+
+    /// The current fill
+    fill: SvgFill,
+
+    /// Transform applied to gradient and texture fills
+    fill_transform: Transform2D,
+
+    /// Colour for strokes
+    stroke_color: Color,
+
+    /// Width of the line in canvas units
+    line_width: f32,
+
+    /// Width of the line in pixels, if it was set using `LineWidthPixels`
+    line_width_pixels: Option<f32>,
+
+    line_join: LineJoin,
+    line_cap: LineCap,
+    dash_pattern: Vec<f32>,
+    dash_offset: f32,
+    winding_rule: WindingRule,
+    blend_mode: BlendMode,
+
+    /// The ID of the clip path definition, if a clip path is set
+    clip: Option<String>,
+
+    /// The transform applied by `DrawSprite`
+    sprite_transform: Transform2D,
+}
+
+///
+/// Where the drawing instructions are being sent
+///
+#[derive(Clone, Copy, Debug)]
+enum SvgTarget {
+    Layer(LayerId),
+    Sprite(NamespaceId, SpriteId),
+}
+
+///
+/// A layer in the SVG output
+///
+#[derive(Clone, Debug)]
+struct SvgLayer {
+    elements: Vec<String>,
+    blend_mode: BlendMode,
+    alpha: f32,
+
+    /// The number of elements that were in the layer when `Store` was called
+    stored: Option<usize>,
+}
+
+///
+/// A sprite in the SVG output
+///
+#[derive(Clone, Debug)]
+struct SvgSprite {
+    elements: Vec<String>,
+
+    /// Transform from viewport coordinates back to the canvas coordinates used when the sprite was selected
+    inverse_transform: Transform2D,
+
+    /// The ID of the `<symbol>` element for the current version of this sprite, if it has been written
+    definition: Option<String>,
+}
+
+///
+/// Converts canvas drawing instructions into a SVG document
+///
+/// The canvas coordinates are mapped to the document in the same way as they are mapped to a window: the viewport
+/// coordinates run from -1 to 1 vertically, with the x axis scaled so pixels are square.
+///
+/// Paths, fills, strokes, gradients, clipping, layers, transforms, sprites and textures are all supported. Blend modes are
+/// mapped to CSS mix blend modes, which has no equivalent for the Porter-Duff modes other than 'source over': these are
+/// drawn using the normal blend mode. SVG has no conic gradients, so these are approximated by a set of wedges. Text
+/// instructions are ignored: use `drawing_with_text_as_paths()` to convert text to paths before writing it to a SVG
+/// document.
+///
+pub struct SvgWriter {
+    /// The size of the document in pixels
+    size: (f32, f32),
+
+    /// The colour set by the last `ClearCanvas` instruction
+    background: Color,
+
+    /// The namespace for resource IDs
+    namespace: NamespaceId,
+
+    state: SvgState,
+    state_stack: Vec<SvgState>,
+    target: SvgTarget,
+
+    layers: BTreeMap<u64, SvgLayer>,
+    sprites: HashMap<(NamespaceId, SpriteId), SvgSprite>,
+    gradients: HashMap<(NamespaceId, GradientId), Vec<GradientOp>>,
+    textures: HashMap<(NamespaceId, TextureId), SvgTexture>,
+
+    /// The definitions that are referenced from the document (gradients, clip paths, sprites, etc)
+    definitions: Vec<String>,
+
+    /// Used to generate unique IDs for the definitions
+    next_id: usize,
+}
+
+impl Default for SvgState {
+    fn default() -> SvgState {
+        SvgState {
+            path: vec![],
+            transform: Transform2D::identity(),
+            fill: SvgFill::Color(Color::Rgba(0.0, 0.0, 0.0, 1.0)),
+            fill_transform: Transform2D::identity(),
+            stroke_color: Color::Rgba(0.0, 0.0, 0.0, 1.0),
+            line_width: 1.0,
+            line_width_pixels: None,
+            line_join: LineJoin::Round,
+            line_cap: LineCap::Butt,
+            dash_pattern: vec![],
+            dash_offset: 0.0,
+            winding_rule: WindingRule::NonZero,
+            blend_mode: BlendMode::SourceOver,
+            clip: None,
+            sprite_transform: Transform2D::identity(),
+        }
+    }
+}
+
+impl SvgLayer {
+    fn new() -> SvgLayer {
+        SvgLayer {
+            elements: vec![],
+            blend_mode: BlendMode::SourceOver,
+            alpha: 1.0,
+            stored: None,
+        }
+    }
+}
+
+impl SvgWriter {
+    ///
+    /// Creates a new SVG writer that will generate a document of the specified size in pixels
+    ///
+    pub fn new(width: f32, height: f32) -> SvgWriter {
+        SvgWriter {
+            size: (width, height),
+            background: Color::Rgba(0.0, 0.0, 0.0, 0.0),
+            namespace: NamespaceId::default(),
+            state: SvgState::default(),
+            state_stack: vec![],
+            target: SvgTarget::Layer(LayerId(0)),
+            layers: BTreeMap::new(),
+            sprites: HashMap::new(),
+            gradients: HashMap::new(),
+            textures: HashMap::new(),
+            definitions: vec![],
+            next_id: 0,
+        }
+    }
+
+    ///
+    /// Adds some drawing instructions to the document
+    ///
+    pub fn draw(&mut self, drawing: impl IntoIterator<Item = Draw>) {
+        for draw in drawing {
+            self.draw_one(draw);
+        }
+    }
+
+    ///
+    /// Generates the SVG document for the drawing instructions that have been written so far
+    ///
+    pub fn to_svg(&self) -> String {
+        let (width, height) = self.size;
+        let mut svg = String::new();
+
+        write!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+            svg_number(width), svg_number(height), svg_number(width), svg_number(height)).ok();
+
+        // Definitions
+        if !self.definitions.is_empty() {
+            svg.push_str("<defs>");
+            self.definitions
+                .iter()
+                .for_each(|definition| svg.push_str(definition));
+            svg.push_str("</defs>");
+        }
+
+        // Background
+        let (background, background_alpha) = svg_color(&self.background);
+        if background_alpha > 0.0 {
+            write!(
+                svg,
+                "<rect width=\"{}\" height=\"{}\" fill=\"{}\"{}/>",
+                svg_number(width),
+                svg_number(height),
+                background,
+                opacity_attribute("fill-opacity", background_alpha)
+            )
+            .ok();
+        }
+
+        // The viewport runs from -1 to 1 vertically, with the y axis pointing upwards
+        let viewport_transform = Transform2D::translate(width / 2.0, height / 2.0)
+            * Transform2D::scale(height / 2.0, -height / 2.0);
+        write!(svg, "<g transform=\"{}\">", svg_matrix(&viewport_transform)).ok();
+
+        for layer in self.layers.values() {
+            if layer.elements.is_empty() {
+                continue;
+            }
+
+            svg.push_str("<g");
+            svg.push_str(&blend_mode_attribute(layer.blend_mode));
+            svg.push_str(&opacity_attribute("opacity", layer.alpha));
+            svg.push('>');
+            layer
+                .elements
+                .iter()
+                .for_each(|element| svg.push_str(element));
+            svg.push_str("</g>");
+        }
+
+        svg.push_str("</g></svg>");
+
+        svg
+    }
+
+    ///
+    /// Generates a new ID for a definition
+    ///
+    fn new_id(&mut self, prefix: &str) -> String {
+        let id = format!("{}{}", prefix, self.next_id);
+        self.next_id += 1;
+
+        id
+    }
+
+    ///
+    /// Processes a single drawing instruction
+    ///
+    fn draw_one(&mut self, draw: Draw) {
+        use self::Draw::*;
+
+        match draw {
+            StartFrame | ShowFrame | ResetFrame => {}
+
+            Path(path_op) => {
+                if let PathOp::NewPath = path_op {
+                    self.state.path.clear();
+                } else {
+                    self.state.path.push(path_op);
+                }
+            }
+
+            Fill => self.fill(),
+            Stroke => self.stroke(),
+
+            LineWidth(width) => {
+                self.state.line_width = width;
+                self.state.line_width_pixels = None;
+            }
+            LineWidthPixels(width) => self.state.line_width_pixels = Some(width),
+            LineJoin(join) => self.state.line_join = join,
+            LineCap(cap) => self.state.line_cap = cap,
+            NewDashPattern => self.state.dash_pattern = vec![],
+            DashLength(length) => self.state.dash_pattern.push(length),
+            DashOffset(offset) => self.state.dash_offset = offset,
+            StrokeColor(color) => self.state.stroke_color = color,
+            WindingRule(winding_rule) => self.state.winding_rule = winding_rule,
+            BlendMode(blend_mode) => self.state.blend_mode = blend_mode,
+
+            FillColor(color) => self.set_fill(SvgFill::Color(color)),
+            FillTexture(texture_id, lower_left, upper_right) => {
+                if let Some((definition, width, height, alpha)) =
+                    self.texture_definition(texture_id)
+                {
+                    self.set_fill(SvgFill::Texture(
+                        definition,
+                        width,
+                        height,
+                        alpha,
+                        lower_left,
+                        upper_right,
+                    ));
+                }
+            }
+            FillGradient(gradient_id, start, end) => {
+                if let Some(gradient) = self.gradients.get(&(self.namespace, gradient_id)) {
+                    self.set_fill(SvgFill::LinearGradient(gradient.clone(), start, end));
+                }
+            }
+            FillRadialGradient(gradient_id, start, end) => {
+                if let Some(gradient) = self.gradients.get(&(self.namespace, gradient_id)) {
+                    self.set_fill(SvgFill::RadialGradient(gradient.clone(), start, end));
+                }
+            }
+            FillConicGradient(gradient_id, center, start_angle) => {
+                if let Some(gradient) = self.gradients.get(&(self.namespace, gradient_id)) {
+                    self.set_fill(SvgFill::ConicGradient(
+                        gradient.clone(),
+                        center,
+                        start_angle,
+                    ));
+                }
+            }
+            FillTransform(transform) => {
+                self.state.fill_transform = transform * self.state.fill_transform
+            }
+
+            IdentityTransform => self.state.transform = Transform2D::identity(),
+            CanvasHeight(height) => {
+                // The viewport is 2 units high
+                let scale = 2.0 / height.max(0.0000001);
+                self.state.transform = Transform2D::scale(scale, scale);
+            }
+            CenterRegion((x1, y1), (x2, y2)) => {
+                // Find the point that's currently at the center of the viewport and move the center of the region there
+                let inverse = self
+                    .state
+                    .transform
+                    .invert()
+                    .unwrap_or_else(Transform2D::identity);
+                let (center_x, center_y) = inverse.transform_point(0.0, 0.0);
+                let (new_x, new_y) = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+
+                self.state.transform = self.state.transform
+                    * Transform2D::translate(-(new_x - center_x), -(new_y - center_y));
+            }
+            MultiplyTransform(transform) => self.state.transform = self.state.transform * transform,
+
+            Unclip => self.state.clip = None,
+            Clip => self.clip(),
+
+            Store => {
+                if let Some(layer) = self.current_layer() {
+                    layer.stored = Some(layer.elements.len());
+                }
+            }
+            Restore => {
+                if let Some(layer) = self.current_layer() {
+                    if let Some(stored) = layer.stored {
+                        layer.elements.truncate(stored);
+                    }
+                }
+            }
+            FreeStoredBuffer => {
+                if let Some(layer) = self.current_layer() {
+                    layer.stored = None;
+                }
+            }
+
+            PushState => self.state_stack.push(self.state.clone()),
+            PopState => {
+                if let Some(state) = self.state_stack.pop() {
+                    self.state = state;
+                }
+            }
+
+            ClearCanvas(color) => {
+                let size = self.size;
+
+                *self = SvgWriter::new(size.0, size.1);
+                self.background = color;
+            }
+
+            Layer(layer_id) => {
+                self.layers.entry(layer_id.0).or_insert_with(SvgLayer::new);
+                self.target = SvgTarget::Layer(layer_id);
+            }
+            LayerBlend(layer_id, blend_mode) => {
+                self.layers
+                    .entry(layer_id.0)
+                    .or_insert_with(SvgLayer::new)
+                    .blend_mode = blend_mode;
+            }
+            LayerAlpha(layer_id, alpha) => {
+                self.layers
+                    .entry(layer_id.0)
+                    .or_insert_with(SvgLayer::new)
+                    .alpha = alpha;
+            }
+            ClearLayer => match self.target {
+                SvgTarget::Layer(_) => {
+                    if let Some(layer) = self.current_layer() {
+                        layer.elements.clear();
+                    }
+                }
+                SvgTarget::Sprite(..) => self.clear_sprite(),
+            },
+            ClearAllLayers => {
+                self.layers
+                    .values_mut()
+                    .for_each(|layer| layer.elements.clear());
+            }
+            SwapLayers(layer1, layer2) => {
+                let first = self.layers.remove(&layer1.0).unwrap_or_else(SvgLayer::new);
+                let second = self.layers.remove(&layer2.0).unwrap_or_else(SvgLayer::new);
+
+                self.layers.insert(layer1.0, second);
+                self.layers.insert(layer2.0, first);
+            }
+
+            Sprite(sprite_id) => {
+                let inverse_transform = self
+                    .state
+                    .transform
+                    .invert()
+                    .unwrap_or_else(Transform2D::identity);
+                let sprite = self
+                    .sprites
+                    .entry((self.namespace, sprite_id))
+                    .or_insert_with(|| SvgSprite {
+                        elements: vec![],
+                        inverse_transform,
+                        definition: None,
+                    });
+
+                sprite.inverse_transform = inverse_transform;
+                self.target = SvgTarget::Sprite(self.namespace, sprite_id);
+            }
+            MoveSpriteFrom(sprite_id) => {
+                if let SvgTarget::Sprite(namespace, target_sprite_id) = self.target {
+                    let elements = self
+                        .sprites
+                        .get(&(self.namespace, sprite_id))
+                        .map(|sprite| sprite.elements.clone())
+                        .unwrap_or_default();
+
+                    if let Some(sprite) = self.sprites.get_mut(&(namespace, target_sprite_id)) {
+                        sprite.elements = elements;
+                        sprite.definition = None;
+                    }
+                }
+            }
+            ClearSprite => self.clear_sprite(),
+            SpriteTransform(transform) => {
+                self.state.sprite_transform = match transform {
+                    crate::draw::SpriteTransform::Identity => Transform2D::identity(),
+                    other => self.state.sprite_transform * Transform2D::from(other),
+                };
+            }
+            DrawSprite(sprite_id) => self.draw_sprite(sprite_id, &[]),
+            DrawSpriteWithFilters(sprite_id, filters) => self.draw_sprite(sprite_id, &filters),
+
+            Texture(texture_id, texture_op) => self.texture(texture_id, texture_op),
+            Gradient(gradient_id, gradient_op) => {
+                let gradient = self
+                    .gradients
+                    .entry((self.namespace, gradient_id))
+                    .or_default();
+
+                if let GradientOp::Create(_) = &gradient_op {
+                    gradient.clear();
+                }
+                gradient.push(gradient_op);
+            }
+
+            Font(..) | BeginLineLayout(..) | DrawLaidOutText | DrawText(..) => {}
+
+            Namespace(namespace) => self.namespace = namespace,
+        }
+    }
+
+    ///
+    /// Retrieves the layer that is being drawn on (None if a sprite is selected)
+    ///
+    fn current_layer(&mut self) -> Option<&mut SvgLayer> {
+        match self.target {
+            SvgTarget::Layer(layer_id) => {
+                Some(self.layers.entry(layer_id.0).or_insert_with(SvgLayer::new))
+            }
+            SvgTarget::Sprite(..) => None,
+        }
+    }
+
+    ///
+    /// Removes the content of the current sprite
+    ///
+    fn clear_sprite(&mut self) {
+        if let SvgTarget::Sprite(namespace, sprite_id) = self.target {
+            if let Some(sprite) = self.sprites.get_mut(&(namespace, sprite_id)) {
+                sprite.elements.clear();
+                sprite.definition = None;
+            }
+        }
+    }
+
+    ///
+    /// Sets the fill for future shapes
+    ///
+    fn set_fill(&mut self, fill: SvgFill) {
+        self.state.fill = fill;
+        self.state.fill_transform = Transform2D::identity();
+    }
+
+    ///
+    /// Adds an element to the current layer or sprite
+    ///
+    fn add_element(&mut self, element: String) {
+        // Clip paths are applied using a group, so the clip path is in the same coordinate scheme as the layer
+        let element = if let Some(clip) = &self.state.clip {
+            format!("<g clip-path=\"url(#{})\">{}</g>", clip, element)
+        } else {
+            element
+        };
+
+        match self.target {
+            SvgTarget::Layer(layer_id) => {
+                self.layers
+                    .entry(layer_id.0)
+                    .or_insert_with(SvgLayer::new)
+                    .elements
+                    .push(element);
+            }
+
+            SvgTarget::Sprite(namespace, sprite_id) => {
+                if let Some(sprite) = self.sprites.get_mut(&(namespace, sprite_id)) {
+                    sprite.elements.push(element);
+                    sprite.definition = None;
+                }
+            }
+        }
+    }
+
+    ///
+    /// The attributes for the current path, its transform and blend mode
+    ///
+    fn path_attributes(&self) -> String {
+        format!(
+            "d=\"{}\" transform=\"{}\"{}",
+            svg_path_data(&self.state.path),
+            svg_matrix(&self.state.transform),
+            blend_mode_attribute(self.state.blend_mode)
+        )
+    }
+
+    ///
+    /// Fills the current path
+    ///
+    fn fill(&mut self) {
+        if self.state.path.is_empty() {
+            return;
+        }
+
+        let fill_rule = svg_fill_rule(self.state.winding_rule);
+
+        let paint = match self.state.fill.clone() {
+            SvgFill::Color(color) => {
+                let (color, alpha) = svg_color(&color);
+                format!(
+                    "fill=\"{}\"{}",
+                    color,
+                    opacity_attribute("fill-opacity", alpha)
+                )
+            }
+
+            SvgFill::Texture(definition, width, height, alpha, (x1, y1), (x2, y2)) => {
+                // Map the texture pixels onto the rectangle specified by the fill
+                let pattern_id = self.new_id("pattern");
+                let pattern_transform = self.state.fill_transform
+                    * Transform2D::translate(x1, y1)
+                    * Transform2D::scale((x2 - x1) / (width as f32), (y2 - y1) / (height as f32));
+
+                self.definitions.push(format!("<pattern id=\"{}\" patternUnits=\"userSpaceOnUse\" width=\"{}\" height=\"{}\" patternTransform=\"{}\"><use xlink:href=\"#{}\"{}/></pattern>",
+                    pattern_id, width, height, svg_matrix(&pattern_transform), definition, opacity_attribute("opacity", alpha)));
+
+                format!("fill=\"url(#{})\"", pattern_id)
+            }
+
+            SvgFill::LinearGradient(gradient, (x1, y1), (x2, y2)) => {
+                let gradient_id = self.new_id("gradient");
+
+                self.definitions.push(format!("<linearGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" spreadMethod=\"{}\" gradientTransform=\"{}\">{}</linearGradient>",
+                    gradient_id,
+                    svg_number(x1), svg_number(y1), svg_number(x2), svg_number(y2),
+                    svg_spread_method(gradient_spread(&gradient)),
+                    svg_matrix(&self.state.fill_transform),
+                    svg_gradient_stops(&gradient)));
+
+                format!("fill=\"url(#{})\"", gradient_id)
+            }
+
+            SvgFill::RadialGradient(gradient, (x1, y1, r1), (x2, y2, r2)) => {
+                // The first circle is the focal circle
+                let gradient_id = self.new_id("gradient");
+
+                self.definitions.push(format!("<radialGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" cx=\"{}\" cy=\"{}\" r=\"{}\" fx=\"{}\" fy=\"{}\" fr=\"{}\" spreadMethod=\"{}\" gradientTransform=\"{}\">{}</radialGradient>",
+                    gradient_id,
+                    svg_number(x2), svg_number(y2), svg_number(r2),
+                    svg_number(x1), svg_number(y1), svg_number(r1),
+                    svg_spread_method(gradient_spread(&gradient)),
+                    svg_matrix(&self.state.fill_transform),
+                    svg_gradient_stops(&gradient)));
+
+                format!("fill=\"url(#{})\"", gradient_id)
+            }
+
+            SvgFill::ConicGradient(gradient, center, start_angle) => {
+                self.fill_conic_gradient(&gradient, center, start_angle);
+                return;
+            }
+        };
+
+        let element = format!(
+            "<path {} fill-rule=\"{}\" {}/>",
+            self.path_attributes(),
+            fill_rule,
+            paint
+        );
+        self.add_element(element);
+    }
+
+    ///
+    /// Fills the current path with a conic gradient, which is approximated by clipping a set of wedges against the path
+    ///
+    fn fill_conic_gradient(
+        &mut self,
+        gradient: &[GradientOp],
+        (x, y): (f32, f32),
+        start_angle: f32,
+    ) {
+        // The wedges are drawn around the origin in 'gradient' coordinates
+        let gradient_transform = self.state.fill_transform
+            * Transform2D::translate(x, y)
+            * Transform2D::rotate(start_angle);
+        let to_gradient = gradient_transform
+            .invert()
+            .unwrap_or_else(Transform2D::identity);
+
+        // The wedges need to be large enough to cover every point in the path
+        let radius = self
+            .state
+            .path
+            .iter()
+            .flat_map(|path_op| match path_op {
+                PathOp::Move(x, y) | PathOp::Line(x, y) => vec![(*x, *y)],
+                PathOp::BezierCurve((cp1, cp2), end) => vec![*cp1, *cp2, *end],
+                PathOp::NewPath | PathOp::ClosePath => vec![],
+            })
+            .map(|(x, y)| {
+                let (x, y) = to_gradient.transform_point(x, y);
+                (x * x + y * y).sqrt()
+            })
+            .fold(0.0, f32::max)
+            * 1.1;
+
+        // Clip the wedges against the path
+        let clip_id = self.new_id("clip");
+        self.definitions.push(format!(
+            "<clipPath id=\"{}\" clipPathUnits=\"userSpaceOnUse\"><path d=\"{}\" transform=\"{}\" clip-rule=\"{}\"/></clipPath>",
+            clip_id,
+            svg_path_data(&self.state.path),
+            svg_matrix(&self.state.transform),
+            svg_fill_rule(self.state.winding_rule)
+        ));
+
+        let mut element = format!(
+            "<g clip-path=\"url(#{})\"{}><g transform=\"{}\">",
+            clip_id,
+            blend_mode_attribute(self.state.blend_mode),
+            svg_matrix(&(self.state.transform * gradient_transform))
+        );
+
+        for wedge in 0..CONIC_GRADIENT_WEDGES {
+            // Wedges overlap slightly to avoid gaps between them
+            let wedge_size = 1.0 / (CONIC_GRADIENT_WEDGES as f32);
+            let start = (wedge as f32) * wedge_size;
+            let end = start + wedge_size * 1.5;
+            let (r, g, b, a) = svg_gradient_color(gradient, start + wedge_size * 0.5);
+            let (color, alpha) = svg_color(&Color::Rgba(r, g, b, a));
+
+            let angle1 = start * 2.0 * f32::consts::PI;
+            let angle2 = end.min(1.0) * 2.0 * f32::consts::PI;
+
+            write!(
+                element,
+                "<path d=\"M0 0 L{} {} L{} {} Z\" fill=\"{}\"{}/>",
+                svg_number(angle1.cos() * radius),
+                svg_number(angle1.sin() * radius),
+                svg_number(angle2.cos() * radius),
+                svg_number(angle2.sin() * radius),
+                color,
+                opacity_attribute("fill-opacity", alpha)
+            )
+            .ok();
+        }
+
+        element.push_str("</g></g>");
+        self.add_element(element);
+    }
+
+    ///
+    /// Strokes the current path
+    ///
+    fn stroke(&mut self) {
+        if self.state.path.is_empty() {
+            return;
+        }
+
+        let (color, alpha) = svg_color(&self.state.stroke_color);
+        let mut element = format!(
+            "<path {} fill=\"none\" stroke=\"{}\"{} stroke-linejoin=\"{}\" stroke-linecap=\"{}\"",
+            self.path_attributes(),
+            color,
+            opacity_attribute("stroke-opacity", alpha),
+            svg_line_join(self.state.line_join),
+            svg_line_cap(self.state.line_cap)
+        );
+
+        if let Some(width_pixels) = self.state.line_width_pixels {
+            // Pixel widths are measured in the coordinates of the document rather than the canvas
+            write!(
+                element,
+                " stroke-width=\"{}\" vector-effect=\"non-scaling-stroke\"",
+                svg_number(width_pixels)
+            )
+            .ok();
+        } else {
+            write!(
+                element,
+                " stroke-width=\"{}\"",
+                svg_number(self.state.line_width)
+            )
+            .ok();
+        }
+
+        if !self.state.dash_pattern.is_empty() {
+            let dash_array = self
+                .state
+                .dash_pattern
+                .iter()
+                .map(|length| svg_number(*length))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            write!(
+                element,
+                " stroke-dasharray=\"{}\" stroke-dashoffset=\"{}\"",
+                dash_array,
+                svg_number(self.state.dash_offset)
+            )
+            .ok();
+        }
+
+        element.push_str("/>");
+        self.add_element(element);
+    }
+
+    ///
+    /// Intersects the clip region with the current path
+    ///
+    fn clip(&mut self) {
+        let clip_id = self.new_id("clip");
+
+        // A clip path on a clipPath element intersects the two regions
+        let parent_clip = if let Some(parent) = &self.state.clip {
+            format!(" clip-path=\"url(#{})\"", parent)
+        } else {
+            String::new()
+        };
+
+        self.definitions.push(format!(
+            "<clipPath id=\"{}\" clipPathUnits=\"userSpaceOnUse\"{}><path d=\"{}\" transform=\"{}\" clip-rule=\"{}\"/></clipPath>",
+            clip_id,
+            parent_clip,
+            svg_path_data(&self.state.path),
+            svg_matrix(&self.state.transform),
+            svg_fill_rule(self.state.winding_rule)
+        ));
+
+        self.state.clip = Some(clip_id);
+    }
+
+    ///
+    /// Returns the ID of the `<symbol>` element for the current version of a sprite, writing it if needed
+    ///
+    fn sprite_definition(&mut self, namespace: NamespaceId, sprite_id: SpriteId) -> Option<String> {
+        let existing = self
+            .sprites
+            .get(&(namespace, sprite_id))?
+            .definition
+            .clone();
+        if existing.is_some() {
+            return existing;
+        }
+
+        let definition_id = self.new_id("sprite");
+        let sprite = self.sprites.get_mut(&(namespace, sprite_id))?;
+
+        self.definitions.push(format!(
+            "<symbol id=\"{}\" overflow=\"visible\">{}</symbol>",
+            definition_id,
+            sprite.elements.concat()
+        ));
+        sprite.definition = Some(definition_id.clone());
+
+        Some(definition_id)
+    }
+
+    ///
+    /// Writes a filter definition, returning its ID
+    ///
+    fn filter_definition(&mut self, filters: &[TextureFilter], unit_scale: f32) -> String {
+        let filter_id = self.new_id("filter");
+
+        // Only bitmap textures can be used as masks or displacement maps
+        let namespace = self.namespace;
+        let textures = &self.textures;
+        let primitives = svg_filter_primitives(filters, unit_scale, |texture_id| {
+            match textures.get(&(namespace, texture_id)) {
+                Some(SvgTexture {
+                    width,
+                    height,
+                    content: SvgTextureContent::Bitmap(pixels),
+                    ..
+                }) => Some(svg_png_data_uri(*width, *height, pixels)),
+                _ => None,
+            }
+        });
+
+        let region = if svg_filter_uses_bounds(filters) {
+            "x=\"0\" y=\"0\" width=\"1\" height=\"1\""
+        } else {
+            "x=\"-0.5\" y=\"-0.5\" width=\"2\" height=\"2\""
+        };
+
+        self.definitions.push(format!(
+            "<filter id=\"{}\" {} color-interpolation-filters=\"sRGB\">{}</filter>",
+            filter_id, region, primitives
+        ));
+
+        filter_id
+    }
+
+    ///
+    /// Draws a sprite using the current sprite transform
+    ///
+    fn draw_sprite(&mut self, sprite_id: SpriteId, filters: &[TextureFilter]) {
+        let definition = if let Some(definition) = self.sprite_definition(self.namespace, sprite_id)
+        {
+            definition
+        } else {
+            return;
+        };
+        let inverse_transform = self.sprites[&(self.namespace, sprite_id)].inverse_transform;
+
+        // The sprite content is in viewport coordinates, so map it back to canvas coordinates before applying the sprite transform
+        let transform = self.state.transform * self.state.sprite_transform * inverse_transform;
+        let mut element = format!(
+            "<use xlink:href=\"#{}\" transform=\"{}\"{}/>",
+            definition,
+            svg_matrix(&transform),
+            blend_mode_attribute(self.state.blend_mode)
+        );
+
+        if !filters.is_empty() {
+            // Filter measurements are in canvas units, but the filter is applied in viewport coordinates
+            let unit_scale =
+                svg_transform_scale(&(self.state.transform * self.state.sprite_transform));
+            let filter_id = self.filter_definition(filters, unit_scale);
+
+            element = format!("<g filter=\"url(#{})\">{}</g>", filter_id, element);
+        }
+
+        self.add_element(element);
+    }
+
+    ///
+    /// Performs an operation on a texture
+    ///
+    fn texture(&mut self, texture_id: TextureId, texture_op: TextureOp) {
+        use self::TextureOp::*;
+
+        let key = (self.namespace, texture_id);
+
+        match texture_op {
+            Create(size, TextureFormat::Rgba) => {
+                self.textures.insert(key, SvgTexture::new(size));
+            }
+
+            Free => {
+                self.textures.remove(&key);
+            }
+
+            SetBytes(position, size, bytes) => {
+                if let Some(texture) = self.textures.get_mut(&key) {
+                    texture.set_bytes(position, size, &bytes);
+                }
+            }
+
+            SetFromSprite(sprite_id, bounds) => {
+                let sprite_definition = self.sprite_definition(self.namespace, sprite_id);
+
+                if let Some(texture) = self.textures.get_mut(&key) {
+                    texture.content = SvgTextureContent::Sprite {
+                        sprite_id,
+                        bounds,
+                        dynamic: false,
+                        sprite_definition,
+                    };
+                    texture.definition = None;
+                }
+            }
+
+            CreateDynamicSprite(sprite_id, bounds, CanvasSize(width, height)) => {
+                let size = TextureSize(width.ceil().max(1.0) as u32, height.ceil().max(1.0) as u32);
+                let mut texture = SvgTexture::new(size);
+
+                texture.content = SvgTextureContent::Sprite {
+                    sprite_id,
+                    bounds,
+                    dynamic: true,
+                    sprite_definition: None,
+                };
+                self.textures.insert(key, texture);
+            }
+
+            FillTransparency(alpha) => {
+                if let Some(texture) = self.textures.get_mut(&key) {
+                    texture.alpha = alpha;
+                }
+            }
+
+            Copy(target_texture_id) => {
+                if let Some(texture) = self.textures.get(&key).cloned() {
+                    self.textures
+                        .insert((self.namespace, target_texture_id), texture);
+                }
+            }
+
+            Filter(filter) => {
+                if let Some(texture) = self.textures.get_mut(&key) {
+                    texture.filters.push(filter);
+                    texture.definition = None;
+                }
+            }
+        }
+    }
+
+    ///
+    /// Returns the ID of the definition for the current content of a texture, along with its size and alpha value
+    ///
+    fn texture_definition(&mut self, texture_id: TextureId) -> Option<(String, u32, u32, f32)> {
+        let key = (self.namespace, texture_id);
+
+        // Dynamic textures follow the current version of their sprite
+        if let SvgTextureContent::Sprite {
+            sprite_id,
+            dynamic: true,
+            ..
+        } = &self.textures.get(&key)?.content
+        {
+            let sprite_id = *sprite_id;
+            let latest_definition = self.sprite_definition(self.namespace, sprite_id);
+
+            if let Some(SvgTexture {
+                content:
+                    SvgTextureContent::Sprite {
+                        sprite_definition, ..
+                    },
+                definition,
+                ..
+            }) = self.textures.get_mut(&key)
+            {
+                if *sprite_definition != latest_definition {
+                    *sprite_definition = latest_definition;
+                    *definition = None;
+                }
+            }
+        }
+
+        let texture = self.textures.get(&key)?.clone();
+        if let Some(definition) = &texture.definition {
+            return Some((
+                definition.clone(),
+                texture.width,
+                texture.height,
+                texture.alpha,
+            ));
+        }
+
+        // Write a new definition for this texture
+        let definition_id = self.new_id("texture");
+        let filter = if texture.filters.is_empty() {
+            String::new()
+        } else {
+            // Texture filters are measured in pixels
+            format!(
+                " filter=\"url(#{})\"",
+                self.filter_definition(&texture.filters, 1.0)
+            )
+        };
+
+        let content = match &texture.content {
+            SvgTextureContent::Bitmap(pixels) => format!(
+                "<image width=\"{}\" height=\"{}\" preserveAspectRatio=\"none\" xlink:href=\"{}\"/>",
+                texture.width,
+                texture.height,
+                svg_png_data_uri(texture.width, texture.height, pixels)
+            ),
+
+            SvgTextureContent::Sprite {
+                sprite_id,
+                bounds: SpriteBounds(SpritePosition(x, y), SpriteSize(width, height)),
+                sprite_definition: Some(sprite_definition),
+                ..
+            } => {
+                // Map the sprite bounds onto the texture pixels
+                let inverse_transform = self
+                    .sprites
+                    .get(&(self.namespace, *sprite_id))
+                    .map(|sprite| sprite.inverse_transform)
+                    .unwrap_or_else(Transform2D::identity);
+                let transform = Transform2D::scale(
+                    (texture.width as f32) / width,
+                    (texture.height as f32) / height,
+                ) * Transform2D::translate(-x, -y)
+                    * inverse_transform;
+
+                format!(
+                    "<use xlink:href=\"#{}\" transform=\"{}\"/>",
+                    sprite_definition,
+                    svg_matrix(&transform)
+                )
+            }
+
+            SvgTextureContent::Sprite {
+                sprite_definition: None,
+                ..
+            } => String::new(),
+        };
+
+        self.definitions.push(format!(
+            "<g id=\"{}\"{}>{}</g>",
+            definition_id, filter, content
+        ));
+
+        if let Some(texture) = self.textures.get_mut(&key) {
+            texture.definition = Some(definition_id.clone());
+        }
+
+        Some((definition_id, texture.width, texture.height, texture.alpha))
+    }
+}
+
+///
+/// Generates an opacity attribute, or an empty string if the value is opaque
+///
+fn opacity_attribute(name: &str, alpha: f32) -> String {
+    if alpha >= 1.0 {
+        String::new()
+    } else {
+        format!(" {}=\"{}\"", name, svg_number(alpha.max(0.0)))
+    }
+}
+
+///
+/// Generates a style attribute for a blend mode, or an empty string for the default blend mode
+///
+fn blend_mode_attribute(blend_mode: BlendMode) -> String {
+    if let Some(mix_blend_mode) = svg_blend_mode(blend_mode) {
+        format!(" style=\"mix-blend-mode:{}\"", mix_blend_mode)
+    } else {
+        String::new()
+    }
+}
+
+///
+/// Converts a set of drawing instructions into a SVG document of the specified size (in pixels)
+///
+pub fn drawing_to_svg(
+    drawing: impl IntoIterator<Item = Draw>,
+    (width, height): (f32, f32),
+) -> String {
+    let mut writer = SvgWriter::new(width, height);
+    writer.draw(drawing);
+
+    writer.to_svg()
+}
+
+///
+/// Reads a stream of drawing instructions and converts them to a SVG document of the specified size (in pixels)
+///
+pub async fn drawing_stream_to_svg(
+    drawing: impl Unpin + Stream<Item = Draw>,
+    (width, height): (f32, f32),
+) -> String {
+    let mut writer = SvgWriter::new(width, height);
+    let mut drawing = drawing;
+
+    while let Some(draw) = drawing.next().await {
+        writer.draw_one(draw);
+    }
+
+    writer.to_svg()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::context::*;
+    use crate::primitives::*;
+
+    use futures::executor;
+    use futures::stream;
+
+    use std::sync::*;
+
+    fn square() -> Vec<Draw> {
+        let mut drawing = vec![];
+
+        drawing.new_path();
+        drawing.move_to(0.0, 0.0);
+        drawing.line_to(10.0, 0.0);
+        drawing.line_to(10.0, 10.0);
+        drawing.line_to(0.0, 10.0);
+        drawing.close_path();
+
+        drawing
+    }
+
+    #[test]
+    fn empty_document() {
+        let svg = drawing_to_svg(vec![], (640.0, 480.0));
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains("width=\"640\" height=\"480\" viewBox=\"0 0 640 480\""));
+        assert!(svg.contains("<g transform=\"matrix(240 0 0 -240 320 240)\">"));
+        assert!(svg.ends_with("</g></svg>"));
+    }
+
+    #[test]
+    fn clear_canvas_sets_background() {
+        let svg = drawing_to_svg(
+            vec![Draw::ClearCanvas(Color::Rgba(1.0, 1.0, 1.0, 1.0))],
+            (100.0, 100.0),
+        );
+
+        assert!(svg.contains("<rect width=\"100\" height=\"100\" fill=\"#ffffff\"/>"));
+    }
+
+    #[test]
+    fn fill_square() {
+        let mut drawing = vec![];
+        drawing.canvas_height(100.0);
+        drawing.extend(square());
+        drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 0.5));
+        drawing.fill();
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(
+            svg.contains("<path d=\"M0 0 L10 0 L10 10 L0 10 Z\" transform=\"matrix(0.02 0 0 0.02 0 0)\" fill-rule=\"nonzero\" fill=\"#ff0000\" fill-opacity=\"0.5\"/>"),
+            "{}",
+            svg
+        );
+    }
+
+    #[test]
+    fn stroke_square() {
+        let mut drawing = square();
+        drawing.line_width(2.0);
+        drawing.line_join(LineJoin::Bevel);
+        drawing.line_cap(LineCap::Square);
+        drawing.stroke_color(Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        drawing.new_dash_pattern();
+        drawing.dash_length(3.0);
+        drawing.dash_length(1.0);
+        drawing.dash_offset(0.5);
+        drawing.stroke();
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(svg.contains("fill=\"none\" stroke=\"#0000ff\" stroke-linejoin=\"bevel\" stroke-linecap=\"square\" stroke-width=\"2\" stroke-dasharray=\"3 1\" stroke-dashoffset=\"0.5\"/>"), "{}", svg);
+    }
+
+    #[test]
+    fn stroke_pixel_width_does_not_scale() {
+        let mut drawing = square();
+        drawing.line_width_pixels(3.0);
+        drawing.stroke();
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(
+            svg.contains("stroke-width=\"3\" vector-effect=\"non-scaling-stroke\""),
+            "{}",
+            svg
+        );
+    }
+
+    #[test]
+    fn push_and_pop_state() {
+        let mut drawing = square();
+        drawing.push_state();
+        drawing.fill_color(Color::Rgba(0.0, 1.0, 0.0, 1.0));
+        drawing.pop_state();
+        drawing.fill();
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(svg.contains("fill=\"#000000\""), "{}", svg);
+        assert!(!svg.contains("fill=\"#00ff00\""), "{}", svg);
+    }
+
+    #[test]
+    fn clip_and_unclip() {
+        let mut drawing = vec![];
+        drawing.circle(0.0, 0.0, 5.0);
+        drawing.clip();
+        drawing.extend(square());
+        drawing.fill();
+        drawing.unclip();
+        drawing.fill();
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(
+            svg.contains("<clipPath id=\"clip0\" clipPathUnits=\"userSpaceOnUse\"><path d=\"M3.5"),
+            "{}",
+            svg
+        );
+        assert!(
+            svg.contains("<g clip-path=\"url(#clip0)\"><path d=\"M0 0 L10 0"),
+            "{}",
+            svg
+        );
+        assert!(svg.matches("<path d=\"M0 0 L10 0").count() == 2, "{}", svg);
+        assert!(svg.matches("clip-path=").count() == 1, "{}", svg);
+    }
+
+    #[test]
+    fn nested_clip_intersects() {
+        let mut drawing = square();
+        drawing.clip();
+        drawing.clip();
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(svg.contains("<clipPath id=\"clip1\" clipPathUnits=\"userSpaceOnUse\" clip-path=\"url(#clip0)\">"), "{}", svg);
+    }
+
+    #[test]
+    fn layers_are_ordered_by_id() {
+        let mut drawing = vec![];
+        drawing.layer(LayerId(2));
+        drawing.extend(square());
+        drawing.fill_color(Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        drawing.fill();
+        drawing.layer(LayerId(1));
+        drawing.extend(square());
+        drawing.fill_color(Color::Rgba(0.0, 1.0, 0.0, 1.0));
+        drawing.fill();
+        drawing.layer_blend(LayerId(2), BlendMode::Multiply);
+        drawing.layer_alpha(LayerId(2), 0.25);
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        let green = svg.find("#00ff00").unwrap();
+        let blue = svg.find("#0000ff").unwrap();
+        assert!(green < blue, "{}", svg);
+        assert!(
+            svg.contains("<g style=\"mix-blend-mode:multiply\" opacity=\"0.25\"><path"),
+            "{}",
+            svg
+        );
+    }
+
+    #[test]
+    fn clear_layer_removes_elements() {
+        let mut drawing = square();
+        drawing.fill();
+        drawing.clear_layer();
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(!svg.contains("<path"), "{}", svg);
+    }
+
+    #[test]
+    fn store_and_restore() {
+        let mut drawing = square();
+        drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.fill();
+        drawing.store();
+        drawing.fill_color(Color::Rgba(0.0, 1.0, 0.0, 1.0));
+        drawing.fill();
+        drawing.restore();
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(svg.contains("#ff0000"), "{}", svg);
+        assert!(!svg.contains("#00ff00"), "{}", svg);
+    }
+
+    #[test]
+    fn draw_sprite_uses_symbol() {
+        let mut drawing = vec![];
+        drawing.sprite(SpriteId(1));
+        drawing.extend(square());
+        drawing.fill();
+        drawing.layer(LayerId(0));
+        drawing.sprite_transform(SpriteTransform::Translate(20.0, 0.0));
+        drawing.draw_sprite(SpriteId(1));
+        drawing.draw_sprite(SpriteId(1));
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(
+            svg.contains("<symbol id=\"sprite0\" overflow=\"visible\"><path d=\"M0 0"),
+            "{}",
+            svg
+        );
+        assert!(svg.matches("<symbol").count() == 1, "{}", svg);
+        assert!(
+            svg.matches("<use xlink:href=\"#sprite0\" transform=\"matrix(1 0 0 1 20 0)\"/>")
+                .count()
+                == 2,
+            "{}",
+            svg
+        );
+    }
+
+    #[test]
+    fn changed_sprite_gets_new_definition() {
+        let mut drawing = vec![];
+        drawing.sprite(SpriteId(1));
+        drawing.extend(square());
+        drawing.fill();
+        drawing.layer(LayerId(0));
+        drawing.draw_sprite(SpriteId(1));
+        drawing.sprite(SpriteId(1));
+        drawing.clear_sprite();
+        drawing.layer(LayerId(0));
+        drawing.draw_sprite(SpriteId(1));
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(
+            svg.contains("<symbol id=\"sprite0\" overflow=\"visible\"><path"),
+            "{}",
+            svg
+        );
+        assert!(
+            svg.contains("<symbol id=\"sprite1\" overflow=\"visible\"></symbol>"),
+            "{}",
+            svg
+        );
+    }
+
+    #[test]
+    fn draw_sprite_with_blur() {
+        let mut drawing = vec![];
+        drawing.sprite(SpriteId(1));
+        drawing.extend(square());
+        drawing.fill();
+        drawing.layer(LayerId(0));
+        drawing.draw_sprite_with_filters(SpriteId(1), vec![TextureFilter::GaussianBlur(8.0)]);
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(
+            svg.contains(
+                "<feGaussianBlur in=\"SourceGraphic\" stdDeviation=\"2\" result=\"filter0\"/>"
+            ),
+            "{}",
+            svg
+        );
+        assert!(
+            svg.contains("<g filter=\"url(#filter1)\"><use xlink:href=\"#sprite0\""),
+            "{}",
+            svg
+        );
+    }
+
+    #[test]
+    fn linear_gradient_fill() {
+        let mut drawing = vec![];
+        drawing.create_gradient(GradientId(1), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.gradient_stop(GradientId(1), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        drawing.extend(square());
+        drawing.fill_gradient(GradientId(1), 0.0, 0.0, 10.0, 0.0);
+        drawing.fill();
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(svg.contains("<linearGradient id=\"gradient0\" gradientUnits=\"userSpaceOnUse\" x1=\"0\" y1=\"0\" x2=\"10\" y2=\"0\" spreadMethod=\"pad\" gradientTransform=\"matrix(1 0 0 1 0 0)\"><stop offset=\"0\" stop-color=\"#ff0000\" stop-opacity=\"1\"/><stop offset=\"1\" stop-color=\"#0000ff\" stop-opacity=\"1\"/></linearGradient>"), "{}", svg);
+        assert!(svg.contains("fill=\"url(#gradient0)\""), "{}", svg);
+    }
+
+    #[test]
+    fn radial_gradient_fill() {
+        let mut drawing = vec![];
+        drawing.create_gradient(GradientId(1), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.gradient_stop(GradientId(1), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        drawing.extend(square());
+        drawing.draw(Draw::FillRadialGradient(
+            GradientId(1),
+            (1.0, 2.0, 0.5),
+            (5.0, 5.0, 4.0),
+        ));
+        drawing.fill();
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(
+            svg.contains("cx=\"5\" cy=\"5\" r=\"4\" fx=\"1\" fy=\"2\" fr=\"0.5\""),
+            "{}",
+            svg
+        );
+    }
+
+    #[test]
+    fn conic_gradient_fill() {
+        let mut drawing = vec![];
+        drawing.create_gradient(GradientId(1), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        drawing.gradient_stop(GradientId(1), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+        drawing.extend(square());
+        drawing.draw(Draw::FillConicGradient(GradientId(1), (5.0, 5.0), 0.0));
+        drawing.fill();
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(
+            svg.contains("<g clip-path=\"url(#clip0)\"><g transform=\"matrix(1 0 0 1 5 5)\">"),
+            "{}",
+            svg
+        );
+        assert!(
+            svg.matches("Z\" fill=\"#").count() == CONIC_GRADIENT_WEDGES,
+            "{}",
+            svg
+        );
+    }
+
+    #[test]
+    fn texture_is_embedded_as_png() {
+        let mut drawing = vec![];
+        drawing.create_texture(TextureId(1), 2, 2, TextureFormat::Rgba);
+        drawing.set_texture_bytes(TextureId(1), 0, 0, 2, 2, Arc::new(vec![255; 16]));
+        drawing.extend(square());
+        drawing.fill_texture(TextureId(1), 0.0, 0.0, 10.0, 10.0);
+        drawing.fill();
+        drawing.fill();
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(svg.contains("<g id=\"texture0\"><image width=\"2\" height=\"2\" preserveAspectRatio=\"none\" xlink:href=\"data:image/png;base64,iVBORw0KGgo"), "{}", svg);
+        assert!(svg.matches("data:image/png").count() == 1, "{}", svg);
+        assert!(svg.contains("<pattern id=\"pattern1\" patternUnits=\"userSpaceOnUse\" width=\"2\" height=\"2\" patternTransform=\"matrix(5 0 0 5 0 0)\"><use xlink:href=\"#texture0\"/></pattern>"), "{}", svg);
+        assert!(svg.contains("fill=\"url(#pattern1)\""), "{}", svg);
+    }
+
+    #[test]
+    fn texture_from_sprite() {
+        let mut drawing = vec![];
+        drawing.sprite(SpriteId(1));
+        drawing.extend(square());
+        drawing.fill();
+        drawing.layer(LayerId(0));
+        drawing.create_texture(TextureId(1), 20, 20, TextureFormat::Rgba);
+        drawing.draw(Draw::Texture(
+            TextureId(1),
+            TextureOp::SetFromSprite(
+                SpriteId(1),
+                SpriteBounds(SpritePosition(0.0, 0.0), SpriteSize(10.0, 10.0)),
+            ),
+        ));
+        drawing.extend(square());
+        drawing.fill_texture(TextureId(1), 0.0, 0.0, 10.0, 10.0);
+        drawing.fill();
+
+        let svg = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(svg.contains("<g id=\"texture1\"><use xlink:href=\"#sprite0\" transform=\"matrix(2 0 0 2 0 0)\"/></g>"), "{}", svg);
+    }
+
+    #[test]
+    fn stream_to_svg() {
+        let mut drawing = square();
+        drawing.fill();
+
+        let from_stream = executor::block_on(drawing_stream_to_svg(
+            stream::iter(drawing.clone()),
+            (100.0, 100.0),
+        ));
+        let from_iter = drawing_to_svg(drawing, (100.0, 100.0));
+
+        assert!(from_stream == from_iter);
+    }
+}
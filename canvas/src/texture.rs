@@ -81,7 +81,11 @@ pub enum TextureOp {
     Free,
 
     /// Sets a region of a texture (specified as minx, miny, width, height) to the specified bitmap
-    SetBytes(TexturePosition, TextureSize, Arc<Vec<u8>>),
+    SetBytes(
+        TexturePosition,
+        TextureSize,
+        #[serde(with = "crate::serialized_data::arc_data")] Arc<Vec<u8>>,
+    ),
 
     /// Renders the specified sprite to the texture (mapping the supplied bounds to the coordinates in the texture)
     SetFromSprite(SpriteId, SpriteBounds),
@@ -8,6 +8,7 @@ use super::super::super::super::geo::*;
 use super::super::graph_path::*;
 use super::super::path::*;
 use super::ray_cast::*;
+use super::winding_rule::*;
 
 //
 // This uses a simple ray casting algorithm to perform the addition
@@ -88,6 +89,33 @@ where
     merged_path.exterior_paths()
 }
 
+///
+/// Generates the path formed by adding two sets of paths, using a winding rule to decide which points are inside each of them
+///
+/// The subpaths of each path can overlap each other: the winding rule is used to determine which regions they cover. The
+/// result is a set of subpaths that do not overlap, covering the regions covered by either path.
+///
+pub fn path_add_with_winding_rule<POut>(
+    path1: &Vec<impl BezierPath<Point = POut::Point>>,
+    winding_rule1: PathWindingRule,
+    path2: &Vec<impl BezierPath<Point = POut::Point>>,
+    winding_rule2: PathWindingRule,
+    accuracy: f64,
+) -> Vec<POut>
+where
+    POut: BezierPathFactory,
+    POut::Point: Coordinate + Coordinate2D,
+{
+    path_combine_with_winding_rule(
+        path1,
+        winding_rule1,
+        path2,
+        winding_rule2,
+        accuracy,
+        |inside1, inside2| inside1 || inside2,
+    )
+}
+
 ///
 /// Generates the path formed by removing any interior points from an existing path. This considers only the outermost edges of the
 /// path to be the true edges, so if there are sub-paths inside an outer path, they will be removed.
@@ -10,6 +10,8 @@ use super::add::*;
 use super::chain_add::*;
 use super::intersect::*;
 use super::sub::*;
+use super::winding_rule::*;
+use super::xor::*;
 
 ///
 /// Description of an arithmetic operation to perform on a bezier path
@@ -37,6 +39,12 @@ where
     /// paths that might be self-overlapping.
     RemoveInteriorPoints(Vec<P>),
 
+    /// Sets the result to a path whose subpaths may overlap, using a winding rule to decide which regions it covers
+    ///
+    /// The other operations treat every edge as an exterior edge, so this can be used to combine paths that are
+    /// intended to be rendered with a non-zero winding rule.
+    WithWindingRule(PathWindingRule, Vec<P>),
+
     /// Adds a series of paths
     Add(Vec<PathCombine<P>>),
 
@@ -45,6 +53,67 @@ where
 
     /// Intersects a series a paths (with the first path)
     Intersect(Vec<PathCombine<P>>),
+
+    /// Finds the exclusive-or of a series of paths (the regions covered by an odd number of the paths)
+    Xor(Vec<PathCombine<P>>),
+
+    /// Adds a series of paths, using a winding rule to decide which regions are covered by any `Path` in the series
+    AddWithWindingRule(PathWindingRule, Vec<PathCombine<P>>),
+
+    /// Subtracts a series of paths (from the first path), using a winding rule to decide which regions are covered by any
+    /// `Path` in the series
+    SubtractWithWindingRule(PathWindingRule, Vec<PathCombine<P>>),
+
+    /// Intersects a series of paths (with the first path), using a winding rule to decide which regions are covered by any
+    /// `Path` in the series
+    IntersectWithWindingRule(PathWindingRule, Vec<PathCombine<P>>),
+
+    /// Finds the exclusive-or of a series of paths, using a winding rule to decide which regions are covered by any `Path` in
+    /// the series
+    XorWithWindingRule(PathWindingRule, Vec<PathCombine<P>>),
+}
+
+///
+/// Generates the path for one of the operands of a combining operation, applying a winding rule if it's a `Path`
+///
+fn path_combine_operand<P: BezierPathFactory>(
+    operation: PathCombine<P>,
+    winding_rule: PathWindingRule,
+    accuracy: f64,
+) -> Vec<P>
+where
+    P::Point: Coordinate + Coordinate2D,
+{
+    match operation {
+        PathCombine::Path(path) => path_with_winding_rule(&path, winding_rule, accuracy),
+        operation => path_combine(operation, accuracy),
+    }
+}
+
+///
+/// Combines a series of operands in turn with the first one
+///
+fn path_combine_series<P: BezierPathFactory>(
+    operations: Vec<PathCombine<P>>,
+    accuracy: f64,
+    operand: impl Fn(PathCombine<P>) -> Vec<P>,
+    combine: impl Fn(&Vec<P>, &Vec<P>, f64) -> Vec<P>,
+) -> Vec<P>
+where
+    P::Point: Coordinate + Coordinate2D,
+{
+    let mut path_iter = operations.into_iter();
+    let result = path_iter
+        .next()
+        .unwrap_or_else(|| PathCombine::Path(vec![]));
+    let mut result = operand(result);
+
+    for next_operation in path_iter {
+        let next_path = operand(next_operation);
+        result = combine(&result, &next_path, accuracy);
+    }
+
+    result
 }
 
 ///
@@ -59,6 +128,9 @@ where
     match operation {
         PathCombine::Path(result) => result,
         PathCombine::RemoveInteriorPoints(path) => path_remove_interior_points(&path, accuracy),
+        PathCombine::WithWindingRule(winding_rule, path) => {
+            path_with_winding_rule(&path, winding_rule, accuracy)
+        }
         PathCombine::Add(paths) => path_add_chain(
             &paths
                 .into_iter()
@@ -67,34 +139,49 @@ where
             accuracy,
         ),
 
-        PathCombine::Subtract(paths) => {
-            let mut path_iter = paths.into_iter();
-            let result = path_iter
-                .next()
-                .unwrap_or_else(|| PathCombine::Path(vec![]));
-            let mut result = path_combine(result, accuracy);
-
-            for to_subtract in path_iter {
-                let to_subtract = path_combine(to_subtract, accuracy);
-                result = path_sub(&result, &to_subtract, accuracy);
-            }
-
-            result
-        }
-
-        PathCombine::Intersect(paths) => {
-            let mut path_iter = paths.into_iter();
-            let result = path_iter
-                .next()
-                .unwrap_or_else(|| PathCombine::Path(vec![]));
-            let mut result = path_combine(result, accuracy);
-
-            for to_intersect in path_iter {
-                let to_intersect = path_combine(to_intersect, accuracy);
-                result = path_intersect(&result, &to_intersect, accuracy);
-            }
+        PathCombine::Subtract(paths) => path_combine_series(
+            paths,
+            accuracy,
+            |path| path_combine(path, accuracy),
+            path_sub,
+        ),
+        PathCombine::Intersect(paths) => path_combine_series(
+            paths,
+            accuracy,
+            |path| path_combine(path, accuracy),
+            path_intersect,
+        ),
+        PathCombine::Xor(paths) => path_combine_series(
+            paths,
+            accuracy,
+            |path| path_combine(path, accuracy),
+            path_xor,
+        ),
 
-            result
-        }
+        PathCombine::AddWithWindingRule(winding_rule, paths) => path_add_chain(
+            &paths
+                .into_iter()
+                .map(|path| path_combine_operand(path, winding_rule, accuracy))
+                .collect(),
+            accuracy,
+        ),
+        PathCombine::SubtractWithWindingRule(winding_rule, paths) => path_combine_series(
+            paths,
+            accuracy,
+            |path| path_combine_operand(path, winding_rule, accuracy),
+            path_sub,
+        ),
+        PathCombine::IntersectWithWindingRule(winding_rule, paths) => path_combine_series(
+            paths,
+            accuracy,
+            |path| path_combine_operand(path, winding_rule, accuracy),
+            path_intersect,
+        ),
+        PathCombine::XorWithWindingRule(winding_rule, paths) => path_combine_series(
+            paths,
+            accuracy,
+            |path| path_combine_operand(path, winding_rule, accuracy),
+            path_xor,
+        ),
     }
 }
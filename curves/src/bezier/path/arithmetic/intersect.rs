@@ -8,6 +8,7 @@ use super::super::super::super::geo::*;
 use super::super::graph_path::*;
 use super::super::path::*;
 use super::ray_cast::*;
+use super::winding_rule::*;
 
 impl<Point: Coordinate + Coordinate2D> GraphPath<Point, PathLabel> {
     ///
@@ -67,3 +68,30 @@ where
     // Produce the final result
     merged_path.exterior_paths()
 }
+
+///
+/// Generates the path formed by intersecting two sets of paths, using a winding rule to decide which points are inside each of them
+///
+/// The subpaths of each path can overlap each other: the winding rule is used to determine which regions they cover. The
+/// result is a set of subpaths that do not overlap, covering the regions covered by both paths.
+///
+pub fn path_intersect_with_winding_rule<POut>(
+    path1: &Vec<impl BezierPath<Point = POut::Point>>,
+    winding_rule1: PathWindingRule,
+    path2: &Vec<impl BezierPath<Point = POut::Point>>,
+    winding_rule2: PathWindingRule,
+    accuracy: f64,
+) -> Vec<POut>
+where
+    POut: BezierPathFactory,
+    POut::Point: Coordinate + Coordinate2D,
+{
+    path_combine_with_winding_rule(
+        path1,
+        winding_rule1,
+        path2,
+        winding_rule2,
+        accuracy,
+        |inside1, inside2| inside1 && inside2,
+    )
+}
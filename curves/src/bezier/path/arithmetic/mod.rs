@@ -12,6 +12,8 @@ mod full_intersect;
 mod intersect;
 mod ray_cast;
 mod sub;
+mod winding_rule;
+mod xor;
 
 pub use self::add::*;
 pub use self::chain::*;
@@ -21,3 +23,5 @@ pub use self::full_intersect::*;
 pub use self::intersect::*;
 pub use self::ray_cast::*;
 pub use self::sub::*;
+pub use self::winding_rule::*;
+pub use self::xor::*;
@@ -0,0 +1,129 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::super::super::super::geo::*;
+use super::super::graph_path::*;
+use super::super::path::*;
+use super::ray_cast::*;
+
+///
+/// Rule used to decide which points are inside a path made up of several subpaths, which may overlap each other
+///
+/// This is called `WindingRule` in most rendering libraries (including `flo_canvas`)
+///
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+pub enum PathWindingRule {
+    /// A point is inside the path if a ray cast from it crosses an odd number of edges
+    ///
+    /// This is how the arithmetic functions such as `path_add()` treat their inputs (every edge is an exterior edge)
+    #[default]
+    EvenOdd,
+
+    /// A point is inside the path if a ray cast from it crosses more edges going in one direction than in the other
+    NonZero,
+}
+
+impl PathWindingRule {
+    ///
+    /// Given the number of edges crossed by a ray (counting edges going in one direction as +1 and the other as -1), returns
+    /// true if the point at the end of the ray is inside the path
+    ///
+    #[inline]
+    pub fn is_inside(&self, crossings: i32) -> bool {
+        match self {
+            PathWindingRule::EvenOdd => (crossings & 1) != 0,
+            PathWindingRule::NonZero => crossings != 0,
+        }
+    }
+}
+
+impl<Point: Coordinate + Coordinate2D> GraphPath<Point, PathLabel> {
+    ///
+    /// Given a labelled graph path, marks exterior edges by applying a winding rule to `PathLabel(0)` and `PathLabel(1)`
+    ///
+    /// The `combine` function is passed whether or not a point is inside the first and second paths, and returns whether
+    /// or not that point is inside the result (for example, `|a, b| a || b` will add the two paths).
+    ///
+    /// Unlike the other arithmetic operations, the paths should be self-collided (using `self_collide()`), as the subpaths
+    /// of each path can overlap each other when a winding rule is applied.
+    ///
+    pub fn set_exterior_by_winding_rule(
+        &mut self,
+        winding_rule1: PathWindingRule,
+        winding_rule2: PathWindingRule,
+        combine: impl Fn(bool, bool) -> bool,
+    ) {
+        self.set_edge_kinds_by_ray_casting(|path_crossings| {
+            combine(
+                winding_rule1.is_inside(path_crossings[0]),
+                winding_rule2.is_inside(path_crossings[1]),
+            )
+        });
+    }
+}
+
+///
+/// Performs an arithmetic operation on two paths, using a winding rule to decide which points are inside each one
+///
+pub(crate) fn path_combine_with_winding_rule<POut>(
+    path1: &Vec<impl BezierPath<Point = POut::Point>>,
+    winding_rule1: PathWindingRule,
+    path2: &Vec<impl BezierPath<Point = POut::Point>>,
+    winding_rule2: PathWindingRule,
+    accuracy: f64,
+    combine: impl Fn(bool, bool) -> bool,
+) -> Vec<POut>
+where
+    POut: BezierPathFactory,
+    POut::Point: Coordinate + Coordinate2D,
+{
+    // Create a graph path containing both sides (the direction of the edges matters for the non-zero winding rule)
+    let mut merged_path = GraphPath::new();
+    for path in path1.iter() {
+        merged_path = merged_path.merge(GraphPath::from_path_keeping_direction(path, PathLabel(0)));
+    }
+    for path in path2.iter() {
+        merged_path = merged_path.merge(GraphPath::from_path_keeping_direction(path, PathLabel(1)));
+    }
+
+    // The subpaths of either side can overlap, so we need to find all of the intersections, not just the ones between the two sides
+    merged_path.self_collide(accuracy);
+    merged_path.round(accuracy);
+
+    // Set the exterior edges using the winding rules
+    merged_path.set_exterior_by_winding_rule(winding_rule1, winding_rule2, combine);
+    merged_path.heal_exterior_gaps();
+
+    // Produce the final result
+    merged_path.exterior_paths()
+}
+
+///
+/// Generates the path formed by applying a winding rule to a set of subpaths which might overlap each other
+///
+/// The result is a set of subpaths that don't overlap, so they can be used with the other arithmetic operations. With the
+/// `EvenOdd` winding rule, this is the same as `path_remove_overlapped_points()`. With the `NonZero` rule, subpaths that
+/// go in the same direction are combined, and subpaths that go in the opposite direction cut holes. This differs from
+/// `path_remove_interior_points()`, which removes all of the holes.
+///
+pub fn path_with_winding_rule<POut>(
+    path: &Vec<impl BezierPath<Point = POut::Point>>,
+    winding_rule: PathWindingRule,
+    accuracy: f64,
+) -> Vec<POut>
+where
+    POut: BezierPathFactory,
+    POut::Point: Coordinate + Coordinate2D,
+{
+    path_combine_with_winding_rule(
+        path,
+        winding_rule,
+        &Vec::<POut>::new(),
+        PathWindingRule::EvenOdd,
+        accuracy,
+        |inside1, _inside2| inside1,
+    )
+}
@@ -0,0 +1,100 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::super::super::super::geo::*;
+use super::super::graph_path::*;
+use super::super::path::*;
+use super::ray_cast::*;
+use super::winding_rule::*;
+
+impl<Point: Coordinate + Coordinate2D> GraphPath<Point, PathLabel> {
+    ///
+    /// Given a labelled graph path, marks exterior edges by finding the parts of `PathSource::Path1` and `PathSource::Path2`
+    /// that do not overlap
+    ///
+    pub fn set_exterior_by_exclusive_or(&mut self) {
+        // Use an even-odd winding rule (all edges are considered 'external')
+        self.set_edge_kinds_by_ray_casting(|path_crossings| {
+            ((path_crossings[0] & 1) != 0) != ((path_crossings[1] & 1) != 0)
+        });
+    }
+}
+
+///
+/// Generates the path formed by the exclusive-or of two sets of paths: that is, the regions covered by one path but not both
+///
+/// Each of the two paths passed into this function is assumed not to overlap themselves. IE, this does not perform self-intersection
+/// on either `path1` or `path2`. This provides both a performance optimisation and finer control over how self-intersecting paths are
+/// handled. See `path_remove_interior_points()` and `path_remove_overlapped_points()` for a way to eliminate overlaps, or
+/// `path_xor_with_winding_rule()` to combine paths that overlap themselves.
+///
+/// The input vectors represent the external edges of the path to combine (a single BezierPath cannot have any holes in it, so a set of them
+/// effectively represents a path intended to be rendered with an even-odd winding rule)
+///
+pub fn path_xor<POut>(
+    path1: &Vec<impl BezierPath<Point = POut::Point>>,
+    path2: &Vec<impl BezierPath<Point = POut::Point>>,
+    accuracy: f64,
+) -> Vec<POut>
+where
+    POut: BezierPathFactory,
+    POut::Point: Coordinate + Coordinate2D,
+{
+    // If either path is empty, short-circuit by returning the other
+    if path1.is_empty() {
+        return path2.iter().map(|path| POut::from_path(path)).collect();
+    } else if path2.is_empty() {
+        return path1.iter().map(|path| POut::from_path(path)).collect();
+    }
+
+    // Create the graph path from the source side
+    let mut merged_path = GraphPath::new();
+    merged_path = merged_path.merge(GraphPath::from_merged_paths(
+        path1.iter().map(|path| (path, PathLabel(0))),
+    ));
+
+    // Collide with the target side to generate a full path
+    merged_path = merged_path.collide(
+        GraphPath::from_merged_paths(path2.iter().map(|path| (path, PathLabel(1)))),
+        accuracy,
+    );
+    merged_path.round(accuracy);
+
+    // Set the exterior edges using the 'exclusive or' algorithm
+    merged_path.set_exterior_by_exclusive_or();
+    merged_path.heal_exterior_gaps();
+
+    // Produce the final result
+    merged_path.exterior_paths()
+}
+
+///
+/// Generates the path formed by the exclusive-or of two sets of paths, using a winding rule to decide which points are inside
+/// each of them
+///
+/// The subpaths of each path can overlap each other: the winding rule is used to determine which regions they cover. The
+/// result is a set of subpaths that do not overlap.
+///
+pub fn path_xor_with_winding_rule<POut>(
+    path1: &Vec<impl BezierPath<Point = POut::Point>>,
+    winding_rule1: PathWindingRule,
+    path2: &Vec<impl BezierPath<Point = POut::Point>>,
+    winding_rule2: PathWindingRule,
+    accuracy: f64,
+) -> Vec<POut>
+where
+    POut: BezierPathFactory,
+    POut::Point: Coordinate + Coordinate2D,
+{
+    path_combine_with_winding_rule(
+        path1,
+        winding_rule1,
+        path2,
+        winding_rule2,
+        accuracy,
+        |inside1, inside2| inside1 != inside2,
+    )
+}
@@ -184,6 +184,21 @@ impl<Point: Coordinate + Coordinate2D, Label: Copy> GraphPath<Point, Label> {
         }
     }
 
+    ///
+    /// Creates a graph path from a bezier path, keeping the direction of its edges
+    ///
+    /// This is the same conversion that `from_path()` uses for clockwise paths, applied to every path: the edges are added in
+    /// the order they appear in the path, whichever direction it goes in. `from_path()` reverses anticlockwise paths so that all
+    /// of the edges in the graph go in a clockwise direction, which loses the information needed by winding rules that depend
+    /// on the direction of the edges, such as the non-zero winding rule.
+    ///
+    pub fn from_path_keeping_direction(
+        path: &impl BezierPath<Point = Point>,
+        label: Label,
+    ) -> GraphPath<Point, Label> {
+        Self::from_clockwise_path(path, label)
+    }
+
     ///
     /// Creates a graph path from a bezier path moving in an anti-clockwise direction
     ///
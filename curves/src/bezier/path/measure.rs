@@ -0,0 +1,320 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::path::*;
+
+use crate::bezier::*;
+use crate::geo::*;
+
+/// Maximum number of iterations to perform when searching for the t value at a particular distance along a curve
+const MAX_ITERATIONS: usize = 32;
+
+/// Distance either side of a t value to sample when a curve has no tangent at that point
+const NEARBY_T: f64 = 1e-6;
+
+///
+/// Measures distances along a path, for operations such as laying out text along a path or animating an object following it
+///
+/// The lengths of each of the curves in the path are calculated when this is created, so it's much faster to use this than
+/// it is to measure the path for each query. Distances are clamped to the length of the path.
+///
+#[derive(Clone, Debug)]
+pub struct PathMeasure<Point: Coordinate> {
+    /// The start point of the path
+    start_point: Point,
+
+    /// The curves that make up the path
+    curves: Vec<Curve<Point>>,
+
+    /// The length of each curve
+    lengths: Vec<f64>,
+
+    /// The distance along the path where each curve ends
+    end_distances: Vec<f64>,
+
+    /// The maximum error when measuring the length of a curve
+    max_error: f64,
+}
+
+impl<Point: Coordinate + Coordinate2D> PathMeasure<Point> {
+    ///
+    /// Measures a path, with each length accurate to within `max_error`
+    ///
+    pub fn new(path: &impl BezierPath<Point = Point>, max_error: f64) -> PathMeasure<Point> {
+        let curves = path.to_curves::<Curve<Point>>();
+        let lengths = curves
+            .iter()
+            .map(|curve| curve_length(curve, max_error))
+            .collect::<Vec<_>>();
+
+        let mut total_length = 0.0;
+        let end_distances = lengths
+            .iter()
+            .map(|length| {
+                total_length += length;
+                total_length
+            })
+            .collect();
+
+        PathMeasure {
+            start_point: path.start_point(),
+            curves,
+            lengths,
+            end_distances,
+            max_error,
+        }
+    }
+
+    ///
+    /// The total length of the path
+    ///
+    #[inline]
+    pub fn total_length(&self) -> f64 {
+        self.end_distances.last().copied().unwrap_or(0.0)
+    }
+
+    ///
+    /// The length of each of the curves that make up the path
+    ///
+    #[inline]
+    pub fn curve_lengths(&self) -> &[f64] {
+        &self.lengths
+    }
+
+    ///
+    /// Finds the index of the curve containing a particular distance along the path, starting the search at `first_curve`
+    ///
+    fn curve_for_distance(&self, distance: f64, first_curve: usize) -> usize {
+        // Find the first curve that ends after the distance (skipping any curves with no length)
+        let mut curve_idx = first_curve
+            + self.end_distances[first_curve..]
+                .partition_point(|end_distance| *end_distance < distance);
+        curve_idx = curve_idx.min(self.curves.len() - 1);
+
+        while curve_idx + 1 < self.curves.len() && self.lengths[curve_idx] <= 0.0 {
+            curve_idx += 1;
+        }
+
+        curve_idx
+    }
+
+    ///
+    /// The distance along the path where a curve starts
+    ///
+    #[inline]
+    fn curve_start(&self, curve_idx: usize) -> f64 {
+        self.end_distances[curve_idx] - self.lengths[curve_idx]
+    }
+
+    ///
+    /// Finds the curve index and the t value of the point at a particular distance along the path
+    ///
+    fn find_distance(&self, distance: f64) -> Option<(usize, f64)> {
+        if self.curves.is_empty() {
+            return None;
+        }
+
+        let distance = distance.max(0.0).min(self.total_length());
+        let curve_idx = self.curve_for_distance(distance, 0);
+        let (t, _) = self.t_for_distance(
+            curve_idx,
+            distance - self.curve_start(curve_idx),
+            (0.0, 0.0),
+        );
+
+        Some((curve_idx, t))
+    }
+
+    ///
+    /// Finds the t value of the point at a particular distance along a curve, along with the measured distance at that t value
+    ///
+    /// `known` is a t value and the distance along the curve at that point, which must be before the distance that's being
+    /// searched for. Only the sections of the curve between the known point and the estimates are measured, so supplying a
+    /// nearby point makes the search faster.
+    ///
+    fn t_for_distance(&self, curve_idx: usize, distance: f64, known: (f64, f64)) -> (f64, f64) {
+        let curve = &self.curves[curve_idx];
+        let length = self.lengths[curve_idx];
+        let (mut known_t, mut known_distance) = known;
+
+        if length <= 0.0 || distance <= known_distance {
+            return (known_t, known_distance);
+        } else if distance >= length {
+            return (1.0, length);
+        }
+
+        // Use Newton-Raphson to find the t value, falling back to a binary search if it goes outside of the known range
+        let mut min_t = known_t;
+        let mut max_t = 1.0;
+        let mut t =
+            known_t + (1.0 - known_t) * (distance - known_distance) / (length - known_distance);
+
+        for _ in 0..MAX_ITERATIONS {
+            // Measure from the previous estimate to this one, rather than from the start of the curve
+            let t_distance = if t >= known_t {
+                known_distance + curve_length(&curve.section(known_t, t), self.max_error)
+            } else {
+                known_distance - curve_length(&curve.section(t, known_t), self.max_error)
+            };
+            known_t = t;
+            known_distance = t_distance;
+
+            let error = t_distance - distance;
+
+            if error.abs() <= self.max_error {
+                break;
+            }
+
+            if error > 0.0 {
+                max_t = t;
+            } else {
+                min_t = t;
+            }
+
+            let speed = curve.tangent_at_pos(t).magnitude();
+            let next_t = if speed > 0.0 { t - error / speed } else { -1.0 };
+
+            t = if next_t > min_t && next_t < max_t {
+                next_t
+            } else {
+                (min_t + max_t) / 2.0
+            };
+        }
+
+        (known_t, known_distance)
+    }
+
+    ///
+    /// Returns the direction of a curve at a t value as a unit vector
+    ///
+    /// The tangent of a curve has no length where a control point is on top of the end point next to it, so this falls
+    /// back to the direction between nearby points, and then to the direction from the start to the end of the curve.
+    ///
+    fn unit_tangent(curve: &Curve<Point>, t: f64) -> Point {
+        let tangent = curve.tangent_at_pos(t);
+        if tangent.magnitude() > f64::EPSILON {
+            return tangent.to_unit_vector();
+        }
+
+        let before = curve.point_at_pos((t - NEARBY_T).max(0.0));
+        let after = curve.point_at_pos((t + NEARBY_T).min(1.0));
+        let direction = after - before;
+        if direction.magnitude() > f64::EPSILON {
+            return direction.to_unit_vector();
+        }
+
+        let chord = curve.end_point() - curve.start_point();
+        if chord.magnitude() > f64::EPSILON {
+            chord.to_unit_vector()
+        } else {
+            Point::origin()
+        }
+    }
+
+    ///
+    /// Returns the point at a particular distance along the path
+    ///
+    pub fn point_at(&self, distance: f64) -> Point {
+        if let Some((curve_idx, t)) = self.find_distance(distance) {
+            self.curves[curve_idx].point_at_pos(t)
+        } else {
+            self.start_point
+        }
+    }
+
+    ///
+    /// Returns the direction of the path at a particular distance along it, as a unit vector
+    ///
+    pub fn tangent_at(&self, distance: f64) -> Point {
+        if let Some((curve_idx, t)) = self.find_distance(distance) {
+            Self::unit_tangent(&self.curves[curve_idx], t)
+        } else {
+            Point::origin()
+        }
+    }
+
+    ///
+    /// Returns the section of the path between two distances along it
+    ///
+    /// If `to` is before `from`, the result is a path containing no curves, starting at the point at `from`.
+    ///
+    pub fn sub_path<POut>(&self, from: f64, to: f64) -> POut
+    where
+        POut: BezierPathFactory<Point = Point>,
+    {
+        let (from_idx, from_t) = match self.find_distance(from) {
+            Some(location) => location,
+            None => return POut::from_points(self.start_point, vec![]),
+        };
+
+        if to < from {
+            return POut::from_points(self.curves[from_idx].point_at_pos(from_t), vec![]);
+        }
+
+        let (to_idx, to_t) = self.find_distance(to).unwrap();
+
+        // Generate the sections of the curves between the two points
+        let sections = if from_idx == to_idx {
+            vec![self.curves[from_idx].section(from_t, to_t)]
+        } else {
+            let mut sections = vec![self.curves[from_idx].section(from_t, 1.0)];
+            sections.extend(
+                self.curves[(from_idx + 1)..to_idx]
+                    .iter()
+                    .map(|curve| curve.section(0.0, 1.0)),
+            );
+            sections.push(self.curves[to_idx].section(0.0, to_t));
+
+            sections
+        };
+
+        let start_point = sections[0].start_point();
+        POut::from_points(
+            start_point,
+            sections.into_iter().map(|section| {
+                let (cp1, cp2) = section.control_points();
+                (cp1, cp2, section.end_point())
+            }),
+        )
+    }
+
+    ///
+    /// Walks along the path, returning the point and the tangent (as a unit vector) at intervals of `step`
+    ///
+    /// The first point is at the start of the path, and the walk continues across the boundaries between curves until the end
+    /// of the path is reached.
+    ///
+    pub fn walk_evenly(&self, step: f64) -> impl '_ + Iterator<Item = (Point, Point)> {
+        let total_length = self.total_length();
+        let num_steps = if step > 0.0 && !self.curves.is_empty() {
+            // Allow for a little rounding error so that the end point is included when the length is a multiple of the step
+            ((total_length + self.max_error * 0.5) / step).floor() as usize + 1
+        } else {
+            0
+        };
+
+        // Each step carries on from the curve, t value and distance along the curve of the previous step
+        let mut curve_idx = 0;
+        let mut known = (0.0, 0.0);
+
+        (0..num_steps).map(move |step_idx| {
+            let distance = ((step_idx as f64) * step).min(total_length);
+
+            let next_curve_idx = self.curve_for_distance(distance, curve_idx);
+            if next_curve_idx != curve_idx {
+                curve_idx = next_curve_idx;
+                known = (0.0, 0.0);
+            }
+
+            let curve_distance = distance - self.curve_start(curve_idx);
+            known = self.t_for_distance(curve_idx, curve_distance, known);
+            let t = known.0;
+
+            let curve = &self.curves[curve_idx];
+            (curve.point_at_pos(t), Self::unit_tangent(curve, t))
+        })
+    }
+}
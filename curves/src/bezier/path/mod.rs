@@ -27,7 +27,7 @@
 //! Anything that implements the `BezierPath` trait can be treated as a path. The `SimpleBezierPath` type is provided
 //! as a convenient default implementation of this trait. These paths represent a single perimeter of a region.
 //!
-//! The arithmetic operations such as `path_sub()`, `path_add()`, `path_intersect()` all work with collections of these
+//! The arithmetic operations such as `path_sub()`, `path_add()`, `path_intersect()`, `path_xor()` all work with collections of these
 //! perimeters, stored in a `Vec`. A path with a hole in the middle will have two perimeters, for example.
 //!
 //! These perimeters must not be self-intersecting: `flo_curves` doesn't use a winding rule as such but instead considers
@@ -37,8 +37,18 @@
 //! there are no overlapping edges. These two functions provide much finer control than is possible through the traditional
 //! idea of the winding rule.
 //!
+//! Where the subpaths do overlap, the `_with_winding_rule()` versions of the arithmetic operations (for example,
+//! `path_add_with_winding_rule()`) can be used instead. These take a `PathWindingRule` for each of their inputs, which
+//! describes which regions are covered by the overlapping subpaths.
+//!
 //! There are a few more advanced algorithms: for example, the `flood_fill_concave()` function provides a vector
-//! implementation of the flood fill algorithm, returning a path that fills a space defined by a ray-casting function.
+//! implementation of the flood fill algorithm, returning a path that fills a space defined by a ray-casting function,
+//! `path_offset()` grows or shrinks a path by moving its edges outwards or inwards, and `path_simplify()` replaces runs
+//! of small curves (such as those found in hand-drawn paths) with fewer, larger ones.
+//!
+//! `PathMorph` interpolates between two paths, which is useful for animating one shape turning into another.
+//! `PathMeasure` measures distances along a path, and can find the point at a particular distance or walk along the path at
+//! even intervals.
 //!
 
 pub mod algorithms;
@@ -47,10 +57,13 @@ mod bounds;
 mod graph_path;
 mod intersection;
 mod is_clockwise;
+mod measure;
+mod morph;
 mod path;
 mod path_builder;
 mod point;
 mod ray;
+mod simplify;
 mod stroke;
 mod to_curves;
 
@@ -59,8 +72,11 @@ pub use self::bounds::*;
 pub use self::graph_path::*;
 pub use self::intersection::*;
 pub use self::is_clockwise::*;
+pub use self::measure::*;
+pub use self::morph::*;
 pub use self::path::*;
 pub use self::path_builder::*;
 pub use self::point::*;
+pub use self::simplify::*;
 pub use self::stroke::*;
 pub use self::to_curves::*;
@@ -0,0 +1,412 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::path::*;
+
+use crate::bezier::*;
+use crate::geo::*;
+
+use std::f64;
+
+/// Number of points to sample from each curve when estimating the area of a subpath
+const AREA_SAMPLES_PER_CURVE: usize = 8;
+
+///
+/// A subpath stored as a list of curves, along with the properties used to match it up with a subpath in the other path
+///
+struct MorphSubpath<Point: Coordinate> {
+    /// The start point of the subpath
+    start_point: Point,
+
+    /// The curves making up this subpath
+    curves: Vec<Curve<Point>>,
+
+    /// The signed area of the subpath (the sign indicates which direction it goes in)
+    signed_area: f64,
+
+    /// The center of the bounding box of this subpath
+    center: Point,
+}
+
+///
+/// Interpolates between two paths, to animate one shape turning into another
+///
+/// The two paths can have different numbers of subpaths, and the subpaths can have different numbers of curves. Subpaths
+/// are matched up by their position and area, and the curves in each matched pair are subdivided so that both sides have the
+/// same number. Closed subpaths are made to go in the same direction, and their start points are moved so that the curves
+/// don't twist around each other as the shape changes. Any subpaths that are left over after matching grow out of or
+/// shrink into their center.
+///
+/// Each subpath in the result of `path_at()` can be drawn directly using `GraphicsContext::bezier_path()`.
+///
+#[derive(Clone, Debug)]
+pub struct PathMorph<Point> {
+    /// The subpaths at the start and end of the morph (each pair has the same number of curves)
+    subpaths: Vec<(
+        (Point, Vec<(Point, Point, Point)>),
+        (Point, Vec<(Point, Point, Point)>),
+    )>,
+}
+
+impl<Point: Coordinate + Coordinate2D> MorphSubpath<Point> {
+    ///
+    /// Reads a subpath from a path
+    ///
+    fn from_path(path: &impl BezierPath<Point = Point>) -> MorphSubpath<Point> {
+        let start_point = path.start_point();
+        let curves = path.to_curves::<Curve<Point>>();
+
+        // Estimate the area using the shoelace formula
+        let mut samples = vec![start_point];
+        for curve in curves.iter() {
+            for sample_idx in 1..=AREA_SAMPLES_PER_CURVE {
+                samples.push(
+                    curve.point_at_pos((sample_idx as f64) / (AREA_SAMPLES_PER_CURVE as f64)),
+                );
+            }
+        }
+
+        let signed_area = samples
+            .iter()
+            .zip(samples.iter().skip(1).chain(samples.iter().take(1)))
+            .map(|(p1, p2)| p1.x() * p2.y() - p2.x() * p1.y())
+            .sum::<f64>()
+            / 2.0;
+
+        let (min, max) = path.bounding_box::<(Point, Point)>();
+        let center = (min + max) * 0.5;
+
+        MorphSubpath {
+            start_point,
+            curves,
+            signed_area,
+            center,
+        }
+    }
+
+    ///
+    /// Creates a subpath that has collapsed into a single point
+    ///
+    fn point(point: Point) -> MorphSubpath<Point> {
+        MorphSubpath {
+            start_point: point,
+            curves: vec![],
+            signed_area: 0.0,
+            center: point,
+        }
+    }
+
+    ///
+    /// True if this subpath ends where it started
+    ///
+    fn is_closed(&self) -> bool {
+        self.curves
+            .last()
+            .map(|curve| curve.end_point().is_near_to(&self.start_point, 1e-6))
+            .unwrap_or(false)
+    }
+
+    ///
+    /// Reverses the direction of this subpath
+    ///
+    fn reverse(&mut self) {
+        self.curves = self
+            .curves
+            .iter()
+            .rev()
+            .map(|curve| curve.reverse::<Curve<_>>())
+            .collect();
+        self.start_point = self
+            .curves
+            .first()
+            .map(|curve| curve.start_point())
+            .unwrap_or(self.start_point);
+        self.signed_area = -self.signed_area;
+    }
+
+    ///
+    /// Subdivides the curves in this subpath until there are at least `num_curves` of them
+    ///
+    fn subdivide(&mut self, num_curves: usize) {
+        if self.curves.len() >= num_curves {
+            return;
+        }
+
+        if self.curves.is_empty() {
+            // A subpath with no curves is a single point
+            let point = self.start_point;
+            self.curves = vec![Curve::from_points(point, (point, point), point); num_curves];
+            return;
+        }
+
+        // Decide how many sections to divide each curve into, by repeatedly adding a section to the curve with the longest sections
+        let lengths = self
+            .curves
+            .iter()
+            .map(|curve| {
+                let (cp1, cp2) = curve.control_points();
+                curve.start_point().distance_to(&cp1)
+                    + cp1.distance_to(&cp2)
+                    + cp2.distance_to(&curve.end_point())
+            })
+            .collect::<Vec<_>>();
+        let mut num_sections = vec![1; self.curves.len()];
+
+        for _ in self.curves.len()..num_curves {
+            let mut longest_idx = 0;
+            for idx in 1..num_sections.len() {
+                if lengths[idx] / (num_sections[idx] as f64)
+                    > lengths[longest_idx] / (num_sections[longest_idx] as f64)
+                {
+                    longest_idx = idx;
+                }
+            }
+
+            num_sections[longest_idx] += 1;
+        }
+
+        // Divide up the curves
+        self.curves = self
+            .curves
+            .iter()
+            .zip(num_sections)
+            .flat_map(|(curve, num_sections)| {
+                (0..num_sections).map(move |section_idx| {
+                    let t_min = (section_idx as f64) / (num_sections as f64);
+                    let t_max = ((section_idx + 1) as f64) / (num_sections as f64);
+
+                    Curve::from_curve(&curve.section(t_min, t_max))
+                })
+            })
+            .collect();
+    }
+
+    ///
+    /// Changes the curve that this subpath starts at (the subpath should be closed)
+    ///
+    fn rotate(&mut self, first_curve: usize) {
+        self.curves.rotate_left(first_curve);
+        self.start_point = self.curves[0].start_point();
+    }
+
+    ///
+    /// Returns the start points of each curve, relative to the center of the subpath
+    ///
+    fn relative_start_points(&self) -> Vec<Point> {
+        self.curves
+            .iter()
+            .map(|curve| curve.start_point() - self.center)
+            .collect()
+    }
+
+    ///
+    /// Converts this subpath to a start point and a list of points
+    ///
+    fn to_points(&self) -> (Point, Vec<(Point, Point, Point)>) {
+        let points = self
+            .curves
+            .iter()
+            .map(|curve| {
+                let (cp1, cp2) = curve.control_points();
+                (cp1, cp2, curve.end_point())
+            })
+            .collect();
+
+        (self.start_point, points)
+    }
+}
+
+///
+/// Makes two subpaths that have been matched together have the same number of curves, going in the same direction and starting at the
+/// corresponding points
+///
+fn align_subpaths<Point: Coordinate + Coordinate2D>(
+    from: &mut MorphSubpath<Point>,
+    to: &mut MorphSubpath<Point>,
+) {
+    let both_closed = from.is_closed() && to.is_closed();
+
+    if both_closed {
+        // Closed subpaths should go in the same direction
+        if (from.signed_area < 0.0) != (to.signed_area < 0.0) {
+            to.reverse();
+        }
+    } else if !from.curves.is_empty() && !to.curves.is_empty() {
+        // Open subpaths should go in the direction that moves the end points the shortest distance
+        let from_end = from.curves[from.curves.len() - 1].end_point();
+        let to_end = to.curves[to.curves.len() - 1].end_point();
+
+        let forward_distance =
+            from.start_point.distance_to(&to.start_point) + from_end.distance_to(&to_end);
+        let reverse_distance =
+            from.start_point.distance_to(&to_end) + from_end.distance_to(&to.start_point);
+
+        if reverse_distance < forward_distance {
+            to.reverse();
+        }
+    }
+
+    // Both sides need the same number of curves
+    let num_curves = from.curves.len().max(to.curves.len());
+    from.subdivide(num_curves);
+    to.subdivide(num_curves);
+
+    if both_closed && num_curves > 1 {
+        // Pick the start point that moves the points on the curve the shortest distance (relative to the center of each subpath)
+        let from_points = from.relative_start_points();
+        let to_points = to.relative_start_points();
+
+        let total_distance = |offset: usize| {
+            (0..num_curves)
+                .map(|idx| {
+                    let from_point = from_points[idx];
+                    let to_point = to_points[(idx + offset) % num_curves];
+                    let difference = to_point - from_point;
+
+                    difference.dot(&difference)
+                })
+                .sum::<f64>()
+        };
+
+        let best_offset = (0..num_curves)
+            .map(|offset| (offset, total_distance(offset)))
+            .fold((0, f64::MAX), |best, (offset, distance)| {
+                if distance < best.1 {
+                    (offset, distance)
+                } else {
+                    best
+                }
+            })
+            .0;
+
+        to.rotate(best_offset);
+    }
+}
+
+impl<Point: Coordinate + Coordinate2D> PathMorph<Point> {
+    ///
+    /// Creates a morph that turns the `from` path (at t=0) into the `to` path (at t=1)
+    ///
+    pub fn new(
+        from: &Vec<impl BezierPath<Point = Point>>,
+        to: &Vec<impl BezierPath<Point = Point>>,
+    ) -> PathMorph<Point> {
+        let from = from.iter().map(MorphSubpath::from_path).collect::<Vec<_>>();
+        let to = to.iter().map(MorphSubpath::from_path).collect::<Vec<_>>();
+
+        // Distances are measured relative to the size of the two paths
+        let center_bounds =
+            Bounds::bounds_for_points(from.iter().chain(to.iter()).map(|subpath| subpath.center));
+        let largest_subpath = from
+            .iter()
+            .chain(to.iter())
+            .map(|subpath| subpath.signed_area.abs().sqrt())
+            .fold(0.0, f64::max);
+        let scale = center_bounds
+            .min()
+            .distance_to(&center_bounds.max())
+            .max(largest_subpath)
+            .max(f64::EPSILON);
+
+        // Match up subpaths, starting with those that are the most similar in position and area
+        let mut candidates = vec![];
+        for (from_idx, from_subpath) in from.iter().enumerate() {
+            for (to_idx, to_subpath) in to.iter().enumerate() {
+                let from_area = from_subpath.signed_area.abs();
+                let to_area = to_subpath.signed_area.abs();
+
+                let distance = from_subpath.center.distance_to(&to_subpath.center) / scale;
+                let area_difference =
+                    (from_area - to_area).abs() / from_area.max(to_area).max(f64::EPSILON);
+
+                candidates.push((distance + area_difference, from_idx, to_idx));
+            }
+        }
+        candidates.sort_by(|(a, _, _), (b, _, _)| a.total_cmp(b));
+
+        let mut matched_from = vec![None; from.len()];
+        let mut to_is_matched = vec![false; to.len()];
+        for (_, from_idx, to_idx) in candidates {
+            if matched_from[from_idx].is_none() && !to_is_matched[to_idx] {
+                matched_from[from_idx] = Some(to_idx);
+                to_is_matched[to_idx] = true;
+            }
+        }
+
+        // Generate the pairs of subpaths, in the order of the 'from' path: subpaths with no partner shrink into or grow out of their center
+        let mut to = to.into_iter().map(Some).collect::<Vec<_>>();
+        let mut pairs = vec![];
+
+        for (from_subpath, to_idx) in from.into_iter().zip(matched_from) {
+            let to_subpath = to_idx
+                .and_then(|to_idx| to[to_idx].take())
+                .unwrap_or_else(|| MorphSubpath::point(from_subpath.center));
+
+            pairs.push((from_subpath, to_subpath));
+        }
+
+        for to_subpath in to.into_iter().flatten() {
+            pairs.push((MorphSubpath::point(to_subpath.center), to_subpath));
+        }
+
+        // Make the curves in each pair correspond to each other
+        let subpaths = pairs
+            .into_iter()
+            .map(|(mut from_subpath, mut to_subpath)| {
+                align_subpaths(&mut from_subpath, &mut to_subpath);
+                (from_subpath.to_points(), to_subpath.to_points())
+            })
+            .collect();
+
+        PathMorph { subpaths }
+    }
+
+    ///
+    /// Returns the path at a particular point in the morph (with t=0 being the `from` path and t=1 being the `to` path)
+    ///
+    pub fn path_at<POut>(&self, t: f64) -> Vec<POut>
+    where
+        POut: BezierPathFactory<Point = Point>,
+    {
+        let lerp = |from: Point, to: Point| from * (1.0 - t) + to * t;
+
+        self.subpaths
+            .iter()
+            .map(|((from_start, from_points), (to_start, to_points))| {
+                POut::from_points(
+                    lerp(*from_start, *to_start),
+                    from_points.iter().zip(to_points.iter()).map(
+                        |((from_cp1, from_cp2, from_end), (to_cp1, to_cp2, to_end))| {
+                            (
+                                lerp(*from_cp1, *to_cp1),
+                                lerp(*from_cp2, *to_cp2),
+                                lerp(*from_end, *to_end),
+                            )
+                        },
+                    ),
+                )
+            })
+            .collect()
+    }
+}
+
+///
+/// Returns a function that interpolates between two paths, with t=0 being the `from` path and t=1 being the `to` path
+///
+/// See `PathMorph` for details on how the paths are matched up.
+///
+pub fn path_morph<POut>(
+    from: &Vec<impl BezierPath<Point = POut::Point>>,
+    to: &Vec<impl BezierPath<Point = POut::Point>>,
+) -> impl Fn(f64) -> Vec<POut>
+where
+    POut: BezierPathFactory,
+    POut::Point: Coordinate + Coordinate2D,
+{
+    let morph = PathMorph::new(from, to);
+
+    move |t| morph.path_at(t)
+}
@@ -0,0 +1,343 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::path::*;
+
+use crate::bezier::*;
+use crate::geo::*;
+
+use std::f64;
+
+/// Minimum angle between the tangents where two curves meet for the join to be treated as a corner
+const CORNER_ANGLE: f64 = f64::consts::PI / 4.0;
+
+/// Proportion of the maximum error used up when converting curves to polylines
+const FLATNESS: f64 = 0.25;
+
+/// Proportion of the maximum error to use as the distance between the points checked along the polylines
+const SPACING: f64 = 0.25;
+
+/// Proportion of the maximum error to use when fitting curves to a run (a closer fit is more likely to pass the tolerance check)
+const FIT_ERROR: f64 = 0.25;
+
+///
+/// Samples a curve so that the lines between the samples are no further than `tolerance` from the curve, adding the samples to
+/// a list (the end point of the curve is not included)
+///
+fn sample_curve_flatness<P: Coordinate + Coordinate2D>(
+    curve: &Curve<P>,
+    tolerance: f64,
+    samples: &mut Vec<P>,
+) {
+    // The distance between a curve and a line divided into n sections is at most 3/4 * max(|p0-2p1+p2|, |p1-2p2+p3|) / n^2
+    let start_point = curve.start_point();
+    let (cp1, cp2) = curve.control_points();
+    let end_point = curve.end_point();
+    let max_second_difference = (start_point - cp1 * 2.0 + cp2)
+        .magnitude()
+        .max((cp1 - cp2 * 2.0 + end_point).magnitude());
+
+    let num_samples = ((0.75 * max_second_difference / tolerance).sqrt().ceil() as usize).max(1);
+
+    for sample_idx in 0..num_samples {
+        samples.push(curve.point_at_pos((sample_idx as f64) / (num_samples as f64)));
+    }
+}
+
+///
+/// Converts a list of curves to a polyline that is no further than `tolerance` from them
+///
+fn flatten_curves<P: Coordinate + Coordinate2D>(curves: &[Curve<P>], tolerance: f64) -> Vec<P> {
+    let mut points = vec![];
+    for curve in curves.iter() {
+        sample_curve_flatness(curve, tolerance, &mut points);
+    }
+    points.push(curves[curves.len() - 1].end_point());
+
+    // The fitting algorithm can't deal with points that are on top of each other
+    points.dedup_by(|point, last_point| point.is_near_to(last_point, f64::EPSILON));
+
+    points
+}
+
+///
+/// Returns the lines that make up a polyline (a polyline with a single point is treated as a line with no length)
+///
+fn polyline_lines<P: Coordinate + Coordinate2D>(points: &[P]) -> Vec<(P, P)> {
+    if points.len() == 1 {
+        vec![(points[0], points[0])]
+    } else {
+        points
+            .iter()
+            .zip(points.iter().skip(1))
+            .map(|(start, end)| (*start, *end))
+            .collect()
+    }
+}
+
+///
+/// Returns the distance between a point and the nearest point on a line
+///
+fn distance_to_line<P: Coordinate + Coordinate2D>(point: &P, (start, end): &(P, P)) -> f64 {
+    let direction = *end - *start;
+    let length_squared = direction.dot(&direction);
+    let pos = if length_squared > 0.0 {
+        ((*point - *start).dot(&direction) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    point.distance_to(&(*start + direction * pos))
+}
+
+///
+/// Returns the bounds of a line, expanded by a particular distance
+///
+fn expanded_bounds<P: Coordinate + Coordinate2D>(
+    (start, end): &(P, P),
+    distance: f64,
+) -> Bounds<P> {
+    let bounds = Bounds::bounds_for_points(vec![*start, *end]);
+    let expand = P::from_components(&[distance, distance]);
+
+    Bounds::from_min_max(bounds.min() - expand, bounds.max() + expand)
+}
+
+///
+/// True if every point on the polyline `points` is within `max_distance` of the polyline `target`
+///
+/// Each line is checked at intervals of `spacing`. Moving along a line changes the distance to the target by no more than
+/// the distance moved, so the points between the checked ones are allowed for by checking against `max_distance` less half
+/// of the spacing.
+///
+fn polyline_within_distance<P: Coordinate + Coordinate2D>(
+    points: &[P],
+    target: &[P],
+    max_distance: f64,
+    spacing: f64,
+) -> bool {
+    let check_distance = max_distance - spacing * 0.5;
+    if check_distance < 0.0 {
+        return false;
+    }
+
+    let target_lines = polyline_lines(target);
+    let target_bounds = target_lines
+        .iter()
+        .map(|line| expanded_bounds(line, 0.0))
+        .collect::<Vec<_>>();
+
+    polyline_lines(points).into_iter().all(|(start, end)| {
+        // Only the lines that overlap the bounds of this line can be near to it
+        let line_bounds = expanded_bounds(&(start, end), max_distance);
+        let nearby_lines = target_lines
+            .iter()
+            .zip(target_bounds.iter())
+            .filter(|(_, bounds)| bounds.overlaps(&line_bounds))
+            .map(|(line, _)| line)
+            .collect::<Vec<_>>();
+
+        let num_samples = ((start.distance_to(&end) / spacing).ceil() as usize).max(1);
+
+        (0..=num_samples).all(|sample_idx| {
+            let point = start + (end - start) * ((sample_idx as f64) / (num_samples as f64));
+
+            nearby_lines
+                .iter()
+                .any(|line| distance_to_line(&point, line) <= check_distance)
+        })
+    })
+}
+
+///
+/// True if two curves meet at a corner (the tangents where the first curve ends and the second begins point in different directions)
+///
+fn is_corner<P: Coordinate + Coordinate2D>(previous: &Curve<P>, next: &Curve<P>) -> bool {
+    let previous_tangent = previous.tangent_at_pos(1.0);
+    let next_tangent = next.tangent_at_pos(0.0);
+
+    let previous_length = previous_tangent.magnitude();
+    let next_length = next_tangent.magnitude();
+
+    if previous_length < f64::EPSILON || next_length < f64::EPSILON {
+        // One of the curves has no direction at this point
+        false
+    } else {
+        let cos_angle = previous_tangent.dot(&next_tangent) / (previous_length * next_length);
+        cos_angle.clamp(-1.0, 1.0).acos() > CORNER_ANGLE
+    }
+}
+
+///
+/// True if a curve should be kept as it is rather than being fitted along with its neighbours
+///
+fn is_feature<P: Coordinate + Coordinate2D>(curve: &Curve<P>, max_error: f64) -> bool {
+    matches!(
+        features_for_curve(curve, max_error),
+        CurveFeatures::Cusp | CurveFeatures::Loop(_, _)
+    )
+}
+
+///
+/// True if a run of curves should end where two curves meet: at corners and on either side of any curve with a feature
+///
+fn ends_run<P: Coordinate + Coordinate2D>(
+    previous: &Curve<P>,
+    next: &Curve<P>,
+    max_error: f64,
+) -> bool {
+    is_corner(previous, next) || is_feature(previous, max_error) || is_feature(next, max_error)
+}
+
+///
+/// True if every point on the fitted curves is within `max_error` of the original polyline, and every point on the original
+/// polyline is within `max_error` of the fitted curves
+///
+/// `original` must be within `max_error * FLATNESS` of the original curves, so the fitted curves are within `max_error`
+/// of the original curves if this returns true.
+///
+fn is_within_tolerance<P: Coordinate + Coordinate2D>(
+    original: &[P],
+    fitted: &[Curve<P>],
+    max_error: f64,
+) -> bool {
+    // Both paths are flattened, which uses up some of the error, and the remainder is the allowed distance between the polylines
+    let fitted = flatten_curves(fitted, max_error * FLATNESS);
+    let max_distance = max_error * (1.0 - 2.0 * FLATNESS);
+    let spacing = max_error * SPACING;
+
+    polyline_within_distance(&fitted, original, max_distance, spacing)
+        && polyline_within_distance(original, &fitted, max_distance, spacing)
+}
+
+///
+/// Simplifies a smooth run of curves (with no corners), adding the result to a list of points
+///
+fn simplify_run<P: Coordinate + Coordinate2D>(
+    curves: &[Curve<P>],
+    max_error: f64,
+    points: &mut Vec<(P, P, P)>,
+) {
+    if curves.len() > 1 {
+        let samples = flatten_curves(curves, max_error * FLATNESS);
+
+        // Refit the samples, and use the fitted curves if they're both simpler and within the error bounds
+        if let Some(fitted) = fit_curve::<Curve<P>>(&samples, max_error * FIT_ERROR) {
+            if fitted.len() < curves.len() {
+                // The fitted curves should end exactly where the run does
+                let end_point = curves[curves.len() - 1].end_point();
+                let num_fitted = fitted.len();
+                let fitted = fitted
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, curve)| {
+                        if idx == num_fitted - 1 {
+                            Curve::from_points(
+                                curve.start_point(),
+                                curve.control_points(),
+                                end_point,
+                            )
+                        } else {
+                            curve
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                if is_within_tolerance(&samples, &fitted, max_error) {
+                    for curve in fitted.into_iter() {
+                        let (cp1, cp2) = curve.control_points();
+                        points.push((cp1, cp2, curve.end_point()));
+                    }
+
+                    return;
+                }
+            }
+        }
+
+        // Try again with the two halves of the run if the fit was not good enough
+        let mid_point = curves.len() / 2;
+        simplify_run(&curves[0..mid_point], max_error, points);
+        simplify_run(&curves[mid_point..], max_error, points);
+    } else {
+        // Single curves are left as they are
+        for curve in curves.iter() {
+            let (cp1, cp2) = curve.control_points();
+            points.push((cp1, cp2, curve.end_point()));
+        }
+    }
+}
+
+///
+/// Simplifies a path by replacing runs of smooth curves with fewer curves that fit the same shape
+///
+/// This is useful for paths with large numbers of small curves, such as ones that are drawn by hand. The path is divided
+/// into sections at its corners (where the tangents of two curves point in different directions, and at any curve that
+/// contains a cusp or a loop), and each section is sampled and then refitted using `fit_curve()`.
+///
+/// Every point on the result is within `max_error` of the original path, and every point on the original path is within
+/// `max_error` of the result. Sections that can't be fitted within this distance are divided in half and fitted again,
+/// down to the original curves if necessary.
+///
+/// Closed paths (where the start point and the end point are the same) are treated as a loop: if the start point is not at
+/// a corner but there is a corner elsewhere, the result will start at that corner instead so that the curves on either
+/// side of the start point can be simplified together.
+///
+pub fn path_simplify<POut>(path: &impl BezierPath<Point = POut::Point>, max_error: f64) -> POut
+where
+    POut: BezierPathFactory,
+    POut::Point: Coordinate + Coordinate2D,
+{
+    // Points have no length, so they're removed from the path
+    let mut curves = path
+        .to_curves::<Curve<POut::Point>>()
+        .into_iter()
+        .filter(|curve| characterize_curve(curve) != CurveCategory::Point)
+        .collect::<Vec<_>>();
+
+    let mut start_point = path.start_point();
+
+    // Closed paths also have a join between the last curve and the first one: move the start to a join that ends a run
+    let num_curves = curves.len();
+    if num_curves > 1
+        && curves[0]
+            .start_point()
+            .is_near_to(&curves[num_curves - 1].end_point(), f64::EPSILON)
+    {
+        // Searching from the last curve means that the path is left as it is if the start point already ends a run
+        let first_run_end = (0..num_curves)
+            .map(|idx| (idx + num_curves - 1) % num_curves)
+            .find(|curve_idx| {
+                ends_run(
+                    &curves[*curve_idx],
+                    &curves[(curve_idx + 1) % num_curves],
+                    max_error,
+                )
+            });
+
+        if let Some(first_run_end) = first_run_end {
+            curves.rotate_left((first_run_end + 1) % num_curves);
+            start_point = curves[0].start_point();
+        }
+    }
+
+    let mut points = vec![];
+    let mut run_start = 0;
+
+    for curve_idx in 0..curves.len() {
+        // Runs end at corners and on either side of any curve with a feature (these curves are kept as they are)
+        let is_run_end = match curves.get(curve_idx + 1) {
+            None => true,
+            Some(next_curve) => ends_run(&curves[curve_idx], next_curve, max_error),
+        };
+
+        if is_run_end {
+            simplify_run(&curves[run_start..=curve_idx], max_error, &mut points);
+            run_start = curve_idx + 1;
+        }
+    }
+
+    POut::from_points(start_point, points)
+}
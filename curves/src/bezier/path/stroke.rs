@@ -5,7 +5,10 @@
  */
 
 use super::arithmetic::*;
+use super::graph_path::*;
+use super::is_clockwise::*;
 use super::path::*;
+use super::point::*;
 
 use crate::arc::*;
 use crate::bezier::*;
@@ -281,6 +284,77 @@ where
     }
 }
 
+///
+/// The round join used by `path_offset()`, which joins two edges using an arc around the outside of the corner
+///
+#[inline]
+fn offset_round_join<TCoord>(
+    join_point: TCoord,
+    start_line: (TCoord, TCoord),
+    end_line: (TCoord, TCoord),
+    limit: f64,
+) -> Vec<(TCoord, (TCoord, TCoord), TCoord)>
+where
+    TCoord: Coordinate + Coordinate2D,
+{
+    const VERY_CLOSE: f64 = 1e-5;
+
+    // Must be the outer part of the corner
+    if !start_line.0.is_near_to(&end_line.0, VERY_CLOSE)
+        && start_line.angle_to(&end_line) > f64::consts::PI
+    {
+        // Center of the circle is the point where the two edges originally met
+        let center_point = join_point;
+
+        // Radius of the circle is the distance between the center point and either of the two points
+        let radius = center_point.distance_to(&start_line.0);
+        let start_radius = start_line.0 - center_point;
+        let end_radius = end_line.0 - center_point;
+
+        // The arc goes around the circle in the direction that the line is travelling in at the start point
+        let start_tangent = start_line.0 - start_line.1;
+        let direction =
+            if start_radius.x() * start_tangent.y() - start_radius.y() * start_tangent.x() < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+
+        // Work out the angle covered by the arc in this direction
+        let start_angle = f64::atan2(start_radius.y(), start_radius.x());
+        let end_angle = f64::atan2(end_radius.y(), end_radius.x());
+        let mut sweep = (end_angle - start_angle) * direction;
+        if sweep < 0.0 {
+            sweep += 2.0 * f64::consts::PI;
+        }
+
+        // Generate a bezier curve approximating the arc (control points are along the tangents at either end)
+        let handle_length = 4.0 / 3.0 * (sweep / 4.0).tan() * direction;
+        let start_handle =
+            TCoord::from_components(&[-start_radius.y(), start_radius.x()]) * handle_length;
+        let end_handle =
+            TCoord::from_components(&[-end_radius.y(), end_radius.x()]) * handle_length;
+
+        let arc_curve = Curve::from_points(
+            start_line.0,
+            (start_line.0 + start_handle, end_line.0 - end_handle),
+            end_line.0,
+        );
+        debug_assert!(
+            (center_point.distance_to(&end_line.0) - center_point.distance_to(&start_line.0)).abs()
+                < 0.01,
+            "Radius doesn't match: {} {}",
+            radius,
+            center_point.distance_to(&end_line.0)
+        );
+
+        vec![arc_curve.all_points()]
+    } else {
+        // Bevel join on the inside part of the corner
+        bevel_join(join_point, start_line, end_line, limit)
+    }
+}
+
 ///
 /// Generates the edges for a single curve
 ///
@@ -443,6 +517,215 @@ where
     }
 }
 
+///
+/// Offsets every edge of a closed subpath by `width` (to the side given by the direction of the subpath), joining the
+/// offset curves together using the join function
+///
+/// Returns `None` if the subpath collapses entirely (for example, when the interior of a circle is offset by more than its
+/// radius): the offset subpath is the original mirrored through its center, so it goes around in the same direction and
+/// needs to be removed before the winding rule is applied.
+///
+fn offset_closed_subpath<TCoord>(
+    subpath: &(TCoord, Vec<(TCoord, TCoord, TCoord)>),
+    width: f64,
+    accuracy: f64,
+    subdivision_options: &SubdivisionOffsetOptions,
+    join: &impl Fn(
+        TCoord,
+        (TCoord, TCoord),
+        (TCoord, TCoord),
+        f64,
+    ) -> Vec<(TCoord, (TCoord, TCoord), TCoord)>,
+) -> Option<(TCoord, Vec<(TCoord, TCoord, TCoord)>)>
+where
+    TCoord: Coordinate + Coordinate2D,
+{
+    let mut curves = subpath.to_curves::<Curve<TCoord>>();
+    let start_point = subpath.start_point();
+
+    // Close the subpath if it isn't already closed
+    if let Some(last_point) = curves.last().map(|curve| curve.end_point()) {
+        if last_point != start_point {
+            curves.push(line_to_bezier(&(last_point, start_point)));
+        }
+    }
+
+    // The midpoint of the offset of an edge should be `width` away from the original subpath: if another part of the
+    // subpath is closer than this for every edge, then there's nothing left after the offset
+    let curve_bounds = curves
+        .iter()
+        .map(|curve| curve.fast_bounding_box::<Bounds<TCoord>>())
+        .collect::<Vec<_>>();
+    let is_collapsed = curves.iter().all(|curve| {
+        let offset_point =
+            curve.point_at_pos(0.5) + curve.normal_at_pos(0.5).to_unit_vector() * width;
+
+        // Only curves whose bounding boxes are close enough to the point need to be searched for the nearest point
+        let expand = TCoord::from_components(&[width, width]);
+        let point_bounds = Bounds::from_min_max(offset_point - expand, offset_point + expand);
+
+        curves
+            .iter()
+            .zip(curve_bounds.iter())
+            .filter(|(_, bounds)| bounds.overlaps(&point_bounds))
+            .any(|(other_curve, _)| {
+                other_curve
+                    .nearest_point(&offset_point)
+                    .is_near_to(&offset_point, width - accuracy)
+            })
+    });
+
+    if is_collapsed {
+        return None;
+    }
+
+    // Offset each edge in turn
+    let mut offset_start = None;
+    let mut points = vec![];
+
+    for curve in curves.iter() {
+        stroke_edge(
+            &mut offset_start,
+            &mut points,
+            curve,
+            subdivision_options,
+            width,
+            join,
+        );
+    }
+
+    // Join the last edge back to the first one
+    let (offset_start_point, offset_start_tangent) = offset_start?;
+    let (last_point, last_tangent) = points.last().map(|(_, cp2, ep)| (*ep, *cp2))?;
+
+    for (_, (cp1, cp2), ep) in join(
+        start_point,
+        (last_point, last_tangent),
+        (offset_start_point, offset_start_tangent),
+        width * 4.0,
+    ) {
+        points.push((cp1, cp2, ep));
+    }
+
+    Some((offset_start_point, points))
+}
+
+///
+/// Grows a path by moving its edges outwards by `distance`, or shrinks it if `distance` is negative
+///
+/// The path is a set of non-overlapping subpaths, in the same format as is used by the arithmetic operations (so a subpath that is
+/// inside another subpath is a hole). Each subpath is treated as closed, and the corners are joined using the join style from
+/// the options (the cap styles are not used). This is useful for operations like growing or shrinking a selection or making the
+/// glyphs of a font bolder.
+///
+/// The result is a set of subpaths with no self-intersections. Holes that are filled in as the path grows and parts of the
+/// path that are too thin to survive shrinking are removed from the result.
+///
+pub fn path_offset<TPathFactory, TCoord>(
+    path: &Vec<impl BezierPath<Point = TCoord>>,
+    distance: f64,
+    options: &StrokeOptions,
+) -> Vec<TPathFactory>
+where
+    TPathFactory: BezierPathFactory<Point = TCoord>,
+    TCoord: Coordinate + Coordinate2D,
+{
+    // An offset of 0 leaves the path unchanged
+    if distance == 0.0 {
+        return path
+            .iter()
+            .map(|subpath| TPathFactory::from_path(subpath))
+            .collect();
+    }
+
+    // Edges are always offset to the same side, so the subpaths are reversed where needed to decide which way they move
+    let width = distance.abs();
+    let grow = distance > 0.0;
+    let join_fn = options.join.join_function();
+
+    let subdivision_options = SubdivisionOffsetOptions::default()
+        .with_min_distance(options.min_sample_distance)
+        .with_max_error(options.accuracy)
+        .with_max_distance(width * 20.0);
+
+    // Where two edges overlap on the inside of a corner, they're joined through the original corner point. This makes the overlapping
+    // region wind the same way as the rest of the path, so it's kept when the winding rule is applied below.
+    let join = |join_point: TCoord,
+                start_line: (TCoord, TCoord),
+                end_line: (TCoord, TCoord),
+                limit: f64| {
+        if start_line.0.is_near_to(&end_line.0, options.accuracy)
+            || start_line.angle_to(&end_line) > f64::consts::PI
+        {
+            if options.join == LineJoin::Round {
+                offset_round_join(join_point, start_line, end_line, limit)
+            } else {
+                join_fn(join_point, start_line, end_line, limit)
+            }
+        } else {
+            vec![
+                line_to_bezier::<Curve<_>>(&(start_line.0, join_point)).all_points(),
+                line_to_bezier::<Curve<_>>(&(join_point, end_line.0)).all_points(),
+            ]
+        }
+    };
+
+    // Offset each of the subpaths
+    let mut offset_subpaths = vec![];
+
+    for (subpath_idx, subpath) in path.iter().enumerate() {
+        // Subpaths that are inside an odd number of other subpaths are holes, which move the opposite way to the rest of the path
+        let start_point = subpath.start_point();
+        let num_containing = path
+            .iter()
+            .enumerate()
+            .filter(|(other_idx, other_subpath)| {
+                *other_idx != subpath_idx && path_contains_point(*other_subpath, &start_point)
+            })
+            .count();
+        let is_hole = (num_containing % 2) != 0;
+
+        // Edges are offset outwards from clockwise subpaths
+        let move_outwards = grow != is_hole;
+        let subpath = if subpath.is_clockwise() == move_outwards {
+            <(TCoord, Vec<_>)>::from_path(subpath)
+        } else {
+            subpath.reversed::<(TCoord, Vec<_>)>()
+        };
+
+        if let Some(offset_subpath) = offset_closed_subpath(
+            &subpath,
+            width,
+            options.accuracy,
+            &subdivision_options,
+            &join,
+        ) {
+            offset_subpaths.push(offset_subpath);
+        }
+    }
+
+    // The interior of the path winds the same way as its outer subpaths. Subpaths that are inverted by the offset (such as holes
+    // that are filled in or shapes that are shrunk away entirely) wind the other way, so they're removed along with the
+    // self-intersections at this point.
+    let interior_winding = if grow { -1 } else { 1 };
+
+    let mut merged_path = GraphPath::new();
+    for offset_subpath in offset_subpaths.iter() {
+        merged_path = merged_path.merge(GraphPath::from_path_keeping_direction(
+            offset_subpath,
+            PathLabel(0),
+        ));
+    }
+
+    merged_path.self_collide(options.accuracy);
+    merged_path.round(options.accuracy);
+    merged_path
+        .set_edge_kinds_by_ray_casting(|path_crossings| path_crossings[0] * interior_winding > 0);
+    merged_path.heal_exterior_gaps();
+
+    merged_path.exterior_paths()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -515,4 +798,33 @@ mod test {
             sp
         );
     }
+
+    #[test]
+    fn offset_rounded_join_goes_around_outside_of_corner() {
+        // Corner where the angles of the two normals wrap around
+        let corner = offset_round_join(
+            Coord2(1.0, 1.0),
+            (Coord2(1.0, 0.0), Coord2(2.0, 0.0)),
+            (Coord2(0.0, 1.0), Coord2(0.0, 2.0)),
+            20.0,
+        );
+
+        let (sp, (cp1, cp2), ep) = corner.last().unwrap();
+        let arc = Curve::from_points(*sp, (*cp1, *cp2), *ep);
+        let midpoint = arc.point_at_pos(0.5);
+
+        assert!(sp.is_near_to(&Coord2(1.0, 0.0), 0.01));
+        assert!(ep.is_near_to(&Coord2(0.0, 1.0), 0.01));
+        assert!(
+            midpoint.is_near_to(
+                &Coord2(
+                    1.0 - f64::consts::FRAC_1_SQRT_2,
+                    1.0 - f64::consts::FRAC_1_SQRT_2
+                ),
+                0.01
+            ),
+            "Midpoint is wrong (found {:?})",
+            midpoint
+        );
+    }
 }
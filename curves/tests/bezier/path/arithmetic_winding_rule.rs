@@ -0,0 +1,309 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use flo_curves::arc::*;
+use flo_curves::bezier::path::*;
+use flo_curves::*;
+
+///
+/// True if a point is inside a set of paths using the even-odd winding rule
+///
+fn contains_point(paths: &Vec<SimpleBezierPath>, point: Coord2) -> bool {
+    paths
+        .iter()
+        .filter(|path| path_contains_point(*path, &point))
+        .count()
+        % 2
+        == 1
+}
+
+///
+/// Two overlapping circles going in the same direction
+///
+fn overlapping_circles() -> Vec<SimpleBezierPath> {
+    let circle1 = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let circle2 = Circle::new(Coord2(9.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+
+    vec![circle1, circle2]
+}
+
+///
+/// Two overlapping circles going in opposite directions
+///
+fn opposing_circles() -> Vec<SimpleBezierPath> {
+    let circle1 = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let circle2 = Circle::new(Coord2(9.0, 5.0), 4.0)
+        .to_path::<SimpleBezierPath>()
+        .reversed::<SimpleBezierPath>();
+
+    vec![circle1, circle2]
+}
+
+fn rectangle(min: Coord2, max: Coord2) -> SimpleBezierPath {
+    BezierPathBuilder::<SimpleBezierPath>::start(min)
+        .line_to(Coord2(max.x(), min.y()))
+        .line_to(max)
+        .line_to(Coord2(min.x(), max.y()))
+        .line_to(min)
+        .build()
+}
+
+#[test]
+fn winding_rule_is_inside() {
+    assert!(PathWindingRule::EvenOdd.is_inside(1));
+    assert!(!PathWindingRule::EvenOdd.is_inside(2));
+    assert!(PathWindingRule::EvenOdd.is_inside(-1));
+    assert!(PathWindingRule::NonZero.is_inside(2));
+    assert!(PathWindingRule::NonZero.is_inside(-1));
+    assert!(!PathWindingRule::NonZero.is_inside(0));
+}
+
+#[test]
+fn non_zero_fills_overlap() {
+    let path = path_with_winding_rule::<SimpleBezierPath>(
+        &overlapping_circles(),
+        PathWindingRule::NonZero,
+        0.01,
+    );
+
+    assert!(path.len() == 1);
+    assert!(contains_point(&path, Coord2(2.0, 5.0)));
+    assert!(contains_point(&path, Coord2(7.0, 5.0)));
+    assert!(contains_point(&path, Coord2(12.0, 5.0)));
+}
+
+#[test]
+fn even_odd_leaves_hole_in_overlap() {
+    let path = path_with_winding_rule::<SimpleBezierPath>(
+        &overlapping_circles(),
+        PathWindingRule::EvenOdd,
+        0.01,
+    );
+
+    assert!(contains_point(&path, Coord2(2.0, 5.0)));
+    assert!(!contains_point(&path, Coord2(7.0, 5.0)));
+    assert!(contains_point(&path, Coord2(12.0, 5.0)));
+}
+
+#[test]
+fn non_zero_opposing_directions_leaves_hole() {
+    let path = path_with_winding_rule::<SimpleBezierPath>(
+        &opposing_circles(),
+        PathWindingRule::NonZero,
+        0.01,
+    );
+
+    assert!(contains_point(&path, Coord2(2.0, 5.0)));
+    assert!(!contains_point(&path, Coord2(7.0, 5.0)));
+    assert!(contains_point(&path, Coord2(12.0, 5.0)));
+}
+
+#[test]
+fn intersect_with_winding_rules() {
+    let rect = vec![rectangle(Coord2(6.0, 0.0), Coord2(20.0, 10.0))];
+
+    let non_zero = path_intersect_with_winding_rule::<SimpleBezierPath>(
+        &overlapping_circles(),
+        PathWindingRule::NonZero,
+        &rect,
+        PathWindingRule::EvenOdd,
+        0.01,
+    );
+    let even_odd = path_intersect_with_winding_rule::<SimpleBezierPath>(
+        &overlapping_circles(),
+        PathWindingRule::EvenOdd,
+        &rect,
+        PathWindingRule::EvenOdd,
+        0.01,
+    );
+
+    assert!(contains_point(&non_zero, Coord2(7.0, 5.0)));
+    assert!(contains_point(&non_zero, Coord2(12.0, 5.0)));
+    assert!(!contains_point(&non_zero, Coord2(2.0, 5.0)));
+
+    assert!(!contains_point(&even_odd, Coord2(7.0, 5.0)));
+    assert!(contains_point(&even_odd, Coord2(12.0, 5.0)));
+    assert!(!contains_point(&even_odd, Coord2(2.0, 5.0)));
+}
+
+#[test]
+fn subtract_with_winding_rules() {
+    let rect = vec![rectangle(Coord2(0.0, 0.0), Coord2(14.0, 10.0))];
+
+    let non_zero = path_sub_with_winding_rule::<SimpleBezierPath>(
+        &rect,
+        PathWindingRule::EvenOdd,
+        &overlapping_circles(),
+        PathWindingRule::NonZero,
+        0.01,
+    );
+    let even_odd = path_sub_with_winding_rule::<SimpleBezierPath>(
+        &rect,
+        PathWindingRule::EvenOdd,
+        &overlapping_circles(),
+        PathWindingRule::EvenOdd,
+        0.01,
+    );
+
+    assert!(contains_point(&non_zero, Coord2(0.5, 0.5)));
+    assert!(!contains_point(&non_zero, Coord2(7.0, 5.0)));
+    assert!(!contains_point(&non_zero, Coord2(2.0, 5.0)));
+
+    assert!(contains_point(&even_odd, Coord2(0.5, 0.5)));
+    assert!(contains_point(&even_odd, Coord2(7.0, 5.0)));
+    assert!(!contains_point(&even_odd, Coord2(2.0, 5.0)));
+}
+
+#[test]
+fn add_with_winding_rules() {
+    let rect = vec![rectangle(Coord2(6.0, 4.0), Coord2(20.0, 6.0))];
+
+    let added = path_add_with_winding_rule::<SimpleBezierPath>(
+        &overlapping_circles(),
+        PathWindingRule::NonZero,
+        &rect,
+        PathWindingRule::EvenOdd,
+        0.01,
+    );
+
+    assert!(contains_point(&added, Coord2(2.0, 5.0)));
+    assert!(contains_point(&added, Coord2(7.0, 5.0)));
+    assert!(contains_point(&added, Coord2(7.0, 7.0)));
+    assert!(contains_point(&added, Coord2(18.0, 5.0)));
+    assert!(!contains_point(&added, Coord2(18.0, 8.0)));
+}
+
+#[test]
+fn xor_with_winding_rules() {
+    let rect = vec![rectangle(Coord2(6.0, 4.0), Coord2(20.0, 6.0))];
+
+    let xor = path_xor_with_winding_rule::<SimpleBezierPath>(
+        &overlapping_circles(),
+        PathWindingRule::NonZero,
+        &rect,
+        PathWindingRule::EvenOdd,
+        0.01,
+    );
+
+    assert!(contains_point(&xor, Coord2(2.0, 5.0)));
+    assert!(!contains_point(&xor, Coord2(7.0, 5.0)));
+    assert!(contains_point(&xor, Coord2(7.0, 7.0)));
+    assert!(contains_point(&xor, Coord2(18.0, 5.0)));
+}
+
+#[test]
+fn combine_with_winding_rule() {
+    let rect = vec![rectangle(Coord2(0.0, 0.0), Coord2(14.0, 10.0))];
+
+    let result = path_combine::<SimpleBezierPath>(
+        PathCombine::Subtract(vec![
+            PathCombine::Path(rect),
+            PathCombine::WithWindingRule(PathWindingRule::NonZero, overlapping_circles()),
+        ]),
+        0.01,
+    );
+
+    assert!(contains_point(&result, Coord2(0.5, 0.5)));
+    assert!(!contains_point(&result, Coord2(7.0, 5.0)));
+    assert!(!contains_point(&result, Coord2(2.0, 5.0)));
+}
+
+#[test]
+fn combine_add_with_winding_rule() {
+    let rect = vec![rectangle(Coord2(6.0, 4.0), Coord2(20.0, 6.0))];
+
+    let result = path_combine::<SimpleBezierPath>(
+        PathCombine::AddWithWindingRule(
+            PathWindingRule::NonZero,
+            vec![
+                PathCombine::Path(overlapping_circles()),
+                PathCombine::Path(rect),
+            ],
+        ),
+        0.01,
+    );
+
+    assert!(contains_point(&result, Coord2(2.0, 5.0)));
+    assert!(contains_point(&result, Coord2(7.0, 5.0)));
+    assert!(contains_point(&result, Coord2(7.0, 7.0)));
+    assert!(contains_point(&result, Coord2(18.0, 5.0)));
+    assert!(!contains_point(&result, Coord2(18.0, 8.0)));
+}
+
+#[test]
+fn combine_subtract_with_winding_rule() {
+    let rect = vec![rectangle(Coord2(0.0, 0.0), Coord2(14.0, 10.0))];
+
+    let non_zero = path_combine::<SimpleBezierPath>(
+        PathCombine::SubtractWithWindingRule(
+            PathWindingRule::NonZero,
+            vec![
+                PathCombine::Path(rect.clone()),
+                PathCombine::Path(overlapping_circles()),
+            ],
+        ),
+        0.01,
+    );
+    let even_odd = path_combine::<SimpleBezierPath>(
+        PathCombine::SubtractWithWindingRule(
+            PathWindingRule::EvenOdd,
+            vec![
+                PathCombine::Path(rect),
+                PathCombine::Path(overlapping_circles()),
+            ],
+        ),
+        0.01,
+    );
+
+    assert!(contains_point(&non_zero, Coord2(0.5, 0.5)));
+    assert!(!contains_point(&non_zero, Coord2(7.0, 5.0)));
+    assert!(!contains_point(&non_zero, Coord2(2.0, 5.0)));
+
+    assert!(contains_point(&even_odd, Coord2(0.5, 0.5)));
+    assert!(contains_point(&even_odd, Coord2(7.0, 5.0)));
+    assert!(!contains_point(&even_odd, Coord2(2.0, 5.0)));
+}
+
+#[test]
+fn combine_intersect_with_winding_rule() {
+    let rect = vec![rectangle(Coord2(6.0, 0.0), Coord2(20.0, 10.0))];
+
+    let result = path_combine::<SimpleBezierPath>(
+        PathCombine::IntersectWithWindingRule(
+            PathWindingRule::NonZero,
+            vec![
+                PathCombine::Path(overlapping_circles()),
+                PathCombine::Path(rect),
+            ],
+        ),
+        0.01,
+    );
+
+    assert!(contains_point(&result, Coord2(7.0, 5.0)));
+    assert!(contains_point(&result, Coord2(12.0, 5.0)));
+    assert!(!contains_point(&result, Coord2(2.0, 5.0)));
+}
+
+#[test]
+fn combine_xor_with_winding_rule() {
+    let rect = vec![rectangle(Coord2(6.0, 4.0), Coord2(20.0, 6.0))];
+
+    let result = path_combine::<SimpleBezierPath>(
+        PathCombine::XorWithWindingRule(
+            PathWindingRule::NonZero,
+            vec![
+                PathCombine::Path(overlapping_circles()),
+                PathCombine::Path(rect),
+            ],
+        ),
+        0.01,
+    );
+
+    assert!(contains_point(&result, Coord2(2.0, 5.0)));
+    assert!(!contains_point(&result, Coord2(7.0, 5.0)));
+    assert!(contains_point(&result, Coord2(7.0, 7.0)));
+    assert!(contains_point(&result, Coord2(18.0, 5.0)));
+}
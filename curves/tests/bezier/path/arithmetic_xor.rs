@@ -0,0 +1,130 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use flo_curves::arc::*;
+use flo_curves::bezier::path::*;
+use flo_curves::*;
+
+///
+/// True if a point is inside a set of paths using the even-odd winding rule
+///
+fn contains_point(paths: &Vec<SimpleBezierPath>, point: Coord2) -> bool {
+    paths
+        .iter()
+        .filter(|path| path_contains_point(*path, &point))
+        .count()
+        % 2
+        == 1
+}
+
+#[test]
+fn xor_overlapping_circles() {
+    let circle1 = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let circle2 = Circle::new(Coord2(9.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+
+    let xor = path_xor::<SimpleBezierPath>(&vec![circle1], &vec![circle2], 0.01);
+
+    assert!(xor.len() != 0);
+
+    // Inside one circle only
+    assert!(contains_point(&xor, Coord2(2.0, 5.0)));
+    assert!(contains_point(&xor, Coord2(12.0, 5.0)));
+
+    // Inside both circles
+    assert!(!contains_point(&xor, Coord2(7.0, 5.0)));
+    assert!(!contains_point(&xor, Coord2(7.0, 7.0)));
+
+    // Outside both circles
+    assert!(!contains_point(&xor, Coord2(7.0, 12.0)));
+    assert!(!contains_point(&xor, Coord2(-1.0, 5.0)));
+
+    // All points should be on one of the circles
+    for path in xor.iter() {
+        for (_, _, point) in path.points() {
+            let distance1 = Coord2(5.0, 5.0).distance_to(&point);
+            let distance2 = Coord2(9.0, 5.0).distance_to(&point);
+
+            assert!((distance1 - 4.0).abs() < 0.1 || (distance2 - 4.0).abs() < 0.1);
+        }
+    }
+}
+
+#[test]
+fn xor_non_overlapping_circles() {
+    let circle1 = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let circle2 = Circle::new(Coord2(15.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+
+    let xor = path_xor::<SimpleBezierPath>(&vec![circle1], &vec![circle2], 0.01);
+
+    assert!(xor.len() == 2);
+    assert!(contains_point(&xor, Coord2(5.0, 5.0)));
+    assert!(contains_point(&xor, Coord2(15.0, 5.0)));
+}
+
+#[test]
+fn xor_circle_inside_circle() {
+    // A circle inside another leaves a doughnut
+    let circle1 = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let circle2 = Circle::new(Coord2(5.0, 5.0), 2.0).to_path::<SimpleBezierPath>();
+
+    let xor = path_xor::<SimpleBezierPath>(&vec![circle1], &vec![circle2], 0.01);
+
+    assert!(xor.len() == 2);
+    assert!(contains_point(&xor, Coord2(2.0, 5.0)));
+    assert!(!contains_point(&xor, Coord2(5.0, 5.0)));
+}
+
+#[test]
+fn xor_with_empty_path() {
+    let circle1 = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+
+    let xor = path_xor::<SimpleBezierPath>(&vec![circle1], &Vec::<SimpleBezierPath>::new(), 0.01);
+
+    assert!(xor.len() == 1);
+    assert!(contains_point(&xor, Coord2(5.0, 5.0)));
+}
+
+#[test]
+fn xor_is_symmetrical() {
+    let circle1 = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let circle2 = Circle::new(Coord2(9.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+
+    let xor1 = path_xor::<SimpleBezierPath>(&vec![circle1.clone()], &vec![circle2.clone()], 0.01);
+    let xor2 = path_xor::<SimpleBezierPath>(&vec![circle2], &vec![circle1], 0.01);
+
+    for x in 0..28 {
+        for y in 0..20 {
+            let point = Coord2((x as f64) * 0.5 - 0.25, (y as f64) * 0.5 - 0.25);
+            assert!(contains_point(&xor1, point) == contains_point(&xor2, point));
+        }
+    }
+}
+
+#[test]
+fn combine_xor() {
+    let circle1 = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let circle2 = Circle::new(Coord2(9.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let circle3 = Circle::new(Coord2(7.0, 9.0), 4.0).to_path::<SimpleBezierPath>();
+
+    let xor = path_combine::<SimpleBezierPath>(
+        PathCombine::Xor(vec![
+            PathCombine::Path(vec![circle1]),
+            PathCombine::Path(vec![circle2]),
+            PathCombine::Path(vec![circle3]),
+        ]),
+        0.01,
+    );
+
+    // Inside one circle
+    assert!(contains_point(&xor, Coord2(2.0, 4.0)));
+    assert!(contains_point(&xor, Coord2(7.0, 12.0)));
+
+    // Inside two circles
+    assert!(!contains_point(&xor, Coord2(7.0, 3.0)));
+
+    // Inside all three circles
+    assert!(contains_point(&xor, Coord2(7.0, 6.0)));
+}
@@ -0,0 +1,242 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use flo_curves::arc::*;
+use flo_curves::bezier::path::*;
+use flo_curves::*;
+
+fn rectangle() -> SimpleBezierPath {
+    BezierPathBuilder::<SimpleBezierPath>::start(Coord2(0.0, 0.0))
+        .line_to(Coord2(10.0, 0.0))
+        .line_to(Coord2(10.0, 10.0))
+        .line_to(Coord2(0.0, 10.0))
+        .line_to(Coord2(0.0, 0.0))
+        .build()
+}
+
+fn path_length(path: &SimpleBezierPath) -> f64 {
+    PathMeasure::new(path, 0.001).total_length()
+}
+
+#[test]
+fn rectangle_length() {
+    let measure = PathMeasure::new(&rectangle(), 0.001);
+
+    assert!((measure.total_length() - 40.0).abs() < 0.01);
+    assert!(measure.curve_lengths().len() == 4);
+    assert!(measure
+        .curve_lengths()
+        .iter()
+        .all(|length| (length - 10.0).abs() < 0.01));
+}
+
+#[test]
+fn circle_length() {
+    let circle = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let measure = PathMeasure::new(&circle, 0.001);
+
+    assert!((measure.total_length() - 8.0 * std::f64::consts::PI).abs() < 0.05);
+}
+
+#[test]
+fn point_at_crosses_curve_boundaries() {
+    let measure = PathMeasure::new(&rectangle(), 0.001);
+
+    assert!(measure.point_at(0.0).is_near_to(&Coord2(0.0, 0.0), 0.01));
+    assert!(measure.point_at(5.0).is_near_to(&Coord2(5.0, 0.0), 0.01));
+    assert!(measure.point_at(10.0).is_near_to(&Coord2(10.0, 0.0), 0.01));
+    assert!(measure.point_at(15.0).is_near_to(&Coord2(10.0, 5.0), 0.01));
+    assert!(measure.point_at(22.5).is_near_to(&Coord2(7.5, 10.0), 0.01));
+    assert!(measure.point_at(37.0).is_near_to(&Coord2(0.0, 3.0), 0.01));
+}
+
+#[test]
+fn point_at_on_curve() {
+    let circle = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let measure = PathMeasure::new(&circle, 0.001);
+
+    for step in 0..=20 {
+        let distance = measure.total_length() * (step as f64) / 20.0;
+        let point = measure.point_at(distance);
+
+        assert!((point.distance_to(&Coord2(5.0, 5.0)) - 4.0).abs() < 0.01);
+    }
+}
+
+#[test]
+fn distances_are_clamped() {
+    let measure = PathMeasure::new(&rectangle(), 0.001);
+
+    assert!(measure.point_at(-10.0).is_near_to(&Coord2(0.0, 0.0), 0.01));
+    assert!(measure.point_at(100.0).is_near_to(&Coord2(0.0, 0.0), 0.01));
+    assert!(measure.point_at(100.0) == measure.point_at(measure.total_length()));
+}
+
+#[test]
+fn tangent_at_follows_edges() {
+    let measure = PathMeasure::new(&rectangle(), 0.001);
+
+    assert!(measure.tangent_at(5.0).is_near_to(&Coord2(1.0, 0.0), 0.001));
+    assert!(measure
+        .tangent_at(15.0)
+        .is_near_to(&Coord2(0.0, 1.0), 0.001));
+    assert!(measure
+        .tangent_at(25.0)
+        .is_near_to(&Coord2(-1.0, 0.0), 0.001));
+    assert!(measure
+        .tangent_at(35.0)
+        .is_near_to(&Coord2(0.0, -1.0), 0.001));
+}
+
+#[test]
+fn sub_path_within_one_curve() {
+    let measure = PathMeasure::new(&rectangle(), 0.001);
+    let sub_path = measure.sub_path::<SimpleBezierPath>(12.0, 18.0);
+
+    assert!(sub_path.1.len() == 1);
+    assert!(sub_path.start_point().is_near_to(&Coord2(10.0, 2.0), 0.01));
+    assert!(sub_path.1[0].2.is_near_to(&Coord2(10.0, 8.0), 0.01));
+    assert!((path_length(&sub_path) - 6.0).abs() < 0.01);
+}
+
+#[test]
+fn sub_path_across_several_curves() {
+    let measure = PathMeasure::new(&rectangle(), 0.001);
+    let sub_path = measure.sub_path::<SimpleBezierPath>(5.0, 35.0);
+
+    assert!(sub_path.1.len() == 4);
+    assert!(sub_path.start_point().is_near_to(&Coord2(5.0, 0.0), 0.01));
+    assert!(sub_path.1[0].2.is_near_to(&Coord2(10.0, 0.0), 0.01));
+    assert!(sub_path.1[1].2.is_near_to(&Coord2(10.0, 10.0), 0.01));
+    assert!(sub_path.1[2].2.is_near_to(&Coord2(0.0, 10.0), 0.01));
+    assert!(sub_path.1[3].2.is_near_to(&Coord2(0.0, 5.0), 0.01));
+    assert!((path_length(&sub_path) - 30.0).abs() < 0.01);
+}
+
+#[test]
+fn sub_path_of_circle() {
+    let circle = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let measure = PathMeasure::new(&circle, 0.001);
+    let half_length = measure.total_length() / 2.0;
+    let sub_path = measure.sub_path::<SimpleBezierPath>(half_length / 2.0, half_length * 1.5);
+
+    assert!((path_length(&sub_path) - half_length).abs() < 0.05);
+    assert!(sub_path
+        .start_point()
+        .is_near_to(&measure.point_at(half_length / 2.0), 0.01));
+}
+
+#[test]
+fn reversed_sub_path_is_empty() {
+    let measure = PathMeasure::new(&rectangle(), 0.001);
+    let sub_path = measure.sub_path::<SimpleBezierPath>(15.0, 5.0);
+
+    assert!(sub_path.1.is_empty());
+    assert!(sub_path.start_point().is_near_to(&Coord2(10.0, 5.0), 0.01));
+}
+
+#[test]
+fn walk_rectangle_evenly() {
+    let measure = PathMeasure::new(&rectangle(), 0.001);
+    let points = measure.walk_evenly(3.0).collect::<Vec<_>>();
+
+    // 0, 3, 6, ... 39
+    assert!(points.len() == 14);
+
+    for (idx, (point, _tangent)) in points.iter().enumerate() {
+        assert!(point.is_near_to(&measure.point_at((idx as f64) * 3.0), 0.001));
+    }
+
+    // The points that are on the same edge should be evenly spaced
+    assert!((points[0].0.distance_to(&points[1].0) - 3.0).abs() < 0.01);
+    assert!((points[4].0.distance_to(&points[5].0) - 3.0).abs() < 0.01);
+    assert!(points[4].1.is_near_to(&Coord2(0.0, 1.0), 0.001));
+}
+
+#[test]
+fn walk_includes_end_point() {
+    let measure = PathMeasure::new(&rectangle(), 0.001);
+    let points = measure.walk_evenly(5.0).collect::<Vec<_>>();
+
+    assert!(points.len() == 9);
+    assert!(points[0].0.is_near_to(&Coord2(0.0, 0.0), 0.01));
+    assert!(points[2].0.is_near_to(&Coord2(10.0, 0.0), 0.01));
+    assert!(points[8].0.is_near_to(&Coord2(0.0, 0.0), 0.01));
+}
+
+#[test]
+fn walk_circle_evenly() {
+    let circle = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let measure = PathMeasure::new(&circle, 0.001);
+    let points = measure.walk_evenly(1.0).collect::<Vec<_>>();
+
+    assert!(points.len() == (measure.total_length().floor() as usize) + 1);
+
+    // Chords of a circle are slightly shorter than the arcs
+    for (prev, next) in points.iter().zip(points.iter().skip(1)) {
+        let distance = prev.0.distance_to(&next.0);
+        assert!(distance < 1.0 && distance > 0.99);
+    }
+}
+
+#[test]
+fn empty_path() {
+    let path = (Coord2(1.0, 2.0), vec![]);
+    let measure = PathMeasure::<Coord2>::new(&path, 0.001);
+
+    assert!(measure.total_length() == 0.0);
+    assert!(measure.point_at(10.0) == Coord2(1.0, 2.0));
+    assert!(measure.tangent_at(10.0) == Coord2(0.0, 0.0));
+    assert!(measure.walk_evenly(1.0).count() == 0);
+
+    let sub_path = measure.sub_path::<SimpleBezierPath>(0.0, 10.0);
+    assert!(sub_path.1.is_empty());
+    assert!(sub_path.start_point() == Coord2(1.0, 2.0));
+}
+
+#[test]
+fn tangent_at_degenerate_control_point() {
+    // The first control point is on top of the start point, so the curve has no tangent at t=0
+    let path = (
+        Coord2(0.0, 0.0),
+        vec![(Coord2(0.0, 0.0), Coord2(10.0, 0.0), Coord2(10.0, 0.0))],
+    );
+    let measure = PathMeasure::new(&path, 0.001);
+
+    let start_tangent = measure.tangent_at(0.0);
+    let end_tangent = measure.tangent_at(measure.total_length());
+
+    assert!(
+        start_tangent.is_near_to(&Coord2(1.0, 0.0), 0.001),
+        "{:?}",
+        start_tangent
+    );
+    assert!(
+        end_tangent.is_near_to(&Coord2(1.0, 0.0), 0.001),
+        "{:?}",
+        end_tangent
+    );
+
+    let walked = measure.walk_evenly(5.0).collect::<Vec<_>>();
+    assert!(walked.len() == 3);
+    assert!(walked
+        .iter()
+        .all(|(_, tangent)| tangent.is_near_to(&Coord2(1.0, 0.0), 0.001)));
+}
+
+#[test]
+fn walk_matches_point_at() {
+    // Walking carries on from the previous step, which should find the same points as searching from the start of the path
+    let circle = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let measure = PathMeasure::new(&circle, 0.001);
+
+    for (idx, (point, tangent)) in measure.walk_evenly(0.7).enumerate() {
+        let distance = (idx as f64) * 0.7;
+
+        assert!(point.is_near_to(&measure.point_at(distance), 0.01));
+        assert!(tangent.is_near_to(&measure.tangent_at(distance), 0.01));
+    }
+}
@@ -10,15 +10,21 @@ mod arithmetic_complicated_paths;
 mod arithmetic_cut;
 mod arithmetic_intersect;
 mod arithmetic_sub;
+mod arithmetic_winding_rule;
+mod arithmetic_xor;
 mod bounds;
 mod checks;
 mod graph_path;
 mod intersection;
 mod is_clockwise;
+mod measure;
+mod morph;
 mod path;
+mod path_offset;
 mod permute;
 mod point;
 mod rays;
+mod simplify;
 mod stroke_tests;
 mod svg;
 mod to_curves;
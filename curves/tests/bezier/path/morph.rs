@@ -0,0 +1,264 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use flo_curves::arc::*;
+use flo_curves::bezier::path::*;
+use flo_curves::bezier::*;
+use flo_curves::*;
+
+fn rectangle(min: Coord2, max: Coord2) -> SimpleBezierPath {
+    BezierPathBuilder::<SimpleBezierPath>::start(min)
+        .line_to(Coord2(max.x(), min.y()))
+        .line_to(max)
+        .line_to(Coord2(min.x(), max.y()))
+        .line_to(min)
+        .build()
+}
+
+fn polygon(center: Coord2, radius: f64, num_sides: usize) -> SimpleBezierPath {
+    let point = |idx: usize| {
+        let angle = (idx as f64) / (num_sides as f64) * 2.0 * std::f64::consts::PI;
+        Coord2(
+            center.x() + angle.cos() * radius,
+            center.y() + angle.sin() * radius,
+        )
+    };
+
+    let mut builder = BezierPathBuilder::<SimpleBezierPath>::start(point(0));
+    for idx in 1..=num_sides {
+        builder = builder.line_to(point(idx));
+    }
+
+    builder.build()
+}
+
+///
+/// Checks that every point on a path is (roughly) the specified distance from the center
+///
+fn assert_radius(path: &SimpleBezierPath, center: Coord2, radius: f64) {
+    for curve in path.to_curves::<Curve<Coord2>>() {
+        for t in 0..=10 {
+            let point = curve.point_at_pos((t as f64) / 10.0);
+            let distance = point.distance_to(&center);
+
+            assert!(
+                (distance - radius).abs() < 0.05,
+                "Expected radius {}, found {} at {:?}",
+                radius,
+                distance,
+                point
+            );
+        }
+    }
+}
+
+///
+/// Finds the center of the bounding box of a path
+///
+fn center(path: &SimpleBezierPath) -> Coord2 {
+    let (min, max): (Coord2, Coord2) = path.bounding_box();
+    (min + max) * 0.5
+}
+
+#[test]
+fn morph_has_same_shape_at_start_and_end() {
+    let square = rectangle(Coord2(0.0, 0.0), Coord2(10.0, 10.0));
+    let circle = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let morph = PathMorph::new(&vec![square.clone()], &vec![circle]);
+
+    let start = morph.path_at::<SimpleBezierPath>(0.0);
+    let end = morph.path_at::<SimpleBezierPath>(1.0);
+
+    assert!(start.len() == 1);
+    assert!(end.len() == 1);
+    assert!(start[0].1.len() == end[0].1.len());
+
+    // The start is the square (every point is on one of its edges)
+    for curve in start[0].to_curves::<Curve<Coord2>>() {
+        for t in 0..=10 {
+            let point = curve.point_at_pos((t as f64) / 10.0);
+            let on_edge = point.x().abs() < 0.001
+                || (point.x() - 10.0).abs() < 0.001
+                || point.y().abs() < 0.001
+                || (point.y() - 10.0).abs() < 0.001;
+
+            assert!(on_edge, "{:?} is not on the square", point);
+        }
+    }
+
+    // The end is the circle
+    assert_radius(&end[0], Coord2(5.0, 5.0), 4.0);
+}
+
+#[test]
+fn morph_between_different_numbers_of_curves() {
+    let triangle = polygon(Coord2(0.0, 0.0), 10.0, 3);
+    let pentagon = polygon(Coord2(0.0, 0.0), 10.0, 5);
+    let morph = PathMorph::new(&vec![triangle], &vec![pentagon]);
+
+    for t in 0..=10 {
+        let path = morph.path_at::<SimpleBezierPath>((t as f64) / 10.0);
+
+        assert!(path.len() == 1);
+        assert!(path[0].1.len() == 5);
+    }
+
+    // Every corner of the pentagon should be present at the end
+    let end = morph.path_at::<SimpleBezierPath>(1.0);
+    for idx in 0..5 {
+        let corner = polygon(Coord2(0.0, 0.0), 10.0, 5).1[idx].2;
+        assert!(end[0]
+            .1
+            .iter()
+            .any(|(_, _, point)| point.is_near_to(&corner, 0.001)));
+    }
+}
+
+#[test]
+fn morph_circle_to_itself_with_different_start_point() {
+    // The morph should rotate the start point so the circle doesn't collapse in the middle
+    let circle = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let mut rotated_curves = circle.1.clone();
+    rotated_curves.rotate_left(2);
+    let rotated_circle = (circle.1[1].2, rotated_curves);
+
+    let morph = PathMorph::new(&vec![circle], &vec![rotated_circle]);
+
+    assert_radius(
+        &morph.path_at::<SimpleBezierPath>(0.5)[0],
+        Coord2(5.0, 5.0),
+        4.0,
+    );
+}
+
+#[test]
+fn morph_circle_to_reversed_circle() {
+    let circle = Circle::new(Coord2(5.0, 5.0), 4.0).to_path::<SimpleBezierPath>();
+    let reversed_circle = circle.reversed::<SimpleBezierPath>();
+
+    let morph = PathMorph::new(&vec![circle], &vec![reversed_circle]);
+
+    for t in 0..=4 {
+        assert_radius(
+            &morph.path_at::<SimpleBezierPath>((t as f64) / 4.0)[0],
+            Coord2(5.0, 5.0),
+            4.0,
+        );
+    }
+}
+
+#[test]
+fn match_subpaths_by_position() {
+    let from = vec![
+        Circle::new(Coord2(0.0, 0.0), 5.0).to_path::<SimpleBezierPath>(),
+        Circle::new(Coord2(100.0, 0.0), 5.0).to_path::<SimpleBezierPath>(),
+    ];
+    let to = vec![
+        Circle::new(Coord2(102.0, 0.0), 6.0).to_path::<SimpleBezierPath>(),
+        Circle::new(Coord2(2.0, 0.0), 4.0).to_path::<SimpleBezierPath>(),
+    ];
+
+    let morph = PathMorph::new(&from, &to);
+    let halfway = morph.path_at::<SimpleBezierPath>(0.5);
+
+    assert!(halfway.len() == 2);
+    assert!(center(&halfway[0]).is_near_to(&Coord2(1.0, 0.0), 0.01));
+    assert!(center(&halfway[1]).is_near_to(&Coord2(101.0, 0.0), 0.01));
+    assert_radius(&halfway[0], Coord2(1.0, 0.0), 4.5);
+    assert_radius(&halfway[1], Coord2(101.0, 0.0), 5.5);
+}
+
+#[test]
+fn match_subpaths_by_area() {
+    // Two subpaths with the same center: the large one should turn into the large one
+    let from = vec![
+        Circle::new(Coord2(0.0, 0.0), 10.0).to_path::<SimpleBezierPath>(),
+        Circle::new(Coord2(0.0, 0.0), 2.0).to_path::<SimpleBezierPath>(),
+    ];
+    let to = vec![
+        Circle::new(Coord2(0.0, 0.0), 3.0).to_path::<SimpleBezierPath>(),
+        Circle::new(Coord2(0.0, 0.0), 9.0).to_path::<SimpleBezierPath>(),
+    ];
+
+    let morph = PathMorph::new(&from, &to);
+    let end = morph.path_at::<SimpleBezierPath>(1.0);
+
+    assert_radius(&end[0], Coord2(0.0, 0.0), 9.0);
+    assert_radius(&end[1], Coord2(0.0, 0.0), 3.0);
+}
+
+#[test]
+fn extra_subpaths_grow_from_their_center() {
+    let from = vec![Circle::new(Coord2(0.0, 0.0), 5.0).to_path::<SimpleBezierPath>()];
+    let to = vec![
+        Circle::new(Coord2(0.0, 0.0), 5.0).to_path::<SimpleBezierPath>(),
+        rectangle(Coord2(50.0, 50.0), Coord2(60.0, 60.0)),
+    ];
+
+    let morph = path_morph::<SimpleBezierPath>(&from, &to);
+
+    let start = morph(0.0);
+    assert!(start.len() == 2);
+    assert!(start[1].start_point() == Coord2(55.0, 55.0));
+    assert!(start[1]
+        .1
+        .iter()
+        .all(|(cp1, cp2, end)| *cp1 == Coord2(55.0, 55.0)
+            && *cp2 == Coord2(55.0, 55.0)
+            && *end == Coord2(55.0, 55.0)));
+
+    let halfway = morph(0.5);
+    let (min, max): (Coord2, Coord2) = halfway[1].bounding_box();
+    assert!(min.is_near_to(&Coord2(52.5, 52.5), 0.01), "{:?}", min);
+    assert!(max.is_near_to(&Coord2(57.5, 57.5), 0.01), "{:?}", max);
+}
+
+#[test]
+fn missing_subpaths_shrink_into_their_center() {
+    let from = vec![
+        Circle::new(Coord2(0.0, 0.0), 5.0).to_path::<SimpleBezierPath>(),
+        rectangle(Coord2(50.0, 50.0), Coord2(60.0, 60.0)),
+    ];
+    let to = vec![Circle::new(Coord2(0.0, 0.0), 5.0).to_path::<SimpleBezierPath>()];
+
+    let morph = path_morph::<SimpleBezierPath>(&from, &to);
+    let end = morph(1.0);
+
+    assert!(end.len() == 2);
+    assert_radius(&end[0], Coord2(0.0, 0.0), 5.0);
+    assert!(end[1]
+        .1
+        .iter()
+        .all(|(_, _, end)| *end == Coord2(55.0, 55.0)));
+}
+
+#[test]
+fn morph_open_paths_in_closest_direction() {
+    let from = vec![
+        BezierPathBuilder::<SimpleBezierPath>::start(Coord2(0.0, 0.0))
+            .line_to(Coord2(10.0, 0.0))
+            .build(),
+    ];
+    let to = vec![
+        BezierPathBuilder::<SimpleBezierPath>::start(Coord2(10.0, 1.0))
+            .line_to(Coord2(5.0, 1.0))
+            .line_to(Coord2(0.0, 1.0))
+            .build(),
+    ];
+
+    let morph = PathMorph::new(&from, &to);
+    let halfway = morph.path_at::<SimpleBezierPath>(0.5);
+
+    assert!(halfway[0]
+        .start_point()
+        .is_near_to(&Coord2(0.0, 0.5), 0.001));
+    assert!(halfway[0]
+        .1
+        .last()
+        .unwrap()
+        .2
+        .is_near_to(&Coord2(10.0, 0.5), 0.001));
+}
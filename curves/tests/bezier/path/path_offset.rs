@@ -0,0 +1,350 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use flo_curves::arc::*;
+use flo_curves::bezier::path::*;
+use flo_curves::bezier::*;
+use flo_curves::*;
+
+///
+/// True if a point is inside a set of paths using the even-odd winding rule
+///
+fn contains_point(paths: &Vec<SimpleBezierPath>, point: Coord2) -> bool {
+    paths
+        .iter()
+        .filter(|path| path_contains_point(*path, &point))
+        .count()
+        % 2
+        == 1
+}
+
+fn rectangle(min: Coord2, max: Coord2) -> SimpleBezierPath {
+    BezierPathBuilder::<SimpleBezierPath>::start(min)
+        .line_to(Coord2(max.x(), min.y()))
+        .line_to(max)
+        .line_to(Coord2(min.x(), max.y()))
+        .line_to(min)
+        .build()
+}
+
+///
+/// An 'L' shape, with a concave corner at 4, 4
+///
+fn l_shape() -> SimpleBezierPath {
+    BezierPathBuilder::<SimpleBezierPath>::start(Coord2(0.0, 0.0))
+        .line_to(Coord2(8.0, 0.0))
+        .line_to(Coord2(8.0, 4.0))
+        .line_to(Coord2(4.0, 4.0))
+        .line_to(Coord2(4.0, 8.0))
+        .line_to(Coord2(0.0, 8.0))
+        .line_to(Coord2(0.0, 0.0))
+        .build()
+}
+
+///
+/// A circle with a circular hole in the middle
+///
+fn ring(outer_radius: f64, inner_radius: f64) -> Vec<SimpleBezierPath> {
+    vec![
+        Circle::new(Coord2(10.0, 10.0), outer_radius).to_path::<SimpleBezierPath>(),
+        Circle::new(Coord2(10.0, 10.0), inner_radius).to_path::<SimpleBezierPath>(),
+    ]
+}
+
+///
+/// Checks that every point on a path is (roughly) the specified distance from the center
+///
+fn assert_radius(path: &SimpleBezierPath, center: Coord2, radius: f64) {
+    for curve in path.to_curves::<Curve<Coord2>>() {
+        for t in 0..=10 {
+            let point = curve.point_at_pos((t as f64) / 10.0);
+            let distance = point.distance_to(&center);
+
+            assert!(
+                (distance - radius).abs() < 0.1,
+                "Expected radius {}, found {} at {:?}",
+                radius,
+                distance,
+                point
+            );
+        }
+    }
+}
+
+#[test]
+fn grow_circle() {
+    let circle = Circle::new(Coord2(10.0, 10.0), 4.0).to_path::<SimpleBezierPath>();
+    let grown = path_offset::<SimpleBezierPath, _>(&vec![circle], 1.0, &StrokeOptions::default());
+
+    assert!(grown.len() == 1);
+    assert_radius(&grown[0], Coord2(10.0, 10.0), 5.0);
+}
+
+#[test]
+fn shrink_circle() {
+    let circle = Circle::new(Coord2(10.0, 10.0), 4.0).to_path::<SimpleBezierPath>();
+    let shrunk = path_offset::<SimpleBezierPath, _>(&vec![circle], -1.0, &StrokeOptions::default());
+
+    assert!(shrunk.len() == 1);
+    assert_radius(&shrunk[0], Coord2(10.0, 10.0), 3.0);
+}
+
+#[test]
+fn grow_anticlockwise_circle() {
+    let circle = Circle::new(Coord2(10.0, 10.0), 4.0)
+        .to_path::<SimpleBezierPath>()
+        .reversed::<SimpleBezierPath>();
+    let grown = path_offset::<SimpleBezierPath, _>(&vec![circle], 1.0, &StrokeOptions::default());
+
+    assert!(grown.len() == 1);
+    assert_radius(&grown[0], Coord2(10.0, 10.0), 5.0);
+}
+
+#[test]
+fn zero_offset_leaves_path_unchanged() {
+    let square = rectangle(Coord2(1.0, 1.0), Coord2(5.0, 5.0));
+    let offset =
+        path_offset::<SimpleBezierPath, _>(&vec![square.clone()], 0.0, &StrokeOptions::default());
+
+    assert!(offset == vec![square]);
+}
+
+#[test]
+fn grow_square_with_miter_join() {
+    let square = rectangle(Coord2(1.0, 1.0), Coord2(5.0, 5.0));
+    let options = StrokeOptions::default().with_join(LineJoin::Miter);
+    let grown = path_offset::<SimpleBezierPath, _>(&vec![square], 1.0, &options);
+
+    assert!(grown.len() == 1);
+
+    let (min, max): (Coord2, Coord2) = grown[0].bounding_box();
+    assert!(min.is_near_to(&Coord2(0.0, 0.0), 0.01), "{:?}", min);
+    assert!(max.is_near_to(&Coord2(6.0, 6.0), 0.01), "{:?}", max);
+
+    // Miter joins keep the corners square
+    assert!(contains_point(&grown, Coord2(0.1, 0.1)));
+    assert!(contains_point(&grown, Coord2(5.9, 5.9)));
+}
+
+#[test]
+fn grow_square_with_round_join() {
+    let square = rectangle(Coord2(1.0, 1.0), Coord2(5.0, 5.0));
+    let options = StrokeOptions::default().with_join(LineJoin::Round);
+    let grown = path_offset::<SimpleBezierPath, _>(&vec![square], 1.0, &options);
+
+    assert!(grown.len() == 1);
+
+    let (min, max): (Coord2, Coord2) = grown[0].bounding_box();
+    assert!(min.is_near_to(&Coord2(0.0, 0.0), 0.01), "{:?}", min);
+    assert!(max.is_near_to(&Coord2(6.0, 6.0), 0.01), "{:?}", max);
+
+    // Corners are rounded off
+    assert!(!contains_point(&grown, Coord2(0.1, 0.1)));
+    assert!(contains_point(&grown, Coord2(0.4, 0.4)));
+    assert!(!contains_point(&grown, Coord2(5.9, 5.9)));
+}
+
+#[test]
+fn grow_square_with_bevel_join() {
+    let square = rectangle(Coord2(1.0, 1.0), Coord2(5.0, 5.0));
+    let options = StrokeOptions::default().with_join(LineJoin::Bevel);
+    let grown = path_offset::<SimpleBezierPath, _>(&vec![square], 1.0, &options);
+
+    assert!(grown.len() == 1);
+
+    // Corners are cut off
+    assert!(!contains_point(&grown, Coord2(0.2, 0.2)));
+    assert!(contains_point(&grown, Coord2(0.6, 0.6)));
+    assert!(contains_point(&grown, Coord2(0.1, 3.0)));
+}
+
+#[test]
+fn shrink_square() {
+    let square = rectangle(Coord2(1.0, 1.0), Coord2(5.0, 5.0));
+    let options = StrokeOptions::default().with_join(LineJoin::Round);
+    let shrunk = path_offset::<SimpleBezierPath, _>(&vec![square], -1.0, &options);
+
+    // Shrinking only produces inside corners, so the result is square whatever the join style
+    assert!(shrunk.len() == 1);
+
+    let (min, max): (Coord2, Coord2) = shrunk[0].bounding_box();
+    assert!(min.is_near_to(&Coord2(2.0, 2.0), 0.01), "{:?}", min);
+    assert!(max.is_near_to(&Coord2(4.0, 4.0), 0.01), "{:?}", max);
+    assert!(contains_point(&shrunk, Coord2(2.1, 2.1)));
+    assert!(contains_point(&shrunk, Coord2(3.9, 3.9)));
+}
+
+#[test]
+fn grow_l_shape() {
+    let options = StrokeOptions::default().with_join(LineJoin::Round);
+    let grown = path_offset::<SimpleBezierPath, _>(&vec![l_shape()], 1.0, &options);
+
+    assert!(grown.len() == 1);
+
+    // The concave corner should be filled in, with no holes left behind
+    assert!(contains_point(&grown, Coord2(4.5, 4.5)));
+    assert!(contains_point(&grown, Coord2(3.9, 4.1)));
+    assert!(contains_point(&grown, Coord2(4.9, 4.9)));
+    assert!(!contains_point(&grown, Coord2(5.1, 5.1)));
+}
+
+#[test]
+fn shrink_l_shape() {
+    let options = StrokeOptions::default().with_join(LineJoin::Round);
+    let shrunk = path_offset::<SimpleBezierPath, _>(&vec![l_shape()], -1.0, &options);
+
+    assert!(shrunk.len() == 1);
+
+    // The concave corner becomes rounded
+    assert!(contains_point(&shrunk, Coord2(1.5, 1.5)));
+    assert!(contains_point(&shrunk, Coord2(2.9, 2.9)));
+    assert!(contains_point(&shrunk, Coord2(3.1, 3.1)));
+    assert!(!contains_point(&shrunk, Coord2(3.4, 3.4)));
+    assert!(!contains_point(&shrunk, Coord2(0.5, 0.5)));
+    assert!(!contains_point(&shrunk, Coord2(7.5, 2.0)));
+}
+
+#[test]
+fn grow_ring_shrinks_hole() {
+    let grown = path_offset::<SimpleBezierPath, _>(&ring(5.0, 2.0), 1.0, &StrokeOptions::default());
+
+    assert!(grown.len() == 2);
+    assert!(contains_point(&grown, Coord2(10.0, 15.5)));
+    assert!(contains_point(&grown, Coord2(10.0, 11.5)));
+    assert!(!contains_point(&grown, Coord2(10.0, 10.0)));
+}
+
+#[test]
+fn grow_ring_fills_hole() {
+    let grown = path_offset::<SimpleBezierPath, _>(&ring(5.0, 2.0), 3.0, &StrokeOptions::default());
+
+    assert!(grown.len() == 1);
+    assert_radius(&grown[0], Coord2(10.0, 10.0), 8.0);
+    assert!(contains_point(&grown, Coord2(10.0, 10.0)));
+}
+
+#[test]
+fn shrink_ring_grows_hole() {
+    let shrunk =
+        path_offset::<SimpleBezierPath, _>(&ring(5.0, 2.0), -1.0, &StrokeOptions::default());
+
+    assert!(shrunk.len() == 2);
+    assert!(contains_point(&shrunk, Coord2(10.0, 13.5)));
+    assert!(!contains_point(&shrunk, Coord2(10.0, 12.5)));
+    assert!(!contains_point(&shrunk, Coord2(10.0, 14.5)));
+}
+
+#[test]
+fn shrink_ring_away() {
+    let shrunk =
+        path_offset::<SimpleBezierPath, _>(&ring(5.0, 2.0), -2.0, &StrokeOptions::default());
+
+    assert!(shrunk.len() == 0);
+}
+
+#[test]
+fn shrink_circle_away() {
+    let circle = Circle::new(Coord2(10.0, 10.0), 1.0).to_path::<SimpleBezierPath>();
+    let shrunk = path_offset::<SimpleBezierPath, _>(&vec![circle], -2.0, &StrokeOptions::default());
+
+    assert!(shrunk.len() == 0);
+}
+
+#[test]
+fn grow_separate_squares_together() {
+    let squares = vec![
+        rectangle(Coord2(1.0, 1.0), Coord2(5.0, 5.0)),
+        rectangle(Coord2(6.0, 1.0), Coord2(10.0, 5.0)),
+    ];
+    let options = StrokeOptions::default().with_join(LineJoin::Miter);
+    let grown = path_offset::<SimpleBezierPath, _>(&squares, 1.0, &options);
+
+    assert!(grown.len() == 1);
+    assert!(contains_point(&grown, Coord2(5.5, 3.0)));
+}
+
+#[test]
+fn shrink_splits_dumbbell() {
+    // Two squares joined by a thin bar
+    let dumbbell = BezierPathBuilder::<SimpleBezierPath>::start(Coord2(0.0, 0.0))
+        .line_to(Coord2(4.0, 0.0))
+        .line_to(Coord2(4.0, 1.5))
+        .line_to(Coord2(8.0, 1.5))
+        .line_to(Coord2(8.0, 0.0))
+        .line_to(Coord2(12.0, 0.0))
+        .line_to(Coord2(12.0, 4.0))
+        .line_to(Coord2(8.0, 4.0))
+        .line_to(Coord2(8.0, 2.5))
+        .line_to(Coord2(4.0, 2.5))
+        .line_to(Coord2(4.0, 4.0))
+        .line_to(Coord2(0.0, 4.0))
+        .line_to(Coord2(0.0, 0.0))
+        .build();
+    let options = StrokeOptions::default().with_join(LineJoin::Miter);
+    let shrunk = path_offset::<SimpleBezierPath, _>(&vec![dumbbell], -1.0, &options);
+
+    assert!(shrunk.len() == 2);
+    assert!(contains_point(&shrunk, Coord2(2.0, 2.0)));
+    assert!(contains_point(&shrunk, Coord2(10.0, 2.0)));
+    assert!(!contains_point(&shrunk, Coord2(6.0, 2.0)));
+}
+
+#[test]
+fn grow_square_fills_square_hole() {
+    let frame = vec![
+        rectangle(Coord2(0.0, 0.0), Coord2(10.0, 10.0)),
+        rectangle(Coord2(4.0, 4.0), Coord2(6.0, 6.0)),
+    ];
+    let options = StrokeOptions::default().with_join(LineJoin::Miter);
+
+    let grown = path_offset::<SimpleBezierPath, _>(&frame, 0.5, &options);
+    assert!(grown.len() == 2);
+    assert!(!contains_point(&grown, Coord2(5.0, 5.0)));
+    assert!(contains_point(&grown, Coord2(4.4, 5.0)));
+
+    let grown = path_offset::<SimpleBezierPath, _>(&frame, 1.5, &options);
+    assert!(grown.len() == 1);
+    assert!(contains_point(&grown, Coord2(5.0, 5.0)));
+}
+
+#[test]
+fn shrink_rounded_rectangle_past_corners() {
+    // Rectangle with corners of radius 1: shrinking by 2 should produce a rectangle with sharp corners
+    const K: f64 = 0.5522847498;
+    let rounded_rectangle = BezierPathBuilder::<SimpleBezierPath>::start(Coord2(1.0, 0.0))
+        .line_to(Coord2(9.0, 0.0))
+        .curve_to(
+            (Coord2(9.0 + K, 0.0), Coord2(10.0, 1.0 - K)),
+            Coord2(10.0, 1.0),
+        )
+        .line_to(Coord2(10.0, 9.0))
+        .curve_to(
+            (Coord2(10.0, 9.0 + K), Coord2(9.0 + K, 10.0)),
+            Coord2(9.0, 10.0),
+        )
+        .line_to(Coord2(1.0, 10.0))
+        .curve_to(
+            (Coord2(1.0 - K, 10.0), Coord2(0.0, 9.0 + K)),
+            Coord2(0.0, 9.0),
+        )
+        .line_to(Coord2(0.0, 1.0))
+        .curve_to(
+            (Coord2(0.0, 1.0 - K), Coord2(1.0 - K, 0.0)),
+            Coord2(1.0, 0.0),
+        )
+        .build();
+
+    let options = StrokeOptions::default().with_join(LineJoin::Round);
+    let shrunk = path_offset::<SimpleBezierPath, _>(&vec![rounded_rectangle], -2.0, &options);
+
+    assert!(shrunk.len() == 1);
+
+    let (min, max): (Coord2, Coord2) = shrunk[0].bounding_box();
+    assert!(min.is_near_to(&Coord2(2.0, 2.0), 0.1), "{:?}", min);
+    assert!(max.is_near_to(&Coord2(8.0, 8.0), 0.1), "{:?}", max);
+    assert!(contains_point(&shrunk, Coord2(2.1, 2.1)));
+    assert!(contains_point(&shrunk, Coord2(7.9, 7.9)));
+}
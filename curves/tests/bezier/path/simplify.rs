@@ -0,0 +1,223 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use flo_curves::bezier::path::*;
+use flo_curves::bezier::*;
+use flo_curves::*;
+
+use std::f64;
+
+///
+/// Creates a path made up of a line for each of a set of points
+///
+fn path_through_points(points: &[Coord2]) -> SimpleBezierPath {
+    let mut builder = BezierPathBuilder::<SimpleBezierPath>::start(points[0]);
+    for point in points.iter().skip(1) {
+        builder = builder.line_to(*point);
+    }
+
+    builder.build()
+}
+
+///
+/// Approximates a circle using a large number of lines
+///
+fn circle_of_lines(center: Coord2, radius: f64, num_lines: usize) -> SimpleBezierPath {
+    let points = (0..=num_lines)
+        .map(|idx| {
+            let angle = (idx as f64) / (num_lines as f64) * 2.0 * f64::consts::PI;
+            Coord2(
+                center.x() + angle.cos() * radius,
+                center.y() + angle.sin() * radius,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    path_through_points(&points)
+}
+
+///
+/// Finds the distance between a point and the nearest point on a path
+///
+fn distance_to_path(path: &SimpleBezierPath, point: Coord2) -> f64 {
+    path.to_curves::<Curve<Coord2>>()
+        .into_iter()
+        .map(|curve| curve.nearest_point(&point).distance_to(&point))
+        .fold(f64::MAX, f64::min)
+}
+
+///
+/// Checks that every point on one path is within a certain distance of the other path
+///
+fn assert_within_distance(
+    path: &SimpleBezierPath,
+    target: &SimpleBezierPath,
+    samples_per_curve: usize,
+    max_distance: f64,
+) {
+    for curve in path.to_curves::<Curve<Coord2>>() {
+        for t in 0..=samples_per_curve {
+            let point = curve.point_at_pos((t as f64) / (samples_per_curve as f64));
+            let distance = distance_to_path(target, point);
+
+            assert!(
+                distance <= max_distance,
+                "Point {:?} is {} away from the other path",
+                point,
+                distance
+            );
+        }
+    }
+}
+
+#[test]
+fn simplify_circle() {
+    let circle = circle_of_lines(Coord2(100.0, 100.0), 50.0, 500);
+    let simplified = path_simplify::<SimpleBezierPath>(&circle, 0.5);
+
+    assert!(simplified.1.len() < 20, "{} curves", simplified.1.len());
+    assert!(simplified.start_point() == circle.start_point());
+    assert!(simplified.1.last().unwrap().2 == circle.1.last().unwrap().2);
+
+    assert_within_distance(&simplified, &circle, 20, 0.5);
+    assert_within_distance(&circle, &simplified, 2, 0.5);
+}
+
+#[test]
+fn simplify_keeps_corners() {
+    // Square, with each side made up of many short lines
+    let corners = [
+        Coord2(0.0, 0.0),
+        Coord2(100.0, 0.0),
+        Coord2(100.0, 100.0),
+        Coord2(0.0, 100.0),
+        Coord2(0.0, 0.0),
+    ];
+    let mut points = vec![corners[0]];
+    for (start, end) in corners.iter().zip(corners.iter().skip(1)) {
+        for idx in 1..=50 {
+            let t = (idx as f64) / 50.0;
+            points.push(*start + (*end - *start) * t);
+        }
+    }
+
+    let square = path_through_points(&points);
+    let simplified = path_simplify::<SimpleBezierPath>(&square, 0.1);
+
+    assert!(simplified.1.len() == 4, "{:?}", simplified);
+    for (corner, (_, _, end_point)) in corners.iter().skip(1).zip(simplified.1.iter()) {
+        assert!(end_point.is_near_to(corner, 0.001), "{:?}", simplified);
+    }
+}
+
+#[test]
+fn simplify_closed_path_keeps_corner_at_start() {
+    // Square, with each side made up of many short lines, starting and ending in the middle of the bottom edge
+    let corners = [
+        Coord2(50.0, 0.0),
+        Coord2(100.0, 0.0),
+        Coord2(100.0, 100.0),
+        Coord2(0.0, 100.0),
+        Coord2(0.0, 0.0),
+        Coord2(50.0, 0.0),
+    ];
+    let mut points = vec![corners[0]];
+    for (start, end) in corners.iter().zip(corners.iter().skip(1)) {
+        for idx in 1..=50 {
+            let t = (idx as f64) / 50.0;
+            points.push(*start + (*end - *start) * t);
+        }
+    }
+
+    let square = path_through_points(&points);
+    let simplified = path_simplify::<SimpleBezierPath>(&square, 0.1);
+
+    // The two halves of the bottom edge are joined, and the path starts at a corner instead
+    assert!(simplified.1.len() == 4, "{:?}", simplified);
+    assert!(
+        simplified
+            .start_point()
+            .is_near_to(&Coord2(100.0, 0.0), 0.001),
+        "{:?}",
+        simplified
+    );
+
+    for (corner, (_, _, end_point)) in corners[2..]
+        .iter()
+        .chain([Coord2(100.0, 0.0)].iter())
+        .filter(|corner| **corner != Coord2(50.0, 0.0))
+        .zip(simplified.1.iter())
+    {
+        assert!(end_point.is_near_to(corner, 0.001), "{:?}", simplified);
+    }
+
+    assert_within_distance(&simplified, &square, 20, 0.1);
+    assert_within_distance(&square, &simplified, 2, 0.1);
+}
+
+#[test]
+fn simplify_wobbly_line() {
+    // Sine wave with a small amount of wobble, like a hand-drawn line
+    let points = (0..=1000)
+        .map(|idx| {
+            let x = (idx as f64) * 0.5;
+            let wobble = if idx % 2 == 0 { 0.05 } else { -0.05 };
+            Coord2(x, (x / 40.0).sin() * 30.0 + wobble)
+        })
+        .collect::<Vec<_>>();
+
+    let line = path_through_points(&points);
+    let simplified = path_simplify::<SimpleBezierPath>(&line, 1.0);
+
+    assert!(simplified.1.len() < 50, "{} curves", simplified.1.len());
+    assert_within_distance(&simplified, &line, 20, 1.0);
+    assert_within_distance(&line, &simplified, 2, 1.0);
+}
+
+#[test]
+fn simplify_with_small_error_still_within_tolerance() {
+    let circle = circle_of_lines(Coord2(100.0, 100.0), 50.0, 100);
+    let simplified = path_simplify::<SimpleBezierPath>(&circle, 0.01);
+
+    assert!(simplified.1.len() <= circle.1.len());
+    assert_within_distance(&simplified, &circle, 20, 0.01);
+    assert_within_distance(&circle, &simplified, 2, 0.01);
+}
+
+#[test]
+fn sharp_corners_are_unchanged() {
+    let zigzag = path_through_points(&[
+        Coord2(0.0, 0.0),
+        Coord2(10.0, 10.0),
+        Coord2(20.0, 0.0),
+        Coord2(30.0, 10.0),
+    ]);
+    let simplified = path_simplify::<SimpleBezierPath>(&zigzag, 1.0);
+
+    assert!(simplified == zigzag);
+}
+
+#[test]
+fn simplify_removes_zero_length_curves() {
+    let path = path_through_points(&[
+        Coord2(0.0, 0.0),
+        Coord2(10.0, 0.0),
+        Coord2(10.0, 0.0),
+        Coord2(20.0, 0.0),
+    ]);
+    let simplified = path_simplify::<SimpleBezierPath>(&path, 0.1);
+
+    assert!(simplified.1.len() == 1, "{:?}", simplified);
+    assert!(simplified.1[0].2 == Coord2(20.0, 0.0));
+}
+
+#[test]
+fn simplify_empty_path() {
+    let path = (Coord2(1.0, 2.0), vec![]);
+    let simplified = path_simplify::<SimpleBezierPath>(&path, 0.1);
+
+    assert!(simplified == path);
+}
@@ -0,0 +1,112 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::env;
+use std::fs::File;
+use std::thread;
+use std::time::Duration;
+
+use futures::executor;
+use futures::prelude::*;
+
+use flo_canvas::*;
+use flo_draw::*;
+
+///
+/// Records an animation to a file, or replays a recording
+///
+/// `cargo run --example flight_recorder -- record animation.florec` will show a short animation and record it to a file.
+/// `cargo run --example flight_recorder -- replay animation.florec [speed] [start frame]` will replay a recording in a
+/// new window, optionally changing the speed or skipping to a later frame. While it's replaying, the number keys move the
+/// replay to different points in the recording (1 moves to 10% of the way through, 0 moves back to the start).
+///
+pub fn main() {
+    let args = env::args().collect::<Vec<_>>();
+
+    match (args.get(1).map(|arg| arg.as_str()), args.get(2)) {
+        (Some("record"), Some(path)) => record(path),
+        (Some("replay"), Some(path)) => {
+            let speed = args.get(3).and_then(|arg| arg.parse().ok()).unwrap_or(1.0);
+            let start_frame = args.get(4).and_then(|arg| arg.parse().ok()).unwrap_or(0);
+
+            replay(path, speed, start_frame)
+        }
+        _ => {
+            println!("Usage: flight_recorder record <file>");
+            println!("       flight_recorder replay <file> [speed] [start frame]");
+        }
+    }
+}
+
+///
+/// Draws a circle moving around the window, recording it to a file
+///
+fn record(path: &str) {
+    let file = File::create(path).expect("Could not create the recording");
+
+    with_2d_graphics(move || {
+        let (canvas, result) = create_recorded_drawing_window("Flight recorder", file);
+
+        for frame in 0..300 {
+            let angle = (frame as f32) / 60.0 * std::f32::consts::PI;
+
+            canvas.draw(|gc| {
+                gc.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+                gc.canvas_height(1000.0);
+                gc.center_region(0.0, 0.0, 1000.0, 1000.0);
+
+                gc.new_path();
+                gc.circle(
+                    500.0 + angle.cos() * 300.0,
+                    500.0 + angle.sin() * 300.0,
+                    50.0,
+                );
+                gc.fill_color(Color::Rgba(0.3, 0.6, 0.8, 1.0));
+                gc.fill();
+            });
+
+            thread::sleep(Duration::from_nanos(1_000_000_000 / 60));
+        }
+
+        // Closing the canvas finishes the recording
+        drop(canvas);
+        if let Err(error) = executor::block_on(result) {
+            println!("Could not write the recording: {}", error);
+        }
+    });
+}
+
+///
+/// Replays a recording made by `record()` (or any other recorded drawing window)
+///
+fn replay(path: &str, speed: f64, start_frame: usize) {
+    let file = File::open(path).expect("Could not open the recording");
+    let recording = CanvasRecording::read(file).expect("Could not read the recording");
+
+    let frame_count = recording.frame_count();
+    println!("{} frames", frame_count);
+
+    with_2d_graphics(move || {
+        let (events, seeker) = create_drawing_window_from_recording_with_seeking(
+            recording,
+            start_frame,
+            speed,
+            "Flight recorder replay",
+        );
+
+        // Run until the window is closed
+        executor::block_on(async move {
+            let mut events = events;
+            while let Some(event) = events.next().await {
+                if let DrawEvent::ReceivedCharacter(digit) = event {
+                    if let Some(tenths) = digit.to_digit(10) {
+                        seeker.seek(frame_count * (tenths as usize) / 10);
+                    }
+                }
+            }
+        });
+    });
+}
@@ -0,0 +1,93 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::env;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use futures::executor;
+use futures::prelude::*;
+
+use flo_canvas::*;
+use flo_draw::*;
+
+///
+/// Draws an animation without a window and serves it over TCP, or displays an animation served by another process
+///
+/// `cargo run --example remote_canvas -- serve 127.0.0.1:3000` will start drawing and wait for viewers to connect.
+/// `cargo run --example remote_canvas -- view 127.0.0.1:3000` opens a window showing what the server is drawing. Any
+/// number of viewers can connect at any time.
+///
+pub fn main() {
+    let args = env::args().collect::<Vec<_>>();
+
+    match (args.get(1).map(|arg| arg.as_str()), args.get(2)) {
+        (Some("serve"), Some(address)) => serve(address),
+        (Some("view"), Some(address)) => view(address),
+        _ => {
+            println!("Usage: remote_canvas serve <address>");
+            println!("       remote_canvas view <address>");
+        }
+    }
+}
+
+///
+/// Draws a spinning square on a canvas that isn't attached to a window, and sends it to any viewers that connect
+///
+fn serve(address: &str) {
+    let canvas = Canvas::new();
+    let server = CanvasServer::for_canvas(&canvas);
+
+    server.listen_tcp(TcpListener::bind(address).expect("Could not listen for viewers"));
+    println!("Serving on {}", address);
+
+    let mut frame = 0;
+    loop {
+        let angle = (frame as f32) * 2.0;
+
+        canvas.draw(|gc| {
+            gc.clear_canvas(Color::Rgba(0.1, 0.1, 0.1, 1.0));
+            gc.canvas_height(1000.0);
+            gc.center_region(0.0, 0.0, 1000.0, 1000.0);
+
+            gc.transform(Transform2D::translate(500.0, 500.0) * Transform2D::rotate_degrees(angle));
+
+            gc.new_path();
+            gc.rect(-200.0, -200.0, 200.0, 200.0);
+            gc.fill_color(Color::Rgba(0.8, 0.6, 0.2, 1.0));
+            gc.fill();
+        });
+
+        frame += 1;
+        thread::sleep(Duration::from_nanos(1_000_000_000 / 60));
+    }
+}
+
+///
+/// Shows the drawing from a server in a window
+///
+fn view(address: &str) {
+    let connection = TcpStream::connect(address).expect("Could not connect to the server");
+
+    with_2d_graphics(move || {
+        let window = create_canvas_window("Remote canvas");
+
+        // Copy the instructions from the server to the window until the connection is closed
+        executor::block_on(async move {
+            let mut remote = remote_drawing_stream(connection).ready_chunks(1000);
+
+            while let Some(instructions) = remote.next().await {
+                let instructions = instructions
+                    .into_iter()
+                    .filter_map(|draw| draw.ok())
+                    .collect();
+
+                window.write(instructions);
+            }
+        });
+    });
+}
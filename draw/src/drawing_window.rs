@@ -46,9 +46,23 @@ pub fn create_drawing_window_with_events<'a, TProperties>(
 where
     TProperties: 'a + FloWindowProperties,
 {
-    let (width, height) = window_properties.size().get();
-
     // Create the canvas
+    let (target, stream) = drawing_target_for_window(window_properties.size().get());
+
+    // Create the events stream
+    let events =
+        create_drawing_window_from_stream(window_drawing_stream(stream), window_properties);
+
+    // Return the result
+    (target, events)
+}
+
+///
+/// Creates a drawing target set up so that the canvas coordinates map 1:1 to the pixels of a window of the specified size
+///
+pub(crate) fn drawing_target_for_window(
+    (width, height): (u64, u64),
+) -> (DrawingTarget, DrawStream) {
     let (target, stream) = DrawingTarget::new();
     target.draw(|gc| {
         // Default window layout is 1:1 for the requested window size
@@ -57,22 +71,30 @@ where
         gc.center_region(0.0, 0.0, width as _, height as _);
     });
 
-    // Get the stream of drawing instructions (and gather them into batches)
-    let target_stream = stream;
-    let target_stream = drawing_without_dashed_lines(target_stream);
-    let target_stream = drawing_with_laid_out_text(target_stream);
-    let target_stream = drawing_with_text_as_paths(target_stream);
-    let target_stream = BatchedStream {
-        stream: Some(target_stream),
-        frame_count: 0,
-        waiting: vec![],
-    };
+    (target, stream)
+}
 
-    // Create the events stream
-    let events = create_drawing_window_from_stream(target_stream, window_properties);
+///
+/// Converts a stream of drawing instructions into the batches of instructions that are sent to a drawing window
+///
+/// Dashed lines and text are converted to paths, and the instructions are gathered into batches, holding back any
+/// frames that are not yet complete.
+///
+pub(crate) fn window_drawing_stream<DrawStream>(
+    draw_stream: DrawStream,
+) -> impl 'static + Send + Unpin + Stream<Item = Vec<Draw>>
+where
+    DrawStream: 'static + Send + Unpin + Stream<Item = Draw>,
+{
+    let draw_stream = drawing_without_dashed_lines(draw_stream);
+    let draw_stream = drawing_with_laid_out_text(draw_stream);
+    let draw_stream = drawing_with_text_as_paths(draw_stream);
 
-    // Return the result
-    (target, events)
+    BatchedStream {
+        stream: Some(draw_stream),
+        frame_count: 0,
+        waiting: vec![],
+    }
 }
 
 ///
@@ -110,19 +132,11 @@ where
         gc.center_region(0.0, 0.0, width as _, height as _);
     });
 
-    // Get the stream of drawing instructions (and gather them into batches)
-    let canvas_stream = canvas.stream();
-    let canvas_stream = drawing_without_dashed_lines(canvas_stream);
-    let canvas_stream = drawing_with_laid_out_text(canvas_stream);
-    let canvas_stream = drawing_with_text_as_paths(canvas_stream);
-    let canvas_stream = BatchedStream {
-        stream: Some(canvas_stream),
-        frame_count: 0,
-        waiting: vec![],
-    };
-
     // Create the events stream
-    let events = create_drawing_window_from_stream(canvas_stream, window_properties);
+    let events = create_drawing_window_from_stream(
+        window_drawing_stream(canvas.stream()),
+        window_properties,
+    );
 
     // Return the result
     (canvas, events)
@@ -70,12 +70,14 @@ pub use self::drawing_window::*;
 pub use self::events::*;
 #[cfg(all(feature = "render-opengl", not(feature = "render-wgpu")))]
 pub use self::glutin::with_2d_graphics;
+pub use self::recording_window::*;
 pub use self::render_window::*;
 #[cfg(all(feature = "render-wgpu"))]
 pub use self::wgpu::with_2d_graphics;
 pub use self::window_properties::*;
 
 mod drawing_window;
+mod recording_window;
 mod render_window;
 mod window_properties;
 
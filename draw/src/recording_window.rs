@@ -0,0 +1,279 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::VecDeque;
+use std::io;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::channel::mpsc;
+use futures::prelude::*;
+use futures::stream;
+use futures::task::Poll;
+use futures_timer::Delay;
+
+use flo_binding::*;
+use flo_canvas::*;
+
+use crate::drawing_window::*;
+use crate::events::*;
+use crate::window_properties::*;
+
+///
+/// Creates a drawing target that will render to a window, recording everything drawn to it
+///
+/// The recording captures every drawing instruction sent to the window along with the time it was sent, which makes it
+/// possible to replay exactly what the window displayed using `create_drawing_window_from_recording()`. This is useful
+/// for tracking down glitches in animations. The recording is usually written to a file: writes are buffered, and the
+/// recording is flushed whenever the window is waiting for more instructions, so it remains readable if the application
+/// stops unexpectedly.
+///
+/// The future that's returned along with the drawing target completes when the window stops or the recording can't be
+/// written, and returns the result of writing the recording.
+///
+pub fn create_recorded_drawing_window<'a, TProperties, TRecording>(
+    window_properties: TProperties,
+    recording: TRecording,
+) -> (
+    DrawingTarget,
+    impl Send + Unpin + Future<Output = io::Result<()>>,
+)
+where
+    TProperties: 'a + FloWindowProperties,
+    TRecording: 'static + Send + Write,
+{
+    let (target, _events, result) =
+        create_recorded_drawing_window_with_events(window_properties, recording);
+
+    // Dropping the events will stop the window from blocking when they're not handled
+    (target, result)
+}
+
+///
+/// Creates a drawing target that will render to a window and record everything drawn to it, along with a stream of
+/// events from that window and a future that returns the result of writing the recording
+///
+pub fn create_recorded_drawing_window_with_events<'a, TProperties, TRecording>(
+    window_properties: TProperties,
+    recording: TRecording,
+) -> (
+    DrawingTarget,
+    impl Send + Stream<Item = DrawEvent>,
+    impl Send + Unpin + Future<Output = io::Result<()>>,
+)
+where
+    TProperties: 'a + FloWindowProperties,
+    TRecording: 'static + Send + Write,
+{
+    // Create the canvas
+    let (target, stream) = drawing_target_for_window(window_properties.size().get());
+
+    // The instructions are recorded before they're converted for the window, so the recording has the original text and dashed lines
+    let (stream, result) = record_drawing_stream(stream, recording);
+
+    // Create the events stream
+    let events =
+        create_drawing_window_from_stream(window_drawing_stream(stream), window_properties);
+
+    (target, events, result)
+}
+
+///
+/// Moves a replay created by `replay_recording_with_seeking()` to a different frame
+///
+#[derive(Clone)]
+pub struct ReplaySeeker {
+    /// Sends the frames to seek to
+    seek_requests: mpsc::UnboundedSender<usize>,
+}
+
+impl ReplaySeeker {
+    ///
+    /// Moves the replay to the start of a frame, and carries on from there at the original speed
+    ///
+    /// Frames that are not in the recording are ignored.
+    ///
+    pub fn seek(&self, frame: usize) {
+        self.seek_requests.unbounded_send(frame).ok();
+    }
+}
+
+///
+/// Creates a stream that replays the instructions in a recording with their original timing
+///
+/// The replay starts at `start_frame`: the instructions from the last `ClearCanvas` before this frame are sent
+/// immediately, so the replay begins with what was displayed when the frame started. `speed` adjusts how quickly the
+/// recording is replayed (2.0 is twice as fast as the original, for example). A speed of 0 or less, or an infinite speed,
+/// replays the whole recording as fast as possible.
+///
+/// The recording can be passed in as an `Arc<CanvasRecording>` to replay it without making a copy.
+///
+pub fn replay_recording<TRecording>(
+    recording: TRecording,
+    start_frame: usize,
+    speed: f64,
+) -> impl Send + Unpin + Stream<Item = Draw>
+where
+    TRecording: 'static + Into<Arc<CanvasRecording>>,
+{
+    replay_recording_from(recording.into(), start_frame, speed, None)
+}
+
+///
+/// Creates a stream that replays the instructions in a recording, along with a `ReplaySeeker` that can move the replay
+/// to a different frame while it's running
+///
+/// This works the same way as `replay_recording()`, except that the stream doesn't finish until the `ReplaySeeker` is
+/// dropped, so that it's possible to seek back once the replay has reached the end of the recording.
+///
+pub fn replay_recording_with_seeking<TRecording>(
+    recording: TRecording,
+    start_frame: usize,
+    speed: f64,
+) -> (impl Send + Unpin + Stream<Item = Draw>, ReplaySeeker)
+where
+    TRecording: 'static + Into<Arc<CanvasRecording>>,
+{
+    let (seek_requests, receive_seeks) = mpsc::unbounded();
+    let replay = replay_recording_from(recording.into(), start_frame, speed, Some(receive_seeks));
+
+    (replay, ReplaySeeker { seek_requests })
+}
+
+///
+/// Replays a recording from a frame, moving to a new frame whenever one is sent to `seek_requests`
+///
+fn replay_recording_from(
+    recording: Arc<CanvasRecording>,
+    start_frame: usize,
+    speed: f64,
+    seek_requests: Option<mpsc::UnboundedReceiver<usize>>,
+) -> impl Send + Unpin + Stream<Item = Draw> {
+    let timed = speed > 0.0 && speed.is_finite();
+    let num_entries = recording.entries().len();
+
+    let mut seek_requests = seek_requests;
+    let mut next_index = recording.seek_start(start_frame).unwrap_or(num_entries);
+    let mut timed_from = recording.frame_start(start_frame).unwrap_or(num_entries);
+    let mut start_time = recording.frame_time(start_frame).unwrap_or_default();
+    let mut replay_started: Option<Instant> = None;
+    let mut delay: Option<Delay> = None;
+    let mut seek_instructions = VecDeque::new();
+
+    stream::poll_fn(move |context| {
+        // Only the most recent seek request matters
+        let mut seek_to = None;
+        while let Some(requests) = &mut seek_requests {
+            match requests.poll_next_unpin(context) {
+                Poll::Ready(Some(frame)) => seek_to = Some(frame),
+                Poll::Ready(None) => seek_requests = None,
+                Poll::Pending => break,
+            }
+        }
+
+        if let Some(frame) = seek_to {
+            if let (Some(seek_start), Some(frame_start)) =
+                (recording.seek_start(frame), recording.frame_start(frame))
+            {
+                next_index = seek_start;
+                timed_from = frame_start;
+                start_time = recording.frame_time(frame).unwrap_or_default();
+                replay_started = None;
+                delay = None;
+
+                // Abandon any frame that was in progress, and clear the canvas if the replay doesn't start by doing so
+                // (the replay starts at the beginning of the recording when there's no ClearCanvas before the frame)
+                seek_instructions.clear();
+                seek_instructions.push_back(Draw::ResetFrame);
+
+                if !matches!(
+                    recording.entries().get(seek_start).map(|entry| &entry.draw),
+                    Some(Draw::ClearCanvas(_))
+                ) {
+                    seek_instructions.push_back(Draw::ClearCanvas(Color::Rgba(0.0, 0.0, 0.0, 0.0)));
+                }
+            }
+        }
+
+        if let Some(draw) = seek_instructions.pop_front() {
+            return Poll::Ready(Some(draw));
+        }
+
+        let entry = if let Some(entry) = recording.entries().get(next_index) {
+            entry
+        } else if seek_requests.is_some() {
+            // Wait for a request to seek back into the recording
+            return Poll::Pending;
+        } else {
+            return Poll::Ready(None);
+        };
+
+        // Wait until it's time to send the instruction
+        if next_index >= timed_from && timed {
+            let started = *replay_started.get_or_insert_with(Instant::now);
+            let due = entry.time.saturating_sub(start_time).as_secs_f64() / speed;
+            let due = Duration::try_from_secs_f64(due).unwrap_or(Duration::MAX);
+            let elapsed = started.elapsed();
+
+            if due > elapsed {
+                let delay = delay.get_or_insert_with(|| Delay::new(due - elapsed));
+
+                if delay.poll_unpin(context).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+
+            delay = None;
+        }
+
+        next_index += 1;
+        Poll::Ready(Some(entry.draw.clone()))
+    })
+}
+
+///
+/// Creates a drawing window that replays a recording made by `create_recorded_drawing_window()`
+///
+/// See `replay_recording()` for the meaning of `start_frame` and `speed`.
+///
+pub fn create_drawing_window_from_recording<'a, TProperties, TRecording>(
+    recording: TRecording,
+    start_frame: usize,
+    speed: f64,
+    window_properties: TProperties,
+) -> impl Send + Stream<Item = DrawEvent>
+where
+    TProperties: 'a + FloWindowProperties,
+    TRecording: 'static + Into<Arc<CanvasRecording>>,
+{
+    let replay = replay_recording(recording, start_frame, speed);
+
+    create_drawing_window_from_stream(window_drawing_stream(replay), window_properties)
+}
+
+///
+/// Creates a drawing window that replays a recording, along with a `ReplaySeeker` that can move the replay to a different
+/// frame while the window is open
+///
+/// See `replay_recording_with_seeking()` for how the replay behaves.
+///
+pub fn create_drawing_window_from_recording_with_seeking<'a, TProperties, TRecording>(
+    recording: TRecording,
+    start_frame: usize,
+    speed: f64,
+    window_properties: TProperties,
+) -> (impl Send + Stream<Item = DrawEvent>, ReplaySeeker)
+where
+    TProperties: 'a + FloWindowProperties,
+    TRecording: 'static + Into<Arc<CanvasRecording>>,
+{
+    let (replay, seeker) = replay_recording_with_seeking(recording, start_frame, speed);
+    let events =
+        create_drawing_window_from_stream(window_drawing_stream(replay), window_properties);
+
+    (events, seeker)
+}
@@ -26,5 +26,38 @@ typedef enum FragmentInputIndex {
     FragmentIndexClipMaskTexture    = 2,
 
     /// The alpha value to use for the fragment
-    FragmentAlpha                   = 3
+    FragmentAlpha                   = 3,
+
+    /// The focal point (x, y) and focal radius of a radial gradient
+    FragmentGradientFocus           = 4,
+
+    /// The copy of the render target read by the blend shader
+    FragmentIndexDestinationTexture = 5,
+
+    /// The blend function to use in the blend shader (one of the BlendShaderMode values)
+    FragmentBlendMode               = 6
 } FragmentInputIndex;
+
+///
+/// The sampler locations for the Metal fragment shaders
+///
+typedef enum FragmentSamplerIndex {
+    /// The sampler used to read a gradient texture (which sets how the gradient is spread)
+    FragmentGradientSampler         = 0
+} FragmentSamplerIndex;
+
+///
+/// The blend functions supported by the blend shader (the blend modes that need to read the destination colour)
+///
+typedef enum BlendShaderMode {
+    BlendShaderOverlay              = 0,
+    BlendShaderColorDodge           = 1,
+    BlendShaderColorBurn            = 2,
+    BlendShaderHardLight            = 3,
+    BlendShaderSoftLight            = 4,
+    BlendShaderDifference           = 5,
+    BlendShaderHue                  = 6,
+    BlendShaderSaturation           = 7,
+    BlendShaderColor                = 8,
+    BlendShaderLuminosity           = 9
+} BlendShaderMode;
@@ -93,10 +93,12 @@ fn compile_metal() {
         "shaders/texture/texture_fragment.metal",
         "texture_fragment.air",
     );
+    compile_metal_shader("shaders/texture/blend_fragment.metal", "blend_fragment.air");
     link_metal_shaders(
         vec![
             "simple.air",
             "texture_fragment.air",
+            "blend_fragment.air",
             "gradient_fragment.air",
             "clip_mask.air",
             "postprocessing.air",
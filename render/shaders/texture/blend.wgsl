@@ -0,0 +1,262 @@
+struct RasterData {
+    @location(0)        tex_coord:  vec2<f32>,
+    @builtin(position)  pos:        vec4<f32>
+}
+
+struct TextureSettings {
+    @location(0)    transform:  mat4x4<f32>,
+    @location(1)    alpha:      f32
+}
+
+@group(0)
+@binding(0)
+var<uniform> transform: mat4x4<f32>;
+
+@group(2)
+@binding(0)
+var<uniform> texture_settings: TextureSettings;
+
+@vertex
+fn blend_vertex_shader(
+    @location(0) pos:       vec2<f32>,
+    @location(1) tex_coord: vec2<f32>,
+) -> RasterData {
+    var result: RasterData;
+
+    result.tex_coord    = tex_coord;
+    result.pos          = vec4<f32>(pos[0], pos[1], 0.0, 1.0) * transform;
+
+    return result;
+}
+
+///
+/// Reads the (pre-multiplied) source colour for a fragment
+///
+fn source_color(vertex: RasterData) -> vec4<f32> {
+    return texture_color(vec4<f32>(), vertex.tex_coord) * texture_settings.alpha;
+}
+
+///
+/// The blend functions are defined on colours without pre-multiplication
+///
+fn unpremultiply(col: vec4<f32>) -> vec3<f32> {
+    if (col[3] > 0.0) {
+        return min(col.rgb / col[3], vec3<f32>(1.0));
+    } else {
+        return vec3<f32>(0.0);
+    }
+}
+
+///
+/// Composites the result of a blend function with the source and destination colours using 'source over'
+///
+fn composite(src: vec4<f32>, dst: vec4<f32>, blended: vec3<f32>) -> vec4<f32> {
+    let both_alpha  = src[3] * dst[3];
+    let rgb         = src.rgb * (1.0 - dst[3]) + dst.rgb * (1.0 - src[3]) + blended * both_alpha;
+    let alpha       = src[3] + dst[3] - both_alpha;
+
+    return clamp(vec4<f32>(rgb, alpha), vec4<f32>(0.0), vec4<f32>(1.0));
+}
+
+fn screen(cs: vec3<f32>, cb: vec3<f32>) -> vec3<f32> {
+    return cs + cb - cs * cb;
+}
+
+fn hard_light(cs: vec3<f32>, cb: vec3<f32>) -> vec3<f32> {
+    return select(screen(2.0 * cs - 1.0, cb), cb * 2.0 * cs, cs <= vec3<f32>(0.5));
+}
+
+fn color_dodge(cs: vec3<f32>, cb: vec3<f32>) -> vec3<f32> {
+    let dodge       = min(cb / max(1.0 - cs, vec3<f32>(0.00001)), vec3<f32>(1.0));
+    let full_dodge  = select(dodge, vec3<f32>(1.0), cs >= vec3<f32>(1.0));
+
+    return select(full_dodge, vec3<f32>(0.0), cb <= vec3<f32>(0.0));
+}
+
+fn color_burn(cs: vec3<f32>, cb: vec3<f32>) -> vec3<f32> {
+    let burn        = 1.0 - min((1.0 - cb) / max(cs, vec3<f32>(0.00001)), vec3<f32>(1.0));
+    let full_burn   = select(burn, vec3<f32>(0.0), cs <= vec3<f32>(0.0));
+
+    return select(full_burn, vec3<f32>(1.0), cb >= vec3<f32>(1.0));
+}
+
+fn soft_light(cs: vec3<f32>, cb: vec3<f32>) -> vec3<f32> {
+    let d       = select(sqrt(cb), ((16.0 * cb - 12.0) * cb + 4.0) * cb, cb <= vec3<f32>(0.25));
+    let darker  = cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb);
+    let lighter = cb + (2.0 * cs - 1.0) * (d - cb);
+
+    return select(lighter, darker, cs <= vec3<f32>(0.5));
+}
+
+fn lum(col: vec3<f32>) -> f32 {
+    return dot(col, vec3<f32>(0.3, 0.59, 0.11));
+}
+
+fn clip_color(col: vec3<f32>) -> vec3<f32> {
+    let l       = lum(col);
+    let n       = min(min(col[0], col[1]), col[2]);
+    let x       = max(max(col[0], col[1]), col[2]);
+
+    var result  = col;
+    if (n < 0.0) {
+        result = l + (result - l) * l / (l - n);
+    }
+    if (x > 1.0) {
+        result = l + (result - l) * (1.0 - l) / (x - l);
+    }
+
+    return result;
+}
+
+fn set_lum(col: vec3<f32>, l: f32) -> vec3<f32> {
+    return clip_color(col + (l - lum(col)));
+}
+
+fn sat(col: vec3<f32>) -> f32 {
+    return max(max(col[0], col[1]), col[2]) - min(min(col[0], col[1]), col[2]);
+}
+
+fn set_sat(col: vec3<f32>, s: f32) -> vec3<f32> {
+    let max_col = max(max(col[0], col[1]), col[2]);
+    let min_col = min(min(col[0], col[1]), col[2]);
+
+    if (max_col > min_col) {
+        return (col - min_col) * s / (max_col - min_col);
+    } else {
+        return vec3<f32>(0.0);
+    }
+}
+
+@fragment
+fn normal_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+
+    return composite(src, dst, unpremultiply(src));
+}
+
+@fragment
+fn multiply_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+
+    return composite(src, dst, unpremultiply(src) * unpremultiply(dst));
+}
+
+@fragment
+fn screen_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+
+    return composite(src, dst, screen(unpremultiply(src), unpremultiply(dst)));
+}
+
+@fragment
+fn darken_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+
+    return composite(src, dst, min(unpremultiply(src), unpremultiply(dst)));
+}
+
+@fragment
+fn lighten_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+
+    return composite(src, dst, max(unpremultiply(src), unpremultiply(dst)));
+}
+
+@fragment
+fn overlay_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+
+    return composite(src, dst, hard_light(unpremultiply(dst), unpremultiply(src)));
+}
+
+@fragment
+fn color_dodge_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+
+    return composite(src, dst, color_dodge(unpremultiply(src), unpremultiply(dst)));
+}
+
+@fragment
+fn color_burn_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+
+    return composite(src, dst, color_burn(unpremultiply(src), unpremultiply(dst)));
+}
+
+@fragment
+fn hard_light_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+
+    return composite(src, dst, hard_light(unpremultiply(src), unpremultiply(dst)));
+}
+
+@fragment
+fn soft_light_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+
+    return composite(src, dst, soft_light(unpremultiply(src), unpremultiply(dst)));
+}
+
+@fragment
+fn difference_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+
+    return composite(src, dst, abs(unpremultiply(dst) - unpremultiply(src)));
+}
+
+@fragment
+fn exclusion_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+    let cs  = unpremultiply(src);
+    let cb  = unpremultiply(dst);
+
+    return composite(src, dst, cb + cs - 2.0 * cb * cs);
+}
+
+@fragment
+fn hue_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+    let cs  = unpremultiply(src);
+    let cb  = unpremultiply(dst);
+
+    return composite(src, dst, set_lum(set_sat(cs, sat(cb)), lum(cb)));
+}
+
+@fragment
+fn saturation_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+    let cs  = unpremultiply(src);
+    let cb  = unpremultiply(dst);
+
+    return composite(src, dst, set_lum(set_sat(cb, sat(cs)), lum(cb)));
+}
+
+@fragment
+fn color_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+
+    return composite(src, dst, set_lum(unpremultiply(src), lum(unpremultiply(dst))));
+}
+
+@fragment
+fn luminosity_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let src = source_color(vertex);
+    let dst = destination_color(vertex.pos);
+
+    return composite(src, dst, set_lum(unpremultiply(dst), lum(unpremultiply(src))));
+}
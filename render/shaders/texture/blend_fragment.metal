@@ -0,0 +1,145 @@
+#include <metal_stdlib>
+
+#import "./bindings/metal_vertex2d.h"
+#import "../simple/rasterizer.metal"
+
+///
+/// The blend functions are defined on colours without pre-multiplication
+///
+float3 unpremultiply(float4 col) {
+    if (col[3] > 0.0) {
+        return metal::min(col.rgb / col[3], float3(1.0));
+    } else {
+        return float3(0.0);
+    }
+}
+
+float3 screen(float3 cs, float3 cb) {
+    return cs + cb - cs * cb;
+}
+
+float3 hard_light(float3 cs, float3 cb) {
+    return metal::select(screen(2.0 * cs - 1.0, cb), cb * 2.0 * cs, cs <= float3(0.5));
+}
+
+float3 color_dodge(float3 cs, float3 cb) {
+    const float3 dodge      = metal::min(cb / metal::max(1.0 - cs, float3(0.00001)), float3(1.0));
+    const float3 full_dodge = metal::select(dodge, float3(1.0), cs >= float3(1.0));
+
+    return metal::select(full_dodge, float3(0.0), cb <= float3(0.0));
+}
+
+float3 color_burn(float3 cs, float3 cb) {
+    const float3 burn       = 1.0 - metal::min((1.0 - cb) / metal::max(cs, float3(0.00001)), float3(1.0));
+    const float3 full_burn  = metal::select(burn, float3(0.0), cs <= float3(0.0));
+
+    return metal::select(full_burn, float3(1.0), cb >= float3(1.0));
+}
+
+float3 soft_light(float3 cs, float3 cb) {
+    const float3 d          = metal::select(metal::sqrt(cb), ((16.0 * cb - 12.0) * cb + 4.0) * cb, cb <= float3(0.25));
+    const float3 darker     = cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb);
+    const float3 lighter    = cb + (2.0 * cs - 1.0) * (d - cb);
+
+    return metal::select(lighter, darker, cs <= float3(0.5));
+}
+
+float lum(float3 col) {
+    return metal::dot(col, float3(0.3, 0.59, 0.11));
+}
+
+float3 clip_color(float3 col) {
+    const float l   = lum(col);
+    const float n   = metal::min(metal::min(col[0], col[1]), col[2]);
+    const float x   = metal::max(metal::max(col[0], col[1]), col[2]);
+
+    if (n < 0.0) {
+        col = l + (col - l) * l / (l - n);
+    }
+    if (x > 1.0) {
+        col = l + (col - l) * (1.0 - l) / (x - l);
+    }
+
+    return col;
+}
+
+float3 set_lum(float3 col, float l) {
+    return clip_color(col + (l - lum(col)));
+}
+
+float sat(float3 col) {
+    return metal::max(metal::max(col[0], col[1]), col[2]) - metal::min(metal::min(col[0], col[1]), col[2]);
+}
+
+float3 set_sat(float3 col, float s) {
+    const float max_col = metal::max(metal::max(col[0], col[1]), col[2]);
+    const float min_col = metal::min(metal::min(col[0], col[1]), col[2]);
+
+    if (max_col > min_col) {
+        return (col - min_col) * s / (max_col - min_col);
+    } else {
+        return float3(0.0);
+    }
+}
+
+///
+/// Blends a pre-multiplied source colour with the destination colour, then composites them using 'source over'
+///
+float4 blend(float4 src, float4 dst, uint blend_mode) {
+    const float3 cs = unpremultiply(src);
+    const float3 cb = unpremultiply(dst);
+    float3 blended;
+
+    switch (blend_mode) {
+        case BlendShaderOverlay:        blended = hard_light(cb, cs); break;
+        case BlendShaderColorDodge:     blended = color_dodge(cs, cb); break;
+        case BlendShaderColorBurn:      blended = color_burn(cs, cb); break;
+        case BlendShaderHardLight:      blended = hard_light(cs, cb); break;
+        case BlendShaderSoftLight:      blended = soft_light(cs, cb); break;
+        case BlendShaderDifference:     blended = metal::abs(cb - cs); break;
+        case BlendShaderHue:            blended = set_lum(set_sat(cs, sat(cb)), lum(cb)); break;
+        case BlendShaderSaturation:     blended = set_lum(set_sat(cb, sat(cs)), lum(cb)); break;
+        case BlendShaderColor:          blended = set_lum(cs, lum(cb)); break;
+        case BlendShaderLuminosity:     blended = set_lum(cb, lum(cs)); break;
+        default:                        blended = cs; break;
+    }
+
+    const float both_alpha  = src[3] * dst[3];
+    const float3 rgb        = src.rgb * (1.0 - dst[3]) + dst.rgb * (1.0 - src[3]) + blended * both_alpha;
+    const float alpha       = src[3] + dst[3] - both_alpha;
+
+    return metal::clamp(float4(rgb, alpha), float4(0.0), float4(1.0));
+}
+
+fragment float4 texture_blend_fragment(
+      RasterizerData              in [[stage_in]],
+      constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
+      constant uint               *blend_mode [[ buffer(FragmentBlendMode) ]],
+      metal::texture2d<half>      texture [[ texture(FragmentIndexTexture) ]],
+      metal::texture2d<half>      destination [[ texture(FragmentIndexDestinationTexture) ]]) {
+    const float4 color          = float4(texture.read(uint2(in.v_TexCoord))) * *texture_alpha;
+    const float4 dst            = float4(destination.read(uint2(in.v_Position.xy)));
+
+    return blend(color, dst, *blend_mode);
+}
+
+fragment float4 texture_multisample_blend_fragment(
+      RasterizerData              in [[stage_in]],
+      constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
+      constant uint               *blend_mode [[ buffer(FragmentBlendMode) ]],
+      metal::texture2d_ms<half>   texture [[ texture(FragmentIndexTexture) ]],
+      metal::texture2d<half>      destination [[ texture(FragmentIndexDestinationTexture) ]]) {
+    const uint num_samples      = texture.get_num_samples();
+    const uint2 tex_coord       = uint2(in.v_TexCoord);
+    half4 color_totals          = half4(0,0,0,0);
+
+    for (uint sample_num=0; sample_num<num_samples; ++sample_num) {
+        const half4 sample      = texture.read(tex_coord, sample_num);
+        color_totals            += sample;
+    }
+
+    const float4 color          = float4(color_totals) / float(num_samples) * *texture_alpha;
+    const float4 dst            = float4(destination.read(uint2(in.v_Position.xy)));
+
+    return blend(color, dst, *blend_mode);
+}
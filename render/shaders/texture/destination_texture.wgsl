@@ -0,0 +1,9 @@
+@group(2)
+@binding(3)
+var f_destination: texture_2d<f32>;
+
+fn destination_color(frag_pos: vec4<f32>) -> vec4<f32> {
+    let pos = vec2<i32>(frag_pos.xy);
+
+    return textureLoad(f_destination, pos, 0);
+}
@@ -5,8 +5,11 @@ struct RasterData {
 }
 
 struct TextureSettings {
-    @location(0)    transform:  mat4x4<f32>,
-    @location(1)    alpha:      f32
+    @location(0)    transform:      mat4x4<f32>,
+    @location(1)    alpha:          f32,
+    @location(2)    focal_x:        f32,
+    @location(3)    focal_y:        f32,
+    @location(4)    focal_radius:   f32
 }
 
 @group(0)
@@ -58,3 +61,68 @@ fn gradient_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
 
     return color;
 }
+
+// Finds the position along a radial gradient where the end circle is the unit circle (the second component is 0 if the point is outside of the gradient)
+fn radial_gradient_pos(pos: vec2<f32>) -> vec2<f32> {
+    let focal_point     = vec2<f32>(texture_settings.focal_x, texture_settings.focal_y);
+    let focal_radius    = texture_settings.focal_radius;
+
+    let cd              = -focal_point;
+    let pd              = pos - focal_point;
+    let dr              = 1.0 - focal_radius;
+
+    let a               = dot(cd, cd) - dr*dr;
+    let b               = dot(pd, cd) + focal_radius*dr;
+    let c               = dot(pd, pd) - focal_radius*focal_radius;
+
+    var result          = vec2<f32>(0.0, 0.0);
+
+    if (abs(a) < 1e-6) {
+        // Focal circle touches the edge of the end circle, so there's only one solution
+        let t = c / (2.0*b);
+        if (focal_radius + t*dr >= 0.0) {
+            result = vec2<f32>(t, 1.0);
+        }
+    } else {
+        let discriminant    = b*b - a*c;
+        let sqrt_disc       = sqrt(max(discriminant, 0.0));
+        let t1              = (b + sqrt_disc) / a;
+        let t2              = (b - sqrt_disc) / a;
+
+        if (discriminant >= 0.0) {
+            if (focal_radius + max(t1, t2)*dr >= 0.0) {
+                result = vec2<f32>(max(t1, t2), 1.0);
+            } else if (focal_radius + min(t1, t2)*dr >= 0.0) {
+                result = vec2<f32>(min(t1, t2), 1.0);
+            }
+        }
+    }
+
+    return result;
+}
+
+@fragment
+fn radial_gradient_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let radial_pos = radial_gradient_pos(vertex.tex_coord);
+
+    var color = textureSample(f_texture, f_sampler, radial_pos[0]);
+    color = alpha_blend(color, texture_settings.alpha * radial_pos[1]);
+
+    color = clip(color, vertex.pos);
+    color = color_post_process(color);
+
+    return color;
+}
+
+@fragment
+fn conic_gradient_fragment_shader(vertex: RasterData) -> @location(0) vec4<f32> {
+    let t = fract(atan2(vertex.tex_coord[1], vertex.tex_coord[0]) / 6.283185307179586);
+
+    var color = textureSample(f_texture, f_sampler, t);
+    color = alpha_blend(color, texture_settings.alpha);
+
+    color = clip(color, vertex.pos);
+    color = color_post_process(color);
+
+    return color;
+}
@@ -5,7 +5,7 @@
 
 typedef struct {
     float4 v_Position [[position]];
-    float v_TexCoord;
+    float2 v_TexCoord;
     float2 v_PaperCoord;
 } GradientData;
 
@@ -21,7 +21,7 @@ vertex GradientData gradient_vertex(
     GradientData data;
 
     data.v_Position     = position;
-    data.v_TexCoord     = tex_coord[0];
+    data.v_TexCoord     = float2(tex_coord[0], tex_coord[1]);
     data.v_PaperCoord   = paper_coord;
 
     return data;
@@ -30,10 +30,10 @@ vertex GradientData gradient_vertex(
 fragment float4 gradient_fragment(
       GradientData                in [[stage_in]],
       constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
-      metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]]) {
-    constexpr metal::sampler texture_sampler (metal::mag_filter::linear, metal::min_filter::linear);
+      metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]],
+      metal::sampler              texture_sampler [[ sampler(FragmentGradientSampler) ]]) {
 
-    const half4 color_sample = texture.sample(texture_sampler, in.v_TexCoord);
+    const half4 color_sample = texture.sample(texture_sampler, in.v_TexCoord[0]);
 
     float4 color  = float4(color_sample);
     color[3]      *= *texture_alpha;
@@ -45,10 +45,10 @@ fragment float4 gradient_clip_mask_multisample_fragment(
       GradientData                in [[stage_in]],
       constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
       metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]],
+      metal::sampler              texture_sampler [[ sampler(FragmentGradientSampler) ]],
       metal::texture2d_ms<half>   clip_mask_texture [[ texture(FragmentIndexClipMaskTexture) ]]) {
     // Color from the gradient
-    constexpr metal::sampler texture_sampler (metal::mag_filter::linear, metal::min_filter::linear);
-    const half4 color_sample    = texture.sample(texture_sampler, in.v_TexCoord);
+    const half4 color_sample    = texture.sample(texture_sampler, in.v_TexCoord[0]);
 
     // Apply the clip mask
     float4 color  = apply_clip_mask(static_cast<float4>(color_sample), in.v_PaperCoord, clip_mask_texture);
@@ -60,10 +60,10 @@ fragment float4 gradient_clip_mask_multisample_fragment(
 fragment float4 gradient_fragment_invert_color_alpha(
       GradientData                in [[stage_in]],
       constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
-      metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]]) {
-    constexpr metal::sampler texture_sampler (metal::mag_filter::linear, metal::min_filter::linear);
+      metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]],
+      metal::sampler              texture_sampler [[ sampler(FragmentGradientSampler) ]]) {
 
-    const half4 color_sample = texture.sample(texture_sampler, in.v_TexCoord);
+    const half4 color_sample = texture.sample(texture_sampler, in.v_TexCoord[0]);
 
     float4 color  = float4(color_sample);
     color[3]      *= *texture_alpha;
@@ -75,10 +75,183 @@ fragment float4 gradient_clip_mask_multisample_fragment_invert_color_alpha(
       GradientData                in [[stage_in]],
       constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
       metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]],
+      metal::sampler              texture_sampler [[ sampler(FragmentGradientSampler) ]],
+      metal::texture2d_ms<half>   clip_mask_texture [[ texture(FragmentIndexClipMaskTexture) ]]) {
+    // Color from the gradient
+    const half4 color_sample    = texture.sample(texture_sampler, in.v_TexCoord[0]);
+
+    // Apply the clip mask
+    float4 color  = apply_clip_mask(static_cast<float4>(color_sample), in.v_PaperCoord, clip_mask_texture);
+    color[3]      *= *texture_alpha;
+
+    return invert_color_alpha(color);
+}
+
+///
+/// Finds the position along a radial gradient where the end circle is the unit circle (the second component is 0 if the point is outside of the gradient)
+///
+float2 radial_gradient_pos(float2 pos, float3 focus) {
+    const float2 focal_point    = float2(focus[0], focus[1]);
+    const float focal_radius    = focus[2];
+
+    const float2 cd             = -focal_point;
+    const float2 pd             = pos - focal_point;
+    const float dr              = 1.0 - focal_radius;
+
+    const float a               = metal::dot(cd, cd) - dr*dr;
+    const float b               = metal::dot(pd, cd) + focal_radius*dr;
+    const float c               = metal::dot(pd, pd) - focal_radius*focal_radius;
+
+    if (metal::abs(a) < 1e-6) {
+        // Focal circle touches the edge of the end circle, so there's only one solution
+        const float t = c / (2.0*b);
+        return (focal_radius + t*dr >= 0.0) ? float2(t, 1.0) : float2(0.0, 0.0);
+    }
+
+    const float discriminant    = b*b - a*c;
+    if (discriminant < 0.0) {
+        return float2(0.0, 0.0);
+    }
+
+    const float t1              = (b + metal::sqrt(discriminant)) / a;
+    const float t2              = (b - metal::sqrt(discriminant)) / a;
+
+    if (focal_radius + metal::max(t1, t2)*dr >= 0.0) {
+        return float2(metal::max(t1, t2), 1.0);
+    } else if (focal_radius + metal::min(t1, t2)*dr >= 0.0) {
+        return float2(metal::min(t1, t2), 1.0);
+    } else {
+        return float2(0.0, 0.0);
+    }
+}
+
+///
+/// Finds the position along a conic gradient that sweeps around the origin
+///
+float conic_gradient_pos(float2 pos) {
+    return metal::fract(metal::atan2(pos[1], pos[0]) / 6.283185307179586);
+}
+
+fragment float4 radial_gradient_fragment(
+      GradientData                in [[stage_in]],
+      constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
+      constant float3             *focus [[ buffer(FragmentGradientFocus) ]],
+      metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]],
+      metal::sampler              texture_sampler [[ sampler(FragmentGradientSampler) ]]) {
+
+    const float2 radial_pos     = radial_gradient_pos(in.v_TexCoord, *focus);
+    const half4 color_sample    = texture.sample(texture_sampler, radial_pos[0]);
+
+    float4 color  = float4(color_sample) * radial_pos[1];
+    color[3]      *= *texture_alpha;
+
+    return color;
+}
+
+fragment float4 radial_gradient_clip_mask_multisample_fragment(
+      GradientData                in [[stage_in]],
+      constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
+      constant float3             *focus [[ buffer(FragmentGradientFocus) ]],
+      metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]],
+      metal::sampler              texture_sampler [[ sampler(FragmentGradientSampler) ]],
+      metal::texture2d_ms<half>   clip_mask_texture [[ texture(FragmentIndexClipMaskTexture) ]]) {
+    // Color from the gradient
+    const float2 radial_pos     = radial_gradient_pos(in.v_TexCoord, *focus);
+    const half4 color_sample    = texture.sample(texture_sampler, radial_pos[0]);
+
+    // Apply the clip mask
+    float4 color  = apply_clip_mask(static_cast<float4>(color_sample) * radial_pos[1], in.v_PaperCoord, clip_mask_texture);
+    color[3]      *= *texture_alpha;
+
+    return color;
+}
+
+fragment float4 radial_gradient_fragment_invert_color_alpha(
+      GradientData                in [[stage_in]],
+      constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
+      constant float3             *focus [[ buffer(FragmentGradientFocus) ]],
+      metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]],
+      metal::sampler              texture_sampler [[ sampler(FragmentGradientSampler) ]]) {
+
+    const float2 radial_pos     = radial_gradient_pos(in.v_TexCoord, *focus);
+    const half4 color_sample    = texture.sample(texture_sampler, radial_pos[0]);
+
+    float4 color  = float4(color_sample) * radial_pos[1];
+    color[3]      *= *texture_alpha;
+
+    return invert_color_alpha(color);
+}
+
+fragment float4 radial_gradient_clip_mask_multisample_fragment_invert_color_alpha(
+      GradientData                in [[stage_in]],
+      constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
+      constant float3             *focus [[ buffer(FragmentGradientFocus) ]],
+      metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]],
+      metal::sampler              texture_sampler [[ sampler(FragmentGradientSampler) ]],
+      metal::texture2d_ms<half>   clip_mask_texture [[ texture(FragmentIndexClipMaskTexture) ]]) {
+    // Color from the gradient
+    const float2 radial_pos     = radial_gradient_pos(in.v_TexCoord, *focus);
+    const half4 color_sample    = texture.sample(texture_sampler, radial_pos[0]);
+
+    // Apply the clip mask
+    float4 color  = apply_clip_mask(static_cast<float4>(color_sample) * radial_pos[1], in.v_PaperCoord, clip_mask_texture);
+    color[3]      *= *texture_alpha;
+
+    return invert_color_alpha(color);
+}
+
+fragment float4 conic_gradient_fragment(
+      GradientData                in [[stage_in]],
+      constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
+      metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]],
+      metal::sampler              texture_sampler [[ sampler(FragmentGradientSampler) ]]) {
+
+    const half4 color_sample = texture.sample(texture_sampler, conic_gradient_pos(in.v_TexCoord));
+
+    float4 color  = float4(color_sample);
+    color[3]      *= *texture_alpha;
+
+    return color;
+}
+
+fragment float4 conic_gradient_clip_mask_multisample_fragment(
+      GradientData                in [[stage_in]],
+      constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
+      metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]],
+      metal::sampler              texture_sampler [[ sampler(FragmentGradientSampler) ]],
+      metal::texture2d_ms<half>   clip_mask_texture [[ texture(FragmentIndexClipMaskTexture) ]]) {
+    // Color from the gradient
+    const half4 color_sample    = texture.sample(texture_sampler, conic_gradient_pos(in.v_TexCoord));
+
+    // Apply the clip mask
+    float4 color  = apply_clip_mask(static_cast<float4>(color_sample), in.v_PaperCoord, clip_mask_texture);
+    color[3]      *= *texture_alpha;
+
+    return color;
+}
+
+fragment float4 conic_gradient_fragment_invert_color_alpha(
+      GradientData                in [[stage_in]],
+      constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
+      metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]],
+      metal::sampler              texture_sampler [[ sampler(FragmentGradientSampler) ]]) {
+
+    const half4 color_sample = texture.sample(texture_sampler, conic_gradient_pos(in.v_TexCoord));
+
+    float4 color  = float4(color_sample);
+    color[3]      *= *texture_alpha;
+
+    return invert_color_alpha(color);
+}
+
+fragment float4 conic_gradient_clip_mask_multisample_fragment_invert_color_alpha(
+      GradientData                in [[stage_in]],
+      constant float              *texture_alpha [[ buffer(FragmentAlpha) ]],
+      metal::texture1d<half>      texture [[ texture(FragmentIndexTexture) ]],
+      metal::sampler              texture_sampler [[ sampler(FragmentGradientSampler) ]],
       metal::texture2d_ms<half>   clip_mask_texture [[ texture(FragmentIndexClipMaskTexture) ]]) {
     // Color from the gradient
-    constexpr metal::sampler texture_sampler (metal::mag_filter::linear, metal::min_filter::linear);
-    const half4 color_sample    = texture.sample(texture_sampler, in.v_TexCoord);
+    const half4 color_sample    = texture.sample(texture_sampler, conic_gradient_pos(in.v_TexCoord));
 
     // Apply the clip mask
     float4 color  = apply_clip_mask(static_cast<float4>(color_sample), in.v_PaperCoord, clip_mask_texture);
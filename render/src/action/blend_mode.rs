@@ -5,7 +5,13 @@
  */
 
 ///
-/// The blending modes that the renderer must support (most of the Porter-Duff modes)
+/// The blending modes that the renderer must support (the Porter-Duff modes, plus the W3C compositing modes)
+///
+/// The OpenGL, wgpu and Metal renderers use the fixed-function blending hardware for most modes. The modes that need to
+/// read the destination colour (see `reads_destination()`) are only supported by `DrawFrameBuffer`, which blends against
+/// a copy of the render target in the shader: triangles drawn with one of these modes are drawn using `SourceOver`
+/// instead. The software renderer supports every mode everywhere. With the hardware renderers, `Darken` and `Lighten`
+/// are exact when the source is opaque, and `Exclusion` is exact when the destination is opaque.
 ///
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum BlendMode {
@@ -17,10 +23,53 @@ pub enum BlendMode {
     DestinationOut,
     SourceATop,
     DestinationATop,
+    Xor,
+    Clear,
+    Copy,
+    Destination,
 
     Screen,
     Multiply,
+    Darken,
+    Lighten,
+    Overlay,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+    Plus,
 
     AllChannelAlphaSourceOver,
     AllChannelAlphaDestinationOver,
 }
+
+impl BlendMode {
+    ///
+    /// True if this blend mode needs to read the destination colour in the shader
+    ///
+    /// The hardware renderers can only use these modes with `DrawFrameBuffer`
+    ///
+    pub fn reads_destination(&self) -> bool {
+        use self::BlendMode::*;
+
+        matches!(
+            self,
+            Overlay
+                | ColorDodge
+                | ColorBurn
+                | HardLight
+                | SoftLight
+                | Difference
+                | Hue
+                | Saturation
+                | Color
+                | Luminosity
+        )
+    }
+}
@@ -8,6 +8,21 @@ use super::identities::*;
 
 use crate::buffer::*;
 
+///
+/// How a gradient shader extends the gradient texture beyond the range 0-1
+///
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GradientSpread {
+    /// The colours at either end of the texture are used outside of the gradient
+    Pad,
+
+    /// The texture is repeated
+    Repeat,
+
+    /// The texture is repeated, and mirrored on every other repetition
+    Reflect,
+}
+
 ///
 /// The shaders that can be chosen for the renderer
 ///
@@ -36,7 +51,33 @@ pub enum ShaderType {
     LinearGradient {
         texture: TextureId,
         texture_transform: Matrix,
-        repeat: bool,
+        spread: GradientSpread,
+        alpha: f32,
+        clip_texture: Option<TextureId>,
+    },
+
+    /// Colour derived from a 1D texture using a two-circle radial gradient
+    ///
+    /// The texture transform maps canvas coordinates to a space where the end circle of the gradient is the unit circle. The
+    /// start circle is described by the focal point and radius in that same space.
+    RadialGradient {
+        texture: TextureId,
+        texture_transform: Matrix,
+        focal_point: (f32, f32),
+        focal_radius: f32,
+        spread: GradientSpread,
+        alpha: f32,
+        clip_texture: Option<TextureId>,
+    },
+
+    /// Colour derived from a 1D texture using the angle around a point (used for rendering conic gradients)
+    ///
+    /// The texture transform maps canvas coordinates to a space where the center of the gradient is the origin and
+    /// the gradient starts along the x axis.
+    ConicGradient {
+        texture: TextureId,
+        texture_transform: Matrix,
+        spread: GradientSpread,
         alpha: f32,
         clip_texture: Option<TextureId>,
     },
@@ -76,13 +117,43 @@ impl ShaderType {
             LinearGradient {
                 texture,
                 texture_transform,
-                repeat,
+                spread,
                 alpha,
                 clip_texture: _,
             } => LinearGradient {
                 texture: texture,
                 texture_transform: texture_transform,
-                repeat,
+                spread,
+                alpha,
+                clip_texture: new_clip_mask_texture,
+            },
+            RadialGradient {
+                texture,
+                texture_transform,
+                focal_point,
+                focal_radius,
+                spread,
+                alpha,
+                clip_texture: _,
+            } => RadialGradient {
+                texture,
+                texture_transform,
+                focal_point,
+                focal_radius,
+                spread,
+                alpha,
+                clip_texture: new_clip_mask_texture,
+            },
+            ConicGradient {
+                texture,
+                texture_transform,
+                spread,
+                alpha,
+                clip_texture: _,
+            } => ConicGradient {
+                texture,
+                texture_transform,
+                spread,
                 alpha,
                 clip_texture: new_clip_mask_texture,
             },
@@ -377,7 +377,10 @@ impl GlRenderer {
             if !source_is_premultiplied {
                 // Target will be pre-multiplied after blending
                 match blend_mode {
-                    SourceOver => gl::BlendFuncSeparate(
+                    // These modes read the destination colour, which is only possible when drawing a frame buffer (see
+                    // `StandardShaderProgram::MsaaBlend`), so they fall back to 'source over' everywhere else
+                    SourceOver | Overlay | ColorDodge | ColorBurn | HardLight | SoftLight
+                    | Difference | Hue | Saturation | Color | Luminosity => gl::BlendFuncSeparate(
                         gl::SRC_ALPHA,
                         gl::ONE_MINUS_SRC_ALPHA,
                         gl::ONE,
@@ -419,6 +422,15 @@ impl GlRenderer {
                         gl::ONE_MINUS_DST_ALPHA,
                         gl::ONE_MINUS_SRC_ALPHA,
                     ),
+                    Xor => gl::BlendFuncSeparate(
+                        gl::ONE_MINUS_DST_ALPHA,
+                        gl::ONE_MINUS_SRC_ALPHA,
+                        gl::ONE_MINUS_DST_ALPHA,
+                        gl::ONE_MINUS_SRC_ALPHA,
+                    ),
+                    Clear => gl::BlendFuncSeparate(gl::ZERO, gl::ZERO, gl::ZERO, gl::ZERO),
+                    Copy => gl::BlendFuncSeparate(gl::SRC_ALPHA, gl::ZERO, gl::ONE, gl::ZERO),
+                    Destination => gl::BlendFuncSeparate(gl::ZERO, gl::ONE, gl::ZERO, gl::ONE),
 
                     // Multiply is a*b. Here we multiply the source colour by the destination colour, then blend the destination back in again to take account of
                     // alpha in the source layer (this version of multiply has no effect on the target alpha value: a more strict version might multiply those too)
@@ -436,6 +448,27 @@ impl GlRenderer {
                         gl::BlendFuncSeparate(gl::ONE_MINUS_DST_COLOR, gl::ONE, gl::ZERO, gl::ONE);
                     }
 
+                    // Darken and lighten pick the minimum or maximum colour (the blend factors are ignored). The source colour is
+                    // precalculated so that transparent pixels leave the destination alone
+                    Darken => {
+                        gl::BlendEquationSeparate(gl::MIN, gl::FUNC_ADD);
+                        gl::BlendFuncSeparate(gl::ONE, gl::ONE, gl::ONE, gl::ONE_MINUS_SRC_ALPHA);
+                    }
+                    Lighten => {
+                        gl::BlendEquationSeparate(gl::MAX, gl::FUNC_ADD);
+                        gl::BlendFuncSeparate(gl::ONE, gl::ONE, gl::ONE, gl::ONE_MINUS_SRC_ALPHA);
+                    }
+
+                    // Exclusion is a+b-2ab, which is the same as a*(1-b) + b*(1-a). This is exact when the destination is opaque.
+                    Exclusion => gl::BlendFuncSeparate(
+                        gl::ONE_MINUS_DST_COLOR,
+                        gl::ONE_MINUS_SRC_COLOR,
+                        gl::ONE,
+                        gl::ONE_MINUS_SRC_ALPHA,
+                    ),
+
+                    Plus => gl::BlendFuncSeparate(gl::SRC_ALPHA, gl::ONE, gl::ONE, gl::ONE),
+
                     AllChannelAlphaSourceOver => gl::BlendFuncSeparate(
                         gl::ONE,
                         gl::ONE_MINUS_SRC_COLOR,
@@ -452,7 +485,10 @@ impl GlRenderer {
             } else {
                 // Source is already pre-multiplied
                 match blend_mode {
-                    SourceOver => gl::BlendFuncSeparate(
+                    // These modes read the destination colour, which is only possible when drawing a frame buffer (see
+                    // `StandardShaderProgram::MsaaBlend`), so they fall back to 'source over' everywhere else
+                    SourceOver | Overlay | ColorDodge | ColorBurn | HardLight | SoftLight
+                    | Difference | Hue | Saturation | Color | Luminosity => gl::BlendFuncSeparate(
                         gl::ONE,
                         gl::ONE_MINUS_SRC_ALPHA,
                         gl::ONE,
@@ -494,6 +530,15 @@ impl GlRenderer {
                         gl::ONE_MINUS_DST_ALPHA,
                         gl::ONE_MINUS_SRC_ALPHA,
                     ),
+                    Xor => gl::BlendFuncSeparate(
+                        gl::ONE_MINUS_DST_ALPHA,
+                        gl::ONE_MINUS_SRC_ALPHA,
+                        gl::ONE_MINUS_DST_ALPHA,
+                        gl::ONE_MINUS_SRC_ALPHA,
+                    ),
+                    Clear => gl::BlendFuncSeparate(gl::ZERO, gl::ZERO, gl::ZERO, gl::ZERO),
+                    Copy => gl::BlendFuncSeparate(gl::ONE, gl::ZERO, gl::ONE, gl::ZERO),
+                    Destination => gl::BlendFuncSeparate(gl::ZERO, gl::ONE, gl::ZERO, gl::ONE),
 
                     Multiply => gl::BlendFuncSeparate(gl::DST_COLOR, gl::ZERO, gl::ZERO, gl::ONE),
 
@@ -503,6 +548,24 @@ impl GlRenderer {
                         gl::BlendFuncSeparate(gl::ONE_MINUS_DST_COLOR, gl::ONE, gl::ZERO, gl::ONE);
                     }
 
+                    Darken => {
+                        gl::BlendEquationSeparate(gl::MIN, gl::FUNC_ADD);
+                        gl::BlendFuncSeparate(gl::ONE, gl::ONE, gl::ONE, gl::ONE_MINUS_SRC_ALPHA);
+                    }
+                    Lighten => {
+                        gl::BlendEquationSeparate(gl::MAX, gl::FUNC_ADD);
+                        gl::BlendFuncSeparate(gl::ONE, gl::ONE, gl::ONE, gl::ONE_MINUS_SRC_ALPHA);
+                    }
+
+                    Exclusion => gl::BlendFuncSeparate(
+                        gl::ONE_MINUS_DST_COLOR,
+                        gl::ONE_MINUS_SRC_COLOR,
+                        gl::ONE,
+                        gl::ONE_MINUS_SRC_ALPHA,
+                    ),
+
+                    Plus => gl::BlendFuncSeparate(gl::ONE, gl::ONE, gl::ONE, gl::ONE),
+
                     AllChannelAlphaSourceOver => gl::BlendFuncSeparate(
                         gl::ONE,
                         gl::ONE_MINUS_SRC_COLOR,
@@ -1170,15 +1233,47 @@ impl GlRenderer {
         // Use the pre-multiplied version of the blend mode
         self.blend_mode(self.blend_mode, true);
 
+        // The blend modes that read the destination colour use a shader that reads from a copy of the render target
+        let blend_mode = self.blend_mode;
+        let destination = if blend_mode.reads_destination() {
+            self.copy_render_target()
+        } else {
+            None
+        };
+
         let shaders = &mut self.shader_programs;
         self.render_targets[source_buffer]
             .as_ref()
             .map(|source_buffer| {
                 unsafe {
                     if let Some(texture) = source_buffer.texture() {
-                        // Activate the resolving program
-                        let shader = shaders
-                            .use_program(StandardShaderProgram::MsaaResolve(4, post_process));
+                        // Activate the resolving program (or the blending program if there's a copy of the destination)
+                        let shader = if let Some(destination) = destination
+                            .as_ref()
+                            .and_then(|destination| destination.texture())
+                        {
+                            // The blend shader generates the final colour, so the blending hardware is not used
+                            gl::Disable(gl::BLEND);
+
+                            let shader =
+                                shaders.use_program(StandardShaderProgram::MsaaBlend(blend_mode));
+
+                            gl::ActiveTexture(gl::TEXTURE1);
+                            gl::BindTexture(gl::TEXTURE_2D, *destination);
+
+                            shader
+                                .uniform_location(
+                                    ShaderUniform::MsaaDestination,
+                                    "t_DestinationTexture",
+                                )
+                                .map(|destination_texture| {
+                                    gl::Uniform1i(destination_texture, 1);
+                                });
+
+                            shader
+                        } else {
+                            shaders.use_program(StandardShaderProgram::MsaaResolve(4, post_process))
+                        };
 
                         // Set the texture for the render buffer
                         gl::ActiveTexture(gl::TEXTURE0);
@@ -1257,10 +1352,59 @@ impl GlRenderer {
         shaders.use_program(StandardShaderProgram::default());
         self.blend_mode(self.blend_mode, was_premultiplied);
 
+        unsafe {
+            gl::Enable(gl::BLEND);
+        }
+
         // Finish up by checking for errors
         panic_on_gl_error("Draw frame buffer");
     }
 
+    ///
+    /// Copies the current render target to a new render target, so the blend shader can read the destination colour
+    ///
+    /// Multisampled render targets are resolved as they are copied, so the copy is always backed by a standard texture
+    ///
+    fn copy_render_target(&self) -> Option<RenderTarget> {
+        let target = if let Some(RenderTargetId(render_id)) = self.active_render_target {
+            self.render_targets[render_id].as_ref()?
+        } else {
+            self.default_render_target.as_ref()?
+        };
+
+        let (width, height) = target.get_size();
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let copy = RenderTarget::new(width, height, RenderTargetType::Standard);
+
+        unsafe {
+            // Blit the render target into the copy (this also resolves MSAA render targets)
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, **target);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, *copy);
+            gl::BlitFramebuffer(
+                0,
+                0,
+                width as i32,
+                height as i32,
+                0,
+                0,
+                width as i32,
+                height as i32,
+                gl::COLOR_BUFFER_BIT,
+                gl::NEAREST,
+            );
+
+            // Carry on rendering to the original render target
+            gl::BindFramebuffer(gl::FRAMEBUFFER, **target);
+        }
+
+        panic_on_gl_error("Copy render target");
+
+        Some(copy)
+    }
+
     ///
     /// Releases an existing render target
     ///
@@ -1274,12 +1418,16 @@ impl GlRenderer {
     fn post_processing_for_blend_mode(
         &self,
         blend_mode: BlendMode,
-        _source_is_premultiplied: bool,
+        source_is_premultiplied: bool,
     ) -> ColorPostProcessingStep {
         match blend_mode {
-            BlendMode::Multiply => ColorPostProcessingStep::InvertColorAlpha,
+            BlendMode::Multiply | BlendMode::Darken => ColorPostProcessingStep::InvertColorAlpha,
             BlendMode::Screen => ColorPostProcessingStep::MultiplyAlpha,
 
+            BlendMode::Lighten | BlendMode::Exclusion if !source_is_premultiplied => {
+                ColorPostProcessingStep::MultiplyAlpha
+            }
+
             _ => ColorPostProcessingStep::NoPostProcessing,
         }
     }
@@ -1376,6 +1524,34 @@ impl GlRenderer {
                 post_processing,
             )),
 
+            Some(RadialGradient {
+                clip_texture: None, ..
+            }) => Some(StandardShaderProgram::RadialGradient(
+                StandardShaderVariant::NoClipping,
+                post_processing,
+            )),
+            Some(RadialGradient {
+                clip_texture: Some(_),
+                ..
+            }) => Some(StandardShaderProgram::RadialGradient(
+                StandardShaderVariant::ClippingMask,
+                post_processing,
+            )),
+
+            Some(ConicGradient {
+                clip_texture: None, ..
+            }) => Some(StandardShaderProgram::ConicGradient(
+                StandardShaderVariant::NoClipping,
+                post_processing,
+            )),
+            Some(ConicGradient {
+                clip_texture: Some(_),
+                ..
+            }) => Some(StandardShaderProgram::ConicGradient(
+                StandardShaderVariant::ClippingMask,
+                post_processing,
+            )),
+
             None => None,
         }
     }
@@ -1557,92 +1733,66 @@ impl GlRenderer {
             LinearGradient {
                 texture,
                 texture_transform,
-                repeat,
+                spread,
                 alpha,
                 clip_texture,
             } => {
-                let textures = &self.textures;
-                let TextureId(texture) = texture;
-                let texture = if texture < self.textures.len() {
-                    self.textures[texture].as_ref()
-                } else {
-                    None
-                };
-                let clip_texture =
-                    clip_texture.and_then(|TextureId(texture_id)| textures[texture_id].as_ref());
-                let variant = if clip_texture.is_some() {
-                    StandardShaderVariant::ClippingMask
-                } else {
-                    StandardShaderVariant::NoClipping
-                };
-                let texture_transform = texture_transform.to_opengl_matrix();
-
-                let program = self
-                    .shader_programs
-                    .use_program(StandardShaderProgram::LinearGradient(variant, premultiply));
-                if let Some(clip_texture) = clip_texture {
-                    program.use_texture(ShaderUniform::ClipTexture, "t_ClipMask", clip_texture, 2);
-                }
+                self.use_gradient_shader(
+                    StandardShaderProgram::LinearGradient,
+                    premultiply,
+                    texture,
+                    texture_transform,
+                    spread,
+                    alpha,
+                    clip_texture,
+                    None,
+                );
 
-                // Set up the texture program
-                if let Some(texture) = texture {
-                    unsafe {
-                        // Bind the texture to texture 0
-                        gl::ActiveTexture(gl::TEXTURE0);
-                        gl::BindTexture(gl::TEXTURE_1D, **texture);
+                panic_on_gl_error("Set linear gradient shader");
+            }
 
-                        gl::TexParameteri(
-                            gl::TEXTURE_1D,
-                            gl::TEXTURE_MIN_FILTER,
-                            gl::LINEAR_MIPMAP_LINEAR as _,
-                        );
-                        gl::TexParameteri(gl::TEXTURE_1D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as _);
+            RadialGradient {
+                texture,
+                texture_transform,
+                focal_point,
+                focal_radius,
+                spread,
+                alpha,
+                clip_texture,
+            } => {
+                self.use_gradient_shader(
+                    StandardShaderProgram::RadialGradient,
+                    premultiply,
+                    texture,
+                    texture_transform,
+                    spread,
+                    alpha,
+                    clip_texture,
+                    Some((focal_point, focal_radius)),
+                );
 
-                        if repeat {
-                            gl::TexParameteri(gl::TEXTURE_1D, gl::TEXTURE_WRAP_S, gl::REPEAT as _);
-                            gl::TexParameteri(gl::TEXTURE_1D, gl::TEXTURE_WRAP_T, gl::REPEAT as _);
-                        } else {
-                            gl::TexParameteri(
-                                gl::TEXTURE_1D,
-                                gl::TEXTURE_WRAP_S,
-                                gl::CLAMP_TO_EDGE as _,
-                            );
-                            gl::TexParameteri(
-                                gl::TEXTURE_1D,
-                                gl::TEXTURE_WRAP_T,
-                                gl::CLAMP_TO_EDGE as _,
-                            );
-                        }
+                panic_on_gl_error("Set radial gradient shader");
+            }
 
-                        // Set in the program uniform
-                        program
-                            .uniform_location(ShaderUniform::Texture, "t_Texture")
-                            .map(|texture_uniform| {
-                                gl::Uniform1i(texture_uniform, 0);
-                            });
-                        program
-                            .uniform_location(ShaderUniform::TextureTransform, "texture_transform")
-                            .map(|transform_uniform| {
-                                gl::UniformMatrix4fv(
-                                    transform_uniform,
-                                    1,
-                                    gl::FALSE,
-                                    texture_transform.as_ptr(),
-                                );
-                            });
-                        program
-                            .uniform_location(ShaderUniform::TextureAlpha, "texture_alpha")
-                            .map(|alpha_uniform| {
-                                gl::Uniform1f(alpha_uniform, alpha);
-                            });
-                    }
-                } else {
-                    // Texture not found: revert to the simple shader
-                    self.shader_programs
-                        .use_program(StandardShaderProgram::default());
-                }
+            ConicGradient {
+                texture,
+                texture_transform,
+                spread,
+                alpha,
+                clip_texture,
+            } => {
+                self.use_gradient_shader(
+                    StandardShaderProgram::ConicGradient,
+                    premultiply,
+                    texture,
+                    texture_transform,
+                    spread,
+                    alpha,
+                    clip_texture,
+                    None,
+                );
 
-                panic_on_gl_error("Set linear gradient shader");
+                panic_on_gl_error("Set conic gradient shader");
             }
         }
 
@@ -1656,6 +1806,110 @@ impl GlRenderer {
         }
     }
 
+    ///
+    /// Selects one of the gradient shaders, binding the 1D gradient texture and setting up its uniforms
+    ///
+    /// The focal point and radius are only used by the radial gradient shader.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    fn use_gradient_shader(
+        &mut self,
+        program: fn(StandardShaderVariant, ColorPostProcessingStep) -> StandardShaderProgram,
+        premultiply: ColorPostProcessingStep,
+        texture: TextureId,
+        texture_transform: Matrix,
+        spread: GradientSpread,
+        alpha: f32,
+        clip_texture: Option<TextureId>,
+        focal: Option<((f32, f32), f32)>,
+    ) {
+        let textures = &self.textures;
+        let TextureId(texture) = texture;
+        let texture = if texture < self.textures.len() {
+            self.textures[texture].as_ref()
+        } else {
+            None
+        };
+        let clip_texture =
+            clip_texture.and_then(|TextureId(texture_id)| textures[texture_id].as_ref());
+        let variant = if clip_texture.is_some() {
+            StandardShaderVariant::ClippingMask
+        } else {
+            StandardShaderVariant::NoClipping
+        };
+        let texture_transform = texture_transform.to_opengl_matrix();
+
+        let program = self
+            .shader_programs
+            .use_program(program(variant, premultiply));
+        if let Some(clip_texture) = clip_texture {
+            program.use_texture(ShaderUniform::ClipTexture, "t_ClipMask", clip_texture, 2);
+        }
+
+        // Set up the texture program
+        if let Some(texture) = texture {
+            unsafe {
+                // Bind the texture to texture 0
+                gl::ActiveTexture(gl::TEXTURE0);
+                gl::BindTexture(gl::TEXTURE_1D, **texture);
+
+                gl::TexParameteri(
+                    gl::TEXTURE_1D,
+                    gl::TEXTURE_MIN_FILTER,
+                    gl::LINEAR_MIPMAP_LINEAR as _,
+                );
+                gl::TexParameteri(gl::TEXTURE_1D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as _);
+
+                let wrap_mode = match spread {
+                    GradientSpread::Pad => gl::CLAMP_TO_EDGE,
+                    GradientSpread::Repeat => gl::REPEAT,
+                    GradientSpread::Reflect => gl::MIRRORED_REPEAT,
+                };
+                gl::TexParameteri(gl::TEXTURE_1D, gl::TEXTURE_WRAP_S, wrap_mode as _);
+                gl::TexParameteri(gl::TEXTURE_1D, gl::TEXTURE_WRAP_T, wrap_mode as _);
+
+                // Set in the program uniform
+                program
+                    .uniform_location(ShaderUniform::Texture, "t_Texture")
+                    .map(|texture_uniform| {
+                        gl::Uniform1i(texture_uniform, 0);
+                    });
+                program
+                    .uniform_location(ShaderUniform::TextureTransform, "texture_transform")
+                    .map(|transform_uniform| {
+                        gl::UniformMatrix4fv(
+                            transform_uniform,
+                            1,
+                            gl::FALSE,
+                            texture_transform.as_ptr(),
+                        );
+                    });
+                program
+                    .uniform_location(ShaderUniform::TextureAlpha, "texture_alpha")
+                    .map(|alpha_uniform| {
+                        gl::Uniform1f(alpha_uniform, alpha);
+                    });
+
+                if let Some(((focal_x, focal_y), focal_radius)) = focal {
+                    program
+                        .uniform_location(ShaderUniform::GradientFocalPoint, "focal_point")
+                        .map(|focal_point_uniform| {
+                            gl::Uniform2f(focal_point_uniform, focal_x, focal_y);
+                        });
+                    program
+                        .uniform_location(ShaderUniform::GradientFocalRadius, "focal_radius")
+                        .map(|focal_radius_uniform| {
+                            gl::Uniform1f(focal_radius_uniform, focal_radius);
+                        });
+                }
+            }
+        } else {
+            // Texture not found: revert to the simple shader
+            self.shader_programs
+                .use_program(StandardShaderProgram::default());
+        }
+    }
+
     ///
     /// Draw triangles from a buffer
     ///
@@ -27,12 +27,21 @@ pub enum ShaderUniform {
     /// The alpha adjustment applied to the texture colour
     TextureAlpha,
 
+    /// The center of the start circle of a radial gradient
+    GradientFocalPoint,
+
+    /// The radius of the start circle of a radial gradient
+    GradientFocalRadius,
+
     /// The texture for a MSAA shader
     MsaaTexture,
 
     /// The alpha value to use for a MSAA shader
     MsaaAlpha,
 
+    /// The copy of the destination read by the MSAA blend shader
+    MsaaDestination,
+
     /// The weights for the gaussian blur shader
     BlurWeights,
 
@@ -7,6 +7,7 @@
 use super::shader::*;
 use super::shader_program::*;
 use super::shader_uniforms::*;
+use crate::action::BlendMode;
 
 ///
 /// The variants that every shader must have
@@ -77,12 +78,21 @@ pub enum StandardShaderProgram {
     /// Uses a 1D texture input to render a linear gradient fill
     LinearGradient(StandardShaderVariant, ColorPostProcessingStep),
 
+    /// Uses a 1D texture input to render a two-circle radial gradient fill
+    RadialGradient(StandardShaderVariant, ColorPostProcessingStep),
+
+    /// Uses a 1D texture input to render a conic gradient fill
+    ConicGradient(StandardShaderVariant, ColorPostProcessingStep),
+
     /// Uses a 1D texture to draw dashed lines
     DashedLine(StandardShaderVariant, ColorPostProcessingStep),
 
     /// Texture renderer that resolves MSAA textures 1-to-1 with the given number of samples
     MsaaResolve(u8, ColorPostProcessingStep),
 
+    /// Blends a 4-sample MSAA texture with a copy of the destination, for the blend modes that read the destination colour
+    MsaaBlend(BlendMode),
+
     /// Turns a texture without pre-multiplied alpha into one with pre-multiplied alpha
     PremultiplyAlpha,
 
@@ -169,6 +179,25 @@ impl FilterSourceFormat {
     }
 }
 
+///
+/// Returns the #defines to declare in the blend shader program for a blend mode
+///
+fn blend_mode_defines(blend_mode: BlendMode) -> Vec<&'static str> {
+    match blend_mode {
+        BlendMode::Overlay => vec!["BLEND_OVERLAY"],
+        BlendMode::ColorDodge => vec!["BLEND_COLOR_DODGE"],
+        BlendMode::ColorBurn => vec!["BLEND_COLOR_BURN"],
+        BlendMode::HardLight => vec!["BLEND_HARD_LIGHT"],
+        BlendMode::SoftLight => vec!["BLEND_SOFT_LIGHT"],
+        BlendMode::Difference => vec!["BLEND_DIFFERENCE"],
+        BlendMode::Hue => vec!["BLEND_HUE"],
+        BlendMode::Saturation => vec!["BLEND_SATURATION"],
+        BlendMode::Color => vec!["BLEND_COLOR"],
+        BlendMode::Luminosity => vec!["BLEND_LUMINOSITY"],
+        _ => vec![],
+    }
+}
+
 impl Default for StandardShaderProgram {
     fn default() -> Self {
         StandardShaderProgram::Simple(
@@ -240,6 +269,9 @@ impl StandardShaderProgram {
             include_bytes!["../../shaders/simple/multisample_resolve_4.glslf"].to_vec(),
         )
         .unwrap();
+        let msaa_blend =
+            String::from_utf8(include_bytes!["../../shaders/texture/blend.glslf"].to_vec())
+                .unwrap();
         let filter_vertex =
             String::from_utf8(include_bytes!["../../shaders/simple/resolve.glslv"].to_vec())
                 .unwrap();
@@ -306,6 +338,30 @@ impl StandardShaderProgram {
                         .chain(post_process.defines())
                         .collect(),
                 ),
+                RadialGradient(variant, post_process) => Self::load_shader(
+                    &gradient_vertex,
+                    &vec!["a_Pos", "a_Color", "a_TexCoord"],
+                    &gradient_fragment,
+                    &vec![],
+                    &variant
+                        .defines()
+                        .into_iter()
+                        .chain(post_process.defines())
+                        .chain(vec!["RADIAL_GRADIENT"])
+                        .collect(),
+                ),
+                ConicGradient(variant, post_process) => Self::load_shader(
+                    &gradient_vertex,
+                    &vec!["a_Pos", "a_Color", "a_TexCoord"],
+                    &gradient_fragment,
+                    &vec![],
+                    &variant
+                        .defines()
+                        .into_iter()
+                        .chain(post_process.defines())
+                        .chain(vec!["CONIC_GRADIENT"])
+                        .collect(),
+                ),
                 DashedLine(variant, post_process) => Self::load_shader(
                     &simple_vertex,
                     &vec!["a_Pos", "a_Color", "a_TexCoord"],
@@ -328,6 +384,13 @@ impl StandardShaderProgram {
                 MsaaResolve(_num_samples, _post_process) => {
                     unimplemented!()
                 }
+                MsaaBlend(blend_mode) => Self::load_shader(
+                    &msaa_vertex,
+                    &vec![],
+                    &msaa_blend,
+                    &vec![],
+                    &blend_mode_defines(blend_mode),
+                ),
 
                 PremultiplyAlpha => {
                     Self::load_shader(&filter_vertex, &vec![], &premultiply, &vec![], &vec![])
@@ -11,6 +11,7 @@ mod gl_renderer;
 #[cfg(feature = "osx-metal")]
 mod metal_renderer;
 mod offscreen;
+mod software_renderer;
 #[cfg(feature = "render-wgpu")]
 mod wgpu_renderer;
 
@@ -24,6 +25,7 @@ pub use self::gl_renderer::GlRenderer;
 #[cfg(feature = "osx-metal")]
 pub use self::metal_renderer::MetalRenderer;
 pub use self::offscreen::*;
+pub use self::software_renderer::SoftwareRenderer;
 #[cfg(feature = "render-wgpu")]
 pub use self::wgpu_renderer::WgpuRenderer;
 
@@ -18,6 +18,7 @@ use flo_canvas;
 use metal;
 
 use std::collections::HashMap;
+use std::mem;
 use std::ops::Range;
 use std::sync::*;
 
@@ -51,6 +52,9 @@ pub struct MetalRenderer {
 
     /// The cache of render pipeline states used by this renderer
     pipeline_states: HashMap<PipelineConfiguration, metal::RenderPipelineState>,
+
+    /// The cache of samplers used to read gradient textures with each type of spread
+    gradient_samplers: HashMap<GradientSpread, metal::SamplerState>,
 }
 
 ///
@@ -78,6 +82,12 @@ struct RenderState<'a> {
     /// The alpha value to apply to the texture
     texture_alpha: Option<f64>,
 
+    /// The focal point and radius for a radial gradient (the last component is padding)
+    gradient_focus: Option<[f32; 4]>,
+
+    /// The sampler used to read a gradient texture
+    gradient_sampler: Option<metal::SamplerState>,
+
     /// The active pipeline configuration
     pipeline_config: PipelineConfiguration,
 
@@ -112,6 +122,7 @@ impl MetalRenderer {
             textures: vec![],
             shader_library: shader_library,
             pipeline_states: HashMap::new(),
+            gradient_samplers: HashMap::new(),
         }
     }
 
@@ -135,6 +146,7 @@ impl MetalRenderer {
             textures: vec![],
             shader_library: shader_library,
             pipeline_states: HashMap::new(),
+            gradient_samplers: HashMap::new(),
         }
     }
 
@@ -158,6 +170,31 @@ impl MetalRenderer {
         }
     }
 
+    ///
+    /// Returns the sampler to use for a gradient texture with a particular spread
+    ///
+    fn get_gradient_sampler(&mut self, spread: GradientSpread) -> metal::SamplerState {
+        let device = &self.device;
+
+        self.gradient_samplers
+            .entry(spread)
+            .or_insert_with(|| {
+                let address_mode = match spread {
+                    GradientSpread::Pad => metal::MTLSamplerAddressMode::ClampToEdge,
+                    GradientSpread::Repeat => metal::MTLSamplerAddressMode::Repeat,
+                    GradientSpread::Reflect => metal::MTLSamplerAddressMode::MirrorRepeat,
+                };
+
+                let descriptor = metal::SamplerDescriptor::new();
+                descriptor.set_min_filter(metal::MTLSamplerMinMagFilter::Linear);
+                descriptor.set_mag_filter(metal::MTLSamplerMinMagFilter::Linear);
+                descriptor.set_address_mode_s(address_mode);
+
+                device.new_sampler(&descriptor)
+            })
+            .clone()
+    }
+
     ///
     /// Creates a command encoder for rendering to the specified texture
     ///
@@ -260,6 +297,21 @@ impl MetalRenderer {
                 alpha.as_ptr() as _,
             );
         }
+
+        if let Some(gradient_focus) = &state.gradient_focus {
+            state.command_encoder.set_fragment_bytes(
+                FragmentInputIndex_FragmentGradientFocus as u64,
+                mem::size_of::<[f32; 4]>() as u64,
+                gradient_focus.as_ptr() as _,
+            );
+        }
+
+        if let Some(gradient_sampler) = &state.gradient_sampler {
+            state.command_encoder.set_fragment_sampler_state(
+                FragmentSamplerIndex_FragmentGradientSampler as u64,
+                Some(gradient_sampler),
+            );
+        }
     }
 
     ///
@@ -295,6 +347,8 @@ impl MetalRenderer {
             matrix: matrix,
             texture_transform: None,
             texture_alpha: None,
+            gradient_focus: None,
+            gradient_sampler: None,
             pipeline_config: pipeline_config,
             pipeline_state: pipeline_state,
             command_buffer: command_buffer,
@@ -624,6 +678,15 @@ impl MetalRenderer {
         alpha: f64,
         state: &mut RenderState,
     ) {
+        // The blend modes that read the destination colour use a shader that reads from a copy of the render target
+        let blend_mode = state.pipeline_config.blend_mode;
+        let destination =
+            if blend_mode.reads_destination() && self.render_targets[source_buffer].is_some() {
+                self.copy_render_target(state)
+            } else {
+                None
+            };
+
         let render_targets = &self.render_targets;
 
         if let Some(source_buffer) = &render_targets[source_buffer] {
@@ -637,13 +700,24 @@ impl MetalRenderer {
 
             // Basic vertex shader and blend mode
             config.vertex_shader = String::from("simple_vertex");
-            config.blend_mode = BlendMode::SourceOver;
             config.source_is_premultiplied = true;
-            config.fragment_shader = if source_buffer.is_multisampled() {
-                String::from("texture_multisample_fragment")
+
+            if destination.is_some() {
+                // The blend shader generates the final colour, so it replaces the destination
+                config.blend_mode = BlendMode::Copy;
+                config.fragment_shader = if source_buffer.is_multisampled() {
+                    String::from("texture_multisample_blend_fragment")
+                } else {
+                    String::from("texture_blend_fragment")
+                };
             } else {
-                String::from("texture_fragment")
-            };
+                config.blend_mode = blend_mode;
+                config.fragment_shader = if source_buffer.is_multisampled() {
+                    String::from("texture_multisample_fragment")
+                } else {
+                    String::from("texture_fragment")
+                };
+            }
 
             // Convert to a pipeline state
             let pipeline_state = self.get_pipeline_state(&config);
@@ -730,6 +804,20 @@ impl MetalRenderer {
                 alpha.as_ptr() as _,
             );
 
+            if let Some(destination) = &destination {
+                state.command_encoder.set_fragment_texture(
+                    FragmentInputIndex_FragmentIndexDestinationTexture as u64,
+                    Some(destination),
+                );
+
+                let blend_shader_mode = Self::blend_shader_mode(blend_mode).to_ne_bytes();
+                state.command_encoder.set_fragment_bytes(
+                    FragmentInputIndex_FragmentBlendMode as u64,
+                    4,
+                    blend_shader_mode.as_ptr() as _,
+                );
+            }
+
             // Draw the texture
             state
                 .command_encoder
@@ -739,6 +827,10 @@ impl MetalRenderer {
             state
                 .command_encoder
                 .set_fragment_texture(FragmentInputIndex_FragmentIndexTexture as u64, None);
+            state.command_encoder.set_fragment_texture(
+                FragmentInputIndex_FragmentIndexDestinationTexture as u64,
+                None,
+            );
 
             state
                 .command_encoder
@@ -747,6 +839,98 @@ impl MetalRenderer {
         }
     }
 
+    ///
+    /// Copies the current render target to a new texture, so the blend shader can read the destination colour
+    ///
+    /// Multisampled render targets are resolved as they are copied, so the copy always has a single sample. Returns None
+    /// if the render target can't be copied (a drawable might be 'framebuffer only')
+    ///
+    fn copy_render_target(&mut self, state: &mut RenderState) -> Option<metal::Texture> {
+        let target = state.target_texture.clone();
+        let is_multisampled = target.sample_count() > 1;
+
+        if !is_multisampled && target.framebuffer_only() {
+            return None;
+        }
+
+        // Create a texture to copy the render target into
+        let texture_descriptor = metal::TextureDescriptor::new();
+
+        texture_descriptor.set_texture_type(metal::MTLTextureType::D2);
+        texture_descriptor.set_width(target.width());
+        texture_descriptor.set_height(target.height());
+        texture_descriptor.set_pixel_format(target.pixel_format());
+        texture_descriptor
+            .set_usage(metal::MTLTextureUsage::RenderTarget | metal::MTLTextureUsage::ShaderRead);
+
+        let copy = self.device.new_texture(&texture_descriptor);
+
+        // Finish the current render pass so the copy contains everything drawn so far
+        state.command_encoder.end_encoding();
+
+        if is_multisampled {
+            // Resolve the multisampled texture into the copy using an otherwise empty render pass
+            let render_descriptor = metal::RenderPassDescriptor::new();
+            let color_attachment = render_descriptor.color_attachments().object_at(0).unwrap();
+
+            color_attachment.set_texture(Some(&target));
+            color_attachment.set_resolve_texture(Some(&copy));
+            color_attachment.set_load_action(metal::MTLLoadAction::Load);
+            color_attachment.set_store_action(metal::MTLStoreAction::StoreAndMultisampleResolve);
+
+            state
+                .command_buffer
+                .new_render_command_encoder(&render_descriptor)
+                .end_encoding();
+        } else {
+            let blit_encoder = self.get_blit_command_encoder(state.command_buffer);
+            blit_encoder.copy_from_texture(
+                &target,
+                0,
+                0,
+                metal::MTLOrigin { x: 0, y: 0, z: 0 },
+                metal::MTLSize {
+                    width: target.width(),
+                    height: target.height(),
+                    depth: 1,
+                },
+                &copy,
+                0,
+                0,
+                metal::MTLOrigin { x: 0, y: 0, z: 0 },
+            );
+            blit_encoder.end_encoding();
+        }
+
+        // Carry on rendering to the render target
+        state.command_encoder =
+            self.get_command_encoder(state.command_buffer, &state.target_texture);
+        self.setup_command_encoder(state);
+
+        Some(copy)
+    }
+
+    ///
+    /// Returns the value to pass to the blend shader for a blend mode (a BlendShaderMode from the bindings)
+    ///
+    fn blend_shader_mode(blend_mode: BlendMode) -> u32 {
+        match blend_mode {
+            BlendMode::Overlay => BlendShaderMode_BlendShaderOverlay as u32,
+            BlendMode::ColorDodge => BlendShaderMode_BlendShaderColorDodge as u32,
+            BlendMode::ColorBurn => BlendShaderMode_BlendShaderColorBurn as u32,
+            BlendMode::HardLight => BlendShaderMode_BlendShaderHardLight as u32,
+            BlendMode::SoftLight => BlendShaderMode_BlendShaderSoftLight as u32,
+            BlendMode::Difference => BlendShaderMode_BlendShaderDifference as u32,
+            BlendMode::Hue => BlendShaderMode_BlendShaderHue as u32,
+            BlendMode::Saturation => BlendShaderMode_BlendShaderSaturation as u32,
+            BlendMode::Color => BlendShaderMode_BlendShaderColor as u32,
+            BlendMode::Luminosity => BlendShaderMode_BlendShaderLuminosity as u32,
+
+            // The other blend modes don't read the destination, so they never use the blend shader
+            _ => BlendShaderMode_BlendShaderOverlay as u32,
+        }
+    }
+
     ///
     /// Stores a texture with the specified texture ID
     ///
@@ -1148,6 +1332,8 @@ impl MetalRenderer {
         state.fill_texture = None;
         state.clip_texture = None;
         state.texture_transform = None;
+        state.gradient_focus = None;
+        state.gradient_sampler = None;
 
         // Update the state according to the shader type
         match shader_type {
@@ -1208,7 +1394,7 @@ impl MetalRenderer {
             ShaderType::LinearGradient {
                 texture: TextureId(gradient_texture),
                 texture_transform,
-                repeat,
+                spread,
                 alpha,
                 clip_texture: None,
             } => {
@@ -1219,12 +1405,13 @@ impl MetalRenderer {
                 state.texture_alpha = Some(alpha as _);
 
                 state.fill_texture = self.textures[gradient_texture].clone();
+                state.gradient_sampler = Some(self.get_gradient_sampler(spread));
             }
 
             ShaderType::LinearGradient {
                 texture: TextureId(gradient_texture),
                 texture_transform,
-                repeat,
+                spread,
                 alpha,
                 clip_texture: Some(TextureId(clip_texture)),
             } => {
@@ -1236,8 +1423,58 @@ impl MetalRenderer {
                 state.texture_alpha = Some(alpha as _);
 
                 state.fill_texture = self.textures[gradient_texture].clone();
+                state.gradient_sampler = Some(self.get_gradient_sampler(spread));
                 state.clip_texture = self.textures[clip_texture].clone();
             }
+
+            ShaderType::RadialGradient {
+                texture: TextureId(gradient_texture),
+                texture_transform,
+                focal_point: (focal_x, focal_y),
+                focal_radius,
+                spread,
+                alpha,
+                clip_texture,
+            } => {
+                state.pipeline_config.vertex_shader = String::from("gradient_vertex");
+                state.pipeline_config.fragment_shader = if clip_texture.is_some() {
+                    String::from("radial_gradient_clip_mask_multisample_fragment")
+                } else {
+                    String::from("radial_gradient_fragment")
+                };
+                state.texture_transform =
+                    Some(MatrixBuffer::from_matrix(&self.device, texture_transform));
+                state.texture_alpha = Some(alpha as _);
+                state.gradient_focus = Some([focal_x, focal_y, focal_radius, 0.0]);
+
+                state.fill_texture = self.textures[gradient_texture].clone();
+                state.gradient_sampler = Some(self.get_gradient_sampler(spread));
+                state.clip_texture = clip_texture
+                    .and_then(|TextureId(clip_texture)| self.textures[clip_texture].clone());
+            }
+
+            ShaderType::ConicGradient {
+                texture: TextureId(gradient_texture),
+                texture_transform,
+                spread,
+                alpha,
+                clip_texture,
+            } => {
+                state.pipeline_config.vertex_shader = String::from("gradient_vertex");
+                state.pipeline_config.fragment_shader = if clip_texture.is_some() {
+                    String::from("conic_gradient_clip_mask_multisample_fragment")
+                } else {
+                    String::from("conic_gradient_fragment")
+                };
+                state.texture_transform =
+                    Some(MatrixBuffer::from_matrix(&self.device, texture_transform));
+                state.texture_alpha = Some(alpha as _);
+
+                state.fill_texture = self.textures[gradient_texture].clone();
+                state.gradient_sampler = Some(self.get_gradient_sampler(spread));
+                state.clip_texture = clip_texture
+                    .and_then(|TextureId(clip_texture)| self.textures[clip_texture].clone());
+            }
         }
 
         // Update the command encoder with the new state
@@ -87,7 +87,9 @@ impl PipelineConfiguration {
         let descriptor = metal::RenderPipelineDescriptor::new();
 
         let fragment_shader = match self.blend_mode {
-            BlendMode::Multiply => format!("{}_invert_color_alpha", self.fragment_shader),
+            BlendMode::Multiply | BlendMode::Darken => {
+                format!("{}_invert_color_alpha", self.fragment_shader)
+            }
             _ => format!("{}", self.fragment_shader),
         };
 
@@ -105,9 +107,16 @@ impl PipelineConfiguration {
             DestinationAlpha, DestinationColor, One, OneMinusDestinationAlpha,
             OneMinusDestinationColor, OneMinusSourceAlpha, OneMinusSourceColor, SourceAlpha, Zero,
         };
+        use metal::MTLBlendOperation::{Add, Max, Min};
         let (src_rgb, dst_rgb, src_alpha, dst_alpha) =
             match (self.blend_mode, self.source_is_premultiplied) {
-                (SourceOver, false) => (SourceAlpha, OneMinusSourceAlpha, One, OneMinusSourceAlpha),
+                // These modes read the destination colour, which is only possible when drawing a frame buffer (see
+                // `blend_fragment.metal`), so they fall back to 'source over' everywhere else
+                (
+                    SourceOver | Overlay | ColorDodge | ColorBurn | HardLight | SoftLight
+                    | Difference | Hue | Saturation | Color | Luminosity,
+                    false,
+                ) => (SourceAlpha, OneMinusSourceAlpha, One, OneMinusSourceAlpha),
                 (DestinationOver, false) => (
                     OneMinusDestinationAlpha,
                     DestinationAlpha,
@@ -135,6 +144,15 @@ impl PipelineConfiguration {
                     OneMinusDestinationAlpha,
                     OneMinusSourceAlpha,
                 ),
+                (Xor, false) => (
+                    OneMinusDestinationAlpha,
+                    OneMinusSourceAlpha,
+                    OneMinusDestinationAlpha,
+                    OneMinusSourceAlpha,
+                ),
+                (Clear, false) => (Zero, Zero, Zero, Zero),
+                (Copy, false) => (SourceAlpha, Zero, One, Zero),
+                (Destination, false) => (Zero, One, Zero, One),
 
                 // Multiply is a*b. Here we multiply the source colour by the destination colour, then blend the destination back in again to take account of
                 // alpha in the source layer (this version of multiply has no effect on the target alpha value: a more strict version might multiply those too)
@@ -145,6 +163,19 @@ impl PipelineConfiguration {
                 // TODO: screen is 1-(1-a)*(1-b) which I think is harder to fake. If we precalculate (1-a) as the src in the shader
                 (Screen, false) => (OneMinusDestinationColor, One, Zero, One),
 
+                // Darken and lighten pick the minimum or maximum colour using the blend operation (the colour blend factors are ignored)
+                (Darken, false) | (Lighten, false) => (One, One, One, OneMinusSourceAlpha),
+
+                // Exclusion is a+b-2ab, which is the same as a*(1-b) + b*(1-a). This is exact when the destination is opaque.
+                (Exclusion, false) => (
+                    OneMinusDestinationColor,
+                    OneMinusSourceColor,
+                    One,
+                    OneMinusSourceAlpha,
+                ),
+
+                (Plus, false) => (SourceAlpha, One, One, One),
+
                 (AllChannelAlphaSourceOver, false) => {
                     (One, OneMinusSourceColor, One, OneMinusSourceAlpha)
                 }
@@ -152,7 +183,11 @@ impl PipelineConfiguration {
                     (OneMinusDestinationColor, One, OneMinusDestinationAlpha, One)
                 }
 
-                (SourceOver, true) => (One, OneMinusSourceAlpha, One, OneMinusSourceAlpha),
+                (
+                    SourceOver | Overlay | ColorDodge | ColorBurn | HardLight | SoftLight
+                    | Difference | Hue | Saturation | Color | Luminosity,
+                    true,
+                ) => (One, OneMinusSourceAlpha, One, OneMinusSourceAlpha),
                 (DestinationOver, true) => (
                     OneMinusDestinationAlpha,
                     DestinationAlpha,
@@ -180,8 +215,25 @@ impl PipelineConfiguration {
                     OneMinusDestinationAlpha,
                     OneMinusSourceAlpha,
                 ),
+                (Xor, true) => (
+                    OneMinusDestinationAlpha,
+                    OneMinusSourceAlpha,
+                    OneMinusDestinationAlpha,
+                    OneMinusSourceAlpha,
+                ),
+                (Clear, true) => (Zero, Zero, Zero, Zero),
+                (Copy, true) => (One, Zero, One, Zero),
+                (Destination, true) => (Zero, One, Zero, One),
                 (Multiply, true) => (DestinationColor, Zero, Zero, One),
                 (Screen, true) => (OneMinusDestinationColor, One, Zero, One),
+                (Darken, true) | (Lighten, true) => (One, One, One, OneMinusSourceAlpha),
+                (Exclusion, true) => (
+                    OneMinusDestinationColor,
+                    OneMinusSourceColor,
+                    One,
+                    OneMinusSourceAlpha,
+                ),
+                (Plus, true) => (One, One, One, One),
 
                 (AllChannelAlphaSourceOver, true) => {
                     (One, OneMinusSourceColor, One, OneMinusSourceAlpha)
@@ -190,6 +242,11 @@ impl PipelineConfiguration {
                     (OneMinusDestinationColor, One, OneMinusDestinationAlpha, One)
                 }
             };
+        let rgb_operation = match self.blend_mode {
+            Darken => Min,
+            Lighten => Max,
+            _ => Add,
+        };
 
         descriptor
             .color_attachments()
@@ -221,6 +278,11 @@ impl PipelineConfiguration {
             .object_at(0)
             .unwrap()
             .set_destination_alpha_blend_factor(dst_alpha);
+        descriptor
+            .color_attachments()
+            .object_at(0)
+            .unwrap()
+            .set_rgb_blend_operation(rgb_operation);
 
         // Create the state
         device.new_render_pipeline_state(&descriptor).unwrap()
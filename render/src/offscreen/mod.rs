@@ -17,6 +17,7 @@ mod opengl_cgl_init;
 mod opengl_egl_init;
 #[cfg(all(feature = "opengl", target_os = "windows"))]
 mod opengl_wgl_init;
+mod software_offscreen;
 #[cfg(feature = "render-wgpu")]
 mod wgpu_offscreen;
 
@@ -31,6 +32,7 @@ pub use self::opengl_cgl_init::*;
 pub use self::opengl_egl_init::*;
 #[cfg(all(feature = "opengl", target_os = "windows"))]
 pub use self::opengl_wgl_init::*;
+pub use self::software_offscreen::*;
 #[cfg(feature = "render-wgpu")]
 pub use self::wgpu_offscreen::*;
 
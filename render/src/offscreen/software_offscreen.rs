@@ -0,0 +1,76 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::error::*;
+use super::offscreen_trait::*;
+
+use crate::action::*;
+use crate::software_renderer::*;
+
+///
+/// An offscreen render context that renders on the CPU, without needing a graphics device
+///
+pub struct SoftwareOffscreenRenderContext {}
+
+///
+/// An offscreen render target that renders on the CPU
+///
+pub struct SoftwareOffscreenRenderTarget {
+    renderer: SoftwareRenderer,
+}
+
+///
+/// Performs on-startup initialisation steps for offscreen rendering using the software renderer
+///
+/// This renders by rasterizing triangles on the CPU, so it's slower than the GPU implementations but will work in
+/// environments where there is no graphics device, such as a CI server.
+///
+pub fn software_initialize_offscreen_rendering(
+) -> Result<SoftwareOffscreenRenderContext, RenderInitError> {
+    Ok(SoftwareOffscreenRenderContext {})
+}
+
+///
+/// Performs on-startup initialisation steps for offscreen rendering
+///
+/// Only required if not using a toolkit renderer (eg, in an HTTP renderer or command-line tool).
+///
+/// This version is used when no GPU renderer is enabled, and renders on the CPU
+///
+#[cfg(not(any(feature = "opengl", feature = "osx-metal", feature = "render-wgpu")))]
+pub fn initialize_offscreen_rendering() -> Result<impl OffscreenRenderContext, RenderInitError> {
+    software_initialize_offscreen_rendering()
+}
+
+impl OffscreenRenderContext for SoftwareOffscreenRenderContext {
+    type RenderTarget = SoftwareOffscreenRenderTarget;
+
+    ///
+    /// Creates a new render target for this context
+    ///
+    fn create_render_target(&mut self, width: usize, height: usize) -> Self::RenderTarget {
+        SoftwareOffscreenRenderTarget {
+            renderer: SoftwareRenderer::new(width, height),
+        }
+    }
+}
+
+impl OffscreenRenderTarget for SoftwareOffscreenRenderTarget {
+    ///
+    /// Sends render actions to this offscreen render target
+    ///
+    #[inline]
+    fn render<ActionIter: IntoIterator<Item = RenderAction>>(&mut self, actions: ActionIter) {
+        self.renderer.render(actions);
+    }
+
+    ///
+    /// Consumes this render target and returns the realized pixels as a byte array
+    ///
+    fn realize(self) -> Vec<u8> {
+        self.renderer.to_rgba_bytes()
+    }
+}
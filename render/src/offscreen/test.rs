@@ -4,7 +4,10 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-#[cfg(all(test, any(feature = "opengl", feature = "osx-metal")))]
+#[cfg(all(
+    test,
+    any(feature = "opengl", feature = "osx-metal", feature = "render-wgpu")
+))]
 mod test {
     use crate::action::*;
     use crate::buffer::*;
@@ -338,4 +341,603 @@ mod test {
             }
         }
     }
+
+    ///
+    /// Draws a blue rectangle on the left and a red rectangle on the right using a blend mode, returning the pixels
+    /// where only the blue rectangle is, where both rectangles overlap and where only the red rectangle is
+    ///
+    /// Returns None if the graphics device is unavailable
+    ///
+    fn render_blended_rectangles(
+        blend_mode: BlendMode,
+        blue: [f32; 4],
+        red: [f32; 4],
+    ) -> Option<[(u8, u8, u8, u8); 3]> {
+        let mut context = match initialize_offscreen_rendering() {
+            Ok(context) => context,
+            Err(RenderInitError::CannotCreateGraphicsDevice) => {
+                println!("Test not run: graphics device unavailable");
+                return None;
+            }
+            Err(other) => {
+                panic!("Unexpected error: {:?}", other);
+            }
+        };
+
+        let rectangle = |min_x: f32, max_x: f32, color: [f32; 4]| {
+            let vertex =
+                |x, y| Vertex2D::with_pos(x, y).with_color(color[0], color[1], color[2], color[3]);
+
+            vec![
+                vertex(min_x, -1.0),
+                vertex(max_x, -1.0),
+                vertex(min_x, 1.0),
+                vertex(max_x, -1.0),
+                vertex(min_x, 1.0),
+                vertex(max_x, 1.0),
+            ]
+        };
+
+        use self::RenderAction::*;
+
+        let mut renderer = context.create_render_target(100, 100);
+        renderer.render(vec![
+            Clear(Rgba8([0, 0, 0, 0])),
+            UseShader(ShaderType::Simple { clip_texture: None }),
+            CreateVertex2DBuffer(VertexBufferId(0), rectangle(-1.0, 0.5, blue)),
+            CreateVertex2DBuffer(VertexBufferId(1), rectangle(-0.5, 1.0, red)),
+            DrawTriangles(VertexBufferId(0), 0..6),
+            BlendMode(blend_mode),
+            DrawTriangles(VertexBufferId(1), 0..6),
+        ]);
+
+        let image = renderer.realize();
+        let pixel = |x: usize| {
+            let pos = (x + 50 * 100) * 4;
+            (image[pos], image[pos + 1], image[pos + 2], image[pos + 3])
+        };
+
+        Some([pixel(12), pixel(50), pixel(88)])
+    }
+
+    #[test]
+    fn porter_duff_blend_modes() {
+        let blue = [0.0, 0.0, 1.0, 1.0];
+        let red = [1.0, 0.0, 0.0, 1.0];
+
+        let opaque_blue = (0, 0, 255, 255);
+        let opaque_red = (255, 0, 0, 255);
+        let clear = (0, 0, 0, 0);
+
+        let expected = [
+            (BlendMode::Xor, [opaque_blue, clear, opaque_red]),
+            (BlendMode::Clear, [opaque_blue, clear, clear]),
+            (BlendMode::Copy, [opaque_blue, opaque_red, opaque_red]),
+            (BlendMode::Destination, [opaque_blue, opaque_blue, clear]),
+        ];
+
+        for (blend_mode, expected) in expected {
+            let pixels = match render_blended_rectangles(blend_mode, blue, red) {
+                Some(pixels) => pixels,
+                None => return,
+            };
+
+            assert!(pixels == expected, "{:?} {:?}", blend_mode, pixels);
+        }
+    }
+
+    ///
+    /// Creates the actions to draw a frame buffer containing a translucent rectangle onto another colour using a blend
+    /// mode. The rectangle covers the left half of the frame buffer.
+    ///
+    fn blend_frame_buffer(blend_mode: BlendMode, onto_render_target: bool) -> Vec<RenderAction> {
+        use self::RenderAction::*;
+
+        let source = Vertex2D::with_pos(0.0, 0.0).with_color(0.8, 0.3, 0.6, 0.75);
+        let vertex = |x, y| Vertex2D {
+            pos: [x, y],
+            ..source
+        };
+        let rectangle = vec![
+            vertex(-1.0, -1.0),
+            vertex(0.0, -1.0),
+            vertex(-1.0, 1.0),
+            vertex(0.0, -1.0),
+            vertex(-1.0, 1.0),
+            vertex(0.0, 1.0),
+        ];
+
+        let mut actions = vec![
+            // Render the source rectangle to a frame buffer
+            CreateRenderTarget(
+                RenderTargetId(0),
+                TextureId(0),
+                Size2D(100, 100),
+                RenderTargetType::Standard,
+            ),
+            SelectRenderTarget(RenderTargetId(0)),
+            Clear(Rgba8([0, 0, 0, 0])),
+            SetTransform(Matrix::identity()),
+            UseShader(ShaderType::Simple { clip_texture: None }),
+            CreateVertex2DBuffer(VertexBufferId(0), rectangle),
+            DrawTriangles(VertexBufferId(0), 0..6),
+        ];
+
+        if onto_render_target {
+            // Blend onto another render target, then draw that to the main frame buffer
+            actions.extend(vec![
+                CreateRenderTarget(
+                    RenderTargetId(1),
+                    TextureId(1),
+                    Size2D(100, 100),
+                    RenderTargetType::Standard,
+                ),
+                SelectRenderTarget(RenderTargetId(1)),
+                Clear(Rgba8([150, 90, 40, 200])),
+                BlendMode(blend_mode),
+                DrawFrameBuffer(RenderTargetId(0), FrameBufferRegion::default(), Alpha(1.0)),
+                RenderToFrameBuffer,
+                Clear(Rgba8([0, 0, 0, 0])),
+                BlendMode(crate::action::BlendMode::SourceOver),
+                DrawFrameBuffer(RenderTargetId(1), FrameBufferRegion::default(), Alpha(1.0)),
+            ]);
+        } else {
+            // Blend directly onto the main frame buffer
+            actions.extend(vec![
+                RenderToFrameBuffer,
+                Clear(Rgba8([150, 90, 40, 200])),
+                BlendMode(blend_mode),
+                DrawFrameBuffer(RenderTargetId(0), FrameBufferRegion::default(), Alpha(1.0)),
+            ]);
+        }
+
+        actions
+    }
+
+    #[test]
+    fn blend_modes_that_read_the_destination() {
+        let mut context = match initialize_offscreen_rendering() {
+            Ok(context) => context,
+            Err(RenderInitError::CannotCreateGraphicsDevice) => {
+                println!("Test not run: graphics device unavailable");
+                return;
+            }
+            Err(other) => {
+                panic!("Unexpected error: {:?}", other);
+            }
+        };
+        let mut software_context = software_initialize_offscreen_rendering().unwrap();
+
+        let pixel = |image: &[u8], x: usize, y: usize| {
+            let pos = (x + y * 100) * 4;
+            [image[pos], image[pos + 1], image[pos + 2], image[pos + 3]]
+        };
+
+        let blend_modes = [
+            BlendMode::Overlay,
+            BlendMode::ColorDodge,
+            BlendMode::ColorBurn,
+            BlendMode::HardLight,
+            BlendMode::SoftLight,
+            BlendMode::Difference,
+            BlendMode::Hue,
+            BlendMode::Saturation,
+            BlendMode::Color,
+            BlendMode::Luminosity,
+        ];
+
+        for blend_mode in blend_modes {
+            for onto_render_target in [false, true] {
+                // The software renderer reads the destination directly, so the result should be the same
+                let mut renderer = context.create_render_target(100, 100);
+                renderer.render(blend_frame_buffer(blend_mode, onto_render_target));
+                let image = renderer.realize();
+
+                let mut software_renderer = software_context.create_render_target(100, 100);
+                software_renderer.render(blend_frame_buffer(blend_mode, onto_render_target));
+                let expected = software_renderer.realize();
+
+                for (x, y) in [(25, 50), (75, 50)] {
+                    let actual = pixel(&image, x, y);
+                    let expected = pixel(&expected, x, y);
+                    let matches = actual
+                        .iter()
+                        .zip(expected.iter())
+                        .all(|(actual, expected)| (*actual as i32 - *expected as i32).abs() <= 2);
+
+                    assert!(
+                        matches,
+                        "{:?} (onto render target: {}) at {}, {}: {:?} != {:?}",
+                        blend_mode, onto_render_target, x, y, actual, expected
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod software_test {
+    use crate::action::*;
+    use crate::buffer::*;
+    use crate::offscreen::*;
+
+    use std::sync::*;
+
+    ///
+    /// Creates a vertex buffer containing a triangle covering the lower-right half of the render target
+    ///
+    fn lower_right_triangle(color: [u8; 4]) -> RenderAction {
+        RenderAction::CreateVertex2DBuffer(
+            VertexBufferId(0),
+            vec![
+                Vertex2D::with_pos(-1.0, -1.0).with_color(
+                    color[0] as f32 / 255.0,
+                    color[1] as f32 / 255.0,
+                    color[2] as f32 / 255.0,
+                    color[3] as f32 / 255.0,
+                ),
+                Vertex2D::with_pos(1.0, 1.0).with_color(
+                    color[0] as f32 / 255.0,
+                    color[1] as f32 / 255.0,
+                    color[2] as f32 / 255.0,
+                    color[3] as f32 / 255.0,
+                ),
+                Vertex2D::with_pos(1.0, -1.0).with_color(
+                    color[0] as f32 / 255.0,
+                    color[1] as f32 / 255.0,
+                    color[2] as f32 / 255.0,
+                    color[3] as f32 / 255.0,
+                ),
+            ],
+        )
+    }
+
+    ///
+    /// Creates a vertex buffer containing two triangles that cover a rectangle
+    ///
+    fn rectangle(min: (f32, f32), max: (f32, f32), color: [u8; 4]) -> Vec<Vertex2D> {
+        let color = color.map(|component| component as f32 / 255.0);
+        let vertex =
+            |x, y| Vertex2D::with_pos(x, y).with_color(color[0], color[1], color[2], color[3]);
+
+        vec![
+            vertex(min.0, min.1),
+            vertex(max.0, min.1),
+            vertex(min.0, max.1),
+            vertex(max.0, min.1),
+            vertex(min.0, max.1),
+            vertex(max.0, max.1),
+        ]
+    }
+
+    fn pixel(image: &[u8], width: usize, x: usize, y: usize) -> (u8, u8, u8, u8) {
+        let pos = (x + y * width) * 4;
+        (image[pos], image[pos + 1], image[pos + 2], image[pos + 3])
+    }
+
+    #[test]
+    fn clear_offscreen() {
+        let mut context = software_initialize_offscreen_rendering().unwrap();
+
+        let mut renderer = context.create_render_target(100, 100);
+        renderer.render(vec![RenderAction::Clear(Rgba8([128, 129, 130, 255]))]);
+
+        let image = renderer.realize();
+
+        assert!(image.len() == 100 * 100 * 4);
+
+        for y in 0..100 {
+            for x in 0..100 {
+                assert!(pixel(&image, 100, x, y) == (128, 129, 130, 255));
+            }
+        }
+    }
+
+    #[test]
+    fn simple_offscreen_render() {
+        use self::RenderAction::*;
+
+        let mut context = software_initialize_offscreen_rendering().unwrap();
+
+        let mut renderer = context.create_render_target(100, 100);
+        renderer.render(vec![
+            Clear(Rgba8([128, 129, 130, 255])),
+            SetTransform(Matrix::identity()),
+            UseShader(ShaderType::Simple { clip_texture: None }),
+            lower_right_triangle([1, 2, 3, 255]),
+            DrawTriangles(VertexBufferId(0), 0..3),
+        ]);
+
+        let image = renderer.realize();
+
+        for y in 0..100 {
+            for x in 0..100 {
+                let expected = if x >= y {
+                    (1, 2, 3, 255)
+                } else {
+                    (128, 129, 130, 255)
+                };
+
+                assert!(pixel(&image, 100, x, y) == expected);
+            }
+        }
+    }
+
+    #[test]
+    fn adjacent_triangles_do_not_overlap() {
+        use self::RenderAction::*;
+
+        let mut context = software_initialize_offscreen_rendering().unwrap();
+
+        // Two triangles with a shared edge, drawn with a translucent colour (any overlap would be darker)
+        let mut renderer = context.create_render_target(64, 64);
+        renderer.render(vec![
+            CreateRenderTarget(
+                RenderTargetId(0),
+                TextureId(0),
+                Size2D(64, 64),
+                RenderTargetType::MultisampledTexture,
+            ),
+            SelectRenderTarget(RenderTargetId(0)),
+            Clear(Rgba8([0, 0, 0, 0])),
+            CreateVertex2DBuffer(
+                VertexBufferId(0),
+                rectangle((-0.7, -0.7), (0.7, 0.7), [0, 0, 255, 128]),
+            ),
+            DrawTriangles(VertexBufferId(0), 0..6),
+            RenderToFrameBuffer,
+            Clear(Rgba8([255, 255, 255, 255])),
+            DrawFrameBuffer(RenderTargetId(0), FrameBufferRegion::default(), Alpha(1.0)),
+        ]);
+
+        let image = renderer.realize();
+
+        // Every pixel inside the rectangle has the same colour
+        let inside = pixel(&image, 64, 12, 12);
+        assert!(
+            inside.2 == 255 && inside.0 > 100 && inside.0 < 160,
+            "{:?}",
+            inside
+        );
+
+        for y in 12..52 {
+            for x in 12..52 {
+                assert!(
+                    pixel(&image, 64, x, y) == inside,
+                    "{} {} {:?}",
+                    x,
+                    y,
+                    pixel(&image, 64, x, y)
+                );
+            }
+        }
+
+        // Outside is the clear colour
+        assert!(pixel(&image, 64, 2, 2) == (255, 255, 255, 255));
+    }
+
+    #[test]
+    fn multisampled_edges_are_antialiased() {
+        use self::RenderAction::*;
+
+        let mut context = software_initialize_offscreen_rendering().unwrap();
+
+        let mut renderer = context.create_render_target(100, 100);
+        renderer.render(vec![
+            CreateRenderTarget(
+                RenderTargetId(0),
+                TextureId(0),
+                Size2D(100, 100),
+                RenderTargetType::Multisampled,
+            ),
+            SelectRenderTarget(RenderTargetId(0)),
+            Clear(Rgba8([0, 0, 0, 0])),
+            lower_right_triangle([0, 0, 0, 255]),
+            DrawTriangles(VertexBufferId(0), 0..3),
+            RenderToFrameBuffer,
+            Clear(Rgba8([255, 255, 255, 255])),
+            DrawFrameBuffer(RenderTargetId(0), FrameBufferRegion::default(), Alpha(1.0)),
+        ]);
+
+        let image = renderer.realize();
+
+        // Pixels on the diagonal are partially covered
+        let (r, _, _, a) = pixel(&image, 100, 50, 50);
+        assert!(r > 32 && r < 224, "{}", r);
+        assert!(a == 255);
+
+        assert!(pixel(&image, 100, 80, 20) == (0, 0, 0, 255));
+        assert!(pixel(&image, 100, 20, 80) == (255, 255, 255, 255));
+    }
+
+    #[test]
+    fn clip_mask_restricts_drawing() {
+        use self::RenderAction::*;
+
+        let mut context = software_initialize_offscreen_rendering().unwrap();
+
+        // Render a clip mask covering the left half of the image, then fill the whole image through it
+        let mut renderer = context.create_render_target(100, 100);
+        renderer.render(vec![
+            CreateRenderTarget(
+                RenderTargetId(1),
+                TextureId(1),
+                Size2D(100, 100),
+                RenderTargetType::MonochromeMultisampledTexture,
+            ),
+            SelectRenderTarget(RenderTargetId(1)),
+            Clear(Rgba8([0, 0, 0, 255])),
+            BlendMode(crate::action::BlendMode::AllChannelAlphaSourceOver),
+            CreateVertex2DBuffer(
+                VertexBufferId(1),
+                rectangle((-1.0, -1.0), (0.0, 1.0), [255, 255, 255, 255]),
+            ),
+            DrawTriangles(VertexBufferId(1), 0..6),
+            RenderToFrameBuffer,
+            BlendMode(crate::action::BlendMode::SourceOver),
+            Clear(Rgba8([255, 255, 255, 255])),
+            UseShader(ShaderType::Simple {
+                clip_texture: Some(TextureId(1)),
+            }),
+            CreateVertex2DBuffer(
+                VertexBufferId(0),
+                rectangle((-1.0, -1.0), (1.0, 1.0), [255, 0, 0, 255]),
+            ),
+            DrawTriangles(VertexBufferId(0), 0..6),
+        ]);
+
+        let image = renderer.realize();
+
+        assert!(pixel(&image, 100, 25, 50) == (255, 0, 0, 255));
+        assert!(pixel(&image, 100, 75, 50) == (255, 255, 255, 255));
+    }
+
+    #[test]
+    fn draw_with_texture_shader() {
+        use self::RenderAction::*;
+
+        let mut context = software_initialize_offscreen_rendering().unwrap();
+
+        // 2x2 texture with a different colour in each pixel
+        let texture_data = vec![
+            255, 0, 0, 255, 0, 255, 0, 255, //
+            0, 0, 255, 255, 255, 255, 255, 255,
+        ];
+
+        // Map the texture to the whole render target (canvas coordinates -1..1 to texture coordinates 0..1)
+        let texture_transform = Matrix([
+            [0.5, 0.0, 0.0, 0.5],
+            [0.0, 0.5, 0.0, 0.5],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+
+        let mut renderer = context.create_render_target(100, 100);
+        renderer.render(vec![
+            Clear(Rgba8([0, 0, 0, 255])),
+            CreateTextureBgra(TextureId(0), Size2D(2, 2)),
+            WriteTextureData(
+                TextureId(0),
+                Position2D(0, 0),
+                Position2D(2, 2),
+                Arc::new(texture_data),
+            ),
+            UseShader(ShaderType::Texture {
+                texture: TextureId(0),
+                texture_transform,
+                repeat: false,
+                alpha: 1.0,
+                clip_texture: None,
+            }),
+            CreateVertex2DBuffer(
+                VertexBufferId(0),
+                rectangle((-1.0, -1.0), (1.0, 1.0), [0, 0, 0, 255]),
+            ),
+            DrawTriangles(VertexBufferId(0), 0..6),
+        ]);
+
+        let image = renderer.realize();
+
+        // The first row of the texture data is at the bottom of the image
+        assert!(pixel(&image, 100, 5, 5) == (255, 0, 0, 255));
+        assert!(pixel(&image, 100, 95, 5) == (0, 255, 0, 255));
+        assert!(pixel(&image, 100, 5, 95) == (0, 0, 255, 255));
+        assert!(pixel(&image, 100, 95, 95) == (255, 255, 255, 255));
+    }
+
+    #[test]
+    fn draw_linear_gradient() {
+        use self::RenderAction::*;
+
+        let mut context = software_initialize_offscreen_rendering().unwrap();
+
+        // Gradient from black to white along the x axis
+        let texture_transform = Matrix([
+            [0.5, 0.0, 0.0, 0.5],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+
+        let mut renderer = context.create_render_target(100, 100);
+        renderer.render(vec![
+            Create1DTextureBgra(TextureId(0), Size1D(2)),
+            WriteTexture1D(
+                TextureId(0),
+                Position1D(0),
+                Position1D(2),
+                Arc::new(vec![0, 0, 0, 255, 255, 255, 255, 255]),
+            ),
+            UseShader(ShaderType::LinearGradient {
+                texture: TextureId(0),
+                texture_transform,
+                spread: GradientSpread::Pad,
+                alpha: 1.0,
+                clip_texture: None,
+            }),
+            CreateVertex2DBuffer(
+                VertexBufferId(0),
+                rectangle((-1.0, -1.0), (1.0, 1.0), [0, 0, 0, 255]),
+            ),
+            DrawTriangles(VertexBufferId(0), 0..6),
+        ]);
+
+        let image = renderer.realize();
+
+        let (left, _, _, _) = pixel(&image, 100, 10, 50);
+        let (middle, _, _, _) = pixel(&image, 100, 50, 50);
+        let (right, _, _, _) = pixel(&image, 100, 90, 50);
+
+        assert!(left == 0, "{}", left);
+        assert!(middle > 100 && middle < 155, "{}", middle);
+        assert!(right == 255, "{}", right);
+    }
+
+    #[test]
+    fn blur_render_target_texture() {
+        use self::RenderAction::*;
+
+        let mut context = software_initialize_offscreen_rendering().unwrap();
+
+        // Draw a rectangle to a render target, blur it, then draw it to the frame buffer using the texture shader
+        let mut renderer = context.create_render_target(100, 100);
+        renderer.render(vec![
+            CreateRenderTarget(
+                RenderTargetId(0),
+                TextureId(0),
+                Size2D(100, 100),
+                RenderTargetType::Standard,
+            ),
+            SelectRenderTarget(RenderTargetId(0)),
+            Clear(Rgba8([0, 0, 0, 0])),
+            CreateVertex2DBuffer(
+                VertexBufferId(0),
+                rectangle((-0.5, -0.5), (0.5, 0.5), [255, 255, 255, 255]),
+            ),
+            DrawTriangles(VertexBufferId(0), 0..6),
+            FilterTexture(
+                TextureId(0),
+                vec![
+                    TextureFilter::GaussianBlurHorizontal29(0.25, 1.0 / 8.0),
+                    TextureFilter::GaussianBlurVertical29(0.25, 1.0 / 8.0),
+                ],
+            ),
+            RenderToFrameBuffer,
+            Clear(Rgba8([0, 0, 0, 255])),
+            DrawFrameBuffer(RenderTargetId(0), FrameBufferRegion::default(), Alpha(1.0)),
+        ]);
+
+        let image = renderer.realize();
+
+        // The middle of the rectangle is unchanged, and the edge is blurred
+        assert!(pixel(&image, 100, 50, 50) == (255, 255, 255, 255));
+        assert!(pixel(&image, 100, 5, 5) == (0, 0, 0, 255));
+
+        let (edge, _, _, _) = pixel(&image, 100, 25, 50);
+        assert!(edge > 64 && edge < 192, "{}", edge);
+
+        let (outside, _, _, _) = pixel(&image, 100, 22, 50);
+        assert!(outside > 0 && outside < edge, "{}", outside);
+    }
 }
@@ -0,0 +1,269 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::action::*;
+
+///
+/// Blends a source colour onto a destination pixel (which has premultiplied alpha) using the specified blend mode
+///
+/// Unlike the GPU renderers, the destination colour is available here, so the blend modes that can only be approximated
+/// with blend factors are implemented using their full definitions.
+///
+pub fn blend(
+    blend_mode: BlendMode,
+    src: [f32; 4],
+    src_is_premultiplied: bool,
+    dst: [f32; 4],
+) -> [f32; 4] {
+    use self::BlendMode::*;
+
+    // The 'all channel' modes treat each colour component as its own alpha value
+    match blend_mode {
+        AllChannelAlphaSourceOver => {
+            return [
+                src[0] + dst[0] * (1.0 - src[0]),
+                src[1] + dst[1] * (1.0 - src[1]),
+                src[2] + dst[2] * (1.0 - src[2]),
+                src[3] + dst[3] * (1.0 - src[3]),
+            ];
+        }
+
+        AllChannelAlphaDestinationOver => {
+            return [
+                src[0] * (1.0 - dst[0]) + dst[0],
+                src[1] * (1.0 - dst[1]) + dst[1],
+                src[2] * (1.0 - dst[2]) + dst[2],
+                src[3] * (1.0 - dst[3]) + dst[3],
+            ];
+        }
+
+        _ => {}
+    }
+
+    // Everything else works on premultiplied colours
+    let src = if src_is_premultiplied {
+        src
+    } else {
+        [src[0] * src[3], src[1] * src[3], src[2] * src[3], src[3]]
+    };
+    let src_alpha = src[3];
+    let dst_alpha = dst[3];
+
+    let porter_duff = |src_factor: f32, dst_factor: f32| {
+        [
+            src[0] * src_factor + dst[0] * dst_factor,
+            src[1] * src_factor + dst[1] * dst_factor,
+            src[2] * src_factor + dst[2] * dst_factor,
+            src[3] * src_factor + dst[3] * dst_factor,
+        ]
+    };
+
+    let result = match blend_mode {
+        SourceOver => porter_duff(1.0, 1.0 - src_alpha),
+        DestinationOver => porter_duff(1.0 - dst_alpha, 1.0),
+        SourceIn => porter_duff(dst_alpha, 0.0),
+        DestinationIn => porter_duff(0.0, src_alpha),
+        SourceOut => porter_duff(1.0 - dst_alpha, 0.0),
+        DestinationOut => porter_duff(0.0, 1.0 - src_alpha),
+        SourceATop => porter_duff(dst_alpha, 1.0 - src_alpha),
+        DestinationATop => porter_duff(1.0 - dst_alpha, src_alpha),
+        Xor => porter_duff(1.0 - dst_alpha, 1.0 - src_alpha),
+        Clear => porter_duff(0.0, 0.0),
+        Copy => porter_duff(1.0, 0.0),
+        Destination => porter_duff(0.0, 1.0),
+
+        Plus => [
+            src[0] + dst[0],
+            src[1] + dst[1],
+            src[2] + dst[2],
+            src[3] + dst[3],
+        ],
+
+        Multiply | Screen | Darken | Lighten | Overlay | ColorDodge | ColorBurn | HardLight
+        | SoftLight | Difference | Exclusion | Hue | Saturation | Color | Luminosity => {
+            blend_function(blend_mode, src, dst)
+        }
+
+        AllChannelAlphaSourceOver | AllChannelAlphaDestinationOver => unreachable!(),
+    };
+
+    result.map(|component| component.clamp(0.0, 1.0))
+}
+
+///
+/// Blends two premultiplied colours using one of the W3C blend functions, compositing the result using 'source over'
+///
+fn blend_function(blend_mode: BlendMode, src: [f32; 4], dst: [f32; 4]) -> [f32; 4] {
+    use self::BlendMode::*;
+
+    let [sr, sg, sb, src_alpha] = src;
+    let [dr, dg, db, dst_alpha] = dst;
+
+    // The blend functions are defined on colours without premultiplication
+    let unpremultiply = |component: f32, alpha: f32| {
+        if alpha > 0.0 {
+            (component / alpha).min(1.0)
+        } else {
+            0.0
+        }
+    };
+    let cs = [
+        unpremultiply(sr, src_alpha),
+        unpremultiply(sg, src_alpha),
+        unpremultiply(sb, src_alpha),
+    ];
+    let cb = [
+        unpremultiply(dr, dst_alpha),
+        unpremultiply(dg, dst_alpha),
+        unpremultiply(db, dst_alpha),
+    ];
+
+    let blended = match blend_mode {
+        Hue => set_lum(set_sat(cs, sat(cb)), lum(cb)),
+        Saturation => set_lum(set_sat(cb, sat(cs)), lum(cb)),
+        Color => set_lum(cs, lum(cb)),
+        Luminosity => set_lum(cb, lum(cs)),
+        _ => [
+            blend_component(blend_mode, cs[0], cb[0]),
+            blend_component(blend_mode, cs[1], cb[1]),
+            blend_component(blend_mode, cs[2], cb[2]),
+        ],
+    };
+
+    let both_alpha = src_alpha * dst_alpha;
+    let composite = |src: f32, dst: f32, blended: f32| {
+        src * (1.0 - dst_alpha) + dst * (1.0 - src_alpha) + both_alpha * blended
+    };
+
+    [
+        composite(sr, dr, blended[0]),
+        composite(sg, dg, blended[1]),
+        composite(sb, db, blended[2]),
+        src_alpha + dst_alpha - both_alpha,
+    ]
+}
+
+///
+/// Blends a single colour component using one of the separable blend functions
+///
+fn blend_component(blend_mode: BlendMode, cs: f32, cb: f32) -> f32 {
+    use self::BlendMode::*;
+
+    match blend_mode {
+        Multiply => cs * cb,
+        Screen => cs + cb - cs * cb,
+        Darken => cs.min(cb),
+        Lighten => cs.max(cb),
+        Overlay => blend_component(HardLight, cb, cs),
+
+        ColorDodge => {
+            if cb <= 0.0 {
+                0.0
+            } else if cs >= 1.0 {
+                1.0
+            } else {
+                (cb / (1.0 - cs)).min(1.0)
+            }
+        }
+
+        ColorBurn => {
+            if cb >= 1.0 {
+                1.0
+            } else if cs <= 0.0 {
+                0.0
+            } else {
+                1.0 - ((1.0 - cb) / cs).min(1.0)
+            }
+        }
+
+        HardLight => {
+            if cs <= 0.5 {
+                cb * 2.0 * cs
+            } else {
+                blend_component(Screen, 2.0 * cs - 1.0, cb)
+            }
+        }
+
+        SoftLight => {
+            if cs <= 0.5 {
+                cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb)
+            } else {
+                let d = if cb <= 0.25 {
+                    ((16.0 * cb - 12.0) * cb + 4.0) * cb
+                } else {
+                    cb.sqrt()
+                };
+
+                cb + (2.0 * cs - 1.0) * (d - cb)
+            }
+        }
+
+        Difference => (cb - cs).abs(),
+        Exclusion => cb + cs - 2.0 * cb * cs,
+
+        _ => cs,
+    }
+}
+
+///
+/// The luminosity of a colour, as used by the non-separable blend functions
+///
+#[inline]
+fn lum([r, g, b]: [f32; 3]) -> f32 {
+    0.3 * r + 0.59 * g + 0.11 * b
+}
+
+///
+/// Moves a colour back into the 0-1 range while preserving its luminosity
+///
+#[inline]
+fn clip_color(color: [f32; 3]) -> [f32; 3] {
+    let l = lum(color);
+    let n = color[0].min(color[1]).min(color[2]);
+    let x = color[0].max(color[1]).max(color[2]);
+
+    let mut color = color;
+    if n < 0.0 {
+        color = color.map(|c| l + (c - l) * l / (l - n));
+    }
+    if x > 1.0 {
+        color = color.map(|c| l + (c - l) * (1.0 - l) / (x - l));
+    }
+
+    color
+}
+
+///
+/// Adjusts a colour so that it has the specified luminosity
+///
+#[inline]
+fn set_lum(color: [f32; 3], l: f32) -> [f32; 3] {
+    let d = l - lum(color);
+    clip_color(color.map(|c| c + d))
+}
+
+///
+/// The saturation of a colour, as used by the non-separable blend functions
+///
+#[inline]
+fn sat([r, g, b]: [f32; 3]) -> f32 {
+    r.max(g).max(b) - r.min(g).min(b)
+}
+
+///
+/// Adjusts a colour so that it has the specified saturation
+///
+#[inline]
+fn set_sat(color: [f32; 3], s: f32) -> [f32; 3] {
+    let max = color[0].max(color[1]).max(color[2]);
+    let min = color[0].min(color[1]).min(color[2]);
+
+    if max > min {
+        color.map(|c| (c - min) * s / (max - min))
+    } else {
+        [0.0, 0.0, 0.0]
+    }
+}
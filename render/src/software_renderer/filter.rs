@@ -0,0 +1,160 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::texture::*;
+
+use crate::action::*;
+
+///
+/// Applies a filter to a texture, which should have premultiplied alpha and one sample per pixel
+///
+/// The textures are used to look up the mask and displacement map textures used by some of the filters. Filters that refer
+/// to missing textures leave the texture unchanged.
+///
+pub fn filter_texture(
+    texture: &SoftwareTexture,
+    filter: &TextureFilter,
+    textures: &[Option<SoftwareTexture>],
+) -> SoftwareTexture {
+    use self::TextureFilter::*;
+
+    let lookup_texture = |TextureId(texture_id): TextureId| {
+        textures
+            .get(texture_id)
+            .and_then(|texture| texture.as_ref())
+    };
+
+    match filter {
+        GaussianBlurHorizontal9(sigma, step)
+        | GaussianBlurHorizontal29(sigma, step)
+        | GaussianBlurHorizontal61(sigma, step)
+        | GaussianBlurHorizontal(sigma, step, _) => {
+            let weights =
+                TextureFilter::weights_for_gaussian_blur(*sigma, *step, filter.kernel_size());
+            map_pixels(texture, |x, y| blur_pixel(texture, &weights, x, y, (1, 0)))
+        }
+
+        GaussianBlurVertical9(sigma, step)
+        | GaussianBlurVertical29(sigma, step)
+        | GaussianBlurVertical61(sigma, step)
+        | GaussianBlurVertical(sigma, step, _) => {
+            let weights =
+                TextureFilter::weights_for_gaussian_blur(*sigma, *step, filter.kernel_size());
+            map_pixels(texture, |x, y| blur_pixel(texture, &weights, x, y, (0, 1)))
+        }
+
+        AlphaBlend(alpha) => map_pixels(texture, |x, y| {
+            texture.pixel(x, y).map(|component| component * alpha)
+        }),
+
+        Mask(mask_texture) => {
+            if let Some(mask_texture) = lookup_texture(*mask_texture) {
+                // The mask is stretched over the whole of the texture
+                map_pixels(texture, |x, y| {
+                    let (u, v) = texture_coordinates(texture, x, y);
+                    let mask_alpha = mask_texture.sample(u, v, TextureWrap::ClampToEdge)[3];
+
+                    texture.pixel(x, y).map(|component| component * mask_alpha)
+                })
+            } else {
+                texture.clone()
+            }
+        }
+
+        DisplacementMap(displacement_texture, x_radius, y_radius) => {
+            if let Some(displacement_texture) = lookup_texture(*displacement_texture) {
+                map_pixels(texture, |x, y| {
+                    let (u, v) = texture_coordinates(texture, x, y);
+                    let [r, g, _, a] = displacement_texture.sample(u, v, TextureWrap::ClampToEdge);
+                    let (r, g) = if displacement_texture.premultiplied && a > 0.0 {
+                        (r / a, g / a)
+                    } else {
+                        (r, g)
+                    };
+
+                    // The red and green channels move the pixel by up to the radius in either direction
+                    let dx = (r - 0.5) * 2.0 * x_radius * a;
+                    let dy = (g - 0.5) * 2.0 * y_radius * a;
+
+                    texture.sample(u + dx, v + dy, TextureWrap::ClampToEdge)
+                })
+            } else {
+                texture.clone()
+            }
+        }
+    }
+}
+
+///
+/// Creates a new texture by calculating the value of every pixel in an existing one
+///
+fn map_pixels(
+    texture: &SoftwareTexture,
+    pixel_fn: impl Fn(usize, usize) -> [f32; 4],
+) -> SoftwareTexture {
+    let mut pixels = Vec::with_capacity(texture.width * texture.height);
+
+    for y in 0..texture.height {
+        for x in 0..texture.width {
+            pixels.push(pixel_fn(x, y));
+        }
+    }
+
+    SoftwareTexture {
+        width: texture.width,
+        height: texture.height,
+        samples: 1,
+        mono: texture.mono,
+        premultiplied: true,
+        pixels,
+    }
+}
+
+///
+/// Returns the texture coordinates of the center of a pixel
+///
+#[inline]
+fn texture_coordinates(texture: &SoftwareTexture, x: usize, y: usize) -> (f32, f32) {
+    (
+        ((x as f32) + 0.5) / (texture.width as f32),
+        ((y as f32) + 0.5) / (texture.height as f32),
+    )
+}
+
+///
+/// Calculates the value of a pixel blurred along a direction using a set of gaussian weights
+///
+/// The first weight is for the pixel itself, and the others are applied to the pixels either side of it.
+///
+fn blur_pixel(
+    texture: &SoftwareTexture,
+    weights: &[f32],
+    x: usize,
+    y: usize,
+    (dx, dy): (isize, isize),
+) -> [f32; 4] {
+    let mut result = [0.0, 0.0, 0.0, 0.0];
+    let x = x as isize;
+    let y = y as isize;
+
+    for (offset, weight) in weights.iter().enumerate() {
+        let offset = offset as isize;
+
+        let before = texture.texel(x - dx * offset, y - dy * offset, TextureWrap::ClampToEdge);
+        for component in 0..4 {
+            result[component] += before[component] * weight;
+        }
+
+        if offset != 0 {
+            let after = texture.texel(x + dx * offset, y + dy * offset, TextureWrap::ClampToEdge);
+            for component in 0..4 {
+                result[component] += after[component] * weight;
+            }
+        }
+    }
+
+    result
+}
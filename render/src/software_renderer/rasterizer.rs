@@ -0,0 +1,137 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+/// The sample position used for render targets with a single sample per pixel
+const SINGLE_SAMPLE: [(f64, f64); 1] = [(0.5, 0.5)];
+
+/// The sample positions used for 4x multisampled render targets (the standard rotated grid pattern)
+const MSAA_4_SAMPLES: [(f64, f64); 4] = [
+    (0.375, 0.125),
+    (0.875, 0.375),
+    (0.125, 0.625),
+    (0.625, 0.875),
+];
+
+///
+/// Returns the positions of the samples within a pixel for a render target with the specified number of samples per pixel
+///
+#[inline]
+pub fn sample_positions(samples: usize) -> &'static [(f64, f64)] {
+    if samples >= 4 {
+        &MSAA_4_SAMPLES
+    } else {
+        &SINGLE_SAMPLE
+    }
+}
+
+///
+/// Evaluates the edge function for the line from a to b at the point p
+///
+/// The edge is always evaluated in the same direction, so triangles that share an edge will get exactly opposite values
+/// for any point along it.
+///
+#[inline]
+fn edge_function(a: (f64, f64), b: (f64, f64), p: (f64, f64)) -> f64 {
+    if a.0 < b.0 || (a.0 == b.0 && a.1 <= b.1) {
+        (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+    } else {
+        -((a.0 - b.0) * (p.1 - b.1) - (a.1 - b.1) * (p.0 - b.0))
+    }
+}
+
+///
+/// True if a point exactly on the edge from a to b should be considered inside the triangle
+///
+/// This is true for exactly one of the two directions along an edge, so points on an edge shared between two triangles are
+/// only drawn once.
+///
+#[inline]
+fn includes_edge(a: (f64, f64), b: (f64, f64)) -> bool {
+    let dx = b.0 - a.0;
+    let dy = b.1 - a.1;
+
+    dy < 0.0 || (dy == 0.0 && dx > 0.0)
+}
+
+///
+/// Rasterizes a triangle, specified in window coordinates, onto a target of the specified size
+///
+/// The fragment function is called for every pixel where at least one sample is inside the triangle, with the pixel position,
+/// the barycentric coordinates of the center of the pixel and a bit mask indicating which of the samples are covered.
+///
+pub fn rasterize_triangle(
+    width: usize,
+    height: usize,
+    samples: usize,
+    vertices: [(f64, f64); 3],
+    mut fragment: impl FnMut(usize, usize, [f64; 3], u32),
+) {
+    // Order the vertices so the triangle has a positive area
+    let area = edge_function(vertices[0], vertices[1], vertices[2]);
+    if area == 0.0 || !area.is_finite() {
+        return;
+    }
+
+    let order = if area > 0.0 { [0, 1, 2] } else { [0, 2, 1] };
+    let area = area.abs();
+    let v = [vertices[order[0]], vertices[order[1]], vertices[order[2]]];
+
+    // Each edge is opposite to one of the vertices
+    let edges = [(v[1], v[2]), (v[2], v[0]), (v[0], v[1])];
+    let includes = [
+        includes_edge(v[1], v[2]),
+        includes_edge(v[2], v[0]),
+        includes_edge(v[0], v[1]),
+    ];
+
+    // Only visit the pixels within the bounding box of the triangle
+    let min_x = v.iter().map(|p| p.0).fold(f64::MAX, f64::min);
+    let min_y = v.iter().map(|p| p.1).fold(f64::MAX, f64::min);
+    let max_x = v.iter().map(|p| p.0).fold(f64::MIN, f64::max);
+    let max_y = v.iter().map(|p| p.1).fold(f64::MIN, f64::max);
+
+    let min_x = min_x.floor().max(0.0).min(width as f64) as usize;
+    let min_y = min_y.floor().max(0.0).min(height as f64) as usize;
+    let max_x = max_x.ceil().max(0.0).min(width as f64) as usize;
+    let max_y = max_y.ceil().max(0.0).min(height as f64) as usize;
+
+    let sample_positions = sample_positions(samples);
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            // Work out which samples are covered by the triangle
+            let mut coverage = 0u32;
+
+            for (sample_idx, (sx, sy)) in sample_positions.iter().enumerate() {
+                let point = ((x as f64) + sx, (y as f64) + sy);
+                let inside = (0..3).all(|edge_idx| {
+                    let (a, b) = edges[edge_idx];
+                    let edge = edge_function(a, b, point);
+
+                    edge > 0.0 || (edge == 0.0 && includes[edge_idx])
+                });
+
+                if inside {
+                    coverage |= 1 << sample_idx;
+                }
+            }
+
+            if coverage == 0 {
+                continue;
+            }
+
+            // Shading uses the barycentric coordinates of the center of the pixel
+            let center = ((x as f64) + 0.5, (y as f64) + 0.5);
+            let mut barycentric = [0.0; 3];
+            for edge_idx in 0..3 {
+                let (a, b) = edges[edge_idx];
+                barycentric[order[edge_idx]] = edge_function(a, b, center) / area;
+            }
+
+            fragment(x, y, barycentric, coverage);
+        }
+    }
+}
@@ -0,0 +1,524 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::blend::*;
+use super::filter::*;
+use super::rasterizer::*;
+use super::shader::*;
+use super::texture::*;
+
+use crate::action::*;
+use crate::buffer::*;
+
+use std::mem;
+use std::ops::Range;
+
+///
+/// Renderer that performs render actions on the CPU by rasterizing triangles into memory
+///
+/// This follows the conventions of the OpenGL renderer: the frame buffer and render targets have their first row at the
+/// bottom, and the multisampled render targets use 4 samples per pixel. Render targets store premultiplied colours, and
+/// textures created from byte data are not premultiplied until a filter is applied to them.
+///
+pub struct SoftwareRenderer {
+    /// The vertex buffers defined for this renderer
+    vertex_buffers: Vec<Option<Vec<Vertex2D>>>,
+
+    /// The index buffers defined for this renderer
+    index_buffers: Vec<Option<Vec<u16>>>,
+
+    /// The textures allocated to this renderer
+    textures: Vec<Option<SoftwareTexture>>,
+
+    /// The render targets assigned to this renderer, and the texture that they draw to
+    render_targets: Vec<Option<TextureId>>,
+
+    /// The frame buffer that represents the output for this renderer
+    frame_buffer: SoftwareTexture,
+
+    /// The render target that's being drawn to, or None to draw to the frame buffer
+    active_render_target: Option<RenderTargetId>,
+
+    /// The shader that's currently set to be used
+    active_shader: ShaderType,
+
+    /// The currently set blend mode
+    blend_mode: BlendMode,
+
+    /// The matrix that's currently in use
+    transform: Matrix,
+}
+
+///
+/// Makes sure that a list of resources has space for the specified ID
+///
+#[inline]
+fn ensure_slot<T>(list: &mut Vec<Option<T>>, id: usize) {
+    if id >= list.len() {
+        list.extend((list.len()..(id + 1)).map(|_| None));
+    }
+}
+
+impl SoftwareRenderer {
+    ///
+    /// Creates a new software renderer with a frame buffer of the specified size
+    ///
+    pub fn new(width: usize, height: usize) -> SoftwareRenderer {
+        SoftwareRenderer {
+            vertex_buffers: vec![],
+            index_buffers: vec![],
+            textures: vec![],
+            render_targets: vec![],
+            frame_buffer: SoftwareTexture::new(width, height, 1, false, true),
+            active_render_target: None,
+            active_shader: ShaderType::Simple { clip_texture: None },
+            blend_mode: BlendMode::SourceOver,
+            transform: Matrix::identity(),
+        }
+    }
+
+    ///
+    /// Returns the width and height of the frame buffer for this renderer
+    ///
+    pub fn size(&self) -> (usize, usize) {
+        (self.frame_buffer.width, self.frame_buffer.height)
+    }
+
+    ///
+    /// Returns the contents of the frame buffer as RGBA bytes, with premultiplied alpha and the first row at the bottom of the image
+    ///
+    pub fn to_rgba_bytes(&self) -> Vec<u8> {
+        self.frame_buffer.to_rgba_bytes()
+    }
+
+    ///
+    /// Performs rendering of the specified actions to this renderer
+    ///
+    pub fn render<Actions: IntoIterator<Item = RenderAction>>(&mut self, actions: Actions) {
+        // Each set of actions starts with the default blend mode
+        self.blend_mode = BlendMode::SourceOver;
+
+        for action in actions {
+            use self::RenderAction::*;
+
+            match action {
+                SetTransform(matrix) => {
+                    self.transform = matrix;
+                }
+                CreateVertex2DBuffer(VertexBufferId(id), vertices) => {
+                    ensure_slot(&mut self.vertex_buffers, id);
+                    self.vertex_buffers[id] = Some(vertices);
+                }
+                CreateIndexBuffer(IndexBufferId(id), indices) => {
+                    ensure_slot(&mut self.index_buffers, id);
+                    self.index_buffers[id] = Some(indices);
+                }
+                FreeVertexBuffer(VertexBufferId(id)) => {
+                    if let Some(buffer) = self.vertex_buffers.get_mut(id) {
+                        *buffer = None;
+                    }
+                }
+                FreeIndexBuffer(IndexBufferId(id)) => {
+                    if let Some(buffer) = self.index_buffers.get_mut(id) {
+                        *buffer = None;
+                    }
+                }
+                BlendMode(blend_mode) => {
+                    self.blend_mode = blend_mode;
+                }
+                CreateRenderTarget(render_id, texture_id, Size2D(width, height), render_type) => {
+                    self.create_render_target(render_id, texture_id, width, height, render_type);
+                }
+                FreeRenderTarget(RenderTargetId(render_id)) => {
+                    if let Some(render_target) = self.render_targets.get_mut(render_id) {
+                        *render_target = None;
+                    }
+                }
+                SelectRenderTarget(render_id) => {
+                    self.active_render_target = Some(render_id);
+                }
+                RenderToFrameBuffer => {
+                    self.active_render_target = None;
+                }
+                DrawFrameBuffer(render_id, region, Alpha(alpha)) => {
+                    self.draw_frame_buffer(render_id, region, alpha as f32);
+                }
+                ShowFrameBuffer => { /* There's only a single frame buffer, so nothing to do */ }
+                CreateTextureBgra(texture_id, Size2D(width, height)) => {
+                    self.create_texture(texture_id, width, height, false);
+                }
+                CreateTextureMono(texture_id, Size2D(width, height)) => {
+                    self.create_texture(texture_id, width, height, true);
+                }
+                Create1DTextureBgra(texture_id, Size1D(width)) => {
+                    self.create_texture(texture_id, width, 1, false);
+                }
+                Create1DTextureMono(texture_id, Size1D(width)) => {
+                    self.create_texture(texture_id, width, 1, true);
+                }
+                WriteTextureData(
+                    TextureId(texture_id),
+                    Position2D(x1, y1),
+                    Position2D(x2, y2),
+                    data,
+                ) => {
+                    if let Some(Some(texture)) = self.textures.get_mut(texture_id) {
+                        texture.write_bytes((x1, y1), (x2, y2), &data);
+                    }
+                }
+                WriteTexture1D(TextureId(texture_id), Position1D(x1), Position1D(x2), data) => {
+                    if let Some(Some(texture)) = self.textures.get_mut(texture_id) {
+                        texture.write_bytes((x1, 0), (x2, 1), &data);
+                    }
+                }
+                CreateMipMaps(_) => { /* Textures are always sampled from the full-size image */ }
+                CopyTexture(TextureId(source_id), TextureId(target_id)) => {
+                    let copy = self.textures.get(source_id).cloned().flatten();
+                    ensure_slot(&mut self.textures, target_id);
+                    self.textures[target_id] = copy;
+                }
+                FilterTexture(texture_id, filters) => {
+                    self.filter_texture(texture_id, filters);
+                }
+                FreeTexture(TextureId(texture_id)) => {
+                    if let Some(texture) = self.textures.get_mut(texture_id) {
+                        *texture = None;
+                    }
+                }
+                Clear(Rgba8([r, g, b, a])) => {
+                    let color = [r, g, b, a].map(|component| (component as f32) / 255.0);
+                    self.with_active_target(|target, _| target.clear(color));
+                }
+                UseShader(shader_type) => {
+                    self.active_shader = shader_type;
+                }
+                DrawTriangles(buffer_id, buffer_range) => {
+                    self.draw_triangles(buffer_id, buffer_range);
+                }
+                DrawIndexedTriangles(vertex_buffer, index_buffer, num_vertices) => {
+                    self.draw_indexed_triangles(vertex_buffer, index_buffer, num_vertices);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Creates an empty texture (with a height of 1 for 1D textures)
+    ///
+    fn create_texture(
+        &mut self,
+        TextureId(texture_id): TextureId,
+        width: usize,
+        height: usize,
+        mono: bool,
+    ) {
+        ensure_slot(&mut self.textures, texture_id);
+        self.textures[texture_id] = Some(SoftwareTexture::new(width, height, 1, mono, false));
+    }
+
+    ///
+    /// Creates a new render target, replacing any existing texture with the same ID
+    ///
+    fn create_render_target(
+        &mut self,
+        RenderTargetId(render_id): RenderTargetId,
+        TextureId(texture_id): TextureId,
+        width: usize,
+        height: usize,
+        render_type: RenderTargetType,
+    ) {
+        use self::RenderTargetType::*;
+
+        let samples = match render_type {
+            Standard | StandardForReading | Monochrome => 1,
+            Multisampled | MultisampledTexture | MonochromeMultisampledTexture => 4,
+        };
+        let mono = match render_type {
+            Monochrome | MonochromeMultisampledTexture => true,
+            Standard | StandardForReading | Multisampled | MultisampledTexture => false,
+        };
+
+        ensure_slot(&mut self.textures, texture_id);
+        ensure_slot(&mut self.render_targets, render_id);
+
+        self.textures[texture_id] = Some(SoftwareTexture::new(width, height, samples, mono, true));
+        self.render_targets[render_id] = Some(TextureId(texture_id));
+    }
+
+    ///
+    /// Applies a set of filters to a texture, which will have premultiplied alpha afterwards
+    ///
+    fn filter_texture(&mut self, TextureId(texture_id): TextureId, filters: Vec<TextureFilter>) {
+        let mut filtered = if let Some(Some(texture)) = self.textures.get(texture_id) {
+            texture.resolve_premultiplied()
+        } else {
+            return;
+        };
+
+        for filter in filters.iter() {
+            filtered = filter_texture(&filtered, filter, &self.textures);
+        }
+
+        self.textures[texture_id] = Some(filtered);
+    }
+
+    ///
+    /// Calls a function with the texture for the active render target and the other textures
+    ///
+    /// The render target's texture is removed from the texture list while the function is running, so it can't be used
+    /// as a source texture while it's being drawn to.
+    ///
+    fn with_active_target(&mut self, action: impl FnOnce(&mut SoftwareTexture, &SoftwareRenderer)) {
+        match self.active_render_target {
+            None => {
+                let mut frame_buffer = mem::replace(
+                    &mut self.frame_buffer,
+                    SoftwareTexture::new(0, 0, 1, false, true),
+                );
+                action(&mut frame_buffer, self);
+                self.frame_buffer = frame_buffer;
+            }
+
+            Some(RenderTargetId(render_id)) => {
+                let texture_id = self.render_targets.get(render_id).cloned().flatten();
+                let TextureId(texture_id) = if let Some(texture_id) = texture_id {
+                    texture_id
+                } else {
+                    return;
+                };
+
+                let target = self
+                    .textures
+                    .get_mut(texture_id)
+                    .and_then(|texture| texture.take());
+                if let Some(mut target) = target {
+                    action(&mut target, self);
+                    self.textures[texture_id] = Some(target);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Renders triangles from a vertex buffer
+    ///
+    fn draw_triangles(&mut self, VertexBufferId(buffer_id): VertexBufferId, range: Range<usize>) {
+        self.with_active_target(|target, renderer| {
+            if let Some(Some(vertices)) = renderer.vertex_buffers.get(buffer_id) {
+                let end = range.end.min(vertices.len());
+                let start = range.start.min(end);
+
+                renderer.rasterize_vertices(target, (start..end).map(|idx| vertices[idx]));
+            }
+        });
+    }
+
+    ///
+    /// Renders triangles using an index buffer
+    ///
+    fn draw_indexed_triangles(
+        &mut self,
+        VertexBufferId(vertex_buffer): VertexBufferId,
+        IndexBufferId(index_buffer): IndexBufferId,
+        num_vertices: usize,
+    ) {
+        self.with_active_target(|target, renderer| {
+            let vertices = renderer.vertex_buffers.get(vertex_buffer);
+            let indices = renderer.index_buffers.get(index_buffer);
+
+            if let (Some(Some(vertices)), Some(Some(indices))) = (vertices, indices) {
+                let num_vertices = num_vertices.min(indices.len());
+                let vertices = indices[0..num_vertices]
+                    .iter()
+                    .filter_map(|idx| vertices.get(*idx as usize).copied());
+
+                renderer.rasterize_vertices(target, vertices);
+            }
+        });
+    }
+
+    ///
+    /// Rasterizes a list of triangles using the active shader, blend mode and transform
+    ///
+    fn rasterize_vertices(
+        &self,
+        target: &mut SoftwareTexture,
+        vertices: impl Iterator<Item = Vertex2D>,
+    ) {
+        let width = target.width;
+        let height = target.height;
+        let samples = target.samples;
+        let vertices = vertices.collect::<Vec<_>>();
+
+        for triangle in vertices.chunks_exact(3) {
+            // Convert the vertices to window coordinates
+            let window = [0, 1, 2].map(|idx| {
+                let [x, y] = transform_point(&self.transform, triangle[idx].pos);
+
+                (
+                    ((x as f64) + 1.0) / 2.0 * (width as f64),
+                    ((y as f64) + 1.0) / 2.0 * (height as f64),
+                )
+            });
+
+            let colors = [0, 1, 2].map(|idx| {
+                triangle[idx]
+                    .color
+                    .map(|component| (component as f32) / 255.0)
+            });
+            let tex_coords = [0, 1, 2].map(|idx| triangle[idx].tex_coord);
+            let positions = [0, 1, 2].map(|idx| triangle[idx].pos);
+
+            rasterize_triangle(
+                width,
+                height,
+                samples,
+                window,
+                |x, y, barycentric, coverage| {
+                    let [w0, w1, w2] = barycentric.map(|weight| weight as f32);
+                    let interpolate =
+                        |values: [f32; 3]| values[0] * w0 + values[1] * w1 + values[2] * w2;
+
+                    let fragment = Fragment {
+                        color: [0, 1, 2, 3].map(|component| {
+                            interpolate([
+                                colors[0][component],
+                                colors[1][component],
+                                colors[2][component],
+                            ])
+                        }),
+                        tex_coord: [0, 1].map(|component| {
+                            interpolate([
+                                tex_coords[0][component],
+                                tex_coords[1][component],
+                                tex_coords[2][component],
+                            ])
+                        }),
+                        pos: [0, 1].map(|component| {
+                            interpolate([
+                                positions[0][component],
+                                positions[1][component],
+                                positions[2][component],
+                            ])
+                        }),
+                        paper_coord: [
+                            ((x as f32) + 0.5) / (width as f32),
+                            ((y as f32) + 0.5) / (height as f32),
+                        ],
+                    };
+
+                    if let Some(shaded) =
+                        shade_fragment(&self.active_shader, &fragment, &self.textures)
+                    {
+                        self.write_samples(
+                            target,
+                            x,
+                            y,
+                            coverage,
+                            shaded.color,
+                            shaded.premultiplied,
+                        );
+                    }
+                },
+            );
+        }
+    }
+
+    ///
+    /// Blends a colour into the covered samples of a pixel using the current blend mode
+    ///
+    #[inline]
+    fn write_samples(
+        &self,
+        target: &mut SoftwareTexture,
+        x: usize,
+        y: usize,
+        coverage: u32,
+        color: [f32; 4],
+        premultiplied: bool,
+    ) {
+        let pixel_start = (y * target.width + x) * target.samples;
+
+        for sample in 0..target.samples {
+            if (coverage & (1 << sample)) != 0 {
+                let idx = pixel_start + sample;
+                let blended = blend(self.blend_mode, color, premultiplied, target.pixels[idx]);
+
+                target.pixels[idx] = target.store_format(blended);
+            }
+        }
+    }
+
+    ///
+    /// Draws the pixels from a render target onto the same pixels in the active render target
+    ///
+    fn draw_frame_buffer(
+        &mut self,
+        RenderTargetId(source_buffer): RenderTargetId,
+        region: FrameBufferRegion,
+        alpha: f32,
+    ) {
+        let source_texture = self.render_targets.get(source_buffer).cloned().flatten();
+        let source_texture = if let Some(source_texture) = source_texture {
+            source_texture
+        } else {
+            return;
+        };
+
+        self.with_active_target(|target, renderer| {
+            let TextureId(source_texture) = source_texture;
+            let source = if let Some(Some(source)) = renderer.textures.get(source_texture) {
+                source
+            } else {
+                return;
+            };
+
+            let width = target.width as f64;
+            let height = target.height as f64;
+            let to_window = |x: f32, y: f32| {
+                (
+                    ((x as f64) + 1.0) / 2.0 * width,
+                    ((y as f64) + 1.0) / 2.0 * height,
+                )
+            };
+
+            let min = to_window(region.min_x(), region.min_y());
+            let max = to_window(region.max_x(), region.max_y());
+            let triangles = [
+                [min, (max.0, min.1), (min.0, max.1)],
+                [(max.0, min.1), (min.0, max.1), max],
+            ];
+
+            for triangle in triangles {
+                rasterize_triangle(
+                    target.width,
+                    target.height,
+                    target.samples,
+                    triangle,
+                    |x, y, _, coverage| {
+                        if x < source.width && y < source.height {
+                            let [r, g, b, a] = source.pixel(x, y);
+                            let color = if source.premultiplied {
+                                [r * alpha, g * alpha, b * alpha, a * alpha]
+                            } else {
+                                [r, g, b, a * alpha]
+                            };
+
+                            renderer.write_samples(
+                                target,
+                                x,
+                                y,
+                                coverage,
+                                color,
+                                source.premultiplied,
+                            );
+                        }
+                    },
+                );
+            }
+        });
+    }
+}
@@ -0,0 +1,306 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::texture::*;
+
+use crate::action::*;
+use crate::buffer::*;
+
+use std::f32;
+
+///
+/// The values interpolated from the vertices of a triangle for a single pixel
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Fragment {
+    /// The vertex colour, with components in the range 0-1
+    pub color: [f32; 4],
+
+    /// The texture coordinates from the vertices
+    pub tex_coord: [f32; 2],
+
+    /// The position of the fragment before the transform was applied
+    pub pos: [f32; 2],
+
+    /// The position of the fragment on the render target, in the range 0-1
+    pub paper_coord: [f32; 2],
+}
+
+///
+/// The colour generated by a shader for a fragment
+///
+#[derive(Clone, Copy, Debug)]
+pub struct ShadedColor {
+    /// The colour components
+    pub color: [f32; 4],
+
+    /// True if the colour components are premultiplied by the alpha component
+    pub premultiplied: bool,
+}
+
+impl ShadedColor {
+    ///
+    /// Multiplies the opacity of this colour by a factor
+    ///
+    #[inline]
+    fn with_alpha_factor(self, factor: f32) -> ShadedColor {
+        let [r, g, b, a] = self.color;
+
+        let color = if self.premultiplied {
+            [r * factor, g * factor, b * factor, a * factor]
+        } else {
+            [r, g, b, a * factor]
+        };
+
+        ShadedColor {
+            color,
+            premultiplied: self.premultiplied,
+        }
+    }
+}
+
+///
+/// Applies a transformation matrix to a point
+///
+#[inline]
+pub fn transform_point(Matrix(matrix): &Matrix, [x, y]: [f32; 2]) -> [f32; 2] {
+    let tx = matrix[0][0] * x + matrix[0][1] * y + matrix[0][3];
+    let ty = matrix[1][0] * x + matrix[1][1] * y + matrix[1][3];
+    let tw = matrix[3][0] * x + matrix[3][1] * y + matrix[3][3];
+
+    if tw != 0.0 && tw != 1.0 {
+        [tx / tw, ty / tw]
+    } else {
+        [tx, ty]
+    }
+}
+
+///
+/// Looks up a texture by ID
+///
+#[inline]
+fn texture(
+    textures: &[Option<SoftwareTexture>],
+    TextureId(texture_id): TextureId,
+) -> Option<&SoftwareTexture> {
+    textures
+        .get(texture_id)
+        .and_then(|texture| texture.as_ref())
+}
+
+///
+/// Returns the texture wrapping mode for a gradient spread
+///
+#[inline]
+fn gradient_wrap(spread: GradientSpread) -> TextureWrap {
+    match spread {
+        GradientSpread::Pad => TextureWrap::ClampToEdge,
+        GradientSpread::Repeat => TextureWrap::Repeat,
+        GradientSpread::Reflect => TextureWrap::MirroredRepeat,
+    }
+}
+
+///
+/// Finds the position along a radial gradient where the end circle is the unit circle (None if the point is outside of the gradient)
+///
+fn radial_gradient_pos(
+    [px, py]: [f32; 2],
+    (focal_x, focal_y): (f32, f32),
+    focal_radius: f32,
+) -> Option<f32> {
+    let (cdx, cdy) = (-focal_x, -focal_y);
+    let (pdx, pdy) = (px - focal_x, py - focal_y);
+    let dr = 1.0 - focal_radius;
+
+    let a = cdx * cdx + cdy * cdy - dr * dr;
+    let b = pdx * cdx + pdy * cdy + focal_radius * dr;
+    let c = pdx * pdx + pdy * pdy - focal_radius * focal_radius;
+
+    if a.abs() < 1e-6 {
+        let t = c / (2.0 * b);
+        return if focal_radius + t * dr >= 0.0 {
+            Some(t)
+        } else {
+            None
+        };
+    }
+
+    let discriminant = b * b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let t1 = (b + discriminant.sqrt()) / a;
+    let t2 = (b - discriminant.sqrt()) / a;
+
+    if focal_radius + t1.max(t2) * dr >= 0.0 {
+        Some(t1.max(t2))
+    } else if focal_radius + t1.min(t2) * dr >= 0.0 {
+        Some(t1.min(t2))
+    } else {
+        None
+    }
+}
+
+///
+/// Reads the coverage of a clip mask at the specified position on the render target
+///
+fn clip_coverage(clip_texture: &SoftwareTexture, [paper_x, paper_y]: [f32; 2]) -> f32 {
+    if clip_texture.width == 0 || clip_texture.height == 0 {
+        return 0.0;
+    }
+
+    let x = (paper_x * (clip_texture.width as f32)) as isize;
+    let y = (paper_y * (clip_texture.height as f32)) as isize;
+
+    if x < 0 || y < 0 || x as usize >= clip_texture.width || y as usize >= clip_texture.height {
+        return 0.0;
+    }
+
+    clip_texture.pixel(x as usize, y as usize)[0]
+}
+
+///
+/// Calculates the colour of a fragment using a shader, returning None if the fragment should be discarded
+///
+pub fn shade_fragment(
+    shader: &ShaderType,
+    fragment: &Fragment,
+    textures: &[Option<SoftwareTexture>],
+) -> Option<ShadedColor> {
+    use self::ShaderType::*;
+
+    let vertex_color = ShadedColor {
+        color: fragment.color,
+        premultiplied: false,
+    };
+
+    let (color, clip_texture) = match shader {
+        Simple { clip_texture } => (vertex_color, clip_texture),
+
+        DashedLine {
+            dash_texture,
+            clip_texture,
+        } => {
+            // The dash pattern is stored in the red channel of a 1D texture
+            let color = if let Some(dash_texture) = texture(textures, *dash_texture) {
+                let dash_alpha =
+                    dash_texture.sample_1d(fragment.tex_coord[0] / 200.0, TextureWrap::Repeat)[0];
+                vertex_color.with_alpha_factor(dash_alpha)
+            } else {
+                vertex_color
+            };
+
+            (color, clip_texture)
+        }
+
+        Texture {
+            texture: texture_id,
+            texture_transform,
+            repeat,
+            alpha,
+            clip_texture,
+        } => {
+            let color = if let Some(source) = texture(textures, *texture_id) {
+                let [u, v] = transform_point(texture_transform, fragment.pos);
+                let wrap = if *repeat {
+                    TextureWrap::Repeat
+                } else {
+                    TextureWrap::ClampToEdge
+                };
+
+                ShadedColor {
+                    color: source.sample(u, v, wrap),
+                    premultiplied: source.premultiplied,
+                }
+                .with_alpha_factor(*alpha)
+            } else {
+                vertex_color
+            };
+
+            (color, clip_texture)
+        }
+
+        LinearGradient {
+            texture: texture_id,
+            texture_transform,
+            spread,
+            alpha,
+            clip_texture,
+        } => {
+            let color = if let Some(gradient) = texture(textures, *texture_id) {
+                let [t, _] = transform_point(texture_transform, fragment.pos);
+
+                ShadedColor {
+                    color: gradient.sample_1d(t, gradient_wrap(*spread)),
+                    premultiplied: gradient.premultiplied,
+                }
+                .with_alpha_factor(*alpha)
+            } else {
+                vertex_color
+            };
+
+            (color, clip_texture)
+        }
+
+        RadialGradient {
+            texture: texture_id,
+            texture_transform,
+            focal_point,
+            focal_radius,
+            spread,
+            alpha,
+            clip_texture,
+        } => {
+            let color = if let Some(gradient) = texture(textures, *texture_id) {
+                let pos = transform_point(texture_transform, fragment.pos);
+                let t = radial_gradient_pos(pos, *focal_point, *focal_radius)?;
+
+                ShadedColor {
+                    color: gradient.sample_1d(t, gradient_wrap(*spread)),
+                    premultiplied: gradient.premultiplied,
+                }
+                .with_alpha_factor(*alpha)
+            } else {
+                vertex_color
+            };
+
+            (color, clip_texture)
+        }
+
+        ConicGradient {
+            texture: texture_id,
+            texture_transform,
+            spread,
+            alpha,
+            clip_texture,
+        } => {
+            let color = if let Some(gradient) = texture(textures, *texture_id) {
+                let [x, y] = transform_point(texture_transform, fragment.pos);
+                let t = (y.atan2(x) / (2.0 * f32::consts::PI)).rem_euclid(1.0);
+
+                ShadedColor {
+                    color: gradient.sample_1d(t, gradient_wrap(*spread)),
+                    premultiplied: gradient.premultiplied,
+                }
+                .with_alpha_factor(*alpha)
+            } else {
+                vertex_color
+            };
+
+            (color, clip_texture)
+        }
+    };
+
+    // Apply the clip mask, if there is one
+    let clip_texture = clip_texture.and_then(|clip_texture| texture(textures, clip_texture));
+
+    if let Some(clip_texture) = clip_texture {
+        Some(color.with_alpha_factor(clip_coverage(clip_texture, fragment.paper_coord)))
+    } else {
+        Some(color)
+    }
+}
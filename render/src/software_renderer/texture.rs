@@ -0,0 +1,285 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+///
+/// How a texture is read outside of the range 0-1
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TextureWrap {
+    /// The pixels at the edge of the texture are used outside of it
+    ClampToEdge,
+
+    /// The texture is repeated
+    Repeat,
+
+    /// The texture is repeated, and mirrored on every other repetition
+    MirroredRepeat,
+}
+
+///
+/// A texture or render target stored in main memory
+///
+/// Pixels are stored as floating point RGBA values, with the first row at the bottom of the texture (matching the
+/// OpenGL conventions used by the render actions). Multisampled textures store several samples for each pixel, which
+/// are averaged together whenever the texture is read.
+///
+#[derive(Clone, Debug)]
+pub struct SoftwareTexture {
+    /// The width of the texture in pixels
+    pub width: usize,
+
+    /// The height of the texture in pixels (1 for 1D textures)
+    pub height: usize,
+
+    /// The number of samples stored for each pixel
+    pub samples: usize,
+
+    /// True if this texture only stores the red channel
+    pub mono: bool,
+
+    /// True if the colour components in this texture are premultiplied by the alpha component
+    pub premultiplied: bool,
+
+    /// The samples making up this texture
+    pub pixels: Vec<[f32; 4]>,
+}
+
+impl TextureWrap {
+    ///
+    /// Maps a texel coordinate into the range 0..size
+    ///
+    #[inline]
+    fn wrap(&self, pos: isize, size: usize) -> usize {
+        let size = size as isize;
+
+        match self {
+            TextureWrap::ClampToEdge => pos.max(0).min(size - 1) as usize,
+            TextureWrap::Repeat => pos.rem_euclid(size) as usize,
+            TextureWrap::MirroredRepeat => {
+                let pos = pos.rem_euclid(size * 2);
+
+                if pos >= size {
+                    (size * 2 - 1 - pos) as usize
+                } else {
+                    pos as usize
+                }
+            }
+        }
+    }
+}
+
+impl SoftwareTexture {
+    ///
+    /// Creates a new texture with all of its pixels set to transparent
+    ///
+    pub fn new(
+        width: usize,
+        height: usize,
+        samples: usize,
+        mono: bool,
+        premultiplied: bool,
+    ) -> SoftwareTexture {
+        let samples = samples.max(1);
+
+        SoftwareTexture {
+            width,
+            height,
+            samples,
+            mono,
+            premultiplied,
+            pixels: vec![[0.0, 0.0, 0.0, 0.0]; width * height * samples],
+        }
+    }
+
+    ///
+    /// Converts a colour to the format stored in this texture
+    ///
+    #[inline]
+    pub fn store_format(&self, color: [f32; 4]) -> [f32; 4] {
+        if self.mono {
+            [color[0], 0.0, 0.0, 1.0]
+        } else {
+            color
+        }
+    }
+
+    ///
+    /// Sets every sample in the texture to the specified colour
+    ///
+    pub fn clear(&mut self, color: [f32; 4]) {
+        let color = self.store_format(color);
+        self.pixels.iter_mut().for_each(|pixel| *pixel = color);
+    }
+
+    ///
+    /// Writes byte data to a region of the texture (1 byte per pixel for mono textures, or 4 bytes in RGBA order otherwise)
+    ///
+    pub fn write_bytes(&mut self, (x1, y1): (usize, usize), (x2, y2): (usize, usize), data: &[u8]) {
+        let bytes_per_pixel = if self.mono { 1 } else { 4 };
+        let region_width = x2.saturating_sub(x1);
+
+        for y in y1..y2.min(self.height) {
+            for x in x1..x2.min(self.width) {
+                let offset = ((y - y1) * region_width + (x - x1)) * bytes_per_pixel;
+                if offset + bytes_per_pixel > data.len() {
+                    continue;
+                }
+
+                let color = if self.mono {
+                    [(data[offset] as f32) / 255.0, 0.0, 0.0, 1.0]
+                } else {
+                    [
+                        (data[offset] as f32) / 255.0,
+                        (data[offset + 1] as f32) / 255.0,
+                        (data[offset + 2] as f32) / 255.0,
+                        (data[offset + 3] as f32) / 255.0,
+                    ]
+                };
+
+                let pixel_start = (y * self.width + x) * self.samples;
+                self.pixels[pixel_start..(pixel_start + self.samples)]
+                    .iter_mut()
+                    .for_each(|sample| *sample = color);
+            }
+        }
+    }
+
+    ///
+    /// Reads a pixel from this texture, averaging its samples if it is multisampled
+    ///
+    #[inline]
+    pub fn pixel(&self, x: usize, y: usize) -> [f32; 4] {
+        let pixel_start = (y * self.width + x) * self.samples;
+
+        if self.samples == 1 {
+            self.pixels[pixel_start]
+        } else {
+            let mut total = [0.0, 0.0, 0.0, 0.0];
+            for sample in self.pixels[pixel_start..(pixel_start + self.samples)].iter() {
+                for component in 0..4 {
+                    total[component] += sample[component];
+                }
+            }
+
+            let samples = self.samples as f32;
+            total.map(|component| component / samples)
+        }
+    }
+
+    ///
+    /// Reads a pixel at a position that might be outside of the texture, using the specified wrapping mode
+    ///
+    #[inline]
+    pub fn texel(&self, x: isize, y: isize, wrap: TextureWrap) -> [f32; 4] {
+        self.pixel(wrap.wrap(x, self.width), wrap.wrap(y, self.height))
+    }
+
+    ///
+    /// Reads the texture at a texture coordinate (where 0-1 covers the whole texture), using bilinear filtering
+    ///
+    pub fn sample(&self, u: f32, v: f32, wrap: TextureWrap) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0, 0.0, 0.0, 0.0];
+        }
+
+        // Texel centres are at the half-pixel positions
+        let x = u * (self.width as f32) - 0.5;
+        let y = v * (self.height as f32) - 0.5;
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let fx = x - x0;
+        let fy = y - y0;
+        let x0 = x0 as isize;
+        let y0 = y0 as isize;
+
+        let tl = self.texel(x0, y0, wrap);
+        let tr = self.texel(x0 + 1, y0, wrap);
+        let bl = self.texel(x0, y0 + 1, wrap);
+        let br = self.texel(x0 + 1, y0 + 1, wrap);
+
+        let mut result = [0.0, 0.0, 0.0, 0.0];
+        for component in 0..4 {
+            let top = tl[component] * (1.0 - fx) + tr[component] * fx;
+            let bottom = bl[component] * (1.0 - fx) + br[component] * fx;
+            result[component] = top * (1.0 - fy) + bottom * fy;
+        }
+
+        result
+    }
+
+    ///
+    /// Reads a 1D texture at a texture coordinate, using linear filtering
+    ///
+    pub fn sample_1d(&self, u: f32, wrap: TextureWrap) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0, 0.0, 0.0, 0.0];
+        }
+
+        let x = u * (self.width as f32) - 0.5;
+        let x0 = x.floor();
+        let fx = x - x0;
+        let x0 = x0 as isize;
+
+        let left = self.texel(x0, 0, wrap);
+        let right = self.texel(x0 + 1, 0, wrap);
+
+        let mut result = [0.0, 0.0, 0.0, 0.0];
+        for component in 0..4 {
+            result[component] = left[component] * (1.0 - fx) + right[component] * fx;
+        }
+
+        result
+    }
+
+    ///
+    /// Returns a copy of this texture with a single sample per pixel and premultiplied alpha
+    ///
+    pub fn resolve_premultiplied(&self) -> SoftwareTexture {
+        let premultiplied = self.premultiplied;
+        let mut pixels = Vec::with_capacity(self.width * self.height);
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let [r, g, b, a] = self.pixel(x, y);
+
+                if premultiplied {
+                    pixels.push([r, g, b, a]);
+                } else {
+                    pixels.push([r * a, g * a, b * a, a]);
+                }
+            }
+        }
+
+        SoftwareTexture {
+            width: self.width,
+            height: self.height,
+            samples: 1,
+            mono: self.mono,
+            premultiplied: true,
+            pixels,
+        }
+    }
+
+    ///
+    /// Converts the contents of this texture to RGBA bytes
+    ///
+    pub fn to_rgba_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.width * self.height * 4);
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pixel = self.pixel(x, y);
+                bytes.extend(
+                    pixel
+                        .iter()
+                        .map(|component| (component.clamp(0.0, 1.0) * 255.0).round() as u8),
+                );
+            }
+        }
+
+        bytes
+    }
+}
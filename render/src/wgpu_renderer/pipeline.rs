@@ -40,6 +40,9 @@ pub(crate) struct Pipeline {
     /// The bind group layout for a linear gradient
     pub(crate) linear_gradient_layout: Arc<wgpu::BindGroupLayout>,
 
+    /// The bind group layout for the input texture and the copy of the destination read by the blend shader
+    pub(crate) blend_layout: Arc<wgpu::BindGroupLayout>,
+
     /// Bind group layout for the alpha blend filter
     pub(crate) alpha_blend_layout: Arc<wgpu::BindGroupLayout>,
 
@@ -78,6 +81,8 @@ impl Pipeline {
         let clip_bind_layout = device.create_bind_group_layout(&clip_bind_layout);
         let texture_layout = device.create_bind_group_layout(&texture_layout);
         let linear_gradient_layout = device.create_bind_group_layout(&linear_gradient_layout);
+        let blend_layout = config.blend_bind_group_layout();
+        let blend_layout = device.create_bind_group_layout(&blend_layout);
 
         let alpha_blend_layout = config.filter_alpha_blend_bind_group_layout();
         let alpha_blend_layout = device.create_bind_group_layout(&alpha_blend_layout);
@@ -94,7 +99,7 @@ impl Pipeline {
         let reduce_layout = device.create_bind_group_layout(&reduce_layout);
 
         let bind_layout = match config.shader_module {
-            WgpuShader::LinearGradient(..) => vec![
+            WgpuShader::Gradient(..) => vec![
                 &matrix_bind_layout,
                 &clip_bind_layout,
                 &linear_gradient_layout,
@@ -102,6 +107,7 @@ impl Pipeline {
             WgpuShader::Texture(..) => {
                 vec![&matrix_bind_layout, &clip_bind_layout, &texture_layout]
             }
+            WgpuShader::Blend(..) => vec![&matrix_bind_layout, &clip_bind_layout, &blend_layout],
             WgpuShader::Simple(..) => vec![&matrix_bind_layout, &clip_bind_layout],
             WgpuShader::Filter(FilterShader::AlphaBlend(..)) => vec![&alpha_blend_layout],
             WgpuShader::Filter(FilterShader::BlurFixed(..)) => vec![&blur_fixed_layout],
@@ -129,6 +135,7 @@ impl Pipeline {
             clip_mask_layout: Arc::new(clip_bind_layout),
             texture_layout: Arc::new(texture_layout),
             linear_gradient_layout: Arc::new(linear_gradient_layout),
+            blend_layout: Arc::new(blend_layout),
             alpha_blend_layout: Arc::new(alpha_blend_layout),
             blur_fixed_layout: Arc::new(blur_fixed_layout),
             blur_texture_layout: Arc::new(blur_texture_layout),
@@ -217,7 +224,7 @@ impl Pipeline {
     ) -> wgpu::BindGroup {
         match (&self.shader_module, clip_texture) {
             (
-                WgpuShader::LinearGradient(StandardShaderVariant::ClippingMask, _, _, _),
+                WgpuShader::Gradient(_, StandardShaderVariant::ClippingMask, _, _, _),
                 Some(clip_texture),
             )
             | (
@@ -241,7 +248,8 @@ impl Pipeline {
 
             (_, None)
             | (WgpuShader::Filter(_), _)
-            | (WgpuShader::LinearGradient(StandardShaderVariant::NoClipping, _, _, _), _)
+            | (WgpuShader::Blend(..), _)
+            | (WgpuShader::Gradient(_, StandardShaderVariant::NoClipping, _, _, _), _)
             | (WgpuShader::Texture(StandardShaderVariant::NoClipping, _, _, _, _), _)
             | (WgpuShader::Simple(StandardShaderVariant::NoClipping, _), _) => {
                 // Group 1 is bound to an empty set if clipping is off or no texture is defined
@@ -257,6 +265,8 @@ impl Pipeline {
     ///
     /// Creates the texture binding for the current shader
     ///
+    /// The destination texture is only used by the blend shader, which reads a copy of the render target that it's drawing to
+    ///
     pub fn bind_input_texture(
         &self,
         device: &wgpu::Device,
@@ -264,6 +274,7 @@ impl Pipeline {
         texture_settings_offset: usize,
         texture: Option<&wgpu::Texture>,
         sampler: Option<&wgpu::Sampler>,
+        destination: Option<&wgpu::Texture>,
     ) -> wgpu::BindGroup {
         let texture_settings_binding = wgpu::BufferBinding {
             buffer: texture_settings,
@@ -273,7 +284,7 @@ impl Pipeline {
         let texture_settings_binding = wgpu::BindingResource::Buffer(texture_settings_binding);
 
         match (self.shader_module, texture, sampler) {
-            (WgpuShader::LinearGradient(..), Some(texture), Some(sampler)) => {
+            (WgpuShader::Gradient(..), Some(texture), Some(sampler)) => {
                 // Create a view of the texture
                 let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
@@ -348,7 +359,72 @@ impl Pipeline {
                 })
             }
 
-            (WgpuShader::LinearGradient(..), _, None)
+            (WgpuShader::Blend(InputTextureType::Sampler, _), Some(texture), Some(sampler))
+                if destination.is_some() =>
+            {
+                // Create views of the source and the destination
+                let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+                let destination_view = destination
+                    .unwrap()
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+
+                // Bind to group 2
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("bind_input_blend_sampler"),
+                    layout: &*self.blend_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: texture_settings_binding,
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::TextureView(&view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: wgpu::BindingResource::Sampler(sampler),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 3,
+                            resource: wgpu::BindingResource::TextureView(&destination_view),
+                        },
+                    ],
+                })
+            }
+
+            (WgpuShader::Blend(InputTextureType::Multisampled, _), Some(texture), _)
+                if destination.is_some() =>
+            {
+                // Create views of the source and the destination
+                let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+                let destination_view = destination
+                    .unwrap()
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+
+                // Bind to group 2
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("bind_input_blend_multisampled"),
+                    layout: &*self.blend_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: texture_settings_binding,
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::TextureView(&view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 3,
+                            resource: wgpu::BindingResource::TextureView(&destination_view),
+                        },
+                    ],
+                })
+            }
+
+            (WgpuShader::Gradient(..), _, None)
+            | (WgpuShader::Blend(..), _, _)
             | (WgpuShader::Texture(_, InputTextureType::Sampler, ..), _, None) => {
                 // Group 2 is bound to an empty set if no texture is defined (or the sampler is missing when it was expected)
                 device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -140,7 +140,12 @@ impl PipelineConfiguration {
             match self.blending_mode {
                 None => None,
 
-                Some(SourceOver) => Some(create_add_blend_state(
+                // These modes read the destination colour, which is only possible when drawing a frame buffer (see
+                // `WgpuShader::Blend`), so they fall back to 'source over' everywhere else
+                Some(
+                    SourceOver | Overlay | ColorDodge | ColorBurn | HardLight | SoftLight
+                    | Difference | Hue | Saturation | Color | Luminosity,
+                ) => Some(create_add_blend_state(
                     SrcAlpha,
                     OneMinusSrcAlpha,
                     One,
@@ -178,6 +183,15 @@ impl PipelineConfiguration {
                     OneMinusDstAlpha,
                     OneMinusSrcAlpha,
                 )),
+                Some(Xor) => Some(create_add_blend_state(
+                    OneMinusDstAlpha,
+                    OneMinusSrcAlpha,
+                    OneMinusDstAlpha,
+                    OneMinusSrcAlpha,
+                )),
+                Some(Clear) => Some(create_add_blend_state(Zero, Zero, Zero, Zero)),
+                Some(Copy) => Some(create_add_blend_state(SrcAlpha, Zero, One, Zero)),
+                Some(Destination) => Some(create_add_blend_state(Zero, One, Zero, One)),
 
                 // Multiply is a*b. Here we multiply the source colour by the destination colour, then blend the destination back in again to take account of
                 // alpha in the source layer (this version of multiply has no effect on the target alpha value: a more strict version might multiply those too)
@@ -199,6 +213,35 @@ impl PipelineConfiguration {
                     Add,
                 )),
 
+                // Darken and lighten pick the minimum or maximum colour (the blend factors are ignored). The source colour is
+                // precalculated so that transparent pixels leave the destination alone
+                Some(Darken) => Some(create_op_blend_state(
+                    One,
+                    One,
+                    One,
+                    OneMinusSrcAlpha,
+                    Min,
+                    Add,
+                )),
+                Some(Lighten) => Some(create_op_blend_state(
+                    One,
+                    One,
+                    One,
+                    OneMinusSrcAlpha,
+                    Max,
+                    Add,
+                )),
+
+                // Exclusion is a+b-2ab, which is the same as a*(1-b) + b*(1-a). This is exact when the destination is opaque.
+                Some(Exclusion) => Some(create_add_blend_state(
+                    OneMinusDst,
+                    OneMinusSrc,
+                    One,
+                    OneMinusSrcAlpha,
+                )),
+
+                Some(Plus) => Some(create_add_blend_state(SrcAlpha, One, One, One)),
+
                 Some(AllChannelAlphaSourceOver) => Some(create_add_blend_state(
                     One,
                     OneMinusDst,
@@ -217,7 +260,12 @@ impl PipelineConfiguration {
             match self.blending_mode {
                 None => None,
 
-                Some(SourceOver) => Some(create_add_blend_state(
+                // These modes read the destination colour, which is only possible when drawing a frame buffer (see
+                // `WgpuShader::Blend`), so they fall back to 'source over' everywhere else
+                Some(
+                    SourceOver | Overlay | ColorDodge | ColorBurn | HardLight | SoftLight
+                    | Difference | Hue | Saturation | Color | Luminosity,
+                ) => Some(create_add_blend_state(
                     One,
                     OneMinusSrcAlpha,
                     One,
@@ -255,6 +303,15 @@ impl PipelineConfiguration {
                     OneMinusDstAlpha,
                     OneMinusSrcAlpha,
                 )),
+                Some(Xor) => Some(create_add_blend_state(
+                    OneMinusDstAlpha,
+                    OneMinusSrcAlpha,
+                    OneMinusDstAlpha,
+                    OneMinusSrcAlpha,
+                )),
+                Some(Clear) => Some(create_add_blend_state(Zero, Zero, Zero, Zero)),
+                Some(Copy) => Some(create_add_blend_state(One, Zero, One, Zero)),
+                Some(Destination) => Some(create_add_blend_state(Zero, One, Zero, One)),
 
                 Some(Multiply) => Some(create_add_blend_state(Dst, Zero, Zero, One)),
 
@@ -268,6 +325,32 @@ impl PipelineConfiguration {
                     Add,
                 )),
 
+                Some(Darken) => Some(create_op_blend_state(
+                    One,
+                    One,
+                    One,
+                    OneMinusSrcAlpha,
+                    Min,
+                    Add,
+                )),
+                Some(Lighten) => Some(create_op_blend_state(
+                    One,
+                    One,
+                    One,
+                    OneMinusSrcAlpha,
+                    Max,
+                    Add,
+                )),
+
+                Some(Exclusion) => Some(create_add_blend_state(
+                    OneMinusDst,
+                    OneMinusSrc,
+                    One,
+                    OneMinusSrcAlpha,
+                )),
+
+                Some(Plus) => Some(create_add_blend_state(One, One, One, One)),
+
                 Some(AllChannelAlphaSourceOver) => Some(create_add_blend_state(
                     One,
                     OneMinusSrc,
@@ -415,7 +498,7 @@ impl PipelineConfiguration {
 
         // The type of binding that's in use depends on if the shader module has a clipping mask or not
         match self.shader_module {
-            WgpuShader::LinearGradient(StandardShaderVariant::ClippingMask, _, _, _)
+            WgpuShader::Gradient(_, StandardShaderVariant::ClippingMask, _, _, _)
             | WgpuShader::Texture(StandardShaderVariant::ClippingMask, _, _, _, _)
             | WgpuShader::Simple(StandardShaderVariant::ClippingMask, _) => {
                 wgpu::BindGroupLayoutDescriptor {
@@ -425,7 +508,8 @@ impl PipelineConfiguration {
             }
 
             WgpuShader::Filter(_)
-            | WgpuShader::LinearGradient(StandardShaderVariant::NoClipping, _, _, _)
+            | WgpuShader::Blend(..)
+            | WgpuShader::Gradient(_, StandardShaderVariant::NoClipping, _, _, _)
             | WgpuShader::Texture(StandardShaderVariant::NoClipping, _, _, _, _)
             | WgpuShader::Simple(StandardShaderVariant::NoClipping, _) => {
                 wgpu::BindGroupLayoutDescriptor {
@@ -514,7 +598,8 @@ impl PipelineConfiguration {
             }
 
             WgpuShader::Filter(_)
-            | WgpuShader::LinearGradient(_, _, _, _)
+            | WgpuShader::Blend(..)
+            | WgpuShader::Gradient(_, _, _, _, _)
             | WgpuShader::Simple(_, _) => wgpu::BindGroupLayoutDescriptor {
                 label: Some("texture_bind_group_layout_not_texture_shader"),
                 entries: &NOT_TEXTURE_SHADER,
@@ -561,12 +646,13 @@ impl PipelineConfiguration {
         ];
 
         match self.shader_module {
-            WgpuShader::LinearGradient(_, _, _, _) => wgpu::BindGroupLayoutDescriptor {
+            WgpuShader::Gradient(_, _, _, _, _) => wgpu::BindGroupLayoutDescriptor {
                 label: Some("texture_bind_group_layout_sampler"),
                 entries: &WITH_SAMPLER,
             },
 
             WgpuShader::Filter(_)
+            | WgpuShader::Blend(..)
             | WgpuShader::Texture(_, _, _, _, _)
             | WgpuShader::Simple(_, _) => wgpu::BindGroupLayoutDescriptor {
                 label: Some("texture_bind_group_layout_not_texture_shader"),
@@ -575,6 +661,88 @@ impl PipelineConfiguration {
         }
     }
 
+    ///
+    /// Creates the bind group layout descriptor for the blend shader bind group (this is bind group 2 in the shaders)
+    ///
+    /// This is the same as the texture bind group, with the copy of the destination texture in binding 3
+    ///
+    #[inline]
+    pub fn blend_bind_group_layout<'a>(&'a self) -> wgpu::BindGroupLayoutDescriptor<'a> {
+        const TEXTURE_SETTINGS: wgpu::BindGroupLayoutEntry = wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+            count: None,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: wgpu::BufferSize::new(mem::size_of::<TextureSettings>() as _),
+            },
+        };
+        const SAMPLED_TEXTURE: wgpu::BindGroupLayoutEntry = wgpu::BindGroupLayoutEntry {
+            binding: 1,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            count: None,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+        };
+        const MULTISAMPLED_TEXTURE: wgpu::BindGroupLayoutEntry = wgpu::BindGroupLayoutEntry {
+            binding: 1,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            count: None,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: true,
+            },
+        };
+        const SAMPLER: wgpu::BindGroupLayoutEntry = wgpu::BindGroupLayoutEntry {
+            binding: 2,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            count: None,
+            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        };
+        const DESTINATION: wgpu::BindGroupLayoutEntry = wgpu::BindGroupLayoutEntry {
+            binding: 3,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            count: None,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+        };
+        static NOT_BLEND_SHADER: [wgpu::BindGroupLayoutEntry; 0] = [];
+        static WITH_SAMPLER: [wgpu::BindGroupLayoutEntry; 4] =
+            [TEXTURE_SETTINGS, SAMPLED_TEXTURE, SAMPLER, DESTINATION];
+        static WITH_MULTISAMPLE: [wgpu::BindGroupLayoutEntry; 3] =
+            [TEXTURE_SETTINGS, MULTISAMPLED_TEXTURE, DESTINATION];
+
+        match self.shader_module {
+            WgpuShader::Blend(InputTextureType::Sampler, _) => wgpu::BindGroupLayoutDescriptor {
+                label: Some("blend_bind_group_layout_sampler"),
+                entries: &WITH_SAMPLER,
+            },
+
+            WgpuShader::Blend(InputTextureType::Multisampled, _) => {
+                wgpu::BindGroupLayoutDescriptor {
+                    label: Some("blend_bind_group_layout_multisampled"),
+                    entries: &WITH_MULTISAMPLE,
+                }
+            }
+
+            WgpuShader::Filter(_)
+            | WgpuShader::Gradient(_, _, _, _, _)
+            | WgpuShader::Texture(_, _, _, _, _)
+            | WgpuShader::Simple(_, _) => wgpu::BindGroupLayoutDescriptor {
+                label: Some("blend_bind_group_layout_not_blend_shader"),
+                entries: &NOT_BLEND_SHADER,
+            },
+        }
+    }
+
     ///
     /// Returns the layout for the alpha blend filter shader
     ///
@@ -64,6 +64,7 @@ pub struct RenderPassResources {
         TextureSettings,
         Option<Arc<wgpu::Texture>>,
         Option<Arc<wgpu::Sampler>>,
+        Option<Arc<wgpu::Texture>>,
     )>,
 
     /// Once the render pass is running, the buffer containing all of the texture settings from the texture_settings Vec
@@ -191,7 +192,7 @@ impl RenderPassResources {
         let mut aligned_settings = vec![0; group_offset * settings.len()];
         for setting_num in 0..settings.len() {
             // Create a buffer containing the setting
-            let (_, texture_setting, _, _) = &settings[setting_num];
+            let (_, texture_setting, _, _, _) = &settings[setting_num];
 
             let settings_void = texture_setting as *const _ as *const c_void;
             let settings_u8 =
@@ -214,13 +215,14 @@ impl RenderPassResources {
         let bind_groups = (0..settings.len())
             .into_iter()
             .map(|setting_num| {
-                let (pipeline, _, texture, sampler) = &settings[setting_num];
+                let (pipeline, _, texture, sampler, destination) = &settings[setting_num];
                 pipeline.bind_input_texture(
                     device,
                     &settings_buffer,
                     setting_num * group_offset,
                     texture.as_ref().map(|t| &**t),
                     sampler.as_ref().map(|s| &**s),
+                    destination.as_ref().map(|d| &**d),
                 )
             })
             .collect();
@@ -71,6 +71,9 @@ pub(crate) struct RendererState {
     /// The sampler for the current shader (or none)
     pub sampler: Option<Arc<wgpu::Sampler>>,
 
+    /// The copy of the render target read by the blend shader (or none)
+    pub destination_texture: Option<Arc<wgpu::Texture>>,
+
     /// The texture to present to the surface once the rendering is done
     pub present: Option<wgpu::SurfaceTexture>,
 }
@@ -110,6 +113,7 @@ impl RendererState {
             input_texture: None,
             clip_texture: None,
             sampler: None,
+            destination_texture: None,
             present: None,
         }
     }
@@ -214,6 +218,7 @@ impl RendererState {
             let texture_settings = self.texture_settings;
             let input_texture = self.input_texture.clone();
             let sampler = self.sampler.clone();
+            let destination_texture = self.destination_texture.clone();
 
             // Set up the texture binding
             let settings_buffer_index = self.render_pass_resources.texture_settings.len();
@@ -223,6 +228,7 @@ impl RendererState {
                 texture_settings,
                 input_texture,
                 sampler,
+                destination_texture,
             ));
 
             // Add a callback function to actually set up the render pipeline (we have to do it indirectly later on because it borrows its resources)
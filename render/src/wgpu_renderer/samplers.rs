@@ -18,9 +18,12 @@ pub(crate) struct Samplers {
     /// Sampler that doesn't repeat
     non_repeating_sampler: Arc<wgpu::Sampler>,
 
-    /// The sampler used for rendering gradients
+    /// The sampler used for rendering gradients, which mirrors the gradient when it repeats
     gradient_sampler: Arc<wgpu::Sampler>,
 
+    /// The sampler used for rendering gradients that repeat without mirroring
+    repeating_gradient_sampler: Arc<wgpu::Sampler>,
+
     /// The sampler used for rendering gradients, which clamps rather than repeating
     non_repeating_gradient_sampler: Arc<wgpu::Sampler>,
 }
@@ -75,6 +78,21 @@ impl Samplers {
             border_color: None,
         });
 
+        let repeating_gradient_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("gradient_sampler"),
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            address_mode_w: wgpu::AddressMode::Repeat,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 0.0,
+            compare: None,
+            anisotropy_clamp: 1,
+            border_color: None,
+        });
+
         let non_repeating_gradient_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             label: Some("gradient_sampler"),
             address_mode_u: wgpu::AddressMode::ClampToEdge,
@@ -94,6 +112,7 @@ impl Samplers {
             default_sampler: Arc::new(default_sampler),
             non_repeating_sampler: Arc::new(non_repeating_sampler),
             gradient_sampler: Arc::new(gradient_sampler),
+            repeating_gradient_sampler: Arc::new(repeating_gradient_sampler),
             non_repeating_gradient_sampler: Arc::new(non_repeating_gradient_sampler),
         }
     }
@@ -113,6 +132,11 @@ impl Samplers {
         Arc::clone(&self.gradient_sampler)
     }
 
+    #[inline]
+    pub fn repeating_gradient_sampler(&self) -> Arc<wgpu::Sampler> {
+        Arc::clone(&self.repeating_gradient_sampler)
+    }
+
     #[inline]
     pub fn non_repeating_gradient_sampler(&self) -> Arc<wgpu::Sampler> {
         Arc::clone(&self.non_repeating_gradient_sampler)
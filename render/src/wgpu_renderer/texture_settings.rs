@@ -12,5 +12,6 @@
 pub struct TextureSettings {
     pub transform: [[f32; 4]; 4],
     pub alpha: f32,
-    pub _padding: [u32; 3],
+    pub focal_point: [f32; 2],
+    pub focal_radius: f32,
 }
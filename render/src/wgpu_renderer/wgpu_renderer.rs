@@ -226,7 +226,8 @@ impl WgpuRenderer {
 
         if let Some(target_surface) = &self.target_surface {
             // Fetch the format
-            let possible_formats = target_surface.get_capabilities(&*self.adapter).formats;
+            let capabilities = target_surface.get_capabilities(&*self.adapter);
+            let possible_formats = capabilities.formats;
             let actual_format = possible_formats
                 .iter()
                 .filter(|format| !format.is_srgb())
@@ -234,8 +235,15 @@ impl WgpuRenderer {
                 .copied();
             let actual_format = actual_format.unwrap_or(possible_formats[0]);
 
+            // The blend modes that read the destination colour need to be able to copy the surface
+            let usage = if capabilities.usages.contains(wgpu::TextureUsages::COPY_SRC) {
+                wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC
+            } else {
+                wgpu::TextureUsages::RENDER_ATTACHMENT
+            };
+
             let surface_config = wgpu::SurfaceConfiguration {
-                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                usage: usage,
                 format: actual_format,
                 width: width,
                 height: height,
@@ -838,16 +846,33 @@ impl WgpuRenderer {
             InputTextureType::Multisampled
         };
 
+        // The blend modes that read the destination colour use a shader that reads from a copy of the render target
+        let blend_mode = self.active_blend_mode.unwrap_or(BlendMode::SourceOver);
+        let destination = if blend_mode.reads_destination() {
+            self.copy_render_target(state)
+        } else {
+            None
+        };
+
         state.input_texture = Some(texture);
         state.sampler = Some(self.samplers.default_sampler());
-        state.pipeline_configuration.shader_module = WgpuShader::Texture(
-            StandardShaderVariant::NoClipping,
-            texture_type,
-            TexturePosition::Separate,
-            AlphaBlendStep::Premultiply,
-            ColorPostProcessingStep::NoPostProcessing,
-        );
-        state.pipeline_configuration.blending_mode = Some(BlendMode::SourceOver);
+
+        if let Some(destination) = destination {
+            // The blend shader generates the final colour, so the blending hardware is not used
+            state.destination_texture = Some(destination);
+            state.pipeline_configuration.shader_module =
+                WgpuShader::Blend(texture_type, blend_mode);
+            state.pipeline_configuration.blending_mode = None;
+        } else {
+            state.pipeline_configuration.shader_module = WgpuShader::Texture(
+                StandardShaderVariant::NoClipping,
+                texture_type,
+                TexturePosition::Separate,
+                AlphaBlendStep::Premultiply,
+                Self::post_processing_for_blend_mode(blend_mode),
+            );
+            state.pipeline_configuration.blending_mode = Some(blend_mode);
+        }
         state.pipeline_configuration.source_is_premultiplied = true;
         state.pipeline_config_changed = true;
         state.pipeline_bindings_changed = true;
@@ -950,6 +975,7 @@ impl WgpuRenderer {
         // Restore the render state
         state.input_texture = old_texture;
         state.sampler = old_sampler;
+        state.destination_texture = None;
         state.active_matrix = old_matrix;
         state.texture_settings = old_texture_settings;
         state.pipeline_configuration = old_pipeline_config;
@@ -957,6 +983,84 @@ impl WgpuRenderer {
         state.pipeline_bindings_changed = true;
     }
 
+    ///
+    /// Copies the current render target to a new texture, so the blend shader can read the destination colour
+    ///
+    /// Multisampled render targets are resolved as they are copied, so the copy always has a single sample. Returns None
+    /// if the render target can't be copied (a surface might not support being the source of a copy)
+    ///
+    fn copy_render_target(&mut self, state: &mut RendererState) -> Option<Arc<wgpu::Texture>> {
+        let render_target_texture;
+        let target = if let Some(RenderTargetId(render_id)) = self.active_render_target {
+            render_target_texture = self.render_targets.get(render_id)?.as_ref()?.texture();
+            &*render_target_texture
+        } else if let Some(surface_texture) = &self.target_surface_texture {
+            &surface_texture.texture
+        } else {
+            &**self.target_texture.as_ref()?
+        };
+
+        let is_multisampled = target.sample_count() > 1;
+        if !is_multisampled && !target.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+            return None;
+        }
+
+        // Finish the current render pass so the copy contains everything drawn so far
+        #[cfg(feature = "profile")]
+        self.profiler
+            .borrow_mut()
+            .start_action(RenderActionType::RunRenderPass);
+        state.run_render_pass();
+        #[cfg(feature = "profile")]
+        self.profiler
+            .borrow_mut()
+            .finish_action(RenderActionType::RunRenderPass);
+
+        let copy = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("copy_render_target"),
+            size: target.size(),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: target.format(),
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+
+        if is_multisampled {
+            // Resolve the multisampled texture into the copy using an otherwise empty render pass
+            let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            let copy_view = copy.create_view(&wgpu::TextureViewDescriptor::default());
+
+            state
+                .encoder
+                .begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("copy_render_target"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &target_view,
+                        resolve_target: Some(&copy_view),
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+        } else {
+            state.encoder.copy_texture_to_texture(
+                target.as_image_copy(),
+                copy.as_image_copy(),
+                target.size(),
+            );
+        }
+
+        Some(Arc::new(copy))
+    }
+
     ///
     /// Displays the current frame buffer to the screen
     ///
@@ -1654,6 +1758,20 @@ impl WgpuRenderer {
         self.update_shader(self.active_shader, self.active_blend_mode, state);
     }
 
+    ///
+    /// Returns the post-processing step that the blending hardware needs the shader to perform for a blend mode
+    ///
+    fn post_processing_for_blend_mode(blend_mode: BlendMode) -> ColorPostProcessingStep {
+        match blend_mode {
+            BlendMode::Multiply | BlendMode::Darken => ColorPostProcessingStep::InvertColorAlpha,
+            BlendMode::Screen | BlendMode::Lighten | BlendMode::Exclusion => {
+                ColorPostProcessingStep::MultiplyAlpha
+            }
+
+            _ => ColorPostProcessingStep::NoPostProcessing,
+        }
+    }
+
     ///
     /// Updates the render settings for a selected shader
     ///
@@ -1680,12 +1798,7 @@ impl WgpuRenderer {
         state.pipeline_configuration.blending_mode = Some(blend_mode);
 
         // The post-processing step depends on the blend mode
-        let post_processing = match blend_mode {
-            BlendMode::Multiply => ColorPostProcessingStep::InvertColorAlpha,
-            BlendMode::Screen => ColorPostProcessingStep::MultiplyAlpha,
-
-            _ => ColorPostProcessingStep::NoPostProcessing,
-        };
+        let post_processing = Self::post_processing_for_blend_mode(blend_mode);
 
         // Set up the pipeline based on the shader type
         match shader_type {
@@ -1790,66 +1903,63 @@ impl WgpuRenderer {
             LinearGradient {
                 texture,
                 texture_transform,
-                repeat,
+                spread,
                 alpha,
                 clip_texture,
             } => {
-                let TextureId(texture_id) = texture;
-                let texture = if let Some(Some(texture)) = self.textures.get(texture_id) {
-                    Some(texture)
-                } else {
-                    None
-                };
-
-                // Work out which clip texture to use (and the corresponding shader variant)
-                let clip_texture = if let Some(TextureId(clip_texture)) = clip_texture {
-                    if let Some(Some(texture)) = self.textures.get(clip_texture) {
-                        Some(Arc::clone(&texture.texture))
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                };
-                let variant = if clip_texture.is_some() {
-                    StandardShaderVariant::ClippingMask
-                } else {
-                    StandardShaderVariant::NoClipping
-                };
-
-                // Alpha blend step depends on if the texture is pre-multiplied
-                let alpha_blend = if let Some(true) = texture.map(|t| t.is_premultiplied) {
-                    AlphaBlendStep::Premultiply
-                } else {
-                    AlphaBlendStep::NoPremultiply
-                };
+                self.update_gradient_shader(
+                    GradientShape::Linear,
+                    texture,
+                    texture_transform,
+                    ((0.0, 0.0), 0.0),
+                    spread,
+                    alpha,
+                    clip_texture,
+                    post_processing,
+                    state,
+                );
+            }
 
-                // Set up the state
-                state.texture_settings = TextureSettings {
-                    transform: texture_transform.0,
-                    alpha: alpha as _,
-                    ..Default::default()
-                };
-                state.input_texture = texture.map(|t| Arc::clone(&t.texture));
-                if repeat {
-                    state.sampler = Some(self.samplers.gradient_sampler());
-                } else {
-                    state.sampler = Some(self.samplers.non_repeating_gradient_sampler());
-                }
+            RadialGradient {
+                texture,
+                texture_transform,
+                focal_point,
+                focal_radius,
+                spread,
+                alpha,
+                clip_texture,
+            } => {
+                self.update_gradient_shader(
+                    GradientShape::Radial,
+                    texture,
+                    texture_transform,
+                    (focal_point, focal_radius),
+                    spread,
+                    alpha,
+                    clip_texture,
+                    post_processing,
+                    state,
+                );
+            }
 
-                if let Some(texture) = &texture {
-                    state.pipeline_configuration.shader_module = WgpuShader::LinearGradient(
-                        variant,
-                        TexturePosition::InputPosition,
-                        alpha_blend,
-                        post_processing,
-                    );
-                    state.pipeline_configuration.source_is_premultiplied = texture.is_premultiplied;
-                } else {
-                    state.pipeline_configuration.shader_module =
-                        WgpuShader::Simple(variant, post_processing);
-                    state.pipeline_configuration.source_is_premultiplied = false;
-                }
+            ConicGradient {
+                texture,
+                texture_transform,
+                spread,
+                alpha,
+                clip_texture,
+            } => {
+                self.update_gradient_shader(
+                    GradientShape::Conic,
+                    texture,
+                    texture_transform,
+                    ((0.0, 0.0), 0.0),
+                    spread,
+                    alpha,
+                    clip_texture,
+                    post_processing,
+                    state,
+                );
             }
         }
 
@@ -1858,6 +1968,84 @@ impl WgpuRenderer {
         state.pipeline_bindings_changed = true;
     }
 
+    ///
+    /// Updates the render settings for one of the gradient shaders
+    ///
+    /// The focal point and radius are only used by radial gradients.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    fn update_gradient_shader(
+        &self,
+        shape: GradientShape,
+        texture: TextureId,
+        texture_transform: Matrix,
+        ((focal_x, focal_y), focal_radius): ((f32, f32), f32),
+        spread: GradientSpread,
+        alpha: f32,
+        clip_texture: Option<TextureId>,
+        post_processing: ColorPostProcessingStep,
+        state: &mut RendererState,
+    ) {
+        let TextureId(texture_id) = texture;
+        let texture = if let Some(Some(texture)) = self.textures.get(texture_id) {
+            Some(texture)
+        } else {
+            None
+        };
+
+        // Work out which clip texture to use (and the corresponding shader variant)
+        let clip_texture = if let Some(TextureId(clip_texture)) = clip_texture {
+            if let Some(Some(texture)) = self.textures.get(clip_texture) {
+                Some(Arc::clone(&texture.texture))
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+        let variant = if clip_texture.is_some() {
+            StandardShaderVariant::ClippingMask
+        } else {
+            StandardShaderVariant::NoClipping
+        };
+
+        // Alpha blend step depends on if the texture is pre-multiplied
+        let alpha_blend = if let Some(true) = texture.map(|t| t.is_premultiplied) {
+            AlphaBlendStep::Premultiply
+        } else {
+            AlphaBlendStep::NoPremultiply
+        };
+
+        // Set up the state
+        state.texture_settings = TextureSettings {
+            transform: texture_transform.0,
+            alpha: alpha as _,
+            focal_point: [focal_x, focal_y],
+            focal_radius: focal_radius,
+        };
+        state.input_texture = texture.map(|t| Arc::clone(&t.texture));
+        state.sampler = Some(match spread {
+            GradientSpread::Pad => self.samplers.non_repeating_gradient_sampler(),
+            GradientSpread::Repeat => self.samplers.repeating_gradient_sampler(),
+            GradientSpread::Reflect => self.samplers.gradient_sampler(),
+        });
+
+        if let Some(texture) = &texture {
+            state.pipeline_configuration.shader_module = WgpuShader::Gradient(
+                shape,
+                variant,
+                TexturePosition::InputPosition,
+                alpha_blend,
+                post_processing,
+            );
+            state.pipeline_configuration.source_is_premultiplied = texture.is_premultiplied;
+        } else {
+            state.pipeline_configuration.shader_module =
+                WgpuShader::Simple(variant, post_processing);
+            state.pipeline_configuration.source_is_premultiplied = false;
+        }
+    }
+
     ///
     /// Renders a set of triangles in a vertex buffer
     ///
@@ -6,6 +6,7 @@
 
 use super::shader_cache::*;
 use super::texture::*;
+use crate::action::BlendMode;
 
 use wgpu;
 
@@ -75,6 +76,21 @@ pub enum TexturePosition {
     Separate,
 }
 
+///
+/// The shape of the gradient rendered by a gradient shader
+///
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GradientShape {
+    /// The gradient runs along the x axis of the texture position
+    Linear,
+
+    /// Two-circle radial gradient, using the focal point and radius from the texture settings
+    Radial,
+
+    /// The gradient sweeps around the origin of the texture position
+    Conic,
+}
+
 ///
 /// The type of texture used as input for a texture shader
 ///
@@ -148,14 +164,18 @@ pub enum WgpuShader {
         ColorPostProcessingStep,
     ),
 
-    /// Renders a linear gradient
-    LinearGradient(
+    /// Renders a linear, radial or conic gradient
+    Gradient(
+        GradientShape,
         StandardShaderVariant,
         TexturePosition,
         AlphaBlendStep,
         ColorPostProcessingStep,
     ),
 
+    /// Blends a texture input with a copy of the destination (input texture type, blend mode)
+    Blend(InputTextureType, BlendMode),
+
     /// Runs a texture-to-texture filter
     Filter(FilterShader),
 }
@@ -169,6 +189,46 @@ impl Default for WgpuShader {
     }
 }
 
+impl GradientShape {
+    ///
+    /// Retrieves the name of the fragment shader entry point in the gradient shader for this shape
+    ///
+    pub fn fragment_shader_entry_point(&self) -> &'static str {
+        match self {
+            GradientShape::Linear => "gradient_fragment_shader",
+            GradientShape::Radial => "radial_gradient_fragment_shader",
+            GradientShape::Conic => "conic_gradient_fragment_shader",
+        }
+    }
+}
+
+///
+/// Retrieves the name of the fragment shader entry point in the blend shader for a blend mode
+///
+/// The Porter-Duff modes are all handled by the blending hardware, so they use the 'normal' blend function
+///
+fn blend_fragment_shader_entry_point(blend_mode: BlendMode) -> &'static str {
+    match blend_mode {
+        BlendMode::Multiply => "multiply_fragment_shader",
+        BlendMode::Screen => "screen_fragment_shader",
+        BlendMode::Darken => "darken_fragment_shader",
+        BlendMode::Lighten => "lighten_fragment_shader",
+        BlendMode::Overlay => "overlay_fragment_shader",
+        BlendMode::ColorDodge => "color_dodge_fragment_shader",
+        BlendMode::ColorBurn => "color_burn_fragment_shader",
+        BlendMode::HardLight => "hard_light_fragment_shader",
+        BlendMode::SoftLight => "soft_light_fragment_shader",
+        BlendMode::Difference => "difference_fragment_shader",
+        BlendMode::Exclusion => "exclusion_fragment_shader",
+        BlendMode::Hue => "hue_fragment_shader",
+        BlendMode::Saturation => "saturation_fragment_shader",
+        BlendMode::Color => "color_fragment_shader",
+        BlendMode::Luminosity => "luminosity_fragment_shader",
+
+        _ => "normal_fragment_shader",
+    }
+}
+
 impl ColorPostProcessingStep {
     ///
     /// Retrieves the `color_post_process` function for this post-processing step
@@ -313,7 +373,8 @@ impl WgpuShaderLoader for WgpuShader {
                 )
             }
 
-            WgpuShader::LinearGradient(
+            WgpuShader::Gradient(
+                shape,
                 variant,
                 texture_position,
                 alpha_blend,
@@ -334,14 +395,39 @@ impl WgpuShaderLoader for WgpuShader {
 
                 // Load the shader
                 let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-                    label: Some("WgpuShader::LinearGradient"),
+                    label: Some("WgpuShader::Gradient"),
                     source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(&base_module)),
                 });
 
                 (
                     Arc::new(shader_module),
                     "gradient_vertex_shader".to_string(),
-                    "gradient_fragment_shader".to_string(),
+                    shape.fragment_shader_entry_point().to_string(),
+                )
+            }
+
+            WgpuShader::Blend(input_type, blend_mode) => {
+                // The base module contains the blend functions in terms of the source and destination texture functions
+                let base_module = include_str!("../../shaders/texture/blend.wgsl");
+
+                // Amend the base module with the functions for reading the source and the destination
+                let base_module = format!(
+                    "{}\n\n{}\n\n{}",
+                    input_type.shader_function(),
+                    include_str!("../../shaders/texture/destination_texture.wgsl"),
+                    base_module
+                );
+
+                // Load the shader
+                let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some("WgpuShader::Blend"),
+                    source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(&base_module)),
+                });
+
+                (
+                    Arc::new(shader_module),
+                    "blend_vertex_shader".to_string(),
+                    blend_fragment_shader_entry_point(*blend_mode).to_string(),
                 )
             }
 
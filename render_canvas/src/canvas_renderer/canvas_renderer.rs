@@ -345,6 +345,20 @@ impl CanvasRenderer {
                     FillGradient(gradient_id, min, max) => {
                         self.tes_fill_gradient(self.current_namespace, gradient_id, min, max)
                     }
+                    FillRadialGradient(gradient_id, start_circle, end_circle) => self
+                        .tes_fill_radial_gradient(
+                            self.current_namespace,
+                            gradient_id,
+                            start_circle,
+                            end_circle,
+                        ),
+                    FillConicGradient(gradient_id, center, start_angle) => self
+                        .tes_fill_conic_gradient(
+                            self.current_namespace,
+                            gradient_id,
+                            center,
+                            start_angle,
+                        ),
                     FillTransform(transform) => self.tes_fill_transform(transform),
                     StrokeColor(color) => self.tes_stroke_color(color),
                     BlendMode(blend_mode) => self.tes_blend_mode(blend_mode),
@@ -30,6 +30,7 @@ impl CanvasRenderer {
             AddStop(pos, stop_colour) => {
                 self.tes_gradient_add_stop(namespace_id, gradient_id, pos, stop_colour)
             }
+            SpreadMode(spread) => self.tes_gradient_spread_mode(namespace_id, gradient_id, spread),
         }
     }
 
@@ -81,4 +82,27 @@ impl CanvasRenderer {
             }
         });
     }
+
+    ///
+    /// Sets the spread mode of an existing gradient definition
+    ///
+    pub(super) fn tes_gradient_spread_mode(
+        &mut self,
+        namespace_id: usize,
+        gradient_id: canvas::GradientId,
+        spread: canvas::GradientSpread,
+    ) {
+        self.core.sync(move |core| {
+            use canvas::GradientOp::SpreadMode;
+
+            match core.canvas_gradients.get_mut(&(namespace_id, gradient_id)) {
+                // The spread mode is applied by the shader, so a gradient that is already a texture can continue to use it
+                Some(RenderGradient::Defined(defn)) | Some(RenderGradient::Ready(_, defn)) => {
+                    defn.push(SpreadMode(spread))
+                }
+
+                None => {}
+            }
+        });
+    }
 }
@@ -85,11 +85,12 @@ impl CanvasRenderer {
                                 ));
                         }
 
-                        FillState::LinearGradient(
+                        FillState::Gradient(
                             gradient_texture,
                             _canvas_texture,
+                            shape,
                             matrix,
-                            repeat,
+                            spread,
                             alpha,
                         ) => {
                             // Increase the usage count for the texture
@@ -102,8 +103,9 @@ impl CanvasRenderer {
                                 .render_order
                                 .push(RenderEntity::SetFillGradient(
                                     gradient_texture,
+                                    shape,
                                     matrix,
-                                    repeat,
+                                    spread,
                                     alpha,
                                 ));
                         }
@@ -184,11 +184,79 @@ impl CanvasRenderer {
             // Check that the texture is ready for rendering (this also commits it at the point it's selected)
             let render_gradient = core.gradient_for_rendering(namespace_id, gradient_id);
             if let Some(render_gradient) = render_gradient {
+                let spread = core.gradient_spread(namespace_id, gradient_id);
+
                 // Choose this gradient
                 let layer = core.layer(self.current_layer);
 
-                layer.state.fill_color =
-                    FillState::linear_gradient_fill(render_gradient, gradient_id, x1, y1, x2, y2);
+                layer.state.fill_color = FillState::linear_gradient_fill(
+                    render_gradient,
+                    gradient_id,
+                    spread,
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                );
+            }
+        });
+    }
+
+    /// Set a radial fill gradient
+    #[inline]
+    pub(super) fn tes_fill_radial_gradient(
+        &mut self,
+        namespace_id: usize,
+        gradient_id: canvas::GradientId,
+        start_circle: (f32, f32, f32),
+        end_circle: (f32, f32, f32),
+    ) {
+        self.core.sync(|core| {
+            // Check that the texture is ready for rendering (this also commits it at the point it's selected)
+            let render_gradient = core.gradient_for_rendering(namespace_id, gradient_id);
+            if let Some(render_gradient) = render_gradient {
+                let spread = core.gradient_spread(namespace_id, gradient_id);
+
+                // Choose this gradient
+                let layer = core.layer(self.current_layer);
+
+                layer.state.fill_color = FillState::radial_gradient_fill(
+                    render_gradient,
+                    gradient_id,
+                    spread,
+                    start_circle,
+                    end_circle,
+                );
+            }
+        });
+    }
+
+    /// Set a conic fill gradient
+    #[inline]
+    pub(super) fn tes_fill_conic_gradient(
+        &mut self,
+        namespace_id: usize,
+        gradient_id: canvas::GradientId,
+        (x, y): (f32, f32),
+        start_angle: f32,
+    ) {
+        self.core.sync(|core| {
+            // Check that the texture is ready for rendering (this also commits it at the point it's selected)
+            let render_gradient = core.gradient_for_rendering(namespace_id, gradient_id);
+            if let Some(render_gradient) = render_gradient {
+                let spread = core.gradient_spread(namespace_id, gradient_id);
+
+                // Choose this gradient
+                let layer = core.layer(self.current_layer);
+
+                layer.state.fill_color = FillState::conic_gradient_fill(
+                    render_gradient,
+                    gradient_id,
+                    spread,
+                    x,
+                    y,
+                    start_angle,
+                );
             }
         });
     }
@@ -233,13 +301,29 @@ impl CanvasRenderer {
                 DestinationIn => render::BlendMode::DestinationIn,
                 SourceAtop => render::BlendMode::SourceATop,
                 DestinationAtop => render::BlendMode::DestinationATop,
+                Xor => render::BlendMode::Xor,
+                Clear => render::BlendMode::Clear,
+                Copy => render::BlendMode::Copy,
+                Destination => render::BlendMode::Destination,
 
                 Multiply => render::BlendMode::Multiply,
                 Screen => render::BlendMode::Screen,
+                Darken => render::BlendMode::Darken,
+                Lighten => render::BlendMode::Lighten,
+                Overlay => render::BlendMode::Overlay,
+                ColorDodge => render::BlendMode::ColorDodge,
+                ColorBurn => render::BlendMode::ColorBurn,
+                HardLight => render::BlendMode::HardLight,
+                SoftLight => render::BlendMode::SoftLight,
+                Difference => render::BlendMode::Difference,
+                Exclusion => render::BlendMode::Exclusion,
+
+                Hue => render::BlendMode::Hue,
+                Saturation => render::BlendMode::Saturation,
+                Color => render::BlendMode::Color,
+                Luminosity => render::BlendMode::Luminosity,
 
-                // TODO: these are not supported yet (they might require explicit shader support)
-                Darken => render::BlendMode::SourceOver,
-                Lighten => render::BlendMode::SourceOver,
+                Plus => render::BlendMode::Plus,
             };
 
             core.layer(self.current_layer)
@@ -8,6 +8,7 @@ use flo_canvas as canvas;
 use flo_render as render;
 
 use super::matrix::*;
+use super::render_gradient::*;
 
 ///
 /// The ways the next path can be filled
@@ -38,11 +39,12 @@ pub enum FillState {
     ///
     /// Fill with a particular gradient
     ///
-    LinearGradient(
+    Gradient(
         render::TextureId,
         canvas::GradientId,
+        GradientShape,
         render::Matrix,
-        bool,
+        render::GradientSpread,
         f32,
     ),
 }
@@ -56,7 +58,7 @@ impl FillState {
             FillState::None => render::Rgba8([0, 0, 0, 255]),
             FillState::Color(color) => *color,
             FillState::Texture(_, _, _, _, _) => render::Rgba8([0, 0, 0, 255]),
-            FillState::LinearGradient(_, _, _, _, _) => render::Rgba8([0, 0, 0, 255]),
+            FillState::Gradient(_, _, _, _, _, _) => render::Rgba8([0, 0, 0, 255]),
         }
     }
 
@@ -102,6 +104,7 @@ impl FillState {
     pub fn linear_gradient_fill(
         render_texture: render::TextureId,
         canvas_gradient: canvas::GradientId,
+        spread: render::GradientSpread,
         x1: f32,
         y1: f32,
         x2: f32,
@@ -137,7 +140,72 @@ impl FillState {
         ]);
 
         // Create the fill-state for this matrix
-        FillState::LinearGradient(render_texture, canvas_gradient, matrix, false, 1.0)
+        FillState::Gradient(
+            render_texture,
+            canvas_gradient,
+            GradientShape::Linear,
+            matrix,
+            spread,
+            1.0,
+        )
+    }
+
+    ///
+    /// Creates a radial gradient fill, which runs from the circle (x1, y1, r1) to the circle (x2, y2, r2)
+    ///
+    pub fn radial_gradient_fill(
+        render_texture: render::TextureId,
+        canvas_gradient: canvas::GradientId,
+        spread: render::GradientSpread,
+        (x1, y1, r1): (f32, f32, f32),
+        (x2, y2, r2): (f32, f32, f32),
+    ) -> FillState {
+        // Avoid division by zero
+        let r2 = if r2 == 0.0 { 0.0000001 } else { r2 };
+
+        // The matrix maps the end circle onto the unit circle
+        let transform = canvas::Transform2D::scale(1.0 / r2, 1.0 / r2)
+            * canvas::Transform2D::translate(-x2, -y2);
+        let matrix = transform_to_matrix(&transform);
+
+        // The start circle is described relative to the end circle
+        let focal_point = ((x1 - x2) / r2, (y1 - y2) / r2);
+        let focal_radius = (r1 / r2).abs();
+
+        FillState::Gradient(
+            render_texture,
+            canvas_gradient,
+            GradientShape::Radial(focal_point, focal_radius),
+            matrix,
+            spread,
+            1.0,
+        )
+    }
+
+    ///
+    /// Creates a conic gradient fill, which sweeps around (x, y) starting at the specified angle
+    ///
+    pub fn conic_gradient_fill(
+        render_texture: render::TextureId,
+        canvas_gradient: canvas::GradientId,
+        spread: render::GradientSpread,
+        x: f32,
+        y: f32,
+        start_angle: f32,
+    ) -> FillState {
+        // The matrix moves the center to the origin and the start angle to the x axis
+        let transform =
+            canvas::Transform2D::rotate(-start_angle) * canvas::Transform2D::translate(-x, -y);
+        let matrix = transform_to_matrix(&transform);
+
+        FillState::Gradient(
+            render_texture,
+            canvas_gradient,
+            GradientShape::Conic,
+            matrix,
+            spread,
+            1.0,
+        )
     }
 
     ///
@@ -148,7 +216,7 @@ impl FillState {
             FillState::None => None,
             FillState::Color(_) => None,
             FillState::Texture(_, texture_id, _, _, _) => Some(*texture_id),
-            FillState::LinearGradient(_, _, _, _, _) => None,
+            FillState::Gradient(_, _, _, _, _, _) => None,
         }
     }
 
@@ -168,7 +236,7 @@ impl FillState {
                     new_alpha,
                 )
             }
-            FillState::LinearGradient(_, _, _, _, _) => self.clone(),
+            FillState::Gradient(_, _, _, _, _, _) => self.clone(),
         }
     }
 
@@ -190,12 +258,13 @@ impl FillState {
                     *alpha,
                 )
             }
-            FillState::LinearGradient(render_texture, canvas_gradient, matrix, repeat, alpha) => {
-                FillState::LinearGradient(
+            FillState::Gradient(render_texture, canvas_gradient, shape, matrix, spread, alpha) => {
+                FillState::Gradient(
                     *render_texture,
                     *canvas_gradient,
+                    *shape,
                     (*matrix).multiply(transform_matrix),
-                    *repeat,
+                    *spread,
                     *alpha,
                 )
             }
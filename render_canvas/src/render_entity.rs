@@ -9,6 +9,7 @@ use lyon::tessellation::VertexBuffers;
 use flo_canvas as canvas;
 use flo_render as render;
 
+use super::render_gradient::*;
 use super::texture_filter_request::*;
 
 ///
@@ -65,7 +66,13 @@ pub enum RenderEntity {
     SetFillTexture(render::TextureId, render::Matrix, bool, f32),
 
     /// Sets the gradient texture to use for the following rendering
-    SetFillGradient(render::TextureId, render::Matrix, bool, f32),
+    SetFillGradient(
+        render::TextureId,
+        GradientShape,
+        render::Matrix,
+        render::GradientSpread,
+        f32,
+    ),
 
     /// Use the specified vertex buffer to define a clipping mask
     EnableClipping(render::VertexBufferId, render::IndexBufferId, usize),
@@ -15,3 +15,21 @@ pub enum RenderGradient {
     Defined(Vec<canvas::GradientOp>),
     Ready(render::TextureId, Vec<canvas::GradientOp>),
 }
+
+///
+/// The shape of a gradient fill
+///
+/// The gradient matrix maps canvas coordinates to gradient coordinates, and the shape determines how the position along
+/// the gradient is found from those coordinates
+///
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GradientShape {
+    /// The gradient runs along the x axis, from 0 to 1
+    Linear,
+
+    /// Two-circle radial gradient: the end circle is the unit circle, and the start circle has the specified center and radius
+    Radial((f32, f32), f32),
+
+    /// The gradient sweeps anticlockwise around the origin, starting at the x axis
+    Conic,
+}
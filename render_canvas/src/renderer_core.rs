@@ -131,7 +131,7 @@ impl RenderCore {
                     .map(|usage_count| *usage_count -= 1);
             }
 
-            SetFillGradient(texture_id, _, _, _) => {
+            SetFillGradient(texture_id, _, _, _, _) => {
                 self.used_textures
                     .get_mut(&texture_id)
                     .map(|usage_count| *usage_count -= 1);
@@ -195,7 +195,7 @@ impl RenderCore {
                 FillState::Texture(texture_id, _, _, _, _) => {
                     unused_textures.remove(texture_id);
                 }
-                FillState::LinearGradient(texture_id, _, _, _, _) => {
+                FillState::Gradient(texture_id, _, _, _, _, _) => {
                     unused_textures.remove(texture_id);
                 }
 
@@ -570,6 +570,27 @@ impl RenderCore {
         }
     }
 
+    ///
+    /// Returns how the specified gradient should be extended beyond its end points
+    ///
+    pub fn gradient_spread(
+        &self,
+        namespace_id: usize,
+        gradient_id: canvas::GradientId,
+    ) -> render::GradientSpread {
+        let definition = match self.canvas_gradients.get(&(namespace_id, gradient_id)) {
+            Some(RenderGradient::Ready(_, definition)) => definition,
+            Some(RenderGradient::Defined(definition)) => definition,
+            None => return render::GradientSpread::Pad,
+        };
+
+        match canvas::gradient_spread(definition) {
+            canvas::GradientSpread::Pad => render::GradientSpread::Pad,
+            canvas::GradientSpread::Repeat => render::GradientSpread::Repeat,
+            canvas::GradientSpread::Reflect => render::GradientSpread::Reflect,
+        }
+    }
+
     ///
     /// Allocates a new layer handle to a blank layer
     ///
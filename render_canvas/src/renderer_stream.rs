@@ -21,6 +21,7 @@ use super::layer_bounds::*;
 use super::layer_handle::*;
 use super::matrix::*;
 use super::render_entity::*;
+use super::render_gradient::*;
 use super::renderer_core::*;
 use super::resource_ids::*;
 use super::texture_filter_request::*;
@@ -51,7 +52,13 @@ enum ShaderModifier {
     Texture(render::TextureId, render::Matrix, bool, f32),
 
     /// Shader should use a gradient
-    Gradient(render::TextureId, render::Matrix, bool, f32),
+    Gradient(
+        render::TextureId,
+        GradientShape,
+        render::Matrix,
+        render::GradientSpread,
+        f32,
+    ),
 }
 
 ///
@@ -340,13 +347,33 @@ impl RenderStreamState {
                             clip_texture: clip,
                         }
                     }
-                    ShaderModifier::Gradient(texture_id, matrix, repeat, alpha) => {
-                        render::ShaderType::LinearGradient {
-                            texture: *texture_id,
-                            texture_transform: *matrix,
-                            repeat: *repeat,
-                            alpha: *alpha,
-                            clip_texture: clip,
+                    ShaderModifier::Gradient(texture_id, shape, matrix, spread, alpha) => {
+                        match shape {
+                            GradientShape::Linear => render::ShaderType::LinearGradient {
+                                texture: *texture_id,
+                                texture_transform: *matrix,
+                                spread: *spread,
+                                alpha: *alpha,
+                                clip_texture: clip,
+                            },
+                            GradientShape::Radial(focal_point, focal_radius) => {
+                                render::ShaderType::RadialGradient {
+                                    texture: *texture_id,
+                                    texture_transform: *matrix,
+                                    focal_point: *focal_point,
+                                    focal_radius: *focal_radius,
+                                    spread: *spread,
+                                    alpha: *alpha,
+                                    clip_texture: clip,
+                                }
+                            }
+                            GradientShape::Conic => render::ShaderType::ConicGradient {
+                                texture: *texture_id,
+                                texture_transform: *matrix,
+                                spread: *spread,
+                                alpha: *alpha,
+                                clip_texture: clip,
+                            },
                         }
                     }
                 };
@@ -367,7 +394,7 @@ impl RenderStreamState {
                         );
                     }
                     ShaderModifier::Texture(_, _, _, _) => {}
-                    ShaderModifier::Gradient(_, _, _, _) => {}
+                    ShaderModifier::Gradient(_, _, _, _, _) => {}
                 }
             }
         }
@@ -479,6 +506,9 @@ impl RenderCore {
         let initial_invalid_bounds = initial_state.invalid_bounds;
         let is_sprite = layer.state.is_sprite;
 
+        // Render target used for drawing with the blend modes that read the destination (allocated when first needed)
+        let mut blend_render_target = None;
+
         render_state.transform = Some(viewport_transform);
         render_state.blend_mode = Some(render::BlendMode::SourceOver);
         render_state.render_target = Some(render_target);
@@ -537,12 +567,62 @@ impl RenderCore {
                 }
 
                 DrawIndexed(vertex_buffer, index_buffer, num_items) => {
-                    // Draw the triangles
-                    render_order.push(render::RenderAction::DrawIndexedTriangles(
-                        *vertex_buffer,
-                        *index_buffer,
-                        *num_items,
-                    ));
+                    let blend_mode = render_state
+                        .blend_mode
+                        .unwrap_or(render::BlendMode::SourceOver);
+
+                    if blend_mode.reads_destination() {
+                        // The renderers can only read the destination while drawing a frame buffer, so these blend modes draw the triangles to a
+                        // separate render target and then blend that with the layer
+                        let draw_triangles = render::RenderAction::DrawIndexedTriangles(
+                            *vertex_buffer,
+                            *index_buffer,
+                            *num_items,
+                        );
+                        let (blend_target, _) = *blend_render_target.get_or_insert_with(|| {
+                            let blend_texture = core.allocate_texture();
+                            let blend_target = core.allocate_render_target();
+
+                            render_order.push(render::RenderAction::CreateRenderTarget(
+                                blend_target,
+                                blend_texture,
+                                render_state.viewport_size,
+                                render::RenderTargetType::MultisampledTexture,
+                            ));
+
+                            (blend_target, blend_texture)
+                        });
+
+                        let mut blend_state = render_state.clone();
+                        blend_state.render_target = Some(blend_target);
+                        blend_state.blend_mode = Some(render::BlendMode::SourceOver);
+
+                        render_order.extend(blend_state.update_from_state(render_state));
+                        render_order.extend(vec![
+                            render::RenderAction::Clear(render::Rgba8([0, 0, 0, 0])),
+                            draw_triangles,
+                            render::RenderAction::SelectRenderTarget(render_target),
+                            render::RenderAction::BlendMode(blend_mode),
+                            render::RenderAction::DrawFrameBuffer(
+                                blend_target,
+                                render::FrameBufferRegion::default(),
+                                render::Alpha(1.0),
+                            ),
+                        ]);
+
+                        // Drawing a frame buffer can reset the shader, so restore the whole state for the following instructions
+                        render_order.extend(render_state.update_from_state(&blend_state));
+
+                        // Reborrow the layer
+                        layer = core.layer(layer_handle);
+                    } else {
+                        // Draw the triangles
+                        render_order.push(render::RenderAction::DrawIndexedTriangles(
+                            *vertex_buffer,
+                            *index_buffer,
+                            *num_items,
+                        ));
+                    }
                 }
 
                 RenderSprite(namespace_id, sprite_id, sprite_transform) => {
@@ -844,13 +924,14 @@ impl RenderCore {
                     render_order.extend(render_state.update_from_state(&old_state));
                 }
 
-                SetFillGradient(texture_id, matrix, repeat, alpha) => {
+                SetFillGradient(texture_id, shape, matrix, spread, alpha) => {
                     // Set the shader modifier to use the gradient texture (overriding any other shader modifier)
                     let old_state = render_state.clone();
                     render_state.shader_modifier = Some(ShaderModifier::Gradient(
                         *texture_id,
+                        *shape,
                         *matrix,
-                        *repeat,
+                        *spread,
                         *alpha,
                     ));
 
@@ -860,6 +941,20 @@ impl RenderCore {
             }
         }
 
+        // Free the render target used for the blend modes that read the destination
+        if let Some((blend_target, blend_texture)) = blend_render_target {
+            render_order.extend(vec![
+                render::RenderAction::FreeRenderTarget(blend_target),
+                render::RenderAction::FreeTexture(blend_texture),
+            ]);
+
+            core.free_render_target(blend_target);
+            core.free_texture(blend_texture);
+
+            // Reborrow the layer
+            layer = core.layer(layer_handle);
+        }
+
         // If the layer has 'commit after rendering' and the next layer does not have 'commit before rendering', then commit what we just rendered
         if layer.commit_after_rendering && !render_state.invalid_bounds.is_undefined() && !is_sprite
         {
@@ -877,10 +972,26 @@ impl RenderCore {
                 canvas::BlendMode::DestinationOut => render::BlendMode::DestinationOut,
                 canvas::BlendMode::SourceAtop => render::BlendMode::SourceATop,
                 canvas::BlendMode::DestinationAtop => render::BlendMode::DestinationATop,
+                canvas::BlendMode::Xor => render::BlendMode::Xor,
+                canvas::BlendMode::Clear => render::BlendMode::Clear,
+                canvas::BlendMode::Copy => render::BlendMode::Copy,
+                canvas::BlendMode::Destination => render::BlendMode::Destination,
                 canvas::BlendMode::Multiply => render::BlendMode::Multiply,
                 canvas::BlendMode::Screen => render::BlendMode::Screen,
-                canvas::BlendMode::Darken => render::BlendMode::SourceOver,
-                canvas::BlendMode::Lighten => render::BlendMode::SourceOver,
+                canvas::BlendMode::Darken => render::BlendMode::Darken,
+                canvas::BlendMode::Lighten => render::BlendMode::Lighten,
+                canvas::BlendMode::Overlay => render::BlendMode::Overlay,
+                canvas::BlendMode::ColorDodge => render::BlendMode::ColorDodge,
+                canvas::BlendMode::ColorBurn => render::BlendMode::ColorBurn,
+                canvas::BlendMode::HardLight => render::BlendMode::HardLight,
+                canvas::BlendMode::SoftLight => render::BlendMode::SoftLight,
+                canvas::BlendMode::Difference => render::BlendMode::Difference,
+                canvas::BlendMode::Exclusion => render::BlendMode::Exclusion,
+                canvas::BlendMode::Hue => render::BlendMode::Hue,
+                canvas::BlendMode::Saturation => render::BlendMode::Saturation,
+                canvas::BlendMode::Color => render::BlendMode::Color,
+                canvas::BlendMode::Luminosity => render::BlendMode::Luminosity,
+                canvas::BlendMode::Plus => render::BlendMode::Plus,
             };
 
             render_order.extend(vec![
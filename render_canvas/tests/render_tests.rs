@@ -368,3 +368,95 @@ fn clip_rect() {
         // Remaining instructions finish the render
     })
 }
+
+#[test]
+fn fill_radial_gradient() {
+    // Fill a circle with a radial gradient, with the start circle offset from the center
+    let mut draw_gradient = vec![];
+    draw_gradient.create_gradient(GradientId(1), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    draw_gradient.gradient_stop(GradientId(1), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    draw_gradient.circle(0.0, 0.0, 100.0);
+    draw_gradient.fill_radial_gradient(GradientId(1), (50.0, 0.0, 10.0), (0.0, 0.0, 100.0));
+    draw_gradient.fill();
+
+    executor::block_on(async {
+        let mut renderer = CanvasRenderer::new();
+        let rendering = renderer
+            .draw(draw_gradient.into_iter())
+            .collect::<Vec<_>>()
+            .await;
+
+        // The start circle should be described relative to the end circle, which becomes the unit circle
+        let radial_shader = rendering.iter().find_map(|action| match action {
+            RenderAction::UseShader(render::ShaderType::RadialGradient {
+                focal_point,
+                focal_radius,
+                ..
+            }) => Some((*focal_point, *focal_radius)),
+            _ => None,
+        });
+
+        assert!(radial_shader == Some(((0.5, 0.0), 0.1)), "{:?}", rendering);
+    });
+}
+
+#[test]
+fn fill_conic_gradient() {
+    // Fill a circle with a conic gradient
+    let mut draw_gradient = vec![];
+    draw_gradient.create_gradient(GradientId(1), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    draw_gradient.gradient_stop(GradientId(1), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    draw_gradient.circle(0.0, 0.0, 100.0);
+    draw_gradient.fill_conic_gradient(GradientId(1), 0.0, 0.0, 0.0);
+    draw_gradient.fill();
+
+    executor::block_on(async {
+        let mut renderer = CanvasRenderer::new();
+        let rendering = renderer
+            .draw(draw_gradient.into_iter())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert!(
+            rendering.iter().any(|action| match action {
+                RenderAction::UseShader(render::ShaderType::ConicGradient { .. }) => true,
+                _ => false,
+            }),
+            "{:?}",
+            rendering
+        );
+    });
+}
+
+#[test]
+fn fill_reflected_gradient() {
+    // Fill a rectangle with a linear gradient that is mirrored beyond its end points
+    let mut draw_gradient = vec![];
+    draw_gradient.create_gradient(GradientId(1), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    draw_gradient.gradient_stop(GradientId(1), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    draw_gradient.gradient_spread(GradientId(1), GradientSpread::Reflect);
+    draw_gradient.rect(0.0, 0.0, 100.0, 100.0);
+    draw_gradient.fill_gradient(GradientId(1), 0.0, 0.0, 10.0, 0.0);
+    draw_gradient.fill();
+
+    executor::block_on(async {
+        let mut renderer = CanvasRenderer::new();
+        let rendering = renderer
+            .draw(draw_gradient.into_iter())
+            .collect::<Vec<_>>()
+            .await;
+
+        let spread = rendering.iter().find_map(|action| match action {
+            RenderAction::UseShader(render::ShaderType::LinearGradient { spread, .. }) => {
+                Some(*spread)
+            }
+            _ => None,
+        });
+
+        assert!(
+            spread == Some(render::GradientSpread::Reflect),
+            "{:?}",
+            rendering
+        );
+    });
+}
@@ -0,0 +1,219 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use futures::executor;
+use futures::prelude::*;
+
+use flo_canvas::*;
+use flo_render::*;
+use flo_render_canvas::*;
+
+use flo_render_software::draw::*;
+use flo_render_software::pixel::*;
+use flo_render_software::render::*;
+use flo_render_software::scanplan::*;
+
+///
+/// Renders a drawing using the software offscreen render context, returning the pixels with the first row at the top
+///
+fn render_offscreen(width: usize, height: usize, drawing: Vec<Draw>) -> Vec<u8> {
+    let mut context = software_initialize_offscreen_rendering().unwrap();
+    let image = executor::block_on(render_canvas_offscreen(
+        &mut context,
+        width,
+        height,
+        1.0,
+        stream::iter(drawing),
+    ));
+
+    // The offscreen renderers put the bottom row first
+    image
+        .chunks_exact(width * 4)
+        .rev()
+        .flat_map(|row| row.iter().copied())
+        .collect()
+}
+
+///
+/// Renders a drawing using flo_render_software
+///
+fn render_with_software_renderer(width: usize, height: usize, drawing: Vec<Draw>) -> Vec<u8> {
+    let mut canvas_drawing = CanvasDrawing::<F32LinearPixel, 4>::empty();
+    canvas_drawing.set_pixel_height(height as _);
+    canvas_drawing.draw(drawing);
+
+    let mut frame = vec![0u8; width * height * 4];
+    let mut rgba = RgbaFrame::from_bytes(width, height, 2.2, &mut frame).unwrap();
+
+    let renderer = CanvasDrawingRegionRenderer::new(
+        PixelScanPlanner::default(),
+        ScanlineRenderer::new(canvas_drawing.program_runner(height as _)),
+        height,
+    );
+    rgba.render(renderer, &canvas_drawing);
+
+    frame
+}
+
+fn pixel(image: &[u8], width: usize, x: usize, y: usize) -> [u8; 4] {
+    let pos = (x + y * width) * 4;
+    [image[pos], image[pos + 1], image[pos + 2], image[pos + 3]]
+}
+
+///
+/// A drawing with some overlapping shapes in a 200x200 region
+///
+fn test_drawing() -> Vec<Draw> {
+    let mut drawing = vec![];
+
+    drawing.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+    drawing.canvas_height(200.0);
+    drawing.center_region(0.0, 0.0, 200.0, 200.0);
+
+    drawing.new_path();
+    drawing.rect(20.0, 20.0, 120.0, 100.0);
+    drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.fill();
+
+    drawing.new_path();
+    drawing.circle(130.0, 130.0, 50.0);
+    drawing.fill_color(Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    drawing.fill();
+
+    drawing.new_path();
+    drawing.move_to(10.0, 180.0);
+    drawing.line_to(190.0, 180.0);
+    drawing.line_width(6.0);
+    drawing.stroke_color(Color::Rgba(0.0, 0.5, 0.0, 1.0));
+    drawing.stroke();
+
+    drawing
+}
+
+#[test]
+fn fill_rectangle_offscreen() {
+    let image = render_offscreen(200, 200, test_drawing());
+
+    // Canvas coordinates have y increasing upwards, so the rectangle is near the bottom of the image
+    assert!(
+        pixel(&image, 200, 60, 140) == [255, 0, 0, 255],
+        "{:?}",
+        pixel(&image, 200, 60, 140)
+    );
+    assert!(
+        pixel(&image, 200, 130, 70) == [0, 0, 255, 255],
+        "{:?}",
+        pixel(&image, 200, 130, 70)
+    );
+    assert!(
+        pixel(&image, 200, 100, 20) == [0, 127, 0, 255],
+        "{:?}",
+        pixel(&image, 200, 100, 20)
+    );
+    assert!(
+        pixel(&image, 200, 190, 190) == [255, 255, 255, 255],
+        "{:?}",
+        pixel(&image, 200, 190, 190)
+    );
+}
+
+#[test]
+fn offscreen_matches_software_renderer() {
+    let offscreen = render_offscreen(200, 200, test_drawing());
+    let software = render_with_software_renderer(200, 200, test_drawing());
+
+    // The renderers anti-alias edges differently and can place edges up to a pixel apart, so compare against the closest
+    // matching pixel nearby and only allow a small number of pixels to be different
+    let difference = |a: [u8; 4], b: [u8; 4]| {
+        (0..4)
+            .map(|component| (a[component] as i32 - b[component] as i32).abs())
+            .max()
+            .unwrap()
+    };
+
+    let mut different_pixels = 0;
+    for y in 1..199 {
+        for x in 1..199 {
+            let offscreen_pixel = pixel(&offscreen, 200, x, y);
+            let closest = (y - 1..=y + 1)
+                .flat_map(|y| (x - 1..=x + 1).map(move |x| (x, y)))
+                .map(|(x, y)| difference(offscreen_pixel, pixel(&software, 200, x, y)))
+                .min()
+                .unwrap();
+
+            if closest > 8 {
+                different_pixels += 1;
+            }
+        }
+    }
+
+    assert!(
+        different_pixels < 200 * 200 / 100,
+        "{} pixels differ",
+        different_pixels
+    );
+}
+
+#[test]
+fn blend_mode_that_reads_destination() {
+    let mut drawing = vec![];
+
+    drawing.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+    drawing.canvas_height(200.0);
+    drawing.center_region(0.0, 0.0, 200.0, 200.0);
+
+    drawing.new_path();
+    drawing.rect(0.0, 0.0, 100.0, 200.0);
+    drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.fill();
+
+    // Shapes drawn with this blend mode are drawn to a separate render target and blended with the layer
+    drawing.blend_mode(flo_canvas::BlendMode::Difference);
+    drawing.new_path();
+    drawing.rect(50.0, 50.0, 150.0, 150.0);
+    drawing.fill_color(Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    drawing.fill();
+
+    let image = render_offscreen(200, 200, drawing.clone());
+    let software = render_with_software_renderer(200, 200, drawing);
+
+    // Blue on red is magenta
+    assert!(
+        pixel(&image, 200, 75, 100) == [255, 0, 255, 255],
+        "{:?}",
+        pixel(&image, 200, 75, 100)
+    );
+
+    // The background colour is drawn behind the layers, so where the layer is empty the blue is drawn unchanged
+    assert!(
+        pixel(&image, 200, 125, 100) == [0, 0, 255, 255],
+        "{:?}",
+        pixel(&image, 200, 125, 100)
+    );
+
+    // Outside the shape, the layer is unchanged
+    assert!(
+        pixel(&image, 200, 25, 100) == [255, 0, 0, 255],
+        "{:?}",
+        pixel(&image, 200, 25, 100)
+    );
+    assert!(
+        pixel(&image, 200, 175, 100) == [255, 255, 255, 255],
+        "{:?}",
+        pixel(&image, 200, 175, 100)
+    );
+
+    for (x, y) in [(75, 100), (25, 100), (175, 100)] {
+        assert!(
+            pixel(&image, 200, x, y) == pixel(&software, 200, x, y),
+            "{} {}: {:?} != {:?}",
+            x,
+            y,
+            pixel(&image, 200, x, y),
+            pixel(&software, 200, x, y)
+        );
+    }
+}
@@ -21,6 +21,10 @@ use super::pixel_programs::*;
 use super::prepared_layer::*;
 use super::texture::*;
 
+/// The colour tables for gradients, with weak references to the gradient definitions they were generated from
+pub(super) type GradientColorTables<TPixel> =
+    HashMap<usize, (Weak<Vec<canvas::GradientOp>>, Arc<Vec<TPixel>>)>;
+
 ///
 /// A `CanvasDrawing` represents the state of a drawing after a series of `Draw` commands have been processed
 ///
@@ -72,6 +76,19 @@ where
 
     /// The textures in this drawing
     pub(super) textures: HashMap<(canvas::NamespaceId, canvas::TextureId), Arc<Texture>>,
+
+    /// The alpha value set for the textures in this drawing by `FillTransparency` (textures not in this list are opaque)
+    pub(super) texture_alpha: HashMap<(canvas::NamespaceId, canvas::TextureId), f32>,
+
+    /// The textures that are rendered from sprites and which should be updated when the sprite changes
+    pub(super) dynamic_textures: HashMap<(canvas::NamespaceId, canvas::TextureId), DynamicTexture>,
+
+    /// The gradients in this drawing
+    pub(super) gradients:
+        HashMap<(canvas::NamespaceId, canvas::GradientId), Arc<Vec<canvas::GradientOp>>>,
+
+    /// The colour tables generated for the gradients used by brushes, indexed by the address of the gradient definition they were generated from
+    pub(super) gradient_color_tables: GradientColorTables<TPixel>,
 }
 
 impl<TPixel, const N: usize> CanvasDrawing<TPixel, N>
@@ -115,6 +132,10 @@ where
             program_data_cache: data_cache,
             state_stack: vec![],
             textures: HashMap::new(),
+            texture_alpha: HashMap::new(),
+            dynamic_textures: HashMap::new(),
+            gradients: HashMap::new(),
+            gradient_color_tables: HashMap::new(),
         }
     }
 
@@ -188,9 +209,15 @@ where
                 LineCap(cap_style) => {
                     self.current_state.line_cap(cap_style);
                 }
-                NewDashPattern => { /* todo!() - dash patterns not supported yet */ }
-                DashLength(_dash_length) => { /* todo!() - dash patterns not supported yet */ }
-                DashOffset(_dash_offset) => { /* todo!() - dash patterns not supported yet */ }
+                NewDashPattern => {
+                    self.current_state.new_dash_pattern();
+                }
+                DashLength(dash_length) => {
+                    self.current_state.dash_length(dash_length as _);
+                }
+                DashOffset(dash_offset) => {
+                    self.current_state.dash_offset(dash_offset as _);
+                }
                 FillColor(fill_color) => {
                     self.current_state
                         .fill_solid_color(fill_color, &mut self.program_data_cache);
@@ -198,8 +225,19 @@ where
                 FillTexture(texture, (x1, y1), (x2, y2)) => {
                     self.fill_texture(texture, x1, y1, x2, y2);
                 }
-                FillGradient(gradient, (x1, y1), (x2, y2)) => { /* todo!() */ }
-                FillTransform(transform) => { /* todo!() */ }
+                FillGradient(gradient, (x1, y1), (x2, y2)) => {
+                    self.fill_gradient(gradient, x1, y1, x2, y2);
+                }
+                FillRadialGradient(gradient, start_circle, end_circle) => {
+                    self.fill_radial_gradient(gradient, start_circle, end_circle);
+                }
+                FillConicGradient(gradient, (x, y), start_angle) => {
+                    self.fill_conic_gradient(gradient, x, y, start_angle);
+                }
+                FillTransform(transform) => {
+                    self.current_state
+                        .fill_transform(transform, &mut self.program_data_cache);
+                }
                 StrokeColor(stroke_color) => {
                     self.current_state
                         .stroke_solid_color(stroke_color, &mut self.program_data_cache);
@@ -258,12 +296,16 @@ where
                 DrawSprite(sprite_id) => {
                     self.sprite_draw(sprite_id);
                 }
-                DrawSpriteWithFilters(sprite_id, filters) => { /* todo!() */ }
+                DrawSpriteWithFilters(sprite_id, filters) => {
+                    self.sprite_draw_with_filters(sprite_id, filters);
+                }
 
                 Texture(texture_id, texture_op) => {
                     self.texture(texture_id, texture_op);
                 }
-                Gradient(gradient_id, gradient_op) => { /* todo!() */ }
+                Gradient(gradient_id, gradient_op) => {
+                    self.gradient(gradient_id, gradient_op);
+                }
 
                 Font(_font_id, _font_op) => { /* Use the glyph and font streams in flo_canvas */ }
                 BeginLineLayout(_x, _y, _alignment) => { /* Use the glyph and font streams in flo_canvas */
@@ -355,6 +397,10 @@ where
         self.current_namespace = canvas::NamespaceId::default();
         self.next_layer_handle = LayerHandle(1);
         self.textures = HashMap::new();
+        self.texture_alpha = HashMap::new();
+        self.dynamic_textures = HashMap::new();
+        self.gradients = HashMap::new();
+        self.gradient_color_tables = HashMap::new();
 
         // Free the old program data
         self.program_data_cache.free_all_data();
@@ -43,8 +43,54 @@ pub enum Brush {
     /// Transparent solid colour brush (will be blended with the image behind)
     TransparentSolidColor(canvas::Color),
 
-    /// A transformed texture, known to have transparent pixels in it
-    TransparentTexture(Arc<RgbaTexture>, canvas::Transform2D),
+    /// A transformed texture, known to have transparent pixels in it, and the alpha value to apply to it
+    TransparentTexture(Arc<RgbaTexture>, canvas::Transform2D, f32),
+
+    /// A linear gradient, with a transform that maps the gradient start point to x=0 and the end point to x=1
+    LinearGradient(Arc<Vec<canvas::GradientOp>>, canvas::Transform2D),
+
+    /// A radial gradient, with a transform that maps the end circle to the unit circle, and the position and radius of the start circle after that transform
+    RadialGradient(
+        Arc<Vec<canvas::GradientOp>>,
+        canvas::Transform2D,
+        (f32, f32),
+        f32,
+    ),
+
+    /// A conic gradient, with a transform that maps the center to the origin and the start angle to the x axis
+    ConicGradient(Arc<Vec<canvas::GradientOp>>, canvas::Transform2D),
+}
+
+impl Brush {
+    ///
+    /// Returns this brush with a transform applied to its texture or gradient (which is applied before any existing transformation)
+    ///
+    pub fn transformed(&self, transform: &canvas::Transform2D) -> Brush {
+        match self {
+            Brush::OpaqueSolidColor(_) | Brush::TransparentSolidColor(_) => self.clone(),
+            Brush::TransparentTexture(texture, texture_transform, alpha) => {
+                Brush::TransparentTexture(
+                    Arc::clone(texture),
+                    texture_transform * transform,
+                    *alpha,
+                )
+            }
+            Brush::LinearGradient(gradient, gradient_transform) => {
+                Brush::LinearGradient(Arc::clone(gradient), gradient_transform * transform)
+            }
+            Brush::RadialGradient(gradient, gradient_transform, focal_point, focal_radius) => {
+                Brush::RadialGradient(
+                    Arc::clone(gradient),
+                    gradient_transform * transform,
+                    *focal_point,
+                    *focal_radius,
+                )
+            }
+            Brush::ConicGradient(gradient, gradient_transform) => {
+                Brush::ConicGradient(Arc::clone(gradient), gradient_transform * transform)
+            }
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -84,6 +130,9 @@ pub struct DrawingState {
     /// The brush to select next time stroke_program is None
     pub(super) next_stroke_brush: Brush,
 
+    /// The transform to apply to the texture or gradient in the fill brush (in render coordinates)
+    pub(super) fill_transform: canvas::Transform2D,
+
     /// The current position along the path
     pub(super) path_position: Coord2,
 
@@ -108,6 +157,12 @@ pub struct DrawingState {
     /// The end cap for the next stroke
     pub(super) stroke_end_cap: curves_path::LineCap,
 
+    /// The lengths of the dashes and gaps to apply to the next stroke (no dashes are applied if this is empty)
+    pub(super) dash_pattern: Vec<f64>,
+
+    /// The offset into the dash pattern where the next stroke starts
+    pub(super) dash_offset: f64,
+
     /// The currently set clip region, if any
     pub(super) clip_path: DrawingClipRegion,
 
@@ -126,6 +181,7 @@ impl Default for DrawingState {
             stroke_program: None,
             next_fill_brush: Brush::OpaqueSolidColor(canvas::Color::Rgba(0.0, 0.0, 0.0, 1.0)),
             next_stroke_brush: Brush::OpaqueSolidColor(canvas::Color::Rgba(0.0, 0.0, 0.0, 1.0)),
+            fill_transform: canvas::Transform2D::identity(),
             path_position: Coord2::origin(),
             path_edges: vec![],
             subpaths: vec![0],
@@ -134,6 +190,8 @@ impl Default for DrawingState {
             stroke_join: curves_path::LineJoin::Round,
             stroke_start_cap: curves_path::LineCap::Butt,
             stroke_end_cap: curves_path::LineCap::Butt,
+            dash_pattern: vec![],
+            dash_offset: 0.0,
             clip_path: DrawingClipRegion::None,
             blend_mode: AlphaOperation::SourceOver,
             sprite_transform: SpriteTransform::ScaleTransform {
@@ -213,6 +271,33 @@ impl DrawingState {
         } else {
             self.next_fill_brush = Brush::TransparentSolidColor(colour);
         }
+        self.fill_transform = canvas::Transform2D::identity();
+    }
+
+    ///
+    /// Applies a transform to the texture or gradient used by the current fill brush
+    ///
+    pub(crate) fn fill_transform<TPixel, const N: usize>(
+        &mut self,
+        transform: canvas::Transform2D,
+        data_cache: &mut PixelProgramDataCache<TPixel>,
+    ) where
+        TPixel: Send + Pixel<N>,
+    {
+        // The fill brush will need to be regenerated with the new transform
+        Self::release_program(&mut self.fill_program, data_cache);
+
+        // The brush maps render coordinates to texture coordinates, so we need the inverse of the transform, converted to render coordinates
+        let inverse_transform = transform
+            .invert()
+            .unwrap_or_else(canvas::Transform2D::identity);
+        let inverse_canvas_transform = self
+            .transform
+            .invert()
+            .unwrap_or_else(canvas::Transform2D::identity);
+        let render_transform = self.transform * inverse_transform * inverse_canvas_transform;
+
+        self.fill_transform = self.fill_transform * render_transform;
     }
 
     ///
@@ -258,19 +343,29 @@ impl DrawingState {
             DestinationOut => AlphaOperation::DestHeldOut,
             SourceAtop => AlphaOperation::SourceAtop,
             DestinationAtop => AlphaOperation::DestAtop,
-
-            Multiply => {
-                todo!()
-            }
-            Screen => {
-                todo!()
-            }
-            Darken => {
-                todo!()
-            }
-            Lighten => {
-                todo!()
-            }
+            Xor => AlphaOperation::Xor,
+            Clear => AlphaOperation::Clear,
+            Copy => AlphaOperation::Source,
+            Destination => AlphaOperation::Target,
+
+            Multiply => AlphaOperation::Multiply,
+            Screen => AlphaOperation::Screen,
+            Darken => AlphaOperation::Darken,
+            Lighten => AlphaOperation::Lighten,
+            Overlay => AlphaOperation::Overlay,
+            ColorDodge => AlphaOperation::ColorDodge,
+            ColorBurn => AlphaOperation::ColorBurn,
+            HardLight => AlphaOperation::HardLight,
+            SoftLight => AlphaOperation::SoftLight,
+            Difference => AlphaOperation::Difference,
+            Exclusion => AlphaOperation::Exclusion,
+
+            Hue => AlphaOperation::Hue,
+            Saturation => AlphaOperation::Saturation,
+            Color => AlphaOperation::Color,
+            Luminosity => AlphaOperation::Luminosity,
+
+            Plus => AlphaOperation::Plus,
         };
 
         if operation != self.blend_mode {
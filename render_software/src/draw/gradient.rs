@@ -0,0 +1,179 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::*;
+
+use flo_canvas as canvas;
+
+use crate::pixel::*;
+
+use super::canvas_drawing::*;
+use super::drawing_state::*;
+
+impl<TPixel, const N: usize> CanvasDrawing<TPixel, N>
+where
+    TPixel: 'static + Send + Sync + Pixel<N>,
+{
+    ///
+    /// Performs a gradient operation on this canvas drawing
+    ///
+    #[inline]
+    pub(crate) fn gradient(
+        &mut self,
+        gradient_id: canvas::GradientId,
+        gradient_op: canvas::GradientOp,
+    ) {
+        use canvas::GradientOp::*;
+
+        // Forget the colour tables for any gradient definitions that are no longer in use
+        self.gradient_color_tables
+            .retain(|_, (gradient, _)| gradient.strong_count() > 0);
+
+        match gradient_op {
+            Create(initial_color) => {
+                // Replace any existing gradient with this ID
+                self.gradients.insert(
+                    (self.current_namespace, gradient_id),
+                    Arc::new(vec![Create(initial_color)]),
+                );
+            }
+
+            AddStop(_, _) | SpreadMode(_) => {
+                // Brushes keep a reference to the gradient, so they will keep using the old definition once it's updated here
+                if let Some(gradient) = self
+                    .gradients
+                    .get_mut(&(self.current_namespace, gradient_id))
+                {
+                    Arc::make_mut(gradient).push(gradient_op);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Sets the brush to fill using the specified gradient, which will run from (x1, y1) to (x2, y2)
+    ///
+    pub(crate) fn fill_gradient(
+        &mut self,
+        gradient_id: canvas::GradientId,
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+    ) {
+        // Fetch the state from this object
+        let gradients = &self.gradients;
+        let current_state = &mut self.current_state;
+        let data_cache = &mut self.program_data_cache;
+
+        if let Some(gradient) = gradients.get(&(self.current_namespace, gradient_id)) {
+            // Transform the coordinates to screen coordinates
+            let (x1, y1) = current_state.transform.transform_point(x1, y1);
+            let (x2, y2) = current_state.transform.transform_point(x2, y2);
+
+            // We want a transformation that maps x1, y1 to 0,0 and x2, y2 to 1,0
+            let dx = x2 - x1;
+            let dy = y2 - y1;
+            let length_squared = (dx * dx + dy * dy).max(f32::EPSILON);
+
+            let a = dx / length_squared;
+            let b = dy / length_squared;
+            let d = -dy / length_squared;
+            let e = dx / length_squared;
+
+            let transform = canvas::Transform2D([
+                [a, b, -x1 * a - y1 * b],
+                [d, e, -x1 * d - y1 * e],
+                [0.0, 0.0, 1.0],
+            ]);
+
+            debug_assert!(
+                (transform.transform_point(x2, y2).0 - 1.0).abs() < 0.01,
+                "{:?} {:?}",
+                transform.transform_point(x2, y2),
+                (1.0, 0.0)
+            );
+
+            // Set as the brush state
+            DrawingState::release_program(&mut current_state.fill_program, data_cache);
+            current_state.next_fill_brush = Brush::LinearGradient(Arc::clone(gradient), transform);
+            current_state.fill_transform = canvas::Transform2D::identity();
+        }
+    }
+
+    ///
+    /// Sets the brush to fill using the specified radial gradient, which will run from the circle (x1, y1, r1) to the circle (x2, y2, r2)
+    ///
+    pub(crate) fn fill_radial_gradient(
+        &mut self,
+        gradient_id: canvas::GradientId,
+        (x1, y1, r1): (f32, f32, f32),
+        (x2, y2, r2): (f32, f32, f32),
+    ) {
+        // Fetch the state from this object
+        let gradients = &self.gradients;
+        let current_state = &mut self.current_state;
+        let data_cache = &mut self.program_data_cache;
+
+        if let Some(gradient) = gradients.get(&(self.current_namespace, gradient_id)) {
+            // Map from render coordinates back to canvas coordinates, then so that the end circle is the unit circle
+            let r2 = if r2.abs() > f32::EPSILON {
+                r2
+            } else {
+                f32::EPSILON
+            };
+            let to_canvas = current_state
+                .transform
+                .invert()
+                .unwrap_or(canvas::Transform2D::identity());
+            let transform = canvas::Transform2D::scale(1.0 / r2, 1.0 / r2)
+                * canvas::Transform2D::translate(-x2, -y2)
+                * to_canvas;
+
+            // The start circle is described relative to the end circle
+            let focal_point = ((x1 - x2) / r2, (y1 - y2) / r2);
+            let focal_radius = (r1 / r2).abs();
+
+            // Set as the brush state
+            DrawingState::release_program(&mut current_state.fill_program, data_cache);
+            current_state.next_fill_brush =
+                Brush::RadialGradient(Arc::clone(gradient), transform, focal_point, focal_radius);
+            current_state.fill_transform = canvas::Transform2D::identity();
+        }
+    }
+
+    ///
+    /// Sets the brush to fill using the specified conic gradient, which will sweep around (x, y) starting at the specified angle
+    ///
+    pub(crate) fn fill_conic_gradient(
+        &mut self,
+        gradient_id: canvas::GradientId,
+        x: f32,
+        y: f32,
+        start_angle: f32,
+    ) {
+        // Fetch the state from this object
+        let gradients = &self.gradients;
+        let current_state = &mut self.current_state;
+        let data_cache = &mut self.program_data_cache;
+
+        if let Some(gradient) = gradients.get(&(self.current_namespace, gradient_id)) {
+            // Map from render coordinates back to canvas coordinates, then so that the center is the origin and the start angle is along the x axis
+            let to_canvas = current_state
+                .transform
+                .invert()
+                .unwrap_or(canvas::Transform2D::identity());
+            let transform = canvas::Transform2D::rotate(-start_angle)
+                * canvas::Transform2D::translate(-x, -y)
+                * to_canvas;
+
+            // Set as the brush state
+            DrawingState::release_program(&mut current_state.fill_program, data_cache);
+            current_state.next_fill_brush = Brush::ConicGradient(Arc::clone(gradient), transform);
+            current_state.fill_transform = canvas::Transform2D::identity();
+        }
+    }
+}
@@ -6,6 +6,8 @@
 
 use std::sync::*;
 
+use flo_sparse_array::*;
+
 use flo_canvas as canvas;
 
 use crate::edgeplan::*;
@@ -178,6 +180,8 @@ where
                 self.program_data_cache.release_program_data(data_id);
             }
         }
+
+        self.prepared_layers.remove(handle.0);
     }
 
     ///
@@ -229,19 +233,29 @@ where
             DestinationOut => AlphaOperation::DestHeldOut,
             SourceAtop => AlphaOperation::SourceAtop,
             DestinationAtop => AlphaOperation::DestAtop,
-
-            Multiply => {
-                todo!()
-            }
-            Screen => {
-                todo!()
-            }
-            Darken => {
-                todo!()
-            }
-            Lighten => {
-                todo!()
-            }
+            Xor => AlphaOperation::Xor,
+            Clear => AlphaOperation::Clear,
+            Copy => AlphaOperation::Source,
+            Destination => AlphaOperation::Target,
+
+            Multiply => AlphaOperation::Multiply,
+            Screen => AlphaOperation::Screen,
+            Darken => AlphaOperation::Darken,
+            Lighten => AlphaOperation::Lighten,
+            Overlay => AlphaOperation::Overlay,
+            ColorDodge => AlphaOperation::ColorDodge,
+            ColorBurn => AlphaOperation::ColorBurn,
+            HardLight => AlphaOperation::HardLight,
+            SoftLight => AlphaOperation::SoftLight,
+            Difference => AlphaOperation::Difference,
+            Exclusion => AlphaOperation::Exclusion,
+
+            Hue => AlphaOperation::Hue,
+            Saturation => AlphaOperation::Saturation,
+            Color => AlphaOperation::Color,
+            Luminosity => AlphaOperation::Luminosity,
+
+            Plus => AlphaOperation::Plus,
         };
 
         if let Some(layer) = self.layer_with_id(layer_id) {
@@ -277,6 +291,8 @@ where
                 program_data_cache.release_program_data(data_id);
             }
         });
+
+        self.prepared_layers = SparseArray::empty();
     }
 
     ///
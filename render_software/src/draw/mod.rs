@@ -9,6 +9,7 @@ pub use renderer::*;
 
 mod canvas_drawing;
 mod drawing_state;
+mod gradient;
 mod layer;
 mod path;
 mod pixel_programs;
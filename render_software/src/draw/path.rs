@@ -180,6 +180,47 @@ impl<TPixel, const N: usize> CanvasDrawing<TPixel, N>
 where
     TPixel: 'static + Send + Sync + Pixel<N>,
 {
+    ///
+    /// Retrieves the colour table used by the gradient pixel programs for a gradient definition
+    ///
+    /// Colour tables are cached, so brushes that use the same gradient share the same table
+    ///
+    fn gradient_color_table(
+        color_tables: &mut GradientColorTables<TPixel>,
+        gradient: &Arc<Vec<canvas::GradientOp>>,
+        gamma: f64,
+    ) -> Arc<Vec<TPixel>> {
+        // The weak reference keeps the allocation alive, so a gradient with the same address is always the same definition
+        let gradient_address = Arc::as_ptr(gradient) as usize;
+
+        if let Some((cached_gradient, color_table)) = color_tables.get(&gradient_address) {
+            if cached_gradient.strong_count() > 0 {
+                return Arc::clone(color_table);
+            }
+        }
+
+        let color_table = canvas::gradient_scale::<_, 256>(gradient.iter().cloned())
+            .iter()
+            .map(|[r, g, b, a]| {
+                let color = canvas::Color::Rgba(
+                    (*r as f32) / 255.0,
+                    (*g as f32) / 255.0,
+                    (*b as f32) / 255.0,
+                    (*a as f32) / 255.0,
+                );
+                TPixel::from_color(color, gamma)
+            })
+            .collect::<Vec<_>>();
+        let color_table = Arc::new(color_table);
+
+        color_tables.insert(
+            gradient_address,
+            (Arc::downgrade(gradient), Arc::clone(&color_table)),
+        );
+
+        color_table
+    }
+
     ///
     /// Creates a shape descriptor from a brush
     ///
@@ -198,6 +239,7 @@ where
         let gamma = self.gamma;
         let program_cache = &self.program_cache;
         let data_cache = &mut self.program_data_cache;
+        let color_tables = &mut self.gradient_color_tables;
 
         let descriptor = match (operation, brush) {
             (AlphaOperation::SourceOver, OpaqueSolidColor(color)) => {
@@ -242,8 +284,70 @@ where
                 }
             }
 
-            (_, TransparentTexture(texture, transform)) => {
-                let texture_data = TextureData::with_texture(Arc::clone(texture), transform);
+            (_, LinearGradient(gradient, transform)) => {
+                let color_table = Self::gradient_color_table(color_tables, gradient, gamma);
+                let gradient_data = LinearGradientData::with_color_table(
+                    color_table,
+                    operation,
+                    transform,
+                    canvas::gradient_spread(gradient.iter()),
+                );
+                let brush_data = program_cache.program_cache.store_program_data(
+                    &program_cache.linear_gradient,
+                    data_cache,
+                    gradient_data,
+                );
+
+                ShapeDescriptor {
+                    programs: smallvec![brush_data],
+                    is_opaque: false,
+                    z_index: 0,
+                }
+            }
+
+            (_, RadialGradient(gradient, transform, focal_point, focal_radius)) => {
+                let color_table = Self::gradient_color_table(color_tables, gradient, gamma);
+                let gradient_data = RadialGradientData::with_color_table(
+                    color_table,
+                    operation,
+                    transform,
+                    *focal_point,
+                    *focal_radius,
+                    canvas::gradient_spread(gradient.iter()),
+                );
+                let brush_data = program_cache.program_cache.store_program_data(
+                    &program_cache.radial_gradient,
+                    data_cache,
+                    gradient_data,
+                );
+
+                ShapeDescriptor {
+                    programs: smallvec![brush_data],
+                    is_opaque: false,
+                    z_index: 0,
+                }
+            }
+
+            (_, ConicGradient(gradient, transform)) => {
+                let color_table = Self::gradient_color_table(color_tables, gradient, gamma);
+                let gradient_data =
+                    ConicGradientData::with_color_table(color_table, operation, transform);
+                let brush_data = program_cache.program_cache.store_program_data(
+                    &program_cache.conic_gradient,
+                    data_cache,
+                    gradient_data,
+                );
+
+                ShapeDescriptor {
+                    programs: smallvec![brush_data],
+                    is_opaque: false,
+                    z_index: 0,
+                }
+            }
+
+            (_, TransparentTexture(texture, transform, alpha)) => {
+                let texture_data =
+                    TextureData::with_texture(Arc::clone(texture), transform, *alpha as f64);
                 let brush_data = program_cache.program_cache.store_program_data(
                     &program_cache.basic_texture,
                     data_cache,
@@ -270,10 +374,12 @@ where
             if let Some(shape_descriptor) = &mut self.current_state.fill_program {
                 shape_descriptor.clone()
             } else {
-                let shape_descriptor = self.create_shape_descriptor(
-                    &self.current_state.next_fill_brush.clone(),
-                    self.current_state.blend_mode,
-                );
+                let fill_brush = self
+                    .current_state
+                    .next_fill_brush
+                    .transformed(&self.current_state.fill_transform);
+                let shape_descriptor =
+                    self.create_shape_descriptor(&fill_brush, self.current_state.blend_mode);
                 self.current_state.fill_program = Some(shape_descriptor.clone());
 
                 shape_descriptor
@@ -38,6 +38,15 @@ where
     /// The general solid colour blending pixel program
     pub(super) blend_color: StoredPixelProgramFromProgram<BlendColorProgram<TPixel>>,
 
+    /// The linear gradient rendering program
+    pub(super) linear_gradient: StoredPixelProgramFromProgram<LinearGradientProgram<TPixel>>,
+
+    /// The radial gradient rendering program
+    pub(super) radial_gradient: StoredPixelProgramFromProgram<RadialGradientProgram<TPixel>>,
+
+    /// The conic gradient rendering program
+    pub(super) conic_gradient: StoredPixelProgramFromProgram<ConicGradientProgram<TPixel>>,
+
     /// The basic texture rendering program
     pub(super) basic_texture:
         StoredPixelProgramFromProgram<BasicTextureProgram<TPixel, RgbaTexture>>,
@@ -58,6 +67,9 @@ where
         let solid_color = cache.add_pixel_program(SolidColorProgram::default());
         let source_over = cache.add_pixel_program(SourceOverColorProgram::default());
         let blend_color = cache.add_pixel_program(BlendColorProgram::default());
+        let linear_gradient = cache.add_pixel_program(LinearGradientProgram::default());
+        let radial_gradient = cache.add_pixel_program(RadialGradientProgram::default());
+        let conic_gradient = cache.add_pixel_program(ConicGradientProgram::default());
         let basic_texture = cache.add_pixel_program(BasicTextureProgram::default());
         let basic_sprite =
             cache.add_pixel_program::<SimpleSpriteProgram<TPixel>>(BasicSpriteProgram::default());
@@ -67,12 +79,15 @@ where
 
         CanvasPixelPrograms {
             program_cache: cache,
-            solid_color: solid_color,
+            solid_color,
             source_over_color: source_over,
-            blend_color: blend_color,
-            basic_texture: basic_texture,
-            basic_sprite: basic_sprite,
-            transformed_sprite: transformed_sprite,
+            blend_color,
+            linear_gradient,
+            radial_gradient,
+            conic_gradient,
+            basic_texture,
+            basic_sprite,
+            transformed_sprite,
         }
     }
 }
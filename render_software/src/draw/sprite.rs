@@ -287,6 +287,158 @@ where
             }
         }
     }
+
+    ///
+    /// Draws a sprite with a set of filters applied to it
+    ///
+    /// The sprite is rendered to a texture at the resolution of the canvas, then the filters are applied to the texture
+    /// and it's drawn using the current sprite transform. Filter sizes are in canvas units.
+    ///
+    pub(crate) fn sprite_draw_with_filters(
+        &mut self,
+        sprite_id: canvas::SpriteId,
+        filters: Vec<canvas::TextureFilter>,
+    ) {
+        use std::iter;
+
+        // Largest width or height of the texture that the sprite is rendered to
+        const MAX_TEXTURE_SIZE: f64 = 4096.0;
+
+        // Prepare the sprite layer for rendering
+        let sprite_layer_handle = if let Some(sprite_layer_handle) =
+            self.sprites.get(&(self.current_namespace, sprite_id))
+        {
+            *sprite_layer_handle
+        } else {
+            return;
+        };
+
+        let sprite_layer = self.prepare_sprite_layer(sprite_layer_handle);
+        if sprite_layer.edges.is_empty() {
+            return;
+        }
+
+        // Transform from sprite coordinates to render coordinates
+        let canvas_transform =
+            self.current_state.transform * self.current_state.sprite_transform.matrix();
+        let inverse_canvas_transform = if let Some(inverse) = canvas_transform.invert() {
+            inverse
+        } else {
+            return;
+        };
+
+        // Work out the bounds of the sprite in sprite coordinates
+        let ((min_x, min_y), (max_x, max_y)) = sprite_layer.bounds;
+        let corners = [
+            (min_x, min_y),
+            (max_x, min_y),
+            (min_x, max_y),
+            (max_x, max_y),
+        ]
+        .map(|(x, y)| {
+            let (x, y) = sprite_layer
+                .inverse_transform
+                .transform_point(x as f32, y as f32);
+            (x as f64, y as f64)
+        });
+        let min_x = corners.iter().map(|(x, _)| *x).fold(f64::MAX, f64::min);
+        let min_y = corners.iter().map(|(_, y)| *y).fold(f64::MAX, f64::min);
+        let max_x = corners.iter().map(|(x, _)| *x).fold(f64::MIN, f64::max);
+        let max_y = corners.iter().map(|(_, y)| *y).fold(f64::MIN, f64::max);
+
+        // Blurs and displacements can move pixels outside of the sprite, so we pad the texture by the size of these filters
+        let (canvas_scale_x, canvas_scale_y) =
+            self.pixels_per_canvas_unit(&self.current_state.transform);
+        let (sprite_scale_x, sprite_scale_y) = self.pixels_per_canvas_unit(&canvas_transform);
+        if sprite_scale_x <= 0.0 || sprite_scale_y <= 0.0 {
+            return;
+        }
+
+        let padding = filters
+            .iter()
+            .map(|filter| match filter {
+                canvas::TextureFilter::GaussianBlur(radius) => radius.abs() as f64,
+                canvas::TextureFilter::DisplacementMap(_, x_radius, y_radius) => {
+                    x_radius.abs().max(y_radius.abs()) as f64
+                }
+                canvas::TextureFilter::AlphaBlend(_) | canvas::TextureFilter::Mask(_) => 0.0,
+            })
+            .sum::<f64>();
+        let min_x = min_x - padding * canvas_scale_x / sprite_scale_x;
+        let max_x = max_x + padding * canvas_scale_x / sprite_scale_x;
+        let min_y = min_y - padding * canvas_scale_y / sprite_scale_y;
+        let max_y = max_y + padding * canvas_scale_y / sprite_scale_y;
+
+        // Render the sprite at the resolution it will appear on the canvas
+        let width = ((max_x - min_x) * sprite_scale_x)
+            .ceil()
+            .clamp(1.0, MAX_TEXTURE_SIZE) as usize;
+        let height = ((max_y - min_y) * sprite_scale_y)
+            .ceil()
+            .clamp(1.0, MAX_TEXTURE_SIZE) as usize;
+        let bounds = canvas::SpriteBounds(
+            canvas::SpritePosition(min_x as f32, min_y as f32),
+            canvas::SpriteSize((max_x - min_x) as f32, (max_y - min_y) as f32),
+        );
+
+        let mut pixels = self.render_sprite_to_pixels(sprite_layer_handle, bounds, width, height);
+
+        // Apply the filters
+        let filter_scale = (
+            (width as f64) / (max_x - min_x) * canvas_scale_x / sprite_scale_x,
+            (height as f64) / (max_y - min_y) * canvas_scale_y / sprite_scale_y,
+        );
+
+        for filter in filters.iter() {
+            if let Some(pixel_filter) = self.create_pixel_filter(filter, filter_scale) {
+                pixels = pixel_filter.filter(width, height, &pixels);
+            }
+        }
+
+        // Create a texture brush that maps from render coordinates to the texture
+        let texture = Arc::new(RgbaTexture::from_pixels(width, height, &pixels));
+        let texture_transform = canvas::Transform2D::scale(
+            (width as f64 / (max_x - min_x)) as f32,
+            (height as f64 / (max_y - min_y)) as f32,
+        ) * canvas::Transform2D::translate(-min_x as f32, -min_y as f32)
+            * inverse_canvas_transform;
+
+        let mut shape_descriptor = self.create_shape_descriptor(
+            &Brush::TransparentTexture(texture, texture_transform, 1.0),
+            AlphaOperation::SourceOver,
+        );
+
+        // Draw the texture as a rectangle covering the sprite
+        let current_layer = self.layers.get_mut(self.current_layer.0).unwrap();
+        shape_descriptor.z_index = current_layer.z_index;
+        current_layer.z_index += 1;
+
+        let corners = [
+            (min_x, min_y),
+            (max_x, min_y),
+            (max_x, max_y),
+            (min_x, max_y),
+            (min_x, min_y),
+        ]
+        .map(|(x, y)| {
+            let (x, y) = canvas_transform.transform_point(x as f32, y as f32);
+            canvas::Coord2(x as _, y as _)
+        });
+
+        let shape_id = ShapeId::new();
+        let sprite_edge = PolylineNonZeroEdge::new(shape_id, corners.to_vec());
+        let sprite_edge: Arc<dyn EdgeDescriptor> = Arc::new(sprite_edge);
+
+        current_layer
+            .used_data
+            .extend(shape_descriptor.programs.iter().copied());
+        current_layer
+            .edges
+            .add_shape(shape_id, shape_descriptor, iter::once(sprite_edge));
+
+        // This 'unprepares' the current layer as for any other drawing operation
+        self.prepared_layers.remove(self.current_layer.0);
+    }
 }
 
 impl DrawingState {
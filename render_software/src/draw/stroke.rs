@@ -4,7 +4,13 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use std::iter;
+
+use itertools::*;
+
+use flo_canvas as canvas;
 use flo_canvas::curves::bezier::path::*;
+use flo_canvas::curves::bezier::*;
 
 use crate::edgeplan::*;
 use crate::edges::*;
@@ -15,14 +21,21 @@ use super::drawing_state::*;
 
 impl DrawingState {
     ///
-    /// Sets the width of the stroke
+    /// Returns the factor that lengths in canvas units should be multiplied by to convert them to render units
     ///
-    pub(crate) fn line_width(&mut self, width: f64) {
+    #[inline]
+    fn stroke_scale(&self) -> f64 {
         let transform = &self.transform.0;
         let scale = (transform[0][0] * transform[0][0] + transform[1][0] * transform[1][0]).sqrt();
-        let scale = scale as f64;
 
-        self.stroke_width = width * scale;
+        scale as f64
+    }
+
+    ///
+    /// Sets the width of the stroke
+    ///
+    pub(crate) fn line_width(&mut self, width: f64) {
+        self.stroke_width = width * self.stroke_scale();
     }
 
     ///
@@ -33,6 +46,96 @@ impl DrawingState {
 
         self.stroke_width = pixel_size * pixel_width;
     }
+
+    ///
+    /// Clears the dash pattern, so the next stroke will be drawn as a solid line
+    ///
+    pub(crate) fn new_dash_pattern(&mut self) {
+        self.dash_pattern.clear();
+        self.dash_offset = 0.0;
+    }
+
+    ///
+    /// Adds a new dash to the dash pattern (dashes alternate between drawn and not drawn)
+    ///
+    pub(crate) fn dash_length(&mut self, length: f64) {
+        // Like the line width, the dash length is fixed using the transform at the point it's set
+        self.dash_pattern.push(length * self.stroke_scale());
+    }
+
+    ///
+    /// Sets the offset for the start of the dash pattern
+    ///
+    pub(crate) fn dash_offset(&mut self, offset: f64) {
+        self.dash_offset = offset * self.stroke_scale();
+    }
+
+    ///
+    /// Divides the current path into dashes using the current dash pattern, returning the new path edges and subpaths
+    ///
+    /// `height_pixels` is used to set the precision of the dashes
+    ///
+    pub(crate) fn dashed_path(&self, height_pixels: f64) -> (Vec<Curve<Coord2>>, Vec<usize>) {
+        // path_to_dashed_lines uses a fixed precision, which is designed for canvas units, so we scale up the path to match the pixel size
+        let pixel_scale = height_pixels / 2.0;
+        let to_pixels = |point: Coord2| point * pixel_scale;
+        let from_pixels = |point: Coord2| point * (1.0 / pixel_scale);
+
+        let mut path_edges = vec![];
+        let mut subpaths = vec![];
+
+        for (start_idx, end_idx) in self
+            .subpaths
+            .iter()
+            .copied()
+            .chain(iter::once(self.path_edges.len()))
+            .tuple_windows()
+        {
+            if start_idx >= end_idx {
+                continue;
+            }
+
+            // Create a path in pixel coordinates from this subpath
+            let subpath = (
+                to_pixels(self.path_edges[start_idx].start_point()),
+                self.path_edges[start_idx..end_idx]
+                    .iter()
+                    .map(|curve| {
+                        let (cp1, cp2) = curve.control_points();
+                        (to_pixels(cp1), to_pixels(cp2), to_pixels(curve.end_point()))
+                    })
+                    .collect::<Vec<_>>(),
+            );
+
+            // Divide into dashes, and add each dash as a new subpath
+            let dashes = canvas::path_to_dashed_lines::<_, SimpleBezierPath, _>(
+                &subpath,
+                self.dash_pattern.iter().map(|length| length * pixel_scale),
+                self.dash_offset * pixel_scale,
+            );
+
+            for (dash_start, dash_curves) in dashes {
+                subpaths.push(path_edges.len());
+
+                let mut last_point = from_pixels(dash_start);
+                for (cp1, cp2, end_point) in dash_curves {
+                    let end_point = from_pixels(end_point);
+                    path_edges.push(Curve::from_points(
+                        last_point,
+                        (from_pixels(cp1), from_pixels(cp2)),
+                        end_point,
+                    ));
+                    last_point = end_point;
+                }
+            }
+        }
+
+        if subpaths.is_empty() {
+            subpaths.push(0);
+        }
+
+        (path_edges, subpaths)
+    }
 }
 
 impl<TPixel, const N: usize> CanvasDrawing<TPixel, N>
@@ -89,14 +192,19 @@ where
             .with_join(current_state.stroke_join);
         let width = current_state.stroke_width;
 
+        // Divide the path into dashes if there's a dash pattern
+        let (path_edges, subpaths) = if current_state.dash_pattern.is_empty() {
+            (
+                current_state.path_edges.clone(),
+                current_state.subpaths.clone(),
+            )
+        } else {
+            current_state.dashed_path(self.height_pixels)
+        };
+
         // Create the edge
-        let stroke_edge = FlattenedLineStrokeEdge::new(
-            shape_id,
-            current_state.path_edges.clone(),
-            current_state.subpaths.clone(),
-            width,
-            stroke_options,
-        );
+        let stroke_edge =
+            FlattenedLineStrokeEdge::new(shape_id, path_edges, subpaths, width, stroke_options);
         current_state
             .clip_shape(shape_id, vec![stroke_edge])
             .into_iter()
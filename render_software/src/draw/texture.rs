@@ -8,10 +8,15 @@ use std::sync::*;
 
 use flo_canvas as canvas;
 
+use crate::edgeplan::*;
+use crate::filters::*;
 use crate::pixel::*;
+use crate::render::*;
+use crate::scanplan::*;
 
 use super::canvas_drawing::*;
 use super::drawing_state::*;
+use super::layer::*;
 
 // TODO: we store Texture as Arc<Texture> but we also tend to use Arc<> internally: do we need both?
 
@@ -24,6 +29,32 @@ pub enum Texture {
     Rgba(Arc<RgbaTexture>),
 }
 
+type SpriteEdges = Arc<EdgePlan<Arc<dyn EdgeDescriptor>>>;
+
+///
+/// A texture that is rendered from a sprite, and which is re-rendered if the sprite or the resolution changes
+///
+#[derive(Clone)]
+pub struct DynamicTexture {
+    /// The layer containing the sprite that this texture is rendered from
+    sprite_layer: LayerHandle,
+
+    /// The region of the sprite that's rendered to the texture
+    bounds: canvas::SpriteBounds,
+
+    /// The size of the texture in canvas units
+    canvas_size: canvas::CanvasSize,
+
+    /// The canvas transform when this texture was created (used to work out the size of the texture in pixels)
+    transform: canvas::Transform2D,
+
+    /// The filters to apply to the texture after it's rendered
+    filters: Vec<canvas::TextureFilter>,
+
+    /// The edges of the sprite the last time the texture was rendered, and the pixel height of the canvas at that time
+    rendered: Option<(SpriteEdges, f64)>,
+}
+
 impl<TPixel, const N: usize> CanvasDrawing<TPixel, N>
 where
     TPixel: 'static + Send + Sync + Pixel<N>,
@@ -45,11 +76,21 @@ where
             SetBytes(position, size, bytes) => {
                 self.texture_set_bytes(texture_id, position, size, bytes);
             }
-            SetFromSprite(sprite_id, bounds) => { /* todo!() */ }
-            CreateDynamicSprite(sprite_id, bounds, size) => { /* todo!() */ }
-            FillTransparency(alpha) => { /* todo!() */ }
-            Copy(target_texture) => { /* todo!() */ }
-            Filter(filter) => { /* todo!() */ }
+            SetFromSprite(sprite_id, bounds) => {
+                self.texture_set_from_sprite(texture_id, sprite_id, bounds);
+            }
+            CreateDynamicSprite(sprite_id, bounds, size) => {
+                self.texture_create_dynamic_sprite(texture_id, sprite_id, bounds, size);
+            }
+            FillTransparency(alpha) => {
+                self.texture_fill_transparency(texture_id, alpha);
+            }
+            Copy(target_texture) => {
+                self.texture_copy(texture_id, target_texture);
+            }
+            Filter(filter) => {
+                self.texture_filter(texture_id, filter);
+            }
         }
     }
 
@@ -58,7 +99,11 @@ where
     ///
     #[inline]
     pub(crate) fn texture_free(&mut self, texture_id: canvas::TextureId) {
-        self.textures.remove(&(self.current_namespace, texture_id));
+        let key = (self.current_namespace, texture_id);
+
+        self.textures.remove(&key);
+        self.texture_alpha.remove(&key);
+        self.dynamic_textures.remove(&key);
     }
 
     ///
@@ -79,8 +124,11 @@ where
         let texture = Texture::Rgba(Arc::new(texture));
 
         // Store it, replacing any existing texture with this ID
-        self.textures
-            .insert((self.current_namespace, texture_id), Arc::new(texture));
+        let key = (self.current_namespace, texture_id);
+
+        self.textures.insert(key, Arc::new(texture));
+        self.texture_alpha.remove(&key);
+        self.dynamic_textures.remove(&key);
     }
 
     ///
@@ -123,10 +171,18 @@ where
         x2: f32,
         y2: f32,
     ) {
+        // Dynamic textures are re-rendered if their sprite has changed
+        self.update_dynamic_texture(texture_id);
+
         // Fetch the state from this object
         let textures = &self.textures;
         let current_state = &mut self.current_state;
         let data_cache = &mut self.program_data_cache;
+        let alpha = self
+            .texture_alpha
+            .get(&(self.current_namespace, texture_id))
+            .copied()
+            .unwrap_or(1.0);
 
         // Transform the coordiantes to screen coordinates
         let (x1, y1) = current_state.transform.transform_point(x1, y1);
@@ -161,9 +217,407 @@ where
                     // Set as the brush state
                     DrawingState::release_program(&mut current_state.fill_program, data_cache);
                     current_state.next_fill_brush =
-                        Brush::TransparentTexture(Arc::clone(rgba_texture), transform);
+                        Brush::TransparentTexture(Arc::clone(rgba_texture), transform, alpha);
+                    current_state.fill_transform = canvas::Transform2D::identity();
                 }
             }
         }
     }
+
+    ///
+    /// Renders a region of a sprite (in sprite coordinates) to a new texture of the specified size
+    ///
+    /// The bottom of the sprite region is mapped to the first row of the texture, matching how the other renderers generate
+    /// textures from sprites.
+    ///
+    pub(crate) fn render_sprite_to_texture(
+        &mut self,
+        sprite_layer: LayerHandle,
+        bounds: canvas::SpriteBounds,
+        width: usize,
+        height: usize,
+    ) -> RgbaTexture {
+        let width = width.max(1);
+        let height = height.max(1);
+        let pixels = self.render_sprite_to_pixels(sprite_layer, bounds, width, height);
+
+        RgbaTexture::from_pixels(width, height, &pixels)
+    }
+
+    ///
+    /// Renders a region of a sprite (in sprite coordinates) to a buffer of pixels, which must be at least 1x1 pixels in size
+    ///
+    pub(crate) fn render_sprite_to_pixels(
+        &mut self,
+        sprite_layer: LayerHandle,
+        canvas::SpriteBounds(canvas::SpritePosition(x, y), canvas::SpriteSize(w, h)): canvas::SpriteBounds,
+        width: usize,
+        height: usize,
+    ) -> Vec<TPixel> {
+        // The prepared layer has its edges in render coordinates
+        let sprite_layer = self.prepare_sprite_layer(sprite_layer);
+
+        // Edges can be rendered directly if the sprite transform is just a scale and a translation, otherwise map them back to sprite coordinates
+        let [[a, b, c], [d, e, f], [_, _, _]] = sprite_layer.transform.0;
+        let (edges, (a, c), (e, f)) = if b == 0.0 && d == 0.0 && a > 0.0 {
+            (
+                sprite_layer.edges,
+                (a as f64, c as f64),
+                (e as f64, f as f64),
+            )
+        } else {
+            let mut edges = sprite_layer
+                .edges
+                .transform(&sprite_layer.inverse_transform);
+            edges.prepare_to_render();
+
+            (Arc::new(edges), (1.0, 0.0), (1.0, 0.0))
+        };
+
+        // Work out the region to render in the coordinates used by the edges
+        let (x, y, w, h) = (x as f64, y as f64, w as f64, h as f64);
+        let x_range = (a * x + c)..(a * (x + w) + c);
+        let pixel_size = (x_range.end - x_range.start) / (width as f64);
+        let transform = ScanlineTransform::for_region(&x_range, width);
+        let mut y_positions = (0..height)
+            .map(|row| e * (y + (row as f64) * h / (height as f64)) + f)
+            .collect::<Vec<_>>();
+
+        // The scan planner needs the y positions in ascending order, so flipped sprites are rendered from the last row
+        let flipped = e < 0.0;
+        if flipped {
+            y_positions.reverse();
+        }
+
+        // Plan and render the scanlines
+        let mut scanlines = vec![(0.0, ScanlinePlan::default()); height];
+        PixelScanPlanner::default().plan_scanlines(
+            &*edges,
+            &transform,
+            &y_positions,
+            x_range,
+            &mut scanlines,
+        );
+
+        let renderer = ScanlineRenderer::new(
+            self.program_data_cache
+                .create_program_runner(PixelSize(pixel_size)),
+        );
+        let mut pixels =
+            vec![
+                TPixel::from_color(canvas::Color::Rgba(0.0, 0.0, 0.0, 0.0), self.gamma);
+                width * height
+            ];
+        let mut region = ScanlineRenderRegion {
+            y_pos: 0.0,
+            transform,
+        };
+
+        let lines: Box<dyn Iterator<Item = &mut [TPixel]>> = if flipped {
+            Box::new(pixels.chunks_exact_mut(width).rev())
+        } else {
+            Box::new(pixels.chunks_exact_mut(width))
+        };
+
+        for ((y_pos, (_, scanline)), line) in y_positions.iter().zip(scanlines.iter()).zip(lines) {
+            region.y_pos = *y_pos;
+            renderer.render(&region, scanline, line);
+        }
+
+        pixels
+    }
+
+    ///
+    /// Renders a sprite to an existing texture
+    ///
+    pub(crate) fn texture_set_from_sprite(
+        &mut self,
+        texture_id: canvas::TextureId,
+        sprite_id: canvas::SpriteId,
+        bounds: canvas::SpriteBounds,
+    ) {
+        let key = (self.current_namespace, texture_id);
+
+        // Both the texture and the sprite need to exist
+        let (width, height) = match self.textures.get(&key).map(|texture| &**texture) {
+            Some(Texture::Rgba(rgba)) => (rgba.width(), rgba.height()),
+            None => return,
+        };
+        let sprite_layer =
+            if let Some(sprite_layer) = self.sprites.get(&(self.current_namespace, sprite_id)) {
+                *sprite_layer
+            } else {
+                return;
+            };
+
+        // Replace the texture with the rendered sprite (this texture is no longer dynamic if it was before)
+        let texture = self.render_sprite_to_texture(sprite_layer, bounds, width, height);
+
+        self.textures
+            .insert(key, Arc::new(Texture::Rgba(Arc::new(texture))));
+        self.dynamic_textures.remove(&key);
+    }
+
+    ///
+    /// Creates a texture that renders a sprite at the resolution of the canvas, and which updates when the sprite is changed
+    ///
+    pub(crate) fn texture_create_dynamic_sprite(
+        &mut self,
+        texture_id: canvas::TextureId,
+        sprite_id: canvas::SpriteId,
+        bounds: canvas::SpriteBounds,
+        canvas_size: canvas::CanvasSize,
+    ) {
+        let key = (self.current_namespace, texture_id);
+
+        if let Some(sprite_layer) = self.sprites.get(&(self.current_namespace, sprite_id)) {
+            // Replace any existing texture with a dynamic one
+            self.textures.remove(&key);
+            self.texture_alpha.remove(&key);
+            self.dynamic_textures.insert(
+                key,
+                DynamicTexture {
+                    sprite_layer: *sprite_layer,
+                    bounds,
+                    canvas_size,
+                    transform: self.current_state.transform,
+                    filters: vec![],
+                    rendered: None,
+                },
+            );
+
+            // Render the initial version of the texture
+            self.update_dynamic_texture(texture_id);
+        }
+    }
+
+    ///
+    /// If a texture is a dynamic texture and its sprite or the resolution of the canvas has changed, re-renders it
+    ///
+    pub(crate) fn update_dynamic_texture(&mut self, texture_id: canvas::TextureId) {
+        let key = (self.current_namespace, texture_id);
+
+        let dynamic_texture = if let Some(dynamic_texture) = self.dynamic_textures.get(&key) {
+            dynamic_texture.clone()
+        } else {
+            return;
+        };
+
+        // Nothing to do if the sprite is unchanged since the last time the texture was rendered
+        let sprite_edges = self
+            .prepare_sprite_layer(dynamic_texture.sprite_layer)
+            .edges;
+        if let Some((rendered_edges, rendered_height)) = &dynamic_texture.rendered {
+            if Arc::ptr_eq(rendered_edges, &sprite_edges) && *rendered_height == self.height_pixels
+            {
+                return;
+            }
+        }
+
+        // Map the size of the texture to pixels
+        let canvas::CanvasSize(w, h) = dynamic_texture.canvas_size;
+        let (scale_x, scale_y) = self.pixels_per_canvas_unit(&dynamic_texture.transform);
+        let width = ((w as f64) * scale_x).ceil();
+        let height = ((h as f64) * scale_y).ceil();
+
+        // Render the texture
+        let texture = self.render_sprite_to_texture(
+            dynamic_texture.sprite_layer,
+            dynamic_texture.bounds,
+            width as usize,
+            height as usize,
+        );
+        self.textures
+            .insert(key, Arc::new(Texture::Rgba(Arc::new(texture))));
+
+        // Mark as rendered before applying the filters (so a filter that refers back to this texture won't re-render it)
+        if let Some(dynamic_texture) = self.dynamic_textures.get_mut(&key) {
+            dynamic_texture.rendered = Some((sprite_edges, self.height_pixels));
+        }
+
+        for filter in dynamic_texture.filters.iter() {
+            self.apply_texture_filter(texture_id, filter);
+        }
+    }
+
+    ///
+    /// Sets the alpha value to use when filling with a texture
+    ///
+    pub(crate) fn texture_fill_transparency(&mut self, texture_id: canvas::TextureId, alpha: f32) {
+        let key = (self.current_namespace, texture_id);
+        self.texture_alpha.insert(key, alpha);
+
+        // Update the current brush if it's using this texture
+        if let (
+            Some(Texture::Rgba(rgba_texture)),
+            Brush::TransparentTexture(brush_texture, transform, _),
+        ) = (
+            self.textures.get(&key).map(|texture| &**texture),
+            &self.current_state.next_fill_brush,
+        ) {
+            if Arc::ptr_eq(rgba_texture, brush_texture) {
+                let brush = Brush::TransparentTexture(Arc::clone(brush_texture), *transform, alpha);
+
+                DrawingState::release_program(
+                    &mut self.current_state.fill_program,
+                    &mut self.program_data_cache,
+                );
+                self.current_state.next_fill_brush = brush;
+            }
+        }
+    }
+
+    ///
+    /// Copies a texture to another texture ID
+    ///
+    pub(crate) fn texture_copy(
+        &mut self,
+        source_texture_id: canvas::TextureId,
+        target_texture_id: canvas::TextureId,
+    ) {
+        self.update_dynamic_texture(source_texture_id);
+
+        let source_key = (self.current_namespace, source_texture_id);
+        let target_key = (self.current_namespace, target_texture_id);
+
+        // Textures are copied on write, so the copy can just share the source texture for now
+        if let Some(texture) = self.textures.get(&source_key) {
+            let texture = Arc::clone(texture);
+
+            self.textures.insert(target_key, texture);
+            self.dynamic_textures.remove(&target_key);
+        }
+    }
+
+    ///
+    /// Applies a filter to a texture
+    ///
+    pub(crate) fn texture_filter(
+        &mut self,
+        texture_id: canvas::TextureId,
+        filter: canvas::TextureFilter,
+    ) {
+        // Dynamic textures re-apply their filters whenever they're re-rendered
+        self.update_dynamic_texture(texture_id);
+
+        if let Some(dynamic_texture) = self
+            .dynamic_textures
+            .get_mut(&(self.current_namespace, texture_id))
+        {
+            dynamic_texture.filters.push(filter);
+        }
+
+        self.apply_texture_filter(texture_id, &filter);
+    }
+
+    ///
+    /// Applies a filter to the pixels of a texture
+    ///
+    /// Filter sizes are in pixels for normal textures, and in canvas units for dynamic textures
+    ///
+    pub(crate) fn apply_texture_filter(
+        &mut self,
+        texture_id: canvas::TextureId,
+        filter: &canvas::TextureFilter,
+    ) {
+        let key = (self.current_namespace, texture_id);
+        let pixel_scale = if let Some(dynamic_texture) = self.dynamic_textures.get(&key) {
+            self.pixels_per_canvas_unit(&dynamic_texture.transform)
+        } else {
+            (1.0, 1.0)
+        };
+
+        if let Some(pixel_filter) = self.create_pixel_filter(filter, pixel_scale) {
+            self.update_rgba_texture(key, move |rgba| {
+                *rgba = filter_rgba_texture(rgba, &*pixel_filter);
+            });
+        }
+    }
+
+    ///
+    /// Creates the pixel filter that implements a canvas texture filter
+    ///
+    /// The pixel scale is the number of pixels per unit for the sizes used in the filter. This returns `None` if the filter
+    /// can't be created (for instance, because it refers to a texture that doesn't exist)
+    ///
+    pub(crate) fn create_pixel_filter(
+        &mut self,
+        filter: &canvas::TextureFilter,
+        (scale_x, scale_y): (f64, f64),
+    ) -> Option<Box<dyn PixelFilter<TPixel, N>>> {
+        use canvas::TextureFilter::*;
+
+        match filter {
+            GaussianBlur(radius) => Some(Box::new(GaussianBlurFilter::with_radius_xy(
+                (*radius as f64) * scale_x,
+                (*radius as f64) * scale_y,
+            ))),
+
+            AlphaBlend(alpha) => Some(Box::new(AlphaBlendFilter::with_alpha(*alpha as f64))),
+
+            Mask(mask_texture_id) => {
+                let mask = self.rgba_texture(*mask_texture_id)?;
+
+                Some(Box::new(MaskFilter::with_mask(mask)))
+            }
+
+            DisplacementMap(displacement_texture_id, x_radius, y_radius) => {
+                let displacement = self.rgba_texture(*displacement_texture_id)?;
+
+                Some(Box::new(DisplacementMapFilter::with_displacement(
+                    displacement,
+                    (*x_radius as f64) * scale_x,
+                    (*y_radius as f64) * scale_y,
+                )))
+            }
+        }
+    }
+
+    ///
+    /// Retrieves the RGBA texture with the specified ID, updating it first if it's a dynamic texture
+    ///
+    fn rgba_texture(&mut self, texture_id: canvas::TextureId) -> Option<Arc<RgbaTexture>> {
+        self.update_dynamic_texture(texture_id);
+
+        match self
+            .textures
+            .get(&(self.current_namespace, texture_id))
+            .map(|texture| &**texture)
+        {
+            Some(Texture::Rgba(rgba)) => Some(Arc::clone(rgba)),
+            None => None,
+        }
+    }
+
+    ///
+    /// Returns the number of pixels covered by a canvas unit horizontally and vertically when using the specified canvas transform
+    ///
+    pub(crate) fn pixels_per_canvas_unit(&self, transform: &canvas::Transform2D) -> (f64, f64) {
+        // Render coordinates run from -1 to 1 vertically
+        let pixels_per_unit = self.height_pixels / 2.0;
+
+        let (x0, y0) = transform.transform_point(0.0, 0.0);
+        let (x1, y1) = transform.transform_point(1.0, 0.0);
+        let (x2, y2) = transform.transform_point(0.0, 1.0);
+
+        (
+            ((x1 - x0) as f64).hypot((y1 - y0) as f64) * pixels_per_unit,
+            ((x2 - x0) as f64).hypot((y2 - y0) as f64) * pixels_per_unit,
+        )
+    }
+
+    ///
+    /// Updates the pixels of an RGBA texture
+    ///
+    fn update_rgba_texture(
+        &mut self,
+        key: (canvas::NamespaceId, canvas::TextureId),
+        update: impl FnOnce(&mut RgbaTexture),
+    ) {
+        if let Some(texture) = self.textures.get_mut(&key) {
+            match Arc::make_mut(texture) {
+                Texture::Rgba(rgba) => update(Arc::make_mut(rgba)),
+            }
+        }
+    }
 }
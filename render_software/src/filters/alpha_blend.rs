@@ -0,0 +1,46 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::pixel_filter::*;
+
+use crate::pixel::*;
+
+///
+/// Filter that makes an image more transparent
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AlphaBlendFilter {
+    /// The alpha value to multiply the pixels by: 1.0 leaves the image as it is, and 0.0 makes it fully transparent
+    alpha: f64,
+}
+
+impl AlphaBlendFilter {
+    ///
+    /// Creates a filter that multiplies the image by an alpha value
+    ///
+    pub fn with_alpha(alpha: f64) -> Self {
+        AlphaBlendFilter {
+            alpha: alpha.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl<TPixel, const N: usize> PixelFilter<TPixel, N> for AlphaBlendFilter
+where
+    TPixel: Send + Sync + Pixel<N>,
+{
+    fn filter(&self, width: usize, height: usize, pixels: &[TPixel]) -> Vec<TPixel> {
+        let alpha = self.alpha;
+
+        generate_rows(width, height, |y, row| {
+            let input = &pixels[(y * width)..((y + 1) * width)];
+
+            for (output, input) in row.iter_mut().zip(input.iter()) {
+                *output = input.multiply_alpha(alpha);
+            }
+        })
+    }
+}
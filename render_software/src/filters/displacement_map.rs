@@ -0,0 +1,106 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::pixel_filter::*;
+
+use crate::pixel::*;
+
+use std::sync::*;
+
+///
+/// Filter that uses the red and green channels of a texture to displace the pixels of an image
+///
+/// The displacement texture is stretched to cover the whole image. A channel value of 0.5 leaves the pixel where it is, and
+/// values of 0.0 and 1.0 move it by the negative and positive radius respectively.
+///
+#[derive(Clone)]
+pub struct DisplacementMapFilter {
+    /// The texture that describes how far to move each pixel
+    displacement: Arc<RgbaTexture>,
+
+    /// The maximum horizontal displacement, in pixels
+    x_radius: f64,
+
+    /// The maximum vertical displacement, in pixels
+    y_radius: f64,
+}
+
+impl DisplacementMapFilter {
+    ///
+    /// Creates a new displacement map filter, with the maximum displacements in pixels
+    ///
+    pub fn with_displacement(displacement: Arc<RgbaTexture>, x_radius: f64, y_radius: f64) -> Self {
+        DisplacementMapFilter {
+            displacement,
+            x_radius,
+            y_radius,
+        }
+    }
+}
+
+///
+/// Reads a pixel from an image using bilinear interpolation, treating pixels outside the image as transparent
+///
+#[inline]
+fn read_bilinear<TPixel, const N: usize>(
+    width: usize,
+    height: usize,
+    pixels: &[TPixel],
+    x: f64,
+    y: f64,
+) -> TPixel
+where
+    TPixel: Pixel<N>,
+{
+    // Pixel centres are at 0.5 offsets
+    let x = x - 0.5;
+    let y = y - 0.5;
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let fx = x - x0;
+    let fy = y - y0;
+    let (x0, y0) = (x0 as i64, y0 as i64);
+
+    let read = |x: i64, y: i64, weight: f64| {
+        if x < 0 || y < 0 || x >= (width as i64) || y >= (height as i64) || weight <= 0.0 {
+            transparent_pixel()
+        } else {
+            pixels[(x as usize) + (y as usize) * width].multiply_alpha(weight)
+        }
+    };
+
+    read(x0, y0, (1.0 - fx) * (1.0 - fy))
+        + read(x0 + 1, y0, fx * (1.0 - fy))
+        + read(x0, y0 + 1, (1.0 - fx) * fy)
+        + read(x0 + 1, y0 + 1, fx * fy)
+}
+
+impl<TPixel, const N: usize> PixelFilter<TPixel, N> for DisplacementMapFilter
+where
+    TPixel: Send + Sync + Pixel<N>,
+{
+    fn filter(&self, width: usize, height: usize, pixels: &[TPixel]) -> Vec<TPixel> {
+        let displacement = &*self.displacement;
+        let scale_x = (displacement.width() as f64) / (width as f64);
+        let scale_y = (displacement.height() as f64) / (height as f64);
+
+        generate_rows(width, height, |y, row| {
+            let pos_y = (y as f64) + 0.5;
+
+            for (x, output) in row.iter_mut().enumerate() {
+                let pos_x = (x as f64) + 0.5;
+
+                // Read the displacement for this pixel
+                let [r, g, _, _] =
+                    displacement.read_pixel((pos_x * scale_x) as i64, (pos_y * scale_y) as i64);
+                let dx = ((r as f64) / 255.0 - 0.5) * 2.0 * self.x_radius;
+                let dy = ((g as f64) / 255.0 - 0.5) * 2.0 * self.y_radius;
+
+                *output = read_bilinear(width, height, pixels, pos_x + dx, pos_y + dy);
+            }
+        })
+    }
+}
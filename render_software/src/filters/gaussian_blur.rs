@@ -0,0 +1,135 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::pixel_filter::*;
+
+use crate::pixel::*;
+
+///
+/// Filter that applies a gaussian blur to an image
+///
+/// Pixels outside of the image are treated as transparent, so the blur will fade out at the edges of the image
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct GaussianBlurFilter {
+    /// The radius of the blur in the horizontal direction, in pixels
+    x_radius: f64,
+
+    /// The radius of the blur in the vertical direction, in pixels
+    y_radius: f64,
+}
+
+impl GaussianBlurFilter {
+    ///
+    /// Creates a gaussian blur filter with a radius in pixels
+    ///
+    /// As for the hardware renderers, the sigma for the blur is a quarter of the radius, so the radius covers
+    /// 4 standard deviations. Radii of a pixel or less (or radii that aren't finite) have no effect.
+    ///
+    pub fn with_radius(radius: f64) -> Self {
+        Self::with_radius_xy(radius, radius)
+    }
+
+    ///
+    /// Creates a gaussian blur filter with different horizontal and vertical radii, in pixels
+    ///
+    pub fn with_radius_xy(x_radius: f64, y_radius: f64) -> Self {
+        GaussianBlurFilter { x_radius, y_radius }
+    }
+
+    ///
+    /// Calculates the weights for a 1D gaussian kernel with the specified radius
+    ///
+    /// The kernel covers at most `max_half_size` pixels either side of the centre: pixels further away than the size of
+    /// the image are always outside of it, so there's no need to generate weights for them.
+    ///
+    fn kernel(radius: f64, max_half_size: usize) -> Vec<f64> {
+        if !radius.is_finite() || radius <= 1.0 {
+            return vec![1.0];
+        }
+
+        let sigma = radius * 0.25;
+        let half_size = radius.ceil().min(max_half_size as f64) as i64;
+
+        let weights = (-half_size..=half_size)
+            .map(|offset| {
+                let offset = offset as f64;
+                (-(offset * offset) / (2.0 * sigma * sigma)).exp()
+            })
+            .collect::<Vec<_>>();
+
+        // Normalise the weights so the blur doesn't change the overall brightness of the image
+        let total = weights.iter().sum::<f64>();
+        weights.into_iter().map(|weight| weight / total).collect()
+    }
+
+    ///
+    /// Applies a 1D kernel to an image, either horizontally or vertically
+    ///
+    fn apply_kernel<TPixel, const N: usize>(
+        kernel: &[f64],
+        width: usize,
+        height: usize,
+        pixels: &[TPixel],
+        horizontal: bool,
+    ) -> Vec<TPixel>
+    where
+        TPixel: Send + Sync + Pixel<N>,
+    {
+        let half_size = (kernel.len() / 2) as i64;
+
+        generate_rows(width, height, |y, row| {
+            for (x, output) in row.iter_mut().enumerate() {
+                let mut total = transparent_pixel();
+
+                for (weight, offset) in kernel.iter().zip(-half_size..=half_size) {
+                    // Positions outside of the image are transparent, so they don't contribute to the total
+                    let (read_x, read_y) = if horizontal {
+                        ((x as i64) + offset, y as i64)
+                    } else {
+                        (x as i64, (y as i64) + offset)
+                    };
+
+                    if read_x < 0
+                        || read_y < 0
+                        || read_x >= (width as i64)
+                        || read_y >= (height as i64)
+                    {
+                        continue;
+                    }
+
+                    let pixel = pixels[(read_x as usize) + (read_y as usize) * width];
+                    total = total + pixel.multiply_alpha(*weight);
+                }
+
+                *output = total;
+            }
+        })
+    }
+}
+
+impl<TPixel, const N: usize> PixelFilter<TPixel, N> for GaussianBlurFilter
+where
+    TPixel: Send + Sync + Pixel<N>,
+{
+    fn filter(&self, width: usize, height: usize, pixels: &[TPixel]) -> Vec<TPixel> {
+        let x_kernel = Self::kernel(self.x_radius, width);
+        let y_kernel = Self::kernel(self.y_radius, height);
+
+        // Gaussian blurs are separable, so we can blur horizontally and then vertically
+        let pixels = if x_kernel.len() > 1 {
+            Self::apply_kernel(&x_kernel, width, height, pixels, true)
+        } else {
+            pixels[0..(width * height)].to_vec()
+        };
+
+        if y_kernel.len() > 1 {
+            Self::apply_kernel(&y_kernel, width, height, &pixels, false)
+        } else {
+            pixels
+        }
+    }
+}
@@ -0,0 +1,54 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::pixel_filter::*;
+
+use crate::pixel::*;
+
+use std::sync::*;
+
+///
+/// Filter that uses the alpha channel of a texture as a mask for an image
+///
+/// The mask is stretched to cover the whole image.
+///
+#[derive(Clone)]
+pub struct MaskFilter {
+    /// The texture to use as the mask
+    mask: Arc<RgbaTexture>,
+}
+
+impl MaskFilter {
+    ///
+    /// Creates a filter that masks an image using the alpha channel of a texture
+    ///
+    pub fn with_mask(mask: Arc<RgbaTexture>) -> Self {
+        MaskFilter { mask }
+    }
+}
+
+impl<TPixel, const N: usize> PixelFilter<TPixel, N> for MaskFilter
+where
+    TPixel: Send + Sync + Pixel<N>,
+{
+    fn filter(&self, width: usize, height: usize, pixels: &[TPixel]) -> Vec<TPixel> {
+        let mask = &*self.mask;
+        let scale_x = (mask.width() as f64) / (width as f64);
+        let scale_y = (mask.height() as f64) / (height as f64);
+
+        generate_rows(width, height, |y, row| {
+            let input = &pixels[(y * width)..((y + 1) * width)];
+            let mask_y = ((y as f64) + 0.5) * scale_y;
+
+            for (x, (output, input)) in row.iter_mut().zip(input.iter()).enumerate() {
+                let mask_x = ((x as f64) + 0.5) * scale_x;
+                let [_, _, _, mask_alpha] = mask.read_pixel(mask_x as i64, mask_y as i64);
+
+                *output = input.multiply_alpha((mask_alpha as f64) / 255.0);
+            }
+        })
+    }
+}
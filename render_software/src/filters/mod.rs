@@ -0,0 +1,17 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+pub use alpha_blend::*;
+pub use displacement_map::*;
+pub use gaussian_blur::*;
+pub use mask::*;
+pub use pixel_filter::*;
+
+mod alpha_blend;
+mod displacement_map;
+mod gaussian_blur;
+mod mask;
+mod pixel_filter;
@@ -0,0 +1,105 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::pixel::*;
+
+use flo_canvas as canvas;
+
+///
+/// A pixel filter generates a new image from an existing image made up of premultiplied linear pixels
+///
+/// These are used to implement the canvas texture filters (see `flo_canvas::TextureFilter`)
+///
+pub trait PixelFilter<TPixel, const N: usize>: Send + Sync
+where
+    TPixel: Pixel<N>,
+{
+    ///
+    /// Applies this filter to an image of the specified size, returning the filtered image
+    ///
+    /// The pixels are stored a row at a time, and there must be at least `width * height` pixels in the input
+    ///
+    fn filter(&self, width: usize, height: usize, pixels: &[TPixel]) -> Vec<TPixel>;
+}
+
+///
+/// Applies a pixel filter to an RGBA texture, returning the result
+///
+pub fn filter_rgba_texture<TPixel, const N: usize>(
+    texture: &RgbaTexture,
+    filter: &dyn PixelFilter<TPixel, N>,
+) -> RgbaTexture
+where
+    TPixel: Pixel<N>,
+{
+    let width = texture.width();
+    let height = texture.height();
+    let pixels = texture.to_pixels::<TPixel, N>();
+    let pixels = filter.filter(width, height, &pixels);
+
+    RgbaTexture::from_pixels(width, height, &pixels)
+}
+
+///
+/// Returns a fully transparent pixel
+///
+#[inline]
+pub(crate) fn transparent_pixel<TPixel, const N: usize>() -> TPixel
+where
+    TPixel: Pixel<N>,
+{
+    TPixel::from_color(canvas::Color::Rgba(0.0, 0.0, 0.0, 0.0), 2.2)
+}
+
+///
+/// Generates an image a row at a time by calling `fill_row` with the y position and the pixels for each row
+///
+#[cfg(feature = "multithreading")]
+pub(crate) fn generate_rows<TPixel, const N: usize>(
+    width: usize,
+    height: usize,
+    fill_row: impl Send + Sync + Fn(usize, &mut [TPixel]),
+) -> Vec<TPixel>
+where
+    TPixel: Send + Sync + Pixel<N>,
+{
+    use rayon::prelude::*;
+
+    let mut pixels = vec![transparent_pixel(); width * height];
+
+    if width > 0 {
+        pixels
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(y, row)| fill_row(y, row));
+    }
+
+    pixels
+}
+
+///
+/// Generates an image a row at a time by calling `fill_row` with the y position and the pixels for each row
+///
+#[cfg(not(feature = "multithreading"))]
+pub(crate) fn generate_rows<TPixel, const N: usize>(
+    width: usize,
+    height: usize,
+    fill_row: impl Send + Sync + Fn(usize, &mut [TPixel]),
+) -> Vec<TPixel>
+where
+    TPixel: Send + Sync + Pixel<N>,
+{
+    let mut pixels = vec![transparent_pixel(); width * height];
+
+    if width > 0 {
+        pixels
+            .chunks_mut(width)
+            .enumerate()
+            .for_each(|(y, row)| fill_row(y, row));
+    }
+
+    pixels
+}
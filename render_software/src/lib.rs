@@ -22,6 +22,9 @@ pub mod pixel;
 /// Well-known pixel programs
 pub mod pixel_programs;
 
+/// Filters that generate a new image from an existing one, such as the blurs used by the canvas texture filters
+pub mod filters;
+
 /// Renderers convert from data represented by a series of instructions to a simpler form
 pub mod render;
 
@@ -4,6 +4,8 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use super::blend_function::*;
+
 use std::ops::*;
 
 ///
@@ -36,6 +38,22 @@ pub enum AlphaOperation {
     SourceAtop,
     DestAtop,
     Xor,
+    Multiply,
+    Screen,
+    Darken,
+    Lighten,
+    Overlay,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+    Plus,
 }
 
 ///
@@ -64,11 +82,18 @@ where
         dest_alpha: AlphaFunction,
     ) -> Self;
 
+    /// Blends the colours of this pixel with the destination using a blend function, then composites the result (for premultiplied alphas)
+    fn blend_with_function(self, dest: Self, blend_fn: BlendFunction) -> Self;
+
     /// Performs the specified alpha blending operation
     #[inline]
     fn alpha_blend(self, dest: Self, operation: AlphaOperation) -> Self {
-        let (src, dst) = operation.functions();
-        self.alpha_blend_with_function(dest, src, dst)
+        if let Some(blend_fn) = operation.blend_function() {
+            self.blend_with_function(dest, blend_fn)
+        } else {
+            let (src, dst) = operation.functions();
+            self.alpha_blend_with_function(dest, src, dst)
+        }
     }
 
     /// Returns the alpha component of this item
@@ -115,6 +140,8 @@ impl AlphaOperation {
     ///
     /// Returns the alpha functions to use for the source and target for this alpha operation
     ///
+    /// For the operations that use a blend function, these are the functions that are applied to the alpha component
+    ///
     #[inline]
     pub const fn functions(&self) -> (AlphaFunction, AlphaFunction) {
         match self {
@@ -141,6 +168,50 @@ impl AlphaOperation {
                 AlphaFunction::OneMinusDestAlpha,
                 AlphaFunction::OneMinusSourceAlpha,
             ),
+            AlphaOperation::Plus => (AlphaFunction::One, AlphaFunction::One),
+            AlphaOperation::Multiply
+            | AlphaOperation::Screen
+            | AlphaOperation::Darken
+            | AlphaOperation::Lighten
+            | AlphaOperation::Overlay
+            | AlphaOperation::ColorDodge
+            | AlphaOperation::ColorBurn
+            | AlphaOperation::HardLight
+            | AlphaOperation::SoftLight
+            | AlphaOperation::Difference
+            | AlphaOperation::Exclusion
+            | AlphaOperation::Hue
+            | AlphaOperation::Saturation
+            | AlphaOperation::Color
+            | AlphaOperation::Luminosity => {
+                (AlphaFunction::One, AlphaFunction::OneMinusSourceAlpha)
+            }
+        }
+    }
+
+    ///
+    /// Returns the blend function used by this operation, if it combines the source and destination colours
+    ///
+    #[inline]
+    pub const fn blend_function(&self) -> Option<BlendFunction> {
+        match self {
+            AlphaOperation::Multiply => Some(BlendFunction::Multiply),
+            AlphaOperation::Screen => Some(BlendFunction::Screen),
+            AlphaOperation::Darken => Some(BlendFunction::Darken),
+            AlphaOperation::Lighten => Some(BlendFunction::Lighten),
+            AlphaOperation::Overlay => Some(BlendFunction::Overlay),
+            AlphaOperation::ColorDodge => Some(BlendFunction::ColorDodge),
+            AlphaOperation::ColorBurn => Some(BlendFunction::ColorBurn),
+            AlphaOperation::HardLight => Some(BlendFunction::HardLight),
+            AlphaOperation::SoftLight => Some(BlendFunction::SoftLight),
+            AlphaOperation::Difference => Some(BlendFunction::Difference),
+            AlphaOperation::Exclusion => Some(BlendFunction::Exclusion),
+            AlphaOperation::Hue => Some(BlendFunction::Hue),
+            AlphaOperation::Saturation => Some(BlendFunction::Saturation),
+            AlphaOperation::Color => Some(BlendFunction::Color),
+            AlphaOperation::Luminosity => Some(BlendFunction::Luminosity),
+            AlphaOperation::Plus => Some(BlendFunction::Plus),
+            _ => None,
         }
     }
 
@@ -153,6 +224,7 @@ impl AlphaOperation {
         TPixel: AlphaBlend + Add<TPixel, Output = TPixel> + Mul<TPixel::Component, Output = TPixel>,
     {
         let (src_fn, dst_fn) = self.functions();
+        let blend_fn = self.blend_function();
 
         let src_fn = match src_fn {
             AlphaFunction::Zero => |pixel, _, _| pixel * TPixel::Component::zero(),
@@ -181,6 +253,10 @@ impl AlphaOperation {
         };
 
         move |pix1, pix2| {
+            if let Some(blend_fn) = blend_fn {
+                return pix1.blend_with_function(pix2, blend_fn);
+            }
+
             let src_alpha = pix1.alpha_component();
             let dst_alpha = pix2.alpha_component();
 
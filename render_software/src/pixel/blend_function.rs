@@ -0,0 +1,271 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+///
+/// A blend function, which combines the source and destination colours before compositing them
+///
+/// These are the W3C compositing blend modes. Apart from `Plus`, the blended colours are composited using
+/// the 'source over' alpha operation.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BlendFunction {
+    /// Multiplies the source and destination colours
+    Multiply,
+
+    /// Multiplies the complements of the source and destination colours
+    Screen,
+
+    /// Chooses the darker of the source and destination colours
+    Darken,
+
+    /// Chooses the lighter of the source and destination colours
+    Lighten,
+
+    /// Multiplies or screens the colours depending on the destination colour
+    Overlay,
+
+    /// Brightens the destination colour to reflect the source colour
+    ColorDodge,
+
+    /// Darkens the destination colour to reflect the source colour
+    ColorBurn,
+
+    /// Multiplies or screens the colours depending on the source colour
+    HardLight,
+
+    /// Darkens or lightens the colours depending on the source colour
+    SoftLight,
+
+    /// Subtracts the darker of the two colours from the lighter colour
+    Difference,
+
+    /// Similar to difference, but with lower contrast
+    Exclusion,
+
+    /// Uses the hue of the source colour with the saturation and luminosity of the destination colour
+    Hue,
+
+    /// Uses the saturation of the source colour with the hue and luminosity of the destination colour
+    Saturation,
+
+    /// Uses the hue and saturation of the source colour with the luminosity of the destination colour
+    Color,
+
+    /// Uses the luminosity of the source colour with the hue and saturation of the destination colour
+    Luminosity,
+
+    /// Adds the source and destination colours together, clamping the result
+    Plus,
+}
+
+impl BlendFunction {
+    ///
+    /// True if this blend function combines each colour component independently of the others
+    ///
+    #[inline]
+    pub const fn is_separable(&self) -> bool {
+        !matches!(
+            self,
+            BlendFunction::Hue
+                | BlendFunction::Saturation
+                | BlendFunction::Color
+                | BlendFunction::Luminosity
+        )
+    }
+
+    ///
+    /// Blends two pre-multiplied colours using this function, with components in the range 0-1
+    ///
+    /// This is the general form of the blend functions, which pixel types can use for the blend functions that don't
+    /// have a faster implementation for their format.
+    ///
+    pub fn blend_premultiplied(&self, src: [f32; 4], dst: [f32; 4]) -> [f32; 4] {
+        let [sr, sg, sb, src_alpha] = src;
+        let [dr, dg, db, dst_alpha] = dst;
+
+        if *self == BlendFunction::Plus {
+            return [
+                (sr + dr).min(1.0),
+                (sg + dg).min(1.0),
+                (sb + db).min(1.0),
+                (src_alpha + dst_alpha).min(1.0),
+            ];
+        }
+
+        // The blend functions are defined on colours without pre-multiplication
+        let unpremultiply = |component: f32, alpha: f32| {
+            if alpha > 0.0 {
+                (component / alpha).min(1.0)
+            } else {
+                0.0
+            }
+        };
+        let cs = [
+            unpremultiply(sr, src_alpha),
+            unpremultiply(sg, src_alpha),
+            unpremultiply(sb, src_alpha),
+        ];
+        let cb = [
+            unpremultiply(dr, dst_alpha),
+            unpremultiply(dg, dst_alpha),
+            unpremultiply(db, dst_alpha),
+        ];
+
+        let blended = if self.is_separable() {
+            [
+                self.blend_component(cs[0], cb[0]),
+                self.blend_component(cs[1], cb[1]),
+                self.blend_component(cs[2], cb[2]),
+            ]
+        } else {
+            match self {
+                BlendFunction::Hue => set_lum(set_sat(cs, sat(cb)), lum(cb)),
+                BlendFunction::Saturation => set_lum(set_sat(cb, sat(cs)), lum(cb)),
+                BlendFunction::Color => set_lum(cs, lum(cb)),
+                BlendFunction::Luminosity => set_lum(cb, lum(cs)),
+                _ => unreachable!(),
+            }
+        };
+
+        // Composite the result using 'source over'
+        let both_alpha = src_alpha * dst_alpha;
+        let composite = |src: f32, dst: f32, blended: f32| {
+            src * (1.0 - dst_alpha) + dst * (1.0 - src_alpha) + both_alpha * blended
+        };
+
+        [
+            composite(sr, dr, blended[0]),
+            composite(sg, dg, blended[1]),
+            composite(sb, db, blended[2]),
+            src_alpha + dst_alpha - both_alpha,
+        ]
+    }
+
+    ///
+    /// Blends a single component of a source and destination colour (which must not be pre-multiplied) using a separable blend function
+    ///
+    #[inline]
+    fn blend_component(&self, cs: f32, cb: f32) -> f32 {
+        match self {
+            BlendFunction::Multiply => cs * cb,
+            BlendFunction::Screen => cs + cb - cs * cb,
+            BlendFunction::Darken => cs.min(cb),
+            BlendFunction::Lighten => cs.max(cb),
+            BlendFunction::Overlay => BlendFunction::HardLight.blend_component(cb, cs),
+
+            BlendFunction::ColorDodge => {
+                if cb <= 0.0 {
+                    0.0
+                } else if cs >= 1.0 {
+                    1.0
+                } else {
+                    (cb / (1.0 - cs)).min(1.0)
+                }
+            }
+
+            BlendFunction::ColorBurn => {
+                if cb >= 1.0 {
+                    1.0
+                } else if cs <= 0.0 {
+                    0.0
+                } else {
+                    1.0 - ((1.0 - cb) / cs).min(1.0)
+                }
+            }
+
+            BlendFunction::HardLight => {
+                if cs <= 0.5 {
+                    cb * 2.0 * cs
+                } else {
+                    BlendFunction::Screen.blend_component(2.0 * cs - 1.0, cb)
+                }
+            }
+
+            BlendFunction::SoftLight => {
+                if cs <= 0.5 {
+                    cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb)
+                } else {
+                    let d = if cb <= 0.25 {
+                        ((16.0 * cb - 12.0) * cb + 4.0) * cb
+                    } else {
+                        cb.sqrt()
+                    };
+
+                    cb + (2.0 * cs - 1.0) * (d - cb)
+                }
+            }
+
+            BlendFunction::Difference => (cb - cs).abs(),
+            BlendFunction::Exclusion => cb + cs - 2.0 * cb * cs,
+            BlendFunction::Plus => (cs + cb).min(1.0),
+
+            BlendFunction::Hue
+            | BlendFunction::Saturation
+            | BlendFunction::Color
+            | BlendFunction::Luminosity => unreachable!(),
+        }
+    }
+}
+
+///
+/// The luminosity of a colour, as used by the non-separable blend functions
+///
+#[inline]
+fn lum([r, g, b]: [f32; 3]) -> f32 {
+    0.3 * r + 0.59 * g + 0.11 * b
+}
+
+///
+/// Moves a colour back into the 0-1 range while preserving its luminosity
+///
+#[inline]
+fn clip_color(color: [f32; 3]) -> [f32; 3] {
+    let l = lum(color);
+    let n = color[0].min(color[1]).min(color[2]);
+    let x = color[0].max(color[1]).max(color[2]);
+
+    let mut color = color;
+    if n < 0.0 {
+        color = color.map(|c| l + (c - l) * l / (l - n));
+    }
+    if x > 1.0 {
+        color = color.map(|c| l + (c - l) * (1.0 - l) / (x - l));
+    }
+
+    color
+}
+
+///
+/// Adjusts a colour so that it has the specified luminosity
+///
+#[inline]
+fn set_lum(color: [f32; 3], l: f32) -> [f32; 3] {
+    let d = l - lum(color);
+    clip_color(color.map(|c| c + d))
+}
+
+///
+/// The saturation of a colour, as used by the non-separable blend functions
+///
+#[inline]
+fn sat([r, g, b]: [f32; 3]) -> f32 {
+    r.max(g).max(b) - r.min(g).min(b)
+}
+
+///
+/// Adjusts a colour so that it has the specified saturation
+///
+#[inline]
+fn set_sat(color: [f32; 3], s: f32) -> [f32; 3] {
+    let max = color[0].max(color[1]).max(color[2]);
+    let min = color[0].min(color[1]).min(color[2]);
+
+    if max > min {
+        color.map(|c| (c - min) * s / (max - min))
+    } else {
+        [0.0, 0.0, 0.0]
+    }
+}
@@ -12,6 +12,7 @@ use wide::*;
 use flo_canvas as canvas;
 
 use super::alpha_blend_trait::*;
+use super::blend_function::*;
 use super::gamma_lut::*;
 use super::pixel_trait::*;
 use super::to_gamma_colorspace_trait::*;
@@ -148,6 +149,29 @@ impl AlphaBlend for F32LinearPixel {
             + dest_alpha_fn.apply(dest, src_alpha, dst_alpha)
     }
 
+    #[inline]
+    fn blend_with_function(self, dest: Self, blend_fn: BlendFunction) -> Self {
+        let src_alpha = self.0.as_array_ref()[3];
+        let dst_alpha = dest.0.as_array_ref()[3];
+
+        // The blend functions here are adjusted for premultiplied alpha
+        let blended = match blend_fn {
+            BlendFunction::Multiply => self.0 * dest.0,
+            BlendFunction::Screen => return F32LinearPixel(self.0 + dest.0 - self.0 * dest.0),
+            BlendFunction::Darken => (self.0 * dst_alpha).min(dest.0 * src_alpha),
+            BlendFunction::Lighten => (self.0 * dst_alpha).max(dest.0 * src_alpha),
+            BlendFunction::Plus => return F32LinearPixel((self.0 + dest.0).min(f32x4::ONE)),
+
+            _ => {
+                return F32LinearPixel(f32x4::new(
+                    blend_fn.blend_premultiplied(self.0.to_array(), dest.0.to_array()),
+                ))
+            }
+        };
+
+        F32LinearPixel(self.0 * (1.0 - dst_alpha) + dest.0 * (1.0 - src_alpha) + blended)
+    }
+
     #[inline]
     fn alpha_component(&self) -> Self::Component {
         self.0.as_array_ref()[3]
@@ -5,6 +5,7 @@
  */
 
 pub use alpha_blend_trait::*;
+pub use blend_function::*;
 pub use f32_linear::*;
 pub use f32_linear_texture_reader::*;
 pub use pixel_program::*;
@@ -20,6 +21,7 @@ pub use u32_linear_texture_reader::*;
 pub use u8_rgba::*;
 
 mod alpha_blend_trait;
+mod blend_function;
 mod f32_linear;
 mod f32_linear_texture_reader;
 pub(crate) mod gamma_lut;
@@ -4,6 +4,8 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use super::pixel_trait::*;
+
 ///
 /// An 8-bpp, non-premultiplied RGBA texture
 ///
@@ -146,4 +148,50 @@ impl RgbaTexture {
             ]
         }
     }
+
+    ///
+    /// Creates a texture from a buffer of linear pixels (which must contain `width * height` pixels)
+    ///
+    pub fn from_pixels<TPixel, const N: usize>(
+        width: usize,
+        height: usize,
+        pixels: &[TPixel],
+    ) -> RgbaTexture
+    where
+        TPixel: Pixel<N>,
+    {
+        let bytes = pixels[0..(width * height)]
+            .iter()
+            .flat_map(|pixel| {
+                let (r, g, b, a) = pixel.to_color(2.2).to_rgba_components();
+
+                // Fully transparent pixels have no colour once the premultiplication is removed
+                if a > 0.0 {
+                    [r, g, b, a].map(|component| (component.clamp(0.0, 1.0) * 255.0).round() as u8)
+                } else {
+                    [0, 0, 0, 0]
+                }
+            })
+            .collect::<Vec<_>>();
+
+        RgbaTexture::new(width, height, bytes)
+    }
+
+    ///
+    /// Converts this texture to a buffer of linear pixels
+    ///
+    pub fn to_pixels<TPixel, const N: usize>(&self) -> Vec<TPixel>
+    where
+        TPixel: Pixel<N>,
+    {
+        let mut pixels = Vec::with_capacity(self.width() * self.height());
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                pixels.push(TPixel::read_pixel(self, x as f64, y as f64));
+            }
+        }
+
+        pixels
+    }
 }
@@ -12,6 +12,7 @@ use wide::*;
 use flo_canvas as canvas;
 
 use super::alpha_blend_trait::*;
+use super::blend_function::*;
 use super::gamma_lut::*;
 use super::pixel_trait::*;
 use super::to_gamma_colorspace_trait::*;
@@ -156,6 +157,48 @@ impl AlphaBlend for U32LinearPixel {
             + dest_alpha_fn.apply(dest, src_alpha, dst_alpha)
     }
 
+    #[inline]
+    fn blend_with_function(self, dest: Self, blend_fn: BlendFunction) -> Self {
+        let src_alpha = self.0.as_array_ref()[3];
+        let dst_alpha = dest.0.as_array_ref()[3];
+
+        // The blend functions here are adjusted for premultiplied alpha
+        let blended = match blend_fn {
+            BlendFunction::Multiply => (self.0 * dest.0) >> 16,
+            BlendFunction::Screen => {
+                return U32LinearPixel(self.0 + dest.0 - ((self.0 * dest.0) >> 16))
+            }
+            BlendFunction::Darken => {
+                let src: u32x4 = (self.0 * dst_alpha) >> 16;
+                let dst: u32x4 = (dest.0 * src_alpha) >> 16;
+                src.min(dst)
+            }
+            BlendFunction::Lighten => {
+                let src: u32x4 = (self.0 * dst_alpha) >> 16;
+                let dst: u32x4 = (dest.0 * src_alpha) >> 16;
+                src.max(dst)
+            }
+            BlendFunction::Plus => {
+                return U32LinearPixel((self.0 + dest.0).min(u32x4::splat(65535)))
+            }
+
+            _ => {
+                let to_f32 = |pixel: u32x4| pixel.to_array().map(|c| c as f32 / 65535.0);
+                let blended = blend_fn.blend_premultiplied(to_f32(self.0), to_f32(dest.0));
+
+                return U32LinearPixel(u32x4::new(
+                    blended.map(|c| (c * 65535.0).round().clamp(0.0, 65535.0) as u32),
+                ));
+            }
+        };
+
+        U32LinearPixel(
+            ((self.0 * (65535 - dst_alpha)) >> 16)
+                + ((dest.0 * (65535 - src_alpha)) >> 16)
+                + blended,
+        )
+    }
+
     #[inline]
     fn alpha_component(&self) -> Self::Component {
         U32FixedPoint(self.0.as_array_ref()[3])
@@ -22,6 +22,9 @@ where
 
     // The top two rows of the transformation matrix between source coordinates and texture coordinates
     transform: [[f64; 3]; 2],
+
+    /// The alpha value to multiply the texture pixels by
+    alpha: f64,
 }
 
 ///
@@ -60,14 +63,19 @@ where
     TTexture: Send + Sync,
 {
     ///
-    /// Creates texture data from a texture and the transform to use
+    /// Creates texture data from a texture, the transform to use and the alpha value to apply to the texture
     ///
-    pub fn with_texture(texture: Arc<TTexture>, transform: &canvas::Transform2D) -> Self {
+    pub fn with_texture(
+        texture: Arc<TTexture>,
+        transform: &canvas::Transform2D,
+        alpha: f64,
+    ) -> Self {
         let [[a, b, c], [d, e, f], [_, _, _]] = transform.0;
 
         TextureData {
-            texture: texture,
+            texture,
             transform: [[a as f64, b as _, c as _], [d as _, e as _, f as _]],
+            alpha,
         }
     }
 }
@@ -93,6 +101,7 @@ where
         // Read the data
         let texture = &*data.texture;
         let [[a, b, c], [d, e, f]] = data.transform;
+        let alpha = data.alpha;
 
         // Convert the start x position to source pixels
         let x_pos = x_transform.pixel_x_to_source_x(pixel_range.start);
@@ -109,7 +118,14 @@ where
             let tx = a * x_pos + byc;
             let ty = d * x_pos + eyf;
 
-            *pixel = TTextureReader::read_pixel(texture, tx, ty).source_over(*pixel);
+            let texture_pixel = TTextureReader::read_pixel(texture, tx, ty);
+            let texture_pixel = if alpha < 1.0 {
+                texture_pixel.multiply_alpha(alpha)
+            } else {
+                texture_pixel
+            };
+
+            *pixel = texture_pixel.source_over(*pixel);
 
             // Move the x position along
             x_pos += dx;
@@ -0,0 +1,118 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::pixel::*;
+use crate::scanplan::*;
+
+use flo_canvas as canvas;
+
+use std::f64;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::sync::*;
+
+///
+/// Data for a conic gradient pixel program
+///
+pub struct ConicGradientData<TPixel>
+where
+    TPixel: Send + Sync,
+{
+    /// The colours of the gradient, evenly spaced from position 0 to position 1
+    color_table: Arc<Vec<TPixel>>,
+
+    /// The blending operation to use when drawing the gradient
+    operation: AlphaOperation,
+
+    // The top two rows of the transformation matrix between source coordinates and gradient coordinates (the gradient sweeps around the origin, starting at the x axis)
+    transform: [[f64; 3]; 2],
+}
+
+///
+/// Pixel program that fills pixels with a colour from a conic (sweep) gradient
+///
+pub struct ConicGradientProgram<TPixel: Copy + Send + Sync> {
+    pixel: PhantomData<Mutex<TPixel>>,
+}
+
+impl<TPixel: Copy + Send + Sync> Default for ConicGradientProgram<TPixel> {
+    fn default() -> Self {
+        ConicGradientProgram { pixel: PhantomData }
+    }
+}
+
+impl<TPixel> ConicGradientData<TPixel>
+where
+    TPixel: Send + Sync,
+{
+    ///
+    /// Creates conic gradient data from a colour table and the transform that maps source coordinates to gradient coordinates
+    ///
+    /// The colour table must have at least one entry in it
+    ///
+    pub fn with_color_table(
+        color_table: Arc<Vec<TPixel>>,
+        operation: AlphaOperation,
+        transform: &canvas::Transform2D,
+    ) -> Self {
+        debug_assert!(!color_table.is_empty());
+
+        let [[a, b, c], [d, e, f], [_, _, _]] = transform.0;
+
+        ConicGradientData {
+            color_table,
+            operation,
+            transform: [[a as f64, b as _, c as _], [d as _, e as _, f as _]],
+        }
+    }
+}
+
+impl<TPixel> PixelProgram for ConicGradientProgram<TPixel>
+where
+    TPixel: Copy + Send + Sync + AlphaBlend,
+{
+    type Pixel = TPixel;
+    type ProgramData = ConicGradientData<TPixel>;
+
+    #[inline]
+    fn draw_pixels(
+        &self,
+        _data_cache: &PixelProgramRenderCache<Self::Pixel>,
+        target: &mut [Self::Pixel],
+        pixel_range: Range<i32>,
+        x_transform: &ScanlineTransform,
+        y_pos: f64,
+        data: &Self::ProgramData,
+    ) {
+        // Read the data
+        let color_table = &data.color_table;
+        let max_idx = (color_table.len() - 1) as f64;
+        let [[a, b, c], [d, e, f]] = data.transform;
+        let op = data.operation.get_function::<TPixel>();
+
+        // Convert the start x position to source pixels
+        let x_pos = x_transform.pixel_x_to_source_x(pixel_range.start);
+
+        // Partially calculate the transform and get the pixel size
+        let byc = b * y_pos + c;
+        let eyf = e * y_pos + f;
+        let dx = x_transform.pixel_size();
+
+        let mut x_pos = x_pos;
+        for pixel in target[(pixel_range.start as usize)..(pixel_range.end as usize)].iter_mut() {
+            // The gradient position is the angle around the origin, as a fraction of a full turn
+            let gx = a * x_pos + byc;
+            let gy = d * x_pos + eyf;
+            let t = (gy.atan2(gx) / (2.0 * f64::consts::PI)).rem_euclid(1.0);
+            let idx = (t * max_idx).round().max(0.0).min(max_idx);
+
+            *pixel = op(color_table[idx as usize], *pixel);
+
+            // Move the x position along
+            x_pos += dx;
+        }
+    }
+}
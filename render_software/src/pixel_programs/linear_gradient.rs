@@ -0,0 +1,138 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::pixel::*;
+use crate::scanplan::*;
+
+use flo_canvas as canvas;
+
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::sync::*;
+
+///
+/// Data for a linear gradient pixel program
+///
+pub struct LinearGradientData<TPixel>
+where
+    TPixel: Send + Sync,
+{
+    /// The colours of the gradient, evenly spaced from position 0 to position 1
+    color_table: Arc<Vec<TPixel>>,
+
+    /// The blending operation to use when drawing the gradient
+    operation: AlphaOperation,
+
+    // The top two rows of the transformation matrix between source coordinates and gradient coordinates (the gradient runs along the x axis from 0 to 1)
+    transform: [[f64; 3]; 2],
+
+    /// How the gradient is extended beyond its end points
+    spread: canvas::GradientSpread,
+}
+
+///
+/// Maps a position along a gradient to the range 0-1 according to the spread mode of the gradient
+///
+#[inline]
+pub(crate) fn spread_gradient_pos(t: f64, spread: canvas::GradientSpread) -> f64 {
+    match spread {
+        canvas::GradientSpread::Pad => t.clamp(0.0, 1.0),
+        canvas::GradientSpread::Repeat => t - t.floor(),
+        canvas::GradientSpread::Reflect => {
+            let t = t.rem_euclid(2.0);
+            if t > 1.0 {
+                2.0 - t
+            } else {
+                t
+            }
+        }
+    }
+}
+
+///
+/// Pixel program that fills pixels with a colour from a linear gradient
+///
+pub struct LinearGradientProgram<TPixel: Copy + Send + Sync> {
+    pixel: PhantomData<Mutex<TPixel>>,
+}
+
+impl<TPixel: Copy + Send + Sync> Default for LinearGradientProgram<TPixel> {
+    fn default() -> Self {
+        LinearGradientProgram { pixel: PhantomData }
+    }
+}
+
+impl<TPixel> LinearGradientData<TPixel>
+where
+    TPixel: Send + Sync,
+{
+    ///
+    /// Creates linear gradient data from a colour table and the transform that maps source coordinates to gradient coordinates
+    ///
+    /// The colour table must have at least one entry in it
+    ///
+    pub fn with_color_table(
+        color_table: Arc<Vec<TPixel>>,
+        operation: AlphaOperation,
+        transform: &canvas::Transform2D,
+        spread: canvas::GradientSpread,
+    ) -> Self {
+        debug_assert!(!color_table.is_empty());
+
+        let [[a, b, c], [d, e, f], [_, _, _]] = transform.0;
+
+        LinearGradientData {
+            color_table,
+            operation,
+            transform: [[a as f64, b as _, c as _], [d as _, e as _, f as _]],
+            spread,
+        }
+    }
+}
+
+impl<TPixel> PixelProgram for LinearGradientProgram<TPixel>
+where
+    TPixel: Copy + Send + Sync + AlphaBlend,
+{
+    type Pixel = TPixel;
+    type ProgramData = LinearGradientData<TPixel>;
+
+    #[inline]
+    fn draw_pixels(
+        &self,
+        _data_cache: &PixelProgramRenderCache<Self::Pixel>,
+        target: &mut [Self::Pixel],
+        pixel_range: Range<i32>,
+        x_transform: &ScanlineTransform,
+        y_pos: f64,
+        data: &Self::ProgramData,
+    ) {
+        // Read the data
+        let color_table = &data.color_table;
+        let max_idx = (color_table.len() - 1) as f64;
+        let [[a, b, c], _] = data.transform;
+        let op = data.operation.get_function::<TPixel>();
+
+        // Convert the start x position to source pixels
+        let x_pos = x_transform.pixel_x_to_source_x(pixel_range.start);
+
+        // Partially calculate the transform and get the pixel size
+        let byc = b * y_pos + c;
+        let dx = x_transform.pixel_size();
+
+        let mut x_pos = x_pos;
+        for pixel in target[(pixel_range.start as usize)..(pixel_range.end as usize)].iter_mut() {
+            // Calculate the gradient position, and apply the spread mode to find the colour
+            let t = spread_gradient_pos(a * x_pos + byc, data.spread);
+            let idx = (t * max_idx).round();
+
+            *pixel = op(color_table[idx as usize], *pixel);
+
+            // Move the x position along
+            x_pos += dx;
+        }
+    }
+}
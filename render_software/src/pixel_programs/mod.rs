@@ -7,6 +7,9 @@
 mod basic_sprite;
 mod basic_texture;
 mod blend;
+mod conic_gradient;
+mod linear_gradient;
+mod radial_gradient;
 mod solid_color;
 mod source_over;
 mod transformed_sprite;
@@ -14,6 +17,9 @@ mod transformed_sprite;
 pub use basic_sprite::*;
 pub use basic_texture::*;
 pub use blend::*;
+pub use conic_gradient::*;
+pub use linear_gradient::*;
+pub use radial_gradient::*;
 pub use solid_color::*;
 pub use source_over::*;
 pub use transformed_sprite::*;
@@ -0,0 +1,185 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::linear_gradient::*;
+use crate::pixel::*;
+use crate::scanplan::*;
+
+use flo_canvas as canvas;
+
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::sync::*;
+
+///
+/// Data for a radial gradient pixel program
+///
+pub struct RadialGradientData<TPixel>
+where
+    TPixel: Send + Sync,
+{
+    /// The colours of the gradient, evenly spaced from position 0 to position 1
+    color_table: Arc<Vec<TPixel>>,
+
+    /// The blending operation to use when drawing the gradient
+    operation: AlphaOperation,
+
+    // The top two rows of the transformation matrix between source coordinates and gradient coordinates (the end circle of the gradient is the unit circle)
+    transform: [[f64; 3]; 2],
+
+    /// The center of the start circle in gradient coordinates
+    focal_point: (f64, f64),
+
+    /// The radius of the start circle in gradient coordinates
+    focal_radius: f64,
+
+    /// How the gradient is extended beyond its start and end circles
+    spread: canvas::GradientSpread,
+}
+
+///
+/// Pixel program that fills pixels with a colour from a two-circle radial gradient
+///
+pub struct RadialGradientProgram<TPixel: Copy + Send + Sync> {
+    pixel: PhantomData<Mutex<TPixel>>,
+}
+
+impl<TPixel: Copy + Send + Sync> Default for RadialGradientProgram<TPixel> {
+    fn default() -> Self {
+        RadialGradientProgram { pixel: PhantomData }
+    }
+}
+
+impl<TPixel> RadialGradientData<TPixel>
+where
+    TPixel: Send + Sync,
+{
+    ///
+    /// Creates radial gradient data from a colour table, the transform that maps source coordinates to gradient coordinates
+    /// and the start circle of the gradient
+    ///
+    /// In gradient coordinates, the gradient ends at the unit circle. The colour table must have at least one entry in it.
+    ///
+    pub fn with_color_table(
+        color_table: Arc<Vec<TPixel>>,
+        operation: AlphaOperation,
+        transform: &canvas::Transform2D,
+        focal_point: (f32, f32),
+        focal_radius: f32,
+        spread: canvas::GradientSpread,
+    ) -> Self {
+        debug_assert!(!color_table.is_empty());
+
+        let [[a, b, c], [d, e, f], [_, _, _]] = transform.0;
+
+        RadialGradientData {
+            color_table,
+            operation,
+            transform: [[a as f64, b as _, c as _], [d as _, e as _, f as _]],
+            focal_point: (focal_point.0 as _, focal_point.1 as _),
+            focal_radius: focal_radius as _,
+            spread,
+        }
+    }
+
+    ///
+    /// Finds the position along the gradient for a point in gradient coordinates, or None if the point is not covered by the gradient
+    ///
+    #[inline]
+    fn gradient_pos(&self, x: f64, y: f64) -> Option<f64> {
+        // The gradient is made up of the circles interpolated between the focal circle and the unit circle: we want the largest t where the point is on the circle
+        let (fx, fy) = self.focal_point;
+        let r0 = self.focal_radius;
+        let dr = 1.0 - r0;
+        let (cdx, cdy) = (-fx, -fy);
+        let (pdx, pdy) = (x - fx, y - fy);
+
+        let a = cdx * cdx + cdy * cdy - dr * dr;
+        let b = pdx * cdx + pdy * cdy + r0 * dr;
+        let c = pdx * pdx + pdy * pdy - r0 * r0;
+
+        if a.abs() < 1e-9 {
+            // Focal circle touches the edge of the end circle, so there's only one solution
+            if b.abs() < 1e-9 {
+                return None;
+            }
+
+            let t = c / (2.0 * b);
+            if r0 + t * dr >= 0.0 {
+                Some(t)
+            } else {
+                None
+            }
+        } else {
+            let discriminant = b * b - a * c;
+            if discriminant < 0.0 {
+                return None;
+            }
+
+            let sqrt_discriminant = discriminant.sqrt();
+            let t1 = (b + sqrt_discriminant) / a;
+            let t2 = (b - sqrt_discriminant) / a;
+            let (t1, t2) = if t1 > t2 { (t1, t2) } else { (t2, t1) };
+
+            if r0 + t1 * dr >= 0.0 {
+                Some(t1)
+            } else if r0 + t2 * dr >= 0.0 {
+                Some(t2)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+impl<TPixel> PixelProgram for RadialGradientProgram<TPixel>
+where
+    TPixel: Copy + Send + Sync + AlphaBlend,
+{
+    type Pixel = TPixel;
+    type ProgramData = RadialGradientData<TPixel>;
+
+    #[inline]
+    fn draw_pixels(
+        &self,
+        _data_cache: &PixelProgramRenderCache<Self::Pixel>,
+        target: &mut [Self::Pixel],
+        pixel_range: Range<i32>,
+        x_transform: &ScanlineTransform,
+        y_pos: f64,
+        data: &Self::ProgramData,
+    ) {
+        // Read the data
+        let color_table = &data.color_table;
+        let max_idx = (color_table.len() - 1) as f64;
+        let [[a, b, c], [d, e, f]] = data.transform;
+        let op = data.operation.get_function::<TPixel>();
+
+        // Convert the start x position to source pixels
+        let x_pos = x_transform.pixel_x_to_source_x(pixel_range.start);
+
+        // Partially calculate the transform and get the pixel size
+        let byc = b * y_pos + c;
+        let eyf = e * y_pos + f;
+        let dx = x_transform.pixel_size();
+
+        let mut x_pos = x_pos;
+        for pixel in target[(pixel_range.start as usize)..(pixel_range.end as usize)].iter_mut() {
+            // Calculate the gradient position, and apply the spread mode to find the colour
+            let gx = a * x_pos + byc;
+            let gy = d * x_pos + eyf;
+
+            if let Some(t) = data.gradient_pos(gx, gy) {
+                let t = spread_gradient_pos(t, data.spread);
+                let idx = (t * max_idx).round();
+                *pixel = op(color_table[idx as usize], *pixel);
+            }
+
+            // Move the x position along
+            x_pos += dx;
+        }
+    }
+}
@@ -11,6 +11,27 @@ use flo_render_software::scanplan::*;
 
 use flo_render_software::canvas::*;
 
+///
+/// Renders a drawing to a 200x200 RGBA frame, returning the bytes of the frame
+///
+fn render_200x200(drawing: Vec<Draw>) -> Vec<u8> {
+    let mut canvas_drawing = CanvasDrawing::<F32LinearPixel, 4>::empty();
+    canvas_drawing.set_pixel_height(200.0);
+    canvas_drawing.draw(drawing);
+
+    let mut frame = vec![0u8; 200 * 200 * 4];
+    let mut rgba = RgbaFrame::from_bytes(200, 200, 2.2, &mut frame).unwrap();
+
+    let renderer = CanvasDrawingRegionRenderer::new(
+        PixelScanPlanner::default(),
+        ScanlineRenderer::new(canvas_drawing.program_runner(200.0)),
+        200,
+    );
+    rgba.render(renderer, &canvas_drawing);
+
+    frame
+}
+
 #[test]
 pub fn render_simple_circle() {
     // Render a basic circle
@@ -37,3 +58,566 @@ pub fn render_simple_circle() {
     );
     rgba.render(renderer, &canvas_drawing);
 }
+
+#[test]
+pub fn render_dashed_line() {
+    // Draw a horizontal dashed line across the middle of the canvas
+    let mut drawing = Vec::<Draw>::new();
+
+    drawing.clear_canvas(Color::Rgba(0.0, 0.0, 0.0, 0.0));
+    drawing.identity_transform();
+    drawing.new_path();
+    drawing.move_to(-0.8, 0.0);
+    drawing.line_to(0.8, 0.0);
+    drawing.line_width(0.1);
+    drawing.new_dash_pattern();
+    drawing.dash_length(0.2);
+    drawing.dash_length(0.2);
+    drawing.stroke_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.stroke();
+
+    let frame = render_200x200(drawing);
+
+    // Each dash is 20 pixels long, so pixels should alternate between drawn and not drawn every 20 pixels, starting at x=20
+    let red_at = |x: usize| frame[(100 * 200 + x) * 4];
+
+    assert!(red_at(10) == 0, "Before line: {}", red_at(10));
+    assert!(red_at(30) == 255, "First dash: {}", red_at(30));
+    assert!(red_at(50) == 0, "First gap: {}", red_at(50));
+    assert!(red_at(70) == 255, "Second dash: {}", red_at(70));
+    assert!(red_at(90) == 0, "Second gap: {}", red_at(90));
+    assert!(red_at(150) == 255, "Fourth dash: {}", red_at(150));
+    assert!(red_at(190) == 0, "After line: {}", red_at(190));
+}
+
+#[test]
+pub fn render_linear_gradient() {
+    // Fill the whole canvas with a gradient that runs from red on the left to blue on the right
+    let mut drawing = Vec::<Draw>::new();
+
+    drawing.clear_canvas(Color::Rgba(0.0, 0.0, 0.0, 0.0));
+    drawing.identity_transform();
+    drawing.create_gradient(GradientId(0), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.gradient_stop(GradientId(0), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    drawing.new_path();
+    drawing.rect(-1.0, -1.0, 1.0, 1.0);
+    drawing.fill_gradient(GradientId(0), -0.5, 0.0, 0.5, 0.0);
+    drawing.fill();
+
+    let frame = render_200x200(drawing);
+
+    let pixel_at = |x: usize| {
+        let idx = (100 * 200 + x) * 4;
+        [frame[idx], frame[idx + 1], frame[idx + 2], frame[idx + 3]]
+    };
+
+    // Gradient is padded before the start and after the end
+    assert!(pixel_at(10) == [255, 0, 0, 255], "{:?}", pixel_at(10));
+    assert!(pixel_at(190) == [0, 0, 255, 255], "{:?}", pixel_at(190));
+
+    // Colours are blended in the middle
+    let middle = pixel_at(100);
+    assert!(middle[0] > 100 && middle[0] < 155, "{:?}", middle);
+    assert!(middle[2] > 100 && middle[2] < 155, "{:?}", middle);
+    assert!(middle[3] == 255, "{:?}", middle);
+
+    // Red decreases as we move along the gradient
+    assert!(pixel_at(70)[0] > pixel_at(130)[0]);
+}
+
+#[test]
+pub fn render_gradient_redefined_after_use() {
+    // Fill the top half with a red-to-blue gradient, then redefine it as green-to-blue and fill the bottom half
+    let mut drawing = Vec::<Draw>::new();
+
+    drawing.clear_canvas(Color::Rgba(0.0, 0.0, 0.0, 0.0));
+    drawing.identity_transform();
+    drawing.create_gradient(GradientId(0), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.gradient_stop(GradientId(0), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    drawing.new_path();
+    drawing.rect(-1.0, 0.0, 1.0, 1.0);
+    drawing.fill_gradient(GradientId(0), -0.5, 0.0, 0.5, 0.0);
+    drawing.fill();
+
+    drawing.create_gradient(GradientId(0), Color::Rgba(0.0, 1.0, 0.0, 1.0));
+    drawing.gradient_stop(GradientId(0), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    drawing.new_path();
+    drawing.rect(-1.0, -1.0, 1.0, 0.0);
+    drawing.fill_gradient(GradientId(0), -0.5, 0.0, 0.5, 0.0);
+    drawing.fill();
+
+    let frame = render_200x200(drawing);
+
+    let pixel_at = |x: usize, y: usize| {
+        let idx = (y * 200 + x) * 4;
+        [frame[idx], frame[idx + 1], frame[idx + 2], frame[idx + 3]]
+    };
+
+    // Each half uses the definition of the gradient at the time it was filled
+    let top = pixel_at(10, 50);
+    let bottom = pixel_at(10, 150);
+    assert!(
+        top == [255, 0, 0, 255] || bottom == [255, 0, 0, 255],
+        "{:?} {:?}",
+        top,
+        bottom
+    );
+    assert!(
+        top == [0, 255, 0, 255] || bottom == [0, 255, 0, 255],
+        "{:?} {:?}",
+        top,
+        bottom
+    );
+}
+
+///
+/// Renders a horizontal red-to-blue gradient from x=-0.5 to x=0.5 with a spread mode and returns the middle row of pixels
+///
+fn render_gradient_with_spread(spread: GradientSpread) -> Vec<[u8; 4]> {
+    let mut drawing = Vec::<Draw>::new();
+
+    drawing.clear_canvas(Color::Rgba(0.0, 0.0, 0.0, 0.0));
+    drawing.identity_transform();
+    drawing.create_gradient(GradientId(0), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.gradient_stop(GradientId(0), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    drawing.gradient_spread(GradientId(0), spread);
+    drawing.new_path();
+    drawing.rect(-1.0, -1.0, 1.0, 1.0);
+    drawing.fill_gradient(GradientId(0), -0.5, 0.0, 0.5, 0.0);
+    drawing.fill();
+
+    let frame = render_200x200(drawing);
+
+    (0..200)
+        .map(|x| {
+            let idx = (100 * 200 + x) * 4;
+            [frame[idx], frame[idx + 1], frame[idx + 2], frame[idx + 3]]
+        })
+        .collect()
+}
+
+#[test]
+pub fn render_repeating_gradient() {
+    let pixels = render_gradient_with_spread(GradientSpread::Repeat);
+
+    // Gradient position is -0.4 at x=10, which repeats as 0.6 (more blue than red)
+    assert!(pixels[10][2] > pixels[10][0], "{:?}", pixels[10]);
+
+    // Gradient position is 1.1 at x=160, which starts again from red
+    assert!(pixels[160][0] > 200, "{:?}", pixels[160]);
+    assert!(pixels[160][2] < 100, "{:?}", pixels[160]);
+    assert!(pixels[160][3] == 255, "{:?}", pixels[160]);
+
+    // The middle of the gradient is unchanged
+    assert!(pixels[100] == render_gradient_with_spread(GradientSpread::Pad)[100]);
+}
+
+#[test]
+pub fn render_reflected_gradient() {
+    let pixels = render_gradient_with_spread(GradientSpread::Reflect);
+
+    // Gradient position is -0.4 at x=10, which reflects as 0.4 (more red than blue)
+    assert!(pixels[10][0] > pixels[10][2], "{:?}", pixels[10]);
+
+    // Gradient position is 1.1 at x=160, which reflects back from blue
+    assert!(pixels[160][2] > 200, "{:?}", pixels[160]);
+    assert!(pixels[160][0] < 100, "{:?}", pixels[160]);
+    assert!(pixels[160][3] == 255, "{:?}", pixels[160]);
+
+    // Colours are mirrored around the end of the gradient (allowing for rounding as pixels are sampled at their centers)
+    assert!(
+        (0..4).all(|c| (pixels[140][c] as i32 - pixels[160][c] as i32).abs() <= 2),
+        "{:?} {:?}",
+        pixels[140],
+        pixels[160]
+    );
+}
+
+#[test]
+pub fn render_rotated_gradient_with_fill_transform() {
+    // Horizontal gradient, rotated by 90 degrees so it runs vertically instead
+    let mut drawing = Vec::<Draw>::new();
+
+    drawing.clear_canvas(Color::Rgba(0.0, 0.0, 0.0, 0.0));
+    drawing.identity_transform();
+    drawing.create_gradient(GradientId(0), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.gradient_stop(GradientId(0), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    drawing.new_path();
+    drawing.rect(-1.0, -1.0, 1.0, 1.0);
+    drawing.fill_gradient(GradientId(0), -0.5, 0.0, 0.5, 0.0);
+    drawing.fill_transform(Transform2D::rotate_degrees(90.0));
+    drawing.fill();
+
+    let frame = render_200x200(drawing);
+
+    let pixel_at = |x: usize, y: usize| {
+        let idx = (y * 200 + x) * 4;
+        [frame[idx], frame[idx + 1], frame[idx + 2], frame[idx + 3]]
+    };
+
+    // The middle row should be the same colour all the way across
+    let (left, right) = (pixel_at(10, 100), pixel_at(190, 100));
+    for component in 0..4 {
+        assert!(
+            (left[component] as i32 - right[component] as i32).abs() <= 2,
+            "{:?} {:?}",
+            left,
+            right
+        );
+    }
+
+    // The end of the gradient is rotated from +x to +y, which is at the top of the frame
+    assert!(
+        pixel_at(100, 10) == [0, 0, 255, 255],
+        "{:?}",
+        pixel_at(100, 10)
+    );
+    assert!(
+        pixel_at(100, 190) == [255, 0, 0, 255],
+        "{:?}",
+        pixel_at(100, 190)
+    );
+}
+
+#[test]
+pub fn render_multiply_blend_modes() {
+    // Grey background, with a red square multiplied on the left using a blend mode and a red square multiplied on the right using a layer blend
+    let mut drawing = Vec::<Draw>::new();
+
+    drawing.clear_canvas(Color::Rgba(0.5, 0.5, 0.5, 1.0));
+    drawing.identity_transform();
+
+    drawing.layer(LayerId(0));
+    drawing.blend_mode(BlendMode::Multiply);
+    drawing.new_path();
+    drawing.rect(-1.0, -1.0, 0.0, 1.0);
+    drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.fill();
+
+    drawing.layer(LayerId(1));
+    drawing.layer_blend(LayerId(1), BlendMode::Multiply);
+    drawing.blend_mode(BlendMode::SourceOver);
+    drawing.new_path();
+    drawing.rect(0.0, -1.0, 1.0, 1.0);
+    drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.fill();
+
+    let frame = render_200x200(drawing);
+
+    let pixel_at = |x: usize, y: usize| {
+        let idx = (y * 200 + x) * 4;
+        [frame[idx], frame[idx + 1], frame[idx + 2], frame[idx + 3]]
+    };
+
+    // Multiplying grey by red should produce a darker red in both cases
+    for (x, y) in [(50, 100), (150, 100)] {
+        let [r, g, b, a] = pixel_at(x, y);
+
+        assert!((r as i32 - 128).abs() <= 2, "{:?}", [r, g, b, a]);
+        assert!(g == 0, "{:?}", [r, g, b, a]);
+        assert!(b == 0, "{:?}", [r, g, b, a]);
+        assert!(a == 255, "{:?}", [r, g, b, a]);
+    }
+}
+
+#[test]
+pub fn render_difference_layer_blend() {
+    // White background with a red square drawn over it using a 'difference' layer blend, which should produce cyan
+    let mut drawing = Vec::<Draw>::new();
+
+    drawing.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+    drawing.identity_transform();
+
+    drawing.layer(LayerId(0));
+    drawing.layer_blend(LayerId(0), BlendMode::Difference);
+    drawing.new_path();
+    drawing.rect(-1.0, -1.0, 0.0, 1.0);
+    drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.fill();
+
+    let frame = render_200x200(drawing);
+
+    let pixel_at = |x: usize, y: usize| {
+        let idx = (y * 200 + x) * 4;
+        [frame[idx], frame[idx + 1], frame[idx + 2], frame[idx + 3]]
+    };
+
+    assert!(
+        pixel_at(50, 100) == [0, 255, 255, 255],
+        "{:?}",
+        pixel_at(50, 100)
+    );
+    assert!(
+        pixel_at(150, 100) == [255, 255, 255, 255],
+        "{:?}",
+        pixel_at(150, 100)
+    );
+}
+
+///
+/// Draws a blue rectangle on the left and a red rectangle on the right using the specified blend mode, returning the
+/// pixels where only the blue rectangle is, where both rectangles overlap and where only the red rectangle is
+///
+fn render_porter_duff(blend_mode: BlendMode) -> [[u8; 4]; 3] {
+    let mut drawing = Vec::<Draw>::new();
+
+    drawing.clear_canvas(Color::Rgba(0.0, 0.0, 0.0, 0.0));
+    drawing.identity_transform();
+
+    drawing.layer(LayerId(0));
+    drawing.new_path();
+    drawing.rect(-1.0, -1.0, 0.5, 1.0);
+    drawing.fill_color(Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    drawing.fill();
+
+    drawing.blend_mode(blend_mode);
+    drawing.new_path();
+    drawing.rect(-0.5, -1.0, 1.0, 1.0);
+    drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.fill();
+
+    let frame = render_200x200(drawing);
+
+    let pixel_at = |x: usize, y: usize| {
+        let idx = (y * 200 + x) * 4;
+        [frame[idx], frame[idx + 1], frame[idx + 2], frame[idx + 3]]
+    };
+
+    [pixel_at(25, 100), pixel_at(100, 100), pixel_at(175, 100)]
+}
+
+#[test]
+pub fn render_porter_duff_blend_modes() {
+    let blue = [0, 0, 255, 255];
+    let red = [255, 0, 0, 255];
+    let clear = [0, 0, 0, 0];
+
+    assert!(
+        render_porter_duff(BlendMode::Xor) == [blue, clear, red],
+        "{:?}",
+        render_porter_duff(BlendMode::Xor)
+    );
+    assert!(
+        render_porter_duff(BlendMode::Clear) == [blue, clear, clear],
+        "{:?}",
+        render_porter_duff(BlendMode::Clear)
+    );
+    assert!(
+        render_porter_duff(BlendMode::Copy) == [blue, red, red],
+        "{:?}",
+        render_porter_duff(BlendMode::Copy)
+    );
+    assert!(
+        render_porter_duff(BlendMode::Destination) == [blue, blue, clear],
+        "{:?}",
+        render_porter_duff(BlendMode::Destination)
+    );
+}
+
+#[test]
+pub fn render_radial_gradient() {
+    // Radial gradient from red in the middle of the canvas to blue at the edge of a circle of radius 0.5
+    let mut drawing = Vec::<Draw>::new();
+
+    drawing.clear_canvas(Color::Rgba(0.0, 0.0, 0.0, 0.0));
+    drawing.identity_transform();
+    drawing.create_gradient(GradientId(0), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.gradient_stop(GradientId(0), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    drawing.new_path();
+    drawing.rect(-1.0, -1.0, 1.0, 1.0);
+    drawing.fill_radial_gradient(GradientId(0), (0.0, 0.0, 0.0), (0.0, 0.0, 0.5));
+    drawing.fill();
+
+    let frame = render_200x200(drawing);
+
+    let pixel_at = |x: usize, y: usize| {
+        let idx = (y * 200 + x) * 4;
+        [frame[idx], frame[idx + 1], frame[idx + 2], frame[idx + 3]]
+    };
+
+    // Red in the center, padded to blue outside of the end circle
+    let center = pixel_at(100, 100);
+    assert!(center[0] > 250 && center[2] < 5, "{:?}", center);
+    assert!(
+        pixel_at(10, 100) == [0, 0, 255, 255],
+        "{:?}",
+        pixel_at(10, 100)
+    );
+    assert!(
+        pixel_at(100, 190) == [0, 0, 255, 255],
+        "{:?}",
+        pixel_at(100, 190)
+    );
+
+    // Points at the same distance from the center have the same colour
+    let (left, top) = (pixel_at(75, 100), pixel_at(100, 75));
+    for component in 0..4 {
+        assert!(
+            (left[component] as i32 - top[component] as i32).abs() <= 2,
+            "{:?} {:?}",
+            left,
+            top
+        );
+    }
+    assert!(left[0] > 100 && left[2] > 100, "{:?}", left);
+}
+
+#[test]
+pub fn render_conic_gradient() {
+    // Conic gradient around the center of the canvas, starting red on the positive x axis and sweeping round to blue
+    let mut drawing = Vec::<Draw>::new();
+
+    drawing.clear_canvas(Color::Rgba(0.0, 0.0, 0.0, 0.0));
+    drawing.identity_transform();
+    drawing.create_gradient(GradientId(0), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.gradient_stop(GradientId(0), 1.0, Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    drawing.new_path();
+    drawing.rect(-1.0, -1.0, 1.0, 1.0);
+    drawing.fill_conic_gradient(GradientId(0), 0.0, 0.0, 0.0);
+    drawing.fill();
+
+    let frame = render_200x200(drawing);
+
+    let pixel_at = |x: usize, y: usize| {
+        let idx = (y * 200 + x) * 4;
+        [frame[idx], frame[idx + 1], frame[idx + 2], frame[idx + 3]]
+    };
+
+    // Just above the x axis is the start of the gradient, and just below is the end (the y axis points upwards)
+    let above = pixel_at(190, 98);
+    let below = pixel_at(190, 102);
+    assert!(above[0] > 240 && above[2] < 15, "{:?}", above);
+    assert!(below[2] > 240 && below[0] < 15, "{:?}", below);
+
+    // Halfway round, the colours are blended
+    let opposite = pixel_at(10, 100);
+    assert!(opposite[0] > 100 && opposite[0] < 155, "{:?}", opposite);
+    assert!(opposite[2] > 100 && opposite[2] < 155, "{:?}", opposite);
+
+    // A quarter of the way round is more red than blue
+    let top = pixel_at(100, 10);
+    assert!(top[0] > top[2], "{:?}", top);
+}
+
+fn draw_quadrant_sprite(drawing: &mut Vec<Draw>, sprite_id: SpriteId) {
+    // A sprite with a red square in one quadrant and a blue square in the opposite one
+    drawing.sprite(sprite_id);
+    drawing.clear_sprite();
+    drawing.new_path();
+    drawing.rect(-1.0, -1.0, 0.0, 0.0);
+    drawing.fill_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
+    drawing.fill();
+    drawing.new_path();
+    drawing.rect(0.0, 0.0, 1.0, 1.0);
+    drawing.fill_color(Color::Rgba(0.0, 0.0, 1.0, 1.0));
+    drawing.fill();
+    drawing.layer(LayerId(0));
+}
+
+#[test]
+pub fn render_texture_from_sprite() {
+    // Render the sprite directly
+    let mut direct = Vec::<Draw>::new();
+    direct.clear_canvas(Color::Rgba(0.0, 0.0, 0.0, 0.0));
+    direct.identity_transform();
+    draw_quadrant_sprite(&mut direct, SpriteId(0));
+    direct.draw_sprite(SpriteId(0));
+
+    // Render the same sprite via a texture
+    let mut via_texture = Vec::<Draw>::new();
+    via_texture.clear_canvas(Color::Rgba(0.0, 0.0, 0.0, 0.0));
+    via_texture.identity_transform();
+    draw_quadrant_sprite(&mut via_texture, SpriteId(0));
+    via_texture.create_texture(TextureId(0), 100, 100, TextureFormat::Rgba);
+    via_texture.set_texture_from_sprite(TextureId(0), SpriteId(0), -1.0, -1.0, 2.0, 2.0);
+    via_texture.new_path();
+    via_texture.rect(-1.0, -1.0, 1.0, 1.0);
+    via_texture.fill_texture(TextureId(0), -1.0, -1.0, 1.0, 1.0);
+    via_texture.fill();
+
+    let direct = render_200x200(direct);
+    let via_texture = render_200x200(via_texture);
+
+    // The quadrants should be in the same place in both renderings
+    let pixel_at = |frame: &Vec<u8>, x: usize, y: usize| {
+        let idx = (y * 200 + x) * 4;
+        [frame[idx], frame[idx + 1], frame[idx + 2], frame[idx + 3]]
+    };
+
+    for (x, y) in [(50, 50), (150, 50), (50, 150), (150, 150)] {
+        assert!(
+            pixel_at(&direct, x, y) == pixel_at(&via_texture, x, y),
+            "({}, {}): {:?} != {:?}",
+            x,
+            y,
+            pixel_at(&direct, x, y),
+            pixel_at(&via_texture, x, y)
+        );
+    }
+
+    assert!(pixel_at(&direct, 50, 50) == [0, 0, 0, 0]);
+    assert!(pixel_at(&direct, 150, 50) != [0, 0, 0, 0]);
+}
+
+#[test]
+pub fn render_dynamic_texture_with_fill_alpha() {
+    let mut drawing = Vec::<Draw>::new();
+    drawing.clear_canvas(Color::Rgba(0.0, 0.0, 0.0, 0.0));
+    drawing.identity_transform();
+    draw_quadrant_sprite(&mut drawing, SpriteId(0));
+    drawing.create_dynamic_texture(TextureId(0), SpriteId(0), -1.0, -1.0, 2.0, 2.0, 2.0, 2.0);
+    drawing.set_texture_fill_alpha(TextureId(0), 0.5);
+
+    // Change the sprite after the texture is created: the dynamic texture should pick up the change
+    drawing.sprite(SpriteId(0));
+    drawing.new_path();
+    drawing.rect(0.0, -1.0, 1.0, 0.0);
+    drawing.fill_color(Color::Rgba(0.0, 1.0, 0.0, 1.0));
+    drawing.fill();
+    drawing.layer(LayerId(0));
+
+    drawing.new_path();
+    drawing.rect(-1.0, -1.0, 1.0, 1.0);
+    drawing.fill_texture(TextureId(0), -1.0, -1.0, 1.0, 1.0);
+    drawing.fill();
+
+    let frame = render_200x200(drawing);
+    let alpha_values = frame
+        .chunks_exact(4)
+        .map(|pixel| pixel[3])
+        .filter(|alpha| *alpha != 0)
+        .collect::<Vec<_>>();
+
+    // Three of the four quadrants are filled at half transparency
+    assert!(
+        alpha_values.len() > 200 * 200 * 3 / 4 - 800,
+        "{}",
+        alpha_values.len()
+    );
+    assert!(
+        alpha_values.iter().all(|alpha| *alpha <= 128),
+        "{:?}",
+        alpha_values.iter().max()
+    );
+}
+
+#[test]
+pub fn render_sprite_with_blur_filter() {
+    let mut drawing = Vec::<Draw>::new();
+    drawing.clear_canvas(Color::Rgba(0.0, 0.0, 0.0, 0.0));
+    drawing.identity_transform();
+    draw_quadrant_sprite(&mut drawing, SpriteId(0));
+    drawing.draw_sprite_with_filters(SpriteId(0), vec![TextureFilter::GaussianBlur(0.2)]);
+
+    let blurred = render_200x200(drawing);
+    let alpha_at = |x: usize, y: usize| blurred[(y * 200 + x) * 4 + 3];
+
+    // Centre of the quadrants are still solid
+    assert!(alpha_at(150, 50) > 250, "{}", alpha_at(150, 50));
+    assert!(alpha_at(50, 150) > 250, "{}", alpha_at(50, 150));
+
+    // The blur spreads out into the empty quadrants near the edges
+    assert!(alpha_at(95, 50) > 0, "{}", alpha_at(95, 50));
+    assert!(alpha_at(95, 50) < 128, "{}", alpha_at(95, 50));
+    assert!(alpha_at(50, 50) == 0, "{}", alpha_at(50, 50));
+}
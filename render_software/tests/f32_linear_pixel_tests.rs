@@ -127,3 +127,154 @@ fn source_over_3() {
     debug_assert!(b == 209, "b({}, {}, {}, {})", r, g, b, a);
     debug_assert!(a == 255, "a({}, {}, {}, {})", r, g, b, a);
 }
+
+fn blend_opaque(operation: AlphaOperation) -> (f32, f32, f32, f32) {
+    let src = F32LinearPixel::from_color(Color::Rgba(0.5, 0.25, 1.0, 1.0), 1.0);
+    let dst = F32LinearPixel::from_color(Color::Rgba(0.5, 1.0, 0.5, 1.0), 1.0);
+
+    src.alpha_blend(dst, operation)
+        .to_color(1.0)
+        .to_rgba_components()
+}
+
+#[test]
+fn multiply_blend() {
+    let (r, g, b, a) = blend_opaque(AlphaOperation::Multiply);
+
+    assert!((r - 0.25).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((g - 0.25).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((b - 0.5).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((a - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+}
+
+#[test]
+fn screen_blend() {
+    let (r, g, b, a) = blend_opaque(AlphaOperation::Screen);
+
+    assert!((r - 0.75).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((g - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((b - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((a - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+}
+
+#[test]
+fn darken_blend() {
+    let (r, g, b, a) = blend_opaque(AlphaOperation::Darken);
+
+    assert!((r - 0.5).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((g - 0.25).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((b - 0.5).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((a - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+}
+
+#[test]
+fn lighten_blend() {
+    let (r, g, b, a) = blend_opaque(AlphaOperation::Lighten);
+
+    assert!((r - 0.5).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((g - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((b - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((a - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+}
+
+#[test]
+fn multiply_transparent_source() {
+    // Half the source colour is multiplied with the destination, the other half is the destination colour
+    let src = F32LinearPixel::from_color(Color::Rgba(1.0, 0.0, 0.0, 0.5), 1.0);
+    let dst = F32LinearPixel::from_color(Color::Rgba(0.5, 0.5, 0.5, 1.0), 1.0);
+    let multiply = AlphaOperation::Multiply.get_function();
+
+    let (r, g, b, a) = multiply(src, dst).to_color(1.0).to_rgba_components();
+
+    assert!((r - 0.5).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((g - 0.25).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((b - 0.25).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((a - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+}
+
+#[test]
+fn overlay_blend() {
+    let (r, g, b, a) = blend_opaque(AlphaOperation::Overlay);
+
+    assert!((r - 0.5).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((g - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((b - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((a - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+}
+
+#[test]
+fn color_burn_blend() {
+    let (r, g, b, a) = blend_opaque(AlphaOperation::ColorBurn);
+
+    assert!((r - 0.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((g - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((b - 0.5).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((a - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+}
+
+#[test]
+fn hard_light_blend() {
+    let (r, g, b, a) = blend_opaque(AlphaOperation::HardLight);
+
+    assert!((r - 0.5).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((g - 0.5).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((b - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((a - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+}
+
+#[test]
+fn difference_blend() {
+    let (r, g, b, a) = blend_opaque(AlphaOperation::Difference);
+
+    assert!((r - 0.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((g - 0.75).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((b - 0.5).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((a - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+}
+
+#[test]
+fn exclusion_blend() {
+    let (r, g, b, a) = blend_opaque(AlphaOperation::Exclusion);
+
+    assert!((r - 0.5).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((g - 0.75).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((b - 0.5).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((a - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+}
+
+#[test]
+fn luminosity_blend() {
+    let (r, g, b, a) = blend_opaque(AlphaOperation::Luminosity);
+
+    assert!((r - 0.1125).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((g - 0.6125).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((b - 0.1125).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((a - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+}
+
+#[test]
+fn plus_blend() {
+    let (r, g, b, a) = blend_opaque(AlphaOperation::Plus);
+
+    assert!((r - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((g - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((b - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((a - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+}
+
+#[test]
+fn difference_transparent_destination() {
+    // Blend functions have no effect where the destination is transparent
+    let src = F32LinearPixel::from_color(Color::Rgba(1.0, 0.5, 0.0, 1.0), 1.0);
+    let dst = F32LinearPixel::from_color(Color::Rgba(0.0, 0.0, 0.0, 0.0), 1.0);
+
+    let (r, g, b, a) = src
+        .alpha_blend(dst, AlphaOperation::Difference)
+        .to_color(1.0)
+        .to_rgba_components();
+
+    assert!((r - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((g - 0.5).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((b - 0.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+    assert!((a - 1.0).abs() < 0.01, "({}, {}, {}, {})", r, g, b, a);
+}
@@ -0,0 +1,117 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use flo_render_software::filters::*;
+use flo_render_software::pixel::*;
+
+use flo_canvas::*;
+
+use std::sync::*;
+
+fn alpha(pixel: &F32LinearPixel) -> f32 {
+    pixel.to_components()[3]
+}
+
+fn single_white_pixel(width: usize, height: usize, x: usize, y: usize) -> Vec<F32LinearPixel> {
+    let mut pixels =
+        vec![F32LinearPixel::from_color(Color::Rgba(0.0, 0.0, 0.0, 0.0), 2.2); width * height];
+    pixels[x + y * width] = F32LinearPixel::white();
+
+    pixels
+}
+
+#[test]
+fn blur_spreads_pixel() {
+    let pixels = single_white_pixel(21, 21, 10, 10);
+    let blurred = GaussianBlurFilter::with_radius(8.0).filter(21, 21, &pixels);
+
+    // The blur should keep the total alpha the same, but spread it out from the centre
+    let total = blurred.iter().map(alpha).sum::<f32>();
+    assert!((total - 1.0).abs() < 0.001, "Total alpha: {}", total);
+
+    assert!(alpha(&blurred[10 + 10 * 21]) < 0.5);
+    assert!(alpha(&blurred[10 + 10 * 21]) > alpha(&blurred[12 + 10 * 21]));
+    assert!(alpha(&blurred[12 + 10 * 21]) > 0.0);
+    assert!(alpha(&blurred[10 + 12 * 21]) > 0.0);
+    assert!(alpha(&blurred[0]) == 0.0);
+
+    // Blur is symmetrical
+    assert!((alpha(&blurred[8 + 10 * 21]) - alpha(&blurred[12 + 10 * 21])).abs() < 0.0001);
+    assert!((alpha(&blurred[10 + 8 * 21]) - alpha(&blurred[10 + 12 * 21])).abs() < 0.0001);
+}
+
+#[test]
+fn small_blur_has_no_effect() {
+    let pixels = single_white_pixel(5, 5, 2, 2);
+    let blurred = GaussianBlurFilter::with_radius(1.0).filter(5, 5, &pixels);
+
+    assert!(blurred == pixels);
+}
+
+#[test]
+fn infinite_blur_has_no_effect() {
+    let pixels = single_white_pixel(5, 5, 2, 2);
+
+    assert!(GaussianBlurFilter::with_radius(f64::INFINITY).filter(5, 5, &pixels) == pixels);
+    assert!(GaussianBlurFilter::with_radius(f64::NAN).filter(5, 5, &pixels) == pixels);
+}
+
+#[test]
+fn huge_blur_is_limited_to_image_size() {
+    let pixels = single_white_pixel(5, 3, 2, 1);
+    let blurred = GaussianBlurFilter::with_radius_xy(1e300, 1e12).filter(5, 3, &pixels);
+
+    // The kernel covers the whole image, so the pixel is spread out evenly
+    assert!(blurred.len() == 15);
+    assert!((alpha(&blurred[0]) - alpha(&blurred[14])).abs() < 0.0001);
+    assert!(alpha(&blurred[0]) > 0.0);
+}
+
+#[test]
+fn alpha_blend_pixels() {
+    let pixels = vec![F32LinearPixel::white(); 4];
+    let blended = AlphaBlendFilter::with_alpha(0.25).filter(2, 2, &pixels);
+
+    assert!(blended
+        .iter()
+        .all(|pixel| pixel.to_components() == [0.25, 0.25, 0.25, 0.25]));
+}
+
+#[test]
+fn mask_pixels() {
+    // Mask is half the size of the image, with the left half opaque and the right half transparent
+    let mask = RgbaTexture::new(2, 1, vec![0, 0, 0, 255, 0, 0, 0, 0]);
+    let pixels = vec![F32LinearPixel::white(); 16];
+    let masked = MaskFilter::with_mask(Arc::new(mask)).filter(4, 4, &pixels);
+
+    for y in 0..4 {
+        assert!(alpha(&masked[y * 4 + 0]) == 1.0);
+        assert!(alpha(&masked[y * 4 + 1]) == 1.0);
+        assert!(alpha(&masked[y * 4 + 2]) == 0.0);
+        assert!(alpha(&masked[y * 4 + 3]) == 0.0);
+    }
+}
+
+#[test]
+fn displacement_map_moves_pixels() {
+    // Red channel of 255 moves pixels by the full x radius (ie, each pixel reads from 2 pixels to the right)
+    let displacement = RgbaTexture::new(1, 1, vec![255, 128, 0, 255]);
+    let pixels = single_white_pixel(8, 1, 5, 0);
+    let displaced = DisplacementMapFilter::with_displacement(Arc::new(displacement), 2.0, 0.0)
+        .filter(8, 1, &pixels);
+
+    assert!(alpha(&displaced[3]) == 1.0, "{:?}", displaced);
+    assert!(alpha(&displaced[5]) == 0.0, "{:?}", displaced);
+}
+
+#[test]
+fn filter_texture() {
+    let texture = RgbaTexture::new(2, 1, vec![255, 0, 0, 255, 0, 0, 255, 255]);
+    let filtered =
+        filter_rgba_texture::<F32LinearPixel, 4>(&texture, &AlphaBlendFilter::with_alpha(0.5));
+
+    assert!(filtered.pixels() == &vec![255, 0, 0, 128, 0, 0, 255, 128]);
+}